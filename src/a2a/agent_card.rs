@@ -6,6 +6,35 @@
 use crate::a2a::types::*;
 use crate::brain::tools::registry::ToolRegistry;
 
+/// MIME types accepted in message parts: text, JSON data, images (attached
+/// to the prompt) and documents (parsed to text)
+const INPUT_MODES: &[&str] = &[
+    "text/plain",
+    "application/json",
+    "image/png",
+    "image/jpeg",
+    "image/gif",
+    "image/webp",
+    "application/pdf",
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    "text/markdown",
+    "text/html",
+    "application/xml",
+];
+
+/// MIME types of artifacts: the text reply plus any file the agent writes
+const OUTPUT_MODES: &[&str] = &[
+    "text/plain",
+    "application/json",
+    "image/png",
+    "application/pdf",
+    "application/octet-stream",
+];
+
+fn modes(types: &[&str]) -> Vec<String> {
+    types.iter().map(|t| t.to_string()).collect()
+}
+
 /// Build the Agent Card for this OpenCrabs instance.
 ///
 /// Skills are generated dynamically based on available tools in the registry.
@@ -24,8 +53,8 @@ pub fn build_agent_card(host: &str, port: u16, tool_registry: Option<&ToolRegist
             "refactoring".to_string(),
         ],
         examples: vec!["Analyze this Rust module for performance issues.".to_string()],
-        input_modes: vec!["text/plain".to_string(), "application/json".to_string()],
-        output_modes: vec!["text/plain".to_string(), "application/json".to_string()],
+        input_modes: modes(INPUT_MODES),
+        output_modes: modes(OUTPUT_MODES),
     }];
 
     // Research skill requires search tools
@@ -48,7 +77,7 @@ pub fn build_agent_card(host: &str, port: u16, tool_registry: Option<&ToolRegist
             examples: vec![
                 "Research the latest developments in AI agent security.".to_string(),
             ],
-            input_modes: vec!["text/plain".to_string()],
+            input_modes: modes(INPUT_MODES),
             output_modes: vec!["text/plain".to_string(), "application/json".to_string()],
         });
     }
@@ -93,12 +122,12 @@ pub fn build_agent_card(host: &str, port: u16, tool_registry: Option<&ToolRegist
         }),
         capabilities: Some(AgentCapabilities {
             streaming: true,
-            push_notifications: false,
+            push_notifications: true,
             state_transition_history: true,
         }),
         skills,
-        default_input_modes: vec!["text/plain".to_string(), "application/json".to_string()],
-        default_output_modes: vec!["text/plain".to_string(), "application/json".to_string()],
+        default_input_modes: modes(INPUT_MODES),
+        default_output_modes: modes(OUTPUT_MODES),
     }
 }
 
@@ -115,6 +144,11 @@ mod tests {
             card.supported_interfaces[0].url,
             "http://127.0.0.1:18790/a2a/v1"
         );
+        let capabilities = card.capabilities.expect("capabilities");
+        assert!(capabilities.streaming);
+        assert!(capabilities.push_notifications);
+        assert!(card.default_input_modes.contains(&"application/pdf".to_string()));
+        assert!(card.default_output_modes.contains(&"text/plain".to_string()));
     }
 
     #[test]
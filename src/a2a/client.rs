@@ -0,0 +1,412 @@
+//! A2A client for delegating work to remote agents.
+//!
+//! Discovers a peer through its Agent Card (`/.well-known/agent.json`), then
+//! talks JSON-RPC 2.0 to the card's JSONRPC interface: `message/send`,
+//! `message/stream` (SSE), `tasks/get` and `tasks/cancel`.
+
+use crate::a2a::types::*;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::time::Duration;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Path of the Agent Card relative to a peer's base URL
+pub const AGENT_CARD_PATH: &str = "/.well-known/agent.json";
+
+/// JSON-RPC path used when a peer's card lists no JSONRPC interface
+const DEFAULT_RPC_PATH: &str = "/a2a/v1";
+
+/// Timeout for discovery and single JSON-RPC calls (not streams)
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A2A client error types
+#[derive(Debug, Error)]
+pub enum ClientError {
+    /// Request could not be sent or the response could not be read
+    #[error("HTTP error from {url}: {reason}")]
+    Http { url: String, reason: String },
+
+    /// Peer answered with a JSON-RPC error
+    #[error("Remote agent error {code}: {message}")]
+    Rpc { code: i64, message: String },
+
+    /// Peer answered with something that isn't valid A2A
+    #[error("Protocol error: {0}")]
+    Protocol(String),
+}
+
+/// Result type for A2A client operations
+pub type Result<T> = std::result::Result<T, ClientError>;
+
+/// Client for one remote A2A agent.
+#[derive(Debug, Clone)]
+pub struct A2aClient {
+    http: reqwest::Client,
+    base_url: String,
+    endpoint: String,
+    token: Option<String>,
+}
+
+impl A2aClient {
+    /// `url` is the peer's base URL; a full Agent Card URL is accepted too.
+    pub fn new(url: &str, token: Option<String>) -> Self {
+        let base_url = url
+            .trim()
+            .trim_end_matches('/')
+            .trim_end_matches(AGENT_CARD_PATH)
+            .trim_end_matches('/')
+            .to_string();
+        Self {
+            http: reqwest::Client::new(),
+            endpoint: format!("{}{}", base_url, DEFAULT_RPC_PATH),
+            base_url,
+            token: token.filter(|t| !t.is_empty()),
+        }
+    }
+
+    /// JSON-RPC endpoint requests are sent to
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Fetch the peer's Agent Card and use its JSONRPC interface from now on.
+    pub async fn discover(&mut self) -> Result<AgentCard> {
+        let url = format!("{}{}", self.base_url, AGENT_CARD_PATH);
+        let resp = self
+            .authorized(self.http.get(&url))
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| http_error(&url, e))?;
+        let card: AgentCard = resp.json().await.map_err(|e| http_error(&url, e))?;
+
+        if let Some(interface) = card
+            .supported_interfaces
+            .iter()
+            .find(|i| i.protocol_binding.eq_ignore_ascii_case("JSONRPC"))
+        {
+            self.endpoint = interface.url.clone();
+        }
+        Ok(card)
+    }
+
+    /// `message/send` — returns the task as the peer first reports it.
+    pub async fn send_message(&self, params: &SendMessageParams) -> Result<Task> {
+        let result = self.call("message/send", params).await?;
+        task_from_result(result)
+    }
+
+    /// `message/stream` — follows the SSE stream until the task reaches a
+    /// final state or the stream ends, and returns the task as last seen.
+    pub async fn stream_message(&self, params: &SendMessageParams) -> Result<Task> {
+        let request = rpc_request("message/stream", params)?;
+        let mut resp = self
+            .authorized(self.http.post(&self.endpoint))
+            .header(reqwest::header::ACCEPT, "text/event-stream")
+            .json(&request)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| http_error(&self.endpoint, e))?;
+
+        // A peer may answer with a plain JSON-RPC error instead of a stream
+        let is_sse = resp
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("text/event-stream"));
+        if !is_sse {
+            let rpc: JsonRpcResponse = resp
+                .json()
+                .await
+                .map_err(|e| http_error(&self.endpoint, e))?;
+            return task_from_result(rpc_result(rpc)?);
+        }
+
+        let mut task: Option<Task> = None;
+        let mut buffer = String::new();
+        while let Some(chunk) = resp.chunk().await.map_err(|e| http_error(&self.endpoint, e))? {
+            buffer.push_str(&String::from_utf8_lossy(&chunk).replace("\r\n", "\n"));
+            while let Some(end) = buffer.find("\n\n") {
+                let event: String = buffer.drain(..end + 2).collect();
+                let Some(data) = sse_data(&event) else {
+                    continue;
+                };
+                let rpc: JsonRpcResponse = serde_json::from_str(&data)
+                    .map_err(|e| ClientError::Protocol(format!("bad stream event: {}", e)))?;
+                let event: StreamEvent = parse(rpc_result(rpc)?)?;
+                if apply_event(&mut task, event) {
+                    return task.ok_or_else(|| ClientError::Protocol("stream ended without a task".into()));
+                }
+            }
+        }
+        task.ok_or_else(|| ClientError::Protocol("stream ended without a task".into()))
+    }
+
+    /// `tasks/get`
+    pub async fn get_task(&self, id: &str) -> Result<Task> {
+        let params = GetTaskParams {
+            id: id.to_string(),
+            history_length: None,
+        };
+        parse(self.call("tasks/get", &params).await?)
+    }
+
+    /// `tasks/cancel`
+    pub async fn cancel_task(&self, id: &str) -> Result<Task> {
+        let params = CancelTaskParams { id: id.to_string() };
+        parse(self.call("tasks/cancel", &params).await?)
+    }
+
+    async fn call(&self, method: &str, params: &impl serde::Serialize) -> Result<Value> {
+        let request = rpc_request(method, params)?;
+        let resp = self
+            .authorized(self.http.post(&self.endpoint))
+            .timeout(REQUEST_TIMEOUT)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| http_error(&self.endpoint, e))?;
+        // JSON-RPC errors may come with any status code, so read the body first
+        let status = resp.status();
+        let rpc: JsonRpcResponse = resp.json().await.map_err(|e| ClientError::Http {
+            url: self.endpoint.clone(),
+            reason: format!("{} ({})", e, status),
+        })?;
+        rpc_result(rpc)
+    }
+
+    fn authorized(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match self.token {
+            Some(ref token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+}
+
+/// Whether a task in `state` will not change any more (or needs the caller)
+pub fn is_terminal(state: &TaskState) -> bool {
+    !matches!(state, TaskState::Submitted | TaskState::Working)
+}
+
+/// Text of a message or artifact's parts. Data parts are rendered as JSON and
+/// file parts as their URL, or their name and type when sent inline.
+pub fn parts_text(parts: &[Part]) -> String {
+    parts
+        .iter()
+        .filter_map(|part| {
+            if let Some(ref text) = part.text {
+                Some(text.clone())
+            } else if let Some(ref data) = part.data {
+                Some(serde_json::to_string_pretty(data).unwrap_or_default())
+            } else if let Some(ref url) = part.url {
+                Some(format!("[file: {}]", url))
+            } else {
+                part.raw.as_ref().map(|_| {
+                    format!(
+                        "[file: {} ({})]",
+                        part.filename.as_deref().unwrap_or("unnamed"),
+                        part.media_type.as_deref().unwrap_or("application/octet-stream")
+                    )
+                })
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn rpc_request(method: &str, params: &impl serde::Serialize) -> Result<JsonRpcRequest> {
+    Ok(JsonRpcRequest {
+        jsonrpc: "2.0".to_string(),
+        method: method.to_string(),
+        params: serde_json::to_value(params).map_err(|e| ClientError::Protocol(e.to_string()))?,
+        id: Value::String(Uuid::new_v4().to_string()),
+    })
+}
+
+fn rpc_result(rpc: JsonRpcResponse) -> Result<Value> {
+    if let Some(error) = rpc.error {
+        return Err(ClientError::Rpc {
+            code: error.code,
+            message: error.message,
+        });
+    }
+    rpc.result
+        .ok_or_else(|| ClientError::Protocol("response has neither result nor error".into()))
+}
+
+fn parse<T: DeserializeOwned>(value: Value) -> Result<T> {
+    serde_json::from_value(value).map_err(|e| ClientError::Protocol(e.to_string()))
+}
+
+/// `message/send` may answer with a Task, or directly with a Message — the
+/// latter is wrapped in a completed task.
+fn task_from_result(result: Value) -> Result<Task> {
+    if result.get("status").is_some() {
+        return parse(result);
+    }
+    let message: Message = parse(result)?;
+    Ok(Task {
+        id: message.task_id.clone().unwrap_or_default(),
+        context_id: message.context_id.clone(),
+        status: TaskStatus {
+            state: TaskState::Completed,
+            message: None,
+            timestamp: None,
+        },
+        artifacts: vec![Artifact {
+            artifact_id: None,
+            name: Some("response".to_string()),
+            description: None,
+            parts: message.parts,
+            metadata: None,
+        }],
+        history: vec![],
+        metadata: None,
+    })
+}
+
+/// The `data:` payload of one SSE event, multi-line data joined
+fn sse_data(event: &str) -> Option<String> {
+    let lines: Vec<&str> = event
+        .lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(|data| data.strip_prefix(' ').unwrap_or(data))
+        .collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// Fold a stream event into the task. Returns true once the task is final.
+fn apply_event(task: &mut Option<Task>, event: StreamEvent) -> bool {
+    match event {
+        StreamEvent::Task(t) => {
+            let done = is_terminal(&t.status.state);
+            *task = Some(t);
+            done
+        }
+        StreamEvent::StatusUpdate(update) => {
+            let task = task.get_or_insert_with(|| empty_task(&update.task_id, &update.context_id));
+            task.status = update.status;
+            update.is_final || is_terminal(&task.status.state)
+        }
+        StreamEvent::ArtifactUpdate(update) => {
+            let task = task.get_or_insert_with(|| empty_task(&update.task_id, &update.context_id));
+            let existing = update.artifact.artifact_id.as_ref().and_then(|id| {
+                task.artifacts
+                    .iter_mut()
+                    .find(|a| a.artifact_id.as_ref() == Some(id))
+            });
+            match existing {
+                Some(artifact) if update.append == Some(true) => {
+                    artifact.parts.extend(update.artifact.parts)
+                }
+                Some(artifact) => *artifact = update.artifact,
+                None => task.artifacts.push(update.artifact),
+            }
+            false
+        }
+    }
+}
+
+fn empty_task(id: &str, context_id: &str) -> Task {
+    Task {
+        id: id.to_string(),
+        context_id: Some(context_id.to_string()),
+        status: TaskStatus {
+            state: TaskState::Working,
+            message: None,
+            timestamp: None,
+        },
+        artifacts: vec![],
+        history: vec![],
+        metadata: None,
+    }
+}
+
+fn http_error(url: &str, e: reqwest::Error) -> ClientError {
+    ClientError::Http {
+        url: url.to_string(),
+        reason: e.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_url_normalization() {
+        let client = A2aClient::new("http://127.0.0.1:18790/.well-known/agent.json", None);
+        assert_eq!(client.endpoint(), "http://127.0.0.1:18790/a2a/v1");
+        let client = A2aClient::new("https://bee.example/", Some(String::new()));
+        assert_eq!(client.endpoint(), "https://bee.example/a2a/v1");
+        assert!(client.token.is_none());
+    }
+
+    #[test]
+    fn test_sse_data() {
+        assert_eq!(sse_data("event: x\ndata: {\"a\":1}\n\n").as_deref(), Some("{\"a\":1}"));
+        assert_eq!(sse_data("data:a\ndata: b\n\n").as_deref(), Some("a\nb"));
+        assert_eq!(sse_data(": keep-alive\n\n"), None);
+    }
+
+    #[test]
+    fn test_apply_stream_events() {
+        let mut task = None;
+        let artifact = |text: &str| Artifact {
+            artifact_id: Some("a1".to_string()),
+            name: None,
+            description: None,
+            parts: vec![Part::text(text)],
+            metadata: None,
+        };
+        let update = |artifact: Artifact, append: bool| {
+            StreamEvent::ArtifactUpdate(TaskArtifactUpdateEvent {
+                kind: "artifact-update".to_string(),
+                task_id: "t1".to_string(),
+                context_id: "c1".to_string(),
+                artifact,
+                append: Some(append),
+                last_chunk: None,
+                metadata: None,
+            })
+        };
+
+        assert!(!apply_event(&mut task, update(artifact("Hello"), false)));
+        assert!(!apply_event(&mut task, update(artifact(" world"), true)));
+        let done = apply_event(
+            &mut task,
+            StreamEvent::StatusUpdate(TaskStatusUpdateEvent {
+                kind: "status-update".to_string(),
+                task_id: "t1".to_string(),
+                context_id: "c1".to_string(),
+                status: TaskStatus {
+                    state: TaskState::Completed,
+                    message: None,
+                    timestamp: None,
+                },
+                is_final: true,
+                metadata: None,
+            }),
+        );
+        assert!(done);
+
+        let task = task.expect("task");
+        assert_eq!(task.id, "t1");
+        assert_eq!(task.artifacts.len(), 1);
+        assert_eq!(parts_text(&task.artifacts[0].parts), "Hello\n world");
+    }
+
+    #[test]
+    fn test_message_result_becomes_completed_task() {
+        let task = task_from_result(serde_json::json!({
+            "role": "agent",
+            "parts": [{ "text": "done" }]
+        }))
+        .expect("task");
+        assert_eq!(task.status.state, TaskState::Completed);
+        assert_eq!(parts_text(&task.artifacts[0].parts), "done");
+    }
+}
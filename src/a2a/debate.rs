@@ -13,8 +13,10 @@
 //! Based on ReConcile (ACL 2024) confidence-weighted voting.
 
 use crate::a2a::types::*;
+use crate::brain::provider::{ContentBlock, LLMRequest, Provider};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 use uuid::Uuid;
 
 // ─── Debate Configuration ────────────────────────────────────
@@ -55,6 +57,10 @@ fn default_consensus_threshold() -> f64 {
     0.8
 }
 
+/// Appended to every round prompt so positions and confidence can be parsed.
+const ANSWER_FORMAT: &str = "Start your answer with a line `Position: <your stance in a few words>` \
+     and end it with a line `Confidence: <0.0-1.0>`.";
+
 // ─── Debate State ────────────────────────────────────────────
 
 /// A single Bee's response in a debate round.
@@ -186,8 +192,10 @@ impl DebateSession {
              2. **Key arguments** supporting your position\n\
              3. **Evidence** or reasoning\n\
              4. **Confidence score** (0.0-1.0) in your position\n\
-             5. **Potential counterarguments** you anticipate\n",
-            self.config.topic
+             5. **Potential counterarguments** you anticipate\n\n\
+             {}\n",
+            self.config.topic,
+            ANSWER_FORMAT,
         );
 
         // Inject knowledge base context if available
@@ -220,10 +228,12 @@ impl DebateSession {
              3. **Synthesize insights** — combine the strongest ideas\n\
              4. **Update your position** if others' arguments changed your mind\n\
              5. **Confidence score** (0.0-1.0) — has your confidence changed?\n\n\
+             {}\n\n\
              ## Previous Round Responses\n\n",
             self.config.topic,
             round_num,
             round_num - 1,
+            ANSWER_FORMAT,
         );
 
         for resp in &prev_round.responses {
@@ -248,35 +258,37 @@ impl DebateSession {
             .bee_endpoints
             .iter()
             .enumerate()
-            .map(|(i, endpoint)| {
-                let msg = Message {
-                    message_id: Some(Uuid::new_v4().to_string()),
-                    context_id: Some(self.id.clone()),
-                    task_id: None,
-                    role: Role::User,
-                    parts: vec![Part::text(&prompt)],
-                    metadata: Some({
-                        let mut m = HashMap::new();
-                        m.insert(
-                            "debate_round".to_string(),
-                            serde_json::json!(round_num),
-                        );
-                        m.insert(
-                            "bee_index".to_string(),
-                            serde_json::json!(i),
-                        );
-                        m.insert(
-                            "debate_session_id".to_string(),
-                            serde_json::json!(self.id),
-                        );
-                        m
-                    }),
-                };
-                (endpoint.clone(), msg)
-            })
+            .map(|(i, endpoint)| (endpoint.clone(), self.round_message(round_num, i, &prompt)))
             .collect()
     }
 
+    /// Build the A2A message carrying a round prompt to one Bee.
+    pub fn round_message(&self, round_num: usize, bee_index: usize, prompt: &str) -> Message {
+        Message {
+            message_id: Some(Uuid::new_v4().to_string()),
+            context_id: Some(self.id.clone()),
+            task_id: None,
+            role: Role::User,
+            parts: vec![Part::text(prompt)],
+            metadata: Some({
+                let mut m = HashMap::new();
+                m.insert(
+                    "debate_round".to_string(),
+                    serde_json::json!(round_num),
+                );
+                m.insert(
+                    "bee_index".to_string(),
+                    serde_json::json!(bee_index),
+                );
+                m.insert(
+                    "debate_session_id".to_string(),
+                    serde_json::json!(self.id),
+                );
+                m
+            }),
+        }
+    }
+
     /// Analyze consensus from a round's responses.
     pub fn analyze_consensus(responses: &[BeeResponse], threshold: f64) -> ConsensusAnalysis {
         let avg_confidence = if responses.is_empty() {
@@ -378,9 +390,13 @@ impl DebateSession {
 
             for resp in &round.responses {
                 report.push_str(&format!(
-                    "### Bee {} (confidence: {:.1})\n{}\n\n",
-                    resp.bee_id, resp.confidence, resp.content
+                    "### Bee {} (confidence: {:.1})\n",
+                    resp.bee_id, resp.confidence
                 ));
+                if let Some(ref position) = resp.position {
+                    report.push_str(&format!("**Position:** {}\n\n", position));
+                }
+                report.push_str(&format!("{}\n\n", resp.content));
             }
 
             if let Some(ref consensus) = round.consensus {
@@ -402,6 +418,12 @@ impl DebateSession {
                         report.push_str(&format!("  - {}\n", p));
                     }
                 }
+                if !consensus.blind_spots.is_empty() {
+                    report.push_str("- **Blind Spots:**\n");
+                    for p in &consensus.blind_spots {
+                        report.push_str(&format!("  - {}\n", p));
+                    }
+                }
                 report.push('\n');
             }
         }
@@ -423,6 +445,8 @@ pub enum DebateError {
     Http(String),
     /// A2A protocol-level error (bad response, timeout, task failure).
     Protocol(String),
+    /// LLM provider failure of a local bee or the queen.
+    Provider(String),
 }
 
 impl std::fmt::Display for DebateError {
@@ -430,10 +454,93 @@ impl std::fmt::Display for DebateError {
         match self {
             Self::Http(e) => write!(f, "HTTP error: {}", e),
             Self::Protocol(e) => write!(f, "Protocol error: {}", e),
+            Self::Provider(e) => write!(f, "Provider error: {}", e),
+        }
+    }
+}
+
+/// A debate participant.
+#[derive(Clone)]
+pub enum Bee {
+    /// A remote A2A agent, reached at its JSON-RPC endpoint.
+    Remote { endpoint: String },
+    /// An in-process bee answering with one of our own providers.
+    Local {
+        name: String,
+        provider: Arc<dyn Provider>,
+        model: String,
+        /// Angle this bee argues from, so bees on the same model still differ
+        perspective: Option<String>,
+    },
+}
+
+impl Bee {
+    /// How the bee shows up in `BeeResponse::endpoint`.
+    pub fn label(&self) -> String {
+        match self {
+            Self::Remote { endpoint } => endpoint.clone(),
+            Self::Local { name, provider, model, .. } => {
+                format!("local:{} ({}/{})", name, provider.name(), model)
+            }
+        }
+    }
+
+    /// Put a round prompt to the bee and wait for its answer.
+    async fn ask(&self, client: &reqwest::Client, message: Message) -> Result<String, DebateError> {
+        match self {
+            Self::Remote { endpoint } => send_a2a_message(client, endpoint, message).await,
+            Self::Local { provider, model, perspective, .. } => {
+                let mut system = "You are a Bee in a Bee Colony debate: several agents research \
+                     the same topic independently, then critique each other's answers. \
+                     Argue your honest position and change it only for good reasons."
+                    .to_string();
+                if let Some(perspective) = perspective {
+                    system.push_str(&format!(" Approach the topic as {}.", perspective));
+                }
+                let prompt = message
+                    .parts
+                    .iter()
+                    .filter_map(|p| p.text.as_deref())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                complete_text(provider.as_ref(), model, system, prompt).await
+            }
         }
     }
 }
 
+/// Run one completion and return its text.
+async fn complete_text(
+    provider: &dyn Provider,
+    model: &str,
+    system: String,
+    prompt: String,
+) -> Result<String, DebateError> {
+    let request = LLMRequest::new(
+        model,
+        vec![crate::brain::provider::Message::user(prompt)],
+    )
+    .with_system(system)
+    .with_max_tokens(4096);
+    let response = provider.complete(request).await;
+    // Failover switches were logged as they happened; drain them so they
+    // don't surface in someone else's turn
+    provider.take_switches();
+    let response = response.map_err(|e| DebateError::Provider(e.to_string()))?;
+    let text: String = response
+        .content
+        .iter()
+        .filter_map(|block| match block {
+            ContentBlock::Text { text } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect();
+    if text.trim().is_empty() {
+        return Err(DebateError::Provider(format!("{} returned no text", model)));
+    }
+    Ok(text)
+}
+
 /// Send a message to a bee endpoint via A2A JSON-RPC and poll until completion.
 async fn send_a2a_message(
     client: &reqwest::Client,
@@ -562,13 +669,55 @@ fn extract_confidence(text: &str) -> f64 {
     0.5
 }
 
+/// Extract the stance from a `Position: ...` line of a response, if any.
+///
+/// Markdown emphasis and heading markers around the line are ignored.
+fn extract_position(text: &str) -> Option<String> {
+    text.lines().find_map(|line| {
+        let clean = line.replace("**", "");
+        let clean = clean.trim().trim_start_matches(['#', '-', '*', ' ']);
+        let rest = clean
+            .get(..9)
+            .filter(|head| head.eq_ignore_ascii_case("position:"))
+            .map(|_| clean[9..].trim().trim_end_matches('.'))?;
+        if rest.is_empty() {
+            None
+        } else {
+            Some(rest.chars().take(80).collect())
+        }
+    })
+}
+
 /// Run a full multi-round debate across bee endpoints.
 ///
 /// 1. Loads knowledge context from QMD memory if not pre-populated
 /// 2. Sends round prompts to all bee endpoints concurrently via A2A JSON-RPC
 /// 3. Collects responses, checks consensus
 /// 4. Repeats or concludes
-pub async fn run_debate(mut config: DebateConfig) -> Result<DebateSession, DebateError> {
+pub async fn run_debate(config: DebateConfig) -> Result<DebateSession, DebateError> {
+    let bees = config
+        .bee_endpoints
+        .iter()
+        .map(|endpoint| Bee::Remote {
+            endpoint: endpoint.clone(),
+        })
+        .collect();
+    run_colony(config, bees, |_| {}).await
+}
+
+/// Run a full multi-round debate across any mix of remote and local bees.
+///
+/// `on_update` sees the session whenever a round starts or finishes, so a UI
+/// can follow the debate live.
+pub async fn run_colony(
+    mut config: DebateConfig,
+    bees: Vec<Bee>,
+    on_update: impl Fn(&DebateSession) + Send + Sync,
+) -> Result<DebateSession, DebateError> {
+    if bees.is_empty() {
+        return Err(DebateError::Protocol("A debate needs at least one bee".to_string()));
+    }
+
     // Load knowledge context from QMD if not pre-populated
     if config.knowledge_context.is_empty()
         && let Ok(store) = crate::memory::get_store()
@@ -590,11 +739,14 @@ pub async fn run_debate(mut config: DebateConfig) -> Result<DebateSession, Debat
         }
     }
 
+    config.num_bees = bees.len();
     let mut session = DebateSession::new(config);
     let client = reqwest::Client::new();
 
     for round_num in 1..=session.config.max_rounds {
         session.state = DebateState::InRound;
+        session.current_round = round_num;
+        on_update(&session);
 
         let prompt = if round_num == 1 {
             session.round1_prompt()
@@ -602,15 +754,15 @@ pub async fn run_debate(mut config: DebateConfig) -> Result<DebateSession, Debat
             session.critique_prompt(round_num)
         };
 
-        let messages = session.build_round_messages(round_num);
-
         // Send to all bees concurrently
         let mut handles = Vec::new();
-        for (i, (endpoint, msg)) in messages.into_iter().enumerate() {
+        for (i, bee) in bees.iter().enumerate() {
             let client = client.clone();
+            let bee = bee.clone();
+            let msg = session.round_message(round_num, i, &prompt);
             handles.push(tokio::spawn(async move {
-                let result = send_a2a_message(&client, &endpoint, msg).await;
-                (i, endpoint, result)
+                let result = bee.ask(&client, msg).await;
+                (i, bee.label(), result)
             }));
         }
 
@@ -620,12 +772,13 @@ pub async fn run_debate(mut config: DebateConfig) -> Result<DebateSession, Debat
             match handle.await {
                 Ok((i, endpoint, Ok(content))) => {
                     let confidence = extract_confidence(&content);
+                    let position = extract_position(&content);
                     responses.push(BeeResponse {
                         bee_id: format!("bee-{}", i),
                         endpoint,
                         content,
                         confidence,
-                        position: None,
+                        position,
                         key_points: vec![],
                     });
                 }
@@ -645,6 +798,7 @@ pub async fn run_debate(mut config: DebateConfig) -> Result<DebateSession, Debat
         }
 
         session.record_round(round_num, prompt, responses);
+        on_update(&session);
 
         if session.state == DebateState::Concluded || session.state == DebateState::Exhausted {
             break;
@@ -654,6 +808,55 @@ pub async fn run_debate(mut config: DebateConfig) -> Result<DebateSession, Debat
     Ok(session)
 }
 
+/// Have the queen write the final synthesis of a finished debate and name the
+/// blind spots of its last round.
+pub async fn synthesize(
+    session: &mut DebateSession,
+    provider: &dyn Provider,
+    model: &str,
+) -> Result<(), DebateError> {
+    let prompt = format!(
+        "{}\n\n---\n\n\
+         Write the final synthesis of this debate. Reply in exactly this format:\n\n\
+         ## Synthesis\n<the strongest combined answer, noting where the bees still disagree>\n\n\
+         ## Blind Spots\n- <an aspect of the topic no bee addressed>\n",
+        session.summary_report()
+    );
+    let system = "You are the Queen of a Bee Colony debate. You weigh the bees' \
+                  arguments by their evidence and confidence, not by majority alone."
+        .to_string();
+    let text = complete_text(provider, model, system, prompt).await?;
+
+    let (synthesis, blind_spots) = parse_synthesis(&text);
+    if let Some(consensus) = session.rounds.last_mut().and_then(|r| r.consensus.as_mut()) {
+        consensus.blind_spots = blind_spots;
+    }
+    session.final_synthesis = Some(synthesis);
+    Ok(())
+}
+
+/// Split a queen reply into the synthesis text and its blind spot bullets.
+fn parse_synthesis(text: &str) -> (String, Vec<String>) {
+    // ASCII lowercasing keeps byte offsets valid for `text`
+    let lower = text.to_ascii_lowercase();
+    let (synthesis, rest) = match lower.find("## blind spots") {
+        Some(pos) => (&text[..pos], &text[pos + "## blind spots".len()..]),
+        None => (text, ""),
+    };
+    let synthesis = synthesis.trim();
+    let synthesis = match synthesis.get(..12) {
+        Some(head) if head.eq_ignore_ascii_case("## synthesis") => synthesis[12..].trim(),
+        _ => synthesis,
+    };
+    let blind_spots = rest
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix(['-', '*']))
+        .map(|spot| spot.trim().to_string())
+        .filter(|spot| !spot.is_empty())
+        .collect();
+    (synthesis.to_string(), blind_spots)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -819,6 +1022,105 @@ mod tests {
         assert!(report.contains("Consensus Analysis"));
     }
 
+    /// Answers every prompt with a fixed stance
+    struct StanceProvider(&'static str);
+
+    #[async_trait::async_trait]
+    impl Provider for StanceProvider {
+        async fn complete(
+            &self,
+            request: LLMRequest,
+        ) -> crate::brain::provider::Result<crate::brain::provider::LLMResponse> {
+            Ok(crate::brain::provider::LLMResponse {
+                id: "r1".to_string(),
+                model: request.model,
+                content: vec![ContentBlock::Text {
+                    text: format!("**Position:** {}\n\nBecause.\n\nConfidence: 0.9", self.0),
+                }],
+                stop_reason: None,
+                usage: Default::default(),
+            })
+        }
+
+        async fn stream(
+            &self,
+            _request: LLMRequest,
+        ) -> crate::brain::provider::Result<crate::brain::provider::ProviderStream> {
+            unimplemented!("not streamed")
+        }
+
+        fn name(&self) -> &str {
+            "stance"
+        }
+
+        fn default_model(&self) -> &str {
+            "stance-1"
+        }
+
+        fn supported_models(&self) -> Vec<String> {
+            vec!["stance-1".to_string()]
+        }
+
+        fn context_window(&self, _model: &str) -> Option<u32> {
+            Some(4096)
+        }
+
+        fn calculate_cost(&self, _model: &str, _input: u32, _output: u32) -> f64 {
+            0.0
+        }
+    }
+
+    #[test]
+    fn test_extract_position() {
+        assert_eq!(
+            extract_position("## **Position:** Pro, with limits.\nMore text").as_deref(),
+            Some("Pro, with limits")
+        );
+        assert_eq!(extract_position("position: con").as_deref(), Some("con"));
+        assert_eq!(extract_position("No stance here"), None);
+        assert_eq!(extract_position("Position:"), None);
+    }
+
+    #[test]
+    fn test_parse_synthesis() {
+        let (synthesis, spots) = parse_synthesis(
+            "## Synthesis\nMemory helps, if scoped.\n\n## Blind Spots\n- Cost\n* Deletion rights\n",
+        );
+        assert_eq!(synthesis, "Memory helps, if scoped.");
+        assert_eq!(spots, vec!["Cost", "Deletion rights"]);
+
+        let (synthesis, spots) = parse_synthesis("Just a summary.");
+        assert_eq!(synthesis, "Just a summary.");
+        assert!(spots.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_run_colony_with_local_bees() {
+        let bee = |name: &str| Bee::Local {
+            name: name.to_string(),
+            provider: Arc::new(StanceProvider("pro")),
+            model: "stance-1".to_string(),
+            perspective: None,
+        };
+        let updates = std::sync::Mutex::new(Vec::new());
+        let session = run_colony(test_config(), vec![bee("a"), bee("b")], |s| {
+            updates.lock().expect("lock").push(s.state.clone());
+        })
+        .await
+        .expect("debate");
+
+        assert_eq!(session.config.num_bees, 2);
+        assert_eq!(session.state, DebateState::Concluded);
+        assert_eq!(session.rounds.len(), 1);
+        let round = &session.rounds[0];
+        assert_eq!(round.responses[0].position.as_deref(), Some("pro"));
+        assert!(round.responses[0].endpoint.starts_with("local:a"));
+        assert_eq!(
+            *updates.lock().expect("lock"),
+            vec![DebateState::InRound, DebateState::Concluded]
+        );
+    }
+
     #[test]
     fn test_critique_prompt_includes_previous_responses() {
         let config = test_config();
@@ -0,0 +1,133 @@
+//! Task update fan-out.
+//!
+//! Every status and artifact update of a task is published here once, and
+//! reaches both its live SSE subscribers (`message/stream`,
+//! `tasks/resubscribe`) and its push notification webhook, if one is set.
+
+use crate::a2a::persistence;
+use crate::a2a::push::PushNotifier;
+use crate::a2a::types::*;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{broadcast, RwLock};
+
+/// Updates buffered per task for slow SSE subscribers
+const SUBSCRIBER_BUFFER: usize = 64;
+
+/// Publishes task updates to SSE subscribers and push notification webhooks.
+#[derive(Clone)]
+pub struct TaskEvents {
+    /// Live update channel of each task still being processed
+    channels: Arc<RwLock<HashMap<String, broadcast::Sender<StreamEvent>>>>,
+    notifier: PushNotifier,
+    pool: SqlitePool,
+}
+
+impl TaskEvents {
+    /// `push_secret` signs webhook deliveries (see `crate::a2a::push`).
+    pub fn new(pool: SqlitePool, push_secret: Option<String>) -> Self {
+        Self {
+            channels: Arc::new(RwLock::new(HashMap::new())),
+            notifier: PushNotifier::new(push_secret),
+            pool,
+        }
+    }
+
+    /// Open the update channel of a new task. Call before its processing
+    /// starts so no update is missed.
+    pub async fn open(&self, task_id: &str) {
+        let (tx, _) = broadcast::channel(SUBSCRIBER_BUFFER);
+        self.channels.write().await.insert(task_id.to_string(), tx);
+    }
+
+    /// Live updates of a task, or `None` once it is no longer being processed.
+    pub async fn subscribe(&self, task_id: &str) -> Option<broadcast::Receiver<StreamEvent>> {
+        self.channels.read().await.get(task_id).map(|tx| tx.subscribe())
+    }
+
+    /// Publish an update. A final status update closes the task's channel.
+    pub async fn publish(&self, event: StreamEvent) {
+        let task_id = event_task_id(&event).to_string();
+        let is_final = is_final(&event);
+
+        if is_final {
+            // Dropping the sender ends subscriptions once they drained the buffer
+            if let Some(tx) = self.channels.write().await.remove(&task_id) {
+                let _ = tx.send(event.clone());
+            }
+        } else if let Some(tx) = self.channels.read().await.get(&task_id) {
+            let _ = tx.send(event.clone());
+        }
+
+        if let Some(config) = persistence::load_push_config(&self.pool, &task_id).await {
+            self.notifier.notify(&task_id, config, event, is_final).await;
+        }
+    }
+}
+
+/// ID of the task an update belongs to
+pub fn event_task_id(event: &StreamEvent) -> &str {
+    match event {
+        StreamEvent::Task(task) => &task.id,
+        StreamEvent::StatusUpdate(update) => &update.task_id,
+        StreamEvent::ArtifactUpdate(update) => &update.task_id,
+    }
+}
+
+/// Whether no update of the task follows this one (until it is resumed)
+pub fn is_final(event: &StreamEvent) -> bool {
+    match event {
+        StreamEvent::Task(task) => {
+            is_terminal(&task.status.state) || task.status.state == TaskState::InputRequired
+        }
+        StreamEvent::StatusUpdate(update) => update.is_final,
+        StreamEvent::ArtifactUpdate(_) => false,
+    }
+}
+
+/// Whether a task in `state` is finished for good
+pub fn is_terminal(state: &TaskState) -> bool {
+    matches!(
+        state,
+        TaskState::Completed | TaskState::Failed | TaskState::Canceled | TaskState::Rejected
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::test_helpers::helpers;
+
+    fn status_update(task_id: &str, state: TaskState, is_final: bool) -> StreamEvent {
+        StreamEvent::StatusUpdate(TaskStatusUpdateEvent {
+            kind: "status-update".to_string(),
+            task_id: task_id.to_string(),
+            context_id: "c1".to_string(),
+            status: TaskStatus {
+                state,
+                message: None,
+                timestamp: None,
+            },
+            is_final,
+            metadata: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_subscribers_get_updates_until_final() {
+        let pool = helpers::placeholder_service_context().await.pool();
+        let events = TaskEvents::new(pool, None);
+        assert!(events.subscribe("t1").await.is_none());
+
+        events.open("t1").await;
+        let mut rx = events.subscribe("t1").await.expect("open channel");
+        events.publish(status_update("t1", TaskState::Working, false)).await;
+        events.publish(status_update("t1", TaskState::Completed, true)).await;
+
+        assert!(!is_final(&rx.recv().await.expect("working")));
+        assert!(is_final(&rx.recv().await.expect("completed")));
+        assert!(rx.recv().await.is_err());
+        assert!(events.subscribe("t1").await.is_none());
+    }
+}
@@ -3,12 +3,21 @@
 //! Dispatches JSON-RPC methods:
 //! - `message/send` → create task + process message via AgentService
 //! - `tasks/get`    → retrieve task by ID
+//! - `tasks/list`   → page through stored tasks
 //! - `tasks/cancel` → cancel a running task
+//! - `tasks/pushNotificationConfig/set|get` → webhook for a task's updates
+//!
+//! `message/stream` and `tasks/resubscribe` answer with SSE and live in `stream`.
 
+pub mod events;
+mod parts;
+mod push_config;
 mod send;
 pub mod stream;
 mod tasks;
 
+pub use events::TaskEvents;
+
 use crate::a2a::types::*;
 use crate::brain::agent::service::AgentService;
 use crate::services::ServiceContext;
@@ -20,6 +29,9 @@ use tokio_util::sync::CancellationToken;
 /// In-memory task store.
 pub type TaskStore = Arc<RwLock<HashMap<String, Task>>>;
 
+/// Finished tasks kept in the store. Older ones are evicted, files and all.
+const MAX_FINISHED_TASKS: usize = 500;
+
 /// Cancellation token store — keyed by task ID.
 pub type CancelStore = Arc<RwLock<HashMap<String, CancellationToken>>>;
 
@@ -33,22 +45,89 @@ pub fn new_cancel_store() -> CancelStore {
     Arc::new(RwLock::new(HashMap::new()))
 }
 
+/// Evict the oldest finished tasks beyond `MAX_FINISHED_TASKS` and delete
+/// their files.
+async fn evict_finished(store: &TaskStore) {
+    let evicted = {
+        let mut tasks = store.write().await;
+        let mut finished: Vec<_> = tasks
+            .values()
+            .filter(|t| events::is_terminal(&t.status.state))
+            .map(|t| (t.status.timestamp.clone(), t.id.clone()))
+            .collect();
+        if finished.len() <= MAX_FINISHED_TASKS {
+            return;
+        }
+        // RFC 3339 timestamps in UTC sort by time
+        finished.sort_unstable();
+        let excess = finished.len() - MAX_FINISHED_TASKS;
+        let evicted: Vec<String> = finished
+            .into_iter()
+            .take(excess)
+            .map(|(_, id)| id)
+            .collect();
+        for task_id in &evicted {
+            tasks.remove(task_id);
+        }
+        evicted
+    };
+    for task_id in evicted {
+        parts::remove_task_files(&task_id).await;
+    }
+}
+
+/// Delete the files of tasks that are not in the store, e.g. finished tasks
+/// that were not restored at startup.
+pub async fn remove_orphaned_files(store: &TaskStore) {
+    let with_files = parts::tasks_with_files().await;
+    let orphaned: Vec<String> = {
+        let tasks = store.read().await;
+        with_files
+            .into_iter()
+            .filter(|id| !tasks.contains_key(id))
+            .collect()
+    };
+    for task_id in orphaned {
+        parts::remove_task_files(&task_id).await;
+    }
+}
+
+/// An output file of a stored task, as `(bytes, MIME type)`.
+pub async fn output_file(
+    store: &TaskStore,
+    task_id: &str,
+    path: &str,
+) -> Option<(Vec<u8>, &'static str)> {
+    if !store.read().await.contains_key(task_id) {
+        return None;
+    }
+    parts::read_output(task_id, path).await
+}
+
 /// Dispatch a JSON-RPC request to the appropriate handler.
 pub async fn dispatch(
     req: JsonRpcRequest,
     store: TaskStore,
     cancel_store: CancelStore,
+    events: TaskEvents,
     agent_service: Arc<AgentService>,
     service_context: ServiceContext,
 ) -> JsonRpcResponse {
     match req.method.as_str() {
         "message/send" => {
             send::handle_send_message(
-                req.id, req.params, store, cancel_store, agent_service, service_context,
+                req.id, req.params, store, cancel_store, events, agent_service, service_context,
             ).await
         }
         "tasks/get" => tasks::handle_get_task(req.id, req.params, store).await,
-        "tasks/cancel" => tasks::handle_cancel_task(req.id, req.params, store, cancel_store, &service_context.pool()).await,
+        "tasks/list" => tasks::handle_list_tasks(req.id, req.params, &service_context.pool()).await,
+        "tasks/cancel" => tasks::handle_cancel_task(req.id, req.params, store, cancel_store, &events, &service_context.pool()).await,
+        "tasks/pushNotificationConfig/set" => {
+            push_config::handle_set_push_config(req.id, req.params, store, &service_context.pool()).await
+        }
+        "tasks/pushNotificationConfig/get" => {
+            push_config::handle_get_push_config(req.id, req.params, &service_context.pool()).await
+        }
         _ => JsonRpcResponse::error(
             req.id,
             error_codes::METHOD_NOT_FOUND,
@@ -104,7 +183,8 @@ mod tests {
             params: serde_json::json!({}),
             id: serde_json::json!(99),
         };
-        let resp = dispatch(req, store, cancel_store, agent, ctx).await;
+        let events = TaskEvents::new(ctx.pool(), None);
+        let resp = dispatch(req, store, cancel_store, events, agent, ctx).await;
         assert!(resp.error.is_some());
         assert_eq!(resp.error.as_ref().expect("err").code, -32601);
     }
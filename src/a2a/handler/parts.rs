@@ -0,0 +1,531 @@
+//! Conversion between A2A parts and what the agent works with.
+//!
+//! Incoming parts become one prompt: text as-is, data parts as a JSON block,
+//! images as `<<IMG:…>>` markers (attached as image content) and documents
+//! saved and parsed to text with `parse_document`. Files the agent saves in
+//! the task's output directory come back as artifacts that link to
+//! `/a2a/files/<task id>/<path>`, served while the task is kept.
+
+use crate::a2a::types::{Artifact, Part};
+use crate::brain::tools::doc_parser::DocParserTool;
+use crate::brain::tools::{Tool, ToolExecutionContext};
+use base64::Engine;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+
+/// Largest file accepted in a part or returned as an artifact (20 MiB)
+const MAX_FILE_BYTES: usize = 20 * 1024 * 1024;
+
+/// Text kept from a parsed document
+const MAX_DOCUMENT_CHARS: usize = 50_000;
+
+/// Extensions and MIME types of the files we recognize
+const MIME_TYPES: &[(&str, &str)] = &[
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("bmp", "image/bmp"),
+    ("svg", "image/svg+xml"),
+    ("pdf", "application/pdf"),
+    (
+        "docx",
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    ),
+    ("txt", "text/plain"),
+    ("md", "text/markdown"),
+    ("rst", "text/x-rst"),
+    ("html", "text/html"),
+    ("htm", "text/html"),
+    ("csv", "text/csv"),
+    ("json", "application/json"),
+    ("xml", "application/xml"),
+    ("zip", "application/zip"),
+];
+
+/// The decoded content of one incoming part.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Content {
+    Text(String),
+    Data(serde_json::Value),
+    /// File sent inline
+    File {
+        name: Option<String>,
+        mime: String,
+        bytes: Vec<u8>,
+    },
+    /// File sent by reference
+    FileUrl {
+        name: Option<String>,
+        mime: Option<String>,
+        url: String,
+    },
+}
+
+/// Decode a message's parts, rejecting empty parts and bad or oversized file bytes.
+pub(super) fn decode(parts: &[Part]) -> Result<Vec<Content>, String> {
+    parts.iter().map(decode_part).collect()
+}
+
+fn decode_part(part: &Part) -> Result<Content, String> {
+    if let Some(ref text) = part.text {
+        return Ok(Content::Text(text.clone()));
+    }
+    if let Some(ref data) = part.data {
+        return Ok(Content::Data(data.clone()));
+    }
+
+    let legacy = part.file.clone().unwrap_or_default();
+    let name = part.filename.clone().or(legacy.name);
+    let mime = part.media_type.clone().or(legacy.mime_type);
+
+    if let Some(raw) = part.raw.as_ref().or(legacy.bytes.as_ref()) {
+        if raw.len() / 4 * 3 > MAX_FILE_BYTES {
+            return Err(format!(
+                "File part exceeds {} MiB",
+                MAX_FILE_BYTES / (1024 * 1024)
+            ));
+        }
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(raw.trim())
+            .map_err(|e| format!("File part is not valid base64: {}", e))?;
+        let mime = mime
+            .or_else(|| name.as_deref().and_then(mime_for).map(str::to_string))
+            .unwrap_or_else(|| "application/octet-stream".to_string());
+        return Ok(Content::File { name, mime, bytes });
+    }
+    if let Some(url) = part.url.clone().or(legacy.uri) {
+        return Ok(Content::FileUrl { name, mime, url });
+    }
+
+    Err("Each part must contain text, data, raw bytes or a url".to_string())
+}
+
+/// Short text describing the contents, for session titles and status messages.
+pub(super) fn summary(contents: &[Content]) -> String {
+    let text = contents
+        .iter()
+        .filter_map(|c| match c {
+            Content::Text(text) => Some(text.trim()),
+            _ => None,
+        })
+        .filter(|t| !t.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    if !text.is_empty() {
+        return text;
+    }
+
+    contents
+        .iter()
+        .filter_map(|c| match c {
+            Content::Text(_) => None,
+            Content::Data(_) => Some("[data]".to_string()),
+            Content::File { name, mime, .. } => {
+                Some(format!("[file: {}]", name.as_deref().unwrap_or(mime)))
+            }
+            Content::FileUrl { url, .. } => Some(format!("[file: {}]", url)),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Build the agent's prompt, saving inline files to `in_dir`.
+pub(super) async fn render(contents: Vec<Content>, in_dir: &Path) -> String {
+    let mut sections = Vec::new();
+    for (index, content) in contents.into_iter().enumerate() {
+        let section = match content {
+            Content::Text(text) => text,
+            Content::Data(value) => format!(
+                "[Structured data]\n```json\n{}\n```",
+                serde_json::to_string_pretty(&value).unwrap_or_default()
+            ),
+            Content::FileUrl { name, mime, url } => {
+                let mime = mime.or_else(|| mime_for(&url).map(str::to_string));
+                let is_web = url.starts_with("http://") || url.starts_with("https://");
+                if is_web && mime.as_deref().is_some_and(|m| m.starts_with("image/")) {
+                    format!("<<IMG:{}>>", url)
+                } else {
+                    // Not fetched here — the agent decides whether to download it
+                    format!(
+                        "[Attached file {}: {} ({})]",
+                        name.as_deref().unwrap_or("by reference"),
+                        url,
+                        mime.as_deref().unwrap_or("unknown type")
+                    )
+                }
+            }
+            Content::File { name, mime, bytes } => {
+                let path = match save_file(in_dir, index, name.as_deref(), &mime, &bytes).await {
+                    Ok(path) => path,
+                    Err(e) => {
+                        tracing::warn!("A2A: Failed to save attached file: {}", e);
+                        sections.push(format!(
+                            "[Attached file {} ({}) could not be saved]",
+                            name.as_deref().unwrap_or("unnamed"),
+                            mime
+                        ));
+                        continue;
+                    }
+                };
+                if mime.starts_with("image/") {
+                    format!("<<IMG:{}>>", path.display())
+                } else {
+                    match parse_document(&path).await {
+                        Some(text) => {
+                            format!("[Attached document saved at {}]\n{}", path.display(), text)
+                        }
+                        None => format!("[Attached file saved at {} ({})]", path.display(), mime),
+                    }
+                }
+            }
+        };
+        sections.push(section);
+    }
+    sections.join("\n\n")
+}
+
+/// Write an inline file, named after the sender's file name (without any
+/// directories) with an extension matching its MIME type. A name that is
+/// already taken gets a `-2`, `-3`, … suffix.
+async fn save_file(
+    dir: &Path,
+    index: usize,
+    name: Option<&str>,
+    mime: &str,
+    bytes: &[u8],
+) -> std::io::Result<PathBuf> {
+    let mut file_name = name
+        .and_then(|n| Path::new(n).file_name())
+        .and_then(|n| n.to_str())
+        .filter(|n| !n.starts_with('.'))
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("part-{}", index + 1));
+    if mime_for(&file_name) != Some(mime)
+        && let Some(ext) = extension_for(mime)
+    {
+        file_name = format!("{}.{}", file_name, ext);
+    }
+
+    tokio::fs::create_dir_all(dir).await?;
+    let (stem, ext) = match file_name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, format!(".{}", ext)),
+        _ => (file_name.as_str(), String::new()),
+    };
+    for n in 1.. {
+        let path = match n {
+            1 => dir.join(&file_name),
+            n => dir.join(format!("{}-{}{}", stem, n, ext)),
+        };
+        let mut file = match tokio::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .await
+        {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        };
+        file.write_all(bytes).await?;
+        file.flush().await?;
+        return Ok(path);
+    }
+    unreachable!("file name suffixes are unbounded")
+}
+
+/// Text of a document, via the `parse_document` tool.
+async fn parse_document(path: &Path) -> Option<String> {
+    let input = serde_json::json!({
+        "path": path.to_string_lossy(),
+        "max_chars": MAX_DOCUMENT_CHARS,
+    });
+    let context = ToolExecutionContext::new(Uuid::nil());
+    match DocParserTool.execute(input, &context).await {
+        Ok(result) if result.success => Some(result.output),
+        Ok(result) => {
+            tracing::debug!(
+                "A2A: {} not parsed: {}",
+                path.display(),
+                result.error.unwrap_or_default()
+            );
+            None
+        }
+        Err(e) => {
+            tracing::debug!("A2A: {} not parsed: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// MIME type for a file name, path or URL, from its extension.
+pub(super) fn mime_for(name: &str) -> Option<&'static str> {
+    let name = name.split(['?', '#']).next().unwrap_or(name);
+    let (_, ext) = name.rsplit_once('.')?;
+    let ext = ext.to_ascii_lowercase();
+    MIME_TYPES
+        .iter()
+        .find(|(e, _)| *e == ext)
+        .map(|(_, mime)| *mime)
+}
+
+/// File extension for a MIME type.
+fn extension_for(mime: &str) -> Option<&'static str> {
+    MIME_TYPES
+        .iter()
+        .find(|(_, m)| m.eq_ignore_ascii_case(mime))
+        .map(|(ext, _)| *ext)
+}
+
+/// Where the files of all tasks live, one directory per task.
+fn files_root() -> PathBuf {
+    crate::config::opencrabs_home().join("a2a").join("files")
+}
+
+/// Where a task's exchanged files live: `in/` for received, `out/` for returned.
+pub(super) fn task_dir(task_id: &str) -> PathBuf {
+    files_root().join(task_id)
+}
+
+/// Delete a task's files, once the task is no longer kept.
+pub(super) async fn remove_task_files(task_id: &str) {
+    let dir = task_dir(task_id);
+    match tokio::fs::remove_dir_all(&dir).await {
+        Ok(()) => tracing::debug!("A2A: Removed files of task {}", task_id),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => tracing::warn!("A2A: Failed to remove {}: {}", dir.display(), e),
+    }
+}
+
+/// Ids of the tasks that have a files directory.
+pub(super) async fn tasks_with_files() -> Vec<String> {
+    let mut ids = Vec::new();
+    let Ok(mut entries) = tokio::fs::read_dir(files_root()).await else {
+        return ids;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        if let Some(id) = entry.file_name().to_str() {
+            ids.push(id.to_string());
+        }
+    }
+    ids
+}
+
+/// Path a task's artifacts link to for one of its output files.
+fn file_url(task_id: &str, name: &str) -> String {
+    let path: Vec<_> = name.split('/').map(urlencoding::encode).collect();
+    format!(
+        "/a2a/files/{}/{}",
+        urlencoding::encode(task_id),
+        path.join("/")
+    )
+}
+
+/// An output file of a task, as `(bytes, MIME type)`. `None` unless `path` is
+/// a file inside the task's output directory.
+pub(super) async fn read_output(task_id: &str, path: &str) -> Option<(Vec<u8>, &'static str)> {
+    let out_dir = tokio::fs::canonicalize(task_dir(task_id).join("out"))
+        .await
+        .ok()?;
+    let file = tokio::fs::canonicalize(out_dir.join(path)).await.ok()?;
+    // Rejects `..` and symlinks leading out of the directory
+    if !file.starts_with(&out_dir) {
+        return None;
+    }
+    let bytes = tokio::fs::read(&file).await.ok()?;
+    Some((bytes, mime_for(path).unwrap_or("application/octet-stream")))
+}
+
+/// Modification time and size of each file under `dir`.
+pub(super) type Snapshot = HashMap<PathBuf, (SystemTime, u64)>;
+
+/// Record the files under `dir`, so `collect_outputs` can tell which changed.
+pub(super) async fn snapshot(dir: &Path) -> Snapshot {
+    let mut files = Snapshot::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let Ok(mut entries) = tokio::fs::read_dir(&current).await else {
+            continue;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let Ok(meta) = entry.metadata().await else {
+                continue;
+            };
+            if meta.is_dir() {
+                pending.push(entry.path());
+            } else if meta.is_file() {
+                let modified = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                files.insert(entry.path(), (modified, meta.len()));
+            }
+        }
+    }
+    files
+}
+
+/// One artifact per file under `dir`, the output directory of `task_id`, that
+/// is new or changed since `before`. Each links to the file rather than
+/// carrying its bytes.
+pub(super) async fn collect_outputs(dir: &Path, before: &Snapshot, task_id: &str) -> Vec<Artifact> {
+    let mut changed: Vec<_> = snapshot(dir)
+        .await
+        .into_iter()
+        .filter(|(path, stat)| before.get(path) != Some(stat))
+        .collect();
+    changed.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut artifacts = Vec::new();
+    for (path, (_, size)) in changed {
+        let name = path
+            .strip_prefix(dir)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .to_string();
+        if size > MAX_FILE_BYTES as u64 {
+            tracing::warn!("A2A: Not returning {} ({} bytes, too large)", name, size);
+            continue;
+        }
+        let mime = mime_for(&name).unwrap_or("application/octet-stream");
+        artifacts.push(Artifact {
+            artifact_id: Some(Uuid::new_v4().to_string()),
+            name: Some(name.clone()),
+            description: Some("File written by the agent".to_string()),
+            parts: vec![Part::file_url(file_url(task_id, &name), mime, name)],
+            metadata: None,
+        });
+    }
+    artifacts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn part(value: serde_json::Value) -> Part {
+        serde_json::from_value(value).expect("part")
+    }
+
+    #[test]
+    fn test_decode_parts() {
+        let contents = decode(&[
+            Part::text("Summarize these"),
+            Part::data(serde_json::json!({"rows": 3})),
+            part(serde_json::json!({"raw": "YWJj", "filename": "notes.txt"})),
+            part(serde_json::json!({
+                "kind": "file",
+                "file": {"uri": "https://example.com/chart.png"}
+            })),
+        ])
+        .expect("decoded");
+
+        assert_eq!(contents[0], Content::Text("Summarize these".to_string()));
+        assert_eq!(contents[1], Content::Data(serde_json::json!({"rows": 3})));
+        assert_eq!(
+            contents[2],
+            Content::File {
+                name: Some("notes.txt".to_string()),
+                mime: "text/plain".to_string(),
+                bytes: b"abc".to_vec(),
+            }
+        );
+        assert!(
+            matches!(contents[3], Content::FileUrl { ref url, .. } if url.ends_with("chart.png"))
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_bad_parts() {
+        assert!(decode(&[part(serde_json::json!({"raw": "not base64!"}))]).is_err());
+        assert!(decode(&[part(serde_json::json!({"metadata": {}}))]).is_err());
+    }
+
+    #[test]
+    fn test_summary_without_text() {
+        let contents = vec![
+            Content::Text("  ".to_string()),
+            Content::File {
+                name: Some("report.pdf".to_string()),
+                mime: "application/pdf".to_string(),
+                bytes: vec![],
+            },
+            Content::Data(serde_json::json!({})),
+        ];
+        assert_eq!(summary(&contents), "[file: report.pdf] [data]");
+    }
+
+    #[test]
+    fn test_mime_lookup() {
+        assert_eq!(mime_for("photo.JPG"), Some("image/jpeg"));
+        assert_eq!(mime_for("https://x.io/a.pdf?dl=1"), Some("application/pdf"));
+        assert_eq!(mime_for("Makefile"), None);
+        assert_eq!(extension_for("image/png"), Some("png"));
+    }
+
+    #[tokio::test]
+    async fn test_render_saves_files() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let prompt = render(
+            vec![
+                Content::Text("Look at this".to_string()),
+                Content::File {
+                    name: Some("../../shot".to_string()),
+                    mime: "image/png".to_string(),
+                    bytes: vec![1, 2, 3],
+                },
+                Content::File {
+                    name: Some("notes.txt".to_string()),
+                    mime: "text/plain".to_string(),
+                    bytes: b"the launch is on friday".to_vec(),
+                },
+            ],
+            dir.path(),
+        )
+        .await;
+
+        let image = dir.path().join("shot.png");
+        assert!(image.exists());
+        assert!(prompt.contains(&format!("<<IMG:{}>>", image.display())));
+        assert!(prompt.contains("the launch is on friday"));
+    }
+
+    #[tokio::test]
+    async fn test_save_file_keeps_same_name_files() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let first = save_file(dir.path(), 0, Some("notes.txt"), "text/plain", b"one")
+            .await
+            .expect("saved");
+        let second = save_file(dir.path(), 1, Some("notes.txt"), "text/plain", b"two")
+            .await
+            .expect("saved");
+
+        assert_eq!(first, dir.path().join("notes.txt"));
+        assert_eq!(second, dir.path().join("notes-2.txt"));
+        assert_eq!(std::fs::read(first).expect("read"), b"one");
+    }
+
+    #[tokio::test]
+    async fn test_collect_outputs_returns_changed_files() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::write(dir.path().join("old.txt"), "old").expect("write");
+        let before = snapshot(dir.path()).await;
+
+        std::fs::create_dir(dir.path().join("charts")).expect("mkdir");
+        std::fs::write(dir.path().join("charts").join("q3.png"), [0u8; 4]).expect("write");
+
+        let artifacts = collect_outputs(dir.path(), &before, "t1").await;
+        assert_eq!(artifacts.len(), 1);
+        let part = &artifacts[0].parts[0];
+        assert_eq!(part.media_type.as_deref(), Some("image/png"));
+        assert_eq!(part.url.as_deref(), Some("/a2a/files/t1/charts/q3.png"));
+        assert!(part.raw.is_none());
+        assert!(
+            artifacts[0]
+                .name
+                .as_deref()
+                .is_some_and(|n| n.ends_with("q3.png"))
+        );
+    }
+}
@@ -0,0 +1,184 @@
+//! Handlers for `tasks/pushNotificationConfig/set` and `tasks/pushNotificationConfig/get`.
+
+use super::TaskStore;
+use crate::a2a::{persistence, types::*};
+use uuid::Uuid;
+
+/// Check a webhook config and give it an ID if it has none.
+fn prepare(mut config: PushNotificationConfig) -> Result<PushNotificationConfig, String> {
+    let url = reqwest::Url::parse(&config.url).map_err(|e| format!("Invalid webhook URL: {}", e))?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err("Webhook URL must be http or https".to_string());
+    }
+    if config.id.is_none() {
+        config.id = Some(Uuid::new_v4().to_string());
+    }
+    Ok(config)
+}
+
+/// The `pushNotificationConfig` of a `message/send` or `message/stream`
+/// request's `configuration`, if it has one.
+pub fn from_configuration(
+    configuration: Option<&serde_json::Value>,
+) -> Result<Option<PushNotificationConfig>, String> {
+    let Some(value) = configuration.and_then(|c| c.get("pushNotificationConfig")) else {
+        return Ok(None);
+    };
+    let config: PushNotificationConfig = serde_json::from_value(value.clone())
+        .map_err(|e| format!("Invalid pushNotificationConfig: {}", e))?;
+    prepare(config).map(Some)
+}
+
+/// Handle `tasks/pushNotificationConfig/set` — attach a webhook to a task.
+pub async fn handle_set_push_config(
+    id: serde_json::Value,
+    params: serde_json::Value,
+    store: TaskStore,
+    pool: &sqlx::SqlitePool,
+) -> JsonRpcResponse {
+    let params: TaskPushNotificationConfig = match serde_json::from_value(params) {
+        Ok(p) => p,
+        Err(e) => {
+            return JsonRpcResponse::error(
+                id,
+                error_codes::INVALID_PARAMS,
+                format!("Invalid params: {}", e),
+            );
+        }
+    };
+
+    if !store.read().await.contains_key(&params.task_id) {
+        return JsonRpcResponse::error(
+            id,
+            error_codes::TASK_NOT_FOUND,
+            format!("Task not found: {}", params.task_id),
+        );
+    }
+
+    let config = match prepare(params.push_notification_config) {
+        Ok(config) => config,
+        Err(e) => return JsonRpcResponse::error(id, error_codes::INVALID_PARAMS, e),
+    };
+    if let Err(e) = persistence::save_push_config(pool, &params.task_id, &config).await {
+        return JsonRpcResponse::error(
+            id,
+            error_codes::INTERNAL_ERROR,
+            format!("Failed to save push notification config: {}", e),
+        );
+    }
+    tracing::info!("A2A: Push notifications for task {} go to {}", params.task_id, config.url);
+
+    let result = TaskPushNotificationConfig {
+        task_id: params.task_id,
+        push_notification_config: config,
+    };
+    JsonRpcResponse::success(
+        id,
+        serde_json::to_value(&result).unwrap_or_else(|_| serde_json::json!({"error": "serialize"})),
+    )
+}
+
+/// Handle `tasks/pushNotificationConfig/get` — the webhook of a task.
+pub async fn handle_get_push_config(
+    id: serde_json::Value,
+    params: serde_json::Value,
+    pool: &sqlx::SqlitePool,
+) -> JsonRpcResponse {
+    let params: GetTaskPushNotificationConfigParams = match serde_json::from_value(params) {
+        Ok(p) => p,
+        Err(e) => {
+            return JsonRpcResponse::error(
+                id,
+                error_codes::INVALID_PARAMS,
+                format!("Invalid params: {}", e),
+            );
+        }
+    };
+
+    let config = persistence::load_push_config(pool, &params.id)
+        .await
+        .filter(|config| {
+            params.push_notification_config_id.is_none()
+                || config.id == params.push_notification_config_id
+        });
+    match config {
+        Some(config) => {
+            let result = TaskPushNotificationConfig {
+                task_id: params.id,
+                push_notification_config: config,
+            };
+            JsonRpcResponse::success(
+                id,
+                serde_json::to_value(&result)
+                    .unwrap_or_else(|_| serde_json::json!({"error": "serialize"})),
+            )
+        }
+        None => JsonRpcResponse::error(
+            id,
+            error_codes::TASK_NOT_FOUND,
+            format!("No push notification config for task {}", params.id),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::handler::new_task_store;
+    use crate::a2a::test_helpers::helpers;
+
+    #[tokio::test]
+    async fn test_set_and_get_push_config() {
+        let pool = helpers::placeholder_service_context().await.pool();
+        let store = new_task_store();
+        store.write().await.insert(
+            "t1".to_string(),
+            Task {
+                id: "t1".to_string(),
+                context_id: None,
+                status: TaskStatus {
+                    state: TaskState::Working,
+                    message: None,
+                    timestamp: None,
+                },
+                artifacts: vec![],
+                history: vec![],
+                metadata: None,
+            },
+        );
+
+        let resp = handle_set_push_config(
+            serde_json::json!(1),
+            serde_json::json!({
+                "taskId": "t1",
+                "pushNotificationConfig": { "url": "ftp://hooks.example" }
+            }),
+            store.clone(),
+            &pool,
+        )
+        .await;
+        assert_eq!(resp.error.expect("error").code, error_codes::INVALID_PARAMS);
+
+        let resp = handle_set_push_config(
+            serde_json::json!(2),
+            serde_json::json!({
+                "taskId": "t1",
+                "pushNotificationConfig": { "url": "https://hooks.example/a2a", "token": "tok" }
+            }),
+            store,
+            &pool,
+        )
+        .await;
+        let set: TaskPushNotificationConfig =
+            serde_json::from_value(resp.result.expect("result")).expect("config");
+        assert!(set.push_notification_config.id.is_some());
+
+        let resp =
+            handle_get_push_config(serde_json::json!(3), serde_json::json!({"id": "t1"}), &pool)
+                .await;
+        let got: TaskPushNotificationConfig =
+            serde_json::from_value(resp.result.expect("result")).expect("config");
+        assert_eq!(got.push_notification_config.url, "https://hooks.example/a2a");
+        assert_eq!(got.push_notification_config.token.as_deref(), Some("tok"));
+    }
+}
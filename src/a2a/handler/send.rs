@@ -1,89 +1,129 @@
-//! Handler for `message/send` — creates a task and processes it via AgentService.
+//! Handler for `message/send`, and the task lifecycle shared with `message/stream`.
+//!
+//! A message without `taskId` starts a new task; one with the `taskId` of a
+//! task in `input-required` state resumes it. Each `contextId` is bound to one
+//! OpenCrabs session, so every task in a context shares its conversation.
+//! File and data parts reach the agent through `parts`, and files it saves
+//! in the task's output directory are returned as artifacts. Each new task
+//! evicts the oldest finished ones beyond the store's limit.
 
-use super::{CancelStore, TaskStore};
+use super::parts::{self, Content};
+use super::{push_config, CancelStore, TaskEvents, TaskStore};
 use crate::a2a::{persistence, types::*};
 use crate::brain::agent::service::AgentService;
-use crate::services::ServiceContext;
-use crate::services::SessionService;
+use crate::services::{ServiceContext, SessionService};
+use std::path::Path;
 use std::sync::Arc;
+use tokio::sync::broadcast;
 use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
-/// Handle `message/send` — create a task and spawn background processing.
+/// Line the agent ends its reply with when it needs the caller's answer
+pub const INPUT_REQUIRED_MARKER: &str = "[[INPUT_REQUIRED]]";
+
+/// Appended to every A2A message so the agent knows how to ask back and
+/// how to return files
+fn a2a_instructions(out_dir: &Path) -> String {
+    format!(
+        "\n\n[This message came from another agent over A2A. If you cannot finish without \
+         more information from it, ask your question and end your reply with the line \
+         [[INPUT_REQUIRED]] — its answer will continue this conversation. To send files back, \
+         save them in {} — each one is returned as an artifact.]",
+        out_dir.display()
+    )
+}
+
+/// Handle `message/send` — start or resume a task and spawn background processing.
 pub async fn handle_send_message(
     id: serde_json::Value,
     params: serde_json::Value,
     store: TaskStore,
     cancel_store: CancelStore,
+    events: TaskEvents,
     agent_service: Arc<AgentService>,
     service_context: ServiceContext,
 ) -> JsonRpcResponse {
-    let send_params: SendMessageParams = match serde_json::from_value(params) {
-        Ok(p) => p,
-        Err(e) => {
-            return JsonRpcResponse::error(
-                id,
-                error_codes::INVALID_PARAMS,
-                format!("Invalid params: {}", e),
-            );
+    match start_task(
+        &id,
+        params,
+        store,
+        cancel_store,
+        events,
+        agent_service,
+        service_context,
+        false,
+    )
+    .await
+    {
+        Ok((task, _)) => {
+            let task_json = serde_json::to_value(&task)
+                .unwrap_or_else(|_| serde_json::json!({"error": "serialize"}));
+            JsonRpcResponse::success(id, task_json)
         }
-    };
+        Err(error_response) => error_response,
+    }
+}
+
+/// Start or resume the task of a `message/send` or `message/stream` request.
+/// Returns the task as processing starts and, with `subscribe`, a receiver
+/// for its updates.
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn start_task(
+    id: &serde_json::Value,
+    params: serde_json::Value,
+    store: TaskStore,
+    cancel_store: CancelStore,
+    events: TaskEvents,
+    agent_service: Arc<AgentService>,
+    service_context: ServiceContext,
+    subscribe: bool,
+) -> Result<(Task, Option<broadcast::Receiver<StreamEvent>>), JsonRpcResponse> {
+    let send_params: SendMessageParams = serde_json::from_value(params).map_err(|e| {
+        JsonRpcResponse::error(
+            id.clone(),
+            error_codes::INVALID_PARAMS,
+            format!("Invalid params: {}", e),
+        )
+    })?;
 
-    let user_text = send_params
-        .message
-        .parts
-        .iter()
-        .filter_map(|p| p.text.as_deref())
-        .collect::<Vec<_>>()
-        .join("\n");
+    let contents = parts::decode(&send_params.message.parts)
+        .map_err(|e| JsonRpcResponse::error(id.clone(), error_codes::INVALID_PARAMS, e))?;
+    let user_text = parts::summary(&contents);
 
     if user_text.trim().is_empty() {
-        return JsonRpcResponse::error(
-            id,
+        return Err(JsonRpcResponse::error(
+            id.clone(),
             error_codes::INVALID_PARAMS,
-            "Message must contain at least one text part",
-        );
+            "Message must contain at least one text, data or file part",
+        ));
     }
 
-    let task_id = Uuid::new_v4().to_string();
-    let context_id = send_params
-        .message
-        .context_id
-        .clone()
-        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let push = push_config::from_configuration(send_params.configuration.as_ref())
+        .map_err(|e| JsonRpcResponse::error(id.clone(), error_codes::INVALID_PARAMS, e))?;
 
-    let task = Task {
-        id: task_id.clone(),
-        context_id: Some(context_id.clone()),
-        status: TaskStatus {
-            state: TaskState::Working,
-            message: Some(Message {
-                message_id: Some(Uuid::new_v4().to_string()),
-                context_id: Some(context_id.clone()),
-                task_id: Some(task_id.clone()),
-                role: Role::Agent,
-                parts: vec![Part::text(format!(
-                    "Task created. Processing: {}",
-                    if user_text.len() > 100 {
-                        format!("{}...", &user_text[..user_text.floor_char_boundary(100)])
-                    } else {
-                        user_text.clone()
-                    }
-                ))],
-                metadata: None,
-            }),
-            timestamp: Some(chrono::Utc::now().to_rfc3339()),
-        },
-        artifacts: vec![],
-        history: vec![send_params.message.clone()],
-        metadata: None,
+    let resumed = send_params.message.task_id.is_some();
+    let task = match send_params.message.task_id {
+        Some(ref task_id) => resume_task(id, &store, task_id, &send_params.message).await?,
+        None => new_task(&send_params.message, &user_text),
     };
+    let task_id = task.id.clone();
+    let context_id = task.context_id.clone().unwrap_or_default();
 
+    let pool = service_context.pool();
+    if let Some(ref config) = push
+        && let Err(e) = persistence::save_push_config(&pool, &task_id, config).await
+    {
+        tracing::error!("A2A: Failed to save push notification config for {}: {}", task_id, e);
+    }
+
+    if !resumed {
+        super::evict_finished(&store).await;
+    }
     {
         let mut tasks = store.write().await;
         tasks.insert(task_id.clone(), task.clone());
     }
-    persistence::upsert_task(&service_context.pool(), &task).await;
+    persistence::upsert_task(&pool, &task).await;
 
     // Determine skill and read-only mode from configuration metadata
     let read_only = send_params
@@ -94,60 +134,188 @@ pub async fn handle_send_message(
         .map(|s| s == "research")
         .unwrap_or(false);
 
-    tracing::info!("A2A: Task {} created, spawning agent (read_only={})", task_id, read_only);
+    tracing::info!(
+        "A2A: Task {} {}, spawning agent (read_only={})",
+        task_id,
+        if resumed { "resumed" } else { "created" },
+        read_only
+    );
+
+    // Opened even without a subscriber, so tasks/resubscribe can attach later
+    events.open(&task_id).await;
+    if resumed {
+        events
+            .publish(StreamEvent::StatusUpdate(TaskStatusUpdateEvent {
+                kind: "status-update".to_string(),
+                task_id: task_id.clone(),
+                context_id: context_id.clone(),
+                status: task.status.clone(),
+                is_final: false,
+                metadata: None,
+            }))
+            .await;
+    }
+    let updates = if subscribe {
+        events.subscribe(&task_id).await
+    } else {
+        None
+    };
 
-    let bg_store = store.clone();
-    let bg_cancel_store = cancel_store.clone();
-    let bg_task_id = task_id.clone();
-    let bg_context_id = context_id.clone();
-    let bg_pool = service_context.pool();
     tokio::spawn(async move {
         process_task(
-            bg_store,
-            bg_cancel_store,
-            bg_task_id,
-            bg_context_id,
+            store,
+            cancel_store,
+            events,
+            task_id,
+            context_id,
             user_text,
+            contents,
             agent_service,
             service_context,
             read_only,
-            bg_pool,
+            pool,
         )
         .await;
     });
 
-    let task_json =
-        serde_json::to_value(&task).unwrap_or_else(|_| serde_json::json!({"error": "serialize"}));
-    JsonRpcResponse::success(id, task_json)
+    Ok((task, updates))
+}
+
+/// A new working task for `message`, in its context or a new one.
+fn new_task(message: &Message, user_text: &str) -> Task {
+    let task_id = Uuid::new_v4().to_string();
+    let context_id = message
+        .context_id
+        .clone()
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    Task {
+        id: task_id.clone(),
+        context_id: Some(context_id.clone()),
+        status: TaskStatus {
+            state: TaskState::Working,
+            message: Some(agent_message(
+                &task_id,
+                &context_id,
+                format!(
+                    "Task created. Processing: {}",
+                    if user_text.len() > 100 {
+                        format!("{}...", &user_text[..user_text.floor_char_boundary(100)])
+                    } else {
+                        user_text.to_string()
+                    }
+                ),
+            )),
+            timestamp: Some(chrono::Utc::now().to_rfc3339()),
+        },
+        artifacts: vec![],
+        history: vec![message.clone()],
+        metadata: None,
+    }
 }
 
-/// Background task processor: creates a DB session, invokes the agent, updates the A2A task.
+/// Put a task waiting for input back to work with the caller's answer.
+async fn resume_task(
+    id: &serde_json::Value,
+    store: &TaskStore,
+    task_id: &str,
+    message: &Message,
+) -> Result<Task, JsonRpcResponse> {
+    let mut tasks = store.write().await;
+    let Some(task) = tasks.get_mut(task_id) else {
+        return Err(JsonRpcResponse::error(
+            id.clone(),
+            error_codes::TASK_NOT_FOUND,
+            format!("Task not found: {}", task_id),
+        ));
+    };
+    if task.status.state != TaskState::InputRequired {
+        return Err(JsonRpcResponse::error(
+            id.clone(),
+            error_codes::UNSUPPORTED_OPERATION,
+            format!(
+                "Task {} is {:?}; only tasks waiting for input can be continued",
+                task_id, task.status.state
+            ),
+        ));
+    }
+    if message.context_id.is_some() && message.context_id != task.context_id {
+        return Err(JsonRpcResponse::error(
+            id.clone(),
+            error_codes::INVALID_PARAMS,
+            format!("contextId does not match task {}", task_id),
+        ));
+    }
+
+    let context_id = task.context_id.clone().unwrap_or_default();
+    task.history.push(message.clone());
+    task.status = TaskStatus {
+        state: TaskState::Working,
+        message: Some(agent_message(task_id, &context_id, "Continuing with the new input.")),
+        timestamp: Some(chrono::Utc::now().to_rfc3339()),
+    };
+    Ok(task.clone())
+}
+
+/// The session of a context, created and bound on first use.
+async fn context_session(
+    pool: &sqlx::SqlitePool,
+    service_context: ServiceContext,
+    context_id: &str,
+    user_text: &str,
+) -> anyhow::Result<Uuid> {
+    let session_service = SessionService::new(service_context);
+    if let Some(session_id) = persistence::session_for_context(pool, context_id).await
+        && session_service.get_session(session_id).await?.is_some()
+    {
+        return Ok(session_id);
+    }
+
+    let title = format!(
+        "A2A: {}",
+        &user_text[..user_text.floor_char_boundary(60.min(user_text.len()))]
+    );
+    let session = session_service.create_session(Some(title)).await?;
+    persistence::bind_context(pool, context_id, session.id).await?;
+    tracing::info!("A2A: Context {} bound to session {}", context_id, session.id);
+    Ok(session.id)
+}
+
+/// Background task processor: runs the agent in the context's session,
+/// updates the A2A task and publishes each update.
 #[allow(clippy::too_many_arguments)]
 async fn process_task(
     store: TaskStore,
     cancel_store: CancelStore,
+    events: TaskEvents,
     task_id: String,
     context_id: String,
     user_text: String,
+    contents: Vec<Content>,
     agent_service: Arc<AgentService>,
     service_context: ServiceContext,
     read_only: bool,
     pool: sqlx::SqlitePool,
 ) {
-    let session_service = SessionService::new(service_context);
-    let title = format!(
-        "A2A: {}",
-        &user_text[..user_text.floor_char_boundary(60.min(user_text.len()))]
-    );
-    let session_id = match session_service.create_session(Some(title)).await {
-        Ok(session) => session.id,
+    let session_id = match context_session(&pool, service_context, &context_id, &user_text).await {
+        Ok(session_id) => session_id,
         Err(e) => {
-            tracing::error!("A2A: Failed to create session for task {}: {}", task_id, e);
-            update_task_failed(&store, &task_id, &context_id, &format!("Session creation failed: {}", e), &pool).await;
+            tracing::error!("A2A: Failed to get a session for task {}: {}", task_id, e);
+            let message =
+                agent_message(&task_id, &context_id, format!("Session creation failed: {}", e));
+            finish_task(&store, &events, &task_id, TaskState::Failed, message, &pool).await;
             return;
         }
     };
 
+    let files_dir = parts::task_dir(&task_id);
+    let prompt = parts::render(contents, &files_dir.join("in")).await;
+    let out_dir = files_dir.join("out");
+    if let Err(e) = tokio::fs::create_dir_all(&out_dir).await {
+        tracing::warn!("A2A: Failed to create output dir for {}: {}", task_id, e);
+    }
+    let existing_files = parts::snapshot(&out_dir).await;
+
     let cancel_token = CancellationToken::new();
     {
         let mut tokens = cancel_store.write().await;
@@ -157,7 +325,7 @@ async fn process_task(
     let result = agent_service
         .send_message_with_tools_and_mode(
             session_id,
-            user_text,
+            format!("{}{}", prompt, a2a_instructions(&out_dir)),
             None,
             read_only,
             Some(cancel_token),
@@ -170,33 +338,71 @@ async fn process_task(
         tokens.remove(&task_id);
     }
 
+    // tasks/cancel already finalized (and published) the task
+    if store
+        .read()
+        .await
+        .get(&task_id)
+        .is_some_and(|t| t.status.state == TaskState::Canceled)
+    {
+        tracing::info!("A2A: Task {} stopped after cancellation", task_id);
+        return;
+    }
+
     match result {
         Ok(response) => {
-            let mut tasks = store.write().await;
-            if let Some(task) = tasks.get_mut(&task_id) {
-                task.status = TaskStatus {
-                    state: TaskState::Completed,
-                    message: Some(Message {
-                        message_id: Some(Uuid::new_v4().to_string()),
-                        context_id: Some(context_id),
-                        task_id: Some(task_id.clone()),
-                        role: Role::Agent,
-                        parts: vec![Part::text("Task completed.")],
-                        metadata: None,
-                    }),
-                    timestamp: Some(chrono::Utc::now().to_rfc3339()),
-                };
-                task.artifacts.push(Artifact {
+            let (reply, needs_input) = split_input_required(&response.content);
+            let reply_message = agent_message(&task_id, &context_id, reply.clone());
+            {
+                let mut tasks = store.write().await;
+                if let Some(task) = tasks.get_mut(&task_id) {
+                    task.history.push(reply_message.clone());
+                }
+            }
+
+            // The reply is the response artifact only once the task completes,
+            // but files are returned from every turn
+            let mut artifacts = Vec::new();
+            if !needs_input {
+                artifacts.push(Artifact {
                     artifact_id: Some(Uuid::new_v4().to_string()),
                     name: Some("response".to_string()),
                     description: Some("Agent response".to_string()),
-                    parts: vec![Part::text(&response.content)],
+                    parts: vec![Part::text(reply)],
                     metadata: None,
                 });
             }
-            if let Some(task) = tasks.get(&task_id) {
-                persistence::upsert_task(&pool, task).await;
+            artifacts.extend(parts::collect_outputs(&out_dir, &existing_files, &task_id).await);
+
+            for artifact in artifacts {
+                {
+                    let mut tasks = store.write().await;
+                    if let Some(task) = tasks.get_mut(&task_id) {
+                        task.artifacts.push(artifact.clone());
+                    }
+                }
+
+                events.publish(StreamEvent::ArtifactUpdate(TaskArtifactUpdateEvent {
+                    kind: "artifact-update".to_string(),
+                    task_id: task_id.clone(),
+                    context_id: context_id.clone(),
+                    artifact,
+                    append: Some(false),
+                    last_chunk: Some(true),
+                    metadata: None,
+                })).await;
             }
+
+            if needs_input {
+                finish_task(&store, &events, &task_id, TaskState::InputRequired,
+                    reply_message, &pool).await;
+                tracing::info!("A2A: Task {} waiting for input", task_id);
+                return;
+            }
+
+            let message = agent_message(&task_id, &context_id, "Task completed.");
+            finish_task(&store, &events, &task_id, TaskState::Completed, message, &pool).await;
+
             tracing::info!(
                 "A2A: Task {} completed ({} tokens used)",
                 task_id,
@@ -205,27 +411,141 @@ async fn process_task(
         }
         Err(e) => {
             tracing::error!("A2A: Task {} failed: {}", task_id, e);
-            update_task_failed(&store, &task_id, &context_id, &e.to_string(), &pool).await;
+            let message = agent_message(&task_id, &context_id, format!("Task failed: {}", e));
+            finish_task(&store, &events, &task_id, TaskState::Failed, message, &pool).await;
         }
     }
 }
 
-/// Mark a task as failed in the store and persist to DB.
-async fn update_task_failed(store: &TaskStore, task_id: &str, context_id: &str, error_msg: &str, pool: &sqlx::SqlitePool) {
-    let mut tasks = store.write().await;
-    if let Some(task) = tasks.get_mut(task_id) {
-        task.status = TaskStatus {
-            state: TaskState::Failed,
-            message: Some(Message {
-                message_id: Some(Uuid::new_v4().to_string()),
-                context_id: Some(context_id.to_string()),
-                task_id: Some(task_id.to_string()),
-                role: Role::Agent,
-                parts: vec![Part::text(format!("Task failed: {}", error_msg))],
-                metadata: None,
-            }),
-            timestamp: Some(chrono::Utc::now().to_rfc3339()),
-        };
-        persistence::upsert_task(pool, task).await;
+/// Set the status a turn ends in (terminal or `input-required`), persist the
+/// task and publish the final update.
+async fn finish_task(
+    store: &TaskStore,
+    events: &TaskEvents,
+    task_id: &str,
+    state: TaskState,
+    message: Message,
+    pool: &sqlx::SqlitePool,
+) {
+    let context_id = message.context_id.clone().unwrap_or_default();
+    let status = TaskStatus {
+        state,
+        message: Some(message),
+        timestamp: Some(chrono::Utc::now().to_rfc3339()),
+    };
+
+    // Update store
+    {
+        let mut tasks = store.write().await;
+        if let Some(task) = tasks.get_mut(task_id) {
+            task.status = status.clone();
+            persistence::upsert_task(pool, task).await;
+        }
+    }
+
+    events.publish(StreamEvent::StatusUpdate(TaskStatusUpdateEvent {
+        kind: "status-update".to_string(),
+        task_id: task_id.to_string(),
+        context_id,
+        status,
+        is_final: true,
+        metadata: None,
+    })).await;
+}
+
+/// A text message from the agent
+fn agent_message(task_id: &str, context_id: &str, text: impl Into<String>) -> Message {
+    Message {
+        message_id: Some(Uuid::new_v4().to_string()),
+        context_id: Some(context_id.to_string()),
+        task_id: Some(task_id.to_string()),
+        role: Role::Agent,
+        parts: vec![Part::text(text)],
+        metadata: None,
+    }
+}
+
+/// The reply without the input-required marker, and whether it had one.
+fn split_input_required(content: &str) -> (String, bool) {
+    if !content.contains(INPUT_REQUIRED_MARKER) {
+        return (content.to_string(), false);
+    }
+    (content.replace(INPUT_REQUIRED_MARKER, "").trim().to_string(), true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::handler::new_task_store;
+    use crate::a2a::test_helpers::helpers;
+
+    fn user_message(task_id: Option<&str>, context_id: Option<&str>) -> Message {
+        Message {
+            message_id: None,
+            context_id: context_id.map(str::to_string),
+            task_id: task_id.map(str::to_string),
+            role: Role::User,
+            parts: vec![Part::text("Use the staging database.")],
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_split_input_required() {
+        assert_eq!(
+            split_input_required("Which database?\n[[INPUT_REQUIRED]]\n"),
+            ("Which database?".to_string(), true)
+        );
+        assert_eq!(split_input_required("Done."), ("Done.".to_string(), false));
+    }
+
+    #[tokio::test]
+    async fn test_resume_only_waiting_tasks() {
+        let store = new_task_store();
+        let id = serde_json::json!(1);
+        let mut task = new_task(&user_message(None, Some("c1")), "Migrate the schema");
+        task.status.state = TaskState::InputRequired;
+        let task_id = task.id.clone();
+        store.write().await.insert(task_id.clone(), task);
+
+        let err = resume_task(&id, &store, "missing", &user_message(Some("missing"), None))
+            .await
+            .expect_err("unknown task");
+        assert_eq!(err.error.expect("error").code, error_codes::TASK_NOT_FOUND);
+
+        let err = resume_task(&id, &store, &task_id, &user_message(Some(&task_id), Some("c2")))
+            .await
+            .expect_err("other context");
+        assert_eq!(err.error.expect("error").code, error_codes::INVALID_PARAMS);
+
+        let resumed = resume_task(&id, &store, &task_id, &user_message(Some(&task_id), None))
+            .await
+            .expect("resumed");
+        assert_eq!(resumed.status.state, TaskState::Working);
+        assert_eq!(resumed.history.len(), 2);
+
+        // Already working again
+        let err = resume_task(&id, &store, &task_id, &user_message(Some(&task_id), None))
+            .await
+            .expect_err("working task");
+        assert_eq!(err.error.expect("error").code, error_codes::UNSUPPORTED_OPERATION);
+    }
+
+    #[tokio::test]
+    async fn test_context_reuses_session() {
+        let ctx = helpers::placeholder_service_context().await;
+        let pool = ctx.pool();
+
+        let first = context_session(&pool, ctx.clone(), "c1", "Plan the migration")
+            .await
+            .expect("session");
+        let again = context_session(&pool, ctx.clone(), "c1", "Follow-up")
+            .await
+            .expect("session");
+        let other = context_session(&pool, ctx.clone(), "c2", "Unrelated")
+            .await
+            .expect("session");
+        assert_eq!(first, again);
+        assert_ne!(first, other);
     }
 }
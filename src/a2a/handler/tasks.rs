@@ -1,8 +1,14 @@
-//! Handlers for `tasks/get` and `tasks/cancel` operations.
+//! Handlers for `tasks/get`, `tasks/list` and `tasks/cancel` operations.
 
-use super::{CancelStore, TaskStore};
+use super::{CancelStore, TaskEvents, TaskStore};
 use crate::a2a::{persistence, types::*};
 
+/// Tasks per `tasks/list` page unless `pageSize` is given
+const DEFAULT_PAGE_SIZE: u32 = 50;
+
+/// Largest `tasks/list` page
+const MAX_PAGE_SIZE: u32 = 100;
+
 /// Handle `tasks/get` — retrieve a task by ID.
 pub async fn handle_get_task(
     id: serde_json::Value,
@@ -35,12 +41,94 @@ pub async fn handle_get_task(
     }
 }
 
+/// Handle `tasks/list` — a page of stored tasks, most recently updated first.
+pub async fn handle_list_tasks(
+    id: serde_json::Value,
+    params: serde_json::Value,
+    pool: &sqlx::SqlitePool,
+) -> JsonRpcResponse {
+    let list_params: ListTasksParams = if params.is_null() {
+        ListTasksParams::default()
+    } else {
+        match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => {
+                return JsonRpcResponse::error(
+                    id,
+                    error_codes::INVALID_PARAMS,
+                    format!("Invalid params: {}", e),
+                );
+            }
+        }
+    };
+
+    let offset = match list_params.page_token.as_deref() {
+        None | Some("") => 0,
+        Some(token) => match token.parse::<u64>() {
+            Ok(offset) => offset,
+            Err(_) => {
+                return JsonRpcResponse::error(
+                    id,
+                    error_codes::INVALID_PARAMS,
+                    format!("Invalid pageToken: {}", token),
+                );
+            }
+        },
+    };
+    let page_size = list_params
+        .page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+
+    let (mut tasks, total_size) = match persistence::list_tasks(
+        pool,
+        list_params.context_id.as_deref(),
+        list_params.status.as_ref(),
+        page_size,
+        offset,
+    )
+    .await
+    {
+        Ok(page) => page,
+        Err(e) => {
+            return JsonRpcResponse::error(
+                id,
+                error_codes::INTERNAL_ERROR,
+                format!("Failed to list tasks: {}", e),
+            );
+        }
+    };
+
+    for task in &mut tasks {
+        if !list_params.include_artifacts {
+            task.artifacts.clear();
+        }
+        if let Some(limit) = list_params.history_length {
+            let keep = task.history.len().saturating_sub(limit as usize);
+            task.history.drain(..keep);
+        }
+    }
+
+    let next = offset + tasks.len() as u64;
+    let result = ListTasksResult {
+        next_page_token: if next < total_size { next.to_string() } else { String::new() },
+        page_size,
+        total_size,
+        tasks,
+    };
+    JsonRpcResponse::success(
+        id,
+        serde_json::to_value(&result).unwrap_or_else(|_| serde_json::json!({"error": "serialize"})),
+    )
+}
+
 /// Handle `tasks/cancel` — cancel a running task and its background agent.
 pub async fn handle_cancel_task(
     id: serde_json::Value,
     params: serde_json::Value,
     store: TaskStore,
     cancel_store: CancelStore,
+    events: &TaskEvents,
     pool: &sqlx::SqlitePool,
 ) -> JsonRpcResponse {
     let cancel_params: CancelTaskParams = match serde_json::from_value(params) {
@@ -63,34 +151,45 @@ pub async fn handle_cancel_task(
         }
     }
 
-    let mut tasks = store.write().await;
-    match tasks.get_mut(&cancel_params.id) {
-        Some(task) => {
-            match task.status.state {
-                TaskState::Completed | TaskState::Failed | TaskState::Canceled => {
-                    JsonRpcResponse::error(
-                        id,
-                        error_codes::UNSUPPORTED_OPERATION,
-                        format!("Cannot cancel task in {:?} state", task.status.state),
-                    )
-                }
-                _ => {
-                    task.status.state = TaskState::Canceled;
-                    task.status.timestamp = Some(chrono::Utc::now().to_rfc3339());
-                    persistence::upsert_task(pool, task).await;
-                    tracing::info!("A2A: Canceled task {}", cancel_params.id);
-                    let task_json = serde_json::to_value(&*task)
-                        .unwrap_or_else(|_| serde_json::json!({"error": "serialize"}));
-                    JsonRpcResponse::success(id, task_json)
-                }
-            }
+    let (task_json, update) = {
+        let mut tasks = store.write().await;
+        let Some(task) = tasks.get_mut(&cancel_params.id) else {
+            return JsonRpcResponse::error(
+                id,
+                error_codes::TASK_NOT_FOUND,
+                format!("Task not found: {}", cancel_params.id),
+            );
+        };
+        if matches!(
+            task.status.state,
+            TaskState::Completed | TaskState::Failed | TaskState::Canceled
+        ) {
+            return JsonRpcResponse::error(
+                id,
+                error_codes::UNSUPPORTED_OPERATION,
+                format!("Cannot cancel task in {:?} state", task.status.state),
+            );
         }
-        None => JsonRpcResponse::error(
-            id,
-            error_codes::TASK_NOT_FOUND,
-            format!("Task not found: {}", cancel_params.id),
-        ),
-    }
+
+        task.status.state = TaskState::Canceled;
+        task.status.timestamp = Some(chrono::Utc::now().to_rfc3339());
+        persistence::upsert_task(pool, task).await;
+        tracing::info!("A2A: Canceled task {}", cancel_params.id);
+        let task_json = serde_json::to_value(&*task)
+            .unwrap_or_else(|_| serde_json::json!({"error": "serialize"}));
+        let update = StreamEvent::StatusUpdate(TaskStatusUpdateEvent {
+            kind: "status-update".to_string(),
+            task_id: task.id.clone(),
+            context_id: task.context_id.clone().unwrap_or_default(),
+            status: task.status.clone(),
+            is_final: true,
+            metadata: None,
+        });
+        (task_json, update)
+    };
+
+    events.publish(update).await;
+    JsonRpcResponse::success(id, task_json)
 }
 
 #[cfg(test)]
@@ -104,15 +203,74 @@ mod tests {
         let store = new_task_store();
         let cancel_store = new_cancel_store();
         let ctx = helpers::placeholder_service_context().await;
+        let events = TaskEvents::new(ctx.pool(), None);
         let resp = handle_cancel_task(
             serde_json::json!(1),
             serde_json::json!({"id": "nonexistent"}),
             store,
             cancel_store,
+            &events,
             &ctx.pool(),
         )
         .await;
         assert!(resp.error.is_some());
         assert_eq!(resp.error.as_ref().expect("err").code, error_codes::TASK_NOT_FOUND);
     }
+
+    #[tokio::test]
+    async fn test_list_tasks_pages() {
+        use crate::a2a::test_helpers::helpers;
+        let pool = helpers::placeholder_service_context().await.pool();
+        for n in 0..3 {
+            let task = Task {
+                id: format!("t{}", n),
+                context_id: Some("c1".to_string()),
+                status: TaskStatus {
+                    state: TaskState::Completed,
+                    message: None,
+                    timestamp: None,
+                },
+                artifacts: vec![Artifact {
+                    artifact_id: None,
+                    name: None,
+                    description: None,
+                    parts: vec![Part::text("result")],
+                    metadata: None,
+                }],
+                history: vec![],
+                metadata: None,
+            };
+            persistence::upsert_task(&pool, &task).await;
+        }
+
+        let resp = handle_list_tasks(
+            serde_json::json!(1),
+            serde_json::json!({"contextId": "c1", "pageSize": 2}),
+            &pool,
+        )
+        .await;
+        let page: ListTasksResult =
+            serde_json::from_value(resp.result.expect("result")).expect("page");
+        assert_eq!(page.tasks.len(), 2);
+        assert_eq!(page.total_size, 3);
+        assert_eq!(page.next_page_token, "2");
+        assert!(page.tasks[0].artifacts.is_empty());
+
+        let resp = handle_list_tasks(
+            serde_json::json!(2),
+            serde_json::json!({"contextId": "c1", "pageToken": "2", "includeArtifacts": true}),
+            &pool,
+        )
+        .await;
+        let page: ListTasksResult =
+            serde_json::from_value(resp.result.expect("result")).expect("page");
+        assert_eq!(page.tasks.len(), 1);
+        assert_eq!(page.next_page_token, "");
+        assert_eq!(page.tasks[0].artifacts.len(), 1);
+
+        let resp =
+            handle_list_tasks(serde_json::json!(3), serde_json::json!({"pageToken": "x"}), &pool)
+                .await;
+        assert_eq!(resp.error.expect("error").code, error_codes::INVALID_PARAMS);
+    }
 }
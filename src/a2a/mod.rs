@@ -2,14 +2,19 @@
 //!
 //! Implements the A2A Protocol RC v1.0 specification:
 //! - Agent Card discovery (`.well-known/agent.json`)
-//! - JSON-RPC 2.0 task API (`message/send`, `tasks/get`, `tasks/cancel`)
+//! - JSON-RPC 2.0 task API (`message/send`, `message/stream`, `tasks/get`,
+//!   `tasks/list`, `tasks/cancel`, `tasks/resubscribe`)
+//! - Push notifications to webhooks, HMAC-signed (`tasks/pushNotificationConfig/*`)
 //! - HTTP gateway server (axum)
+//! - Client for delegating to remote agents (`a2a_delegate` tool)
 //! - Multi-agent debate protocol (Bee Colony)
 
 pub mod agent_card;
+pub mod client;
 pub mod debate;
 pub mod handler;
 pub mod persistence;
+pub mod push;
 pub mod server;
 pub mod types;
 
@@ -1,15 +1,22 @@
-//! SQLite persistence for A2A tasks.
+//! SQLite persistence for A2A tasks and Bee Colony debates.
 //!
-//! Tasks are stored as JSON blobs alongside indexed state/timestamps
-//! so they survive server restarts.
+//! Tasks and debates are stored as JSON blobs alongside indexed
+//! state/timestamps so they survive server restarts.
 
-use super::types::Task;
+use super::debate::DebateSession;
+use super::types::{PushNotificationConfig, Task, TaskState};
 use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// Value of the `state` column for `state`
+fn state_key(state: &TaskState) -> String {
+    format!("{:?}", state).to_lowercase()
+}
 
 /// Save or update a task in the database.
 pub async fn upsert_task(pool: &SqlitePool, task: &Task) {
     let now = chrono::Utc::now().timestamp();
-    let state = format!("{:?}", task.status.state).to_lowercase();
+    let state = state_key(&task.status.state);
     let data = match serde_json::to_string(task) {
         Ok(d) => d,
         Err(e) => {
@@ -59,3 +66,250 @@ pub async fn load_active_tasks(pool: &SqlitePool) -> Vec<Task> {
         })
         .collect()
 }
+
+/// One page of tasks, most recently updated first, and the number of tasks
+/// matching the filters.
+pub async fn list_tasks(
+    pool: &SqlitePool,
+    context_id: Option<&str>,
+    state: Option<&TaskState>,
+    limit: u32,
+    offset: u64,
+) -> Result<(Vec<Task>, u64), sqlx::Error> {
+    let state = state.map(state_key);
+
+    let (total,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM a2a_tasks
+         WHERE (?1 IS NULL OR context_id = ?1) AND (?2 IS NULL OR state = ?2)",
+    )
+    .bind(context_id)
+    .bind(state.as_deref())
+    .fetch_one(pool)
+    .await?;
+
+    let rows: Vec<(String,)> = sqlx::query_as(
+        "SELECT data FROM a2a_tasks
+         WHERE (?1 IS NULL OR context_id = ?1) AND (?2 IS NULL OR state = ?2)
+         ORDER BY updated_at DESC, id
+         LIMIT ?3 OFFSET ?4",
+    )
+    .bind(context_id)
+    .bind(state.as_deref())
+    .bind(i64::from(limit))
+    .bind(i64::try_from(offset).unwrap_or(i64::MAX))
+    .fetch_all(pool)
+    .await?;
+
+    let tasks = rows
+        .iter()
+        .filter_map(|(data,)| {
+            serde_json::from_str::<Task>(data)
+                .inspect_err(|e| tracing::warn!("A2A persistence: bad task JSON: {}", e))
+                .ok()
+        })
+        .collect();
+    Ok((tasks, u64::try_from(total).unwrap_or(0)))
+}
+
+/// Save the push notification config of a task, replacing any earlier one.
+pub async fn save_push_config(
+    pool: &SqlitePool,
+    task_id: &str,
+    config: &PushNotificationConfig,
+) -> Result<(), sqlx::Error> {
+    let data = serde_json::to_string(config).map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+    sqlx::query(
+        "INSERT INTO a2a_push_configs (task_id, data, created_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT(task_id) DO UPDATE SET data = ?2",
+    )
+    .bind(task_id)
+    .bind(&data)
+    .bind(chrono::Utc::now().timestamp())
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// The push notification config of a task, if it has one.
+pub async fn load_push_config(pool: &SqlitePool, task_id: &str) -> Option<PushNotificationConfig> {
+    let row: Option<(String,)> =
+        match sqlx::query_as("SELECT data FROM a2a_push_configs WHERE task_id = ?1")
+            .bind(task_id)
+            .fetch_optional(pool)
+            .await
+        {
+            Ok(r) => r,
+            Err(e) => {
+                tracing::error!("A2A persistence: failed to load push config for {}: {}", task_id, e);
+                return None;
+            }
+        };
+
+    row.and_then(|(data,)| {
+        serde_json::from_str(&data)
+            .inspect_err(|e| tracing::warn!("A2A persistence: bad push config JSON: {}", e))
+            .ok()
+    })
+}
+
+/// The session a context is bound to, if any.
+pub async fn session_for_context(pool: &SqlitePool, context_id: &str) -> Option<Uuid> {
+    let row: Option<(String,)> =
+        match sqlx::query_as("SELECT session_id FROM a2a_contexts WHERE context_id = ?1")
+            .bind(context_id)
+            .fetch_optional(pool)
+            .await
+        {
+            Ok(r) => r,
+            Err(e) => {
+                tracing::error!("A2A persistence: failed to load context {}: {}", context_id, e);
+                return None;
+            }
+        };
+    row.and_then(|(id,)| Uuid::parse_str(&id).ok())
+}
+
+/// Bind a context to a session, replacing any earlier binding.
+pub async fn bind_context(
+    pool: &SqlitePool,
+    context_id: &str,
+    session_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO a2a_contexts (context_id, session_id, created_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT(context_id) DO UPDATE SET session_id = ?2",
+    )
+    .bind(context_id)
+    .bind(session_id.to_string())
+    .bind(chrono::Utc::now().timestamp())
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Save a debate, replacing an earlier save of it.
+pub async fn save_debate(pool: &SqlitePool, session: &DebateSession) -> Result<(), sqlx::Error> {
+    let data = serde_json::to_string(session).map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+    sqlx::query(
+        "INSERT INTO debates (id, topic, state, data, created_at) VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(id) DO UPDATE SET state = ?3, data = ?4",
+    )
+    .bind(&session.id)
+    .bind(&session.config.topic)
+    .bind(format!("{:?}", session.state).to_lowercase())
+    .bind(&data)
+    .bind(chrono::Utc::now().timestamp())
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// A saved debate by ID, or the most recent one when `id` is `None`.
+pub async fn load_debate(pool: &SqlitePool, id: Option<&str>) -> Option<DebateSession> {
+    let query = match id {
+        Some(id) => sqlx::query_as("SELECT data FROM debates WHERE id = ?1").bind(id.to_string()),
+        None => sqlx::query_as("SELECT data FROM debates ORDER BY created_at DESC, rowid DESC LIMIT 1"),
+    };
+    let row: Option<(String,)> = match query.fetch_optional(pool).await {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::error!("A2A persistence: failed to load debate: {}", e);
+            return None;
+        }
+    };
+
+    row.and_then(|(data,)| {
+        serde_json::from_str(&data)
+            .inspect_err(|e| tracing::warn!("A2A persistence: bad debate JSON: {}", e))
+            .ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::test_helpers::helpers;
+    use crate::a2a::types::TaskStatus;
+
+    fn task(id: &str, context_id: &str, state: TaskState) -> Task {
+        Task {
+            id: id.to_string(),
+            context_id: Some(context_id.to_string()),
+            status: TaskStatus {
+                state,
+                message: None,
+                timestamp: None,
+            },
+            artifacts: vec![],
+            history: vec![],
+            metadata: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_tasks_filters_and_pages() {
+        let pool = helpers::placeholder_service_context().await.pool();
+        upsert_task(&pool, &task("t1", "c1", TaskState::Completed)).await;
+        upsert_task(&pool, &task("t2", "c1", TaskState::InputRequired)).await;
+        upsert_task(&pool, &task("t3", "c2", TaskState::Completed)).await;
+
+        let (tasks, total) = list_tasks(&pool, Some("c1"), None, 1, 0).await.unwrap();
+        assert_eq!(total, 2);
+        assert_eq!(tasks.len(), 1);
+        let (rest, _) = list_tasks(&pool, Some("c1"), None, 1, 1).await.unwrap();
+        assert_ne!(tasks[0].id, rest[0].id);
+
+        let (tasks, total) =
+            list_tasks(&pool, None, Some(&TaskState::InputRequired), 10, 0).await.unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(tasks[0].id, "t2");
+    }
+
+    #[tokio::test]
+    async fn test_push_config_round_trip() {
+        let pool = helpers::placeholder_service_context().await.pool();
+        assert!(load_push_config(&pool, "t1").await.is_none());
+
+        let mut config = PushNotificationConfig {
+            id: None,
+            url: "https://hooks.example/a2a".to_string(),
+            token: Some("tok".to_string()),
+            authentication: None,
+        };
+        save_push_config(&pool, "t1", &config).await.unwrap();
+        config.url = "https://hooks.example/v2".to_string();
+        save_push_config(&pool, "t1", &config).await.unwrap();
+
+        let loaded = load_push_config(&pool, "t1").await.expect("config");
+        assert_eq!(loaded.url, "https://hooks.example/v2");
+        assert_eq!(loaded.token.as_deref(), Some("tok"));
+    }
+
+    #[tokio::test]
+    async fn test_debate_round_trip() {
+        use crate::a2a::debate::{DebateConfig, DebateState};
+
+        let pool = helpers::placeholder_service_context().await.pool();
+        assert!(load_debate(&pool, None).await.is_none());
+
+        let config = DebateConfig {
+            topic: "Tabs or spaces?".to_string(),
+            num_bees: 2,
+            max_rounds: 2,
+            consensus_threshold: 0.8,
+            knowledge_context: vec![],
+            bee_endpoints: vec![],
+        };
+        let mut first = DebateSession::new(config.clone());
+        save_debate(&pool, &first).await.unwrap();
+        let second = DebateSession::new(config);
+        save_debate(&pool, &second).await.unwrap();
+        first.state = DebateState::Exhausted;
+        save_debate(&pool, &first).await.unwrap();
+
+        let loaded = load_debate(&pool, Some(&first.id)).await.expect("debate");
+        assert_eq!(loaded.state, DebateState::Exhausted);
+        let latest = load_debate(&pool, None).await.expect("latest");
+        assert_eq!(latest.id, second.id);
+    }
+}
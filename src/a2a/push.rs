@@ -0,0 +1,174 @@
+//! Push notification delivery for A2A tasks.
+//!
+//! Each status or artifact update of a task with a push notification config is
+//! POSTed as JSON to the config's webhook URL. Deliveries for one task are sent
+//! in order by a per-task worker and retried with exponential backoff.
+//!
+//! Headers on every delivery:
+//! - `X-A2A-Notification-Token` — the config's `token`, if set
+//! - `Authorization: Bearer …` — when the config's `authentication` asks for it
+//! - `X-A2A-Signature: sha256=<hex>` — HMAC-SHA256 of the body with
+//!   `[a2a] push_secret`, if set
+
+use crate::a2a::types::{PushNotificationConfig, StreamEvent};
+use openssl::hash::MessageDigest;
+use openssl::pkey::PKey;
+use openssl::sign::Signer;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex};
+
+/// Header carrying the config's token
+pub const TOKEN_HEADER: &str = "X-A2A-Notification-Token";
+
+/// Header carrying the body's HMAC signature
+pub const SIGNATURE_HEADER: &str = "X-A2A-Signature";
+
+/// Delivery attempts per event before it is dropped
+const MAX_ATTEMPTS: u32 = 5;
+
+/// Delay before the first retry; doubled on every further retry
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Timeout for one webhook request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+type Delivery = (PushNotificationConfig, StreamEvent);
+
+/// Sends task events to webhooks.
+#[derive(Clone)]
+pub struct PushNotifier {
+    http: reqwest::Client,
+    secret: Option<Arc<String>>,
+    /// Delivery queue of each task with a running worker
+    queues: Arc<Mutex<HashMap<String, mpsc::UnboundedSender<Delivery>>>>,
+}
+
+impl PushNotifier {
+    pub fn new(secret: Option<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            secret: secret.filter(|s| !s.is_empty()).map(Arc::new),
+            queues: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Queue `event` for delivery to `config`. `is_final` ends the task's
+    /// worker once everything queued has been delivered.
+    pub async fn notify(
+        &self,
+        task_id: &str,
+        config: PushNotificationConfig,
+        event: StreamEvent,
+        is_final: bool,
+    ) {
+        let mut queues = self.queues.lock().await;
+        let queue = queues.entry(task_id.to_string()).or_insert_with(|| {
+            let (tx, mut rx) = mpsc::unbounded_channel::<Delivery>();
+            let notifier = self.clone();
+            tokio::spawn(async move {
+                while let Some((config, event)) = rx.recv().await {
+                    notifier.deliver(&config, &event).await;
+                }
+            });
+            tx
+        });
+        let _ = queue.send((config, event));
+        if is_final {
+            queues.remove(task_id);
+        }
+    }
+
+    /// POST one event, retrying network errors, 429 and 5xx responses.
+    async fn deliver(&self, config: &PushNotificationConfig, event: &StreamEvent) {
+        let body = match serde_json::to_vec(event) {
+            Ok(body) => body,
+            Err(e) => {
+                tracing::error!("A2A push: failed to serialize event: {}", e);
+                return;
+            }
+        };
+        let signature = match self.secret.as_deref().map(|s| sign(s, &body)).transpose() {
+            Ok(signature) => signature,
+            Err(e) => {
+                tracing::error!("A2A push: failed to sign event: {}", e);
+                return;
+            }
+        };
+
+        let mut backoff = INITIAL_BACKOFF;
+        for attempt in 1..=MAX_ATTEMPTS {
+            let mut request = self
+                .http
+                .post(&config.url)
+                .timeout(REQUEST_TIMEOUT)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body.clone());
+            if let Some(ref token) = config.token {
+                request = request.header(TOKEN_HEADER, token);
+            }
+            if let Some(ref auth) = config.authentication
+                && auth.schemes.iter().any(|s| s.eq_ignore_ascii_case("bearer"))
+                && let Some(ref credentials) = auth.credentials
+            {
+                request = request.bearer_auth(credentials);
+            }
+            if let Some(ref signature) = signature {
+                request = request.header(SIGNATURE_HEADER, signature);
+            }
+
+            let retryable = match request.send().await {
+                Ok(resp) if resp.status().is_success() => return,
+                Ok(resp) => {
+                    let status = resp.status();
+                    tracing::warn!(
+                        "A2A push: {} answered {} (attempt {}/{})",
+                        config.url, status, attempt, MAX_ATTEMPTS
+                    );
+                    status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "A2A push: delivery to {} failed (attempt {}/{}): {}",
+                        config.url, attempt, MAX_ATTEMPTS, e
+                    );
+                    true
+                }
+            };
+            if !retryable || attempt == MAX_ATTEMPTS {
+                break;
+            }
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+        tracing::error!("A2A push: gave up delivering an event to {}", config.url);
+    }
+}
+
+/// `sha256=<hex>` HMAC-SHA256 signature of `body`
+pub fn sign(secret: &str, body: &[u8]) -> Result<String, openssl::error::ErrorStack> {
+    let key = PKey::hmac(secret.as_bytes())?;
+    let mut signer = Signer::new(MessageDigest::sha256(), &key)?;
+    signer.update(body)?;
+    let hex: String = signer
+        .sign_to_vec()?
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    Ok(format!("sha256={}", hex))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?").unwrap(),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
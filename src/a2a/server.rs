@@ -1,17 +1,18 @@
 //! A2A Gateway HTTP server powered by axum.
 //!
 //! Serves:
-//! - `GET  /.well-known/agent.json` — Agent Card discovery
-//! - `POST /a2a/v1`                 — JSON-RPC 2.0 endpoint
-//! - `GET  /a2a/health`             — Health check
+//! - `GET  /.well-known/agent.json`  — Agent Card discovery
+//! - `POST /a2a/v1`                  — JSON-RPC 2.0 endpoint
+//! - `GET  /a2a/files/{task}/{path}` — Files returned as task artifacts
+//! - `GET  /a2a/health`              — Health check
 
 use crate::a2a::{agent_card, handler, types::*};
 use crate::brain::agent::service::AgentService;
 use crate::config::A2aConfig;
 use crate::services::ServiceContext;
 use axum::{
-    extract::State,
-    http::StatusCode,
+    extract::{Path, State},
+    http::{header, StatusCode},
     middleware,
     response::{sse, IntoResponse, Json, Sse},
     routing::{get, post},
@@ -20,6 +21,7 @@ use axum::{
 use futures::stream;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use tokio::sync::broadcast;
 use tower_http::cors::{AllowOrigin, CorsLayer};
 
 /// Shared state for the A2A gateway.
@@ -27,6 +29,8 @@ use tower_http::cors::{AllowOrigin, CorsLayer};
 pub struct A2aState {
     pub task_store: handler::TaskStore,
     pub cancel_store: handler::CancelStore,
+    /// Fans task updates out to SSE subscribers and push notification webhooks
+    pub events: handler::TaskEvents,
     pub host: String,
     pub port: u16,
     pub agent_service: Arc<AgentService>,
@@ -64,9 +68,10 @@ async fn require_bearer(
     }
 }
 
-/// Build the axum router for the A2A gateway.
-pub fn build_router(state: A2aState, allowed_origins: &[String]) -> Router {
-    let cors = if allowed_origins.is_empty() {
+/// CORS layer allowing only `allowed_origins` (none when empty). Shared with
+/// the HTTP API gateway.
+pub(crate) fn cors_layer(allowed_origins: &[String]) -> CorsLayer {
+    if allowed_origins.is_empty() {
         CorsLayer::new()
     } else {
         let origins: Vec<_> = allowed_origins
@@ -74,11 +79,17 @@ pub fn build_router(state: A2aState, allowed_origins: &[String]) -> Router {
             .filter_map(|o| o.parse().ok())
             .collect();
         CorsLayer::new().allow_origin(AllowOrigin::list(origins))
-    };
+    }
+}
 
-    // Auth-protected JSON-RPC endpoint
+/// Build the axum router for the A2A gateway.
+pub fn build_router(state: A2aState, allowed_origins: &[String]) -> Router {
+    let cors = cors_layer(allowed_origins);
+
+    // Auth-protected JSON-RPC endpoint and task files
     let protected = Router::new()
         .route("/a2a/v1", post(handle_jsonrpc))
+        .route("/a2a/files/{task_id}/{*path}", get(get_task_file))
         .route_layer(middleware::from_fn_with_state(state.clone(), require_bearer));
 
     // Public endpoints (discovery + health)
@@ -113,10 +124,17 @@ pub async fn start_server(
             store.insert(task.id.clone(), task);
         }
     }
+    // Finished tasks aren't restored, so their files are no longer served
+    handler::remove_orphaned_files(&task_store).await;
+
+    if config.push_secret.is_none() {
+        tracing::info!("A2A: no push_secret set — push notifications are sent unsigned");
+    }
 
     let state = A2aState {
         task_store,
         cancel_store: handler::new_cancel_store(),
+        events: handler::TaskEvents::new(service_context.pool(), config.push_secret.clone()),
         host: config.bind.clone(),
         port: config.port,
         agent_service,
@@ -146,8 +164,20 @@ async fn get_agent_card(State(state): State<A2aState>) -> Json<AgentCard> {
     Json(card)
 }
 
+/// GET /a2a/files/{task_id}/{*path} -- a file a stored task returned as an artifact.
+async fn get_task_file(
+    State(state): State<A2aState>,
+    Path((task_id, path)): Path<(String, String)>,
+) -> axum::response::Response {
+    match handler::output_file(&state.task_store, &task_id, &path).await {
+        Some((bytes, mime)) => ([(header::CONTENT_TYPE, mime)], bytes).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
 /// POST /a2a/v1 -- JSON-RPC 2.0 endpoint.
-/// Returns JSON for most methods, SSE stream for `message/stream`.
+/// Returns JSON for most methods, SSE stream for `message/stream` and
+/// `tasks/resubscribe`.
 async fn handle_jsonrpc(
     State(state): State<A2aState>,
     Json(req): Json<JsonRpcRequest>,
@@ -164,8 +194,8 @@ async fn handle_jsonrpc(
             .into_response();
     }
 
-    // message/stream returns SSE instead of JSON
-    if req.method == "message/stream" {
+    // message/stream and tasks/resubscribe return SSE instead of JSON
+    if req.method == "message/stream" || req.method == "tasks/resubscribe" {
         return handle_stream(state, req).await;
     }
 
@@ -173,6 +203,7 @@ async fn handle_jsonrpc(
         req,
         state.task_store,
         state.cancel_store,
+        state.events,
         state.agent_service,
         state.service_context.clone(),
     )
@@ -180,27 +211,58 @@ async fn handle_jsonrpc(
     (StatusCode::OK, Json(response)).into_response()
 }
 
-/// Handle `message/stream` -- returns an SSE stream of task updates.
+/// Handle `message/stream` and `tasks/resubscribe` -- returns an SSE stream of
+/// the task followed by its updates, ending after the final one.
 async fn handle_stream(state: A2aState, req: JsonRpcRequest) -> axum::response::Response {
-    match handler::stream::handle_stream_message(
-        req.id,
-        req.params,
-        state.task_store,
-        state.cancel_store,
-        state.agent_service,
-        state.service_context,
-    )
-    .await
-    {
-        Ok((id, rx)) => {
-            let stream = stream::unfold((id, rx), |(id, mut rx)| async move {
-                let event = rx.recv().await?;
-                let result = serde_json::to_value(&event).unwrap_or_default();
-                let rpc_response = JsonRpcResponse::success(id.clone(), result);
-                let data = serde_json::to_string(&rpc_response).unwrap_or_default();
-                let sse_event = Ok::<_, std::convert::Infallible>(sse::Event::default().data(data));
-                Some((sse_event, (id, rx)))
-            });
+    let subscription = if req.method == "tasks/resubscribe" {
+        handler::stream::handle_resubscribe(req.id, req.params, state.task_store, state.events)
+            .await
+    } else {
+        handler::stream::handle_stream_message(
+            req.id,
+            req.params,
+            state.task_store,
+            state.cancel_store,
+            state.events,
+            state.agent_service,
+            state.service_context,
+        )
+        .await
+    };
+
+    match subscription {
+        Ok((id, subscription)) => {
+            let first = StreamEvent::Task(subscription.task);
+            let done = handler::events::is_final(&first);
+            let updates = if done { None } else { subscription.updates };
+            let stream = stream::unfold(
+                (id, Some(first), updates),
+                |(id, mut pending, mut updates)| async move {
+                    let event = match pending.take() {
+                        Some(event) => event,
+                        None => loop {
+                            match updates.as_mut()?.recv().await {
+                                Ok(event) => break event,
+                                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                                    tracing::warn!(
+                                        "A2A stream: subscriber lagged, {} updates skipped",
+                                        skipped
+                                    );
+                                }
+                                Err(broadcast::error::RecvError::Closed) => return None,
+                            }
+                        },
+                    };
+                    if handler::events::is_final(&event) {
+                        updates = None;
+                    }
+                    let result = serde_json::to_value(&event).unwrap_or_default();
+                    let rpc_response = JsonRpcResponse::success(id.clone(), result);
+                    let data = serde_json::to_string(&rpc_response).unwrap_or_default();
+                    let sse_event = Ok::<_, std::convert::Infallible>(sse::Event::default().data(data));
+                    Some((sse_event, (id, pending, updates)))
+                },
+            );
             Sse::new(stream).into_response()
         }
         Err(error_response) => {
@@ -229,13 +291,15 @@ mod tests {
 
     async fn test_state() -> A2aState {
         use crate::a2a::test_helpers::helpers;
+        let service_context = helpers::placeholder_service_context().await;
         A2aState {
             task_store: handler::new_task_store(),
             cancel_store: handler::new_cancel_store(),
+            events: handler::TaskEvents::new(service_context.pool(), None),
             host: "127.0.0.1".to_string(),
             port: 18790,
             agent_service: helpers::placeholder_agent_service().await,
-            service_context: helpers::placeholder_service_context().await,
+            service_context,
             api_key: None,
         }
     }
@@ -252,6 +316,18 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::OK);
     }
 
+    #[tokio::test]
+    async fn test_files_of_unknown_tasks_not_served() {
+        let app = build_router(test_state().await, &[]);
+        let req = Request::builder()
+            .uri("/a2a/files/missing/report.pdf")
+            .body(Body::empty())
+            .expect("request");
+
+        let resp = app.oneshot(req).await.expect("response");
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
     #[tokio::test]
     async fn test_agent_card_endpoint() {
         let app = build_router(test_state().await, &[]);
@@ -263,4 +339,56 @@ mod tests {
         let resp = app.oneshot(req).await.expect("response");
         assert_eq!(resp.status(), StatusCode::OK);
     }
+
+    #[tokio::test]
+    async fn test_resubscribe_to_finished_task() {
+        let state = test_state().await;
+        state.task_store.write().await.insert(
+            "t1".to_string(),
+            Task {
+                id: "t1".to_string(),
+                context_id: Some("c1".to_string()),
+                status: TaskStatus {
+                    state: TaskState::Completed,
+                    message: None,
+                    timestamp: None,
+                },
+                artifacts: vec![],
+                history: vec![],
+                metadata: None,
+            },
+        );
+        let app = build_router(state, &[]);
+
+        let rpc = |params: serde_json::Value| {
+            let body = serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "method": "tasks/resubscribe", "params": params
+            });
+            Request::builder()
+                .method("POST")
+                .uri("/a2a/v1")
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .expect("request")
+        };
+
+        // A finished task is streamed once, then the stream ends
+        let resp = app
+            .clone()
+            .oneshot(rpc(serde_json::json!({"id": "t1"})))
+            .await
+            .expect("response");
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.expect("body");
+        let body = String::from_utf8_lossy(&body);
+        assert_eq!(body.matches("data:").count(), 1);
+        assert!(body.contains("\"completed\""));
+
+        let resp = app
+            .oneshot(rpc(serde_json::json!({"id": "missing"})))
+            .await
+            .expect("response");
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.expect("body");
+        let rpc_response: JsonRpcResponse = serde_json::from_slice(&body).expect("json");
+        assert_eq!(rpc_response.error.expect("error").code, error_codes::TASK_NOT_FOUND);
+    }
 }
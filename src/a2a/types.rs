@@ -70,6 +70,12 @@ pub struct Part {
     pub data: Option<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,
+    /// Base64-encoded file bytes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw: Option<String>,
+    /// Legacy (v0.3) `FilePart` payload; accepted on input, never emitted.
+    #[serde(default, skip_serializing)]
+    pub file: Option<FilePayload>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub media_type: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -78,18 +84,79 @@ pub struct Part {
     pub metadata: Option<HashMap<String, serde_json::Value>>,
 }
 
+/// Legacy v0.3 file payload: `{"bytes"|"uri", "mimeType", "name"}`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilePayload {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
 impl Part {
-    /// Create a text part.
-    pub fn text(s: impl Into<String>) -> Self {
+    fn empty() -> Self {
         Self {
-            text: Some(s.into()),
+            text: None,
             data: None,
             url: None,
+            raw: None,
+            file: None,
             media_type: None,
             filename: None,
             metadata: None,
         }
     }
+
+    /// Create a text part.
+    pub fn text(s: impl Into<String>) -> Self {
+        Self {
+            text: Some(s.into()),
+            ..Self::empty()
+        }
+    }
+
+    /// Create a structured data part.
+    pub fn data(value: serde_json::Value) -> Self {
+        Self {
+            data: Some(value),
+            media_type: Some("application/json".to_string()),
+            ..Self::empty()
+        }
+    }
+
+    /// Create a file part carrying inline bytes.
+    pub fn file_bytes(
+        bytes: &[u8],
+        media_type: impl Into<String>,
+        filename: impl Into<String>,
+    ) -> Self {
+        use base64::Engine;
+        Self {
+            raw: Some(base64::engine::general_purpose::STANDARD.encode(bytes)),
+            media_type: Some(media_type.into()),
+            filename: Some(filename.into()),
+            ..Self::empty()
+        }
+    }
+
+    /// Create a file part referring to a URL.
+    pub fn file_url(
+        url: impl Into<String>,
+        media_type: impl Into<String>,
+        filename: impl Into<String>,
+    ) -> Self {
+        Self {
+            url: Some(url.into()),
+            media_type: Some(media_type.into()),
+            filename: Some(filename.into()),
+            ..Self::empty()
+        }
+    }
 }
 
 /// Message per §4.1.4.
@@ -330,6 +397,85 @@ pub struct CancelTaskParams {
     pub id: String,
 }
 
+/// TaskIdParams — used by `tasks/resubscribe`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskIdParams {
+    pub id: String,
+}
+
+/// ListTasks params. `pageToken` is the opaque `nextPageToken` of the
+/// previous page.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListTasksParams {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<TaskState>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history_length: Option<u32>,
+    #[serde(default)]
+    pub include_artifacts: bool,
+}
+
+/// ListTasks result. `nextPageToken` is empty on the last page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListTasksResult {
+    pub tasks: Vec<Task>,
+    pub next_page_token: String,
+    pub page_size: u32,
+    pub total_size: u64,
+}
+
+// ─── Push Notifications (§4.3) ───────────────────────────────
+
+/// Authentication the webhook expects, per §4.3.2.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PushNotificationAuthenticationInfo {
+    pub schemes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credentials: Option<String>,
+}
+
+/// Webhook receiving a task's update events, per §4.3.1.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PushNotificationConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub url: String,
+    /// Echoed back in `X-A2A-Notification-Token` so the receiver can match it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authentication: Option<PushNotificationAuthenticationInfo>,
+}
+
+/// Push notification config bound to a task — `tasks/pushNotificationConfig/set`
+/// params and result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskPushNotificationConfig {
+    pub task_id: String,
+    pub push_notification_config: PushNotificationConfig,
+}
+
+/// `tasks/pushNotificationConfig/get` params.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTaskPushNotificationConfigParams {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub push_notification_config_id: Option<String>,
+}
+
 // ─── A2A Error Codes (§9.5) ─────────────────────────────────
 
 /// Standard A2A error codes.
@@ -363,6 +509,27 @@ mod tests {
         assert!(part.data.is_none());
     }
 
+    #[test]
+    fn test_part_file_round_trip() {
+        let part = Part::file_bytes(b"abc", "text/plain", "a.txt");
+        let json = serde_json::to_value(&part).expect("serialize");
+        assert_eq!(json["raw"], "YWJj");
+        assert_eq!(json["mediaType"], "text/plain");
+        assert!(json.get("file").is_none());
+    }
+
+    #[test]
+    fn test_part_legacy_file_deserialization() {
+        let part: Part = serde_json::from_value(serde_json::json!({
+            "kind": "file",
+            "file": {"bytes": "YWJj", "mimeType": "image/png", "name": "x.png"}
+        }))
+        .expect("deserialize");
+        let file = part.file.expect("file payload");
+        assert_eq!(file.bytes.as_deref(), Some("YWJj"));
+        assert_eq!(file.mime_type.as_deref(), Some("image/png"));
+    }
+
     #[test]
     fn test_task_state_serialization() {
         let state = TaskState::Working;
@@ -119,7 +119,7 @@ impl AgentContext {
 
         for content in &message.content {
             match content {
-                ContentBlock::Text { text } => {
+                ContentBlock::Text { text } | ContentBlock::Thinking { text, .. } => {
                     tokens += Self::estimate_tokens(text);
                 }
                 ContentBlock::ToolUse { name, input, .. } => {
@@ -129,6 +129,9 @@ impl AgentContext {
                 ContentBlock::ToolResult { content, .. } => {
                     tokens += Self::estimate_tokens(content);
                 }
+                ContentBlock::RedactedThinking { data } => {
+                    tokens += Self::estimate_tokens(data);
+                }
                 ContentBlock::Image { .. } => {
                     // Images use a fixed token count (approximate)
                     tokens += 1000;
@@ -151,7 +154,7 @@ impl AgentContext {
         let mut tokens = 0;
         for content in &message.content {
             match content {
-                ContentBlock::Text { text } => {
+                ContentBlock::Text { text } | ContentBlock::Thinking { text, .. } => {
                     tokens += Self::estimate_tokens(text);
                 }
                 ContentBlock::ToolUse { name, input, .. } => {
@@ -161,6 +164,9 @@ impl AgentContext {
                 ContentBlock::ToolResult { content, .. } => {
                     tokens += Self::estimate_tokens(content);
                 }
+                ContentBlock::RedactedThinking { data } => {
+                    tokens += Self::estimate_tokens(data);
+                }
                 ContentBlock::Image { .. } => {
                     tokens += 1000;
                 }
@@ -9,8 +9,9 @@ use crate::brain::provider::{
     ContentBlock, ImageSource, LLMRequest, LLMResponse, Message, Provider, ProviderStream, Role,
     StopReason,
 };
-use crate::brain::tools::{ToolExecutionContext, ToolRegistry};
-use crate::services::{MessageService, ServiceContext, SessionService};
+use crate::brain::tools::sandbox::Sandbox;
+use crate::brain::tools::{Permission, PermissionRules, ToolExecutionContext, ToolRegistry};
+use crate::services::{CheckpointService, MessageService, ServiceContext, SessionService};
 use serde_json::Value;
 use std::future::Future;
 use std::pin::Pin;
@@ -21,6 +22,8 @@ use uuid::Uuid;
 /// Tool approval request information
 #[derive(Debug, Clone)]
 pub struct ToolApprovalInfo {
+    /// Session the tool call belongs to
+    pub session_id: Uuid,
     /// Tool name
     pub tool_name: String,
     /// Tool description
@@ -47,6 +50,8 @@ pub enum ProgressEvent {
     IntermediateText { text: String },
     /// Real-time streaming chunk from the LLM (word-by-word)
     StreamingChunk { text: String },
+    /// Real-time reasoning chunk, kept separate from the answer text
+    ThinkingChunk { text: String },
     Compacting,
     /// Compaction finished — carry the summary so the TUI can display it
     CompactionSummary { summary: String },
@@ -54,10 +59,23 @@ pub enum ProgressEvent {
     RestartReady { status: String },
     /// Real-time token count update — fire after every API response and tool execution
     TokenCount(usize),
+    /// The failover chain switched providers after an error
+    ProviderSwitched { from: String, to: String, reason: String },
+    /// A Bee Colony debate started or finished a round
+    DebateUpdated(Box<crate::a2a::debate::DebateSession>),
 //    /// A queued user message was injected into the agent context between tool iterations
 //    QueuedMessageInjected { content: String },
 }
 
+/// What happens to a tool call before it runs
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ToolGate {
+    Run,
+    Ask,
+    /// Blocked by the deny rule with this text
+    Deny(String),
+}
+
 /// Callback for reporting progress during agent execution
 pub type ProgressCallback = Arc<dyn Fn(ProgressEvent) + Send + Sync>;
 
@@ -99,6 +117,15 @@ pub struct AgentService {
     /// Max output tokens for API calls from config
     max_tokens: u32,
 
+    /// Agent settings from config (per-model thinking budgets, tool concurrency)
+    agent_config: crate::config::AgentConfig,
+
+    /// Allow/deny/ask rules checked before a tool's own approval requirement
+    permission_rules: Arc<std::sync::RwLock<PermissionRules>>,
+
+    /// Sandbox for calls matching a `[permissions] sandbox` rule
+    sandbox: Arc<Sandbox>,
+
     /// Callback for requesting tool approval from user
     approval_callback: Option<ApprovalCallback>,
 
@@ -132,6 +159,11 @@ impl AgentService {
             auto_approve_tools: false,
             context_limit: config.agent.context_limit,
             max_tokens: config.agent.max_tokens,
+            permission_rules: Arc::new(std::sync::RwLock::new(PermissionRules::from_config(
+                &config.permissions,
+            ))),
+            sandbox: Arc::new(Sandbox::new(config.sandbox)),
+            agent_config: config.agent,
             approval_callback: None,
             progress_callback: None,
             message_queue_callback: None,
@@ -156,11 +188,57 @@ impl AgentService {
         self.max_tokens
     }
 
+    /// Apply the configured thinking budget for the request's model
+    fn with_thinking_budget(&self, request: LLMRequest) -> LLMRequest {
+        match self.agent_config.thinking_budget_for(&request.model) {
+            Some(budget) => request.with_thinking_budget(budget),
+            None => request,
+        }
+    }
+
+    /// Shared permission rules — the approval UI adds "always allow" rules here
+    pub fn permission_rules(&self) -> Arc<std::sync::RwLock<PermissionRules>> {
+        Arc::clone(&self.permission_rules)
+    }
+
+    /// "Always" in an approval prompt: allow calls like this one from now on.
+    /// Adds a narrowly scoped rule (e.g. `bash(cargo test:*)`), saves it to
+    /// `[permissions] allow` in config.toml and returns the rule text. `None`
+    /// when no rule is narrow enough, so the call is only allowed once.
+    pub fn allow_always(&self, tool_name: &str, tool_input: &Value) -> Option<String> {
+        let rule = crate::brain::tools::permissions::suggest_rule(
+            tool_name,
+            tool_input,
+            &self.working_directory(),
+        )?;
+        self.permission_rules
+            .write()
+            .expect("permission rules lock poisoned")
+            .add_allow(&rule);
+
+        let mut allow = crate::config::Config::load()
+            .map(|c| c.permissions.allow)
+            .unwrap_or_default();
+        if !allow.contains(&rule) {
+            allow.push(rule.clone());
+            if let Err(e) = crate::config::Config::write_array("permissions", "allow", &allow) {
+                tracing::warn!("Failed to save permission rule '{}': {}", rule, e);
+            }
+        }
+        Some(rule)
+    }
+
     /// Get the tool registry
     pub fn tool_registry(&self) -> &Arc<ToolRegistry> {
         &self.tool_registry
     }
 
+    /// Replace the permission rules loaded from config
+    pub fn with_permission_rules(mut self, rules: PermissionRules) -> Self {
+        self.permission_rules = Arc::new(std::sync::RwLock::new(rules));
+        self
+    }
+
     /// Set the default system brain
     pub fn with_system_brain(mut self, prompt: String) -> Self {
         self.default_system_brain = Some(prompt);
@@ -291,11 +369,9 @@ impl AgentService {
             .await?;
 
         // Send to provider
-        let response = self
-            .provider
-            .complete(request)
-            .await
-            .map_err(AgentError::Provider)?;
+        let response = self.provider.complete(request).await;
+        self.report_provider_switches();
+        let response = response.map_err(AgentError::Provider)?;
 
         // Extract text from response
         let assistant_text = Self::extract_text_from_response(&response);
@@ -307,16 +383,18 @@ impl AgentService {
             .map_err(|e| AgentError::Database(e.to_string()))?;
 
         // Calculate total tokens and cost for this message
-        let total_tokens = response.usage.input_tokens + response.usage.output_tokens;
-        let cost = self.provider.calculate_cost(
-            &response.model,
-            response.usage.input_tokens,
-            response.usage.output_tokens,
-        );
+        let total_tokens = response.usage.total();
+        let cost = self.provider.calculate_usage_cost(&response.model, &response.usage);
 
         // Update message with usage info
         message_service
-            .update_message_usage(assistant_db_msg.id, total_tokens as i32, cost)
+            .update_message_usage_with_cache(
+                assistant_db_msg.id,
+                total_tokens as i32,
+                cost,
+                response.usage.cache_creation_input_tokens as i32,
+                response.usage.cache_read_input_tokens as i32,
+            )
             .await
             .map_err(|e| AgentError::Database(e.to_string()))?;
 
@@ -330,10 +408,11 @@ impl AgentService {
             message_id: assistant_db_msg.id,
             content: assistant_text,
             stop_reason: response.stop_reason,
-            context_tokens: response.usage.input_tokens,
+            context_tokens: response.usage.prompt_tokens(),
             usage: response.usage,
             cost,
             model: response.model,
+            changed_files: Vec::new(),
         })
     }
 
@@ -355,11 +434,9 @@ impl AgentService {
         let request = request.with_streaming();
 
         // Get streaming response from provider
-        let stream = self
-            .provider
-            .stream(request)
-            .await
-            .map_err(AgentError::Provider)?;
+        let stream = self.provider.stream(request).await;
+        self.report_provider_switches();
+        let stream = stream.map_err(AgentError::Provider)?;
 
         Ok(AgentStreamResponse {
             session_id,
@@ -433,11 +510,18 @@ impl AgentService {
         context.add_message(user_msg);
 
         // Save user message to database (text only — images are ephemeral)
-        let _user_db_msg = message_service
+        let user_db_msg = message_service
             .create_message(session_id, "user".to_string(), user_message)
             .await
             .map_err(|e| AgentError::Database(e.to_string()))?;
 
+        // Files this turn's tools change are snapshotted here first (for /undo)
+        let checkpoint = Arc::new(CheckpointService::new(self.context.clone()).begin_turn(
+            session_id,
+            Some(user_db_msg.id),
+            &user_db_msg.content,
+        ));
+
         // Reserve tokens for tool definitions (not tracked in context.token_count).
         // Each tool schema is roughly 300-800 tokens; reserve a flat budget.
         let tool_overhead = self.tool_registry.count() * 500;
@@ -504,11 +588,11 @@ impl AgentService {
             .with_read_only_mode(read_only_mode);
         tool_context.sudo_callback = self.sudo_callback.clone();
         tool_context.shared_working_directory = Some(Arc::clone(&self.working_directory));
+        tool_context.checkpoint = Some(Arc::clone(&checkpoint));
 
         // Tool execution loop
         let mut iteration = 0;
-        let mut total_input_tokens = 0u32;
-        let mut total_output_tokens = 0u32;
+        let mut total_usage = crate::brain::provider::TokenUsage::default();
         let mut last_input_tokens = 0u32;
         let mut final_response: Option<LLMResponse> = None;
         let mut accumulated_text = String::new(); // Collect text from all iterations (not just final)
@@ -596,8 +680,9 @@ impl AgentService {
             }
 
             // Build LLM request with tools if available
-            let mut request =
-                LLMRequest::new(model_name.clone(), context.messages.clone()).with_max_tokens(self.max_tokens);
+            let mut request = self.with_thinking_budget(
+                LLMRequest::new(model_name.clone(), context.messages.clone()).with_max_tokens(self.max_tokens),
+            );
 
             if let Some(system) = &context.system_brain {
                 request = request.with_system(system.clone());
@@ -640,8 +725,10 @@ impl AgentService {
                     }
 
                     // Rebuild request with compacted context
-                    let mut retry_req = LLMRequest::new(model_name.clone(), context.messages.clone())
-                        .with_max_tokens(self.max_tokens);
+                    let mut retry_req = self.with_thinking_budget(
+                        LLMRequest::new(model_name.clone(), context.messages.clone())
+                            .with_max_tokens(self.max_tokens),
+                    );
                     if let Some(system) = &context.system_brain {
                         retry_req = retry_req.with_system(system.clone());
                     }
@@ -654,14 +741,14 @@ impl AgentService {
             };
 
             // Track token usage
-            last_input_tokens = response.usage.input_tokens;
-            total_input_tokens += response.usage.input_tokens;
-            total_output_tokens += response.usage.output_tokens;
+            // Cached prompt tokens still occupy the context window
+            last_input_tokens = response.usage.prompt_tokens();
+            total_usage += response.usage;
 
             // Calibrate context token count with the API's real input_tokens.
             // Even with tiktoken, there's some drift since Anthropic's tokenizer differs slightly.
             // The API knows the exact count — use it to keep our tracking honest.
-            let api_input = response.usage.input_tokens as usize;
+            let api_input = last_input_tokens as usize;
             let tool_overhead = self.tool_registry.count() * 500;
             let real_message_tokens = api_input.saturating_sub(tool_overhead);
             if real_message_tokens > 0 {
@@ -693,8 +780,7 @@ impl AgentService {
                         iteration, stream_retry_count, MAX_STREAM_RETRIES,
                     );
                     // Subtract the tokens we just counted — they'll be re-counted on retry
-                    total_input_tokens -= response.usage.input_tokens;
-                    total_output_tokens -= response.usage.output_tokens;
+                    total_usage -= response.usage;
                     // Don't increment iteration — this is a retry, not a new turn
                     iteration -= 1;
                     continue;
@@ -734,7 +820,9 @@ impl AgentService {
                             iteration_text.push_str(text);
                         }
                     }
-                    ContentBlock::ToolUse { id, name, input } => {
+                    ContentBlock::ToolUse {
+                        id, name, input, ..
+                    } => {
                         // GRANULAR LOG: Tool call received from provider
                         let input_keys: Vec<_> = input.as_object().map(|o| o.keys().cloned().collect()).unwrap_or_default();
                         tracing::info!(
@@ -950,13 +1038,67 @@ impl AgentService {
             let mut tool_descriptions: Vec<String> = Vec::new(); // For DB persistence
             let mut tool_outputs: Vec<(bool, String)> = Vec::new(); // (success, output) parallel to descriptions
 
-            for (tool_id, tool_name, tool_input) in tool_uses {
+            // Context for calls that are cleared to run — user-approved, auto-approved,
+            // or allowed by a permission rule — so the registry's approval check passes
+            let approved_tool_context = ToolExecutionContext {
+                session_id: tool_context.session_id,
+                working_directory: tool_context.working_directory.clone(),
+                env_vars: tool_context.env_vars.clone(),
+                auto_approve: true,
+                timeout_secs: tool_context.timeout_secs,
+                read_only_mode: tool_context.read_only_mode,
+                sudo_callback: tool_context.sudo_callback.clone(),
+                shared_working_directory: tool_context.shared_working_directory.clone(),
+                checkpoint: tool_context.checkpoint.clone(),
+                sandbox: tool_context.sandbox.clone(),
+            };
+
+            let mut pending = tool_uses.into_iter().peekable();
+            while let Some((tool_id, tool_name, tool_input)) = pending.next() {
                 // Check for cancellation before each tool
                 if let Some(ref token) = cancel_token
                     && token.is_cancelled() {
                         break;
                     }
 
+                // Consecutive read-only calls run concurrently; results keep the call order
+                if self.runs_concurrently(&tool_name, &tool_input, &tool_context) {
+                    let mut batch = vec![(tool_id, tool_name, tool_input)];
+                    while let Some(call) = pending
+                        .next_if(|(_, name, input)| self.runs_concurrently(name, input, &tool_context))
+                    {
+                        batch.push(call);
+                    }
+
+                    tracing::info!(
+                        "Executing {} read-only tools concurrently (iteration {})",
+                        batch.len(),
+                        iteration,
+                    );
+                    for (_, tool_name, tool_input) in &batch {
+                        tool_descriptions.push(Self::format_tool_summary(tool_name, tool_input));
+                        if let Some(ref cb) = self.progress_callback {
+                            cb(ProgressEvent::ToolStarted {
+                                tool_name: tool_name.clone(),
+                                tool_input: tool_input.clone(),
+                            });
+                        }
+                    }
+
+                    let outcomes = self.run_tool_batch(&batch, &approved_tool_context).await;
+                    for ((tool_id, tool_name, tool_input), (success, content)) in
+                        batch.into_iter().zip(outcomes)
+                    {
+                        tool_outputs.push(self.report_tool_completed(&tool_name, tool_input, success, &content));
+                        tool_results.push(ContentBlock::ToolResult {
+                            tool_use_id: tool_id,
+                            content,
+                            is_error: Some(!success),
+                        });
+                    }
+                    continue;
+                }
+
                 tracing::info!(
                     "Executing tool '{}' (iteration {})",
                     tool_name,
@@ -977,21 +1119,38 @@ impl AgentService {
                     });
                 }
 
-                // Check if approval is needed
-                let needs_approval = if let Some(tool) = self.tool_registry.get(&tool_name) {
-                    tool.requires_approval()
-                        && !self.auto_approve_tools
-                        && !tool_context.auto_approve
-                } else {
-                    false
-                };
+                let gate = self.tool_gate(&tool_name, &tool_input, &tool_context);
+
+                // Blocked by a deny rule — tell the model why so it doesn't keep retrying
+                if let ToolGate::Deny(rule) = gate {
+                    tracing::warn!("Tool '{}' blocked by permission rule '{}'", tool_name, rule);
+                    let content = format!(
+                        "Blocked by the user's permission rule `{}` ([permissions] deny in config.toml). \
+                         Do not retry this call or work around it with another tool; \
+                         explain what you need and let the user decide.",
+                        rule
+                    );
+                    tool_outputs.push(self.report_tool_completed(
+                        &tool_name,
+                        tool_input_for_progress,
+                        false,
+                        &content,
+                    ));
+                    tool_results.push(ContentBlock::ToolResult {
+                        tool_use_id: tool_id,
+                        content,
+                        is_error: Some(true),
+                    });
+                    continue;
+                }
 
                 // Request approval if needed
-                if needs_approval {
+                if gate == ToolGate::Ask {
                     if let Some(ref approval_callback) = self.approval_callback {
                         // Get tool details for approval request
                         let tool_info = if let Some(tool) = self.tool_registry.get(&tool_name) {
                             ToolApprovalInfo {
+                                session_id,
                                 tool_name: tool_name.clone(),
                                 tool_description: tool.description().to_string(),
                                 tool_input: tool_input.clone(),
@@ -1029,90 +1188,21 @@ impl AgentService {
                                     continue;
                                 }
                                 tracing::info!("User approved tool '{}'", tool_name);
-                                // Create approved context for this tool execution
-                                let approved_tool_context = ToolExecutionContext {
-                                    session_id: tool_context.session_id,
-                                    working_directory: tool_context.working_directory.clone(),
-                                    env_vars: tool_context.env_vars.clone(),
-                                    auto_approve: true, // User approved this execution
-                                    timeout_secs: tool_context.timeout_secs,
-                                    read_only_mode: tool_context.read_only_mode,
-                                    sudo_callback: tool_context.sudo_callback.clone(),
-                                    shared_working_directory: tool_context.shared_working_directory.clone(),
-                                };
-
                                 // Execute the tool with approved context
-                                match self
-                                    .tool_registry
-                                    .execute(&tool_name, tool_input, &approved_tool_context)
-                                    .await
-                                {
-                                    Ok(result) => {
-                                        let success = result.success;
-                                        let content = if result.success {
-                                            result.output
-                                        } else {
-                                            result.error.unwrap_or_else(|| {
-                                                "Tool execution failed".to_string()
-                                            })
-                                        };
-                                        
-                                        // GRANULAR LOG: Tool execution result
-                                        if success {
-                                            tracing::info!(
-                                                "[TOOL_EXEC] ✅ Tool '{}' executed successfully, output_len={}",
-                                                tool_name,
-                                                content.len()
-                                            );
-                                        } else {
-                                            tracing::error!(
-                                                "[TOOL_EXEC] ❌ Tool '{}' failed: {}",
-                                                tool_name,
-                                                content.chars().take(200).collect::<String>()
-                                            );
-                                        }
-                                        
-                                        let output_summary: String = content.chars().take(2000).collect();
-                                        tool_outputs.push((success, output_summary.clone()));
-                                        if let Some(ref cb) = self.progress_callback {
-                                            cb(ProgressEvent::ToolCompleted {
-                                                tool_name: tool_name.clone(),
-                                                tool_input: tool_input_for_progress.clone(),
-                                                success,
-                                                summary: output_summary,
-                                            });
-                                        }
-                                        tool_results.push(ContentBlock::ToolResult {
-                                            tool_use_id: tool_id,
-                                            content,
-                                            is_error: Some(!success),
-                                        });
-                                    }
-                                    Err(e) => {
-                                        let err_msg = format!("Tool execution error: {}", e);
-                                        // GRANULAR LOG: Tool execution error
-                                        tracing::error!(
-                                            "[TOOL_EXEC] 💥 Tool '{}' error: {}",
-                                            tool_name,
-                                            err_msg
-                                        );
-                                        let output_summary: String = err_msg.chars().take(2000).collect();
-                                        tool_outputs.push((false, output_summary.clone()));
-                                        if let Some(ref cb) = self.progress_callback {
-                                            cb(ProgressEvent::ToolCompleted {
-                                                tool_name: tool_name.clone(),
-                                                tool_input: tool_input_for_progress.clone(),
-                                                success: false,
-                                                summary: output_summary,
-                                            });
-                                        }
-                                        tool_results.push(ContentBlock::ToolResult {
-                                            tool_use_id: tool_id,
-                                            content: err_msg,
-                                            is_error: Some(true),
-                                        });
-                                    }
-                                }
+                                let (success, content) = self
+                                    .run_tool(&tool_name, tool_input, &approved_tool_context)
+                                    .await;
+                                tool_outputs.push(self.report_tool_completed(
+                                    &tool_name,
+                                    tool_input_for_progress,
+                                    success,
+                                    &content,
+                                ));
+                                tool_results.push(ContentBlock::ToolResult {
+                                    tool_use_id: tool_id,
+                                    content,
+                                    is_error: Some(!success),
+                                });
                                 continue; // Skip the normal execution path below
                             }
                             Err(e) => {
@@ -1143,78 +1233,20 @@ impl AgentService {
                     }
                 }
 
-                // Execute the tool (no approval needed)
-                match self
-                    .tool_registry
-                    .execute(&tool_name, tool_input, &tool_context)
-                    .await
-                {
-                    Ok(result) => {
-                        let success = result.success;
-                        let content = if result.success {
-                            result.output
-                        } else {
-                            result
-                                .error
-                                .unwrap_or_else(|| "Tool execution failed".to_string())
-                        };
-                        
-                        // GRANULAR LOG: Direct tool execution result
-                        if success {
-                            tracing::info!(
-                                "[TOOL_EXEC] ✅ Tool '{}' executed successfully, output_len={}",
-                                tool_name,
-                                content.len()
-                            );
-                        } else {
-                            tracing::error!(
-                                "[TOOL_EXEC] ❌ Tool '{}' failed: {}",
-                                tool_name,
-                                content.chars().take(200).collect::<String>()
-                            );
-                        }
-                        
-                        let output_summary: String = content.chars().take(2000).collect();
-                        tool_outputs.push((success, output_summary.clone()));
-                        if let Some(ref cb) = self.progress_callback {
-                            cb(ProgressEvent::ToolCompleted {
-                                tool_name: tool_name.clone(),
-                                tool_input: tool_input_for_progress.clone(),
-                                success,
-                                summary: output_summary,
-                            });
-                        }
-                        tool_results.push(ContentBlock::ToolResult {
-                            tool_use_id: tool_id,
-                            content,
-                            is_error: Some(!success),
-                        });
-                    }
-                    Err(e) => {
-                        let err_msg = format!("Tool execution error: {}", e);
-                        // GRANULAR LOG: Direct tool execution error
-                        tracing::error!(
-                            "[TOOL_EXEC] 💥 Tool '{}' error: {}",
-                            tool_name,
-                            err_msg
-                        );
-                        let output_summary: String = err_msg.chars().take(2000).collect();
-                        tool_outputs.push((false, output_summary.clone()));
-                        if let Some(ref cb) = self.progress_callback {
-                            cb(ProgressEvent::ToolCompleted {
-                                tool_name: tool_name.clone(),
-                                tool_input: tool_input_for_progress.clone(),
-                                success: false,
-                                summary: output_summary,
-                            });
-                        }
-                        tool_results.push(ContentBlock::ToolResult {
-                            tool_use_id: tool_id,
-                            content: err_msg,
-                            is_error: Some(true),
-                        });
-                    }
-                }
+                // Execute the tool (no approval needed, or allowed by a rule)
+                let (success, content) =
+                    self.run_tool(&tool_name, tool_input, &approved_tool_context).await;
+                tool_outputs.push(self.report_tool_completed(
+                    &tool_name,
+                    tool_input_for_progress,
+                    success,
+                    &content,
+                ));
+                tool_results.push(ContentBlock::ToolResult {
+                    tool_use_id: tool_id,
+                    content,
+                    is_error: Some(!success),
+                });
             }
 
             // Append tool call data to accumulated text for DB persistence.
@@ -1337,14 +1369,18 @@ impl AgentService {
             .map_err(|e| AgentError::Database(e.to_string()))?;
 
         // Calculate total cost
-        let total_tokens = total_input_tokens + total_output_tokens;
-        let cost =
-            self.provider
-                .calculate_cost(&response.model, total_input_tokens, total_output_tokens);
+        let total_tokens = total_usage.total();
+        let cost = self.provider.calculate_usage_cost(&response.model, &total_usage);
 
         // Update message with usage info
         message_service
-            .update_message_usage(assistant_db_msg.id, total_tokens as i32, cost)
+            .update_message_usage_with_cache(
+                assistant_db_msg.id,
+                total_tokens as i32,
+                cost,
+                total_usage.cache_creation_input_tokens as i32,
+                total_usage.cache_read_input_tokens as i32,
+            )
             .await
             .map_err(|e| AgentError::Database(e.to_string()))?;
 
@@ -1358,13 +1394,11 @@ impl AgentService {
             message_id: assistant_db_msg.id,
             content: final_text,
             stop_reason: response.stop_reason,
-            usage: crate::brain::provider::TokenUsage {
-                input_tokens: total_input_tokens,
-                output_tokens: total_output_tokens,
-            },
+            usage: total_usage,
             context_tokens: last_input_tokens,
             cost,
             model: response.model,
+            changed_files: checkpoint.files().await,
         })
     }
 
@@ -1422,8 +1456,9 @@ impl AgentService {
             .map_err(|e| AgentError::Database(e.to_string()))?;
 
         // Build base LLM request
-        let request =
-            LLMRequest::new(model_name.clone(), context.messages.clone()).with_max_tokens(self.max_tokens);
+        let request = self.with_thinking_budget(
+            LLMRequest::new(model_name.clone(), context.messages.clone()).with_max_tokens(self.max_tokens),
+        );
 
         let request = if let Some(system) = context.system_brain {
             request.with_system(system)
@@ -1434,6 +1469,27 @@ impl AgentService {
         Ok((model_name, request, message_service, session_service))
     }
 
+    /// Drain the failover switches of the provider call that just returned,
+    /// showing them through the progress callback when there is one. Drained
+    /// after every call, so they neither pile up nor show in a later turn.
+    fn report_provider_switches(&self) {
+        for switch in self.provider.take_switches() {
+            match self.progress_callback {
+                Some(ref cb) => cb(ProgressEvent::ProviderSwitched {
+                    from: switch.from,
+                    to: switch.to,
+                    reason: switch.reason,
+                }),
+                None => tracing::info!(
+                    "Switched provider from '{}' to '{}' ({})",
+                    switch.from,
+                    switch.to,
+                    switch.reason
+                ),
+            }
+        }
+    }
+
     /// Stream a request and accumulate into an LLMResponse.
     ///
     /// Sends text deltas to the progress callback as `StreamingChunk` events
@@ -1444,14 +1500,15 @@ impl AgentService {
         use futures::StreamExt;
 
         let request_model = request.model.clone();
-        let mut stream = self.provider.stream(request).await?;
+        let stream_result = self.provider.stream(request).await;
+        self.report_provider_switches();
+        let mut stream = stream_result?;
 
         // Accumulate state from stream events
         let mut id = String::new();
         let mut model = String::new();
         let mut stop_reason: Option<StopReason> = None;
-        let mut input_tokens = 0u32;
-        let mut output_tokens = 0u32;
+        let mut usage = TokenUsage::default();
 
         // Track partial content blocks by index
         // Text blocks: accumulate text deltas
@@ -1480,7 +1537,7 @@ impl AgentService {
                 StreamEvent::MessageStart { message } => {
                     id = message.id;
                     model = message.model;
-                    input_tokens = message.usage.input_tokens;
+                    usage = message.usage;
                 }
                 StreamEvent::ContentBlockStart { index, content_block } => {
                     // Ensure block_states has enough capacity
@@ -1511,6 +1568,27 @@ impl AgentService {
                             ContentDelta::InputJsonDelta { partial_json } => {
                                 block_states[index].json_buf.push_str(&partial_json);
                             }
+                            ContentDelta::ThinkingDelta { thinking } => {
+                                if let Some(ref cb) = self.progress_callback {
+                                    cb(ProgressEvent::ThinkingChunk { text: thinking.clone() });
+                                }
+                                match block_states[index].block {
+                                    ContentBlock::Thinking { ref mut text, .. } => text.push_str(&thinking),
+                                    // Delta arrived without a start event — replace the placeholder
+                                    ContentBlock::Text { ref text } if text.is_empty() => {
+                                        block_states[index].block = ContentBlock::Thinking {
+                                            text: thinking,
+                                            signature: None,
+                                        };
+                                    }
+                                    _ => {}
+                                }
+                            }
+                            ContentDelta::SignatureDelta { signature } => {
+                                if let ContentBlock::Thinking { signature: ref mut s, .. } = block_states[index].block {
+                                    s.get_or_insert_with(String::new).push_str(&signature);
+                                }
+                            }
                         }
                     }
                 }
@@ -1525,17 +1603,20 @@ impl AgentService {
                         }
                     }
                 }
-                StreamEvent::MessageDelta { delta, usage } => {
+                StreamEvent::MessageDelta { delta, usage: delta_usage } => {
                     stop_reason = delta.stop_reason;
                     // Take the largest values — MiniMax sends two deltas:
                     // first (0,0), then the real usage. Other providers
                     // may only send one. Using max() handles both cases.
-                    if usage.input_tokens > input_tokens {
-                        input_tokens = usage.input_tokens;
-                    }
-                    if usage.output_tokens > output_tokens {
-                        output_tokens = usage.output_tokens;
-                    }
+                    // Anthropic reports cache counts at message_start; keep them unless a delta repeats them.
+                    usage.input_tokens = usage.input_tokens.max(delta_usage.input_tokens);
+                    usage.output_tokens = usage.output_tokens.max(delta_usage.output_tokens);
+                    usage.cache_creation_input_tokens = usage
+                        .cache_creation_input_tokens
+                        .max(delta_usage.cache_creation_input_tokens);
+                    usage.cache_read_input_tokens = usage
+                        .cache_read_input_tokens
+                        .max(delta_usage.cache_read_input_tokens);
                 }
                 StreamEvent::MessageStop => break,
                 StreamEvent::Ping => {}
@@ -1551,16 +1632,21 @@ impl AgentService {
             tracing::warn!(
                 "⚠️ Stream ended without MessageStop/[DONE]. {} content blocks accumulated, \
                  {} output tokens counted. Possible network interruption or provider timeout.",
-                block_states.len(), output_tokens,
+                block_states.len(), usage.output_tokens,
             );
         }
 
         // Build final content blocks from accumulated state
         // Filter out empty text blocks — Anthropic rejects "text content blocks must be non-empty"
+        // Thinking blocks that never received text or a signature are dropped the same way
         let content_blocks: Vec<ContentBlock> = block_states
             .into_iter()
             .map(|s| s.block)
             .filter(|b| !matches!(b, ContentBlock::Text { text } if text.is_empty()))
+            .filter(|b| {
+                !matches!(b, ContentBlock::Thinking { text, signature }
+                    if text.is_empty() && signature.as_deref().is_none_or(str::is_empty))
+            })
             .collect();
 
         Ok(LLMResponse {
@@ -1570,7 +1656,7 @@ impl AgentService {
             model: if model.is_empty() { request_model } else { model },
             content: content_blocks,
             stop_reason,
-            usage: TokenUsage { input_tokens, output_tokens },
+            usage,
         })
     }
 
@@ -1751,21 +1837,14 @@ impl AgentService {
 
         let summary = Self::extract_text_from_response(&response);
 
-        // Save to daily memory log
-        if let Err(e) = self.save_to_memory(&summary).await {
-            tracing::warn!("Failed to save compaction summary to daily log: {}", e);
+        // Save to the daily memory log (`~/.opencrabs/memory/YYYY-MM-DD.md`), indexed
+        // for memory_search. The brain workspace's `MEMORY.md` is left untouched — it
+        // stays as user-curated durable memory.
+        match crate::memory::append_daily_log("Auto-Compaction Summary", &summary).await {
+            Ok(path) => tracing::info!("Saved compaction summary to {}", path.display()),
+            Err(e) => tracing::warn!("Failed to save compaction summary to daily log: {}", e),
         }
 
-        // Index the updated memory file in the background so memory_search picks it up
-        let memory_path = crate::config::opencrabs_home()
-            .join("memory")
-            .join(format!("{}.md", chrono::Local::now().format("%Y-%m-%d")));
-        tokio::spawn(async move {
-            if let Ok(store) = crate::memory::get_store() {
-                let _ = crate::memory::index_file(store, &memory_path).await;
-            }
-        });
-
         // Compact the context: keep last 4 message pairs (8 messages)
         context.compact_with_summary(summary.clone(), 8);
 
@@ -1783,37 +1862,6 @@ impl AgentService {
         Ok(summary)
     }
 
-    /// Save a compaction summary to a daily memory log at `~/.opencrabs/memory/YYYY-MM-DD.md`.
-    ///
-    /// Multiple compactions per day append to the same file. The brain workspace's
-    /// `MEMORY.md` is left untouched — it stays as user-curated durable memory.
-    async fn save_to_memory(&self, summary: &str) -> std::result::Result<(), String> {
-        let memory_dir = crate::config::opencrabs_home().join("memory");
-
-        std::fs::create_dir_all(&memory_dir)
-            .map_err(|e| format!("Failed to create memory directory: {}", e))?;
-
-        let date = chrono::Local::now().format("%Y-%m-%d");
-        let memory_path = memory_dir.join(format!("{}.md", date));
-
-        // Read existing content (if any — multiple compactions per day stack)
-        let existing = std::fs::read_to_string(&memory_path).unwrap_or_default();
-
-        let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
-        let new_content = format!(
-            "{}\n\n---\n\n## Auto-Compaction Summary ({})\n\n{}\n",
-            existing.trim(),
-            timestamp,
-            summary
-        );
-
-        std::fs::write(&memory_path, new_content.trim_start())
-            .map_err(|e| format!("Failed to write daily memory log: {}", e))?;
-
-        tracing::info!("Saved compaction summary to {}", memory_path.display());
-        Ok(())
-    }
-
     /// Build a user Message, auto-attaching images from `<<IMG:path>>` markers.
     /// The TUI inserts these markers for detected image paths/URLs (handles spaces).
     async fn build_user_message(text: &str) -> Message {
@@ -1883,6 +1931,149 @@ impl AgentService {
         }
     }
 
+    /// Decide whether a tool call runs, needs approval, or is blocked.
+    /// Permission rules come first; unmatched calls fall back to `Tool::requires_approval`.
+    fn tool_gate(
+        &self,
+        tool_name: &str,
+        tool_input: &Value,
+        tool_context: &ToolExecutionContext,
+    ) -> ToolGate {
+        let permission = self
+            .permission_rules
+            .read()
+            .expect("permission rules lock poisoned")
+            .evaluate(tool_name, tool_input, &tool_context.working_directory);
+
+        match permission {
+            Permission::Deny(rule) => ToolGate::Deny(rule),
+            Permission::Ask => ToolGate::Ask,
+            Permission::Allow => ToolGate::Run,
+            Permission::Default => {
+                let needs_approval = self.tool_registry.get(tool_name).is_some_and(|tool| {
+                    tool.requires_approval_for(tool_input)
+                        && !self.auto_approve_tools
+                        && !tool_context.auto_approve
+                });
+                if needs_approval { ToolGate::Ask } else { ToolGate::Run }
+            }
+        }
+    }
+
+    /// Read-only tools that need no approval can run alongside each other
+    fn runs_concurrently(
+        &self,
+        tool_name: &str,
+        tool_input: &Value,
+        tool_context: &ToolExecutionContext,
+    ) -> bool {
+        self.tool_registry
+            .get(tool_name)
+            .is_some_and(|tool| tool.is_read_only())
+            && self.tool_gate(tool_name, tool_input, tool_context) == ToolGate::Run
+    }
+
+    /// Execute a tool and flatten the result into `(success, content)`
+    async fn run_tool(
+        &self,
+        tool_name: &str,
+        tool_input: Value,
+        tool_context: &ToolExecutionContext,
+    ) -> (bool, String) {
+        let sandboxed;
+        let tool_context = if tool_context.sandbox.is_none()
+            && self
+                .permission_rules
+                .read()
+                .expect("permission rules lock poisoned")
+                .sandboxes(tool_name, &tool_input, &tool_context.working_directory)
+        {
+            tracing::info!("[TOOL_EXEC] Running '{}' in the sandbox", tool_name);
+            sandboxed = ToolExecutionContext {
+                sandbox: Some(Arc::clone(&self.sandbox)),
+                ..tool_context.clone()
+            };
+            &sandboxed
+        } else {
+            tool_context
+        };
+
+        match self.tool_registry.execute(tool_name, tool_input, tool_context).await {
+            Ok(result) => {
+                let success = result.success;
+                let content = if result.success {
+                    result.output
+                } else {
+                    result
+                        .error
+                        .unwrap_or_else(|| "Tool execution failed".to_string())
+                };
+
+                // GRANULAR LOG: Tool execution result
+                if success {
+                    tracing::info!(
+                        "[TOOL_EXEC] ✅ Tool '{}' executed successfully, output_len={}",
+                        tool_name,
+                        content.len()
+                    );
+                } else {
+                    tracing::error!(
+                        "[TOOL_EXEC] ❌ Tool '{}' failed: {}",
+                        tool_name,
+                        content.chars().take(200).collect::<String>()
+                    );
+                }
+                (success, content)
+            }
+            Err(e) => {
+                let err_msg = format!("Tool execution error: {}", e);
+                // GRANULAR LOG: Tool execution error
+                tracing::error!("[TOOL_EXEC] 💥 Tool '{}' error: {}", tool_name, err_msg);
+                (false, err_msg)
+            }
+        }
+    }
+
+    /// Execute a batch of tool calls concurrently, at most `agent.max_concurrent` at a time.
+    /// Outcomes are returned in the same order as the calls.
+    async fn run_tool_batch(
+        &self,
+        calls: &[(String, String, Value)],
+        tool_context: &ToolExecutionContext,
+    ) -> Vec<(bool, String)> {
+        use futures::StreamExt;
+
+        let limit = self.agent_config.max_concurrent.max(1) as usize;
+        futures::stream::iter(
+            calls
+                .iter()
+                .map(|(_, name, input)| self.run_tool(name, input.clone(), tool_context)),
+        )
+        .buffered(limit)
+        .collect()
+        .await
+    }
+
+    /// Emit `ToolCompleted` and return the `(success, summary)` entry kept for DB persistence
+    fn report_tool_completed(
+        &self,
+        tool_name: &str,
+        tool_input: Value,
+        success: bool,
+        content: &str,
+    ) -> (bool, String) {
+        let output_summary: String = content.chars().take(2000).collect();
+        if let Some(ref cb) = self.progress_callback {
+            cb(ProgressEvent::ToolCompleted {
+                tool_name: tool_name.to_string(),
+                tool_input,
+                success,
+                summary: output_summary.clone(),
+            });
+        }
+        (success, output_summary)
+    }
+
     /// Compact tool description for DB persistence (mirrors TUI's format_tool_description)
     fn format_tool_summary(tool_name: &str, tool_input: &Value) -> String {
         match tool_name {
@@ -1978,6 +2169,9 @@ pub struct AgentResponse {
 
     /// Model used
     pub model: String,
+
+    /// Files the turn's tools changed, snapshotted in its checkpoint
+    pub changed_files: Vec<std::path::PathBuf>,
 }
 
 /// Streaming response from the agent
@@ -2021,6 +2215,7 @@ mod tests {
                 usage: TokenUsage {
                     input_tokens: 10,
                     output_tokens: 20,
+                    ..Default::default()
                 },
             })
         }
@@ -2087,6 +2282,56 @@ mod tests {
         }
     }
 
+    /// Provider that is always overloaded, to push a failover chain onto its backup
+    struct DownProvider;
+
+    impl DownProvider {
+        fn overloaded() -> crate::brain::provider::ProviderError {
+            crate::brain::provider::ProviderError::ApiError {
+                status: 529,
+                message: "Overloaded".to_string(),
+                error_type: Some("overloaded_error".to_string()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Provider for DownProvider {
+        async fn complete(
+            &self,
+            _request: LLMRequest,
+        ) -> crate::brain::provider::Result<LLMResponse> {
+            Err(Self::overloaded())
+        }
+
+        async fn stream(
+            &self,
+            _request: LLMRequest,
+        ) -> crate::brain::provider::Result<ProviderStream> {
+            Err(Self::overloaded())
+        }
+
+        fn name(&self) -> &str {
+            "down"
+        }
+
+        fn default_model(&self) -> &str {
+            "mock-model"
+        }
+
+        fn supported_models(&self) -> Vec<String> {
+            vec!["mock-model".to_string()]
+        }
+
+        fn context_window(&self, _model: &str) -> Option<u32> {
+            Some(4096)
+        }
+
+        fn calculate_cost(&self, _model: &str, _input: u32, _output: u32) -> f64 {
+            0.0
+        }
+    }
+
     async fn create_test_service() -> (AgentService, Uuid) {
         let db = Database::connect_in_memory().await.unwrap();
         db.run_migrations().await.unwrap();
@@ -2127,6 +2372,24 @@ mod tests {
         assert!(response.cost > 0.0);
     }
 
+    #[tokio::test]
+    async fn test_send_message_drains_switches_without_callback() {
+        let (agent_service, session_id) = create_test_service().await;
+        let chain = Arc::new(
+            crate::brain::provider::FailoverProvider::new("down", Arc::new(DownProvider))
+                .with_fallback("mock", Arc::new(MockProvider)),
+        );
+        let agent_service = AgentService::new(chain.clone(), agent_service.context.clone());
+
+        agent_service
+            .send_message(session_id, "Hello, world!".to_string(), None)
+            .await
+            .unwrap();
+
+        // Logged rather than left for a later turn to report
+        assert!(chain.take_switches().is_empty());
+    }
+
     #[tokio::test]
     async fn test_send_message_with_system_brain() {
         let (agent_service, session_id) = create_test_service().await;
@@ -2178,12 +2441,14 @@ mod tests {
                             id: "tool-1".to_string(),
                             name: "test_tool".to_string(),
                             input: serde_json::json!({"message": "test"}),
+                            signature: None,
                         },
                     ],
                     stop_reason: Some(StopReason::ToolUse),
                     usage: TokenUsage {
                         input_tokens: 10,
                         output_tokens: 20,
+                        ..Default::default()
                     },
                 })
             } else {
@@ -2198,6 +2463,7 @@ mod tests {
                     usage: TokenUsage {
                         input_tokens: 15,
                         output_tokens: 25,
+                        ..Default::default()
                     },
                 })
             }
@@ -2235,13 +2501,19 @@ mod tests {
                             delta: ContentDelta::TextDelta { text: text.clone() },
                         }));
                     }
-                    ContentBlock::ToolUse { id, name, input } => {
+                    ContentBlock::ToolUse {
+                        id,
+                        name,
+                        input,
+                        signature,
+                    } => {
                         events.push(Ok(StreamEvent::ContentBlockStart {
                             index: i,
                             content_block: ContentBlock::ToolUse {
                                 id: id.clone(),
                                 name: name.clone(),
                                 input: serde_json::Value::Object(Default::default()),
+                                signature: signature.clone(),
                             },
                         }));
                         events.push(Ok(StreamEvent::ContentBlockDelta {
@@ -2373,6 +2645,120 @@ mod tests {
         assert!(response.usage.output_tokens >= 45); // 20 + 25
     }
 
+    /// Read-only tool that sleeps and tracks how many calls overlap
+    #[derive(Default)]
+    struct SlowReadTool {
+        in_flight: std::sync::atomic::AtomicUsize,
+        peak: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl crate::brain::tools::Tool for SlowReadTool {
+        fn name(&self) -> &str {
+            "slow_read"
+        }
+
+        fn description(&self) -> &str {
+            "A slow read-only tool"
+        }
+
+        fn input_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object"})
+        }
+
+        fn capabilities(&self) -> Vec<crate::brain::tools::ToolCapability> {
+            vec![crate::brain::tools::ToolCapability::ReadFiles]
+        }
+
+        async fn execute(
+            &self,
+            input: serde_json::Value,
+            _context: &crate::brain::tools::ToolExecutionContext,
+        ) -> crate::brain::tools::Result<crate::brain::tools::ToolResult> {
+            use std::sync::atomic::Ordering;
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now, Ordering::SeqCst);
+            let delay = input["delay_ms"].as_u64().unwrap_or(0);
+            tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(crate::brain::tools::ToolResult::success(
+                input["id"].as_str().unwrap_or_default().to_string(),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_only_tools_run_concurrently_in_order() {
+        let db = Database::connect_in_memory().await.unwrap();
+        db.run_migrations().await.unwrap();
+        let context = ServiceContext::new(db.pool().clone());
+
+        let tool = Arc::new(SlowReadTool::default());
+        let mut registry = ToolRegistry::new();
+        registry.register(tool.clone());
+        registry.register(Arc::new(MockTool));
+
+        let mut agent_service = AgentService::new(Arc::new(MockProvider), context)
+            .with_tool_registry(Arc::new(registry));
+        agent_service.agent_config.max_concurrent = 3;
+
+        let tool_context = ToolExecutionContext::new(Uuid::new_v4());
+        let input = serde_json::json!({});
+        assert!(agent_service.runs_concurrently("slow_read", &input, &tool_context));
+        assert!(!agent_service.runs_concurrently("test_tool", &input, &tool_context));
+
+        // Later calls finish first; results must still follow the call order
+        let calls: Vec<(String, String, Value)> = (0..6)
+            .map(|i| {
+                (
+                    format!("tool-{}", i),
+                    "slow_read".to_string(),
+                    serde_json::json!({"id": format!("r{}", i), "delay_ms": 60 - i * 10}),
+                )
+            })
+            .collect();
+        let outcomes = agent_service.run_tool_batch(&calls, &tool_context).await;
+
+        let contents: Vec<&str> = outcomes.iter().map(|(_, c)| c.as_str()).collect();
+        assert_eq!(contents, vec!["r0", "r1", "r2", "r3", "r4", "r5"]);
+        assert!(outcomes.iter().all(|(success, _)| *success));
+        let peak = tool.peak.load(std::sync::atomic::Ordering::SeqCst);
+        assert!(peak > 1 && peak <= 3, "peak concurrency was {}", peak);
+    }
+
+    #[tokio::test]
+    async fn test_permission_rules_gate_tool_calls() {
+        let (agent_service, _) = create_test_service().await;
+        let rules = PermissionRules::from_config(&crate::config::PermissionsConfig {
+            allow: vec!["slow_read".to_string()],
+            deny: vec!["test_tool".to_string()],
+            ask: vec!["slow_read(*secret*)".to_string()],
+            sandbox: vec![],
+        });
+
+        let mut registry = ToolRegistry::new();
+        registry.register(Arc::new(SlowReadTool::default()));
+        registry.register(Arc::new(MockTool));
+        let agent_service = agent_service
+            .with_tool_registry(Arc::new(registry))
+            .with_auto_approve_tools(true)
+            .with_permission_rules(rules);
+
+        let tool_context = ToolExecutionContext::new(Uuid::new_v4());
+        let plain = serde_json::json!({"id": "notes"});
+        let secret = serde_json::json!({"id": "secret.txt"});
+
+        assert_eq!(
+            agent_service.tool_gate("test_tool", &plain, &tool_context),
+            ToolGate::Deny("test_tool".to_string())
+        );
+        // Ask rules prompt even when auto-approve is on
+        assert_eq!(agent_service.tool_gate("slow_read", &secret, &tool_context), ToolGate::Ask);
+        assert!(!agent_service.runs_concurrently("slow_read", &secret, &tool_context));
+        assert_eq!(agent_service.tool_gate("slow_read", &plain, &tool_context), ToolGate::Run);
+        assert!(agent_service.runs_concurrently("slow_read", &plain, &tool_context));
+    }
+
     #[tokio::test]
     async fn test_message_queue_injection_between_tool_calls() {
         let db = Database::connect_in_memory().await.unwrap();
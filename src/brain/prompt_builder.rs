@@ -6,7 +6,7 @@
 use std::path::PathBuf;
 
 /// Files loaded from the brain workspace, in assembly order.
-const BRAIN_FILES: &[(&str, &str)] = &[
+pub const BRAIN_FILES: &[(&str, &str)] = &[
     ("SOUL.md", "personality"),
     ("IDENTITY.md", "identity"),
     ("USER.md", "user"),
@@ -42,12 +42,13 @@ When asked to make changes:
 
 Available tools and their REQUIRED parameters (use exact parameter names):
 - ls: List directory contents. Params: path (string), recursive (bool)
-- glob: Find files matching patterns. Params: pattern (string, REQUIRED — e.g. "**/*.rs")
-- grep: Search for text in files. Params: pattern (string, REQUIRED — the search text), path (string), regex (bool), case_insensitive (bool), file_pattern (string), limit (int), context (int)
+- glob: Find files matching patterns, newest first. Params: pattern (string, REQUIRED — e.g. "**/*.rs"), base_dir (string), limit (int)
+- grep: Search for text in files (skips gitignored files). Params: pattern (string, REQUIRED — the search text), path (string), regex (bool), case_insensitive (bool), multiline (bool), type (string — e.g. "rust", "py"), file_pattern (string), output_mode ("content" | "files_with_matches" | "count"), limit (int), context (int)
 - read_file: Read file contents. Params: path (string, REQUIRED)
 - edit_file: Modify existing files. Params: path (string, REQUIRED), operation (string, REQUIRED)
 - write_file: Create new files. Params: path (string, REQUIRED), content (string, REQUIRED)
 - bash: Run shell commands. Params: command (string, REQUIRED)
+- process: Run dev servers, watchers and long test suites in the background. Params: operation (string, REQUIRED — "start", "output", "status", "send_input" or "kill"), command (string), handle (string), wait_secs (int), input (string)
 - execute_code: Test code snippets. Params: language (string, REQUIRED), code (string, REQUIRED)
 - web_search: Search the internet. Params: query (string, REQUIRED)
 - http_request: Call external APIs. Params: method (string, REQUIRED), url (string, REQUIRED)
@@ -55,6 +56,9 @@ Available tools and their REQUIRED parameters (use exact parameter names):
 - session_context: Remember important facts. Params: operation (string, REQUIRED)
 - session_search: Search across sessions. Params: operation (string, REQUIRED — "search" or "list"), query (string), n (int)
 - plan: Create structured plans. Params: operation (string, REQUIRED)
+- schedule: Run prompts on a schedule. Params: operation (string, REQUIRED — "create", "list", "pause", "resume", "delete" or "history"), prompt (string), schedule (string — "every 30m" or cron "0 9 * * mon-fri"), timezone (string), deliver_to (string), id (string)
+- a2a_delegate: Hand a task to another A2A agent and wait for its result. Params: peer (string, REQUIRED — configured peer name or URL), message (string), skill (string), context_id (string — continue an earlier conversation), task_id (string — answer a task waiting for input), operation (string — "send" or "discover")
+- debate: Run a multi-agent Bee Colony debate on a contested question. Params: topic (string, REQUIRED), bees (array of configured bee names), endpoints (array of remote A2A bee URLs), num_bees (int), max_rounds (int), consensus_threshold (number)
 
 CRITICAL: PLAN TOOL USAGE
 When a user says "create a plan", "make a plan", or describes a complex multi-step task, you MUST use the plan tool immediately.
@@ -114,13 +114,20 @@ impl AnthropicProvider {
 
     /// Convert our generic request to Anthropic-specific format
     fn to_anthropic_request(&self, request: LLMRequest) -> AnthropicRequest {
+        let max_tokens = request.max_tokens.unwrap_or(16384);
+        let thinking = ThinkingConfig::new(request.thinking_budget, max_tokens);
         AnthropicRequest {
             model: request.model,
-            messages: request.messages,
-            system: request.system,
-            max_tokens: request.max_tokens.unwrap_or(16384),
-            temperature: request.temperature,
-            tools: request.tools,
+            prompt: CachedPrompt::new(
+                request.system,
+                request.tools,
+                request.messages,
+                thinking.is_some(),
+            ),
+            max_tokens,
+            // Thinking requires the default temperature
+            temperature: request.temperature.filter(|_| thinking.is_none()),
+            thinking,
             stream: Some(request.stream),
             metadata: request.metadata,
         }
@@ -129,72 +136,245 @@ impl AnthropicProvider {
     /// Convert Anthropic response to our generic format
     #[allow(clippy::wrong_self_convention)]
     fn from_anthropic_response(&self, response: AnthropicResponse) -> LLMResponse {
-        LLMResponse {
-            id: response.id,
-            model: response.model,
-            content: response.content,
-            stop_reason: response.stop_reason,
-            usage: response.usage,
-        }
+        response.into()
     }
 
     /// Handle API error response
     async fn handle_error(&self, response: reqwest::Response) -> ProviderError {
-        let status = response.status().as_u16();
-
-        // Extract Retry-After header for rate limits
-        let retry_after = response.headers().get("retry-after").and_then(|v| {
-            v.to_str().ok().and_then(|s| {
-                // Retry-After can be either seconds or HTTP date
-                // Try parsing as seconds first
-                s.parse::<u64>().ok()
-            })
+        error_from_response(response).await
+    }
+}
+
+/// Build a Messages API body for Claude hosted on a cloud platform (Bedrock, Vertex).
+/// The model lives in the URL there, and the API version moves into the body.
+pub(super) fn to_platform_request(
+    request: LLMRequest,
+    anthropic_version: &'static str,
+    stream: bool,
+) -> AnthropicPlatformRequest {
+    let max_tokens = request.max_tokens.unwrap_or(16384);
+    let thinking = ThinkingConfig::new(request.thinking_budget, max_tokens);
+    AnthropicPlatformRequest {
+        anthropic_version,
+        prompt: CachedPrompt::new(
+            request.system,
+            request.tools,
+            request.messages,
+            thinking.is_some(),
+        ),
+        max_tokens,
+        temperature: request.temperature.filter(|_| thinking.is_none()),
+        thinking,
+        stream: stream.then_some(true),
+    }
+}
+
+/// Smallest thinking budget the API accepts
+const MIN_THINKING_BUDGET: u32 = 1024;
+
+impl ThinkingConfig {
+    /// Thinking settings for a request; the budget must stay below `max_tokens`
+    fn new(budget: Option<u32>, max_tokens: u32) -> Option<Self> {
+        let budget = budget?.min(max_tokens.saturating_sub(1));
+        if budget < MIN_THINKING_BUDGET {
+            tracing::warn!(
+                "Thinking budget {} is below the minimum of {} (max_tokens {}), thinking disabled",
+                budget,
+                MIN_THINKING_BUDGET,
+                max_tokens
+            );
+            return None;
+        }
+        Some(Self {
+            kind: "enabled",
+            budget_tokens: budget,
+        })
+    }
+}
+
+/// Number of trailing user turns that get a cache breakpoint.
+/// Together with the system and tools breakpoints this uses all four the API allows.
+const HISTORY_CACHE_BREAKPOINTS: usize = 2;
+
+impl CachedPrompt {
+    /// Add prompt-cache breakpoints to the stable prefix of a request.
+    ///
+    /// The system prompt and tool list are identical on every tool-loop iteration,
+    /// so each ends in a breakpoint. History gets a rolling boundary on the last
+    /// user turns: the newest one writes the cache for the next iteration, the one
+    /// before it reads what the previous iteration wrote.
+    ///
+    /// Thinking blocks are only replayed when thinking is enabled, and only with the
+    /// signature Anthropic issued — reasoning from other providers can't be sent back.
+    /// Redacted thinking is Anthropic's own and replayed whenever thinking is on.
+    fn new(
+        system: Option<String>,
+        tools: Option<Vec<Tool>>,
+        messages: Vec<Message>,
+        thinking: bool,
+    ) -> Self {
+        let system = system.filter(|s| !s.is_empty()).map(|text| {
+            vec![Cached::breakpoint(SystemBlock { kind: "text", text })]
+        });
+
+        let tools = tools.map(|tools| {
+            let last = tools.len().saturating_sub(1);
+            tools
+                .into_iter()
+                .enumerate()
+                .map(|(i, tool)| Cached::new(tool, i == last))
+                .collect()
         });
 
-        // Try to parse error body
-        if let Ok(error_body) = response.json::<AnthropicError>().await {
-            let message = if status == 429 {
-                // Enhance rate limit error message
-                if let Some(secs) = retry_after {
-                    format!(
-                        "{} (retry after {} seconds)",
-                        error_body.error.message, secs
-                    )
-                } else {
-                    format!(
-                        "{} (rate limited, please retry later)",
-                        error_body.error.message
-                    )
+        let mut remaining = HISTORY_CACHE_BREAKPOINTS;
+        let mut messages: Vec<CachedMessage> = messages
+            .into_iter()
+            .rev()
+            .map(|mut message| {
+                message.content.retain(|block| match block {
+                    ContentBlock::Thinking { signature, .. } => {
+                        thinking && signature.as_deref().is_some_and(|s| !s.is_empty())
+                    }
+                    ContentBlock::RedactedThinking { .. } => thinking,
+                    _ => true,
+                });
+                // Gemini's function-call signatures aren't part of Anthropic's tool_use
+                for block in &mut message.content {
+                    if let ContentBlock::ToolUse { signature, .. } = block {
+                        *signature = None;
+                    }
                 }
-            } else {
-                error_body.error.message
-            };
+                // Empty text blocks can't carry cache_control — mark the last non-empty block
+                let target = (remaining > 0 && message.role == Role::User)
+                    .then(|| message.content.iter().rposition(|b| !is_empty_text(b)))
+                    .flatten();
+                if target.is_some() {
+                    remaining -= 1;
+                }
+                CachedMessage {
+                    role: message.role,
+                    content: message
+                        .content
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, block)| Cached::new(block, Some(i) == target))
+                        .collect(),
+                }
+            })
+            .collect();
+        messages.reverse();
 
-            return if status == 429 {
-                ProviderError::RateLimitExceeded(message)
-            } else {
-                ProviderError::ApiError {
+        Self {
+            system,
+            tools,
+            messages,
+        }
+    }
+}
+
+fn is_empty_text(block: &ContentBlock) -> bool {
+    matches!(block, ContentBlock::Text { text } if text.is_empty())
+}
+
+/// Drain complete `data: ` lines from an SSE buffer and parse them as stream events.
+/// A trailing partial line stays in the buffer for the next chunk.
+pub(super) fn drain_sse_events(buf: &mut String) -> Vec<Result<StreamEvent>> {
+    let mut events = Vec::new();
+
+    // Process complete lines (terminated by \n)
+    while let Some(newline_pos) = buf.find('\n') {
+        let line = buf[..newline_pos].trim().to_string();
+        buf.drain(..=newline_pos);
+
+        if let Some(json_str) = line.strip_prefix("data: ") {
+            if json_str == "[DONE]" {
+                continue;
+            }
+            // In-stream errors ({"type":"error","error":{...}}) — e.g. overloaded mid-response
+            if let Ok(StreamErrorEvent { kind, error }) = serde_json::from_str::<StreamErrorEvent>(json_str)
+                && kind == "error"
+            {
+                let status = if error.error_type == "overloaded_error" { 529 } else { 500 };
+                events.push(Err(ProviderError::ApiError {
                     status,
-                    message,
-                    error_type: Some(error_body.error.error_type),
+                    message: error.message,
+                    error_type: Some(error.error_type),
+                }));
+                continue;
+            }
+            match serde_json::from_str::<StreamEvent>(json_str) {
+                Ok(event) => events.push(Ok(event)),
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to parse SSE event JSON: {}. Data: {}",
+                        e,
+                        json_str.chars().take(200).collect::<String>()
+                    );
+                    // Don't propagate parse errors for individual events
                 }
-            };
+            }
         }
+    }
+
+    events
+}
 
-        // Fallback error
-        if status == 429 {
-            let message = if let Some(secs) = retry_after {
-                format!("Rate limit exceeded (retry after {} seconds)", secs)
+/// Parse an Anthropic-format error response (also returned by Vertex rawPredict)
+pub(super) async fn error_from_response(response: reqwest::Response) -> ProviderError {
+    let status = response.status().as_u16();
+
+    // Extract Retry-After header for rate limits
+    let retry_after = response.headers().get("retry-after").and_then(|v| {
+        v.to_str().ok().and_then(|s| {
+            // Retry-After can be either seconds or HTTP date
+            // Try parsing as seconds first
+            s.parse::<u64>().ok()
+        })
+    });
+
+    // Try to parse error body
+    if let Ok(error_body) = response.json::<AnthropicError>().await {
+        let message = if status == 429 {
+            // Enhance rate limit error message
+            if let Some(secs) = retry_after {
+                format!(
+                    "{} (retry after {} seconds)",
+                    error_body.error.message, secs
+                )
             } else {
-                "Rate limit exceeded, please retry later".to_string()
-            };
+                format!(
+                    "{} (rate limited, please retry later)",
+                    error_body.error.message
+                )
+            }
+        } else {
+            error_body.error.message
+        };
+
+        return if status == 429 {
             ProviderError::RateLimitExceeded(message)
         } else {
             ProviderError::ApiError {
                 status,
-                message: "Unknown error".to_string(),
-                error_type: None,
+                message,
+                error_type: Some(error_body.error.error_type),
             }
+        };
+    }
+
+    // Fallback error
+    if status == 429 {
+        let message = if let Some(secs) = retry_after {
+            format!("Rate limit exceeded (retry after {} seconds)", secs)
+        } else {
+            "Rate limit exceeded, please retry later".to_string()
+        };
+        ProviderError::RateLimitExceeded(message)
+    } else {
+        ProviderError::ApiError {
+            status,
+            message: "Unknown error".to_string(),
+            error_type: None,
         }
     }
 }
@@ -309,30 +489,7 @@ impl Provider for AnthropicProvider {
                             let mut buf = buffer.lock().expect("SSE buffer lock poisoned");
                             buf.push_str(&text);
 
-                            let mut events = Vec::new();
-
-                            // Process complete lines (terminated by \n)
-                            while let Some(newline_pos) = buf.find('\n') {
-                                let line = buf[..newline_pos].trim().to_string();
-                                buf.drain(..=newline_pos);
-
-                                if let Some(json_str) = line.strip_prefix("data: ") {
-                                    if json_str == "[DONE]" {
-                                        continue;
-                                    }
-                                    match serde_json::from_str::<StreamEvent>(json_str) {
-                                        Ok(event) => events.push(Ok(event)),
-                                        Err(e) => {
-                                            tracing::warn!(
-                                                "Failed to parse SSE event JSON: {}. Data: {}",
-                                                e,
-                                                json_str.chars().take(200).collect::<String>()
-                                            );
-                                            // Don't propagate parse errors for individual events
-                                        }
-                                    }
-                                }
-                            }
+                            let events = drain_sse_events(&mut buf);
 
                             if events.is_empty() {
                                 vec![Ok(StreamEvent::Ping)]
@@ -444,29 +601,112 @@ impl Provider for AnthropicProvider {
     fn calculate_cost(&self, model: &str, input_tokens: u32, output_tokens: u32) -> f64 {
         crate::pricing::PricingConfig::load().calculate_cost(model, input_tokens, output_tokens)
     }
+
+    fn calculate_usage_cost(&self, model: &str, usage: &TokenUsage) -> f64 {
+        crate::pricing::PricingConfig::load().calculate_cost_with_cache(
+            model,
+            usage.input_tokens,
+            usage.output_tokens,
+            usage.cache_creation_input_tokens,
+            usage.cache_read_input_tokens,
+        )
+    }
 }
 
 // Anthropic-specific request format
 #[derive(Debug, Serialize)]
 struct AnthropicRequest {
     model: String,
-    messages: Vec<Message>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    system: Option<String>,
+    #[serde(flatten)]
+    prompt: CachedPrompt,
     max_tokens: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    tools: Option<Vec<Tool>>,
+    thinking: Option<ThinkingConfig>,
     #[serde(skip_serializing_if = "Option::is_none")]
     stream: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     metadata: Option<std::collections::HashMap<String, String>>,
 }
 
+// Messages API body for cloud-hosted Claude (Bedrock, Vertex)
+#[derive(Debug, Serialize)]
+pub(super) struct AnthropicPlatformRequest {
+    anthropic_version: &'static str,
+    #[serde(flatten)]
+    prompt: CachedPrompt,
+    max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking: Option<ThinkingConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream: Option<bool>,
+}
+
+// Extended thinking settings: {"type": "enabled", "budget_tokens": N}
+#[derive(Debug, Serialize)]
+struct ThinkingConfig {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    budget_tokens: u32,
+}
+
+// System, tools and messages with prompt-cache breakpoints
+#[derive(Debug, Serialize)]
+struct CachedPrompt {
+    messages: Vec<CachedMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<Vec<Cached<SystemBlock>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<Cached<Tool>>>,
+}
+
+#[derive(Debug, Serialize)]
+struct CachedMessage {
+    role: Role,
+    content: Vec<Cached<ContentBlock>>,
+}
+
+#[derive(Debug, Serialize)]
+struct SystemBlock {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    text: String,
+}
+
+// Any request element, optionally marked as the end of a cacheable prefix
+#[derive(Debug, Serialize)]
+struct Cached<T> {
+    #[serde(flatten)]
+    inner: T,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cache_control: Option<CacheControl>,
+}
+
+impl<T> Cached<T> {
+    fn new(inner: T, breakpoint: bool) -> Self {
+        Self {
+            inner,
+            cache_control: breakpoint.then_some(CacheControl { kind: "ephemeral" }),
+        }
+    }
+
+    fn breakpoint(inner: T) -> Self {
+        Self::new(inner, true)
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct CacheControl {
+    #[serde(rename = "type")]
+    kind: &'static str,
+}
+
 // Anthropic-specific response format
 #[derive(Debug, Deserialize)]
-struct AnthropicResponse {
+pub(super) struct AnthropicResponse {
     id: String,
     model: String,
     content: Vec<ContentBlock>,
@@ -474,12 +714,32 @@ struct AnthropicResponse {
     usage: TokenUsage,
 }
 
+impl From<AnthropicResponse> for LLMResponse {
+    fn from(response: AnthropicResponse) -> Self {
+        LLMResponse {
+            id: response.id,
+            model: response.model,
+            content: response.content,
+            stop_reason: response.stop_reason,
+            usage: response.usage,
+        }
+    }
+}
+
 // Anthropic error format
 #[derive(Debug, Deserialize)]
 struct AnthropicError {
     error: AnthropicErrorDetail,
 }
 
+// In-stream error event
+#[derive(Debug, Deserialize)]
+struct StreamErrorEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    error: AnthropicErrorDetail,
+}
+
 #[derive(Debug, Deserialize)]
 struct AnthropicErrorDetail {
     #[serde(rename = "type")]
@@ -590,4 +850,169 @@ mod tests {
         assert!(provider.supports_tools());
         assert!(provider.supports_vision());
     }
+
+    #[test]
+    fn test_platform_request_moves_version_into_body() {
+        let request = LLMRequest::new("claude-3-5-haiku-20241022", vec![Message::user("Hi")])
+            .with_system("sys");
+        let body =
+            serde_json::to_value(to_platform_request(request, "bedrock-2023-05-31", false)).unwrap();
+        assert_eq!(body["anthropic_version"], "bedrock-2023-05-31");
+        assert_eq!(body["system"][0]["text"], "sys");
+        assert!(body.get("model").is_none());
+        assert!(body.get("stream").is_none());
+    }
+
+    #[test]
+    fn test_cache_breakpoints() {
+        let tool = |name: &str| Tool {
+            name: name.to_string(),
+            description: String::new(),
+            input_schema: serde_json::json!({"type": "object"}),
+        };
+        let messages = vec![
+            Message::user("first"),
+            Message::assistant("reply"),
+            Message::user("second"),
+            Message::assistant("reply"),
+            Message {
+                role: Role::User,
+                content: vec![
+                    ContentBlock::ToolResult {
+                        tool_use_id: "t1".to_string(),
+                        content: "ok".to_string(),
+                        is_error: None,
+                    },
+                    ContentBlock::Text { text: String::new() },
+                ],
+            },
+        ];
+        let request = LLMRequest::new("claude-sonnet-4-5", messages)
+            .with_system("sys")
+            .with_tools(vec![tool("a"), tool("b")]);
+        let provider = AnthropicProvider::new("test-key".to_string());
+        let body = serde_json::to_value(provider.to_anthropic_request(request)).unwrap();
+
+        let ephemeral = serde_json::json!({"type": "ephemeral"});
+        assert_eq!(body["system"][0]["cache_control"], ephemeral);
+        assert!(body["tools"][0].get("cache_control").is_none());
+        assert_eq!(body["tools"][1]["cache_control"], ephemeral);
+        assert_eq!(body["tools"][1]["name"], "b");
+
+        let marked: Vec<(usize, usize)> = body["messages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .flat_map(|(m, msg)| {
+                msg["content"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, b)| b.get("cache_control").is_some())
+                    .map(move |(b, _)| (m, b))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        // Last two user turns; the empty trailing text block is skipped
+        assert_eq!(marked, vec![(2, 0), (4, 0)]);
+        assert_eq!(body["messages"][4]["content"][0]["type"], "tool_result");
+    }
+
+    #[test]
+    fn test_thinking_request() {
+        let messages = vec![
+            Message::user("q"),
+            Message {
+                role: Role::Assistant,
+                content: vec![
+                    ContentBlock::Thinking {
+                        text: "signed".to_string(),
+                        signature: Some("sig".to_string()),
+                    },
+                    ContentBlock::Thinking {
+                        text: "from another provider".to_string(),
+                        signature: None,
+                    },
+                    ContentBlock::RedactedThinking {
+                        data: "encrypted".to_string(),
+                    },
+                    ContentBlock::ToolUse {
+                        id: "t1".to_string(),
+                        name: "ls".to_string(),
+                        input: serde_json::json!({}),
+                        signature: Some("gemini-signature".to_string()),
+                    },
+                ],
+            },
+        ];
+        let provider = AnthropicProvider::new("test-key".to_string());
+        let request = LLMRequest::new("claude-sonnet-4-5", messages.clone())
+            .with_max_tokens(16000)
+            .with_temperature(0.2)
+            .with_thinking_budget(8000);
+        let body = serde_json::to_value(provider.to_anthropic_request(request)).unwrap();
+        assert_eq!(body["thinking"]["type"], "enabled");
+        assert_eq!(body["thinking"]["budget_tokens"], 8000);
+        assert!(body.get("temperature").is_none());
+        let content = body["messages"][1]["content"].as_array().unwrap();
+        assert_eq!(content.len(), 3);
+        assert_eq!(content[0]["signature"], "sig");
+        assert_eq!(content[1]["type"], "redacted_thinking");
+        assert_eq!(content[1]["data"], "encrypted");
+        assert_eq!(content[2]["type"], "tool_use");
+        assert!(content[2].get("signature").is_none());
+
+        // Without a budget, thinking blocks are not replayed
+        let request = LLMRequest::new("claude-sonnet-4-5", messages);
+        let body = serde_json::to_value(provider.to_anthropic_request(request)).unwrap();
+        assert!(body.get("thinking").is_none());
+        assert_eq!(body["messages"][1]["content"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_thinking_budget_clamped_to_max_tokens() {
+        assert_eq!(ThinkingConfig::new(Some(50_000), 16_000).unwrap().budget_tokens, 15_999);
+        assert!(ThinkingConfig::new(Some(500), 16_000).is_none());
+        assert!(ThinkingConfig::new(None, 16_000).is_none());
+    }
+
+    #[test]
+    fn test_drain_sse_events_keeps_partial_line() {
+        let mut buf = String::from(
+            "event: ping\ndata: {\"type\": \"ping\"}\ndata: {\"type\": \"message_st",
+        );
+        let events = drain_sse_events(&mut buf);
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], Ok(StreamEvent::Ping)));
+        assert_eq!(buf, "data: {\"type\": \"message_st");
+    }
+
+    #[test]
+    fn test_drain_sse_events_parses_redacted_thinking() {
+        let mut buf = String::from(
+            "data: {\"type\": \"content_block_start\", \"index\": 0, \"content_block\": {\"type\": \"redacted_thinking\", \"data\": \"EmwKAhgB\"}}\n",
+        );
+        let events = drain_sse_events(&mut buf);
+        assert!(matches!(
+            events.as_slice(),
+            [Ok(StreamEvent::ContentBlockStart {
+                index: 0,
+                content_block: ContentBlock::RedactedThinking { data },
+            })] if data == "EmwKAhgB"
+        ));
+    }
+
+    #[test]
+    fn test_drain_sse_events_maps_overloaded_error() {
+        let mut buf = String::from(
+            "data: {\"type\": \"error\", \"error\": {\"type\": \"overloaded_error\", \"message\": \"Overloaded\"}}\n",
+        );
+        let events = drain_sse_events(&mut buf);
+        assert!(matches!(
+            events.as_slice(),
+            [Err(ProviderError::ApiError { status: 529, .. })]
+        ));
+    }
 }
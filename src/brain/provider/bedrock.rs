@@ -0,0 +1,972 @@
+//! AWS Bedrock Provider Implementation
+//!
+//! Runs Claude models through Amazon Bedrock's `InvokeModel` and
+//! `InvokeModelWithResponseStream` APIs. The request and response bodies are the
+//! Anthropic Messages format, so the mapping in `anthropic.rs` is reused as-is;
+//! this module only adds SigV4 request signing and decoding of the
+//! `application/vnd.amazon.eventstream` framing used for streaming.
+//!
+//! ## Credentials
+//! `api_key` in `[providers.bedrock]` is `ACCESS_KEY_ID:SECRET_ACCESS_KEY` or
+//! `ACCESS_KEY_ID:SECRET_ACCESS_KEY:SESSION_TOKEN`. When unset, the standard
+//! `AWS_ACCESS_KEY_ID` / `AWS_SECRET_ACCESS_KEY` / `AWS_SESSION_TOKEN` env vars are used.
+//!
+//! ## Supported Models
+//! - anthropic.claude-opus-4-6-v1
+//! - anthropic.claude-sonnet-4-5-20250929-v1:0
+//! - anthropic.claude-haiku-4-5-20251001-v1:0
+//! - anthropic.claude-3-5-sonnet-20241022-v2:0
+//! - anthropic.claude-3-5-haiku-20241022-v1:0
+//!
+//! Cross-region inference profiles (`us.anthropic...`, `eu.anthropic...`) work too.
+
+use super::anthropic::{AnthropicResponse, drain_sse_events, to_platform_request};
+use super::error::{ProviderError, Result};
+use super::r#trait::{Provider, ProviderStream};
+use super::types::*;
+use async_trait::async_trait;
+use base64::Engine;
+use futures::stream::StreamExt;
+use openssl::hash::MessageDigest;
+use openssl::pkey::PKey;
+use openssl::sign::Signer;
+use reqwest::Client;
+use serde::Deserialize;
+use std::time::Duration;
+
+const BEDROCK_ANTHROPIC_VERSION: &str = "bedrock-2023-05-31";
+const BEDROCK_SERVICE: &str = "bedrock";
+const DEFAULT_REGION: &str = "us-east-1";
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(120);
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Static AWS credentials used for SigV4 signing
+#[derive(Clone)]
+pub struct AwsCredentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub session_token: Option<String>,
+}
+
+impl AwsCredentials {
+    /// Parse `ACCESS_KEY_ID:SECRET_ACCESS_KEY[:SESSION_TOKEN]` as stored in keys.toml
+    pub fn from_key_string(key: &str) -> Option<Self> {
+        let mut parts = key.trim().splitn(3, ':');
+        let access_key_id = parts.next().filter(|s| !s.is_empty())?.to_string();
+        let secret_access_key = parts.next().filter(|s| !s.is_empty())?.to_string();
+        let session_token = parts.next().filter(|s| !s.is_empty()).map(str::to_string);
+        Some(Self {
+            access_key_id,
+            secret_access_key,
+            session_token,
+        })
+    }
+
+    /// Read the standard `AWS_*` environment variables
+    pub fn from_env() -> Option<Self> {
+        let access_key_id = std::env::var("AWS_ACCESS_KEY_ID").ok().filter(|s| !s.is_empty())?;
+        let secret_access_key = std::env::var("AWS_SECRET_ACCESS_KEY")
+            .ok()
+            .filter(|s| !s.is_empty())?;
+        let session_token = std::env::var("AWS_SESSION_TOKEN")
+            .ok()
+            .filter(|s| !s.is_empty());
+        Some(Self {
+            access_key_id,
+            secret_access_key,
+            session_token,
+        })
+    }
+}
+
+/// AWS Bedrock provider for Claude models
+#[derive(Clone)]
+pub struct BedrockProvider {
+    credentials: AwsCredentials,
+    region: String,
+    endpoint: String,
+    client: Client,
+    custom_default_model: Option<String>,
+}
+
+impl BedrockProvider {
+    /// Create a new Bedrock provider for a region
+    pub fn new(credentials: AwsCredentials, region: String) -> Self {
+        let endpoint = format!("https://bedrock-runtime.{}.amazonaws.com", region);
+        Self::with_endpoint(credentials, region, endpoint)
+    }
+
+    /// Create with a custom endpoint (VPC endpoints, FIPS endpoints, tests)
+    pub fn with_endpoint(credentials: AwsCredentials, region: String, endpoint: String) -> Self {
+        let client = Client::builder()
+            .timeout(DEFAULT_TIMEOUT)
+            .connect_timeout(DEFAULT_CONNECT_TIMEOUT)
+            .pool_idle_timeout(DEFAULT_POOL_IDLE_TIMEOUT)
+            .pool_max_idle_per_host(2)
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            credentials,
+            region,
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            client,
+            custom_default_model: None,
+        }
+    }
+
+    /// Set custom default model
+    pub fn with_default_model(mut self, model: String) -> Self {
+        self.custom_default_model = Some(model);
+        self
+    }
+
+    /// Region to use for a `base_url` override: `bedrock-runtime.<region>.amazonaws.com`
+    /// hosts carry it, anything else falls back to `AWS_REGION` / `AWS_DEFAULT_REGION`.
+    pub fn region_from_endpoint(endpoint: Option<&str>) -> String {
+        endpoint
+            .and_then(|url| reqwest::Url::parse(url).ok())
+            .and_then(|url| {
+                url.host_str().and_then(|host| {
+                    host.strip_prefix("bedrock-runtime.")
+                        .and_then(|rest| rest.split('.').next())
+                        .filter(|r| !r.is_empty() && !r.starts_with("amazonaws"))
+                        .map(str::to_string)
+                })
+            })
+            .or_else(|| std::env::var("AWS_REGION").ok().filter(|s| !s.is_empty()))
+            .or_else(|| {
+                std::env::var("AWS_DEFAULT_REGION")
+                    .ok()
+                    .filter(|s| !s.is_empty())
+            })
+            .unwrap_or_else(|| DEFAULT_REGION.to_string())
+    }
+
+    /// Build the signed request for a model action (`invoke` or `invoke-with-response-stream`)
+    fn signed_request(
+        &self,
+        model: &str,
+        action: &str,
+        body: Vec<u8>,
+        accept: &str,
+    ) -> Result<reqwest::RequestBuilder> {
+        // Model IDs contain ':' (e.g. "...-v2:0") and must be percent-encoded in the path
+        let path = format!("/model/{}/{}", uri_encode(model, true), action);
+        let url = reqwest::Url::parse(&format!("{}{}", self.endpoint, path))
+            .map_err(|e| ProviderError::Internal(format!("Invalid Bedrock endpoint: {}", e)))?;
+
+        let host = match (url.host_str(), url.port()) {
+            (Some(h), Some(p)) => format!("{}:{}", h, p),
+            (Some(h), None) => h.to_string(),
+            (None, _) => {
+                return Err(ProviderError::Internal(
+                    "Bedrock endpoint has no host".to_string(),
+                ));
+            }
+        };
+
+        let amz_date = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let mut headers: Vec<(&str, String)> = vec![
+            ("accept", accept.to_string()),
+            ("content-type", "application/json".to_string()),
+            ("host", host),
+            ("x-amz-date", amz_date.clone()),
+        ];
+        if let Some(token) = &self.credentials.session_token {
+            headers.push(("x-amz-security-token", token.clone()));
+        }
+
+        let authorization = sigv4_authorization(
+            "POST",
+            url.path(),
+            "",
+            &headers,
+            &body,
+            &self.credentials,
+            &self.region,
+            BEDROCK_SERVICE,
+            &amz_date,
+        )?;
+
+        let mut builder = self
+            .client
+            .post(url)
+            .header(reqwest::header::AUTHORIZATION, authorization)
+            .body(body);
+        for (name, value) in headers {
+            // reqwest derives Host from the URL
+            if name != "host" {
+                builder = builder.header(name, value);
+            }
+        }
+        Ok(builder)
+    }
+
+    /// Handle API error response
+    async fn handle_error(&self, response: reqwest::Response) -> ProviderError {
+        let status = response.status().as_u16();
+        let error_type = response
+            .headers()
+            .get("x-amzn-errortype")
+            .and_then(|v| v.to_str().ok())
+            // Header looks like "ThrottlingException:http://internal.amazon.com/..."
+            .map(|v| v.split(':').next().unwrap_or(v).to_string());
+
+        let message = response
+            .json::<BedrockError>()
+            .await
+            .map(|e| e.message)
+            .unwrap_or_else(|_| "Unknown error".to_string());
+
+        if status == 429 || error_type.as_deref() == Some("ThrottlingException") {
+            return ProviderError::RateLimitExceeded(format!(
+                "{} (rate limited, please retry later)",
+                message
+            ));
+        }
+
+        if error_type.as_deref() == Some("UnrecognizedClientException") {
+            tracing::error!("Bedrock rejected AWS credentials: {}", message);
+            return ProviderError::InvalidApiKey;
+        }
+
+        ProviderError::ApiError {
+            status,
+            message,
+            error_type,
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for BedrockProvider {
+    async fn complete(&self, request: LLMRequest) -> Result<LLMResponse> {
+        use super::retry::{retry_with_backoff, RetryConfig};
+
+        let model = request.model.clone();
+        tracing::info!(
+            "Bedrock API request: model={}, region={}, messages={}",
+            model,
+            self.region,
+            request.messages.len()
+        );
+
+        let body = serde_json::to_vec(&to_platform_request(
+            request,
+            BEDROCK_ANTHROPIC_VERSION,
+            false,
+        ))?;
+        let retry_config = RetryConfig::default();
+
+        let result = retry_with_backoff(
+            || async {
+                // Sign per attempt — the signature embeds a timestamp
+                let response = self
+                    .signed_request(&model, "invoke", body.clone(), "application/json")?
+                    .send()
+                    .await?;
+
+                if !response.status().is_success() {
+                    return Err(self.handle_error(response).await);
+                }
+
+                let anthropic_response: AnthropicResponse = response.json().await?;
+                let llm_response: LLMResponse = anthropic_response.into();
+
+                tracing::info!(
+                    "Bedrock API response: input_tokens={}, output_tokens={}, stop_reason={:?}",
+                    llm_response.usage.input_tokens,
+                    llm_response.usage.output_tokens,
+                    llm_response.stop_reason
+                );
+
+                Ok(llm_response)
+            },
+            &retry_config,
+        )
+        .await;
+
+        if let Err(ref e) = result {
+            tracing::error!("Bedrock API request failed: {}", e);
+        }
+
+        result
+    }
+
+    async fn stream(&self, request: LLMRequest) -> Result<ProviderStream> {
+        use super::retry::{retry_with_backoff, RetryConfig};
+
+        let model = request.model.clone();
+        tracing::info!(
+            "Bedrock streaming request: model={}, region={}, messages={}",
+            model,
+            self.region,
+            request.messages.len()
+        );
+
+        // InvokeModelWithResponseStream rejects a "stream" field in the body
+        let body = serde_json::to_vec(&to_platform_request(
+            request,
+            BEDROCK_ANTHROPIC_VERSION,
+            false,
+        ))?;
+        let retry_config = RetryConfig::default();
+
+        let response = retry_with_backoff(
+            || async {
+                let response = self
+                    .signed_request(
+                        &model,
+                        "invoke-with-response-stream",
+                        body.clone(),
+                        "application/vnd.amazon.eventstream",
+                    )?
+                    .send()
+                    .await?;
+
+                if !response.status().is_success() {
+                    return Err(self.handle_error(response).await);
+                }
+
+                Ok(response)
+            },
+            &retry_config,
+        )
+        .await?;
+
+        // Event-stream frames can be split across TCP chunks, so buffer raw bytes
+        let byte_stream = response.bytes_stream();
+        let buffer = std::sync::Arc::new(std::sync::Mutex::new(Vec::<u8>::new()));
+
+        let event_stream = byte_stream
+            .map(
+                move |chunk_result| -> Vec<std::result::Result<StreamEvent, ProviderError>> {
+                    match chunk_result {
+                        Err(e) => vec![Err(ProviderError::StreamError(e.to_string()))],
+                        Ok(chunk) => {
+                            let mut buf = buffer.lock().expect("event-stream buffer lock poisoned");
+                            buf.extend_from_slice(&chunk);
+
+                            let mut events = Vec::new();
+                            loop {
+                                match decode_frame(&buf) {
+                                    Ok(Some((frame, consumed))) => {
+                                        buf.drain(..consumed);
+                                        events.extend(frame_to_events(frame));
+                                    }
+                                    Ok(None) => break,
+                                    Err(e) => {
+                                        buf.clear();
+                                        events.push(Err(e));
+                                        break;
+                                    }
+                                }
+                            }
+
+                            if events.is_empty() {
+                                vec![Ok(StreamEvent::Ping)]
+                            } else {
+                                events
+                            }
+                        }
+                    }
+                },
+            )
+            .flat_map(futures::stream::iter);
+
+        Ok(Box::pin(event_stream))
+    }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    fn supports_tools(&self) -> bool {
+        true
+    }
+
+    fn supports_vision(&self) -> bool {
+        true
+    }
+
+    fn name(&self) -> &str {
+        "bedrock"
+    }
+
+    fn default_model(&self) -> &str {
+        self.custom_default_model
+            .as_deref()
+            .unwrap_or("anthropic.claude-sonnet-4-5-20250929-v1:0")
+    }
+
+    fn supported_models(&self) -> Vec<String> {
+        vec![
+            "anthropic.claude-opus-4-6-v1".to_string(),
+            "anthropic.claude-sonnet-4-5-20250929-v1:0".to_string(),
+            "anthropic.claude-haiku-4-5-20251001-v1:0".to_string(),
+            "anthropic.claude-3-5-sonnet-20241022-v2:0".to_string(),
+            "anthropic.claude-3-5-haiku-20241022-v1:0".to_string(),
+        ]
+    }
+
+    fn context_window(&self, model: &str) -> Option<u32> {
+        if model.contains("anthropic.claude") {
+            Some(200_000)
+        } else {
+            None
+        }
+    }
+
+    fn calculate_cost(&self, model: &str, input_tokens: u32, output_tokens: u32) -> f64 {
+        crate::pricing::PricingConfig::load().calculate_cost(model, input_tokens, output_tokens)
+    }
+
+    fn calculate_usage_cost(&self, model: &str, usage: &TokenUsage) -> f64 {
+        crate::pricing::PricingConfig::load().calculate_cost_with_cache(
+            model,
+            usage.input_tokens,
+            usage.output_tokens,
+            usage.cache_creation_input_tokens,
+            usage.cache_read_input_tokens,
+        )
+    }
+}
+
+// ============================================================================
+// SigV4 signing
+// ============================================================================
+
+/// Build the SigV4 `Authorization` header value.
+/// `headers` must contain every header to sign (lowercase names), including `host`
+/// and `x-amz-date`.
+#[allow(clippy::too_many_arguments)]
+fn sigv4_authorization(
+    method: &str,
+    path: &str,
+    query: &str,
+    headers: &[(&str, String)],
+    payload: &[u8],
+    credentials: &AwsCredentials,
+    region: &str,
+    service: &str,
+    amz_date: &str,
+) -> Result<String> {
+    let date = &amz_date[..8.min(amz_date.len())];
+
+    let mut sorted: Vec<(String, String)> = headers
+        .iter()
+        .map(|(k, v)| (k.to_lowercase(), v.trim().to_string()))
+        .collect();
+    sorted.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let canonical_headers: String = sorted
+        .iter()
+        .map(|(k, v)| format!("{}:{}\n", k, v))
+        .collect();
+    let signed_headers = sorted
+        .iter()
+        .map(|(k, _)| k.as_str())
+        .collect::<Vec<_>>()
+        .join(";");
+
+    // Non-S3 services sign the path with each segment encoded a second time
+    let canonical_uri = if path.is_empty() {
+        "/".to_string()
+    } else {
+        path.split('/')
+            .map(|segment| uri_encode(segment, true))
+            .collect::<Vec<_>>()
+            .join("/")
+    };
+
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        method,
+        canonical_uri,
+        query,
+        canonical_headers,
+        signed_headers,
+        hex(&openssl::sha::sha256(payload))
+    );
+
+    let scope = format!("{}/{}/{}/aws4_request", date, region, service);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex(&openssl::sha::sha256(canonical_request.as_bytes()))
+    );
+
+    let k_date = hmac_sha256(
+        format!("AWS4{}", credentials.secret_access_key).as_bytes(),
+        date.as_bytes(),
+    )?;
+    let k_region = hmac_sha256(&k_date, region.as_bytes())?;
+    let k_service = hmac_sha256(&k_region, service.as_bytes())?;
+    let k_signing = hmac_sha256(&k_service, b"aws4_request")?;
+    let signature = hex(&hmac_sha256(&k_signing, string_to_sign.as_bytes())?);
+
+    Ok(format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        credentials.access_key_id, scope, signed_headers, signature
+    ))
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Result<Vec<u8>> {
+    let sign = || -> std::result::Result<Vec<u8>, openssl::error::ErrorStack> {
+        let pkey = PKey::hmac(key)?;
+        let mut signer = Signer::new(MessageDigest::sha256(), &pkey)?;
+        signer.update(data)?;
+        signer.sign_to_vec()
+    };
+    sign().map_err(|e| ProviderError::Internal(format!("HMAC-SHA256 failed: {}", e)))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// RFC 3986 percent-encoding as SigV4 expects (unreserved characters pass through)
+fn uri_encode(input: &str, encode_slash: bool) -> String {
+    let mut out = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            b'/' if !encode_slash => out.push('/'),
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+// ============================================================================
+// Event-stream decoding (application/vnd.amazon.eventstream)
+// ============================================================================
+
+/// A decoded event-stream message
+struct EventFrame {
+    message_type: Option<String>,
+    event_type: Option<String>,
+    exception_type: Option<String>,
+    payload: Vec<u8>,
+}
+
+/// Decode one frame from the front of `buf`.
+/// Returns `Ok(None)` when more bytes are needed, or the frame and its length.
+///
+/// Frame layout: total_len(u32) headers_len(u32) prelude_crc(u32) headers payload message_crc(u32)
+fn decode_frame(buf: &[u8]) -> Result<Option<(EventFrame, usize)>> {
+    if buf.len() < 12 {
+        return Ok(None);
+    }
+    let read_u32 = |at: usize| u32::from_be_bytes([buf[at], buf[at + 1], buf[at + 2], buf[at + 3]]);
+
+    let total_len = read_u32(0) as usize;
+    let headers_len = read_u32(4) as usize;
+    if crc32(&buf[..8]) != read_u32(8) {
+        return Err(ProviderError::StreamError(
+            "Bedrock event-stream prelude checksum mismatch".to_string(),
+        ));
+    }
+    if total_len < 16 + headers_len {
+        return Err(ProviderError::StreamError(format!(
+            "Bedrock event-stream frame too short: {} bytes",
+            total_len
+        )));
+    }
+    if buf.len() < total_len {
+        return Ok(None);
+    }
+    if crc32(&buf[..total_len - 4]) != read_u32(total_len - 4) {
+        return Err(ProviderError::StreamError(
+            "Bedrock event-stream message checksum mismatch".to_string(),
+        ));
+    }
+
+    let mut frame = EventFrame {
+        message_type: None,
+        event_type: None,
+        exception_type: None,
+        payload: buf[12 + headers_len..total_len - 4].to_vec(),
+    };
+
+    // Headers: name_len(u8) name type(u8) value — only string values (type 7) matter here
+    let headers = &buf[12..12 + headers_len];
+    let mut pos = 0;
+    while pos < headers.len() {
+        let name_len = headers[pos] as usize;
+        pos += 1;
+        let name = String::from_utf8_lossy(headers.get(pos..pos + name_len).unwrap_or_default())
+            .to_string();
+        pos += name_len;
+        let Some(&value_type) = headers.get(pos) else {
+            break;
+        };
+        pos += 1;
+        let value_len = match value_type {
+            0 | 1 => 0,
+            2 => 1,
+            3 => 2,
+            4 => 4,
+            5 | 8 => 8,
+            9 => 16,
+            6 | 7 => {
+                let Some(len) = headers.get(pos..pos + 2) else {
+                    break;
+                };
+                pos += 2;
+                u16::from_be_bytes([len[0], len[1]]) as usize
+            }
+            _ => {
+                return Err(ProviderError::StreamError(format!(
+                    "Unknown event-stream header type {}",
+                    value_type
+                )));
+            }
+        };
+        let value = headers.get(pos..pos + value_len).unwrap_or_default();
+        pos += value_len;
+
+        if value_type == 7 {
+            let value = String::from_utf8_lossy(value).to_string();
+            match name.as_str() {
+                ":message-type" => frame.message_type = Some(value),
+                ":event-type" => frame.event_type = Some(value),
+                ":exception-type" => frame.exception_type = Some(value),
+                _ => {}
+            }
+        }
+    }
+
+    Ok(Some((frame, total_len)))
+}
+
+/// Map an event-stream frame to stream events.
+/// `chunk` events carry a base64-encoded Anthropic SSE event in `{"bytes": "..."}`.
+fn frame_to_events(frame: EventFrame) -> Vec<Result<StreamEvent>> {
+    if frame.message_type.as_deref() == Some("exception") {
+        let message = serde_json::from_slice::<BedrockError>(&frame.payload)
+            .map(|e| e.message)
+            .unwrap_or_else(|_| String::from_utf8_lossy(&frame.payload).to_string());
+        let kind = frame.exception_type.unwrap_or_default();
+        return vec![Err(if kind == "throttlingException" {
+            ProviderError::RateLimitExceeded(message)
+        } else {
+            ProviderError::StreamError(format!("Bedrock {}: {}", kind, message))
+        })];
+    }
+
+    if frame.event_type.as_deref() != Some("chunk") {
+        return Vec::new();
+    }
+
+    let chunk = match serde_json::from_slice::<BedrockChunk>(&frame.payload) {
+        Ok(c) => c,
+        Err(e) => {
+            tracing::warn!("Failed to parse Bedrock chunk payload: {}", e);
+            return Vec::new();
+        }
+    };
+    let decoded = match base64::engine::general_purpose::STANDARD.decode(chunk.bytes) {
+        Ok(d) => d,
+        Err(e) => {
+            tracing::warn!("Failed to decode Bedrock chunk bytes: {}", e);
+            return Vec::new();
+        }
+    };
+
+    // Each chunk is one Anthropic stream event; reuse the SSE parser for it
+    let mut line = format!("data: {}\n", String::from_utf8_lossy(&decoded));
+    drain_sse_events(&mut line)
+}
+
+/// CRC-32 (IEEE 802.3), as used by the event-stream prelude and message checksums
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+// ============================================================================
+// Bedrock API Types
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+struct BedrockError {
+    #[serde(alias = "Message")]
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BedrockChunk {
+    bytes: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_credentials() -> AwsCredentials {
+        AwsCredentials {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            session_token: None,
+        }
+    }
+
+    /// Encode an event-stream frame with string headers (test helper)
+    fn encode_frame(headers: &[(&str, &str)], payload: &[u8]) -> Vec<u8> {
+        let mut header_bytes = Vec::new();
+        for (name, value) in headers {
+            header_bytes.push(name.len() as u8);
+            header_bytes.extend_from_slice(name.as_bytes());
+            header_bytes.push(7);
+            header_bytes.extend_from_slice(&(value.len() as u16).to_be_bytes());
+            header_bytes.extend_from_slice(value.as_bytes());
+        }
+        let total_len = 12 + header_bytes.len() + payload.len() + 4;
+        let mut frame = Vec::new();
+        frame.extend_from_slice(&(total_len as u32).to_be_bytes());
+        frame.extend_from_slice(&(header_bytes.len() as u32).to_be_bytes());
+        let prelude_crc = crc32(&frame);
+        frame.extend_from_slice(&prelude_crc.to_be_bytes());
+        frame.extend_from_slice(&header_bytes);
+        frame.extend_from_slice(payload);
+        let message_crc = crc32(&frame);
+        frame.extend_from_slice(&message_crc.to_be_bytes());
+        frame
+    }
+
+    fn chunk_frame(event: serde_json::Value) -> Vec<u8> {
+        let bytes = base64::engine::general_purpose::STANDARD.encode(event.to_string());
+        encode_frame(
+            &[
+                (":event-type", "chunk"),
+                (":content-type", "application/json"),
+                (":message-type", "event"),
+            ],
+            serde_json::json!({ "bytes": bytes }).to_string().as_bytes(),
+        )
+    }
+
+    #[test]
+    fn test_credentials_from_key_string() {
+        let creds = AwsCredentials::from_key_string("AKID:secret").unwrap();
+        assert_eq!(creds.access_key_id, "AKID");
+        assert_eq!(creds.secret_access_key, "secret");
+        assert!(creds.session_token.is_none());
+
+        let creds = AwsCredentials::from_key_string("AKID:secret:token:with:colons").unwrap();
+        assert_eq!(creds.session_token.as_deref(), Some("token:with:colons"));
+
+        assert!(AwsCredentials::from_key_string("only-one-part").is_none());
+    }
+
+    #[test]
+    fn test_region_from_endpoint() {
+        assert_eq!(
+            BedrockProvider::region_from_endpoint(Some(
+                "https://bedrock-runtime.eu-central-1.amazonaws.com"
+            )),
+            "eu-central-1"
+        );
+    }
+
+    #[test]
+    fn test_crc32_known_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_sigv4_get_vanilla() {
+        // AWS SigV4 test suite: get-vanilla
+        let headers = vec![
+            ("host", "example.amazonaws.com".to_string()),
+            ("x-amz-date", "20150830T123600Z".to_string()),
+        ];
+        let auth = sigv4_authorization(
+            "GET",
+            "/",
+            "",
+            &headers,
+            b"",
+            &test_credentials(),
+            "us-east-1",
+            "service",
+            "20150830T123600Z",
+        )
+        .unwrap();
+        assert_eq!(
+            auth,
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+             SignedHeaders=host;x-amz-date, \
+             Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        );
+    }
+
+    #[test]
+    fn test_uri_encode_model_id() {
+        assert_eq!(
+            uri_encode("anthropic.claude-3-5-sonnet-20241022-v2:0", true),
+            "anthropic.claude-3-5-sonnet-20241022-v2%3A0"
+        );
+    }
+
+    #[test]
+    fn test_decode_frame_needs_more_bytes() {
+        let frame = chunk_frame(serde_json::json!({"type": "ping"}));
+        assert!(decode_frame(&frame[..frame.len() - 1]).unwrap().is_none());
+        let (_, consumed) = decode_frame(&frame).unwrap().unwrap();
+        assert_eq!(consumed, frame.len());
+    }
+
+    #[test]
+    fn test_chunk_frame_maps_to_stream_event() {
+        let frame = chunk_frame(serde_json::json!({
+            "type": "content_block_delta",
+            "index": 0,
+            "delta": {"type": "text_delta", "text": "Hi"}
+        }));
+        let (decoded, _) = decode_frame(&frame).unwrap().unwrap();
+        let events = frame_to_events(decoded);
+        assert!(matches!(
+            events.as_slice(),
+            [Ok(StreamEvent::ContentBlockDelta { delta: ContentDelta::TextDelta { text }, .. })] if text == "Hi"
+        ));
+    }
+
+    #[test]
+    fn test_exception_frame_maps_to_error() {
+        let frame = encode_frame(
+            &[
+                (":message-type", "exception"),
+                (":exception-type", "throttlingException"),
+            ],
+            br#"{"message":"Too many requests"}"#,
+        );
+        let (decoded, _) = decode_frame(&frame).unwrap().unwrap();
+        let events = frame_to_events(decoded);
+        assert!(matches!(
+            events.as_slice(),
+            [Err(ProviderError::RateLimitExceeded(m))] if m == "Too many requests"
+        ));
+    }
+
+    // --- HTTP tests with mock server ---
+
+    #[tokio::test]
+    async fn test_complete_signs_and_parses() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock(
+                "POST",
+                "/model/anthropic.claude-3-5-haiku-20241022-v1%3A0/invoke",
+            )
+            .match_header(
+                "authorization",
+                mockito::Matcher::Regex(
+                    r"^AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/\d{8}/us-west-2/bedrock/aws4_request, SignedHeaders=accept;content-type;host;x-amz-date, Signature=[0-9a-f]{64}$".to_string(),
+                ),
+            )
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"anthropic_version":"bedrock-2023-05-31"}"#.to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "id": "msg_bdrk_01",
+                    "type": "message",
+                    "role": "assistant",
+                    "model": "claude-3-5-haiku-20241022",
+                    "content": [{"type": "text", "text": "Hello from Bedrock"}],
+                    "stop_reason": "end_turn",
+                    "usage": {"input_tokens": 10, "output_tokens": 5}
+                }"#,
+            )
+            .create_async()
+            .await;
+
+        let provider =
+            BedrockProvider::with_endpoint(test_credentials(), "us-west-2".to_string(), server.url());
+        let response = provider
+            .complete(LLMRequest::new(
+                "anthropic.claude-3-5-haiku-20241022-v1:0",
+                vec![Message::user("Hi")],
+            ))
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(response.id, "msg_bdrk_01");
+        assert_eq!(response.usage.output_tokens, 5);
+        assert_eq!(response.stop_reason, Some(StopReason::EndTurn));
+    }
+
+    #[tokio::test]
+    async fn test_stream_decodes_event_stream() {
+        let mut body = Vec::new();
+        body.extend(chunk_frame(serde_json::json!({
+            "type": "message_start",
+            "message": {
+                "id": "msg_bdrk_02", "model": "claude-3-5-haiku-20241022", "role": "assistant",
+                "usage": {"input_tokens": 3, "output_tokens": 0}
+            }
+        })));
+        body.extend(chunk_frame(serde_json::json!({
+            "type": "content_block_delta", "index": 0,
+            "delta": {"type": "text_delta", "text": "streamed"}
+        })));
+        body.extend(chunk_frame(serde_json::json!({"type": "message_stop"})));
+
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock(
+                "POST",
+                "/model/anthropic.claude-3-5-haiku-20241022-v1%3A0/invoke-with-response-stream",
+            )
+            .with_status(200)
+            .with_header("content-type", "application/vnd.amazon.eventstream")
+            .with_body(body)
+            .create_async()
+            .await;
+
+        let provider =
+            BedrockProvider::with_endpoint(test_credentials(), "us-east-1".to_string(), server.url());
+        let mut stream = provider
+            .stream(LLMRequest::new(
+                "anthropic.claude-3-5-haiku-20241022-v1:0",
+                vec![Message::user("Hi")],
+            ))
+            .await
+            .unwrap();
+
+        let mut text = String::new();
+        let mut saw_start = false;
+        let mut saw_stop = false;
+        while let Some(event) = stream.next().await {
+            match event.unwrap() {
+                StreamEvent::MessageStart { .. } => saw_start = true,
+                StreamEvent::ContentBlockDelta {
+                    delta: ContentDelta::TextDelta { text: t },
+                    ..
+                } => text.push_str(&t),
+                StreamEvent::MessageStop => saw_stop = true,
+                _ => {}
+            }
+        }
+        assert!(saw_start);
+        assert_eq!(text, "streamed");
+        assert!(saw_stop);
+    }
+}
@@ -9,6 +9,7 @@
 
 use super::error::{ProviderError, Result};
 use super::r#trait::{Provider, ProviderStream};
+use super::think_tags::{split_think_tags, ThinkSegment, ThinkTagSplitter};
 use super::types::*;
 use async_trait::async_trait;
 use futures::stream::StreamExt;
@@ -21,6 +22,11 @@ const DEFAULT_TIMEOUT: Duration = Duration::from_secs(120);
 const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
 const DEFAULT_POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
 
+// Streamed content block layout: reasoning, answer text, then one block per tool call
+const THINKING_BLOCK_INDEX: usize = 0;
+const TEXT_BLOCK_INDEX: usize = 1;
+const TOOL_BLOCK_OFFSET: usize = 2;
+
 /// OpenAI provider for GPT models
 #[derive(Clone)]
 pub struct OpenAIProvider {
@@ -144,6 +150,7 @@ impl OpenAIProvider {
                 content: Some(system),
                 tool_calls: None,
                 tool_call_id: None,
+                reasoning_content: None,
             });
         }
 
@@ -165,7 +172,9 @@ impl OpenAIProvider {
                     ContentBlock::Text { text } => {
                         text_parts.push(text);
                     }
-                    ContentBlock::ToolUse { id, name, input } => {
+                    ContentBlock::ToolUse {
+                        id, name, input, ..
+                    } => {
                         tool_uses.push((id, name, input));
                     }
                     ContentBlock::ToolResult {
@@ -175,6 +184,8 @@ impl OpenAIProvider {
                     } => {
                         tool_results.push((tool_use_id, content));
                     }
+                    // Reasoning is not sent back to OpenAI-compatible APIs
+                    ContentBlock::Thinking { .. } | ContentBlock::RedactedThinking { .. } => {}
                     ContentBlock::Image { .. } => {
                         // Skip images for now (OpenAI needs special handling)
                         tracing::warn!("Image content blocks not yet supported for OpenAI");
@@ -207,6 +218,7 @@ impl OpenAIProvider {
                     content: content_str,
                     tool_calls: Some(openai_tool_calls),
                     tool_call_id: None,
+                    reasoning_content: None,
                 });
             }
             // Handle tool result messages
@@ -217,6 +229,7 @@ impl OpenAIProvider {
                         content: Some(content),
                         tool_calls: None,
                         tool_call_id: Some(tool_use_id),
+                        reasoning_content: None,
                     });
                 }
             }
@@ -233,6 +246,7 @@ impl OpenAIProvider {
                     content: content_str,
                     tool_calls: None,
                     tool_call_id: None,
+                    reasoning_content: None,
                 });
             }
         }
@@ -277,6 +291,7 @@ impl OpenAIProvider {
                     content: Some(String::new()),
                     tool_calls: None,
                     tool_call_id: None,
+                    reasoning_content: None,
                 },
                 finish_reason: Some("error".to_string()),
             });
@@ -284,11 +299,29 @@ impl OpenAIProvider {
         // Convert content to content blocks
         let mut content_blocks = Vec::new();
 
+        // Reasoning comes either in its own field or inline as <think> tags
+        let mut thinking = choice
+            .message
+            .reasoning_content
+            .filter(|r| !r.trim().is_empty());
+        let mut text = String::new();
+        if let Some(content) = choice.message.content {
+            let (inline_thinking, answer) = split_think_tags(&content);
+            thinking = thinking.or(inline_thinking);
+            text = answer;
+        }
+
+        if let Some(thinking) = thinking {
+            content_blocks.push(ContentBlock::Thinking {
+                text: thinking,
+                signature: None,
+            });
+        }
+
         // Add text content if present
-        if let Some(content) = choice.message.content
-            && !content.is_empty() {
-                content_blocks.push(ContentBlock::Text { text: content });
-            }
+        if !text.is_empty() {
+            content_blocks.push(ContentBlock::Text { text });
+        }
 
         // Convert tool_calls to ToolUse content blocks
         if let Some(tool_calls) = choice.message.tool_calls {
@@ -318,6 +351,7 @@ impl OpenAIProvider {
                     id: tool_call.id,
                     name: tool_call.function.name,
                     input,
+                    signature: None,
                 });
             }
         }
@@ -340,6 +374,7 @@ impl OpenAIProvider {
             usage: TokenUsage {
                 input_tokens: response.usage.prompt_tokens.unwrap_or(0),
                 output_tokens: response.usage.completion_tokens.unwrap_or(0),
+                ..Default::default()
             },
         }
     }
@@ -536,6 +571,52 @@ impl Provider for OpenAIProvider {
             seen_delta_content: bool,
             /// Index -> accumulated tool call
             tool_calls: std::collections::HashMap<usize, ToolCallAccum>,
+            emitted_thinking_start: bool,
+            /// Separates inline `<think>` reasoning from the answer text
+            think_splitter: ThinkTagSplitter,
+        }
+
+        impl StreamState {
+            /// Emit reasoning and answer segments into their own content blocks
+            fn push_segments(
+                &mut self,
+                segments: Vec<ThinkSegment>,
+                events: &mut Vec<std::result::Result<StreamEvent, ProviderError>>,
+            ) {
+                for segment in segments {
+                    match segment {
+                        ThinkSegment::Thinking(thinking) => {
+                            if !self.emitted_thinking_start {
+                                self.emitted_thinking_start = true;
+                                events.push(Ok(StreamEvent::ContentBlockStart {
+                                    index: THINKING_BLOCK_INDEX,
+                                    content_block: ContentBlock::Thinking {
+                                        text: String::new(),
+                                        signature: None,
+                                    },
+                                }));
+                            }
+                            events.push(Ok(StreamEvent::ContentBlockDelta {
+                                index: THINKING_BLOCK_INDEX,
+                                delta: ContentDelta::ThinkingDelta { thinking },
+                            }));
+                        }
+                        ThinkSegment::Text(text) => {
+                            if !self.emitted_content_start {
+                                self.emitted_content_start = true;
+                                events.push(Ok(StreamEvent::ContentBlockStart {
+                                    index: TEXT_BLOCK_INDEX,
+                                    content_block: ContentBlock::Text { text: String::new() },
+                                }));
+                            }
+                            events.push(Ok(StreamEvent::ContentBlockDelta {
+                                index: TEXT_BLOCK_INDEX,
+                                delta: ContentDelta::TextDelta { text },
+                            }));
+                        }
+                    }
+                }
+            }
         }
 
         let state = std::sync::Arc::new(std::sync::Mutex::new(StreamState {
@@ -543,6 +624,8 @@ impl Provider for OpenAIProvider {
             emitted_content_start: false,
             seen_delta_content: false,
             tool_calls: std::collections::HashMap::new(),
+            emitted_thinking_start: false,
+            think_splitter: ThinkTagSplitter::new(),
         }));
         
         let event_stream = byte_stream
@@ -569,6 +652,8 @@ impl Provider for OpenAIProvider {
 
                             if let Some(json_str) = line.strip_prefix("data: ") {
                                 if json_str == "[DONE]" {
+                                    let rest = st.think_splitter.finish();
+                                    st.push_segments(rest, &mut events);
                                     // Flush any accumulated tool calls before DONE
                                     for (_idx, accum) in st.tool_calls.drain() {
                                         let input = serde_json::from_str(&accum.arguments)
@@ -578,11 +663,12 @@ impl Provider for OpenAIProvider {
                                             accum.id, accum.name, &accum.arguments.chars().take(200).collect::<String>()
                                         );
                                         events.push(Ok(StreamEvent::ContentBlockStart {
-                                            index: _idx,
+                                            index: _idx + TOOL_BLOCK_OFFSET,
                                             content_block: ContentBlock::ToolUse {
                                                 id: accum.id,
                                                 name: accum.name,
                                                 input,
+                                                signature: None,
                                             },
                                         }));
                                     }
@@ -604,6 +690,7 @@ impl Provider for OpenAIProvider {
                                                     usage: crate::brain::provider::types::TokenUsage {
                                                         input_tokens: 0,
                                                         output_tokens: 0,
+                                                        ..Default::default()
                                                     },
                                                 },
                                             }));
@@ -691,35 +778,41 @@ impl Provider for OpenAIProvider {
                                                         idx, accum.id, accum.name, accum.arguments.len()
                                                     );
                                                     events.push(Ok(StreamEvent::ContentBlockStart {
-                                                        index: idx,
+                                                        index: idx + TOOL_BLOCK_OFFSET,
                                                         content_block: ContentBlock::ToolUse {
                                                             id: accum.id,
                                                             name: accum.name,
                                                             input,
+                                                            signature: None,
                                                         },
                                                     }));
                                                 }
                                             }
 
+                                        // Reasoning arrives in its own delta field or inline as <think> tags
+                                        let mut segments = Vec::new();
+                                        if let Some(reasoning) = chunk.choices.first()
+                                            .and_then(|c| c.delta.as_ref())
+                                            .and_then(|d| d.reasoning_content.clone())
+                                            && !reasoning.is_empty() {
+                                                segments.push(ThinkSegment::Thinking(reasoning));
+                                            }
+
                                         // Emit text content
                                         if let Some(ref c) = content {
                                             if !st.emitted_content_start && c.is_empty() {
                                                 st.emitted_content_start = true;
                                                 events.push(Ok(StreamEvent::ContentBlockStart {
-                                                    index: 0,
+                                                    index: TEXT_BLOCK_INDEX,
                                                     content_block: ContentBlock::Text { text: String::new() },
                                                 }));
                                             }
-
-                                            if !c.is_empty() {
-                                                events.push(Ok(StreamEvent::ContentBlockDelta {
-                                                    index: 0,
-                                                    delta: ContentDelta::TextDelta {
-                                                        text: c.clone(),
-                                                    },
-                                                }));
-                                            }
+                                            segments.extend(st.think_splitter.push(c));
                                         }
+                                        if finish_reason_str.is_some() {
+                                            segments.extend(st.think_splitter.finish());
+                                        }
+                                        st.push_segments(segments, &mut events);
 
                                         // Extract usage from final chunk
                                         if let Some(ref usage) = chunk.usage
@@ -743,6 +836,7 @@ impl Provider for OpenAIProvider {
                                                     usage: crate::brain::provider::types::TokenUsage {
                                                         input_tokens,
                                                         output_tokens,
+                                                        ..Default::default()
                                                     },
                                                 }));
                                             }
@@ -897,6 +991,9 @@ struct OpenAIMessage {
     tool_calls: Option<Vec<OpenAIToolCall>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tool_call_id: Option<String>,
+    /// Reasoning text (DeepSeek/vLLM `reasoning_content`, OpenRouter `reasoning`); never sent back
+    #[serde(default, alias = "reasoning", skip_serializing)]
+    reasoning_content: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -993,6 +1090,8 @@ struct StreamingFunctionCall {
 struct OpenAIMessageDelta {
     role: Option<String>,
     content: Option<String>,
+    #[serde(default, alias = "reasoning")]
+    reasoning_content: Option<String>,
     tool_calls: Option<Vec<StreamingToolCall>>,
 }
 
@@ -1053,4 +1152,33 @@ mod tests {
         let cost = provider.calculate_cost("gpt-3.5-turbo", 1000, 1000);
         assert!((cost - 0.002).abs() < 0.0001);
     }
+
+    #[test]
+    fn test_reasoning_becomes_thinking_block() {
+        let provider = OpenAIProvider::new("test-key".to_string());
+        let response = |message: serde_json::Value| {
+            let json = serde_json::json!({
+                "id": "r1",
+                "model": "deepseek-reasoner",
+                "choices": [{"index": 0, "message": message, "finish_reason": "stop"}],
+                "usage": {"prompt_tokens": 1, "completion_tokens": 1}
+            });
+            provider.from_openai_response(serde_json::from_value(json).unwrap())
+        };
+
+        let field = response(serde_json::json!({
+            "role": "assistant",
+            "content": "4",
+            "reasoning_content": "2 + 2"
+        }));
+        let inline = response(serde_json::json!({
+            "role": "assistant",
+            "content": "<think>2 + 2</think>\n\n4"
+        }));
+        for r in [field, inline] {
+            assert_eq!(r.content.len(), 2);
+            assert!(matches!(&r.content[0], ContentBlock::Thinking { text, signature: None } if text == "2 + 2"));
+            assert!(matches!(&r.content[1], ContentBlock::Text { text } if text == "4"));
+        }
+    }
 }
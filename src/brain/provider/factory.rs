@@ -4,7 +4,11 @@
 
 use super::{
     anthropic::AnthropicProvider,
+    bedrock::{AwsCredentials, BedrockProvider},
     custom_openai_compatible::OpenAIProvider,
+    failover::FailoverProvider,
+    gemini::GeminiProvider,
+    vertex::{ServiceAccountKey, VertexProvider},
     Provider,
 };
 use crate::config::{Config, ProviderConfig};
@@ -12,51 +16,79 @@ use anyhow::Result;
 use std::sync::Arc;
 
 /// Create a provider based on config.toml
-/// No hardcoded priority - providers are enabled/disabled in config
+/// No hardcoded priority - providers are enabled/disabled in config.
+/// When `fallback = [...]` lists a chain, the result is a `FailoverProvider`.
 pub fn create_provider(config: &Config) -> Result<Arc<dyn Provider>> {
+    let (label, primary) = create_primary_provider(config)?;
+    with_failover_chain(config, label, primary)
+}
+
+/// Create the single enabled provider, with its chain label (see `chain_label`)
+fn create_primary_provider(config: &Config) -> Result<(String, Arc<dyn Provider>)> {
     // Check which providers are enabled in config.toml
     
     // Try Minimax first
     if config.providers.minimax.as_ref().is_some_and(|p| p.enabled) {
         tracing::info!("Using enabled provider: Minimax");
-        return try_create_minimax(config)?
-            .ok_or_else(|| anyhow::anyhow!("Minimax enabled but failed to create"));
+        let provider = try_create_minimax(config)?
+            .ok_or_else(|| anyhow::anyhow!("Minimax enabled but failed to create"))?;
+        return Ok(("minimax".to_string(), provider));
     }
 
     // Try OpenRouter
     if config.providers.openrouter.as_ref().is_some_and(|p| p.enabled) {
         tracing::info!("Using enabled provider: OpenRouter");
-        return try_create_openrouter(config)?
-            .ok_or_else(|| anyhow::anyhow!("OpenRouter enabled but failed to create"));
+        let provider = try_create_openrouter(config)?
+            .ok_or_else(|| anyhow::anyhow!("OpenRouter enabled but failed to create"))?;
+        return Ok(("openrouter".to_string(), provider));
     }
 
     // Try Anthropic
     if config.providers.anthropic.as_ref().is_some_and(|p| p.enabled) {
         tracing::info!("Using enabled provider: Anthropic");
-        return try_create_anthropic(config)?
-            .ok_or_else(|| anyhow::anyhow!("Anthropic enabled but failed to create"));
+        let provider = try_create_anthropic(config)?
+            .ok_or_else(|| anyhow::anyhow!("Anthropic enabled but failed to create"))?;
+        return Ok(("anthropic".to_string(), provider));
     }
 
     // Try OpenAI (official)
     if config.providers.openai.as_ref().is_some_and(|p| p.enabled) {
         tracing::info!("Using enabled provider: OpenAI");
-        return try_create_openai(config)?
-            .ok_or_else(|| anyhow::anyhow!("OpenAI enabled but failed to create"));
+        let provider = try_create_openai(config)?
+            .ok_or_else(|| anyhow::anyhow!("OpenAI enabled but failed to create"))?;
+        return Ok(("openai".to_string(), provider));
     }
 
     // Try Custom OpenAI-compatible (first enabled named provider)
     if config.providers.active_custom().is_some() {
         tracing::info!("Using enabled provider: Custom OpenAI-Compatible");
-        return try_create_custom(config)?
-            .ok_or_else(|| anyhow::anyhow!("Custom provider enabled but failed to create"));
+        let provider = try_create_custom(config)?
+            .ok_or_else(|| anyhow::anyhow!("Custom provider enabled but failed to create"))?;
+        return Ok((chain_label(config, "custom"), provider));
     }
 
     // Try Gemini
     if config.providers.gemini.as_ref().is_some_and(|p| p.enabled) {
         tracing::info!("Using enabled provider: Google Gemini");
-        return Err(anyhow::anyhow!(
-            "Gemini provider not yet implemented"
-        ));
+        let provider = try_create_gemini(config)?
+            .ok_or_else(|| anyhow::anyhow!("Gemini enabled but failed to create"))?;
+        return Ok(("gemini".to_string(), provider));
+    }
+
+    // Try AWS Bedrock
+    if config.providers.bedrock.as_ref().is_some_and(|p| p.enabled) {
+        tracing::info!("Using enabled provider: AWS Bedrock");
+        let provider = try_create_bedrock(config)?
+            .ok_or_else(|| anyhow::anyhow!("Bedrock enabled but no AWS credentials found"))?;
+        return Ok(("bedrock".to_string(), provider));
+    }
+
+    // Try Vertex AI
+    if config.providers.vertex.as_ref().is_some_and(|p| p.enabled) {
+        tracing::info!("Using enabled provider: Vertex AI");
+        let provider = try_create_vertex(config)?
+            .ok_or_else(|| anyhow::anyhow!("Vertex enabled but no service account key found"))?;
+        return Ok(("vertex".to_string(), provider));
     }
 
     // Try fallback if primary fails
@@ -64,12 +96,87 @@ pub fn create_provider(config: &Config) -> Result<Arc<dyn Provider>> {
         && fallback.enabled
             && let Some(fallback_type) = &fallback.provider {
                 tracing::warn!("No primary provider enabled, trying fallback: {}", fallback_type);
-                return create_fallback(config, fallback_type);
+                return Ok((chain_label(config, fallback_type), create_fallback(config, fallback_type)?));
             }
 
     // No provider enabled - return placeholder provider so app can start and show onboarding
     tracing::info!("No provider configured, using placeholder provider");
-    Ok(Arc::new(super::PlaceholderProvider))
+    Ok(("none".to_string(), Arc::new(super::PlaceholderProvider)))
+}
+
+/// Wrap the primary provider in a `FailoverProvider` when the config lists a
+/// fallback chain. Chain entries that aren't configured are skipped with a warning.
+fn with_failover_chain(
+    config: &Config,
+    primary_label: String,
+    primary: Arc<dyn Provider>,
+) -> Result<Arc<dyn Provider>> {
+    let Some(fallback) = config
+        .providers
+        .fallback
+        .as_ref()
+        .filter(|f| f.enabled && !f.chain.is_empty())
+    else {
+        return Ok(primary);
+    };
+
+    let mut backends: Vec<(String, Arc<dyn Provider>)> = Vec::new();
+    if primary_label != "none" {
+        backends.push((primary_label, primary.clone()));
+    }
+    for entry in &fallback.chain {
+        let label = chain_label(config, entry);
+        if backends.iter().any(|(l, _)| *l == label) {
+            continue;
+        }
+        match create_fallback(config, entry) {
+            Ok(provider) => backends.push((label, provider)),
+            Err(e) => tracing::warn!("Skipping fallback '{}' in failover chain: {}", entry, e),
+        }
+    }
+
+    let mut backends = backends.into_iter();
+    let Some((label, first)) = backends.next() else {
+        return Ok(primary);
+    };
+    let rest: Vec<_> = backends.collect();
+    if rest.is_empty() {
+        return Ok(first);
+    }
+
+    let chain = rest
+        .into_iter()
+        .fold(FailoverProvider::new(label, first), |chain, (label, provider)| {
+            chain.with_fallback(label, provider)
+        });
+    tracing::info!("Provider failover chain: {}", chain.labels().join(" -> "));
+    Ok(Arc::new(chain))
+}
+
+/// Canonical chain label for a provider name, so aliases and the primary dedupe:
+/// "google" → "gemini", "custom" → "custom.<active name>", etc.
+fn chain_label(config: &Config, name: &str) -> String {
+    match name {
+        "google" => "gemini".to_string(),
+        "aws-bedrock" => "bedrock".to_string(),
+        "vertexai" => "vertex".to_string(),
+        "custom" => format!(
+            "custom.{}",
+            config
+                .providers
+                .active_custom()
+                .map(|(n, _)| n)
+                .unwrap_or("default")
+        ),
+        other => other.to_string(),
+    }
+}
+
+/// Create a provider by name, as listed in `[providers.fallback] chain`
+/// (e.g. "anthropic", "gemini", "custom.ollama"), whether or not it is the
+/// active one. Used for per-bee providers in Bee Colony debates.
+pub fn create_named_provider(config: &Config, name: &str) -> Result<Arc<dyn Provider>> {
+    create_fallback(config, name)
 }
 
 /// Create fallback provider
@@ -100,6 +207,31 @@ fn create_fallback(config: &Config, fallback_type: &str) -> Result<Arc<dyn Provi
             try_create_custom(config)?
                 .ok_or_else(|| anyhow::anyhow!("Custom provider not configured"))
         }
+        named if named.starts_with("custom.") => {
+            let name = &named["custom.".len()..];
+            tracing::info!("Using fallback: Custom OpenAI-Compatible '{}'", name);
+            let custom_config = config
+                .providers
+                .custom_by_name(name)
+                .ok_or_else(|| anyhow::anyhow!("Custom provider '{}' not configured", name))?;
+            create_named_custom(name, custom_config)?
+                .ok_or_else(|| anyhow::anyhow!("Custom provider '{}' has no api_key", name))
+        }
+        "gemini" | "google" => {
+            tracing::info!("Using fallback: Google Gemini");
+            try_create_gemini(config)?
+                .ok_or_else(|| anyhow::anyhow!("Gemini not configured"))
+        }
+        "bedrock" | "aws-bedrock" => {
+            tracing::info!("Using fallback: AWS Bedrock");
+            try_create_bedrock(config)?
+                .ok_or_else(|| anyhow::anyhow!("Bedrock not configured"))
+        }
+        "vertex" | "vertexai" => {
+            tracing::info!("Using fallback: Vertex AI");
+            try_create_vertex(config)?
+                .ok_or_else(|| anyhow::anyhow!("Vertex not configured"))
+        }
         _ => Err(anyhow::anyhow!("Unknown fallback provider: {}", fallback_type)),
     }
 }
@@ -159,11 +291,14 @@ fn try_create_minimax(config: &Config) -> Result<Option<Arc<dyn Provider>>> {
 /// Try to create Custom OpenAI-compatible provider if configured.
 /// Picks the first enabled named custom provider from the map.
 fn try_create_custom(config: &Config) -> Result<Option<Arc<dyn Provider>>> {
-    let (name, custom_config) = match config.providers.active_custom() {
-        Some((n, c)) => (n.to_string(), c.clone()),
-        None => return Ok(None),
-    };
+    match config.providers.active_custom() {
+        Some((name, custom_config)) => create_named_custom(name, custom_config),
+        None => Ok(None),
+    }
+}
 
+/// Create a named custom OpenAI-compatible provider
+fn create_named_custom(name: &str, custom_config: &ProviderConfig) -> Result<Option<Arc<dyn Provider>>> {
     let Some(api_key) = &custom_config.api_key else {
         return Ok(None);
     };
@@ -178,8 +313,8 @@ fn try_create_custom(config: &Config) -> Result<Option<Arc<dyn Provider>>> {
 
     tracing::info!("Using Custom OpenAI-compatible '{}' at: {}", name, base_url);
     let provider = configure_openai_compatible(
-        OpenAIProvider::with_base_url(api_key.clone(), base_url).with_name(&name),
-        &custom_config,
+        OpenAIProvider::with_base_url(api_key.clone(), base_url).with_name(name),
+        custom_config,
     );
     Ok(Some(Arc::new(provider)))
 }
@@ -244,6 +379,109 @@ fn try_create_anthropic(config: &Config) -> Result<Option<Arc<dyn Provider>>> {
     Ok(Some(Arc::new(provider)))
 }
 
+/// Try to create Google Gemini provider if configured
+fn try_create_gemini(config: &Config) -> Result<Option<Arc<dyn Provider>>> {
+    let gemini_config = match &config.providers.gemini {
+        Some(cfg) => cfg,
+        None => return Ok(None),
+    };
+
+    let Some(api_key) = &gemini_config.api_key else {
+        return Ok(None);
+    };
+
+    let mut provider = match &gemini_config.base_url {
+        Some(base_url) => {
+            tracing::info!("Using Gemini at: {}", base_url);
+            GeminiProvider::with_base_url(api_key.clone(), base_url.clone())
+        }
+        None => GeminiProvider::new(api_key.clone()),
+    };
+
+    if let Some(model) = &gemini_config.default_model {
+        tracing::info!("Using custom default model: {}", model);
+        provider = provider.with_default_model(model.clone());
+    }
+
+    tracing::info!("Using Google Gemini provider");
+
+    Ok(Some(Arc::new(provider)))
+}
+
+/// Try to create AWS Bedrock provider if configured.
+/// Credentials come from `api_key` ("ACCESS_KEY_ID:SECRET[:SESSION_TOKEN]") or the AWS_* env vars.
+fn try_create_bedrock(config: &Config) -> Result<Option<Arc<dyn Provider>>> {
+    let bedrock_config = match &config.providers.bedrock {
+        Some(cfg) => cfg,
+        None => return Ok(None),
+    };
+
+    let credentials = match bedrock_config.api_key.as_deref().filter(|k| !k.trim().is_empty()) {
+        Some(key) => Some(
+            AwsCredentials::from_key_string(key).ok_or_else(|| {
+                anyhow::anyhow!("Bedrock api_key must be ACCESS_KEY_ID:SECRET_ACCESS_KEY[:SESSION_TOKEN]")
+            })?,
+        ),
+        None => AwsCredentials::from_env(),
+    };
+    let Some(credentials) = credentials else {
+        return Ok(None);
+    };
+
+    let region = BedrockProvider::region_from_endpoint(bedrock_config.base_url.as_deref());
+    let mut provider = match &bedrock_config.base_url {
+        Some(endpoint) => {
+            tracing::info!("Using Bedrock at: {}", endpoint);
+            BedrockProvider::with_endpoint(credentials, region.clone(), endpoint.clone())
+        }
+        None => BedrockProvider::new(credentials, region.clone()),
+    };
+
+    if let Some(model) = &bedrock_config.default_model {
+        tracing::info!("Using custom default model: {}", model);
+        provider = provider.with_default_model(model.clone());
+    }
+
+    tracing::info!("Using AWS Bedrock provider (region {})", region);
+
+    Ok(Some(Arc::new(provider)))
+}
+
+/// Try to create Vertex AI provider if configured.
+/// `api_key` holds the service-account JSON or a path to it; falls back to GOOGLE_APPLICATION_CREDENTIALS.
+fn try_create_vertex(config: &Config) -> Result<Option<Arc<dyn Provider>>> {
+    let vertex_config = match &config.providers.vertex {
+        Some(cfg) => cfg,
+        None => return Ok(None),
+    };
+
+    let service_account = match vertex_config.api_key.as_deref().filter(|k| !k.trim().is_empty()) {
+        Some(key) => Some(ServiceAccountKey::load(key)?),
+        None => ServiceAccountKey::from_env(),
+    };
+    let Some(service_account) = service_account else {
+        return Ok(None);
+    };
+
+    let region = VertexProvider::region_from_base_url(vertex_config.base_url.as_deref());
+    let mut provider = match &vertex_config.base_url {
+        Some(base_url) => {
+            tracing::info!("Using Vertex AI at: {}", base_url);
+            VertexProvider::with_base_url(service_account, region.clone(), base_url.clone())
+        }
+        None => VertexProvider::new(service_account, region.clone()),
+    };
+
+    if let Some(model) = &vertex_config.default_model {
+        tracing::info!("Using custom default model: {}", model);
+        provider = provider.with_default_model(model.clone());
+    }
+
+    tracing::info!("Using Vertex AI provider (region {})", region);
+
+    Ok(Some(Arc::new(provider)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -329,4 +567,139 @@ mod tests {
         let result = create_provider(&config);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_create_provider_with_gemini() {
+        let config = Config {
+            providers: ProviderConfigs {
+                gemini: Some(ProviderConfig {
+                    enabled: true,
+                    api_key: Some("test-key".to_string()),
+                    base_url: None,
+                    default_model: Some("gemini-2.5-pro".to_string()),
+                    models: vec![],
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let provider = create_provider(&config).unwrap();
+        assert_eq!(provider.name(), "gemini");
+        assert_eq!(provider.default_model(), "gemini-2.5-pro");
+    }
+
+    #[test]
+    fn test_gemini_enabled_without_key_fails() {
+        let config = Config {
+            providers: ProviderConfigs {
+                gemini: Some(ProviderConfig {
+                    enabled: true,
+                    api_key: None,
+                    base_url: None,
+                    default_model: None,
+                    models: vec![],
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert!(create_provider(&config).is_err());
+    }
+
+    #[test]
+    fn test_create_provider_with_bedrock() {
+        let config = Config {
+            providers: ProviderConfigs {
+                bedrock: Some(ProviderConfig {
+                    enabled: true,
+                    api_key: Some("AKIDEXAMPLE:secret".to_string()),
+                    base_url: Some("https://bedrock-runtime.eu-west-1.amazonaws.com".to_string()),
+                    default_model: None,
+                    models: vec![],
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let provider = create_provider(&config).unwrap();
+        assert_eq!(provider.name(), "bedrock");
+    }
+
+    #[test]
+    fn test_bedrock_malformed_key_fails() {
+        let config = Config {
+            providers: ProviderConfigs {
+                bedrock: Some(ProviderConfig {
+                    enabled: true,
+                    api_key: Some("not-a-key-pair".to_string()),
+                    base_url: None,
+                    default_model: None,
+                    models: vec![],
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert!(create_provider(&config).is_err());
+    }
+
+    fn test_config_with_chain(chain: &[&str]) -> Config {
+        let key = |k: &str| ProviderConfig {
+            enabled: false,
+            api_key: Some(k.to_string()),
+            base_url: None,
+            default_model: None,
+            models: vec![],
+        };
+        Config {
+            providers: ProviderConfigs {
+                anthropic: Some(ProviderConfig { enabled: true, ..key("sk-ant") }),
+                openrouter: Some(key("sk-or")),
+                fallback: Some(crate::config::FallbackProviderConfig {
+                    enabled: true,
+                    provider: None,
+                    chain: chain.iter().map(|s| s.to_string()).collect(),
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_failover_chain_wraps_primary() {
+        let config = test_config_with_chain(&["anthropic", "openrouter"]);
+        let provider = create_provider(&config).unwrap();
+        // The chain starts on the primary
+        assert_eq!(provider.name(), "anthropic");
+    }
+
+    #[test]
+    fn test_failover_chain_skips_unconfigured_entries() {
+        let config = test_config_with_chain(&["gemini", "custom.missing"]);
+        let provider = create_provider(&config).unwrap();
+        assert_eq!(provider.name(), "anthropic");
+    }
+
+    #[test]
+    fn test_failover_chain_without_primary() {
+        let mut config = test_config_with_chain(&["openrouter"]);
+        config.providers.anthropic = None;
+        // A lone chain entry is used directly rather than wrapped
+        let provider = create_provider(&config).unwrap();
+        assert_eq!(provider.name(), "openai-compatible");
+    }
+
+    #[test]
+    fn test_chain_label_aliases() {
+        let config = Config::default();
+        assert_eq!(chain_label(&config, "google"), "gemini");
+        assert_eq!(chain_label(&config, "aws-bedrock"), "bedrock");
+        assert_eq!(chain_label(&config, "custom"), "custom.default");
+        assert_eq!(chain_label(&config, "custom.ollama"), "custom.ollama");
+    }
 }
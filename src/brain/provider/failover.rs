@@ -0,0 +1,487 @@
+//! Failover Provider
+//!
+//! Wraps an ordered chain of providers (from `fallback = [...]` in config.toml)
+//! behind a single `Provider`. Requests go to the active backend; when it fails
+//! with an error that `retry::should_failover` classifies as transient (5xx, 429,
+//! overloaded, connection errors) the next backend in the chain is tried.
+//!
+//! The switch is sticky: later requests stay on the backend that last succeeded,
+//! and the chain wraps around so the primary is retried once the fallbacks fail too.
+//! Each switch is queued as a [`ProviderSwitch`] which `AgentService` drains via
+//! [`Provider::take_switches`] and surfaces as a progress event.
+
+use super::error::{ProviderError, Result};
+use super::retry::should_failover;
+use super::r#trait::{Provider, ProviderStream};
+use super::types::*;
+use async_trait::async_trait;
+use futures::stream::StreamExt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// A switch from one backend to the next in a failover chain
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProviderSwitch {
+    /// Chain label of the backend that failed (e.g. "anthropic", "custom.ollama")
+    pub from: String,
+    /// Chain label of the backend now in use
+    pub to: String,
+    /// Error that triggered the switch
+    pub reason: String,
+}
+
+struct Backend {
+    label: String,
+    provider: Arc<dyn Provider>,
+}
+
+/// Provider that fails over through an ordered chain of backends
+pub struct FailoverProvider {
+    backends: Vec<Backend>,
+    active: AtomicUsize,
+    switches: Mutex<Vec<ProviderSwitch>>,
+}
+
+impl FailoverProvider {
+    /// Create a chain with its primary backend.
+    /// Labels identify backends in switch notices and logs.
+    pub fn new(label: impl Into<String>, primary: Arc<dyn Provider>) -> Self {
+        Self {
+            backends: vec![Backend {
+                label: label.into(),
+                provider: primary,
+            }],
+            active: AtomicUsize::new(0),
+            switches: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Append a fallback backend to the end of the chain
+    pub fn with_fallback(mut self, label: impl Into<String>, provider: Arc<dyn Provider>) -> Self {
+        self.backends.push(Backend {
+            label: label.into(),
+            provider,
+        });
+        self
+    }
+
+    /// Labels of all backends, in chain order
+    pub fn labels(&self) -> Vec<&str> {
+        self.backends.iter().map(|b| b.label.as_str()).collect()
+    }
+
+    /// Label of the backend currently serving requests
+    pub fn active_label(&self) -> &str {
+        &self.active_backend().label
+    }
+
+    fn active_backend(&self) -> &Backend {
+        &self.backends[self.active.load(Ordering::Relaxed) % self.backends.len()]
+    }
+
+    /// Backend indices to try for one request: active first, then the rest of the chain
+    fn attempt_order(&self) -> impl Iterator<Item = usize> + '_ {
+        let start = self.active.load(Ordering::Relaxed);
+        let len = self.backends.len();
+        (0..len).map(move |offset| (start + offset) % len)
+    }
+
+    fn owns_model(backend: &Backend, model: &str) -> bool {
+        backend.provider.default_model() == model
+            || backend.provider.supported_models().iter().any(|m| m == model)
+    }
+
+    /// Translate a model name to one the given backend understands.
+    ///
+    /// Models the backend lists are kept as-is; OpenRouter-style `vendor/model` names
+    /// are matched on their last segment. A model that belongs to another backend in
+    /// the chain becomes this backend's default. Unknown models pass through to the
+    /// primary (it may accept names outside its static list) but not to fallbacks.
+    fn translate_model(&self, idx: usize, model: &str) -> String {
+        let backend = &self.backends[idx];
+        if Self::owns_model(backend, model) {
+            return model.to_string();
+        }
+
+        let short = model.rsplit('/').next().unwrap_or(model);
+        if let Some(m) = backend
+            .provider
+            .supported_models()
+            .into_iter()
+            .find(|m| m.rsplit('/').next() == Some(short))
+        {
+            return m;
+        }
+
+        let owned_elsewhere = self
+            .backends
+            .iter()
+            .enumerate()
+            .any(|(i, b)| i != idx && Self::owns_model(b, model));
+        if idx == 0 && !owned_elsewhere {
+            model.to_string()
+        } else {
+            backend.provider.default_model().to_string()
+        }
+    }
+
+    /// Decide whether to move past a failed backend; records the switch if so
+    fn fail_over(&self, idx: usize, next: Option<usize>, error: &ProviderError) -> bool {
+        let Some(next) = next else {
+            return false;
+        };
+        if !should_failover(error) {
+            return false;
+        }
+
+        let switch = ProviderSwitch {
+            from: self.backends[idx].label.clone(),
+            to: self.backends[next].label.clone(),
+            reason: error.to_string(),
+        };
+        tracing::warn!(
+            "Provider '{}' failed ({}), failing over to '{}'",
+            switch.from,
+            switch.reason,
+            switch.to
+        );
+        self.active.store(next, Ordering::Relaxed);
+        self.switches
+            .lock()
+            .expect("failover switch lock poisoned")
+            .push(switch);
+        true
+    }
+
+    fn request_for(&self, idx: usize, request: &LLMRequest) -> LLMRequest {
+        let mut request = request.clone();
+        request.model = self.translate_model(idx, &request.model);
+        request
+    }
+}
+
+#[async_trait]
+impl Provider for FailoverProvider {
+    async fn complete(&self, request: LLMRequest) -> Result<LLMResponse> {
+        let order: Vec<usize> = self.attempt_order().collect();
+        let mut last_error = None;
+
+        for (pos, &idx) in order.iter().enumerate() {
+            let backend = &self.backends[idx];
+            match backend.provider.complete(self.request_for(idx, &request)).await {
+                Ok(response) => return Ok(response),
+                Err(e) => {
+                    if !self.fail_over(idx, order.get(pos + 1).copied(), &e) {
+                        return Err(e);
+                    }
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| ProviderError::Internal("empty failover chain".into())))
+    }
+
+    async fn stream(&self, request: LLMRequest) -> Result<ProviderStream> {
+        let order: Vec<usize> = self.attempt_order().collect();
+        let mut last_error = None;
+
+        for (pos, &idx) in order.iter().enumerate() {
+            let backend = &self.backends[idx];
+            let next = order.get(pos + 1).copied();
+
+            let mut stream = match backend.provider.stream(self.request_for(idx, &request)).await {
+                Ok(s) => s,
+                Err(e) => {
+                    if !self.fail_over(idx, next, &e) {
+                        return Err(e);
+                    }
+                    last_error = Some(e);
+                    continue;
+                }
+            };
+
+            // Overloaded errors often arrive as the first stream event rather than an
+            // HTTP status — peek until real content so those can fail over too.
+            let first = loop {
+                match stream.next().await {
+                    Some(Ok(StreamEvent::Ping)) => continue,
+                    other => break other,
+                }
+            };
+
+            match first {
+                Some(Err(e)) => {
+                    if !self.fail_over(idx, next, &e) {
+                        return Err(e);
+                    }
+                    last_error = Some(e);
+                }
+                Some(Ok(event)) => {
+                    return Ok(Box::pin(futures::stream::iter([Ok(event)]).chain(stream)));
+                }
+                None => return Ok(Box::pin(futures::stream::empty())),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| ProviderError::Internal("empty failover chain".into())))
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.active_backend().provider.supports_streaming()
+    }
+
+    fn supports_tools(&self) -> bool {
+        self.active_backend().provider.supports_tools()
+    }
+
+    fn supports_vision(&self) -> bool {
+        self.active_backend().provider.supports_vision()
+    }
+
+    fn name(&self) -> &str {
+        self.active_backend().provider.name()
+    }
+
+    fn default_model(&self) -> &str {
+        self.active_backend().provider.default_model()
+    }
+
+    fn supported_models(&self) -> Vec<String> {
+        self.active_backend().provider.supported_models()
+    }
+
+    async fn fetch_models(&self) -> Vec<String> {
+        self.active_backend().provider.fetch_models().await
+    }
+
+    fn context_window(&self, model: &str) -> Option<u32> {
+        // Ask whichever backend knows the model, so a switch doesn't shrink the budget
+        self.backends
+            .iter()
+            .find(|b| Self::owns_model(b, model))
+            .unwrap_or_else(|| self.active_backend())
+            .provider
+            .context_window(model)
+    }
+
+    fn calculate_cost(&self, model: &str, input_tokens: u32, output_tokens: u32) -> f64 {
+        self.active_backend()
+            .provider
+            .calculate_cost(model, input_tokens, output_tokens)
+    }
+
+    fn calculate_usage_cost(&self, model: &str, usage: &TokenUsage) -> f64 {
+        self.active_backend().provider.calculate_usage_cost(model, usage)
+    }
+
+    fn take_switches(&self) -> Vec<ProviderSwitch> {
+        std::mem::take(&mut *self.switches.lock().expect("failover switch lock poisoned"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU32;
+
+    /// Backend that fails with a fixed error a set number of times, then succeeds
+    struct ScriptedProvider {
+        name: &'static str,
+        models: Vec<String>,
+        failures: AtomicU32,
+        error: fn() -> ProviderError,
+        seen_models: Mutex<Vec<String>>,
+    }
+
+    impl ScriptedProvider {
+        fn new(name: &'static str, model: &str, failures: u32, error: fn() -> ProviderError) -> Arc<Self> {
+            Arc::new(Self {
+                name,
+                models: vec![model.to_string()],
+                failures: AtomicU32::new(failures),
+                error,
+                seen_models: Mutex::new(Vec::new()),
+            })
+        }
+
+        fn fail(&self) -> Option<ProviderError> {
+            let left = self.failures.load(Ordering::SeqCst);
+            if left > 0 {
+                self.failures.store(left - 1, Ordering::SeqCst);
+                Some((self.error)())
+            } else {
+                None
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Provider for ScriptedProvider {
+        async fn complete(&self, request: LLMRequest) -> Result<LLMResponse> {
+            self.seen_models.lock().unwrap().push(request.model.clone());
+            if let Some(e) = self.fail() {
+                return Err(e);
+            }
+            Ok(LLMResponse {
+                id: format!("{}-resp", self.name),
+                model: request.model,
+                content: vec![ContentBlock::Text {
+                    text: self.name.to_string(),
+                }],
+                stop_reason: Some(StopReason::EndTurn),
+                usage: TokenUsage {
+                    input_tokens: 1,
+                    output_tokens: 1,
+                    ..Default::default()
+                },
+            })
+        }
+
+        async fn stream(&self, request: LLMRequest) -> Result<ProviderStream> {
+            self.seen_models.lock().unwrap().push(request.model.clone());
+            let events: Vec<Result<StreamEvent>> = match self.fail() {
+                // Fail inside the stream, the way Anthropic reports "overloaded"
+                Some(e) => vec![Ok(StreamEvent::Ping), Err(e)],
+                None => vec![
+                    Ok(StreamEvent::ContentBlockDelta {
+                        index: 0,
+                        delta: ContentDelta::TextDelta {
+                            text: self.name.to_string(),
+                        },
+                    }),
+                    Ok(StreamEvent::MessageStop),
+                ],
+            };
+            Ok(Box::pin(futures::stream::iter(events)))
+        }
+
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn default_model(&self) -> &str {
+            &self.models[0]
+        }
+
+        fn supported_models(&self) -> Vec<String> {
+            self.models.clone()
+        }
+
+        fn context_window(&self, _model: &str) -> Option<u32> {
+            Some(100_000)
+        }
+
+        fn calculate_cost(&self, _model: &str, _input: u32, _output: u32) -> f64 {
+            0.0
+        }
+    }
+
+    fn overloaded() -> ProviderError {
+        ProviderError::ApiError {
+            status: 529,
+            message: "Overloaded".to_string(),
+            error_type: Some("overloaded_error".to_string()),
+        }
+    }
+
+    fn bad_request() -> ProviderError {
+        ProviderError::InvalidRequest("bad".to_string())
+    }
+
+    #[tokio::test]
+    async fn test_complete_fails_over_and_translates_model() {
+        let primary = ScriptedProvider::new("anthropic", "claude-sonnet-4-5", 1, overloaded);
+        let backup = ScriptedProvider::new("openrouter", "anthropic/claude-sonnet-4-5", 0, overloaded);
+        let chain = FailoverProvider::new("anthropic", primary.clone())
+            .with_fallback("openrouter", backup.clone());
+
+        let response = chain
+            .complete(LLMRequest::new("claude-sonnet-4-5", vec![Message::user("hi")]))
+            .await
+            .unwrap();
+
+        assert_eq!(response.id, "openrouter-resp");
+        assert_eq!(
+            backup.seen_models.lock().unwrap().as_slice(),
+            ["anthropic/claude-sonnet-4-5".to_string()]
+        );
+        assert_eq!(chain.active_label(), "openrouter");
+        assert_eq!(chain.name(), "openrouter");
+
+        let switches = chain.take_switches();
+        assert_eq!(switches.len(), 1);
+        assert_eq!(switches[0].from, "anthropic");
+        assert_eq!(switches[0].to, "openrouter");
+        assert!(chain.take_switches().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_non_transient_error_does_not_fail_over() {
+        let primary = ScriptedProvider::new("anthropic", "claude-sonnet-4-5", 1, bad_request);
+        let backup = ScriptedProvider::new("ollama", "qwen3", 0, overloaded);
+        let chain = FailoverProvider::new("anthropic", primary)
+            .with_fallback("custom.ollama", backup.clone());
+
+        let result = chain
+            .complete(LLMRequest::new("claude-sonnet-4-5", vec![Message::user("hi")]))
+            .await;
+
+        assert!(matches!(result, Err(ProviderError::InvalidRequest(_))));
+        assert!(backup.seen_models.lock().unwrap().is_empty());
+        assert!(chain.take_switches().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stream_fails_over_on_in_stream_error() {
+        let primary = ScriptedProvider::new("anthropic", "claude-sonnet-4-5", 1, overloaded);
+        let backup = ScriptedProvider::new("ollama", "qwen3", 0, overloaded);
+        let chain = FailoverProvider::new("anthropic", primary)
+            .with_fallback("custom.ollama", backup.clone());
+
+        let mut stream = chain
+            .stream(LLMRequest::new("claude-sonnet-4-5", vec![Message::user("hi")]))
+            .await
+            .unwrap();
+
+        let mut text = String::new();
+        while let Some(event) = stream.next().await {
+            if let StreamEvent::ContentBlockDelta {
+                delta: ContentDelta::TextDelta { text: t },
+                ..
+            } = event.unwrap()
+            {
+                text.push_str(&t);
+            }
+        }
+        assert_eq!(text, "ollama");
+        // Model owned by the primary becomes the fallback's default
+        assert_eq!(backup.seen_models.lock().unwrap().as_slice(), ["qwen3".to_string()]);
+        assert_eq!(chain.take_switches().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_chain_wraps_back_to_primary() {
+        let primary = ScriptedProvider::new("anthropic", "claude-sonnet-4-5", 1, overloaded);
+        let backup = ScriptedProvider::new("ollama", "qwen3", 5, overloaded);
+        let chain = FailoverProvider::new("anthropic", primary)
+            .with_fallback("custom.ollama", backup);
+
+        // Request 1: primary fails, backup fails, chain is exhausted
+        assert!(
+            chain
+                .complete(LLMRequest::new("claude-sonnet-4-5", vec![Message::user("hi")]))
+                .await
+                .is_err()
+        );
+        assert_eq!(chain.active_label(), "custom.ollama");
+
+        // Request 2: backup fails again, primary has recovered
+        let response = chain
+            .complete(LLMRequest::new("claude-sonnet-4-5", vec![Message::user("hi")]))
+            .await
+            .unwrap();
+        assert_eq!(response.id, "anthropic-resp");
+        assert_eq!(chain.active_label(), "anthropic");
+    }
+}
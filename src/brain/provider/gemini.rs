@@ -0,0 +1,1309 @@
+//! Google Gemini Provider Implementation
+//!
+//! Implements the Provider trait for Google's Gemini models via the
+//! Generative Language API (`generateContent` / `streamGenerateContent`).
+//!
+//! ## Mapping
+//! - `Role::Assistant` ↔ Gemini `model` role, system brain → `systemInstruction`
+//! - `ContentBlock::ToolUse` ↔ `functionCall` parts, with the part's
+//!   `thoughtSignature` kept on the block and sent back when it is replayed
+//! - `ContentBlock::ToolResult` → `functionResponse` parts (name resolved from the
+//!   matching `ToolUse` earlier in the conversation)
+//! - `ContentBlock::Image` → `inlineData` (base64) or `fileData` (URL) parts
+//!
+//! ## Supported Models
+//! - gemini-2.5-pro
+//! - gemini-2.5-flash
+//! - gemini-2.0-flash
+//! - gemini-1.5-pro (legacy)
+//! - gemini-1.5-flash (legacy)
+
+use super::error::{ProviderError, Result};
+use super::r#trait::{Provider, ProviderStream};
+use super::types::*;
+use async_trait::async_trait;
+use futures::stream::StreamExt;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+const GEMINI_API_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(120);
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Google Gemini provider
+#[derive(Clone)]
+pub struct GeminiProvider {
+    api_key: String,
+    base_url: String,
+    client: Client,
+    custom_default_model: Option<String>,
+}
+
+impl GeminiProvider {
+    /// Create a new Gemini provider against the public Generative Language API
+    pub fn new(api_key: String) -> Self {
+        Self::with_base_url(api_key, GEMINI_API_URL.to_string())
+    }
+
+    /// Create with a custom base URL (proxies, regional endpoints, tests)
+    pub fn with_base_url(api_key: String, base_url: String) -> Self {
+        let client = Client::builder()
+            .timeout(DEFAULT_TIMEOUT)
+            .connect_timeout(DEFAULT_CONNECT_TIMEOUT)
+            .pool_idle_timeout(DEFAULT_POOL_IDLE_TIMEOUT)
+            .pool_max_idle_per_host(2)
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            api_key,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            client,
+            custom_default_model: None,
+        }
+    }
+
+    /// Set custom default model
+    pub fn with_default_model(mut self, model: String) -> Self {
+        self.custom_default_model = Some(model);
+        self
+    }
+
+    /// Build request headers
+    fn headers(&self) -> std::result::Result<reqwest::header::HeaderMap, ProviderError> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        let key: reqwest::header::HeaderValue = self
+            .api_key
+            .trim()
+            .parse()
+            .map_err(|_| ProviderError::InvalidApiKey)?;
+        headers.insert("x-goog-api-key", key);
+        headers.insert(
+            reqwest::header::CONTENT_TYPE,
+            "application/json".parse().expect("valid content-type"),
+        );
+        Ok(headers)
+    }
+
+    /// Endpoint for a model method, e.g. `generateContent`
+    fn model_url(&self, model: &str, method: &str) -> String {
+        // Accept both "gemini-2.0-flash" and "models/gemini-2.0-flash"
+        let model = model.strip_prefix("models/").unwrap_or(model);
+        format!("{}/models/{}:{}", self.base_url, model, method)
+    }
+
+    /// Convert our generic request to Gemini's `GenerateContentRequest`
+    fn to_gemini_request(&self, request: LLMRequest) -> GeminiRequest {
+        // functionResponse parts must carry the function name, but our ToolResult
+        // blocks only carry the tool_use_id — resolve it from earlier ToolUse blocks.
+        let mut tool_names: HashMap<String, String> = HashMap::new();
+        let mut contents: Vec<GeminiContent> = Vec::new();
+
+        for msg in request.messages {
+            let role = match msg.role {
+                Role::Assistant => "model",
+                Role::User | Role::System => "user",
+            };
+
+            let mut parts = Vec::new();
+            for block in msg.content {
+                match block {
+                    ContentBlock::Text { text } => {
+                        if !text.is_empty() {
+                            parts.push(GeminiPart::text(text));
+                        }
+                    }
+                    // Gemini's thought summaries can't be replayed
+                    ContentBlock::Thinking { .. } | ContentBlock::RedactedThinking { .. } => {}
+                    ContentBlock::Image { source } => match source {
+                        ImageSource::Base64 { media_type, data } => {
+                            parts.push(GeminiPart {
+                                inline_data: Some(GeminiInlineData {
+                                    mime_type: media_type,
+                                    data,
+                                }),
+                                ..Default::default()
+                            });
+                        }
+                        ImageSource::Url { url } => {
+                            parts.push(GeminiPart {
+                                file_data: Some(GeminiFileData {
+                                    mime_type: mime_from_extension(&url).to_string(),
+                                    file_uri: url,
+                                }),
+                                ..Default::default()
+                            });
+                        }
+                    },
+                    ContentBlock::ToolUse {
+                        id,
+                        name,
+                        input,
+                        signature,
+                    } => {
+                        tool_names.insert(id, name.clone());
+                        parts.push(GeminiPart {
+                            function_call: Some(GeminiFunctionCall { name, args: input }),
+                            thought_signature: signature,
+                            ..Default::default()
+                        });
+                    }
+                    ContentBlock::ToolResult {
+                        tool_use_id,
+                        content,
+                        is_error,
+                    } => {
+                        let name = tool_names
+                            .get(&tool_use_id)
+                            .cloned()
+                            .unwrap_or_else(|| tool_use_id.clone());
+                        let response = if is_error.unwrap_or(false) {
+                            serde_json::json!({ "error": content })
+                        } else {
+                            serde_json::json!({ "content": content })
+                        };
+                        parts.push(GeminiPart {
+                            function_response: Some(GeminiFunctionResponse { name, response }),
+                            ..Default::default()
+                        });
+                    }
+                }
+            }
+
+            if parts.is_empty() {
+                continue;
+            }
+
+            // Gemini rejects consecutive turns from the same role — merge them
+            match contents.last_mut() {
+                Some(last) if last.role == role => last.parts.extend(parts),
+                _ => contents.push(GeminiContent {
+                    role: role.to_string(),
+                    parts,
+                }),
+            }
+        }
+
+        let system_instruction = request.system.map(|system| GeminiSystemInstruction {
+            parts: vec![GeminiPart::text(system)],
+        });
+
+        let tools = request.tools.filter(|t| !t.is_empty()).map(|tools| {
+            vec![GeminiTool {
+                function_declarations: tools
+                    .into_iter()
+                    .map(|tool| GeminiFunctionDeclaration {
+                        name: tool.name,
+                        description: tool.description,
+                        parameters: sanitize_schema(tool.input_schema),
+                    })
+                    .collect(),
+            }]
+        });
+
+        GeminiRequest {
+            contents,
+            system_instruction,
+            tools,
+            generation_config: Some(GeminiGenerationConfig {
+                temperature: request.temperature,
+                max_output_tokens: request.max_tokens,
+                thinking_config: request.thinking_budget.map(|budget| GeminiThinkingConfig {
+                    thinking_budget: budget,
+                    include_thoughts: true,
+                }),
+            }),
+        }
+    }
+
+    /// Convert a Gemini response to our generic format
+    #[allow(clippy::wrong_self_convention)]
+    fn from_gemini_response(&self, response: GeminiResponse, model: &str) -> LLMResponse {
+        let candidate = response.candidates.into_iter().next();
+        let finish_reason = candidate.as_ref().and_then(|c| c.finish_reason.clone());
+
+        let mut content = Vec::new();
+        let mut text = String::new();
+        let mut thinking = String::new();
+        if let Some(parts) = candidate.and_then(|c| c.content).map(|c| c.parts) {
+            for part in parts {
+                if let Some(t) = part.text {
+                    if part.thought == Some(true) {
+                        thinking.push_str(&t);
+                    } else {
+                        text.push_str(&t);
+                    }
+                }
+                if let Some(call) = part.function_call {
+                    content.push(ContentBlock::ToolUse {
+                        id: new_tool_call_id(),
+                        name: call.name,
+                        input: call.args,
+                        signature: part.thought_signature,
+                    });
+                }
+            }
+        }
+        if !text.is_empty() {
+            content.insert(0, ContentBlock::Text { text });
+        }
+        if !thinking.is_empty() {
+            content.insert(
+                0,
+                ContentBlock::Thinking {
+                    text: thinking,
+                    signature: None,
+                },
+            );
+        }
+
+        let has_tool_calls = content
+            .iter()
+            .any(|b| matches!(b, ContentBlock::ToolUse { .. }));
+
+        LLMResponse {
+            id: response
+                .response_id
+                .unwrap_or_else(|| format!("gemini-{}", uuid::Uuid::new_v4())),
+            model: response.model_version.unwrap_or_else(|| model.to_string()),
+            content,
+            stop_reason: map_finish_reason(finish_reason.as_deref(), has_tool_calls),
+            usage: response.usage_metadata.map(|u| u.into()).unwrap_or_default(),
+        }
+    }
+
+    /// Handle API error response
+    async fn handle_error(&self, response: reqwest::Response) -> ProviderError {
+        let status = response.status().as_u16();
+
+        let retry_after = response
+            .headers()
+            .get("retry-after")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|s| s.parse::<u64>().ok());
+
+        let (message, error_type) = match response.json::<GeminiErrorResponse>().await {
+            Ok(body) => (body.error.message, body.error.status),
+            Err(_) => ("Unknown error".to_string(), None),
+        };
+
+        if status == 429 {
+            let message = if let Some(secs) = retry_after {
+                format!("{} (retry after {} seconds)", message, secs)
+            } else {
+                format!("{} (rate limited, please retry later)", message)
+            };
+            return ProviderError::RateLimitExceeded(message);
+        }
+
+        if status == 401 || status == 403 {
+            tracing::error!("Gemini rejected API key ({}): {}", status, message);
+            return ProviderError::InvalidApiKey;
+        }
+
+        if status == 404 {
+            return ProviderError::ModelNotFound(message);
+        }
+
+        ProviderError::ApiError {
+            status,
+            message,
+            error_type,
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for GeminiProvider {
+    async fn complete(&self, request: LLMRequest) -> Result<LLMResponse> {
+        use super::retry::{retry_with_backoff, RetryConfig};
+
+        let model = request.model.clone();
+        tracing::info!(
+            "Gemini API request: model={}, messages={}, max_tokens={}",
+            model,
+            request.messages.len(),
+            request.max_tokens.unwrap_or(0)
+        );
+
+        let gemini_request = self.to_gemini_request(request);
+        let url = self.model_url(&model, "generateContent");
+        let retry_config = RetryConfig::default();
+
+        let result = retry_with_backoff(
+            || async {
+                let response = self
+                    .client
+                    .post(&url)
+                    .headers(self.headers()?)
+                    .json(&gemini_request)
+                    .send()
+                    .await?;
+
+                if !response.status().is_success() {
+                    return Err(self.handle_error(response).await);
+                }
+
+                let gemini_response: GeminiResponse = response.json().await?;
+                let llm_response = self.from_gemini_response(gemini_response, &model);
+
+                tracing::info!(
+                    "Gemini API response: input_tokens={}, output_tokens={}, stop_reason={:?}",
+                    llm_response.usage.input_tokens,
+                    llm_response.usage.output_tokens,
+                    llm_response.stop_reason
+                );
+
+                Ok(llm_response)
+            },
+            &retry_config,
+        )
+        .await;
+
+        if let Err(ref e) = result {
+            tracing::error!("Gemini API request failed: {}", e);
+        }
+
+        result
+    }
+
+    async fn stream(&self, request: LLMRequest) -> Result<ProviderStream> {
+        use super::retry::{retry_with_backoff, RetryConfig};
+
+        let model = request.model.clone();
+        tracing::info!(
+            "Gemini streaming request: model={}, messages={}",
+            model,
+            request.messages.len()
+        );
+
+        let gemini_request = self.to_gemini_request(request);
+        let url = format!("{}?alt=sse", self.model_url(&model, "streamGenerateContent"));
+        let retry_config = RetryConfig::default();
+
+        let response = retry_with_backoff(
+            || async {
+                let response = self
+                    .client
+                    .post(&url)
+                    .headers(self.headers()?)
+                    .json(&gemini_request)
+                    .send()
+                    .await?;
+
+                if !response.status().is_success() {
+                    return Err(self.handle_error(response).await);
+                }
+
+                Ok(response)
+            },
+            &retry_config,
+        )
+        .await?;
+
+        /// State persisted across SSE chunks
+        struct StreamState {
+            emitted_message_start: bool,
+            emitted_text_start: bool,
+            /// Next content block index for function calls and thoughts (text always uses 0)
+            next_index: usize,
+            thinking_index: Option<usize>,
+            saw_tool_call: bool,
+            finished: bool,
+        }
+
+        let byte_stream = response.bytes_stream();
+        let buffer = std::sync::Arc::new(std::sync::Mutex::new(String::new()));
+        let state = std::sync::Arc::new(std::sync::Mutex::new(StreamState {
+            emitted_message_start: false,
+            emitted_text_start: false,
+            next_index: 1,
+            thinking_index: None,
+            saw_tool_call: false,
+            finished: false,
+        }));
+
+        let event_stream = byte_stream
+            .map(
+                move |chunk_result| -> Vec<std::result::Result<StreamEvent, ProviderError>> {
+                    match chunk_result {
+                        Err(e) => vec![Err(ProviderError::StreamError(e.to_string()))],
+                        Ok(chunk) => {
+                            let mut buf = buffer.lock().expect("SSE buffer lock poisoned");
+                            buf.push_str(&String::from_utf8_lossy(&chunk));
+
+                            let mut st = state.lock().expect("SSE state lock poisoned");
+                            let mut events = Vec::new();
+
+                            while let Some(newline_pos) = buf.find('\n') {
+                                let line = buf[..newline_pos].trim().to_string();
+                                buf.drain(..=newline_pos);
+
+                                let Some(json_str) = line.strip_prefix("data:") else {
+                                    continue;
+                                };
+                                let json_str = json_str.trim();
+                                if json_str.is_empty() || st.finished {
+                                    continue;
+                                }
+
+                                let chunk = match serde_json::from_str::<GeminiResponse>(json_str) {
+                                    Ok(c) => c,
+                                    Err(e) => {
+                                        tracing::warn!(
+                                            "Failed to parse Gemini SSE chunk: {}. Data: {}",
+                                            e,
+                                            json_str.chars().take(200).collect::<String>()
+                                        );
+                                        continue;
+                                    }
+                                };
+
+                                if !st.emitted_message_start {
+                                    st.emitted_message_start = true;
+                                    events.push(Ok(StreamEvent::MessageStart {
+                                        message: StreamMessage {
+                                            id: chunk.response_id.clone().unwrap_or_else(|| {
+                                                format!("gemini-{}", uuid::Uuid::new_v4())
+                                            }),
+                                            model: chunk
+                                                .model_version
+                                                .clone()
+                                                .unwrap_or_else(|| model.clone()),
+                                            role: Role::Assistant,
+                                            usage: TokenUsage::default(),
+                                        },
+                                    }));
+                                }
+
+                                let candidate = chunk.candidates.into_iter().next();
+                                let finish_reason =
+                                    candidate.as_ref().and_then(|c| c.finish_reason.clone());
+
+                                let parts = candidate
+                                    .and_then(|c| c.content)
+                                    .map(|c| c.parts)
+                                    .unwrap_or_default();
+                                for part in parts {
+                                    if part.thought == Some(true) {
+                                        if let Some(thinking) = part.text
+                                            && !thinking.is_empty()
+                                        {
+                                            let index = match st.thinking_index {
+                                                Some(index) => index,
+                                                None => {
+                                                    let index = st.next_index;
+                                                    st.next_index += 1;
+                                                    st.thinking_index = Some(index);
+                                                    events.push(Ok(StreamEvent::ContentBlockStart {
+                                                        index,
+                                                        content_block: ContentBlock::Thinking {
+                                                            text: String::new(),
+                                                            signature: None,
+                                                        },
+                                                    }));
+                                                    index
+                                                }
+                                            };
+                                            events.push(Ok(StreamEvent::ContentBlockDelta {
+                                                index,
+                                                delta: ContentDelta::ThinkingDelta { thinking },
+                                            }));
+                                        }
+                                    } else if let Some(text) = part.text
+                                        && !text.is_empty()
+                                    {
+                                        if !st.emitted_text_start {
+                                            st.emitted_text_start = true;
+                                            events.push(Ok(StreamEvent::ContentBlockStart {
+                                                index: 0,
+                                                content_block: ContentBlock::Text {
+                                                    text: String::new(),
+                                                },
+                                            }));
+                                        }
+                                        events.push(Ok(StreamEvent::ContentBlockDelta {
+                                            index: 0,
+                                            delta: ContentDelta::TextDelta { text },
+                                        }));
+                                    }
+
+                                    // Gemini sends each function call whole, never in fragments
+                                    if let Some(call) = part.function_call {
+                                        let index = st.next_index;
+                                        st.next_index += 1;
+                                        st.saw_tool_call = true;
+                                        events.push(Ok(StreamEvent::ContentBlockStart {
+                                            index,
+                                            content_block: ContentBlock::ToolUse {
+                                                id: new_tool_call_id(),
+                                                name: call.name,
+                                                input: call.args,
+                                                signature: part.thought_signature,
+                                            },
+                                        }));
+                                        events.push(Ok(StreamEvent::ContentBlockStop { index }));
+                                    }
+                                }
+
+                                if let Some(reason) = finish_reason {
+                                    st.finished = true;
+                                    if st.emitted_text_start {
+                                        events.push(Ok(StreamEvent::ContentBlockStop { index: 0 }));
+                                    }
+                                    events.push(Ok(StreamEvent::MessageDelta {
+                                        delta: MessageDelta {
+                                            stop_reason: map_finish_reason(
+                                                Some(&reason),
+                                                st.saw_tool_call,
+                                            ),
+                                            stop_sequence: None,
+                                        },
+                                        usage: chunk.usage_metadata.map(|u| u.into()).unwrap_or(
+                                            TokenUsage::default(),
+                                        ),
+                                    }));
+                                    events.push(Ok(StreamEvent::MessageStop));
+                                }
+                            }
+
+                            if events.is_empty() {
+                                vec![Ok(StreamEvent::Ping)]
+                            } else {
+                                events
+                            }
+                        }
+                    }
+                },
+            )
+            .flat_map(futures::stream::iter);
+
+        Ok(Box::pin(event_stream))
+    }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    fn supports_tools(&self) -> bool {
+        true
+    }
+
+    fn supports_vision(&self) -> bool {
+        true
+    }
+
+    fn name(&self) -> &str {
+        "gemini"
+    }
+
+    fn default_model(&self) -> &str {
+        self.custom_default_model
+            .as_deref()
+            .unwrap_or("gemini-2.5-flash")
+    }
+
+    fn supported_models(&self) -> Vec<String> {
+        vec![
+            "gemini-2.5-pro".to_string(),
+            "gemini-2.5-flash".to_string(),
+            "gemini-2.0-flash".to_string(),
+            "gemini-1.5-pro".to_string(),
+            "gemini-1.5-flash".to_string(),
+        ]
+    }
+
+    async fn fetch_models(&self) -> Vec<String> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct ModelEntry {
+            name: String,
+            #[serde(default)]
+            supported_generation_methods: Vec<String>,
+        }
+        #[derive(Deserialize)]
+        struct ModelsResponse {
+            #[serde(default)]
+            models: Vec<ModelEntry>,
+        }
+
+        let headers = match self.headers() {
+            Ok(h) => h,
+            Err(_) => return self.supported_models(),
+        };
+
+        match self
+            .client
+            .get(format!("{}/models", self.base_url))
+            .headers(headers)
+            .query(&[("pageSize", "1000")])
+            .send()
+            .await
+        {
+            Ok(resp) if resp.status().is_success() => match resp.json::<ModelsResponse>().await {
+                Ok(body) => {
+                    let mut models: Vec<String> = body
+                        .models
+                        .into_iter()
+                        .filter(|m| {
+                            m.supported_generation_methods
+                                .iter()
+                                .any(|g| g == "generateContent")
+                        })
+                        .map(|m| m.name.trim_start_matches("models/").to_string())
+                        .collect();
+                    models.sort();
+                    if models.is_empty() {
+                        return self.supported_models();
+                    }
+                    models
+                }
+                Err(_) => self.supported_models(),
+            },
+            _ => self.supported_models(),
+        }
+    }
+
+    fn context_window(&self, model: &str) -> Option<u32> {
+        let m = model.strip_prefix("models/").unwrap_or(model);
+        if m.starts_with("gemini-1.5-pro") {
+            Some(2_097_152)
+        } else if m.starts_with("gemini-2.5")
+            || m.starts_with("gemini-2.0")
+            || m.starts_with("gemini-1.5-flash")
+        {
+            Some(1_048_576)
+        } else {
+            None
+        }
+    }
+
+    fn calculate_cost(&self, model: &str, input_tokens: u32, output_tokens: u32) -> f64 {
+        crate::pricing::PricingConfig::load().calculate_cost(model, input_tokens, output_tokens)
+    }
+}
+
+/// Map Gemini `finishReason` onto our StopReason.
+/// Gemini reports `STOP` for function calls too, so tool use is inferred from content.
+fn map_finish_reason(reason: Option<&str>, has_tool_calls: bool) -> Option<StopReason> {
+    let reason = reason?;
+    if has_tool_calls {
+        return Some(StopReason::ToolUse);
+    }
+    Some(match reason {
+        "MAX_TOKENS" => StopReason::MaxTokens,
+        _ => StopReason::EndTurn,
+    })
+}
+
+/// Gemini does not assign ids to function calls — mint one so ToolResult can reference it
+fn new_tool_call_id() -> String {
+    format!("call_{}", uuid::Uuid::new_v4().simple())
+}
+
+/// Best-effort MIME type for image URLs passed as `fileData`
+fn mime_from_extension(url: &str) -> &'static str {
+    let path = url.split(['?', '#']).next().unwrap_or(url).to_lowercase();
+    match path.rsplit('.').next().unwrap_or("") {
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "heic" => "image/heic",
+        _ => "image/jpeg",
+    }
+}
+
+/// Strip JSON Schema keywords that Gemini's OpenAPI-subset schema rejects
+fn sanitize_schema(mut schema: serde_json::Value) -> serde_json::Value {
+    fn strip(value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(map) => {
+                map.remove("$schema");
+                map.remove("additionalProperties");
+                for v in map.values_mut() {
+                    strip(v);
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for v in items {
+                    strip(v);
+                }
+            }
+            _ => {}
+        }
+    }
+    strip(&mut schema);
+    schema
+}
+
+// ============================================================================
+// Gemini API Types
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiRequest {
+    contents: Vec<GeminiContent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system_instruction: Option<GeminiSystemInstruction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<GeminiTool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generation_config: Option<GeminiGenerationConfig>,
+}
+
+#[derive(Debug, Serialize)]
+struct GeminiSystemInstruction {
+    parts: Vec<GeminiPart>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct GeminiContent {
+    #[serde(default)]
+    role: String,
+    #[serde(default)]
+    parts: Vec<GeminiPart>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiPart {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inline_data: Option<GeminiInlineData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file_data: Option<GeminiFileData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    function_call: Option<GeminiFunctionCall>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    function_response: Option<GeminiFunctionResponse>,
+    /// Set on thought-summary parts when `includeThoughts` is enabled
+    #[serde(default, skip_serializing)]
+    thought: Option<bool>,
+    /// Opaque signature thinking models attach to `functionCall` parts; it
+    /// must come back on the same part or the next request is rejected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    thought_signature: Option<String>,
+}
+
+impl GeminiPart {
+    fn text(text: String) -> Self {
+        Self {
+            text: Some(text),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiInlineData {
+    mime_type: String,
+    data: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiFileData {
+    mime_type: String,
+    file_uri: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct GeminiFunctionCall {
+    name: String,
+    #[serde(default)]
+    args: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct GeminiFunctionResponse {
+    name: String,
+    response: serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiTool {
+    function_declarations: Vec<GeminiFunctionDeclaration>,
+}
+
+#[derive(Debug, Serialize)]
+struct GeminiFunctionDeclaration {
+    name: String,
+    description: String,
+    parameters: serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiGenerationConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_output_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking_config: Option<GeminiThinkingConfig>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiThinkingConfig {
+    thinking_budget: u32,
+    include_thoughts: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiResponse {
+    #[serde(default)]
+    candidates: Vec<GeminiCandidate>,
+    #[serde(default)]
+    usage_metadata: Option<GeminiUsage>,
+    #[serde(default)]
+    model_version: Option<String>,
+    #[serde(default)]
+    response_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiCandidate {
+    #[serde(default)]
+    content: Option<GeminiContent>,
+    #[serde(default)]
+    finish_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiUsage {
+    #[serde(default)]
+    prompt_token_count: u32,
+    #[serde(default)]
+    candidates_token_count: u32,
+}
+
+impl From<GeminiUsage> for TokenUsage {
+    fn from(u: GeminiUsage) -> Self {
+        TokenUsage {
+            input_tokens: u.prompt_token_count,
+            output_tokens: u.candidates_token_count,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiErrorResponse {
+    error: GeminiError,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiError {
+    message: String,
+    #[serde(default)]
+    status: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_provider(url: &str) -> GeminiProvider {
+        GeminiProvider::with_base_url("test-gemini-key".to_string(), url.to_string())
+    }
+
+    #[test]
+    fn test_gemini_provider_creation() {
+        let provider = GeminiProvider::new("test-key".to_string());
+        assert_eq!(provider.name(), "gemini");
+        assert_eq!(provider.default_model(), "gemini-2.5-flash");
+        assert!(provider.supports_vision());
+    }
+
+    #[test]
+    fn test_custom_default_model() {
+        let provider = GeminiProvider::new("test-key".to_string())
+            .with_default_model("gemini-2.5-pro".to_string());
+        assert_eq!(provider.default_model(), "gemini-2.5-pro");
+    }
+
+    #[test]
+    fn test_context_window() {
+        let provider = GeminiProvider::new("test-key".to_string());
+        assert_eq!(provider.context_window("gemini-2.5-pro"), Some(1_048_576));
+        assert_eq!(provider.context_window("models/gemini-1.5-pro-002"), Some(2_097_152));
+        assert_eq!(provider.context_window("unknown"), None);
+    }
+
+    #[test]
+    fn test_request_mapping_roles_and_tools() {
+        let provider = GeminiProvider::new("test-key".to_string());
+        let request = LLMRequest::new(
+            "gemini-2.5-flash",
+            vec![
+                Message::user("list files"),
+                Message {
+                    role: Role::Assistant,
+                    content: vec![ContentBlock::ToolUse {
+                        id: "call_1".to_string(),
+                        name: "ls".to_string(),
+                        input: serde_json::json!({"path": "."}),
+                        signature: None,
+                    }],
+                },
+                Message {
+                    role: Role::User,
+                    content: vec![ContentBlock::ToolResult {
+                        tool_use_id: "call_1".to_string(),
+                        content: "src\nCargo.toml".to_string(),
+                        is_error: None,
+                    }],
+                },
+            ],
+        )
+        .with_system("You are helpful")
+        .with_tools(vec![Tool {
+            name: "ls".to_string(),
+            description: "List files".to_string(),
+            input_schema: serde_json::json!({
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "type": "object",
+                "properties": {"path": {"type": "string"}},
+                "additionalProperties": false
+            }),
+        }]);
+
+        let body = serde_json::to_value(provider.to_gemini_request(request)).unwrap();
+
+        assert_eq!(body["systemInstruction"]["parts"][0]["text"], "You are helpful");
+        assert_eq!(body["contents"][0]["role"], "user");
+        assert_eq!(body["contents"][1]["role"], "model");
+        assert_eq!(body["contents"][1]["parts"][0]["functionCall"]["name"], "ls");
+        // Tool result resolves the function name from the earlier ToolUse
+        let fr = &body["contents"][2]["parts"][0]["functionResponse"];
+        assert_eq!(fr["name"], "ls");
+        assert_eq!(fr["response"]["content"], "src\nCargo.toml");
+
+        let params = &body["tools"][0]["functionDeclarations"][0]["parameters"];
+        assert!(params.get("$schema").is_none());
+        assert!(params.get("additionalProperties").is_none());
+        assert_eq!(params["type"], "object");
+    }
+
+    #[test]
+    fn test_request_mapping_inline_image() {
+        let provider = GeminiProvider::new("test-key".to_string());
+        let request = LLMRequest::new(
+            "gemini-2.5-flash",
+            vec![Message {
+                role: Role::User,
+                content: vec![
+                    ContentBlock::Text {
+                        text: "what is this?".to_string(),
+                    },
+                    ContentBlock::Image {
+                        source: ImageSource::Base64 {
+                            media_type: "image/png".to_string(),
+                            data: "iVBORw0KGgo=".to_string(),
+                        },
+                    },
+                ],
+            }],
+        );
+
+        let body = serde_json::to_value(provider.to_gemini_request(request)).unwrap();
+        let inline = &body["contents"][0]["parts"][1]["inlineData"];
+        assert_eq!(inline["mimeType"], "image/png");
+        assert_eq!(inline["data"], "iVBORw0KGgo=");
+    }
+
+    #[test]
+    fn test_consecutive_same_role_turns_are_merged() {
+        let provider = GeminiProvider::new("test-key".to_string());
+        let request = LLMRequest::new(
+            "gemini-2.5-flash",
+            vec![Message::user("one"), Message::user("two")],
+        );
+        let body = serde_json::to_value(provider.to_gemini_request(request)).unwrap();
+        assert_eq!(body["contents"].as_array().unwrap().len(), 1);
+        assert_eq!(body["contents"][0]["parts"].as_array().unwrap().len(), 2);
+    }
+
+    // --- HTTP tests with mock server ---
+
+    #[tokio::test]
+    async fn test_complete_text_response() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/models/gemini-2.5-flash:generateContent")
+            .match_header("x-goog-api-key", "test-gemini-key")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "candidates": [{
+                        "content": {"role": "model", "parts": [{"text": "Hello from Gemini"}]},
+                        "finishReason": "STOP"
+                    }],
+                    "usageMetadata": {"promptTokenCount": 12, "candidatesTokenCount": 4},
+                    "modelVersion": "gemini-2.5-flash"
+                }"#,
+            )
+            .create_async()
+            .await;
+
+        let provider = test_provider(&server.url());
+        let response = provider
+            .complete(LLMRequest::new("gemini-2.5-flash", vec![Message::user("Hi")]))
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(response.model, "gemini-2.5-flash");
+        assert_eq!(response.stop_reason, Some(StopReason::EndTurn));
+        assert_eq!(response.usage.input_tokens, 12);
+        assert_eq!(response.usage.output_tokens, 4);
+        assert!(matches!(&response.content[0], ContentBlock::Text { text } if text == "Hello from Gemini"));
+    }
+
+    #[test]
+    fn test_thought_parts_and_thinking_config() {
+        let provider = GeminiProvider::new("test-gemini-key".to_string());
+        let request = LLMRequest::new("gemini-2.5-pro", vec![Message::user("Hi")])
+            .with_thinking_budget(2048);
+        let body = serde_json::to_value(provider.to_gemini_request(request)).unwrap();
+        assert_eq!(body["generationConfig"]["thinkingConfig"]["thinkingBudget"], 2048);
+        assert_eq!(body["generationConfig"]["thinkingConfig"]["includeThoughts"], true);
+
+        let response: GeminiResponse = serde_json::from_str(
+            r#"{"candidates": [{
+                "content": {"role": "model", "parts": [
+                    {"text": "Greeting, reply briefly", "thought": true},
+                    {"text": "Hello"}
+                ]},
+                "finishReason": "STOP"
+            }]}"#,
+        )
+        .unwrap();
+        let response = provider.from_gemini_response(response, "gemini-2.5-pro");
+        assert!(matches!(&response.content[0], ContentBlock::Thinking { text, .. } if text == "Greeting, reply briefly"));
+        assert!(matches!(&response.content[1], ContentBlock::Text { text } if text == "Hello"));
+    }
+
+    #[tokio::test]
+    async fn test_complete_function_call_response() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/models/gemini-2.5-flash:generateContent")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "candidates": [{
+                        "content": {"role": "model", "parts": [
+                            {"functionCall": {"name": "read_file", "args": {"path": "Cargo.toml"}}}
+                        ]},
+                        "finishReason": "STOP"
+                    }],
+                    "usageMetadata": {"promptTokenCount": 20, "candidatesTokenCount": 8}
+                }"#,
+            )
+            .create_async()
+            .await;
+
+        let provider = test_provider(&server.url());
+        let response = provider
+            .complete(LLMRequest::new("gemini-2.5-flash", vec![Message::user("read it")]))
+            .await
+            .unwrap();
+
+        assert_eq!(response.stop_reason, Some(StopReason::ToolUse));
+        match &response.content[0] {
+            ContentBlock::ToolUse {
+                id, name, input, ..
+            } => {
+                assert!(id.starts_with("call_"));
+                assert_eq!(name, "read_file");
+                assert_eq!(input["path"], "Cargo.toml");
+            }
+            other => panic!("expected ToolUse, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_function_call_thought_signature_round_trip() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/models/gemini-2.5-pro:generateContent")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "candidates": [{
+                        "content": {"role": "model", "parts": [
+                            {"functionCall": {"name": "ls", "args": {"path": "."}}, "thoughtSignature": "c2lnLTE="}
+                        ]},
+                        "finishReason": "STOP"
+                    }]
+                }"#,
+            )
+            .create_async()
+            .await;
+
+        let provider = test_provider(&server.url());
+        let response = provider
+            .complete(LLMRequest::new(
+                "gemini-2.5-pro",
+                vec![Message::user("list files")],
+            ))
+            .await
+            .unwrap();
+        assert!(matches!(
+            &response.content[0],
+            ContentBlock::ToolUse { signature: Some(s), .. } if s == "c2lnLTE="
+        ));
+
+        // Replaying the tool loop sends the signature back on the same functionCall part
+        let request = LLMRequest::new(
+            "gemini-2.5-pro",
+            vec![
+                Message::user("list files"),
+                Message {
+                    role: Role::Assistant,
+                    content: response.content,
+                },
+            ],
+        );
+        let body = serde_json::to_value(provider.to_gemini_request(request)).unwrap();
+        let part = &body["contents"][1]["parts"][0];
+        assert_eq!(part["functionCall"]["name"], "ls");
+        assert_eq!(part["thoughtSignature"], "c2lnLTE=");
+    }
+
+    #[tokio::test]
+    async fn test_stream_maps_events() {
+        let mut server = mockito::Server::new_async().await;
+        let sse = concat!(
+            "data: {\"candidates\":[{\"content\":{\"role\":\"model\",\"parts\":[{\"text\":\"Hel\"}]}}],\"modelVersion\":\"gemini-2.5-flash\"}\r\n\r\n",
+            "data: {\"candidates\":[{\"content\":{\"role\":\"model\",\"parts\":[{\"text\":\"lo\"}]}}]}\r\n\r\n",
+            "data: {\"candidates\":[{\"content\":{\"role\":\"model\",\"parts\":[{\"functionCall\":{\"name\":\"ls\",\"args\":{\"path\":\".\"}},\"thoughtSignature\":\"c2ln\"}]},\"finishReason\":\"STOP\"}],\"usageMetadata\":{\"promptTokenCount\":7,\"candidatesTokenCount\":3}}\r\n\r\n",
+        );
+        let mock = server
+            .mock("POST", "/models/gemini-2.5-flash:streamGenerateContent")
+            .match_query(mockito::Matcher::UrlEncoded("alt".into(), "sse".into()))
+            .with_status(200)
+            .with_header("content-type", "text/event-stream")
+            .with_body(sse)
+            .create_async()
+            .await;
+
+        let provider = test_provider(&server.url());
+        let mut stream = provider
+            .stream(LLMRequest::new("gemini-2.5-flash", vec![Message::user("Hi")]).with_streaming())
+            .await
+            .unwrap();
+
+        let mut text = String::new();
+        let mut tool_name = None;
+        let mut tool_signature = None;
+        let mut stop_reason = None;
+        let mut usage = None;
+        let mut saw_stop = false;
+        while let Some(event) = stream.next().await {
+            match event.unwrap() {
+                StreamEvent::ContentBlockDelta {
+                    delta: ContentDelta::TextDelta { text: t },
+                    ..
+                } => text.push_str(&t),
+                StreamEvent::ContentBlockStart {
+                    content_block:
+                        ContentBlock::ToolUse {
+                            name, signature, ..
+                        },
+                    ..
+                } => {
+                    tool_name = Some(name);
+                    tool_signature = signature;
+                }
+                StreamEvent::MessageDelta { delta, usage: u } => {
+                    stop_reason = delta.stop_reason;
+                    usage = Some(u);
+                }
+                StreamEvent::MessageStop => saw_stop = true,
+                _ => {}
+            }
+        }
+
+        mock.assert_async().await;
+        assert_eq!(text, "Hello");
+        assert_eq!(tool_name.as_deref(), Some("ls"));
+        assert_eq!(tool_signature.as_deref(), Some("c2ln"));
+        assert_eq!(stop_reason, Some(StopReason::ToolUse));
+        assert_eq!(usage.map(|u| u.input_tokens), Some(7));
+        assert!(saw_stop);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_maps_to_rate_limit_error() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/models/gemini-2.5-flash:generateContent")
+            .with_status(429)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"error": {"code": 429, "message": "Quota exceeded", "status": "RESOURCE_EXHAUSTED"}}"#)
+            .expect_at_least(1)
+            .create_async()
+            .await;
+
+        let provider = test_provider(&server.url());
+        let err = provider
+            .complete(LLMRequest::new("gemini-2.5-flash", vec![Message::user("Hi")]))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, ProviderError::RateLimitExceeded(ref m) if m.contains("Quota exceeded")));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_models_filters_generate_content() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/models")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"models": [
+                    {"name": "models/gemini-2.5-pro", "supportedGenerationMethods": ["generateContent", "countTokens"]},
+                    {"name": "models/text-embedding-004", "supportedGenerationMethods": ["embedContent"]},
+                    {"name": "models/gemini-2.0-flash", "supportedGenerationMethods": ["generateContent"]}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let provider = test_provider(&server.url());
+        let models = provider.fetch_models().await;
+        assert_eq!(models, vec!["gemini-2.0-flash".to_string(), "gemini-2.5-pro".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_models_falls_back_on_error() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/models")
+            .match_query(mockito::Matcher::Any)
+            .with_status(500)
+            .create_async()
+            .await;
+
+        let provider = test_provider(&server.url());
+        assert_eq!(provider.fetch_models().await, provider.supported_models());
+    }
+}
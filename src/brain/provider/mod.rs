@@ -5,6 +5,7 @@
 pub mod error;
 pub mod placeholder;
 pub mod retry;
+pub mod think_tags;
 #[allow(clippy::module_inception)]
 mod r#trait;
 pub mod types;
@@ -17,9 +18,17 @@ pub use types::*;
 
 // Provider implementations
 pub mod anthropic;
+pub mod bedrock;
 pub mod factory;
 pub mod custom_openai_compatible;
+pub mod failover;
+pub mod gemini;
+pub mod vertex;
 
 pub use anthropic::AnthropicProvider;
+pub use bedrock::BedrockProvider;
 pub use custom_openai_compatible::OpenAIProvider;
-pub use factory::create_provider;
+pub use factory::{create_named_provider, create_provider};
+pub use failover::{FailoverProvider, ProviderSwitch};
+pub use gemini::GeminiProvider;
+pub use vertex::VertexProvider;
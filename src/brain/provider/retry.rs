@@ -215,6 +215,23 @@ pub fn extract_retry_after(error: &ProviderError) -> Option<Duration> {
     }
 }
 
+/// Whether a failover chain should move on to its next provider after this error.
+///
+/// Anything worth retrying against the same provider (5xx, 429, timeouts, connection
+/// errors) is also worth failing over on — by the time a failover chain sees the error,
+/// the provider's own `retry_with_backoff` attempts are already spent. Overload errors
+/// reported inside a stream count too.
+pub fn should_failover(error: &ProviderError) -> bool {
+    match error {
+        ProviderError::ApiError {
+            error_type: Some(t),
+            ..
+        } if t == "overloaded_error" => true,
+        ProviderError::StreamError(msg) => msg.to_lowercase().contains("overloaded"),
+        _ => error.is_retryable(),
+    }
+}
+
 /// Parse retry seconds from error message
 fn parse_retry_seconds(msg: &str) -> Option<u64> {
     // Try to extract numbers followed by "second" or "s"
@@ -415,4 +432,28 @@ mod tests {
         assert_eq!(parse_retry_seconds("retry in 5"), Some(5));
         assert_eq!(parse_retry_seconds("no numbers here"), None);
     }
+
+    #[test]
+    fn test_should_failover() {
+        assert!(should_failover(&ProviderError::RateLimitExceeded("slow down".to_string())));
+        assert!(should_failover(&ProviderError::ApiError {
+            status: 503,
+            message: "Service Unavailable".to_string(),
+            error_type: None,
+        }));
+        assert!(should_failover(&ProviderError::ApiError {
+            status: 400,
+            message: "Overloaded".to_string(),
+            error_type: Some("overloaded_error".to_string()),
+        }));
+        assert!(should_failover(&ProviderError::StreamError("Provider overloaded".to_string())));
+
+        assert!(!should_failover(&ProviderError::InvalidApiKey));
+        assert!(!should_failover(&ProviderError::ContextLengthExceeded(250_000)));
+        assert!(!should_failover(&ProviderError::ApiError {
+            status: 400,
+            message: "Bad Request".to_string(),
+            error_type: Some("invalid_request_error".to_string()),
+        }));
+    }
 }
@@ -0,0 +1,157 @@
+//! `<think>` Tag Splitting
+//!
+//! DeepSeek-R1, Qwen3/QwQ and similar reasoning models served through
+//! OpenAI-compatible endpoints inline their reasoning in the answer text as
+//! `<think>...</think>`. This module separates it out so it becomes a
+//! `ContentBlock::Thinking` instead of leaking into the reply.
+//!
+//! The splitter works on streamed fragments: a tag may arrive split across
+//! chunks, so any trailing text that could be the start of a tag is held back
+//! until the next chunk (or `finish`) decides it.
+
+const OPEN_TAG: &str = "<think>";
+const CLOSE_TAG: &str = "</think>";
+
+/// A piece of model output, classified by whether it was inside `<think>` tags
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ThinkSegment {
+    Thinking(String),
+    Text(String),
+}
+
+/// Incremental `<think>` tag splitter
+#[derive(Debug, Default)]
+pub struct ThinkTagSplitter {
+    in_think: bool,
+    pending: String,
+    /// Drop the whitespace models put between `</think>` and the answer
+    trim_next_text: bool,
+}
+
+impl ThinkTagSplitter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a chunk of output and get back the segments that are now certain
+    pub fn push(&mut self, chunk: &str) -> Vec<ThinkSegment> {
+        self.pending.push_str(chunk);
+        let mut segments = Vec::new();
+
+        loop {
+            let tag = if self.in_think { CLOSE_TAG } else { OPEN_TAG };
+            if let Some(pos) = self.pending.find(tag) {
+                let before: String = self.pending.drain(..pos).collect();
+                self.pending.drain(..tag.len());
+                self.emit(before, &mut segments);
+                self.in_think = !self.in_think;
+                self.trim_next_text = !self.in_think;
+                continue;
+            }
+
+            // Hold back a trailing partial tag (tags are ASCII, so this is a char boundary)
+            let keep = (1..tag.len())
+                .rev()
+                .find(|&k| self.pending.ends_with(&tag[..k]))
+                .unwrap_or(0);
+            let ready: String = self.pending.drain(..self.pending.len() - keep).collect();
+            self.emit(ready, &mut segments);
+            break;
+        }
+
+        segments
+    }
+
+    /// Flush whatever is still held back at the end of the response
+    pub fn finish(&mut self) -> Vec<ThinkSegment> {
+        let rest = std::mem::take(&mut self.pending);
+        let mut segments = Vec::new();
+        self.emit(rest, &mut segments);
+        segments
+    }
+
+    fn emit(&mut self, text: String, segments: &mut Vec<ThinkSegment>) {
+        if self.in_think {
+            if !text.is_empty() {
+                segments.push(ThinkSegment::Thinking(text));
+            }
+            return;
+        }
+
+        let text = if self.trim_next_text {
+            let trimmed = text.trim_start();
+            if trimmed.is_empty() {
+                return;
+            }
+            self.trim_next_text = false;
+            trimmed.to_string()
+        } else {
+            text
+        };
+        if !text.is_empty() {
+            segments.push(ThinkSegment::Text(text));
+        }
+    }
+}
+
+/// Split a complete response into its reasoning and answer parts
+pub fn split_think_tags(content: &str) -> (Option<String>, String) {
+    let mut splitter = ThinkTagSplitter::new();
+    let mut segments = splitter.push(content);
+    segments.extend(splitter.finish());
+
+    let mut thinking = String::new();
+    let mut text = String::new();
+    for segment in segments {
+        match segment {
+            ThinkSegment::Thinking(t) => thinking.push_str(&t),
+            ThinkSegment::Text(t) => text.push_str(&t),
+        }
+    }
+
+    let thinking = thinking.trim();
+    ((!thinking.is_empty()).then(|| thinking.to_string()), text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_complete_response() {
+        let (thinking, text) = split_think_tags("<think>\nThe user wants 4.\n</think>\n\n2 + 2 = 4");
+        assert_eq!(thinking.as_deref(), Some("The user wants 4."));
+        assert_eq!(text, "2 + 2 = 4");
+
+        let (thinking, text) = split_think_tags("No reasoning here");
+        assert!(thinking.is_none());
+        assert_eq!(text, "No reasoning here");
+    }
+
+    #[test]
+    fn test_tags_split_across_chunks() {
+        let mut splitter = ThinkTagSplitter::new();
+        let mut segments = Vec::new();
+        for chunk in ["<thi", "nk>hmm", " ok</th", "ink>", "\n", "Answer <", "b>"] {
+            segments.extend(splitter.push(chunk));
+        }
+        segments.extend(splitter.finish());
+
+        assert_eq!(
+            segments,
+            vec![
+                ThinkSegment::Thinking("hmm".to_string()),
+                ThinkSegment::Thinking(" ok".to_string()),
+                ThinkSegment::Text("Answer ".to_string()),
+                ThinkSegment::Text("<b>".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unclosed_partial_tag_flushed_on_finish() {
+        let mut splitter = ThinkTagSplitter::new();
+        assert_eq!(splitter.push("a <th"), vec![ThinkSegment::Text("a ".to_string())]);
+        assert_eq!(splitter.finish(), vec![ThinkSegment::Text("<th".to_string())]);
+    }
+}
@@ -3,7 +3,8 @@
 //! Defines the interface that all LLM providers must implement.
 
 use super::error::Result;
-use super::types::{LLMRequest, LLMResponse, StreamEvent};
+use super::failover::ProviderSwitch;
+use super::types::{LLMRequest, LLMResponse, StreamEvent, TokenUsage};
 use async_trait::async_trait;
 use futures::Stream;
 use std::pin::Pin;
@@ -68,6 +69,19 @@ pub trait Provider: Send + Sync {
 
     /// Calculate cost for token usage (in USD)
     fn calculate_cost(&self, model: &str, input_tokens: u32, output_tokens: u32) -> f64;
+
+    /// Calculate cost for a full usage record (in USD).
+    /// Providers with prompt caching override this to bill cached tokens at their own
+    /// rates; the default charges every prompt token at the input rate.
+    fn calculate_usage_cost(&self, model: &str, usage: &TokenUsage) -> f64 {
+        self.calculate_cost(model, usage.prompt_tokens(), usage.output_tokens)
+    }
+
+    /// Drain backend switches since the last call.
+    /// Only failover chains switch; single-backend providers report nothing.
+    fn take_switches(&self) -> Vec<ProviderSwitch> {
+        Vec::new()
+    }
 }
 
 /// Provider capabilities
@@ -60,11 +60,14 @@ pub enum ContentBlock {
     Text { text: String },
     /// Image content (base64 or URL)
     Image { source: ImageSource },
-    /// Tool use request from assistant
+    /// Tool use request from assistant. Gemini thinking models attach a
+    /// `thoughtSignature` to function calls that must be sent back with them.
     ToolUse {
         id: String,
         name: String,
         input: serde_json::Value,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        signature: Option<String>,
     },
     /// Tool result from user
     ToolResult {
@@ -73,6 +76,18 @@ pub enum ContentBlock {
         #[serde(skip_serializing_if = "Option::is_none")]
         is_error: Option<bool>,
     },
+    /// Model reasoning: Anthropic extended thinking, OpenAI-compatible
+    /// `reasoning_content`, or `<think>` tags. Anthropic's `signature` must be
+    /// sent back unchanged when the block is replayed during a tool loop.
+    Thinking {
+        #[serde(rename = "thinking")]
+        text: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        signature: Option<String>,
+    },
+    /// Anthropic thinking that was flagged and encrypted. Like a signed
+    /// `Thinking` block, it is sent back unchanged during a tool loop.
+    RedactedThinking { data: String },
 }
 
 /// Image source for image content blocks
@@ -110,6 +125,9 @@ pub struct LLMRequest {
     /// Additional metadata
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, String>>,
+    /// Extended thinking budget in tokens (providers without reasoning ignore it)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thinking_budget: Option<u32>,
 }
 
 impl LLMRequest {
@@ -124,6 +142,7 @@ impl LLMRequest {
             max_tokens: None,
             stream: false,
             metadata: None,
+            thinking_budget: None,
         }
     }
 
@@ -156,6 +175,12 @@ impl LLMRequest {
         self.stream = true;
         self
     }
+
+    /// Enable extended thinking with a token budget
+    pub fn with_thinking_budget(mut self, budget: u32) -> Self {
+        self.thinking_budget = Some(budget);
+        self
+    }
 }
 
 /// Tool definition for LLM
@@ -199,18 +224,56 @@ pub enum StopReason {
 }
 
 /// Token usage information
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+///
+/// With prompt caching, `input_tokens` only counts the uncached part of the prompt;
+/// cached tokens are reported separately because they're billed at different rates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TokenUsage {
     /// Input tokens
+    #[serde(default)]
     pub input_tokens: u32,
     /// Output tokens
+    #[serde(default)]
     pub output_tokens: u32,
+    /// Prompt tokens written to the cache on this request
+    #[serde(default)]
+    pub cache_creation_input_tokens: u32,
+    /// Prompt tokens served from the cache
+    #[serde(default)]
+    pub cache_read_input_tokens: u32,
 }
 
 impl TokenUsage {
+    /// Full prompt size, cached or not — the real context window usage
+    pub fn prompt_tokens(&self) -> u32 {
+        self.input_tokens + self.cache_creation_input_tokens + self.cache_read_input_tokens
+    }
+
     /// Total tokens used
     pub fn total(&self) -> u32 {
-        self.input_tokens + self.output_tokens
+        self.prompt_tokens() + self.output_tokens
+    }
+}
+
+impl std::ops::AddAssign for TokenUsage {
+    fn add_assign(&mut self, other: Self) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_creation_input_tokens += other.cache_creation_input_tokens;
+        self.cache_read_input_tokens += other.cache_read_input_tokens;
+    }
+}
+
+impl std::ops::SubAssign for TokenUsage {
+    fn sub_assign(&mut self, other: Self) {
+        self.input_tokens = self.input_tokens.saturating_sub(other.input_tokens);
+        self.output_tokens = self.output_tokens.saturating_sub(other.output_tokens);
+        self.cache_creation_input_tokens = self
+            .cache_creation_input_tokens
+            .saturating_sub(other.cache_creation_input_tokens);
+        self.cache_read_input_tokens = self
+            .cache_read_input_tokens
+            .saturating_sub(other.cache_read_input_tokens);
     }
 }
 
@@ -259,6 +322,10 @@ pub enum ContentDelta {
     TextDelta { text: String },
     /// Tool input delta (JSON)
     InputJsonDelta { partial_json: String },
+    /// Thinking text delta
+    ThinkingDelta { thinking: String },
+    /// Signature for the current thinking block (Anthropic sends it last)
+    SignatureDelta { signature: String },
 }
 
 /// Message delta for final updates
@@ -297,12 +364,59 @@ mod tests {
         assert!(request.stream);
     }
 
+    #[test]
+    fn test_thinking_block_wire_format() {
+        let block: ContentBlock = serde_json::from_str(
+            r#"{"type": "thinking", "thinking": "Let me check", "signature": "sig=="}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            &block,
+            ContentBlock::Thinking { text, signature: Some(sig) } if text == "Let me check" && sig == "sig=="
+        ));
+        let json = serde_json::to_value(&block).unwrap();
+        assert_eq!(json["thinking"], "Let me check");
+
+        let block: ContentBlock =
+            serde_json::from_str(r#"{"type": "redacted_thinking", "data": "EmwKAhgB"}"#).unwrap();
+        assert!(matches!(&block, ContentBlock::RedactedThinking { data } if data == "EmwKAhgB"));
+        let json = serde_json::to_value(&block).unwrap();
+        assert_eq!(json["type"], "redacted_thinking");
+        assert_eq!(json["data"], "EmwKAhgB");
+    }
+
     #[test]
     fn test_token_usage() {
         let usage = TokenUsage {
             input_tokens: 100,
             output_tokens: 200,
+            ..Default::default()
         };
         assert_eq!(usage.total(), 300);
     }
+
+    #[test]
+    fn test_token_usage_with_cache() {
+        let mut usage = TokenUsage {
+            input_tokens: 50,
+            output_tokens: 20,
+            cache_creation_input_tokens: 1000,
+            cache_read_input_tokens: 4000,
+        };
+        assert_eq!(usage.prompt_tokens(), 5050);
+        assert_eq!(usage.total(), 5070);
+
+        usage += usage;
+        assert_eq!(usage.cache_read_input_tokens, 8000);
+        usage -= TokenUsage { input_tokens: 500, ..Default::default() };
+        assert_eq!(usage.input_tokens, 0);
+    }
+
+    #[test]
+    fn test_token_usage_partial_json() {
+        // Anthropic's message_delta only carries output_tokens
+        let usage: TokenUsage = serde_json::from_str(r#"{"output_tokens": 15}"#).unwrap();
+        assert_eq!(usage.output_tokens, 15);
+        assert_eq!(usage.input_tokens, 0);
+    }
 }
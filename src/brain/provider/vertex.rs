@@ -0,0 +1,596 @@
+//! Google Vertex AI Provider Implementation
+//!
+//! Runs Claude models published on Vertex AI Model Garden through the
+//! `rawPredict` / `streamRawPredict` endpoints. Bodies are the Anthropic Messages
+//! format and streaming is plain SSE, so the mapping in `anthropic.rs` is reused;
+//! this module adds service-account authentication (a signed RS256 JWT exchanged
+//! for an OAuth access token, cached until shortly before it expires).
+//!
+//! ## Credentials
+//! `api_key` in `[providers.vertex]` is either the service-account JSON itself or a
+//! path to the key file. When unset, `GOOGLE_APPLICATION_CREDENTIALS` is used.
+//!
+//! ## Supported Models
+//! - claude-opus-4-6
+//! - claude-sonnet-4-5@20250929
+//! - claude-haiku-4-5@20251001
+//! - claude-3-5-sonnet-v2@20241022
+//! - claude-3-5-haiku@20241022
+
+use super::anthropic::{
+    AnthropicPlatformRequest, AnthropicResponse, drain_sse_events, error_from_response,
+    to_platform_request,
+};
+use super::error::{ProviderError, Result};
+use super::r#trait::{Provider, ProviderStream};
+use super::types::*;
+use async_trait::async_trait;
+use base64::Engine;
+use futures::stream::StreamExt;
+use openssl::hash::MessageDigest;
+use openssl::pkey::PKey;
+use openssl::sign::Signer;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+const VERTEX_ANTHROPIC_VERSION: &str = "vertex-2023-10-16";
+const CLOUD_PLATFORM_SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
+const GOOGLE_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const DEFAULT_REGION: &str = "us-east5";
+/// Refresh the access token this long before Google says it expires
+const TOKEN_REFRESH_MARGIN_SECS: i64 = 60;
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(120);
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Google service-account key (the JSON downloaded from the Cloud console)
+#[derive(Clone, Deserialize)]
+pub struct ServiceAccountKey {
+    pub project_id: String,
+    pub client_email: String,
+    pub private_key: String,
+    #[serde(default)]
+    pub private_key_id: Option<String>,
+    #[serde(default = "default_token_uri")]
+    pub token_uri: String,
+}
+
+fn default_token_uri() -> String {
+    GOOGLE_TOKEN_URL.to_string()
+}
+
+impl ServiceAccountKey {
+    /// Parse from inline JSON or load from a key file path
+    pub fn load(value: &str) -> anyhow::Result<Self> {
+        let value = value.trim();
+        let json = if value.starts_with('{') {
+            value.to_string()
+        } else {
+            let path = crate::config::expand_tilde(std::path::Path::new(value));
+            std::fs::read_to_string(&path).map_err(|e| {
+                anyhow::anyhow!("Failed to read service account key {}: {}", path.display(), e)
+            })?
+        };
+        serde_json::from_str(&json)
+            .map_err(|e| anyhow::anyhow!("Invalid service account key: {}", e))
+    }
+
+    /// Load from `GOOGLE_APPLICATION_CREDENTIALS`
+    pub fn from_env() -> Option<Self> {
+        let path = std::env::var("GOOGLE_APPLICATION_CREDENTIALS").ok()?;
+        Self::load(&path).ok()
+    }
+}
+
+struct CachedToken {
+    access_token: String,
+    expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Google Vertex AI provider for Claude models
+#[derive(Clone)]
+pub struct VertexProvider {
+    service_account: ServiceAccountKey,
+    region: String,
+    base_url: String,
+    client: Client,
+    token: Arc<Mutex<Option<CachedToken>>>,
+    custom_default_model: Option<String>,
+}
+
+impl VertexProvider {
+    /// Create a new Vertex provider for a region (e.g. `us-east5`, `europe-west1`, `global`)
+    pub fn new(service_account: ServiceAccountKey, region: String) -> Self {
+        let base_url = if region == "global" {
+            "https://aiplatform.googleapis.com".to_string()
+        } else {
+            format!("https://{}-aiplatform.googleapis.com", region)
+        };
+        Self::with_base_url(service_account, region, base_url)
+    }
+
+    /// Create with a custom base URL (Private Service Connect, tests)
+    pub fn with_base_url(service_account: ServiceAccountKey, region: String, base_url: String) -> Self {
+        let client = Client::builder()
+            .timeout(DEFAULT_TIMEOUT)
+            .connect_timeout(DEFAULT_CONNECT_TIMEOUT)
+            .pool_idle_timeout(DEFAULT_POOL_IDLE_TIMEOUT)
+            .pool_max_idle_per_host(2)
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            service_account,
+            region,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            client,
+            token: Arc::new(Mutex::new(None)),
+            custom_default_model: None,
+        }
+    }
+
+    /// Set custom default model
+    pub fn with_default_model(mut self, model: String) -> Self {
+        self.custom_default_model = Some(model);
+        self
+    }
+
+    /// Region for a `base_url` override: `<region>-aiplatform.googleapis.com` hosts
+    /// carry it, anything else falls back to `CLOUD_ML_REGION`.
+    pub fn region_from_base_url(base_url: Option<&str>) -> String {
+        base_url
+            .and_then(|url| reqwest::Url::parse(url).ok())
+            .and_then(|url| {
+                url.host_str().and_then(|host| {
+                    host.strip_suffix("-aiplatform.googleapis.com")
+                        .map(str::to_string)
+                })
+            })
+            .or_else(|| std::env::var("CLOUD_ML_REGION").ok().filter(|s| !s.is_empty()))
+            .unwrap_or_else(|| DEFAULT_REGION.to_string())
+    }
+
+    /// `rawPredict` / `streamRawPredict` endpoint for a model
+    fn model_url(&self, model: &str, method: &str) -> String {
+        format!(
+            "{}/v1/projects/{}/locations/{}/publishers/anthropic/models/{}:{}",
+            self.base_url, self.service_account.project_id, self.region, model, method
+        )
+    }
+
+    /// Return a valid access token, exchanging a fresh JWT when the cached one is stale
+    async fn access_token(&self) -> Result<String> {
+        let mut cached = self.token.lock().await;
+        let now = chrono::Utc::now();
+        if let Some(token) = cached.as_ref()
+            && token.expires_at > now
+        {
+            return Ok(token.access_token.clone());
+        }
+
+        let assertion = sign_jwt(&self.service_account, now.timestamp())?;
+        let response = self
+            .client
+            .post(&self.service_account.token_uri)
+            .form(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+                ("assertion", assertion.as_str()),
+            ])
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let body = response.text().await.unwrap_or_default();
+            tracing::error!("Vertex token exchange failed ({}): {}", status, body);
+            return Err(if status == 400 || status == 401 {
+                ProviderError::InvalidApiKey
+            } else {
+                ProviderError::ApiError {
+                    status,
+                    message: format!("Token exchange failed: {}", body),
+                    error_type: None,
+                }
+            });
+        }
+
+        let token: TokenResponse = response.json().await?;
+        let lifetime = (token.expires_in - TOKEN_REFRESH_MARGIN_SECS).max(0);
+        *cached = Some(CachedToken {
+            access_token: token.access_token.clone(),
+            expires_at: now + chrono::Duration::seconds(lifetime),
+        });
+        tracing::debug!("Vertex access token refreshed (valid {}s)", token.expires_in);
+
+        Ok(token.access_token)
+    }
+
+    /// POST an Anthropic-format body to a model endpoint
+    async fn post(&self, url: &str, body: &AnthropicPlatformRequest) -> Result<reqwest::Response> {
+        let token = self.access_token().await?;
+        let response = self
+            .client
+            .post(url)
+            .bearer_auth(token)
+            .json(body)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            if response.status().as_u16() == 401 {
+                // Token revoked or clock skew — force a fresh exchange on retry
+                *self.token.lock().await = None;
+            }
+            return Err(error_from_response(response).await);
+        }
+
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl Provider for VertexProvider {
+    async fn complete(&self, request: LLMRequest) -> Result<LLMResponse> {
+        use super::retry::{retry_with_backoff, RetryConfig};
+
+        let model = request.model.clone();
+        tracing::info!(
+            "Vertex API request: model={}, region={}, messages={}",
+            model,
+            self.region,
+            request.messages.len()
+        );
+
+        let body = to_platform_request(request, VERTEX_ANTHROPIC_VERSION, false);
+        let url = self.model_url(&model, "rawPredict");
+        let retry_config = RetryConfig::default();
+
+        let result = retry_with_backoff(
+            || async {
+                let response = self.post(&url, &body).await?;
+                let anthropic_response: AnthropicResponse = response.json().await?;
+                let llm_response: LLMResponse = anthropic_response.into();
+
+                tracing::info!(
+                    "Vertex API response: input_tokens={}, output_tokens={}, stop_reason={:?}",
+                    llm_response.usage.input_tokens,
+                    llm_response.usage.output_tokens,
+                    llm_response.stop_reason
+                );
+
+                Ok(llm_response)
+            },
+            &retry_config,
+        )
+        .await;
+
+        if let Err(ref e) = result {
+            tracing::error!("Vertex API request failed: {}", e);
+        }
+
+        result
+    }
+
+    async fn stream(&self, request: LLMRequest) -> Result<ProviderStream> {
+        use super::retry::{retry_with_backoff, RetryConfig};
+
+        let model = request.model.clone();
+        tracing::info!(
+            "Vertex streaming request: model={}, region={}, messages={}",
+            model,
+            self.region,
+            request.messages.len()
+        );
+
+        let body = to_platform_request(request, VERTEX_ANTHROPIC_VERSION, true);
+        let url = self.model_url(&model, "streamRawPredict");
+        let retry_config = RetryConfig::default();
+
+        let response =
+            retry_with_backoff(|| async { self.post(&url, &body).await }, &retry_config).await?;
+
+        // Same SSE framing as the Anthropic API
+        let byte_stream = response.bytes_stream();
+        let buffer = Arc::new(std::sync::Mutex::new(String::new()));
+
+        let event_stream = byte_stream
+            .map(
+                move |chunk_result| -> Vec<std::result::Result<StreamEvent, ProviderError>> {
+                    match chunk_result {
+                        Err(e) => vec![Err(ProviderError::StreamError(e.to_string()))],
+                        Ok(chunk) => {
+                            let mut buf = buffer.lock().expect("SSE buffer lock poisoned");
+                            buf.push_str(&String::from_utf8_lossy(&chunk));
+
+                            let events = drain_sse_events(&mut buf);
+
+                            if events.is_empty() {
+                                vec![Ok(StreamEvent::Ping)]
+                            } else {
+                                events
+                            }
+                        }
+                    }
+                },
+            )
+            .flat_map(futures::stream::iter);
+
+        Ok(Box::pin(event_stream))
+    }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    fn supports_tools(&self) -> bool {
+        true
+    }
+
+    fn supports_vision(&self) -> bool {
+        true
+    }
+
+    fn name(&self) -> &str {
+        "vertex"
+    }
+
+    fn default_model(&self) -> &str {
+        self.custom_default_model
+            .as_deref()
+            .unwrap_or("claude-sonnet-4-5@20250929")
+    }
+
+    fn supported_models(&self) -> Vec<String> {
+        vec![
+            "claude-opus-4-6".to_string(),
+            "claude-sonnet-4-5@20250929".to_string(),
+            "claude-haiku-4-5@20251001".to_string(),
+            "claude-3-5-sonnet-v2@20241022".to_string(),
+            "claude-3-5-haiku@20241022".to_string(),
+        ]
+    }
+
+    fn context_window(&self, model: &str) -> Option<u32> {
+        if model.starts_with("claude-") {
+            Some(200_000)
+        } else {
+            None
+        }
+    }
+
+    fn calculate_cost(&self, model: &str, input_tokens: u32, output_tokens: u32) -> f64 {
+        crate::pricing::PricingConfig::load().calculate_cost(model, input_tokens, output_tokens)
+    }
+
+    fn calculate_usage_cost(&self, model: &str, usage: &TokenUsage) -> f64 {
+        crate::pricing::PricingConfig::load().calculate_cost_with_cache(
+            model,
+            usage.input_tokens,
+            usage.output_tokens,
+            usage.cache_creation_input_tokens,
+            usage.cache_read_input_tokens,
+        )
+    }
+}
+
+/// Build and sign (RS256) the JWT assertion for the OAuth jwt-bearer grant
+fn sign_jwt(key: &ServiceAccountKey, issued_at: i64) -> Result<String> {
+    let b64 = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+
+    let header = JwtHeader {
+        alg: "RS256",
+        typ: "JWT",
+        kid: key.private_key_id.as_deref(),
+    };
+    let claims = JwtClaims {
+        iss: &key.client_email,
+        scope: CLOUD_PLATFORM_SCOPE,
+        aud: &key.token_uri,
+        iat: issued_at,
+        exp: issued_at + 3600,
+    };
+
+    let signing_input = format!(
+        "{}.{}",
+        b64.encode(serde_json::to_vec(&header)?),
+        b64.encode(serde_json::to_vec(&claims)?)
+    );
+
+    let sign = || -> std::result::Result<Vec<u8>, openssl::error::ErrorStack> {
+        let pkey = PKey::private_key_from_pem(key.private_key.as_bytes())?;
+        let mut signer = Signer::new(MessageDigest::sha256(), &pkey)?;
+        signer.update(signing_input.as_bytes())?;
+        signer.sign_to_vec()
+    };
+    let signature = sign().map_err(|e| {
+        tracing::error!("Failed to sign Vertex JWT: {}", e);
+        ProviderError::InvalidApiKey
+    })?;
+
+    Ok(format!("{}.{}", signing_input, b64.encode(signature)))
+}
+
+// ============================================================================
+// Vertex / Google OAuth Types
+// ============================================================================
+
+#[derive(Serialize)]
+struct JwtHeader<'a> {
+    alg: &'a str,
+    typ: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kid: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct JwtClaims<'a> {
+    iss: &'a str,
+    scope: &'a str,
+    aud: &'a str,
+    iat: i64,
+    exp: i64,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default = "default_expires_in")]
+    expires_in: i64,
+}
+
+fn default_expires_in() -> i64 {
+    3600
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Throwaway RSA key so tests can sign JWTs without real credentials
+    fn test_key(token_uri: String) -> ServiceAccountKey {
+        let rsa = openssl::rsa::Rsa::generate(2048).unwrap();
+        let pem = PKey::from_rsa(rsa).unwrap().private_key_to_pem_pkcs8().unwrap();
+        ServiceAccountKey {
+            project_id: "test-project".to_string(),
+            client_email: "crab@test-project.iam.gserviceaccount.com".to_string(),
+            private_key: String::from_utf8(pem).unwrap(),
+            private_key_id: Some("key-1".to_string()),
+            token_uri,
+        }
+    }
+
+    #[test]
+    fn test_service_account_inline_json() {
+        let key = ServiceAccountKey::load(
+            r#"{"project_id":"p","client_email":"e@p.iam.gserviceaccount.com","private_key":"pem"}"#,
+        )
+        .unwrap();
+        assert_eq!(key.project_id, "p");
+        assert_eq!(key.token_uri, GOOGLE_TOKEN_URL);
+    }
+
+    #[test]
+    fn test_region_from_base_url() {
+        assert_eq!(
+            VertexProvider::region_from_base_url(Some("https://europe-west1-aiplatform.googleapis.com")),
+            "europe-west1"
+        );
+    }
+
+    #[test]
+    fn test_jwt_is_verifiable() {
+        let key = test_key(GOOGLE_TOKEN_URL.to_string());
+        let jwt = sign_jwt(&key, 1_700_000_000).unwrap();
+        let parts: Vec<&str> = jwt.split('.').collect();
+        assert_eq!(parts.len(), 3);
+
+        let b64 = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        let claims: serde_json::Value =
+            serde_json::from_slice(&b64.decode(parts[1]).unwrap()).unwrap();
+        assert_eq!(claims["iss"], "crab@test-project.iam.gserviceaccount.com");
+        assert_eq!(claims["scope"], CLOUD_PLATFORM_SCOPE);
+        assert_eq!(claims["exp"], 1_700_003_600);
+
+        let pkey = PKey::private_key_from_pem(key.private_key.as_bytes()).unwrap();
+        let mut verifier =
+            openssl::sign::Verifier::new(MessageDigest::sha256(), &pkey).unwrap();
+        verifier
+            .update(format!("{}.{}", parts[0], parts[1]).as_bytes())
+            .unwrap();
+        assert!(verifier.verify(&b64.decode(parts[2]).unwrap()).unwrap());
+    }
+
+    #[test]
+    fn test_model_url() {
+        let provider = VertexProvider::new(test_key(GOOGLE_TOKEN_URL.to_string()), "us-east5".to_string());
+        assert_eq!(
+            provider.model_url("claude-sonnet-4-5@20250929", "rawPredict"),
+            "https://us-east5-aiplatform.googleapis.com/v1/projects/test-project/locations/us-east5/publishers/anthropic/models/claude-sonnet-4-5@20250929:rawPredict"
+        );
+    }
+
+    // --- HTTP tests with mock server ---
+
+    #[tokio::test]
+    async fn test_complete_exchanges_token_once() {
+        let mut server = mockito::Server::new_async().await;
+        let token_mock = server
+            .mock("POST", "/token")
+            .match_body(mockito::Matcher::Regex(
+                "grant_type=urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Ajwt-bearer".to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"access_token": "ya29.test", "expires_in": 3599, "token_type": "Bearer"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let predict_mock = server
+            .mock(
+                "POST",
+                "/v1/projects/test-project/locations/us-east5/publishers/anthropic/models/claude-3-5-haiku@20241022:rawPredict",
+            )
+            .match_header("authorization", "Bearer ya29.test")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"anthropic_version":"vertex-2023-10-16"}"#.to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "id": "msg_vrtx_01",
+                    "type": "message",
+                    "role": "assistant",
+                    "model": "claude-3-5-haiku-20241022",
+                    "content": [{"type": "text", "text": "Hello from Vertex"}],
+                    "stop_reason": "end_turn",
+                    "usage": {"input_tokens": 9, "output_tokens": 4}
+                }"#,
+            )
+            .expect(2)
+            .create_async()
+            .await;
+
+        let provider = VertexProvider::with_base_url(
+            test_key(format!("{}/token", server.url())),
+            "us-east5".to_string(),
+            server.url(),
+        );
+        for _ in 0..2 {
+            let response = provider
+                .complete(LLMRequest::new("claude-3-5-haiku@20241022", vec![Message::user("Hi")]))
+                .await
+                .unwrap();
+            assert_eq!(response.id, "msg_vrtx_01");
+        }
+
+        // Second request reuses the cached token
+        token_mock.assert_async().await;
+        predict_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_token_exchange_failure_is_auth_error() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/token")
+            .with_status(400)
+            .with_body(r#"{"error": "invalid_grant"}"#)
+            .create_async()
+            .await;
+
+        let provider = VertexProvider::with_base_url(
+            test_key(format!("{}/token", server.url())),
+            "us-east5".to_string(),
+            server.url(),
+        );
+        let err = provider
+            .complete(LLMRequest::new("claude-3-5-haiku@20241022", vec![Message::user("Hi")]))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ProviderError::InvalidApiKey));
+    }
+}
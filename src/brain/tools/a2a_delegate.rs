@@ -0,0 +1,476 @@
+//! A2A Delegate Tool
+//!
+//! Hands a task to a remote A2A agent: discovers the peer's Agent Card, picks
+//! a skill, sends the message (`message/send` or `message/stream`) and polls
+//! `tasks/get` until the task finishes. Known peers come from `[[a2a.peers]]`.
+
+use super::error::{Result, ToolError};
+use super::r#trait::{Tool, ToolCapability, ToolExecutionContext, ToolResult};
+use crate::a2a::client::{is_terminal, parts_text, A2aClient, ClientError};
+use crate::a2a::types::{AgentSkill, Message, Part, Role, SendMessageParams, Task, TaskState};
+use crate::config::A2aPeerConfig;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::time::Duration;
+use tokio::time::Instant;
+use uuid::Uuid;
+
+/// Seconds to wait for the remote task unless `timeout_secs` is given
+const DEFAULT_TIMEOUT_SECS: u64 = 300;
+
+/// Longest wait allowed for one delegation
+const MAX_TIMEOUT_SECS: u64 = 3600;
+
+/// Delay between `tasks/get` polls
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Tool for delegating tasks to other A2A agents.
+pub struct A2aDelegateTool {
+    peers: Vec<A2aPeerConfig>,
+    description: String,
+}
+
+impl A2aDelegateTool {
+    pub fn new(peers: Vec<A2aPeerConfig>) -> Self {
+        let mut description = String::from(
+            "Delegate a task to another AI agent over the A2A protocol and return its result. \
+             'peer' is a configured peer name or the agent's base URL. Operation 'send' \
+             (default) sends 'message' and waits for the remote task to finish; 'discover' \
+             shows the peer's Agent Card and skills. 'skill' picks a skill by ID or name; \
+             without it the best match for the message is used. Pass 'context_id' from an \
+             earlier result to continue that conversation, and 'task_id' as well to answer \
+             a task that is waiting for input.",
+        );
+        if peers.is_empty() {
+            description.push_str(" No peers are configured — add [[a2a.peers]] to config.toml.");
+        } else {
+            description.push_str("\n\nConfigured peers:");
+            for peer in &peers {
+                description.push_str(&format!("\n- {}", peer.name));
+                if let Some(ref about) = peer.description {
+                    description.push_str(&format!(": {}", about));
+                }
+            }
+        }
+        Self { peers, description }
+    }
+
+    /// Client for a configured peer name or a URL
+    fn client_for(&self, peer: &str) -> Option<(String, A2aClient)> {
+        if let Some(config) = self.peers.iter().find(|p| p.name.eq_ignore_ascii_case(peer)) {
+            return Some((
+                config.name.clone(),
+                A2aClient::new(&config.url, config.token.clone()),
+            ));
+        }
+        (peer.starts_with("http://") || peer.starts_with("https://"))
+            .then(|| (peer.to_string(), A2aClient::new(peer, None)))
+    }
+}
+
+fn str_field<'a>(input: &'a Value, field: &str) -> Option<&'a str> {
+    input
+        .get(field)
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+fn client_error(e: ClientError) -> ToolError {
+    ToolError::Execution(e.to_string())
+}
+
+/// The skill named by `requested` (ID or name), or else the skill sharing the
+/// most words with the message. `Err` if `requested` matches no skill.
+fn pick_skill<'a>(
+    skills: &'a [AgentSkill],
+    requested: Option<&str>,
+    message: &str,
+) -> std::result::Result<Option<&'a AgentSkill>, String> {
+    if let Some(requested) = requested {
+        return skills
+            .iter()
+            .find(|s| s.id.eq_ignore_ascii_case(requested) || s.name.eq_ignore_ascii_case(requested))
+            .map(Some)
+            .ok_or_else(|| {
+                let known: Vec<&str> = skills.iter().map(|s| s.id.as_str()).collect();
+                format!(
+                    "Unknown skill '{}'. Available: {}",
+                    requested,
+                    if known.is_empty() { "none".to_string() } else { known.join(", ") }
+                )
+            });
+    }
+
+    let words: Vec<String> = message
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.len() > 2)
+        .map(str::to_lowercase)
+        .collect();
+    let score = |skill: &AgentSkill| {
+        let mut keywords = vec![skill.id.to_lowercase(), skill.name.to_lowercase()];
+        keywords.extend(skill.tags.iter().map(|t| t.to_lowercase()));
+        words
+            .iter()
+            .filter(|w| keywords.iter().any(|k| k.contains(w.as_str())))
+            .count()
+    };
+    Ok(skills
+        .iter()
+        .map(|skill| (score(skill), skill))
+        .filter(|(score, _)| *score > 0)
+        .max_by_key(|(score, _)| *score)
+        .map(|(_, skill)| skill))
+}
+
+/// Cancels the remote task if the tool call is dropped before it finishes
+/// (e.g. the user cancelled the agent turn).
+struct CancelOnDrop {
+    client: A2aClient,
+    task_id: Option<String>,
+}
+
+impl CancelOnDrop {
+    fn disarm(&mut self) {
+        self.task_id = None;
+    }
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        let Some(task_id) = self.task_id.take() else {
+            return;
+        };
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            let client = self.client.clone();
+            handle.spawn(async move {
+                if let Err(e) = client.cancel_task(&task_id).await {
+                    tracing::warn!("Failed to cancel remote A2A task {}: {}", task_id, e);
+                }
+            });
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for A2aDelegateTool {
+    fn name(&self) -> &str {
+        "a2a_delegate"
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn input_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "operation": {
+                    "type": "string",
+                    "enum": ["send", "discover"],
+                    "description": "'send' a task (default) or 'discover' the peer's skills"
+                },
+                "peer": {
+                    "type": "string",
+                    "description": "Configured peer name, or the agent's base URL"
+                },
+                "message": {
+                    "type": "string",
+                    "description": "The task for the remote agent, with all the context it needs (send)"
+                },
+                "skill": {
+                    "type": "string",
+                    "description": "Skill ID or name to use (default: best match for the message)"
+                },
+                "context_id": {
+                    "type": "string",
+                    "description": "Continue the conversation with this context_id from an earlier result"
+                },
+                "task_id": {
+                    "type": "string",
+                    "description": "Answer a task that is waiting for input (its task_id from an earlier result)"
+                },
+                "stream": {
+                    "type": "boolean",
+                    "description": "Use message/stream if the peer supports it (default: true)"
+                },
+                "timeout_secs": {
+                    "type": "integer",
+                    "description": "Seconds to wait for the result before cancelling (default: 300)"
+                }
+            },
+            "required": ["peer"]
+        })
+    }
+
+    fn capabilities(&self) -> Vec<ToolCapability> {
+        vec![ToolCapability::Network]
+    }
+
+    fn validate_input(&self, input: &Value) -> Result<()> {
+        let Some(peer) = str_field(input, "peer") else {
+            return Err(ToolError::InvalidInput("'peer' is required".to_string()));
+        };
+        if self.client_for(peer).is_none() {
+            let known: Vec<&str> = self.peers.iter().map(|p| p.name.as_str()).collect();
+            return Err(ToolError::InvalidInput(format!(
+                "Unknown peer '{}'. Configured peers: {}. A URL (http:// or https://) also works.",
+                peer,
+                if known.is_empty() { "none".to_string() } else { known.join(", ") }
+            )));
+        }
+        match str_field(input, "operation").unwrap_or("send") {
+            "send" => {
+                if str_field(input, "message").is_none() {
+                    return Err(ToolError::InvalidInput("'send' needs 'message'".to_string()));
+                }
+            }
+            "discover" => {}
+            other => {
+                return Err(ToolError::InvalidInput(format!(
+                    "Unknown operation '{}'. Use send or discover.",
+                    other
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    async fn execute(&self, input: Value, _context: &ToolExecutionContext) -> Result<ToolResult> {
+        let peer = str_field(&input, "peer").unwrap_or_default();
+        let (peer_name, mut client) = self
+            .client_for(peer)
+            .ok_or_else(|| ToolError::InvalidInput(format!("Unknown peer '{}'", peer)))?;
+        let card = client.discover().await.map_err(client_error)?;
+
+        if str_field(&input, "operation") == Some("discover") {
+            let mut output = format!("{} — {}\n", card.name, client.endpoint());
+            if let Some(ref description) = card.description {
+                output.push_str(&format!("{}\n", description));
+            }
+            output.push_str("\nSkills:");
+            for skill in &card.skills {
+                output.push_str(&format!("\n- {} ({})", skill.id, skill.name));
+                if let Some(ref description) = skill.description {
+                    output.push_str(&format!(": {}", description));
+                }
+            }
+            if card.skills.is_empty() {
+                output.push_str(" none listed");
+            }
+            return Ok(ToolResult::success(output));
+        }
+
+        let text = str_field(&input, "message").unwrap_or_default();
+        let skill = match pick_skill(&card.skills, str_field(&input, "skill"), text) {
+            Ok(skill) => skill,
+            Err(e) => return Ok(ToolResult::error(e)),
+        };
+        let timeout_secs = input
+            .get("timeout_secs")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_TIMEOUT_SECS)
+            .clamp(1, MAX_TIMEOUT_SECS);
+        let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+        // Only a task whose id came back can be cancelled
+        let timed_out = |cancelled: Option<&str>| {
+            ToolResult::error(match cancelled {
+                Some(task_id) => format!(
+                    "{} did not finish within {}s; remote task {} was cancelled",
+                    peer_name, timeout_secs, task_id
+                ),
+                None => format!(
+                    "{} did not answer within {}s. The remote task may still be running; \
+                     it was not cancelled because its id never came back.",
+                    peer_name, timeout_secs
+                ),
+            })
+        };
+
+        let params = SendMessageParams {
+            message: Message {
+                message_id: Some(Uuid::new_v4().to_string()),
+                context_id: str_field(&input, "context_id").map(str::to_string),
+                task_id: str_field(&input, "task_id").map(str::to_string),
+                role: Role::User,
+                parts: vec![Part::text(text)],
+                metadata: None,
+            },
+            configuration: skill.map(|s| serde_json::json!({ "skill": s.id })),
+            metadata: None,
+        };
+        let streaming = input.get("stream").and_then(|v| v.as_bool()).unwrap_or(true)
+            && card.capabilities.as_ref().is_some_and(|c| c.streaming);
+        let sent = if streaming {
+            tokio::time::timeout_at(deadline, client.stream_message(&params)).await
+        } else {
+            tokio::time::timeout_at(deadline, client.send_message(&params)).await
+        };
+        let Ok(sent) = sent else {
+            return Ok(timed_out(None));
+        };
+        let mut task = sent.map_err(client_error)?;
+
+        let mut guard = CancelOnDrop {
+            client: client.clone(),
+            task_id: Some(task.id.clone()),
+        };
+        while !is_terminal(&task.status.state) {
+            if Instant::now() + POLL_INTERVAL > deadline {
+                // Dropping the guard sends tasks/cancel
+                drop(guard);
+                return Ok(timed_out(Some(&task.id)));
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+            task = client.get_task(&task.id).await.map_err(client_error)?;
+        }
+        guard.disarm();
+
+        Ok(task_result(&peer_name, skill, &task))
+    }
+}
+
+/// Tool result for a task in a final (or input-needing) state
+fn task_result(peer: &str, skill: Option<&AgentSkill>, task: &Task) -> ToolResult {
+    let status_text = task
+        .status
+        .message
+        .as_ref()
+        .map(|m| parts_text(&m.parts))
+        .unwrap_or_default();
+
+    let result = match task.status.state {
+        TaskState::Completed => {
+            let artifacts: Vec<String> = task
+                .artifacts
+                .iter()
+                .map(|a| parts_text(&a.parts))
+                .filter(|text| !text.trim().is_empty())
+                .collect();
+            let mut output = if artifacts.is_empty() {
+                status_text
+            } else {
+                artifacts.join("\n\n")
+            };
+            // Lets a follow-up continue the same remote conversation
+            if let Some(ref context_id) = task.context_id {
+                output.push_str(&format!("\n\n[context_id: {}]", context_id));
+            }
+            ToolResult::success(output)
+        }
+        TaskState::InputRequired | TaskState::AuthRequired => ToolResult::success(format!(
+            "{} needs more input before it can continue. Answer with task_id {} and \
+             context_id {}:\n{}",
+            peer,
+            task.id,
+            task.context_id.as_deref().unwrap_or("-"),
+            status_text
+        )),
+        ref state => ToolResult::error(format!(
+            "{} task {} ended as {:?}{}",
+            peer,
+            task.id,
+            state,
+            if status_text.is_empty() { String::new() } else { format!(": {}", status_text) }
+        )),
+    };
+
+    let mut result = result
+        .with_metadata("peer".to_string(), peer.to_string())
+        .with_metadata("task_id".to_string(), task.id.clone());
+    if let Some(skill) = skill {
+        result = result.with_metadata("skill".to_string(), skill.id.clone());
+    }
+    if let Some(ref context_id) = task.context_id {
+        result = result.with_metadata("context_id".to_string(), context_id.clone());
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::types::{Artifact, TaskStatus};
+
+    fn skill(id: &str, tags: &[&str]) -> AgentSkill {
+        AgentSkill {
+            id: id.to_string(),
+            name: id.replace('-', " "),
+            description: None,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            examples: vec![],
+            input_modes: vec![],
+            output_modes: vec![],
+        }
+    }
+
+    #[test]
+    fn test_pick_skill() {
+        let skills = vec![
+            skill("research", &["search", "web"]),
+            skill("code-review", &["code", "review", "rust"]),
+        ];
+
+        let picked = pick_skill(&skills, Some("Code Review"), "").unwrap();
+        assert_eq!(picked.map(|s| s.id.as_str()), Some("code-review"));
+
+        let picked = pick_skill(&skills, None, "Please review this Rust code").unwrap();
+        assert_eq!(picked.map(|s| s.id.as_str()), Some("code-review"));
+
+        assert!(pick_skill(&skills, None, "hello there").unwrap().is_none());
+        assert!(pick_skill(&skills, Some("translate"), "").is_err());
+    }
+
+    #[test]
+    fn test_peer_resolution() {
+        let tool = A2aDelegateTool::new(vec![A2aPeerConfig {
+            name: "researcher".to_string(),
+            url: "http://10.0.0.2:18790".to_string(),
+            token: Some("s3cret".to_string()),
+            description: Some("Deep web research".to_string()),
+        }]);
+        assert!(tool.description().contains("- researcher: Deep web research"));
+
+        let (name, client) = tool.client_for("Researcher").unwrap();
+        assert_eq!(name, "researcher");
+        assert_eq!(client.endpoint(), "http://10.0.0.2:18790/a2a/v1");
+        assert!(tool.client_for("https://agent.example").is_some());
+        assert!(tool.client_for("nobody").is_none());
+
+        assert!(tool.validate_input(&serde_json::json!({ "peer": "researcher" })).is_err());
+        assert!(tool
+            .validate_input(&serde_json::json!({ "peer": "researcher", "operation": "discover" }))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_task_result() {
+        let mut task = Task {
+            id: "t1".to_string(),
+            context_id: Some("c1".to_string()),
+            status: TaskStatus {
+                state: TaskState::Completed,
+                message: None,
+                timestamp: None,
+            },
+            artifacts: vec![Artifact {
+                artifact_id: None,
+                name: None,
+                description: None,
+                parts: vec![Part::text("42")],
+                metadata: None,
+            }],
+            history: vec![],
+            metadata: None,
+        };
+        let result = task_result("researcher", None, &task);
+        assert!(result.success);
+        assert_eq!(result.output, "42\n\n[context_id: c1]");
+        assert_eq!(result.metadata.get("context_id").map(String::as_str), Some("c1"));
+
+        task.status.state = TaskState::Failed;
+        let result = task_result("researcher", None, &task);
+        assert!(!result.success);
+    }
+}
@@ -1,8 +1,14 @@
 //! Bash/Shell Command Execution Tool
 //!
-//! Allows executing shell commands in the system.
+//! Allows executing shell commands in the system. Each command runs in a
+//! fresh shell unless persistent shells are enabled (`[agent] persistent_shell`),
+//! in which case a session keeps one bash process across calls. Commands
+//! matching a `[permissions] sandbox` rule always run one-shot in the sandbox.
 
 use super::error::{Result, ToolError};
+use super::sandbox::{Sandbox, SandboxRun};
+#[cfg(unix)]
+use super::shell::ShellSessions;
 use super::r#trait::{Tool, ToolCapability, ToolExecutionContext, ToolResult};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -12,11 +18,38 @@ use tokio::process::Command;
 use tokio::time::{timeout, Duration};
 
 /// Bash execution tool
-pub struct BashTool;
+#[derive(Default)]
+pub struct BashTool {
+    /// Long-lived shells by session, when enabled
+    #[cfg(unix)]
+    shells: Option<ShellSessions>,
+}
+
+impl BashTool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep one bash process per session so `cd`, `export`, virtualenvs and
+    /// `source`d scripts persist between calls (Unix only)
+    #[cfg_attr(not(unix), allow(unused_mut))]
+    pub fn with_persistent_shells(mut self, enabled: bool) -> Self {
+        #[cfg(unix)]
+        {
+            self.shells = enabled.then(ShellSessions::new);
+        }
+        #[cfg(not(unix))]
+        if enabled {
+            tracing::warn!("Persistent shells are only supported on Unix; using one-shot shells");
+        }
+        self
+    }
+}
 
 #[derive(Debug, Deserialize, Serialize)]
 struct BashInput {
-    /// Command to execute
+    /// Command to execute (may be empty when only resetting)
+    #[serde(default)]
     command: String,
 
     /// Optional working directory (overrides context)
@@ -26,6 +59,10 @@ struct BashInput {
     /// Optional timeout in seconds (overrides context default)
     #[serde(skip_serializing_if = "Option::is_none")]
     timeout_secs: Option<u64>,
+
+    /// Restart the session's persistent shell before running the command
+    #[serde(default)]
+    reset: bool,
 }
 
 /// Check if a bash command is safe for read-only mode (Plan mode)
@@ -147,6 +184,15 @@ impl Tool for BashTool {
     }
 
     fn description(&self) -> &str {
+        #[cfg(unix)]
+        if self.shells.is_some() {
+            return "Execute a shell command in this session's persistent bash shell: the working \
+                    directory, exported variables, activated virtualenvs and sourced scripts carry \
+                    over between calls, and a cd also changes the agent's working directory. \
+                    Returns stdout, stderr, and exit code. A command that times out is interrupted \
+                    without losing the shell; set reset to start a fresh shell. Commands do not \
+                    read stdin. Use carefully as this can modify system state.";
+        }
         "Execute a shell command. Returns stdout, stderr, and exit code. Use carefully as this can modify system state."
     }
 
@@ -165,6 +211,10 @@ impl Tool for BashTool {
                 "timeout_secs": {
                     "type": "integer",
                     "description": "Optional: Timeout in seconds (default 120, max 600). Use higher values for builds."
+                },
+                "reset": {
+                    "type": "boolean",
+                    "description": "Optional: Restart the persistent shell (clears cwd changes, variables and virtualenvs) before running. The command may be empty to only reset."
                 }
             },
             "required": ["command"]
@@ -187,7 +237,7 @@ impl Tool for BashTool {
         let input: BashInput = serde_json::from_value(input.clone())
             .map_err(|e| ToolError::InvalidInput(format!("Invalid input: {}", e)))?;
 
-        if input.command.trim().is_empty() {
+        if input.command.trim().is_empty() && !input.reset {
             return Err(ToolError::InvalidInput(
                 "Command cannot be empty".to_string(),
             ));
@@ -209,6 +259,35 @@ impl Tool for BashTool {
             )));
         }
 
+        #[cfg(unix)]
+        if let Some(ref shells) = self.shells {
+            if input.reset {
+                let was_running = shells.reset(context.session_id).await;
+                if input.command.trim().is_empty() {
+                    return Ok(ToolResult::success(if was_running {
+                        "Shell reset. The next command starts a fresh shell.".to_string()
+                    } else {
+                        "No shell was running. The next command starts a fresh shell.".to_string()
+                    }));
+                }
+            }
+
+            // An explicit working_dir, a sudo password prompt or the sandbox runs one-shot below
+            if input.working_dir.is_none()
+                && !input.command.trim_start().starts_with("sudo ")
+                && context.sandbox.is_none()
+            {
+                return self.run_persistent(shells, &input, context).await;
+            }
+        }
+
+        if input.command.trim().is_empty() {
+            return Ok(ToolResult::success(
+                "Persistent shells are off, so every command already starts in a fresh shell."
+                    .to_string(),
+            ));
+        }
+
         // Determine working directory
         let working_dir = if let Some(ref dir) = input.working_dir {
             std::path::PathBuf::from(dir)
@@ -236,6 +315,12 @@ impl Tool for BashTool {
             .unwrap_or(context.timeout_secs)
             .min(600);
 
+        if let Some(ref sandbox) = context.sandbox {
+            return self
+                .run_sandboxed(sandbox, &input, &working_dir, effective_timeout, context)
+                .await;
+        }
+
         // Detect sudo commands and request password via callback
         let is_sudo = input.command.trim_start().starts_with("sudo ");
         let sudo_password = if is_sudo {
@@ -319,43 +404,172 @@ impl Tool for BashTool {
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
         let exit_code = output.status.code().unwrap_or(-1);
 
-        // Build output message
-        let mut result_text = String::new();
+        Ok(command_result(&stdout, &stderr, exit_code, &working_dir))
+    }
+}
 
-        if !stdout.is_empty() {
-            result_text.push_str("STDOUT:\n");
-            result_text.push_str(&stdout);
+impl BashTool {
+    /// Run a command inside the sandbox, starting in `working_dir` (which must
+    /// be inside the project)
+    async fn run_sandboxed(
+        &self,
+        sandbox: &Sandbox,
+        input: &BashInput,
+        working_dir: &std::path::Path,
+        effective_timeout: u64,
+        context: &ToolExecutionContext,
+    ) -> Result<ToolResult> {
+        if input.command.trim_start().starts_with("sudo ") {
+            return Ok(ToolResult::error(
+                "sudo is not available for sandboxed commands".to_string(),
+            ));
         }
 
-        if !stderr.is_empty() {
-            if !result_text.is_empty() {
-                result_text.push_str("\n\n");
+        let scratch = sandbox.scratch_dir()?;
+        let capabilities = self.capabilities();
+        let run = SandboxRun {
+            cwd: working_dir,
+            scratch: scratch.path(),
+            capabilities: &capabilities,
+        };
+        let args = ["-c".to_string(), input.command.clone()];
+        let mut cmd = match sandbox.command("sh", &args, &run) {
+            Ok(cmd) => cmd,
+            Err(e) => return Ok(ToolResult::error(e.to_string())),
+        };
+
+        let output = match timeout(Duration::from_secs(effective_timeout), cmd.output()).await {
+            Ok(Ok(output)) => output,
+            Ok(Err(e)) => {
+                return Ok(ToolResult::error(format!(
+                    "Sandboxed command failed to start: {}",
+                    e
+                )));
             }
-            result_text.push_str("STDERR:\n");
-            result_text.push_str(&stderr);
-        }
+            Err(_) => return Err(ToolError::Timeout(effective_timeout)),
+        };
 
-        if result_text.is_empty() {
-            result_text = "(no output)".to_string();
-        }
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        let exit_code = output.status.code().unwrap_or(-1);
 
-        let success = output.status.success();
+        Ok(command_result(&stdout, &stderr, exit_code, working_dir)
+            .with_metadata("sandbox".to_string(), sandbox.describe(&run)))
+    }
 
-        let result = if success {
-            ToolResult::success(result_text)
-        } else {
-            ToolResult {
-                success: false,
-                output: result_text,
-                error: Some(format!("Command exited with code {}", exit_code)),
-                metadata: std::collections::HashMap::new(),
+    /// Run a command in the session's persistent shell and sync its working
+    /// directory back to the agent
+    #[cfg(unix)]
+    async fn run_persistent(
+        &self,
+        shells: &ShellSessions,
+        input: &BashInput,
+        context: &ToolExecutionContext,
+    ) -> Result<ToolResult> {
+        let effective_timeout = input.timeout_secs.unwrap_or(context.timeout_secs).min(600);
+
+        // The shared directory is current even when a cd earlier this turn moved it
+        let working_dir = context
+            .shared_working_directory
+            .as_ref()
+            .map(|wd| wd.read().expect("working_directory lock poisoned").clone())
+            .unwrap_or_else(|| context.working_directory.clone());
+
+        let output = match shells
+            .run(
+                context.session_id,
+                &input.command,
+                &working_dir,
+                Duration::from_secs(effective_timeout),
+            )
+            .await
+        {
+            Ok(output) => output,
+            Err(e) => {
+                return Ok(ToolResult::error(format!(
+                    "Shell failed: {}. The next command starts a fresh shell.",
+                    e
+                )));
             }
         };
 
-        Ok(result
-            .with_metadata("exit_code".to_string(), exit_code.to_string())
-            .with_metadata("working_dir".to_string(), working_dir.display().to_string()))
+        if output.cwd != working_dir
+            && output.cwd.is_dir()
+            && let Some(ref shared_wd) = context.shared_working_directory
+        {
+            *shared_wd.write().expect("working_directory lock poisoned") = output.cwd.clone();
+        }
+
+        let mut result = command_result(
+            &output.stdout,
+            &output.stderr,
+            output.exit_code,
+            &output.cwd,
+        );
+        if output.timed_out {
+            result.success = false;
+            result.error = Some(if output.alive {
+                format!(
+                    "Command timed out after {}s and was interrupted; the shell is still running",
+                    effective_timeout
+                )
+            } else {
+                format!(
+                    "Command timed out after {}s and ignored the interrupt; the shell was killed \
+                     and the next command starts a fresh one",
+                    effective_timeout
+                )
+            });
+        } else if !output.alive {
+            result
+                .output
+                .push_str("\n\n(The shell exited; the next command starts a fresh one.)");
+        }
+        Ok(result)
+    }
+}
+
+/// Build the tool result for a finished command
+fn command_result(
+    stdout: &str,
+    stderr: &str,
+    exit_code: i32,
+    working_dir: &std::path::Path,
+) -> ToolResult {
+    // Build output message
+    let mut result_text = String::new();
+
+    if !stdout.is_empty() {
+        result_text.push_str("STDOUT:\n");
+        result_text.push_str(stdout);
+    }
+
+    if !stderr.is_empty() {
+        if !result_text.is_empty() {
+            result_text.push_str("\n\n");
+        }
+        result_text.push_str("STDERR:\n");
+        result_text.push_str(stderr);
+    }
+
+    if result_text.is_empty() {
+        result_text = "(no output)".to_string();
     }
+
+    let result = if exit_code == 0 {
+        ToolResult::success(result_text)
+    } else {
+        ToolResult {
+            success: false,
+            output: result_text,
+            error: Some(format!("Command exited with code {}", exit_code)),
+            metadata: std::collections::HashMap::new(),
+        }
+    };
+
+    result
+        .with_metadata("exit_code".to_string(), exit_code.to_string())
+        .with_metadata("working_dir".to_string(), working_dir.display().to_string())
 }
 
 #[cfg(test)]
@@ -365,7 +579,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_bash_simple_command() {
-        let tool = BashTool;
+        let tool = BashTool::new();
         let session_id = Uuid::new_v4();
         let context = ToolExecutionContext::new(session_id).with_auto_approve(true);
 
@@ -386,7 +600,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_bash_with_exit_code() {
-        let tool = BashTool;
+        let tool = BashTool::new();
         let session_id = Uuid::new_v4();
         let context = ToolExecutionContext::new(session_id).with_auto_approve(true);
 
@@ -403,7 +617,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_bash_invalid_command() {
-        let tool = BashTool;
+        let tool = BashTool::new();
         let session_id = Uuid::new_v4();
         let context = ToolExecutionContext::new(session_id).with_auto_approve(true);
 
@@ -418,7 +632,7 @@ mod tests {
     #[tokio::test]
     #[cfg(not(target_os = "windows"))] // Skip on Windows due to cmd.exe limitations
     async fn test_bash_timeout() {
-        let tool = BashTool;
+        let tool = BashTool::new();
         let session_id = Uuid::new_v4();
         let context = ToolExecutionContext::new(session_id)
             .with_auto_approve(true)
@@ -433,9 +647,69 @@ mod tests {
         assert!(matches!(result.unwrap_err(), ToolError::Timeout(_)));
     }
 
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_persistent_shell_keeps_state() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(temp_dir.path().join("sub")).unwrap();
+        let shared_wd = std::sync::Arc::new(std::sync::RwLock::new(temp_dir.path().to_path_buf()));
+
+        let tool = BashTool::new().with_persistent_shells(true);
+        let mut context = ToolExecutionContext::new(Uuid::new_v4())
+            .with_working_directory(temp_dir.path().to_path_buf());
+        context.shared_working_directory = Some(shared_wd.clone());
+
+        let input = serde_json::json!({ "command": "cd sub && export GREETING=hello" });
+        let result = tool.execute(input, &context).await.unwrap();
+        assert!(result.success);
+
+        let input = serde_json::json!({ "command": "echo $GREETING; pwd" });
+        let result = tool.execute(input, &context).await.unwrap();
+        assert!(result.output.contains("hello"));
+        assert!(result.output.contains("sub"));
+        assert!(shared_wd.read().unwrap().ends_with("sub"));
+
+        let input = serde_json::json!({ "command": "echo ${GREETING:-unset}", "reset": true });
+        let result = tool.execute(input, &context).await.unwrap();
+        assert!(result.output.contains("unset"));
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_persistent_shell_survives_timeout_and_exit() {
+        let tool = BashTool::new().with_persistent_shells(true);
+        let context = ToolExecutionContext::new(Uuid::new_v4()).with_timeout(1);
+
+        let input = serde_json::json!({ "command": "export KEPT=yes" });
+        tool.execute(input, &context).await.unwrap();
+
+        // Interrupted, but the shell and its variables stay
+        let input = serde_json::json!({ "command": "sleep 30" });
+        let result = tool.execute(input, &context).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("timed out"));
+
+        let input = serde_json::json!({ "command": "echo \"unterminated" });
+        let result = tool.execute(input, &context).await.unwrap();
+        assert!(!result.success);
+
+        let input = serde_json::json!({ "command": "echo $KEPT" });
+        let result = tool.execute(input, &context).await.unwrap();
+        assert!(result.output.contains("yes"));
+
+        // `exit` ends the shell; the next command gets a fresh one
+        let input = serde_json::json!({ "command": "exit 3" });
+        let result = tool.execute(input, &context).await.unwrap();
+        assert_eq!(result.metadata.get("exit_code"), Some(&"3".to_string()));
+
+        let input = serde_json::json!({ "command": "echo ${KEPT:-gone}" });
+        let result = tool.execute(input, &context).await.unwrap();
+        assert!(result.output.contains("gone"));
+    }
+
     #[test]
     fn test_bash_tool_schema() {
-        let tool = BashTool;
+        let tool = BashTool::new();
         assert_eq!(tool.name(), "bash");
         assert!(tool.requires_approval());
 
@@ -446,7 +720,7 @@ mod tests {
 
     #[test]
     fn test_validate_empty_command() {
-        let tool = BashTool;
+        let tool = BashTool::new();
         let input = serde_json::json!({
             "command": ""
         });
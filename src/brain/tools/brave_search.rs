@@ -84,6 +84,10 @@ impl Tool for BraveSearchTool {
         vec![ToolCapability::Network]
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     fn requires_approval(&self) -> bool {
         false
     }
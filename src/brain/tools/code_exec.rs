@@ -1,8 +1,11 @@
 //! Code Execution Tool
 //!
-//! Execute code in various languages within a sandboxed environment.
+//! Execute code in various languages. Code runs with the user's privileges
+//! unless the call matches a `[permissions] sandbox` rule, in which case it
+//! runs inside the sandbox with the scratch directory holding the source.
 
 use super::error::{Result, ToolError};
+use super::sandbox::SandboxRun;
 use super::r#trait::{Tool, ToolCapability, ToolExecutionContext, ToolResult};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -42,7 +45,7 @@ impl Tool for CodeExecTool {
     }
 
     fn description(&self) -> &str {
-        "Execute code in a sandboxed environment. Supports Python, JavaScript (Node.js), Rust, and shell scripts. Returns stdout, stderr, and exit code."
+        "Execute a code snippet from a temporary file, in the working directory. Supports Python, JavaScript (Node.js), Rust, and shell scripts. Returns stdout, stderr, and exit code."
     }
 
     fn input_schema(&self) -> Value {
@@ -141,11 +144,7 @@ impl Tool for CodeExecTool {
         let (interpreter, extension, extra_args) = match input.language.as_str() {
             "python" | "python3" => ("python3", "py", vec![]),
             "javascript" | "js" | "node" => ("node", "js", vec![]),
-            "rust" => (
-                "rustc",
-                "rs",
-                vec!["--out-dir".to_string(), "/tmp".to_string()],
-            ),
+            "rust" => ("rustc", "rs", vec!["--out-dir".to_string()]),
             "sh" | "bash" => ("bash", "sh", vec![]),
             _ => {
                 return Ok(ToolResult::error(format!(
@@ -164,8 +163,16 @@ impl Tool for CodeExecTool {
             )));
         }
 
+        // Sandboxed code lives in the scratch directory, the only writable place
+        let scratch = match context.sandbox {
+            Some(ref sandbox) => Some(sandbox.scratch_dir()?),
+            None => None,
+        };
+        let temp_dir = scratch
+            .as_ref()
+            .map_or_else(std::env::temp_dir, |s| s.path().to_path_buf());
+
         // Create temporary file
-        let temp_dir = std::env::temp_dir();
         let timestamp = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .map_err(|e| ToolError::Internal(format!("Failed to get system time: {}", e)))?
@@ -177,22 +184,36 @@ impl Tool for CodeExecTool {
             .await
             .map_err(ToolError::Io)?;
 
-        // Prepare command
-        let mut cmd = Command::new(interpreter);
-        cmd.current_dir(&context.working_directory);
-
-        // Add extra args (like rustc --out-dir)
-        for arg in extra_args {
-            cmd.arg(arg);
-        }
-
-        // Add user-provided args
-        for arg in &input.args {
-            cmd.arg(arg);
+        // Extra args (like rustc --out-dir), then user-provided args, then the temp file
+        let mut args = extra_args;
+        if input.language == "rust" {
+            args.push(temp_dir.to_string_lossy().into_owned());
         }
+        args.extend(input.args.iter().cloned());
+        args.push(temp_file.to_string_lossy().into_owned());
 
-        // Add the temp file path
-        cmd.arg(&temp_file);
+        // Prepare command
+        let capabilities = self.capabilities();
+        let mut sandbox_note = None;
+        let mut cmd = match context.sandbox {
+            Some(ref sandbox) => {
+                let run = SandboxRun {
+                    cwd: &context.working_directory,
+                    scratch: &temp_dir,
+                    capabilities: &capabilities,
+                };
+                sandbox_note = Some(sandbox.describe(&run));
+                match sandbox.command(interpreter, &args, &run) {
+                    Ok(cmd) => cmd,
+                    Err(e) => return Ok(ToolResult::error(e.to_string())),
+                }
+            }
+            None => {
+                let mut cmd = Command::new(interpreter);
+                cmd.current_dir(&context.working_directory).args(&args);
+                cmd
+            }
+        };
 
         // Execute with timeout
         let exec_timeout = Duration::from_secs(input.timeout_secs);
@@ -260,6 +281,9 @@ impl Tool for CodeExecTool {
         tool_result
             .metadata
             .insert("language".to_string(), input.language);
+        if let Some(note) = sandbox_note {
+            tool_result.metadata.insert("sandbox".to_string(), note);
+        }
 
         Ok(tool_result)
     }
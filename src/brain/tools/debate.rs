@@ -0,0 +1,437 @@
+//! Debate Tool
+//!
+//! Runs a Bee Colony debate (see `crate::a2a::debate`): several bees answer a
+//! topic independently, then critique each other over a few rounds until they
+//! agree or run out of rounds, and the queen (the active provider) writes the
+//! final synthesis. Bees are remote A2A agents or local bees on our own
+//! providers, configured with `[[a2a.bees]]`. Finished debates are saved to
+//! SQLite and to the daily memory log.
+
+use super::error::{Result, ToolError};
+use super::r#trait::{Tool, ToolCapability, ToolExecutionContext, ToolResult};
+use crate::a2a::debate::{run_colony, synthesize, Bee, DebateConfig, DebateSession};
+use crate::a2a::persistence;
+use crate::brain::agent::{ProgressCallback, ProgressEvent};
+use crate::brain::provider::{create_named_provider, Provider};
+use crate::config::{A2aBeeConfig, Config};
+use async_trait::async_trait;
+use serde_json::Value;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+
+/// Local bees spun up when no bees are configured or selected
+const DEFAULT_LOCAL_BEES: usize = 3;
+
+/// Most bees in one debate
+const MAX_BEES: usize = 6;
+
+/// Most rounds in one debate
+const MAX_ROUNDS: usize = 5;
+
+/// Angles of the ad-hoc local bees, so bees on the same model still differ
+const PERSPECTIVES: [&str; MAX_BEES] = [
+    "a pragmatic engineer",
+    "a skeptical critic",
+    "an optimistic visionary",
+    "a security and risk analyst",
+    "an advocate for the end user",
+    "a cost-conscious operator",
+];
+
+/// Tool for running Bee Colony debates.
+pub struct DebateTool {
+    /// Configured bees by name
+    bees: Vec<(String, Bee)>,
+    /// Active provider: the queen, and the provider of ad-hoc local bees
+    provider: Arc<dyn Provider>,
+    pool: SqlitePool,
+    progress: Option<ProgressCallback>,
+    description: String,
+}
+
+impl DebateTool {
+    pub fn new(config: &Config, provider: Arc<dyn Provider>, pool: SqlitePool) -> Self {
+        let bees: Vec<(String, Bee)> = config
+            .a2a
+            .bees
+            .iter()
+            .filter_map(|bee| match resolve_bee(config, bee, &provider) {
+                Ok(resolved) => Some((bee.name.clone(), resolved)),
+                Err(e) => {
+                    tracing::warn!("Skipping debate bee '{}': {}", bee.name, e);
+                    None
+                }
+            })
+            .collect();
+
+        let mut description = String::from(
+            "Run a Bee Colony debate: several AI agents (bees) research 'topic' independently, \
+             then critique each other's answers over up to 'max_rounds' rounds until they reach \
+             consensus. Returns each round's positions and confidence, the consensus analysis \
+             (agreements, contentions, blind spots) and a final synthesis. Use it for contested \
+             questions that benefit from several viewpoints. 'bees' picks configured bees by \
+             name, 'endpoints' adds remote A2A bees by JSON-RPC URL.",
+        );
+        if bees.is_empty() {
+            description.push_str(&format!(
+                " No bees are configured, so {} local bees with different perspectives run on \
+                 the active model ('num_bees' changes how many).",
+                DEFAULT_LOCAL_BEES
+            ));
+        } else {
+            description.push_str("\n\nConfigured bees:");
+            for (name, bee) in &bees {
+                description.push_str(&format!("\n- {} ({})", name, bee.label()));
+            }
+        }
+
+        Self {
+            bees,
+            provider,
+            pool,
+            progress: None,
+            description,
+        }
+    }
+
+    /// Report rounds as they start and finish (the TUI debate panel follows these).
+    pub fn with_progress(mut self, progress: ProgressCallback) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// The bees taking part, from the `bees`, `endpoints` and `num_bees` inputs.
+    fn pick_bees(&self, input: &Value) -> std::result::Result<Vec<Bee>, String> {
+        let mut bees = Vec::new();
+
+        let names = string_list(input, "bees");
+        for name in &names {
+            let (_, bee) = self
+                .bees
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                .ok_or_else(|| {
+                    let known: Vec<&str> = self.bees.iter().map(|(n, _)| n.as_str()).collect();
+                    format!(
+                        "Unknown bee '{}'. Configured: {}",
+                        name,
+                        if known.is_empty() { "none".to_string() } else { known.join(", ") }
+                    )
+                })?;
+            bees.push(bee.clone());
+        }
+
+        for endpoint in string_list(input, "endpoints") {
+            if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+                return Err(format!("Bee endpoint must be an http(s) URL: {}", endpoint));
+            }
+            bees.push(Bee::Remote { endpoint });
+        }
+
+        if bees.is_empty() {
+            if self.bees.is_empty() {
+                let count = input
+                    .get("num_bees")
+                    .and_then(|v| v.as_u64())
+                    .map(|n| n as usize)
+                    .unwrap_or(DEFAULT_LOCAL_BEES)
+                    .clamp(2, MAX_BEES);
+                let model = self.provider.default_model().to_string();
+                bees = PERSPECTIVES[..count]
+                    .iter()
+                    .enumerate()
+                    .map(|(i, perspective)| Bee::Local {
+                        name: format!("bee-{}", i),
+                        provider: self.provider.clone(),
+                        model: model.clone(),
+                        perspective: Some(perspective.to_string()),
+                    })
+                    .collect();
+            } else {
+                bees = self.bees.iter().map(|(_, bee)| bee.clone()).collect();
+            }
+        }
+
+        if bees.len() > MAX_BEES {
+            return Err(format!("A debate takes at most {} bees", MAX_BEES));
+        }
+        Ok(bees)
+    }
+
+    fn report(&self, session: &DebateSession) {
+        if let Some(ref cb) = self.progress {
+            cb(ProgressEvent::DebateUpdated(Box::new(session.clone())));
+        }
+    }
+}
+
+/// Build the bee of a `[[a2a.bees]]` entry.
+fn resolve_bee(
+    config: &Config,
+    bee: &A2aBeeConfig,
+    active: &Arc<dyn Provider>,
+) -> anyhow::Result<Bee> {
+    if let Some(ref url) = bee.url {
+        return Ok(Bee::Remote {
+            endpoint: url.clone(),
+        });
+    }
+    let provider = match bee.provider {
+        Some(ref name) => create_named_provider(config, name)?,
+        None => active.clone(),
+    };
+    let model = bee
+        .model
+        .clone()
+        .unwrap_or_else(|| provider.default_model().to_string());
+    Ok(Bee::Local {
+        name: bee.name.clone(),
+        provider,
+        model,
+        perspective: bee.perspective.clone(),
+    })
+}
+
+/// Non-empty strings of an array input
+fn string_list(input: &Value, field: &str) -> Vec<String> {
+    input
+        .get(field)
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|v| v.as_str())
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// What a finished debate leaves in long-term memory.
+fn memory_entry(session: &DebateSession) -> String {
+    let mut entry = format!(
+        "**Topic:** {}\n**Outcome:** {:?} after {} round(s) with {} bees\n",
+        session.config.topic, session.state, session.current_round, session.config.num_bees
+    );
+    if let Some(round) = session.rounds.last() {
+        let positions: Vec<String> = round
+            .responses
+            .iter()
+            .map(|r| {
+                format!(
+                    "- {}: {} (confidence {:.1})",
+                    r.endpoint,
+                    r.position.as_deref().unwrap_or("no stated position"),
+                    r.confidence
+                )
+            })
+            .collect();
+        entry.push_str(&format!("\n**Final positions:**\n{}\n", positions.join("\n")));
+        if let Some(ref consensus) = round.consensus
+            && !consensus.blind_spots.is_empty()
+        {
+            entry.push_str(&format!(
+                "\n**Blind spots:** {}\n",
+                consensus.blind_spots.join("; ")
+            ));
+        }
+    }
+    if let Some(ref synthesis) = session.final_synthesis {
+        entry.push_str(&format!("\n**Synthesis:**\n{}\n", synthesis));
+    }
+    entry
+}
+
+#[async_trait]
+impl Tool for DebateTool {
+    fn name(&self) -> &str {
+        "debate"
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn input_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "topic": {
+                    "type": "string",
+                    "description": "The question or topic to debate"
+                },
+                "bees": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Names of configured bees to use (default: all configured bees)"
+                },
+                "endpoints": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "JSON-RPC URLs of extra remote A2A bees (e.g. http://host:18790/a2a/v1)"
+                },
+                "num_bees": {
+                    "type": "integer",
+                    "description": "Local bees to run when none are configured or selected (2-6, default 3)"
+                },
+                "max_rounds": {
+                    "type": "integer",
+                    "description": "Most debate rounds (1-5, default 3)"
+                },
+                "consensus_threshold": {
+                    "type": "number",
+                    "description": "Share of agreeing bees and average confidence that ends the debate early (0.0-1.0, default 0.8)"
+                }
+            },
+            "required": ["topic"]
+        })
+    }
+
+    fn capabilities(&self) -> Vec<ToolCapability> {
+        vec![ToolCapability::Network]
+    }
+
+    async fn execute(&self, input: Value, _context: &ToolExecutionContext) -> Result<ToolResult> {
+        let topic = input
+            .get("topic")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| ToolError::InvalidInput("'topic' is required".to_string()))?;
+        let bees = self.pick_bees(&input).map_err(ToolError::InvalidInput)?;
+        let max_rounds = input
+            .get("max_rounds")
+            .and_then(|v| v.as_u64())
+            .map(|n| (n as usize).clamp(1, MAX_ROUNDS))
+            .unwrap_or(3);
+        let consensus_threshold = input
+            .get("consensus_threshold")
+            .and_then(|v| v.as_f64())
+            .map(|t| t.clamp(0.0, 1.0))
+            .unwrap_or(0.8);
+
+        let config = DebateConfig {
+            topic: topic.to_string(),
+            num_bees: bees.len(),
+            max_rounds,
+            consensus_threshold,
+            knowledge_context: vec![],
+            bee_endpoints: bees
+                .iter()
+                .filter_map(|bee| match bee {
+                    Bee::Remote { endpoint } => Some(endpoint.clone()),
+                    Bee::Local { .. } => None,
+                })
+                .collect(),
+        };
+
+        let mut session = match run_colony(config, bees, |s| self.report(s)).await {
+            Ok(session) => session,
+            Err(e) => return Ok(ToolResult::error(format!("Debate failed: {}", e))),
+        };
+
+        if let Err(e) =
+            synthesize(&mut session, self.provider.as_ref(), self.provider.default_model()).await
+        {
+            tracing::warn!("Debate {}: queen synthesis failed: {}", session.id, e);
+        }
+        self.report(&session);
+
+        if let Err(e) = persistence::save_debate(&self.pool, &session).await {
+            tracing::warn!("Failed to save debate {}: {}", session.id, e);
+        }
+        // Today's memory log, so memory_search finds the verdict later
+        if let Err(e) =
+            crate::memory::append_daily_log("Bee Colony Debate", &memory_entry(&session)).await
+        {
+            tracing::warn!("Failed to save debate {} to memory: {}", session.id, e);
+        }
+
+        Ok(ToolResult::success(session.summary_report())
+            .with_metadata("debate_id".to_string(), session.id.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::debate::{BeeResponse, DebateState};
+    use crate::a2a::test_helpers::helpers;
+    use crate::brain::provider::PlaceholderProvider;
+
+    async fn tool(bees: Vec<A2aBeeConfig>) -> DebateTool {
+        let mut config = Config::default();
+        config.a2a.bees = bees;
+        let pool = helpers::placeholder_service_context().await.pool();
+        DebateTool::new(&config, Arc::new(PlaceholderProvider), pool)
+    }
+
+    fn remote_bee(name: &str) -> A2aBeeConfig {
+        A2aBeeConfig {
+            name: name.to_string(),
+            url: Some(format!("http://{}:18790/a2a/v1", name)),
+            provider: None,
+            model: None,
+            perspective: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pick_bees() {
+        let unconfigured = tool(vec![]).await;
+        let bees = unconfigured
+            .pick_bees(&serde_json::json!({"num_bees": 4}))
+            .expect("local bees");
+        assert_eq!(bees.len(), 4);
+        assert!(bees.iter().all(|b| matches!(b, Bee::Local { perspective: Some(_), .. })));
+
+        let configured = tool(vec![remote_bee("alpha"), remote_bee("beta")]).await;
+        assert_eq!(configured.pick_bees(&serde_json::json!({})).expect("all").len(), 2);
+        let picked = configured
+            .pick_bees(&serde_json::json!({
+                "bees": ["Beta"],
+                "endpoints": ["https://gamma.example/a2a/v1"]
+            }))
+            .expect("picked");
+        let labels: Vec<String> = picked.iter().map(Bee::label).collect();
+        assert_eq!(
+            labels,
+            vec!["http://beta:18790/a2a/v1", "https://gamma.example/a2a/v1"]
+        );
+
+        assert!(configured.pick_bees(&serde_json::json!({"bees": ["delta"]})).is_err());
+        assert!(configured.pick_bees(&serde_json::json!({"endpoints": ["ftp://x"]})).is_err());
+    }
+
+    #[test]
+    fn test_memory_entry() {
+        let mut session = DebateSession::new(DebateConfig {
+            topic: "Monorepo or polyrepo?".to_string(),
+            num_bees: 1,
+            max_rounds: 1,
+            consensus_threshold: 0.8,
+            knowledge_context: vec![],
+            bee_endpoints: vec![],
+        });
+        session.record_round(
+            1,
+            "prompt".to_string(),
+            vec![BeeResponse {
+                bee_id: "bee-0".to_string(),
+                endpoint: "local:bee-0 (mock/m1)".to_string(),
+                content: "Position: monorepo".to_string(),
+                confidence: 0.9,
+                position: Some("monorepo".to_string()),
+                key_points: vec![],
+            }],
+        );
+        session.final_synthesis = Some("Monorepo for small teams.".to_string());
+        assert_eq!(session.state, DebateState::Concluded);
+
+        let entry = memory_entry(&session);
+        assert!(entry.contains("Monorepo or polyrepo?"));
+        assert!(entry.contains("local:bee-0 (mock/m1): monorepo (confidence 0.9)"));
+        assert!(entry.contains("Monorepo for small teams."));
+    }
+}
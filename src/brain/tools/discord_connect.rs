@@ -112,6 +112,7 @@ impl Tool for DiscordConnectTool {
 
         let dc_agent = crate::channels::discord::DiscordAgent::new(
             agent,
+            factory.approvals(),
             service_context,
             allowed_users,
             voice_config,
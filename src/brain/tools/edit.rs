@@ -174,6 +174,7 @@ impl Tool for EditTool {
                 "{}.backup",
                 path.extension().and_then(|s| s.to_str()).unwrap_or("txt")
             ));
+            context.snapshot_file(&backup_path).await;
             fs::write(&backup_path, &content)
                 .await
                 .map_err(ToolError::Io)?;
@@ -285,6 +286,7 @@ impl Tool for EditTool {
         };
 
         // Write modified content
+        context.snapshot_file(&path).await;
         fs::write(&path, &new_content)
             .await
             .map_err(ToolError::Io)?;
@@ -401,6 +401,10 @@ impl Tool for ExaSearchTool {
         vec![ToolCapability::Network]
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     fn requires_approval(&self) -> bool {
         false
     }
@@ -1,13 +1,21 @@
 //! Glob Pattern Matching Tool
 //!
-//! Find files matching glob patterns.
+//! Find files matching glob patterns, newest first. The directory walk is
+//! parallel and skips gitignored and hidden files unless asked not to.
 
 use super::error::{Result, ToolError};
 use super::r#trait::{Tool, ToolCapability, ToolExecutionContext, ToolResult};
 use async_trait::async_trait;
+use globset::{GlobBuilder, GlobMatcher};
+use ignore::{WalkBuilder, WalkState};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// Results returned when `limit` is not given
+const DEFAULT_LIMIT: usize = 200;
 
 /// Glob pattern matching tool
 pub struct GlobTool;
@@ -28,6 +36,10 @@ struct GlobInput {
     /// Include hidden files
     #[serde(default)]
     include_hidden: bool,
+
+    /// Include files matched by .gitignore
+    #[serde(default)]
+    include_ignored: bool,
 }
 
 #[async_trait]
@@ -37,7 +49,8 @@ impl Tool for GlobTool {
     }
 
     fn description(&self) -> &str {
-        "Find files matching a glob pattern. Supports wildcards: * (any chars), ** (recursive directories), ? (single char), [abc] (char class)."
+        "Find files matching a glob pattern, most recently modified first. Skips gitignored and hidden files by default. \
+         Supports wildcards: * (any chars within a directory), ** (recursive directories), ? (single char), [abc] (char class), {a,b} (alternatives)."
     }
 
     fn input_schema(&self) -> Value {
@@ -54,13 +67,18 @@ impl Tool for GlobTool {
                 },
                 "limit": {
                     "type": "integer",
-                    "description": "Maximum number of results to return",
+                    "description": "Maximum number of results to return (default: 200)",
                     "minimum": 1
                 },
                 "include_hidden": {
                     "type": "boolean",
                     "description": "Include hidden files (starting with .)",
                     "default": false
+                },
+                "include_ignored": {
+                    "type": "boolean",
+                    "description": "Include files matched by .gitignore (e.g. target/, node_modules/)",
+                    "default": false
                 }
             },
             "required": ["pattern"]
@@ -90,6 +108,7 @@ impl Tool for GlobTool {
 
     async fn execute(&self, input: Value, context: &ToolExecutionContext) -> Result<ToolResult> {
         let input: GlobInput = serde_json::from_value(input)?;
+        let limit = input.limit.unwrap_or(DEFAULT_LIMIT).max(1);
 
         // Resolve base directory
         let base_dir = if let Some(ref dir) = input.base_dir {
@@ -109,45 +128,26 @@ impl Tool for GlobTool {
             )));
         }
 
-        // Build full pattern with base directory
-        let full_pattern = base_dir.join(&input.pattern);
-        let pattern_str = full_pattern
-            .to_str()
-            .ok_or_else(|| ToolError::InvalidInput("Invalid path encoding".to_string()))?;
-
-        // Use glob crate to find matches
-        let glob_result = glob::glob(pattern_str)
-            .map_err(|e| ToolError::InvalidInput(format!("Invalid glob pattern: {}", e)))?;
-
-        let mut matches: Vec<PathBuf> = Vec::new();
-
-        for entry in glob_result {
-            match entry {
-                Ok(path) => {
-                    // Filter hidden files if not requested
-                    if !input.include_hidden
-                        && let Some(file_name) = path.file_name()
-                            && file_name
-                                .to_str()
-                                .map(|s| s.starts_with('.'))
-                                .unwrap_or(false)
-                            {
-                                continue;
-                            }
-
-                    matches.push(path);
-
-                    // Apply limit
-                    if let Some(limit) = input.limit
-                        && matches.len() >= limit {
-                            break;
-                        }
-                }
-                Err(e) => {
-                    tracing::warn!("Error reading glob entry: {}", e);
-                }
-            }
-        }
+        // Only walk below the pattern's literal prefix ("src/" in "src/**/*.rs")
+        let (root, relative_pattern) = split_pattern(&base_dir, &input.pattern);
+        let matcher = GlobBuilder::new(&relative_pattern)
+            .literal_separator(true)
+            .build()
+            .map_err(|e| ToolError::InvalidInput(format!("Invalid glob pattern: {}", e)))?
+            .compile_matcher();
+        let max_depth =
+            (!relative_pattern.contains("**")).then(|| relative_pattern.split('/').count());
+
+        let mut matches = if root.is_dir() {
+            let (include_hidden, include_ignored) = (input.include_hidden, input.include_ignored);
+            tokio::task::spawn_blocking(move || {
+                find_files(&root, &matcher, max_depth, include_hidden, include_ignored)
+            })
+            .await
+            .map_err(|e| ToolError::Internal(format!("Glob task failed: {}", e)))?
+        } else {
+            Vec::new()
+        };
 
         if matches.is_empty() {
             return Ok(ToolResult::success(format!(
@@ -156,17 +156,19 @@ impl Tool for GlobTool {
             )));
         }
 
-        // Sort matches for consistent output
-        matches.sort();
+        // Newest first; the path keeps the order stable for equal times
+        matches.sort_by(|(a_path, a_time), (b_path, b_time)| {
+            b_time.cmp(a_time).then_with(|| a_path.cmp(b_path))
+        });
 
         // Format output
         let mut output = format!(
-            "Found {} files matching '{}':\n\n",
+            "Found {} files matching '{}' (most recently modified first):\n\n",
             matches.len(),
             input.pattern
         );
 
-        for path in &matches {
+        for (path, _) in matches.iter().take(limit) {
             // Make path relative to base_dir for cleaner output
             let display_path = path
                 .strip_prefix(&base_dir)
@@ -176,11 +178,162 @@ impl Tool for GlobTool {
             output.push_str(&format!("  {}\n", display_path));
         }
 
-        if let Some(limit) = input.limit
-            && matches.len() >= limit {
-                output.push_str(&format!("\n(Limited to {} results)", limit));
-            }
+        if matches.len() > limit {
+            output.push_str(&format!(
+                "\n[truncated: showing {} of {} files. Narrow the pattern or raise limit]",
+                limit,
+                matches.len()
+            ));
+        }
 
         Ok(ToolResult::success(output))
     }
 }
+
+/// Split a pattern into the directory named by its leading literal
+/// components and the glob for paths below it
+pub(super) fn split_pattern(base_dir: &Path, pattern: &str) -> (PathBuf, String) {
+    let is_glob = |part: &str| part.contains(['*', '?', '[', '{']);
+    let parts: Vec<&str> = pattern.split('/').collect();
+    let literal = parts
+        .iter()
+        .position(|part| is_glob(part))
+        .unwrap_or(parts.len() - 1);
+
+    let prefix = parts[..literal].join("/");
+    let root = if prefix.is_empty() && pattern.starts_with('/') {
+        PathBuf::from("/")
+    } else {
+        base_dir.join(prefix)
+    };
+    (root, parts[literal..].join("/"))
+}
+
+/// Files under `root` whose path relative to it matches, with their mtimes
+fn find_files(
+    root: &Path,
+    matcher: &GlobMatcher,
+    max_depth: Option<usize>,
+    include_hidden: bool,
+    include_ignored: bool,
+) -> Vec<(PathBuf, SystemTime)> {
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .standard_filters(!include_ignored)
+        .hidden(!include_hidden)
+        .require_git(false)
+        .max_depth(max_depth);
+
+    let found = Mutex::new(Vec::new());
+    let found_ref = &found;
+    builder.build_parallel().run(move || {
+        Box::new(move |entry| {
+            let Ok(entry) = entry else {
+                return WalkState::Continue;
+            };
+            if entry.file_type().is_some_and(|t| t.is_file())
+                && entry
+                    .path()
+                    .strip_prefix(root)
+                    .is_ok_and(|relative| matcher.is_match(relative))
+            {
+                let modified = entry
+                    .metadata()
+                    .ok()
+                    .and_then(|m| m.modified().ok())
+                    .unwrap_or(SystemTime::UNIX_EPOCH);
+                found_ref
+                    .lock()
+                    .expect("glob results lock poisoned")
+                    .push((entry.path().to_path_buf(), modified));
+            }
+            WalkState::Continue
+        })
+    });
+    found.into_inner().expect("glob results lock poisoned")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::time::Duration;
+    use tempfile::TempDir;
+    use uuid::Uuid;
+
+    fn write(dir: &TempDir, path: &str, age_secs: u64) {
+        let path = dir.path().join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let file = std::fs::File::create(path).unwrap();
+        file.set_modified(SystemTime::now() - Duration::from_secs(age_secs))
+            .unwrap();
+    }
+
+    async fn glob(dir: &TempDir, input: Value) -> String {
+        let context = ToolExecutionContext::new(Uuid::new_v4())
+            .with_working_directory(dir.path().to_path_buf());
+        let result = GlobTool.execute(input, &context).await.unwrap();
+        assert!(result.success, "{:?}", result.error);
+        result.output
+    }
+
+    #[test]
+    fn test_split_pattern() {
+        let base = Path::new("/work");
+        assert_eq!(
+            split_pattern(base, "src/**/*.rs"),
+            (PathBuf::from("/work/src"), "**/*.rs".to_string())
+        );
+        assert_eq!(
+            split_pattern(base, "*.{md,txt}"),
+            (PathBuf::from("/work"), "*.{md,txt}".to_string())
+        );
+        assert_eq!(
+            split_pattern(base, "docs/README.md"),
+            (PathBuf::from("/work/docs"), "README.md".to_string())
+        );
+        assert_eq!(
+            split_pattern(base, "/etc/*.conf"),
+            (PathBuf::from("/etc"), "*.conf".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_newest_first_and_gitignore() {
+        let dir = TempDir::new().unwrap();
+        write(&dir, ".gitignore", 0);
+        std::fs::write(dir.path().join(".gitignore"), "target/\n").unwrap();
+        write(&dir, "src/old.rs", 300);
+        write(&dir, "src/nested/new.rs", 10);
+        write(&dir, "src/notes.md", 0);
+        write(&dir, "target/debug/build.rs", 0);
+
+        let out = glob(&dir, json!({"pattern": "**/*.rs"})).await;
+        let new = out.find("new.rs").unwrap();
+        let old = out.find("old.rs").unwrap();
+        assert!(new < old, "{}", out);
+        assert!(!out.contains("build.rs"));
+        assert!(!out.contains("notes.md"));
+
+        // `*` stays within one directory
+        let out = glob(&dir, json!({"pattern": "src/*.rs"})).await;
+        assert!(out.contains("old.rs") && !out.contains("new.rs"));
+
+        let out = glob(&dir, json!({"pattern": "**/*.rs", "include_ignored": true})).await;
+        assert!(out.contains("build.rs"));
+    }
+
+    #[tokio::test]
+    async fn test_results_are_capped() {
+        let dir = TempDir::new().unwrap();
+        for i in 0..5 {
+            write(&dir, &format!("file{}.txt", i), i);
+        }
+
+        let out = glob(&dir, json!({"pattern": "*.txt", "limit": 2})).await;
+        assert!(out.contains("Found 5 files"));
+        assert!(out.contains("file0.txt") && out.contains("file1.txt"));
+        assert!(!out.contains("file4.txt"));
+        assert!(out.contains("[truncated: showing 2 of 5 files"));
+    }
+}
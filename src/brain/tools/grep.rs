@@ -1,20 +1,35 @@
 //! Grep Content Search Tool
 //!
-//! Search file contents for matching patterns.
+//! Search file contents for matching patterns. Directories are walked in
+//! parallel by the `ignore` crate, which honours `.gitignore`, `.ignore` and
+//! hidden files the way ripgrep does.
 
 use super::error::{Result, ToolError};
 use super::r#trait::{Tool, ToolCapability, ToolExecutionContext, ToolResult};
 use async_trait::async_trait;
+use ignore::types::{Types, TypesBuilder};
+use ignore::{WalkBuilder, WalkState};
+use regex::{Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::path::{Path, PathBuf};
-use tokio::fs;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
-/// Directories that are almost never useful to grep through.
-const SKIP_DIRS: &[&str] = &[
-    "target", "node_modules", ".git", "dist", "build", "__pycache__",
-    ".mypy_cache", ".tox", ".eggs", "vendor", ".bundle",
-];
+/// Results returned when `limit` is not given
+const DEFAULT_LIMIT: usize = 200;
+
+/// The walk stops once this many matches are collected
+const MAX_COLLECTED_MATCHES: usize = 10_000;
+
+/// Larger files are skipped
+const MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Longer lines (minified code, data files) are cut short
+const MAX_LINE_CHARS: usize = 500;
+
+/// Output beyond this is dropped, whatever the limit
+const MAX_OUTPUT_CHARS: usize = 30_000;
 
 /// Grep search tool
 pub struct GrepTool;
@@ -36,6 +51,10 @@ struct GrepInput {
     #[serde(default)]
     case_insensitive: bool,
 
+    /// Let the pattern span lines (`.` matches newlines)
+    #[serde(default)]
+    multiline: bool,
+
     /// Show line numbers
     #[serde(default = "default_true")]
     line_numbers: bool,
@@ -48,11 +67,44 @@ struct GrepInput {
     #[serde(default)]
     file_pattern: Option<String>,
 
-    /// Maximum number of matches to return
+    /// File type to filter (e.g., "rust", "py")
+    #[serde(default, rename = "type")]
+    file_type: Option<String>,
+
+    /// What to return
+    #[serde(default)]
+    output_mode: OutputMode,
+
+    /// Also search gitignored, hidden and dependency/build directories
+    #[serde(default)]
+    include_ignored: bool,
+
+    /// Maximum number of results to return
     #[serde(default)]
     limit: Option<usize>,
 }
 
+/// What a search returns
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum OutputMode {
+    /// Matching lines with optional context
+    #[default]
+    Content,
+    /// Paths of files with at least one match
+    FilesWithMatches,
+    /// Match count per file
+    Count,
+}
+
+/// Matches found in one file
+struct FileMatches {
+    path: PathBuf,
+    count: usize,
+    /// Formatted matches (content mode only)
+    blocks: Vec<String>,
+}
+
 fn default_true() -> bool {
     true
 }
@@ -64,7 +116,9 @@ impl Tool for GrepTool {
     }
 
     fn description(&self) -> &str {
-        "Search for patterns in file contents. Supports literal string or regex search with context lines."
+        "Search for patterns in file contents. Skips gitignored and hidden files, like glob. \
+         Supports literal or regex search, multiline patterns, file type filters, context lines, \
+         and returning matching lines, matching file paths or per-file counts."
     }
 
     fn input_schema(&self) -> Value {
@@ -89,6 +143,11 @@ impl Tool for GrepTool {
                     "description": "Case insensitive search",
                     "default": false
                 },
+                "multiline": {
+                    "type": "boolean",
+                    "description": "Let the pattern match across lines ('.' matches newlines)",
+                    "default": false
+                },
                 "line_numbers": {
                     "type": "boolean",
                     "description": "Show line numbers in results",
@@ -101,11 +160,26 @@ impl Tool for GrepTool {
                 },
                 "file_pattern": {
                     "type": "string",
-                    "description": "File pattern to filter (e.g., '*.rs', '*.{js,ts}')"
+                    "description": "File name pattern to filter (e.g., '*.rs', '*.{js,ts}')"
+                },
+                "type": {
+                    "type": "string",
+                    "description": "File type to filter, using ripgrep's type names (e.g., 'rust', 'py', 'js', 'ts', 'go', 'md')"
+                },
+                "output_mode": {
+                    "type": "string",
+                    "enum": ["content", "files_with_matches", "count"],
+                    "description": "'content' for matching lines (default), 'files_with_matches' for file paths only, 'count' for matches per file",
+                    "default": "content"
+                },
+                "include_ignored": {
+                    "type": "boolean",
+                    "description": "Also search gitignored and hidden files and directories like target/ and node_modules/",
+                    "default": false
                 },
                 "limit": {
                     "type": "integer",
-                    "description": "Maximum number of matches to return",
+                    "description": "Maximum number of matches (or files, for files_with_matches and count) to return (default: 200)",
                     "minimum": 1
                 }
             },
@@ -135,12 +209,8 @@ impl Tool for GrepTool {
     }
 
     async fn execute(&self, input: Value, context: &ToolExecutionContext) -> Result<ToolResult> {
-        let mut input: GrepInput = serde_json::from_value(input)?;
-
-        // Default limit to prevent runaway searches
-        if input.limit.is_none() {
-            input.limit = Some(200);
-        }
+        let input: GrepInput = serde_json::from_value(input)?;
+        let limit = input.limit.unwrap_or(DEFAULT_LIMIT).max(1);
 
         // Build regex pattern
         let pattern_str = if input.regex {
@@ -149,14 +219,38 @@ impl Tool for GrepTool {
             regex::escape(&input.pattern)
         };
 
-        let regex = if input.case_insensitive {
-            regex::RegexBuilder::new(&pattern_str)
-                .case_insensitive(true)
-                .build()
-        } else {
-            regex::Regex::new(&pattern_str)
-        }
-        .map_err(|e| ToolError::InvalidInput(format!("Invalid pattern: {}", e)))?;
+        let regex = RegexBuilder::new(&pattern_str)
+            .case_insensitive(input.case_insensitive)
+            .multi_line(input.multiline)
+            .dot_matches_new_line(input.multiline)
+            .build()
+            .map_err(|e| ToolError::InvalidInput(format!("Invalid pattern: {}", e)))?;
+
+        let file_types = match input.file_type {
+            Some(ref name) => {
+                let mut types = TypesBuilder::new();
+                types.add_defaults().select(name);
+                Some(types.build().map_err(|e| {
+                    ToolError::InvalidInput(format!(
+                        "Invalid type: {} (use ripgrep type names like rust, py, js, ts, go)",
+                        e
+                    ))
+                })?)
+            }
+            None => None,
+        };
+        let file_pattern = match input.file_pattern {
+            Some(ref pattern) => {
+                let invalid = |e: ignore::Error| {
+                    ToolError::InvalidInput(format!("Invalid file pattern: {}", e))
+                };
+                let mut types = TypesBuilder::new();
+                types.add("pattern", pattern).map_err(invalid)?;
+                types.select("pattern");
+                Some(types.build().map_err(invalid)?)
+            }
+            None => None,
+        };
 
         // Resolve search path
         let search_path = if let Some(ref p) = input.path {
@@ -176,166 +270,359 @@ impl Tool for GrepTool {
             )));
         }
 
-        let mut matches = Vec::new();
-        let mut total_matches = 0;
-
-        if search_path.is_file() {
-            self.search_file(
-                &search_path,
-                &regex,
-                &input,
-                &mut matches,
-                &mut total_matches,
-            )
-            .await?;
-        } else {
-            self.search_directory(
-                &search_path,
-                &regex,
-                &input,
-                &mut matches,
-                &mut total_matches,
-            )
-            .await?;
-        }
+        let pattern = input.pattern.clone();
+        let mode = input.output_mode;
+        let (mut results, stopped_early) = tokio::task::spawn_blocking(move || {
+            if search_path.is_file() {
+                // An explicitly named file is searched whatever the filters say
+                let found = search_file(&search_path, &regex, &input);
+                (found.into_iter().collect(), false)
+            } else {
+                search_tree(&search_path, &regex, &input, file_types, file_pattern)
+            }
+        })
+        .await
+        .map_err(|e| ToolError::Internal(format!("Search task failed: {}", e)))?;
 
-        if matches.is_empty() {
+        if results.is_empty() {
             return Ok(ToolResult::success(format!(
                 "No matches found for pattern: '{}'",
-                input.pattern
+                pattern
             )));
         }
 
-        let output = matches.join("\n\n");
-        let summary = if let Some(_limit) = input.limit {
-            if total_matches > matches.len() {
-                format!(
-                    "\n\n({} matches shown, {} total)",
-                    matches.len(),
-                    total_matches
-                )
-            } else {
-                format!("\n\n({} matches)", total_matches)
-            }
-        } else {
-            format!("\n\n({} matches)", total_matches)
-        };
-
-        Ok(ToolResult::success(format!("{}{}", output, summary)))
+        results.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(ToolResult::success(render(
+            &results,
+            mode,
+            limit,
+            stopped_early,
+        )))
     }
 }
 
-impl GrepTool {
-    async fn search_file(
-        &self,
-        path: &Path,
-        regex: &regex::Regex,
-        input: &GrepInput,
-        matches: &mut Vec<String>,
-        total_matches: &mut usize,
-    ) -> Result<()> {
-        // Check file pattern filter
-        if let Some(ref pattern) = input.file_pattern {
-            let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-            let glob_pattern = glob::Pattern::new(pattern)
-                .map_err(|e| ToolError::InvalidInput(format!("Invalid file pattern: {}", e)))?;
-
-            if !glob_pattern.matches(file_name) {
-                return Ok(());
+/// Walk `root` in parallel and search every file the filters let through.
+/// Returns the matches and whether the walk stopped at `MAX_COLLECTED_MATCHES`.
+fn search_tree(
+    root: &Path,
+    regex: &Regex,
+    input: &GrepInput,
+    file_types: Option<Types>,
+    file_pattern: Option<Types>,
+) -> (Vec<FileMatches>, bool) {
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .standard_filters(!input.include_ignored)
+        .require_git(false);
+    if let Some(types) = file_types {
+        builder.types(types);
+    }
+    // Only `.gitignore` decides what else is skipped, but git's own
+    // database is never worth searching, even with hidden files included
+    builder.filter_entry(|entry| {
+        entry.depth() == 0
+            || !entry.file_type().is_some_and(|t| t.is_dir())
+            || entry.file_name() != ".git"
+    });
+
+    let found = Mutex::new(Vec::new());
+    let collected = AtomicUsize::new(0);
+    let stopped = AtomicBool::new(false);
+    // Each worker thread gets a visitor holding copies of these references
+    let (found_ref, collected_ref, stopped_ref) = (&found, &collected, &stopped);
+    let file_pattern = file_pattern.as_ref();
+
+    builder.build_parallel().run(move || {
+        Box::new(move |entry| {
+            let Ok(entry) = entry else {
+                return WalkState::Continue;
+            };
+            if !entry.file_type().is_some_and(|t| t.is_file())
+                || file_pattern.is_some_and(|p| !p.matched(entry.path(), false).is_whitelist())
+                || entry.metadata().is_ok_and(|m| m.len() > MAX_FILE_BYTES)
+            {
+                return WalkState::Continue;
             }
-        }
 
-        let content = match fs::read_to_string(path).await {
-            Ok(c) => c,
-            Err(_) => return Ok(()), // Skip binary files or unreadable files
+            let Some(matches) = search_file(entry.path(), regex, input) else {
+                return WalkState::Continue;
+            };
+            let total = collected_ref.fetch_add(matches.count, Ordering::Relaxed) + matches.count;
+            found_ref
+                .lock()
+                .expect("grep results lock poisoned")
+                .push(matches);
+            if total >= MAX_COLLECTED_MATCHES {
+                stopped_ref.store(true, Ordering::Relaxed);
+                return WalkState::Quit;
+            }
+            WalkState::Continue
+        })
+    });
+
+    let found = found.into_inner().expect("grep results lock poisoned");
+    (found, stopped.load(Ordering::Relaxed))
+}
+
+/// Search one file. Binary, non-UTF-8 and unreadable files have no matches.
+fn search_file(path: &Path, regex: &Regex, input: &GrepInput) -> Option<FileMatches> {
+    let bytes = std::fs::read(path).ok()?;
+    if bytes[..bytes.len().min(8192)].contains(&0) {
+        return None;
+    }
+    let content = String::from_utf8(bytes).ok()?;
+    let lines: Vec<&str> = content.lines().collect();
+
+    // First and last line of each match
+    let spans: Vec<(usize, usize)> = if input.multiline {
+        let line_starts: Vec<usize> = std::iter::once(0)
+            .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        let line_of = |offset: usize| {
+            (line_starts.partition_point(|&start| start <= offset) - 1)
+                .min(lines.len().saturating_sub(1))
         };
+        regex
+            .find_iter(&content)
+            .map(|m| {
+                let last_byte = m.end().saturating_sub(1).max(m.start());
+                (line_of(m.start()), line_of(last_byte))
+            })
+            .collect()
+    } else {
+        lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| regex.is_match(line))
+            .map(|(i, _)| (i, i))
+            .collect()
+    };
+    if spans.is_empty() {
+        return None;
+    }
+
+    let blocks = if input.output_mode == OutputMode::Content {
+        spans
+            .iter()
+            .map(|&(first, last)| format_match(path, &lines, first, last, input))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    Some(FileMatches {
+        path: path.to_path_buf(),
+        count: spans.len(),
+        blocks,
+    })
+}
+
+/// Format one match spanning `first..=last` with its context lines
+fn format_match(
+    path: &Path,
+    lines: &[&str],
+    first: usize,
+    last: usize,
+    input: &GrepInput,
+) -> String {
+    let mut result = format!("{}:", path.display());
+    if input.line_numbers {
+        result.push_str(&format!("{}:", first + 1));
+    }
 
-        let lines: Vec<&str> = content.lines().collect();
-        let display_path = path.display().to_string();
+    let ctx = input.context.unwrap_or(0);
+    let start = first.saturating_sub(ctx);
+    let end = (last + ctx + 1).min(lines.len());
+    for (i, line) in lines.iter().enumerate().take(end).skip(start) {
+        let line = clip_line(line);
+        if (first..=last).contains(&i) {
+            result.push_str(&format!("\n> {}", line));
+        } else {
+            result.push_str(&format!("\n  {}: {}", i + 1, line));
+        }
+    }
+    result
+}
 
-        for (line_num, line) in lines.iter().enumerate() {
-            if regex.is_match(line) {
-                *total_matches += 1;
+fn clip_line(line: &str) -> String {
+    if line.chars().count() > MAX_LINE_CHARS {
+        let cut: String = line.chars().take(MAX_LINE_CHARS).collect();
+        format!("{}…", cut)
+    } else {
+        line.to_string()
+    }
+}
 
-                // Check limit
-                if let Some(limit) = input.limit
-                    && matches.len() >= limit {
-                        return Ok(());
-                    }
+/// Render sorted results for the output mode, capped at `limit` entries
+fn render(results: &[FileMatches], mode: OutputMode, limit: usize, stopped_early: bool) -> String {
+    let total_matches: usize = results.iter().map(|f| f.count).sum();
+    let (entries, unit): (Vec<String>, &str) = match mode {
+        OutputMode::Content => (
+            results
+                .iter()
+                .flat_map(|f| f.blocks.iter().cloned())
+                .collect(),
+            "matches",
+        ),
+        OutputMode::FilesWithMatches => (
+            results
+                .iter()
+                .map(|f| f.path.display().to_string())
+                .collect(),
+            "files",
+        ),
+        OutputMode::Count => (
+            results
+                .iter()
+                .map(|f| format!("{}:{}", f.path.display(), f.count))
+                .collect(),
+            "files",
+        ),
+    };
+    let separator = if mode == OutputMode::Content {
+        "\n\n"
+    } else {
+        "\n"
+    };
+
+    let mut output = String::new();
+    let mut shown = 0;
+    for entry in entries.iter().take(limit) {
+        if !output.is_empty() && output.len() + entry.len() > MAX_OUTPUT_CHARS {
+            break;
+        }
+        if !output.is_empty() {
+            output.push_str(separator);
+        }
+        output.push_str(entry);
+        shown += 1;
+    }
 
-                let mut result = String::new();
-                result.push_str(&format!("{}:", display_path));
+    if shown < entries.len() || stopped_early {
+        output.push_str(&format!(
+            "\n\n[truncated: showing {} of {}{} {}. Narrow the pattern, path or type, or raise limit]",
+            shown,
+            entries.len(),
+            if stopped_early { "+" } else { "" },
+            unit
+        ));
+    } else {
+        output.push_str(&format!(
+            "\n\n({} matches in {} files)",
+            total_matches,
+            results.len()
+        ));
+    }
+    output
+}
 
-                if input.line_numbers {
-                    result.push_str(&format!("{}:", line_num + 1));
-                }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+    use uuid::Uuid;
+
+    async fn grep(dir: &TempDir, input: Value) -> String {
+        let context = ToolExecutionContext::new(Uuid::new_v4())
+            .with_working_directory(dir.path().to_path_buf());
+        let result = GrepTool.execute(input, &context).await.unwrap();
+        assert!(result.success, "{:?}", result.error);
+        result.output
+    }
 
-                // Add context before
-                if let Some(ctx) = input.context {
-                    let start = line_num.saturating_sub(ctx);
-                    for (i, line) in lines.iter().enumerate().skip(start).take(line_num - start) {
-                        result.push_str(&format!("\n  {}: {}", i + 1, line));
-                    }
-                }
+    fn write(dir: &TempDir, path: &str, content: &str) {
+        let path = dir.path().join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
 
-                // Add matching line
-                result.push_str(&format!("\n> {}", line));
-
-                // Add context after
-                if let Some(ctx) = input.context {
-                    let end = (line_num + ctx + 1).min(lines.len());
-                    for (i, line) in lines
-                        .iter()
-                        .enumerate()
-                        .skip(line_num + 1)
-                        .take(end - line_num - 1)
-                    {
-                        result.push_str(&format!("\n  {}: {}", i + 1, line));
-                    }
-                }
+    #[tokio::test]
+    async fn test_skips_ignored_files() {
+        let dir = TempDir::new().unwrap();
+        write(&dir, ".gitignore", "generated.rs\ntarget/\nnode_modules/\n");
+        write(&dir, "src/lib.rs", "fn needle() {}\n");
+        write(&dir, "generated.rs", "fn needle() {}\n");
+        write(&dir, "target/debug/out.rs", "fn needle() {}\n");
+        write(&dir, "node_modules/pkg/index.js", "needle()\n");
+        // Not ignored, so searched like any other source
+        write(&dir, "vendor/dep/dep.go", "func needle() {}\n");
+        write(&dir, "build/gen.py", "needle()\n");
+        write(&dir, ".git/HEAD", "needle\n");
+
+        let out = grep(
+            &dir,
+            json!({"pattern": "needle", "output_mode": "files_with_matches"}),
+        )
+        .await;
+        assert!(out.contains("lib.rs"));
+        assert!(out.contains("dep.go"));
+        assert!(out.contains("gen.py"));
+        assert!(!out.contains("generated.rs"));
+        assert!(!out.contains("out.rs"));
+        assert!(!out.contains("index.js"));
+
+        let out = grep(
+            &dir,
+            json!({"pattern": "needle", "output_mode": "files_with_matches", "include_ignored": true}),
+        )
+        .await;
+        assert!(out.contains("generated.rs"));
+        assert!(out.contains("index.js"));
+        assert!(!out.contains("HEAD"));
+    }
 
-                matches.push(result);
-            }
-        }
+    #[tokio::test]
+    async fn test_type_filter_and_count_mode() {
+        let dir = TempDir::new().unwrap();
+        write(&dir, "a.rs", "needle\nneedle\n");
+        write(&dir, "b.py", "needle\n");
+
+        let out = grep(
+            &dir,
+            json!({"pattern": "needle", "type": "rust", "output_mode": "count"}),
+        )
+        .await;
+        assert!(out.contains("a.rs:2"));
+        assert!(!out.contains("b.py"));
+
+        let out = grep(
+            &dir,
+            json!({"pattern": "needle", "file_pattern": "*.{py,txt}"}),
+        )
+        .await;
+        assert!(out.contains("b.py:1:"));
+        assert!(!out.contains("a.rs"));
+
+        let context = ToolExecutionContext::new(Uuid::new_v4())
+            .with_working_directory(dir.path().to_path_buf());
+        let err = GrepTool
+            .execute(json!({"pattern": "needle", "type": "nonsense"}), &context)
+            .await;
+        assert!(matches!(err, Err(ToolError::InvalidInput(_))));
+    }
 
-        Ok(())
+    #[tokio::test]
+    async fn test_multiline_match() {
+        let dir = TempDir::new().unwrap();
+        write(&dir, "lib.rs", "// head\nfn foo(\n    x: i32,\n) {}\n");
+
+        let out = grep(
+            &dir,
+            json!({"pattern": r"fn foo\(.*?\)", "regex": true, "multiline": true}),
+        )
+        .await;
+        assert!(out.contains("lib.rs:2:\n> fn foo(\n>     x: i32,\n> ) {}"));
+        assert!(!out.contains("// head"));
+        assert!(out.contains("(1 matches in 1 files)"));
     }
 
-    fn search_directory<'a>(
-        &'a self,
-        dir: &'a PathBuf,
-        regex: &'a regex::Regex,
-        input: &'a GrepInput,
-        matches: &'a mut Vec<String>,
-        total_matches: &'a mut usize,
-    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + 'a>> {
-        Box::pin(async move {
-            let mut entries = fs::read_dir(dir).await.map_err(ToolError::Io)?;
-
-            while let Some(entry) = entries.next_entry().await.map_err(ToolError::Io)? {
-                let path = entry.path();
-
-                // Check limit
-                if let Some(limit) = input.limit
-                    && matches.len() >= limit {
-                        return Ok(());
-                    }
-
-                if path.is_file() {
-                    self.search_file(&path, regex, input, matches, total_matches)
-                        .await?;
-                } else if path.is_dir() {
-                    // Skip hidden and heavy directories
-                    if let Some(name) = path.file_name().and_then(|n| n.to_str())
-                        && (name.starts_with('.') || SKIP_DIRS.contains(&name)) {
-                            continue;
-                        }
-                    self.search_directory(&path, regex, input, matches, total_matches)
-                        .await?;
-                }
-            }
+    #[tokio::test]
+    async fn test_results_are_capped() {
+        let dir = TempDir::new().unwrap();
+        write(&dir, "a.txt", &"needle\n".repeat(5));
 
-            Ok(())
-        })
+        let out = grep(&dir, json!({"pattern": "needle", "limit": 2})).await;
+        assert_eq!(out.matches("> needle").count(), 2);
+        assert!(out.contains("[truncated: showing 2 of 5 matches"));
     }
 }
@@ -4,7 +4,11 @@
 //! including file operations, shell commands, and more.
 
 pub mod error;
+pub mod permissions;
 pub mod registry;
+pub mod sandbox;
+#[cfg(unix)]
+mod shell;
 mod r#trait;
 
 // Tool implementations - Phase 1: Essential File Operations
@@ -25,12 +29,16 @@ pub mod notebook;
 pub mod web_search;
 
 // Tool implementations - Phase 3: Workflow & Integration
+pub mod a2a_delegate;
 pub mod config_tool;
 pub mod context;
+pub mod debate;
 pub mod http;
 pub mod memory_search;
 pub mod plan_tool;
+pub mod process;
 pub mod rebuild;
+pub mod schedule;
 pub mod session_search;
 pub mod slash_command;
 pub mod task;
@@ -52,8 +60,11 @@ pub mod discord_send;
 pub mod slack_connect;
 #[cfg(feature = "slack")]
 pub mod slack_send;
+#[cfg(feature = "signal")]
+pub mod signal_send;
 
 // Re-exports
 pub use error::{Result, ToolError};
+pub use permissions::{Permission, PermissionRules};
 pub use r#trait::{Tool, ToolCapability, ToolExecutionContext, ToolResult};
 pub use registry::ToolRegistry;
@@ -199,6 +199,7 @@ impl Tool for NotebookEditTool {
         // Create backup if requested
         if input.create_backup {
             let backup_path = path.with_extension("ipynb.backup");
+            context.snapshot_file(&backup_path).await;
             fs::write(&backup_path, &content)
                 .await
                 .map_err(ToolError::Io)?;
@@ -312,6 +313,7 @@ impl Tool for NotebookEditTool {
         let new_content = serde_json::to_string_pretty(&notebook)
             .map_err(|e| ToolError::Execution(format!("Failed to serialize notebook: {}", e)))?;
 
+        context.snapshot_file(&path).await;
         fs::write(&path, new_content).await.map_err(ToolError::Io)?;
 
         Ok(ToolResult::success(format!(
@@ -0,0 +1,1046 @@
+//! Process Tool
+//!
+//! Runs long-lived commands — dev servers, `cargo watch`, long test suites —
+//! in the background so the agent can keep working while they run. Each
+//! process gets a handle; `output` returns what it printed since the last
+//! read. Processes belong to the session that started them and are killed
+//! when that session is deleted or OpenCrabs exits.
+
+use super::error::{Result, ToolError};
+use super::sandbox::{SandboxRun, ScratchDir};
+use super::r#trait::{Tool, ToolCapability, ToolExecutionContext, ToolResult};
+use anyhow::Context;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::process::{Child, ChildStdin, Command};
+use tokio::sync::{oneshot, watch};
+use tokio::task::JoinHandle;
+use tokio::time::{Duration, timeout};
+use uuid::Uuid;
+
+/// Processes a session may have running at once
+const MAX_RUNNING_PER_SESSION: usize = 8;
+
+/// Finished processes kept per session so their output can still be read
+const MAX_FINISHED_PER_SESSION: usize = 8;
+
+/// Bytes kept per stream; older output is dropped
+const MAX_BUFFER_BYTES: usize = 512 * 1024;
+
+/// Lines returned per stream by `output` unless `tail` is given
+const DEFAULT_TAIL_LINES: usize = 200;
+
+/// Longest text returned per stream by `output`
+const MAX_OUTPUT_CHARS: usize = 30_000;
+
+/// Lines of recent output in a `ProcessInfo`
+const RECENT_LINES: usize = 5;
+
+/// How long `start` waits to catch early output or an immediate failure
+const STARTUP_WAIT: Duration = Duration::from_secs(1);
+
+/// How long a process gets to exit after SIGTERM before it is killed
+const KILL_GRACE: Duration = Duration::from_secs(3);
+
+/// Where a background process is at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessStatus {
+    Running,
+    /// Exited on its own (-1 when ended by a signal)
+    Exited(i32),
+    /// Stopped by `kill`, session cleanup or shutdown
+    Killed,
+}
+
+/// Snapshot of a background process, for listings and the TUI panel
+#[derive(Debug, Clone)]
+pub struct ProcessInfo {
+    pub handle: String,
+    pub session_id: Uuid,
+    pub command: String,
+    pub cwd: PathBuf,
+    pub pid: Option<u32>,
+    pub status: ProcessStatus,
+    /// Time since start, or how long it ran once finished
+    pub runtime: Duration,
+    /// Last non-empty lines of output, both streams interleaved
+    pub recent: Vec<String>,
+}
+
+impl ProcessInfo {
+    /// Status as shown to the agent and in the panel, e.g. "running 2m 5s"
+    pub fn status_label(&self) -> String {
+        let runtime = format_runtime(self.runtime);
+        match self.status {
+            ProcessStatus::Running => format!("running {}", runtime),
+            ProcessStatus::Exited(code) => format!("exited with code {} after {}", code, runtime),
+            ProcessStatus::Killed => format!("killed after {}", runtime),
+        }
+    }
+}
+
+/// Background processes of all sessions
+pub struct ProcessManager {
+    processes: Mutex<Vec<Arc<ManagedProcess>>>,
+    next_id: AtomicU64,
+}
+
+impl Default for ProcessManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProcessManager {
+    pub fn new() -> Self {
+        Self {
+            processes: Mutex::new(Vec::new()),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Processes of one session (or all with `None`), oldest first
+    pub fn list(&self, session_id: Option<Uuid>) -> Vec<ProcessInfo> {
+        self.processes
+            .lock()
+            .expect("process table lock poisoned")
+            .iter()
+            .filter(|p| session_id.is_none_or(|id| p.session_id == id))
+            .map(|p| p.info())
+            .collect()
+    }
+
+    /// Stop a process by handle. Returns its final state, or `None` if there
+    /// is no such process.
+    pub async fn kill(&self, handle: &str) -> Option<ProcessInfo> {
+        let process = self.find(None, handle)?;
+        process.stop().await;
+        Some(process.info())
+    }
+
+    /// Stop a session's processes and forget them (the session has ended).
+    /// Returns how many were still running.
+    pub async fn kill_session(&self, session_id: Uuid) -> usize {
+        let processes: Vec<_> = self
+            .processes
+            .lock()
+            .expect("process table lock poisoned")
+            .iter()
+            .filter(|p| p.session_id == session_id)
+            .cloned()
+            .collect();
+        let stopped = stop_all(&processes).await;
+
+        self.processes
+            .lock()
+            .expect("process table lock poisoned")
+            .retain(|p| p.session_id != session_id);
+        if stopped > 0 {
+            tracing::info!(
+                "Stopped {} background process(es) of session {}",
+                stopped,
+                session_id
+            );
+        }
+        stopped
+    }
+
+    /// Stop every running process (OpenCrabs is exiting)
+    pub async fn kill_all(&self) -> usize {
+        let processes = self
+            .processes
+            .lock()
+            .expect("process table lock poisoned")
+            .clone();
+        let stopped = stop_all(&processes).await;
+        if stopped > 0 {
+            tracing::info!("Stopped {} background process(es) on exit", stopped);
+        }
+        stopped
+    }
+
+    /// Spawn `cmd`, which runs `command` in `cwd`. A sandboxed command brings
+    /// its scratch directory, kept until the process is forgotten.
+    async fn start(
+        &self,
+        session_id: Uuid,
+        mut cmd: Command,
+        command: &str,
+        cwd: &Path,
+        scratch: Option<ScratchDir>,
+    ) -> anyhow::Result<Arc<ManagedProcess>> {
+        let running = self
+            .list(Some(session_id))
+            .iter()
+            .filter(|p| p.status == ProcessStatus::Running)
+            .count();
+        if running >= MAX_RUNNING_PER_SESSION {
+            anyhow::bail!(
+                "This session already has {} background processes running. Kill one first.",
+                running
+            );
+        }
+
+        cmd.stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        // Own process group, so kill reaches whatever the command spawned
+        #[cfg(unix)]
+        cmd.process_group(0);
+        let mut child = cmd
+            .spawn()
+            .with_context(|| format!("Failed to start '{}'", command))?;
+
+        let output = Arc::new(Mutex::new(Output::default()));
+        let mut readers = Vec::new();
+        if let Some(stdout) = child.stdout.take() {
+            readers.push(spawn_reader(stdout, output.clone(), false));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            readers.push(spawn_reader(stderr, output.clone(), true));
+        }
+
+        let (exit_tx, exit_rx) = watch::channel(None);
+        let (kill_tx, kill_rx) = oneshot::channel();
+        let process = Arc::new(ManagedProcess {
+            handle: format!("p{}", self.next_id.fetch_add(1, Ordering::Relaxed)),
+            session_id,
+            command: command.to_string(),
+            cwd: cwd.to_path_buf(),
+            pid: child.id(),
+            started_at: Instant::now(),
+            output,
+            stdin: tokio::sync::Mutex::new(child.stdin.take()),
+            exit: exit_rx,
+            kill: Mutex::new(Some(kill_tx)),
+            _scratch: scratch,
+        });
+        tokio::spawn(wait_for_exit(child, readers, kill_rx, exit_tx));
+
+        {
+            let mut processes = self.processes.lock().expect("process table lock poisoned");
+            processes.push(process.clone());
+
+            // Forget the oldest finished processes beyond the limit
+            let finished: Vec<String> = processes
+                .iter()
+                .filter(|p| p.session_id == session_id && p.status() != ProcessStatus::Running)
+                .map(|p| p.handle.clone())
+                .collect();
+            let excess = finished.len().saturating_sub(MAX_FINISHED_PER_SESSION);
+            processes.retain(|p| !finished[..excess].contains(&p.handle));
+        }
+
+        tracing::info!(
+            "Started background process {} (pid {:?}) in session {}: {}",
+            process.handle,
+            process.pid,
+            session_id,
+            command
+        );
+        Ok(process)
+    }
+
+    /// Find a process by handle, limited to one session if given
+    fn find(&self, session_id: Option<Uuid>, handle: &str) -> Option<Arc<ManagedProcess>> {
+        self.processes
+            .lock()
+            .expect("process table lock poisoned")
+            .iter()
+            .find(|p| p.handle == handle && session_id.is_none_or(|id| p.session_id == id))
+            .cloned()
+    }
+}
+
+impl Drop for ProcessManager {
+    fn drop(&mut self) {
+        // The waiter tasks may never run again at shutdown, so signal the
+        // process groups directly
+        #[cfg(unix)]
+        {
+            let processes = self
+                .processes
+                .get_mut()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            for process in processes.iter() {
+                if process.status() == ProcessStatus::Running
+                    && let Some(pid) = process.pid
+                {
+                    let _ = std::process::Command::new("kill")
+                        .args(["-s", "KILL", "--", &format!("-{}", pid)])
+                        .stderr(Stdio::null())
+                        .status();
+                }
+            }
+        }
+    }
+}
+
+/// A process started by the tool
+struct ManagedProcess {
+    handle: String,
+    session_id: Uuid,
+    command: String,
+    cwd: PathBuf,
+    pid: Option<u32>,
+    started_at: Instant,
+    output: Arc<Mutex<Output>>,
+    /// `None` once closed with `close_stdin`
+    stdin: tokio::sync::Mutex<Option<ChildStdin>>,
+    /// Final status and when it was reached, set by the waiter task
+    exit: watch::Receiver<Option<(ProcessStatus, Instant)>>,
+    /// Tells the waiter task to stop the process (dropping it does too)
+    kill: Mutex<Option<oneshot::Sender<()>>>,
+    /// Writable scratch directory of a sandboxed process
+    _scratch: Option<ScratchDir>,
+}
+
+impl ManagedProcess {
+    fn status(&self) -> ProcessStatus {
+        self.exit
+            .borrow()
+            .map_or(ProcessStatus::Running, |(status, _)| status)
+    }
+
+    fn info(&self) -> ProcessInfo {
+        let (status, runtime) = match *self.exit.borrow() {
+            Some((status, at)) => (status, at.duration_since(self.started_at)),
+            None => (ProcessStatus::Running, self.started_at.elapsed()),
+        };
+        let recent = self
+            .output
+            .lock()
+            .expect("process output lock poisoned")
+            .combined
+            .last_lines(RECENT_LINES);
+
+        ProcessInfo {
+            handle: self.handle.clone(),
+            session_id: self.session_id,
+            command: self.command.clone(),
+            cwd: self.cwd.clone(),
+            pid: self.pid,
+            status,
+            runtime,
+            recent,
+        }
+    }
+
+    /// Wait up to `limit` for the process to finish. Returns whether it has.
+    async fn wait(&self, limit: Duration) -> bool {
+        let mut exit = self.exit.clone();
+        matches!(
+            timeout(limit, exit.wait_for(|exit| exit.is_some())).await,
+            Ok(Ok(_))
+        )
+    }
+
+    /// Ask the waiter task to stop the process
+    fn signal_stop(&self) {
+        let sender = self.kill.lock().expect("process kill lock poisoned").take();
+        if let Some(sender) = sender {
+            let _ = sender.send(());
+        }
+    }
+
+    /// Stop the process and wait until it is gone
+    async fn stop(&self) {
+        self.signal_stop();
+        self.wait(KILL_GRACE + Duration::from_secs(2)).await;
+    }
+}
+
+/// Stop several processes at once. Returns how many were running.
+async fn stop_all(processes: &[Arc<ManagedProcess>]) -> usize {
+    let running: Vec<_> = processes
+        .iter()
+        .filter(|p| p.status() == ProcessStatus::Running)
+        .collect();
+    for process in &running {
+        process.signal_stop();
+    }
+    for process in &running {
+        process.wait(KILL_GRACE + Duration::from_secs(2)).await;
+    }
+    running.len()
+}
+
+/// Captured output of a process
+#[derive(Default)]
+struct Output {
+    stdout: StreamBuffer,
+    stderr: StreamBuffer,
+    /// Both streams interleaved, for `recent` lines
+    combined: StreamBuffer,
+}
+
+/// One stream's most recent bytes and how far `output` has read it
+#[derive(Default)]
+struct StreamBuffer {
+    data: Vec<u8>,
+    /// Stream offset of `data[0]`
+    start: u64,
+    /// Stream offset `output` has read up to
+    read: u64,
+}
+
+impl StreamBuffer {
+    fn push(&mut self, bytes: &[u8]) {
+        self.data.extend_from_slice(bytes);
+        if self.data.len() > MAX_BUFFER_BYTES {
+            let excess = self.data.len() - MAX_BUFFER_BYTES;
+            self.data.drain(..excess);
+            self.start += excess as u64;
+        }
+    }
+
+    /// Bytes written but not yet read
+    fn unread(&self) -> u64 {
+        (self.start + self.data.len() as u64).saturating_sub(self.read.max(self.start))
+    }
+
+    /// Take the text written since the last call, and how many bytes were
+    /// dropped from the buffer before they could be read
+    fn take_unread(&mut self) -> (String, u64) {
+        let dropped = self.start.saturating_sub(self.read);
+        let from = (self.read.max(self.start) - self.start) as usize;
+        let mut bytes = &self.data[from..];
+        // Leave a character cut off mid-way for the next read
+        if let Err(e) = std::str::from_utf8(bytes)
+            && e.error_len().is_none()
+        {
+            bytes = &bytes[..e.valid_up_to()];
+        }
+        self.read = self.start + (from + bytes.len()) as u64;
+        (String::from_utf8_lossy(bytes).into_owned(), dropped)
+    }
+
+    /// Last `n` non-empty lines, as a terminal would show them after `\r`
+    fn last_lines(&self, n: usize) -> Vec<String> {
+        let tail = &self.data[self.data.len().saturating_sub(8 * 1024)..];
+        let text = String::from_utf8_lossy(tail);
+        let mut lines: Vec<String> = text
+            .lines()
+            .filter_map(|line| line.rsplit('\r').find(|part| !part.trim().is_empty()))
+            .rev()
+            .take(n)
+            .map(str::to_string)
+            .collect();
+        lines.reverse();
+        lines
+    }
+}
+
+fn spawn_reader<R>(mut reader: R, output: Arc<Mutex<Output>>, is_stderr: bool) -> JoinHandle<()>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut chunk = vec![0u8; 8192];
+        loop {
+            match reader.read(&mut chunk).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    let mut output = output.lock().expect("process output lock poisoned");
+                    if is_stderr {
+                        output.stderr.push(&chunk[..n]);
+                    } else {
+                        output.stdout.push(&chunk[..n]);
+                    }
+                    output.combined.push(&chunk[..n]);
+                }
+            }
+        }
+    })
+}
+
+/// Own the child until it exits or is told to stop, then record the outcome
+async fn wait_for_exit(
+    mut child: Child,
+    readers: Vec<JoinHandle<()>>,
+    kill: oneshot::Receiver<()>,
+    exit: watch::Sender<Option<(ProcessStatus, Instant)>>,
+) {
+    let status = tokio::select! {
+        status = child.wait() => {
+            ProcessStatus::Exited(status.ok().and_then(|s| s.code()).unwrap_or(-1))
+        }
+        // A dropped sender (the process was forgotten) stops it too
+        _ = kill => {
+            if let Err(e) = terminate(&mut child).await {
+                tracing::warn!("Failed to stop background process: {}", e);
+            }
+            ProcessStatus::Killed
+        }
+    };
+
+    // Let the readers drain the pipes; something the command spawned may
+    // hold them open, so don't wait for that
+    let _ = timeout(Duration::from_secs(1), async {
+        for reader in readers {
+            let _ = reader.await;
+        }
+    })
+    .await;
+
+    let _ = exit.send(Some((status, Instant::now())));
+}
+
+/// SIGTERM the process group, then SIGKILL whatever is left after the grace period
+async fn terminate(child: &mut Child) -> std::io::Result<()> {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        signal_group(pid, "TERM").await;
+        let exited = timeout(KILL_GRACE, child.wait()).await.is_ok();
+        signal_group(pid, "KILL").await;
+        if exited {
+            return Ok(());
+        }
+    }
+    child.kill().await
+}
+
+#[cfg(unix)]
+async fn signal_group(pid: u32, signal: &str) {
+    let _ = Command::new("kill")
+        .args(["-s", signal, "--", &format!("-{}", pid)])
+        .stderr(Stdio::null())
+        .status()
+        .await;
+}
+
+/// "45s", "3m 20s", "2h 5m"
+fn format_runtime(runtime: Duration) -> String {
+    let secs = runtime.as_secs();
+    match secs {
+        0..60 => format!("{}s", secs),
+        60..3600 => format!("{}m {}s", secs / 60, secs % 60),
+        _ => format!("{}h {}m", secs / 3600, (secs % 3600) / 60),
+    }
+}
+
+/// Format one stream's new output, keeping the last `tail` lines
+fn format_stream(label: &str, text: &str, dropped: u64, tail: usize) -> Option<String> {
+    if text.is_empty() && dropped == 0 {
+        return None;
+    }
+
+    let mut section = format!("{}:\n", label);
+    if dropped > 0 {
+        section.push_str(&format!(
+            "[… {} bytes dropped before they were read]\n",
+            dropped
+        ));
+    }
+
+    let lines: Vec<&str> = text.lines().collect();
+    let mut body = if lines.len() > tail {
+        section.push_str(&format!(
+            "[… {} earlier lines skipped]\n",
+            lines.len() - tail
+        ));
+        lines[lines.len() - tail..].join("\n")
+    } else {
+        text.trim_end_matches('\n').to_string()
+    };
+    if body.len() > MAX_OUTPUT_CHARS {
+        let cut = body.floor_char_boundary(body.len() - MAX_OUTPUT_CHARS);
+        body = format!("[… {} bytes skipped]\n{}", cut, &body[cut..]);
+    }
+    section.push_str(&body);
+    Some(section)
+}
+
+fn str_field<'a>(input: &'a Value, field: &str) -> Option<&'a str> {
+    input
+        .get(field)
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+/// Command running `command` through the platform shell in `cwd`
+fn shell_command(command: &str, cwd: &Path) -> Command {
+    let (shell, shell_arg) = if cfg!(target_os = "windows") {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+    let mut cmd = Command::new(shell);
+    cmd.arg(shell_arg).arg(command).current_dir(cwd);
+    cmd
+}
+
+/// Tool for running and managing background processes
+pub struct ProcessTool {
+    manager: Arc<ProcessManager>,
+}
+
+impl ProcessTool {
+    pub fn new(manager: Arc<ProcessManager>) -> Self {
+        Self { manager }
+    }
+
+    async fn start(&self, input: &Value, context: &ToolExecutionContext) -> Result<ToolResult> {
+        let command = str_field(input, "command").unwrap_or_default();
+        let cwd = match str_field(input, "cwd") {
+            Some(dir) => context.working_directory.join(dir),
+            None => context.working_directory.clone(),
+        };
+        if !cwd.is_dir() {
+            return Ok(ToolResult::error(format!(
+                "Working directory does not exist: {}",
+                cwd.display()
+            )));
+        }
+
+        let (cmd, scratch) = match context.sandbox {
+            Some(ref sandbox) => {
+                let scratch = sandbox.scratch_dir()?;
+                let capabilities = self.capabilities();
+                let run = SandboxRun {
+                    cwd: &cwd,
+                    scratch: scratch.path(),
+                    capabilities: &capabilities,
+                };
+                let args = ["-c".to_string(), command.to_string()];
+                match sandbox.command("sh", &args, &run) {
+                    Ok(cmd) => (cmd, Some(scratch)),
+                    Err(e) => return Ok(ToolResult::error(e.to_string())),
+                }
+            }
+            None => (shell_command(command, &cwd), None),
+        };
+
+        let process = match self
+            .manager
+            .start(context.session_id, cmd, command, &cwd, scratch)
+            .await
+        {
+            Ok(process) => process,
+            Err(e) => return Ok(ToolResult::error(format!("{:#}", e))),
+        };
+
+        // Catch a command that fails straight away
+        process.wait(STARTUP_WAIT).await;
+        let mut text = format!(
+            "Started {} (pid {}): {}\n",
+            process.handle,
+            process.pid.map_or("?".to_string(), |pid| pid.to_string()),
+            command
+        );
+        text.push_str(&read_new(&process, DEFAULT_TAIL_LINES));
+        if process.status() == ProcessStatus::Running {
+            text.push_str(&format!(
+                "\n\nUse operation 'output' with handle '{}' to read what it prints next.",
+                process.handle
+            ));
+        }
+
+        Ok(ToolResult::success(text).with_metadata("handle".to_string(), process.handle.clone()))
+    }
+
+    fn status(&self, input: &Value, context: &ToolExecutionContext) -> Result<ToolResult> {
+        if let Some(handle) = str_field(input, "handle") {
+            let Some(process) = self.manager.find(Some(context.session_id), handle) else {
+                return Ok(unknown_handle(handle));
+            };
+            let info = process.info();
+            let unread = {
+                let output = process.output.lock().expect("process output lock poisoned");
+                output.stdout.unread() + output.stderr.unread()
+            };
+            let mut text = format!(
+                "{}: {}\nCommand: {}\nDirectory: {}\nUnread output: {} bytes",
+                info.handle,
+                info.status_label(),
+                info.command,
+                info.cwd.display(),
+                unread
+            );
+            if !info.recent.is_empty() {
+                text.push_str("\nRecent output:\n");
+                text.push_str(&info.recent.join("\n"));
+            }
+            return Ok(ToolResult::success(text));
+        }
+
+        let processes = self.manager.list(Some(context.session_id));
+        if processes.is_empty() {
+            return Ok(ToolResult::success(
+                "No background processes in this session.".to_string(),
+            ));
+        }
+        let lines: Vec<String> = processes
+            .iter()
+            .map(|p| format!("{}: {} — {}", p.handle, p.status_label(), p.command))
+            .collect();
+        Ok(ToolResult::success(lines.join("\n")))
+    }
+
+    async fn send_input(&self, process: &ManagedProcess, input: &Value) -> Result<ToolResult> {
+        if process.status() != ProcessStatus::Running {
+            return Ok(ToolResult::error(format!(
+                "{} is no longer running.",
+                process.handle
+            )));
+        }
+
+        let text = input.get("input").and_then(|v| v.as_str()).unwrap_or("");
+        let close = input
+            .get("close_stdin")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let mut stdin = process.stdin.lock().await;
+        let Some(pipe) = stdin.as_mut() else {
+            return Ok(ToolResult::error(format!(
+                "{}'s stdin is already closed.",
+                process.handle
+            )));
+        };
+        if !text.is_empty() {
+            let mut data = text.to_string();
+            if !data.ends_with('\n') {
+                data.push('\n');
+            }
+            if let Err(e) = async {
+                pipe.write_all(data.as_bytes()).await?;
+                pipe.flush().await
+            }
+            .await
+            {
+                return Ok(ToolResult::error(format!(
+                    "Failed to write to {}: {}",
+                    process.handle, e
+                )));
+            }
+        }
+        if close {
+            *stdin = None;
+        }
+
+        Ok(ToolResult::success(format!(
+            "Sent {} bytes to {}{}. Use 'output' to see its response.",
+            text.len(),
+            process.handle,
+            if close { " and closed its stdin" } else { "" }
+        )))
+    }
+}
+
+fn unknown_handle(handle: &str) -> ToolResult {
+    ToolResult::error(format!(
+        "No background process '{}' in this session. Use operation 'status' to list them.",
+        handle
+    ))
+}
+
+/// Status line plus the output printed since the last read
+fn read_new(process: &ManagedProcess, tail: usize) -> String {
+    let info = process.info();
+    let ((stdout, stdout_dropped), (stderr, stderr_dropped)) = {
+        let mut output = process.output.lock().expect("process output lock poisoned");
+        (output.stdout.take_unread(), output.stderr.take_unread())
+    };
+
+    let mut text = format!("{}: {}", info.handle, info.status_label());
+    let sections: Vec<String> = [
+        format_stream("STDOUT", &stdout, stdout_dropped, tail),
+        format_stream("STDERR", &stderr, stderr_dropped, tail),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if sections.is_empty() {
+        text.push_str("\n(no new output)");
+    } else {
+        text.push_str("\n\n");
+        text.push_str(&sections.join("\n\n"));
+    }
+    text
+}
+
+#[async_trait]
+impl Tool for ProcessTool {
+    fn name(&self) -> &str {
+        "process"
+    }
+
+    fn description(&self) -> &str {
+        "Run long-lived commands in the background — dev servers, file watchers like \
+         `cargo watch`, long test suites — instead of blocking on bash. Operations: \
+         'start' (needs command; returns a handle), 'output' (stdout/stderr printed since \
+         the last read; wait_secs waits for the process to finish first), 'status' (one \
+         process, or all of this session's without a handle), 'send_input' (write a line \
+         to its stdin) and 'kill'. Processes are stopped when the session is deleted or \
+         OpenCrabs exits."
+    }
+
+    fn input_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "operation": {
+                    "type": "string",
+                    "enum": ["start", "output", "status", "send_input", "kill"],
+                    "description": "What to do"
+                },
+                "command": {
+                    "type": "string",
+                    "description": "Shell command to run (start)"
+                },
+                "cwd": {
+                    "type": "string",
+                    "description": "Working directory, absolute or relative to the current one (start)"
+                },
+                "handle": {
+                    "type": "string",
+                    "description": "Process handle returned by start, e.g. 'p1' (output/send_input/kill; optional for status)"
+                },
+                "tail": {
+                    "type": "integer",
+                    "description": "Most lines to return per stream (output, default 200)"
+                },
+                "wait_secs": {
+                    "type": "integer",
+                    "description": "Wait up to this long for the process to exit before reading (output, max 600)"
+                },
+                "input": {
+                    "type": "string",
+                    "description": "Text to write to stdin; a newline is added if missing (send_input)"
+                },
+                "close_stdin": {
+                    "type": "boolean",
+                    "description": "Close stdin after writing, signalling end of input (send_input)"
+                }
+            },
+            "required": ["operation"]
+        })
+    }
+
+    fn capabilities(&self) -> Vec<ToolCapability> {
+        vec![
+            ToolCapability::ExecuteShell,
+            ToolCapability::SystemModification,
+            ToolCapability::Network,
+        ]
+    }
+
+    /// Reading a process's output or status changes nothing, so only
+    /// `start`, `send_input` and `kill` ask first
+    fn requires_approval_for(&self, input: &Value) -> bool {
+        !matches!(str_field(input, "operation"), Some("output" | "status"))
+    }
+
+    fn validate_input(&self, input: &Value) -> Result<()> {
+        let operation = str_field(input, "operation").unwrap_or_default();
+        match operation {
+            "start" => {
+                if str_field(input, "command").is_none() {
+                    return Err(ToolError::InvalidInput(
+                        "'start' needs 'command'".to_string(),
+                    ));
+                }
+            }
+            "output" | "send_input" | "kill" => {
+                if str_field(input, "handle").is_none() {
+                    return Err(ToolError::InvalidInput(format!(
+                        "'{}' needs 'handle'",
+                        operation
+                    )));
+                }
+            }
+            "status" => {}
+            other => {
+                return Err(ToolError::InvalidInput(format!(
+                    "Unknown operation '{}'. Use start, output, status, send_input or kill.",
+                    other
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    async fn execute(&self, input: Value, context: &ToolExecutionContext) -> Result<ToolResult> {
+        let operation = str_field(&input, "operation").unwrap_or_default();
+        if context.read_only_mode && matches!(operation, "start" | "send_input" | "kill") {
+            return Ok(ToolResult::error(format!(
+                "'{}' is not allowed in Plan mode (read-only). Approve the plan first.",
+                operation
+            )));
+        }
+
+        match operation {
+            "start" => self.start(&input, context).await,
+            "status" => self.status(&input, context),
+            operation => {
+                let handle = str_field(&input, "handle").unwrap_or_default();
+                let Some(process) = self.manager.find(Some(context.session_id), handle) else {
+                    return Ok(unknown_handle(handle));
+                };
+                match operation {
+                    "send_input" => self.send_input(&process, &input).await,
+                    "kill" => {
+                        let was_running = process.status() == ProcessStatus::Running;
+                        process.stop().await;
+                        let mut text = read_new(&process, DEFAULT_TAIL_LINES);
+                        if !was_running {
+                            text.insert_str(0, "Already finished.\n");
+                        }
+                        Ok(ToolResult::success(text))
+                    }
+                    _ => {
+                        let wait_secs = input
+                            .get("wait_secs")
+                            .and_then(|v| v.as_u64())
+                            .unwrap_or(0)
+                            .min(600);
+                        if wait_secs > 0 {
+                            process.wait(Duration::from_secs(wait_secs)).await;
+                        }
+                        let tail = input
+                            .get("tail")
+                            .and_then(|v| v.as_u64())
+                            .map_or(DEFAULT_TAIL_LINES, |t| t.clamp(1, 5000) as usize);
+                        Ok(ToolResult::success(read_new(&process, tail)))
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tool() -> (ProcessTool, Arc<ProcessManager>) {
+        let manager = Arc::new(ProcessManager::new());
+        (ProcessTool::new(manager.clone()), manager)
+    }
+
+    #[tokio::test]
+    async fn test_start_read_incrementally_and_kill() {
+        let (tool, manager) = tool();
+        let ctx = ToolExecutionContext::new(Uuid::new_v4());
+
+        let started = tool
+            .execute(
+                json!({
+                    "operation": "start",
+                    "command": "echo first; sleep 1.5; echo second; sleep 30"
+                }),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(started.success);
+        assert!(started.output.contains("first"));
+        let handle = started.metadata.get("handle").unwrap().clone();
+
+        let output = tool
+            .execute(
+                json!({"operation": "output", "handle": handle, "wait_secs": 2}),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(output.output.contains("second"));
+        assert!(!output.output.contains("first"));
+        assert!(output.output.contains("running"));
+
+        let killed = tool
+            .execute(json!({"operation": "kill", "handle": handle}), &ctx)
+            .await
+            .unwrap();
+        assert!(killed.output.contains("killed"));
+        assert_eq!(manager.list(None)[0].status, ProcessStatus::Killed);
+
+        // Other sessions can't see it
+        let other = ToolExecutionContext::new(Uuid::new_v4());
+        let result = tool
+            .execute(json!({"operation": "output", "handle": handle}), &other)
+            .await
+            .unwrap();
+        assert!(!result.success);
+    }
+
+    #[tokio::test]
+    async fn test_send_input_and_exit_code() {
+        let (tool, _manager) = tool();
+        let ctx = ToolExecutionContext::new(Uuid::new_v4());
+
+        let started = tool
+            .execute(
+                json!({"operation": "start", "command": "read line; echo \"got $line\"; exit 3"}),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        let handle = started.metadata.get("handle").unwrap().clone();
+
+        let sent = tool
+            .execute(
+                json!({"operation": "send_input", "handle": handle, "input": "hello"}),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(sent.success);
+
+        let output = tool
+            .execute(
+                json!({"operation": "output", "handle": handle, "wait_secs": 5}),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(output.output.contains("got hello"));
+        assert!(output.output.contains("exited with code 3"));
+    }
+
+    #[tokio::test]
+    async fn test_kill_session_forgets_processes() {
+        let (tool, manager) = tool();
+        let ctx = ToolExecutionContext::new(Uuid::new_v4());
+
+        tool.execute(json!({"operation": "start", "command": "sleep 30"}), &ctx)
+            .await
+            .unwrap();
+        assert_eq!(manager.kill_session(ctx.session_id).await, 1);
+        assert!(manager.list(Some(ctx.session_id)).is_empty());
+    }
+
+    #[test]
+    fn test_only_polling_skips_approval() {
+        let (tool, _) = tool();
+        let call = |operation: &str| json!({"operation": operation, "handle": "p1"});
+        assert!(!tool.requires_approval_for(&call("output")));
+        assert!(!tool.requires_approval_for(&call("status")));
+        assert!(tool.requires_approval_for(&call("send_input")));
+        assert!(tool.requires_approval_for(&call("kill")));
+        assert!(tool.requires_approval_for(&json!({"operation": "start", "command": "ls"})));
+    }
+
+    #[test]
+    fn test_stream_buffer_reads_only_new_output() {
+        let mut buffer = StreamBuffer::default();
+        buffer.push(b"one\n");
+        assert_eq!(buffer.take_unread(), ("one\n".to_string(), 0));
+        buffer.push("two \u{e9}".as_bytes().split_last().unwrap().1);
+        // The cut-off character waits for its last byte
+        assert_eq!(buffer.take_unread().0, "two ");
+        buffer.push(&"\u{e9}".as_bytes()[1..]);
+        assert_eq!(buffer.take_unread().0, "\u{e9}");
+        assert_eq!(buffer.unread(), 0);
+    }
+}
@@ -15,6 +15,12 @@ const PARAM_ALIASES: &[(&str, &str, &str)] = &[
     // grep/glob: LLMs often send "query" instead of "pattern"
     ("grep", "query", "pattern"),
     ("glob", "query", "pattern"),
+    // grep: ripgrep-style names for the file filters
+    ("grep", "glob", "file_pattern"),
+    ("grep", "include", "file_pattern"),
+    ("grep", "file_type", "type"),
+    // glob: "path" → "base_dir"
+    ("glob", "path", "base_dir"),
     // file tools: "file", "file_path", "filepath" → "path"
     ("read_file", "file", "path"),
     ("read_file", "file_path", "path"),
@@ -122,7 +128,7 @@ impl ToolRegistry {
         tool.validate_input(&input)?;
 
         // Check if approval is required
-        if tool.requires_approval() && !context.auto_approve {
+        if tool.requires_approval_for(&input) && !context.auto_approve {
             return Err(ToolError::ApprovalRequired(format!(
                 "Tool '{}' requires approval before execution",
                 name
@@ -326,4 +332,26 @@ mod tests {
             .unwrap();
         assert!(result.success);
     }
+
+    #[test]
+    fn test_normalize_search_aliases() {
+        let input = normalize_tool_input(
+            "grep",
+            serde_json::json!({ "query": "fn main", "include": "*.rs", "file_type": "rust" }),
+        );
+        assert_eq!(
+            input,
+            serde_json::json!({ "pattern": "fn main", "file_pattern": "*.rs", "type": "rust" })
+        );
+
+        // The correct name wins when both are present
+        let input = normalize_tool_input(
+            "glob",
+            serde_json::json!({ "pattern": "**/*.rs", "query": "x", "path": "src" }),
+        );
+        assert_eq!(
+            input,
+            serde_json::json!({ "pattern": "**/*.rs", "query": "x", "base_dir": "src" })
+        );
+    }
 }
@@ -0,0 +1,433 @@
+//! Command Sandbox
+//!
+//! Runs `bash` and `execute_code` commands that match a `[permissions]
+//! sandbox` rule in Linux namespaces, configured by `[sandbox]`:
+//! - the project directory is mounted read-only unless `project_writable`.
+//!   It is pinned when the sandbox is created (`project_dir`, or the directory
+//!   OpenCrabs started in), so a later `cd` can't widen it
+//! - a per-call scratch directory is the only other writable place, and is
+//!   `HOME` and `TMPDIR` inside the sandbox
+//! - no network unless the tool declares `ToolCapability::Network` and
+//!   `network = true`
+//! - CPU time and data-segment rlimits on every process
+//! - a clean environment (API keys and tokens are not passed in)
+//!
+//! Two backends:
+//! - `bubblewrap` (`bwrap`) builds a fresh root with only system directories
+//!   (`/usr`, `/etc`, ...), the project and the scratch directory in it. This
+//!   is what `auto` means.
+//! - `unshare` (util-linux), only when configured explicitly. It makes the
+//!   project read-only and cuts off the network, but the rest of the
+//!   filesystem, `$HOME` included, stays writable with the user's normal
+//!   permissions. Every use logs a warning.
+//!
+//! When no backend is available the call fails instead of running unsandboxed.
+
+use super::error::{Result, ToolError};
+use super::r#trait::ToolCapability;
+use crate::config::SandboxConfig;
+use std::io;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use uuid::Uuid;
+
+/// System directories mounted read-only by bubblewrap
+const SYSTEM_DIRS: &[&str] = &["/bin", "/sbin", "/lib", "/lib32", "/lib64"];
+
+/// Mounted read-only by bubblewrap when present
+const OPTIONAL_DIRS: &[&str] = &["/etc", "/opt", "/nix"];
+
+/// Environment variables passed through to sandboxed commands
+const PASSED_ENV: &[&str] = &["PATH", "LANG", "LC_ALL", "TERM"];
+
+/// Sandbox settings shared by every sandboxed call
+#[derive(Debug, Clone)]
+pub struct Sandbox {
+    config: SandboxConfig,
+    /// Project directory, mounted read-only unless `project_writable`
+    project: PathBuf,
+}
+
+/// One sandboxed command
+#[derive(Debug, Clone, Copy)]
+pub struct SandboxRun<'a> {
+    /// Directory the command starts in (inside the project or scratch)
+    pub cwd: &'a Path,
+    /// Writable scratch directory, see [`Sandbox::scratch_dir`]
+    pub scratch: &'a Path,
+    /// Capabilities of the calling tool
+    pub capabilities: &'a [ToolCapability],
+}
+
+/// Temporary writable directory for one sandboxed call, removed on drop
+#[derive(Debug)]
+pub struct ScratchDir {
+    path: PathBuf,
+}
+
+impl ScratchDir {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Backend {
+    Bubblewrap(PathBuf),
+    Unshare(PathBuf),
+}
+
+impl Sandbox {
+    pub fn new(config: SandboxConfig) -> Self {
+        let project = match config.project_dir {
+            Some(ref dir) => PathBuf::from(expand_home(dir)),
+            None => std::env::current_dir().unwrap_or_default(),
+        };
+        Self {
+            project: canonical(&project),
+            config,
+        }
+    }
+
+    /// Create a fresh scratch directory for one call
+    pub fn scratch_dir(&self) -> io::Result<ScratchDir> {
+        let path =
+            std::env::temp_dir().join(format!("opencrabs-sandbox-{}", Uuid::new_v4().simple()));
+        std::fs::create_dir_all(&path)?;
+        Ok(ScratchDir { path })
+    }
+
+    /// Short description for tool result metadata, e.g. "bubblewrap, no network"
+    pub fn describe(&self, run: &SandboxRun<'_>) -> String {
+        let backend = match self.backend() {
+            Ok(Backend::Bubblewrap(_)) => "bubblewrap",
+            Ok(Backend::Unshare(_)) => "unshare (filesystem not isolated)",
+            Err(_) => "unavailable",
+        };
+        let network = if self.network(run) {
+            "network"
+        } else {
+            "no network"
+        };
+        format!("{}, {}", backend, network)
+    }
+
+    /// Build a command that runs `program args` inside the sandbox. The
+    /// command is killed when dropped, taking the whole sandbox with it.
+    pub fn command(&self, program: &str, args: &[String], run: &SandboxRun<'_>) -> Result<Command> {
+        if !cfg!(target_os = "linux") {
+            return Err(ToolError::Execution(
+                "The command sandbox is only available on Linux".to_string(),
+            ));
+        }
+
+        // Mounting the home directory or the whole filesystem would defeat the sandbox
+        if self.project.parent().is_none() || dirs::home_dir().is_some_and(|h| h == self.project) {
+            return Err(ToolError::Execution(format!(
+                "Refusing to use {} as the sandbox project. Start OpenCrabs in the project \
+                 directory or set project_dir in [sandbox].",
+                self.project.display()
+            )));
+        }
+        let run = SandboxRun {
+            cwd: &canonical(run.cwd),
+            scratch: &canonical(run.scratch),
+            capabilities: run.capabilities,
+        };
+        if !run.cwd.starts_with(&self.project) && !run.cwd.starts_with(run.scratch) {
+            return Err(ToolError::Execution(format!(
+                "Sandboxed commands must run inside the project ({}), not {}",
+                self.project.display(),
+                run.cwd.display()
+            )));
+        }
+
+        let (binary, args) = match self.backend()? {
+            Backend::Bubblewrap(path) => (path, self.bubblewrap_args(program, args, &run)),
+            Backend::Unshare(path) => {
+                tracing::warn!(
+                    "Sandboxing {} with unshare: only the project is read-only, the rest of \
+                     the filesystem stays writable. Install bubblewrap for full isolation.",
+                    program
+                );
+                (path, self.unshare_args(program, args, &run))
+            }
+        };
+
+        let mut cmd = Command::new(binary);
+        cmd.args(args)
+            .env_clear()
+            .envs(
+                PASSED_ENV
+                    .iter()
+                    .filter_map(|key| std::env::var(key).ok().map(|v| (*key, v))),
+            )
+            .env("HOME", run.scratch)
+            .env("TMPDIR", run.scratch)
+            .current_dir(run.scratch)
+            .kill_on_drop(true);
+        Ok(cmd)
+    }
+
+    fn backend(&self) -> Result<Backend> {
+        let bubblewrap = || which::which("bwrap").ok().map(Backend::Bubblewrap);
+        let unshare = || which::which("unshare").ok().map(Backend::Unshare);
+
+        let backend = match self.config.backend.as_str() {
+            "auto" | "bubblewrap" | "bwrap" => bubblewrap(),
+            "unshare" => unshare(),
+            other => {
+                return Err(ToolError::Execution(format!(
+                    "Unknown sandbox backend '{}' (expected auto, bubblewrap or unshare)",
+                    other
+                )));
+            }
+        };
+        backend.ok_or_else(|| {
+            ToolError::Execution(match self.config.backend.as_str() {
+                "unshare" => "unshare not found. Install util-linux.".to_string(),
+                _ => "bubblewrap (bwrap) not found. Install it to use the sandbox, or set \
+                      backend = \"unshare\" in [sandbox] for weaker isolation."
+                    .to_string(),
+            })
+        })
+    }
+
+    fn network(&self, run: &SandboxRun<'_>) -> bool {
+        self.config.network && run.capabilities.contains(&ToolCapability::Network)
+    }
+
+    /// `ulimit` prefix for the innermost shell
+    fn limits(&self) -> String {
+        let mut limits = String::new();
+        if self.config.memory_mb > 0 {
+            // RLIMIT_DATA rather than RLIMIT_AS, which breaks JIT runtimes
+            // that reserve large address ranges up front
+            limits.push_str(&format!("ulimit -d {} && ", self.config.memory_mb * 1024));
+        }
+        if self.config.cpu_secs > 0 {
+            limits.push_str(&format!("ulimit -t {} && ", self.config.cpu_secs));
+        }
+        limits
+    }
+
+    fn bubblewrap_args(&self, program: &str, args: &[String], run: &SandboxRun<'_>) -> Vec<String> {
+        let mut out = Args::default();
+        out.push(&["--die-with-parent", "--new-session", "--unshare-all"]);
+        if self.network(run) {
+            out.push(&["--share-net"]);
+        }
+
+        out.push(&["--ro-bind", "/usr", "/usr"]);
+        for &dir in SYSTEM_DIRS {
+            // Merged-/usr systems link /bin and friends into /usr
+            match std::fs::read_link(dir) {
+                Ok(target) => out.push(&["--symlink", &target.to_string_lossy(), dir]),
+                Err(_) => out.push(&["--ro-bind-try", dir, dir]),
+            }
+        }
+        for &dir in OPTIONAL_DIRS {
+            out.push(&["--ro-bind-try", dir, dir]);
+        }
+        if self.network(run) {
+            out.push(&[
+                "--ro-bind-try",
+                "/run/systemd/resolve",
+                "/run/systemd/resolve",
+            ]);
+        }
+        out.push(&["--proc", "/proc", "--dev", "/dev", "--tmpfs", "/tmp"]);
+
+        let project = self.project.to_string_lossy();
+        let bind = if self.config.project_writable {
+            "--bind"
+        } else {
+            "--ro-bind"
+        };
+        out.push(&[bind, &project, &project]);
+        for path in &self.config.read_only_paths {
+            let path = expand_home(path);
+            out.push(&["--ro-bind-try", &path, &path]);
+        }
+        for path in &self.config.writable_paths {
+            let path = expand_home(path);
+            out.push(&["--bind-try", &path, &path]);
+        }
+
+        let scratch = run.scratch.to_string_lossy();
+        out.push(&["--bind", &scratch, &scratch]);
+        out.push(&["--chdir", &run.cwd.to_string_lossy()]);
+
+        let script = format!("{}exec \"$@\"", self.limits());
+        out.push(&["--", "sh", "-c", &script, "sh", program]);
+        out.0.extend_from_slice(args);
+        out.0
+    }
+
+    fn unshare_args(&self, program: &str, args: &[String], run: &SandboxRun<'_>) -> Vec<String> {
+        let mut out = Args::default();
+        out.push(&[
+            "--user",
+            "--map-root-user",
+            "--mount",
+            "--pid",
+            "--fork",
+            "--kill-child",
+            "--mount-proc",
+            "--ipc",
+            "--uts",
+        ]);
+        if !self.network(run) {
+            out.push(&["--net"]);
+        }
+
+        // $1 = project, $2 = working directory, the rest is the command
+        let remount = if self.config.project_writable {
+            ""
+        } else {
+            "mount --bind \"$1\" \"$1\" && mount -o remount,bind,ro \"$1\" && "
+        };
+        let script = format!(
+            "{}cd \"$2\" && shift 2 && {}exec \"$@\"",
+            remount,
+            self.limits()
+        );
+        out.push(&[
+            "sh",
+            "-c",
+            &script,
+            "sh",
+            &self.project.to_string_lossy(),
+            &run.cwd.to_string_lossy(),
+            program,
+        ]);
+        out.0.extend_from_slice(args);
+        out.0
+    }
+}
+
+/// Argument list builder
+#[derive(Default)]
+struct Args(Vec<String>);
+
+impl Args {
+    fn push(&mut self, items: &[&str]) {
+        self.0.extend(items.iter().map(|s| s.to_string()));
+    }
+}
+
+/// Resolve symlinks so mounts and the cwd check see real paths
+fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest).to_string_lossy().into_owned(),
+        _ => path.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(capabilities: &[ToolCapability]) -> SandboxRun<'_> {
+        SandboxRun {
+            cwd: Path::new("/work/project/src"),
+            scratch: Path::new("/tmp/opencrabs-sandbox-test"),
+            capabilities,
+        }
+    }
+
+    fn has_pair(args: &[String], flag: &str, value: &str) -> bool {
+        args.windows(2).any(|w| w[0] == flag && w[1] == value)
+    }
+
+    #[test]
+    fn test_bubblewrap_args() {
+        let sandbox = Sandbox::new(SandboxConfig {
+            project_dir: Some("/work/project".to_string()),
+            network: true,
+            ..Default::default()
+        });
+        let args = sandbox.bubblewrap_args("sh", &["-c".to_string(), "ls".to_string()], &run(&[]));
+
+        assert!(has_pair(&args, "--ro-bind", "/work/project"));
+        assert!(has_pair(&args, "--bind", "/tmp/opencrabs-sandbox-test"));
+        assert!(has_pair(&args, "--chdir", "/work/project/src"));
+        // Network stays off for tools that don't declare it
+        assert!(!args.contains(&"--share-net".to_string()));
+        assert!(args.contains(&"ulimit -d 4194304 && ulimit -t 600 && exec \"$@\"".to_string()));
+        assert_eq!(&args[args.len() - 3..], ["sh", "-c", "ls"]);
+
+        let args = sandbox.bubblewrap_args("sh", &[], &run(&[ToolCapability::Network]));
+        assert!(args.contains(&"--share-net".to_string()));
+    }
+
+    #[test]
+    fn test_unshare_args() {
+        let sandbox = Sandbox::new(SandboxConfig {
+            project_dir: Some("/work/project".to_string()),
+            project_writable: true,
+            cpu_secs: 0,
+            ..Default::default()
+        });
+        let args = sandbox.unshare_args(
+            "python3",
+            &["main.py".to_string()],
+            &run(&[ToolCapability::Network]),
+        );
+
+        // Network is opt-in in the config as well
+        assert!(args.contains(&"--net".to_string()));
+        let script = &args[args.iter().position(|a| a == "-c").unwrap() + 1];
+        assert_eq!(
+            script,
+            "cd \"$2\" && shift 2 && ulimit -d 4194304 && exec \"$@\""
+        );
+        assert_eq!(
+            &args[args.len() - 4..],
+            ["/work/project", "/work/project/src", "python3", "main.py"]
+        );
+    }
+
+    #[test]
+    fn test_auto_never_picks_unshare() {
+        let sandbox = Sandbox::new(SandboxConfig::default());
+        assert!(!matches!(sandbox.backend(), Ok(Backend::Unshare(_))));
+    }
+
+    #[test]
+    fn test_command_rejects_cwd_outside_project() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let sandbox = Sandbox::new(SandboxConfig {
+            project_dir: Some(dir.path().to_string_lossy().into_owned()),
+            ..Default::default()
+        });
+        // A `cd` out of the project doesn't move the mount along
+        let outside = SandboxRun {
+            cwd: Path::new("/etc"),
+            ..run(&[])
+        };
+        assert!(sandbox.command("sh", &[], &outside).is_err());
+
+        for project in ["/", "~/"] {
+            let sandbox = Sandbox::new(SandboxConfig {
+                project_dir: Some(project.to_string()),
+                ..Default::default()
+            });
+            let inside = SandboxRun {
+                cwd: Path::new("/"),
+                ..run(&[])
+            };
+            assert!(sandbox.command("sh", &[], &inside).is_err());
+        }
+    }
+}
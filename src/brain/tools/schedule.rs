@@ -0,0 +1,389 @@
+//! Schedule Tool
+//!
+//! Agent-callable tool for managing scheduled prompts ("every weekday at 9:00,
+//! summarize yesterday's merged PRs into Slack"). Schedules run while the TUI
+//! or `opencrabs daemon` is up; see `crate::scheduler::runner`.
+
+use super::error::{Result, ToolError};
+use super::r#trait::{Tool, ToolCapability, ToolExecutionContext, ToolResult};
+use crate::scheduler::store::{ScheduleStore, ScheduledPrompt};
+use async_trait::async_trait;
+use serde_json::Value;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// Runs shown by `history` unless `limit` is given
+const DEFAULT_HISTORY: i64 = 10;
+
+/// Longest run output shown by `history`
+const HISTORY_PREVIEW_CHARS: usize = 200;
+
+/// Tool for creating, listing, pausing and deleting scheduled prompts.
+pub struct ScheduleTool {
+    store: ScheduleStore,
+}
+
+impl ScheduleTool {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            store: ScheduleStore::new(pool),
+        }
+    }
+}
+
+fn str_field<'a>(input: &'a Value, field: &str) -> Option<&'a str> {
+    input
+        .get(field)
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+fn execution_error(e: anyhow::Error) -> ToolError {
+    ToolError::Execution(format!("{:#}", e))
+}
+
+#[async_trait]
+impl Tool for ScheduleTool {
+    fn name(&self) -> &str {
+        "schedule"
+    }
+
+    fn description(&self) -> &str {
+        "Manage scheduled prompts that run automatically while OpenCrabs is running \
+         (TUI or daemon). Each run is an agent turn whose reply can be posted to a channel. \
+         Operations: 'create' (needs prompt and schedule), 'list', 'pause', 'resume', \
+         'delete' and 'history' (need id — a schedule ID, ID prefix or name). \
+         Schedules are 'every <interval>' (e.g. 'every 30m', 'every 1h30m') or a cron \
+         expression 'minute hour day month weekday' (e.g. '0 9 * * mon-fri' for \
+         weekdays at 9:00), read in 'timezone'."
+    }
+
+    fn input_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "operation": {
+                    "type": "string",
+                    "enum": ["create", "list", "pause", "resume", "delete", "history"],
+                    "description": "What to do"
+                },
+                "id": {
+                    "type": "string",
+                    "description": "Schedule ID, ID prefix or name (pause/resume/delete/history)"
+                },
+                "name": {
+                    "type": "string",
+                    "description": "Short name for a new schedule (default: start of the prompt)"
+                },
+                "prompt": {
+                    "type": "string",
+                    "description": "What the agent should do on each run (create)"
+                },
+                "schedule": {
+                    "type": "string",
+                    "description": "'every 30m' or a cron expression like '0 9 * * mon-fri' (create)"
+                },
+                "timezone": {
+                    "type": "string",
+                    "description": "IANA timezone for cron schedules, e.g. 'Europe/Berlin' (default: local time)"
+                },
+                "session": {
+                    "type": "string",
+                    "description": "'current' to run in this session, a session ID, or omit for a dedicated session"
+                },
+                "deliver_to": {
+                    "type": "string",
+                    "enum": ["tui", "telegram_send", "discord_send", "slack_send", "whatsapp_send", "signal_send"],
+                    "description": "Where each run's reply is posted (default: the TUI, if running)"
+                },
+                "target": {
+                    "type": "string",
+                    "description": "Chat ID, channel ID or phone number for deliver_to (default: the channel owner)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Number of runs to show (history, default: 10)"
+                }
+            },
+            "required": ["operation"]
+        })
+    }
+
+    fn capabilities(&self) -> Vec<ToolCapability> {
+        // Scheduled runs auto-approve their tool calls, so creating one needs consent
+        vec![ToolCapability::SystemModification]
+    }
+
+    fn validate_input(&self, input: &Value) -> Result<()> {
+        let operation = str_field(input, "operation").unwrap_or_default();
+        match operation {
+            "create" => {
+                if str_field(input, "prompt").is_none() || str_field(input, "schedule").is_none() {
+                    return Err(ToolError::InvalidInput(
+                        "'create' needs 'prompt' and 'schedule'".to_string(),
+                    ));
+                }
+            }
+            "pause" | "resume" | "delete" | "history" => {
+                if str_field(input, "id").is_none() {
+                    return Err(ToolError::InvalidInput(format!("'{}' needs 'id'", operation)));
+                }
+            }
+            "list" => {}
+            other => {
+                return Err(ToolError::InvalidInput(format!(
+                    "Unknown operation '{}'. Use create, list, pause, resume, delete or history.",
+                    other
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    async fn execute(&self, input: Value, context: &ToolExecutionContext) -> Result<ToolResult> {
+        match str_field(&input, "operation").unwrap_or_default() {
+            "create" => self.create(&input, context).await,
+            "list" => self.list().await,
+            operation => {
+                let key = str_field(&input, "id").unwrap_or_default();
+                let Some(schedule) = self.store.find(key).await.map_err(execution_error)? else {
+                    return Ok(ToolResult::error(format!(
+                        "No schedule matches '{}'. Use 'list' to see schedules.",
+                        key
+                    )));
+                };
+                match operation {
+                    "pause" | "resume" => {
+                        let enabled = operation == "resume";
+                        self.store
+                            .set_enabled(&schedule, enabled)
+                            .await
+                            .map_err(execution_error)?;
+                        Ok(ToolResult::success(format!(
+                            "{} schedule '{}' ({}).",
+                            if enabled { "Resumed" } else { "Paused" },
+                            schedule.name,
+                            schedule.short_id()
+                        )))
+                    }
+                    "delete" => {
+                        self.store.delete(schedule.id).await.map_err(execution_error)?;
+                        Ok(ToolResult::success(format!(
+                            "Deleted schedule '{}' ({}) and its run history.",
+                            schedule.name,
+                            schedule.short_id()
+                        )))
+                    }
+                    _ => {
+                        let limit = input
+                            .get("limit")
+                            .and_then(|v| v.as_i64())
+                            .unwrap_or(DEFAULT_HISTORY)
+                            .clamp(1, 100);
+                        self.history(&schedule, limit).await
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl ScheduleTool {
+    async fn create(&self, input: &Value, context: &ToolExecutionContext) -> Result<ToolResult> {
+        let prompt = str_field(input, "prompt").unwrap_or_default().to_string();
+        let name = str_field(input, "name")
+            .map(str::to_string)
+            .unwrap_or_else(|| prompt.chars().take(40).collect());
+        let session_id = match str_field(input, "session") {
+            None | Some("new") => None,
+            Some("current") => Some(context.session_id),
+            Some(id) => match Uuid::parse_str(id) {
+                Ok(id) => Some(id),
+                Err(_) => {
+                    return Ok(ToolResult::error(format!(
+                        "'session' must be 'current' or a session ID, got '{}'",
+                        id
+                    )));
+                }
+            },
+        };
+
+        let schedule = ScheduledPrompt::new(
+            name,
+            prompt,
+            str_field(input, "schedule").unwrap_or_default().to_string(),
+            str_field(input, "timezone").map(str::to_string),
+        )
+        .and_then(|s| {
+            s.with_delivery(
+                str_field(input, "deliver_to").map(str::to_string),
+                str_field(input, "target").map(str::to_string),
+            )
+        });
+        let schedule = match schedule {
+            Ok(schedule) => schedule.with_session(session_id),
+            Err(e) => return Ok(ToolResult::error(e.to_string())),
+        };
+
+        self.store.create(&schedule).await.map_err(execution_error)?;
+        Ok(ToolResult::success(format!(
+            "Created schedule '{}' ({}): {}. {}",
+            schedule.name,
+            schedule.short_id(),
+            describe_timing(&schedule),
+            describe_delivery(&schedule)
+        )))
+    }
+
+    async fn list(&self) -> Result<ToolResult> {
+        let schedules = self.store.list().await.map_err(execution_error)?;
+        if schedules.is_empty() {
+            return Ok(ToolResult::success("No schedules.".to_string()));
+        }
+
+        let mut output = String::new();
+        for schedule in &schedules {
+            output.push_str(&format!(
+                "{} \"{}\" [{}] {}\n    prompt: {}\n    {}\n",
+                schedule.short_id(),
+                schedule.name,
+                if schedule.enabled { "active" } else { "paused" },
+                describe_timing(schedule),
+                schedule.prompt,
+                describe_delivery(schedule)
+            ));
+        }
+        Ok(ToolResult::success(output))
+    }
+
+    async fn history(&self, schedule: &ScheduledPrompt, limit: i64) -> Result<ToolResult> {
+        let runs = self
+            .store
+            .runs(schedule.id, limit)
+            .await
+            .map_err(execution_error)?;
+        if runs.is_empty() {
+            return Ok(ToolResult::success(format!(
+                "Schedule '{}' has not run yet. {}.",
+                schedule.name,
+                describe_timing(schedule)
+            )));
+        }
+
+        let zone = schedule.zone();
+        let mut output = format!("Last {} run(s) of '{}':\n", runs.len(), schedule.name);
+        for run in runs {
+            let preview: String = run
+                .output
+                .unwrap_or_default()
+                .chars()
+                .take(HISTORY_PREVIEW_CHARS)
+                .collect();
+            output.push_str(&format!(
+                "- {} {}{}: {}\n",
+                zone.format(run.started_at),
+                run.status,
+                if run.delivered { " (delivered)" } else { "" },
+                preview.replace('\n', " ")
+            ));
+        }
+        Ok(ToolResult::success(output))
+    }
+}
+
+/// "every 30m, next run 2026-03-02 09:00 CET"
+fn describe_timing(schedule: &ScheduledPrompt) -> String {
+    let zone = schedule.zone();
+    let tz = schedule.timezone.as_deref().unwrap_or("local time");
+    match schedule.next_run_at {
+        Some(next) if schedule.enabled => {
+            format!("{} ({}), next run {}", schedule.schedule, tz, zone.format(next))
+        }
+        _ => format!("{} ({})", schedule.schedule, tz),
+    }
+}
+
+fn describe_delivery(schedule: &ScheduledPrompt) -> String {
+    match (&schedule.deliver_to, &schedule.target) {
+        (Some(channel), Some(target)) => format!("Replies go to {} ({}).", channel, target),
+        (Some(channel), None) => format!("Replies go to {} (owner).", channel),
+        _ => "Replies go to the TUI.".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+    use serde_json::json;
+
+    async fn tool() -> ScheduleTool {
+        let db = Database::connect_in_memory().await.unwrap();
+        db.run_migrations().await.unwrap();
+        ScheduleTool::new(db.pool().clone())
+    }
+
+    #[tokio::test]
+    async fn test_create_list_pause_delete() {
+        let tool = tool().await;
+        let ctx = ToolExecutionContext::new(Uuid::new_v4());
+
+        let created = tool
+            .execute(
+                json!({
+                    "operation": "create",
+                    "name": "PR digest",
+                    "prompt": "Summarize yesterday's merged PRs",
+                    "schedule": "0 9 * * mon-fri",
+                    "timezone": "America/New_York",
+                    "deliver_to": "slack_send",
+                    "session": "current"
+                }),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(created.success, "{:?}", created.error);
+        assert!(created.output.contains("next run"));
+
+        let listed = tool.execute(json!({"operation": "list"}), &ctx).await.unwrap();
+        assert!(listed.output.contains("PR digest"));
+        assert!(listed.output.contains("[active]"));
+
+        let paused = tool
+            .execute(json!({"operation": "pause", "id": "pr digest"}), &ctx)
+            .await
+            .unwrap();
+        assert!(paused.success);
+        let listed = tool.execute(json!({"operation": "list"}), &ctx).await.unwrap();
+        assert!(listed.output.contains("[paused]"));
+
+        let deleted = tool
+            .execute(json!({"operation": "delete", "id": "PR digest"}), &ctx)
+            .await
+            .unwrap();
+        assert!(deleted.success);
+        let listed = tool.execute(json!({"operation": "list"}), &ctx).await.unwrap();
+        assert_eq!(listed.output, "No schedules.");
+    }
+
+    #[tokio::test]
+    async fn test_create_rejects_bad_input() {
+        let tool = tool().await;
+        let ctx = ToolExecutionContext::new(Uuid::new_v4());
+
+        assert!(tool.validate_input(&json!({"operation": "create", "prompt": "x"})).is_err());
+        assert!(tool.validate_input(&json!({"operation": "pause"})).is_err());
+
+        let bad_zone = tool
+            .execute(
+                json!({"operation": "create", "prompt": "x", "schedule": "every 1h", "timezone": "Mars/Base"}),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert!(!bad_zone.success);
+        assert!(bad_zone.error.unwrap().contains("Mars/Base"));
+    }
+}
@@ -0,0 +1,350 @@
+//! Persistent Shell Sessions
+//!
+//! One long-lived bash process per agent session, so `cd`, `export`,
+//! activated virtualenvs and `source`d scripts carry over between `bash`
+//! tool calls. Each command is written to a temp script and `source`d with
+//! stdin from `/dev/null`; a sentinel line on stdout and stderr then marks
+//! the end of its output, carrying the exit status and the shell's cwd.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStderr, ChildStdin, ChildStdout, Command};
+use tokio::sync::Mutex;
+use tokio::time::{Duration, timeout};
+use uuid::Uuid;
+
+/// Shells kept at once; the least recently used one is closed beyond this
+const MAX_SHELLS: usize = 16;
+
+/// How long an interrupted command gets to exit before the shell is killed
+const INTERRUPT_GRACE: Duration = Duration::from_secs(5);
+
+/// Persistent shells by session ID
+pub(crate) struct ShellSessions {
+    shells: Mutex<HashMap<Uuid, (Arc<Mutex<PersistentShell>>, Instant)>>,
+}
+
+/// Result of one command in a persistent shell
+#[derive(Debug)]
+pub(crate) struct ShellOutput {
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: i32,
+    /// Shell's working directory after the command
+    pub cwd: PathBuf,
+    /// The command hit its timeout and was interrupted
+    pub timed_out: bool,
+    /// The shell survived the command (`false` after `exit` or a forced kill)
+    pub alive: bool,
+}
+
+impl ShellSessions {
+    pub fn new() -> Self {
+        Self {
+            shells: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Run `command` in the session's shell, starting one in `cwd` if there is
+    /// none. The shell moves to `cwd` first if it is somewhere else.
+    pub async fn run(
+        &self,
+        session_id: Uuid,
+        command: &str,
+        cwd: &Path,
+        limit: Duration,
+    ) -> io::Result<ShellOutput> {
+        let shell = self.get_or_spawn(session_id, cwd).await?;
+        let result = shell.lock().await.run(command, cwd, limit).await;
+
+        if !matches!(result, Ok(ref output) if output.alive) {
+            let mut shells = self.shells.lock().await;
+            if shells
+                .get(&session_id)
+                .is_some_and(|(current, _)| Arc::ptr_eq(current, &shell))
+            {
+                shells.remove(&session_id);
+            }
+        }
+        result
+    }
+
+    /// Close the session's shell. Returns whether one was running.
+    pub async fn reset(&self, session_id: Uuid) -> bool {
+        self.shells.lock().await.remove(&session_id).is_some()
+    }
+
+    async fn get_or_spawn(
+        &self,
+        session_id: Uuid,
+        cwd: &Path,
+    ) -> io::Result<Arc<Mutex<PersistentShell>>> {
+        let mut shells = self.shells.lock().await;
+        if let Some((shell, last_used)) = shells.get_mut(&session_id) {
+            *last_used = Instant::now();
+            return Ok(shell.clone());
+        }
+
+        if shells.len() >= MAX_SHELLS
+            && let Some(oldest) = shells
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(id, _)| *id)
+        {
+            shells.remove(&oldest);
+        }
+
+        let shell = Arc::new(Mutex::new(PersistentShell::spawn(cwd).await?));
+        shells.insert(session_id, (shell.clone(), Instant::now()));
+        tracing::debug!("Started persistent shell for session {}", session_id);
+        Ok(shell)
+    }
+}
+
+/// A bash process in its own process group, reading commands from stdin
+struct PersistentShell {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    stderr: BufReader<ChildStderr>,
+    /// Random marker that starts the line ending a command's output
+    sentinel: String,
+    cwd: PathBuf,
+}
+
+impl PersistentShell {
+    async fn spawn(cwd: &Path) -> io::Result<Self> {
+        let mut child = Command::new("bash")
+            .args(["--noprofile", "--norc"])
+            .current_dir(cwd)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .process_group(0)
+            .kill_on_drop(true)
+            .spawn()?;
+
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| io::Error::other("shell has no stdin"))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| io::Error::other("shell has no stdout"))?;
+        let stderr = child
+            .stderr
+            .take()
+            .ok_or_else(|| io::Error::other("shell has no stderr"))?;
+
+        let mut shell = Self {
+            child,
+            stdin,
+            stdout: BufReader::new(stdout),
+            stderr: BufReader::new(stderr),
+            sentinel: format!("__OPENCRABS_{}__", Uuid::new_v4().simple()),
+            cwd: cwd.to_path_buf(),
+        };
+        // A trap (unlike ignoring) lets SIGINT stop the running command
+        // while the shell itself carries on
+        shell.stdin.write_all(b"trap ':' INT\n").await?;
+        Ok(shell)
+    }
+
+    async fn run(&mut self, command: &str, cwd: &Path, limit: Duration) -> io::Result<ShellOutput> {
+        // Sourcing a file keeps a syntax error in the command from
+        // swallowing the sentinel lines
+        let script =
+            std::env::temp_dir().join(format!("opencrabs-shell-{}.sh", Uuid::new_v4().simple()));
+        tokio::fs::write(&script, command).await?;
+
+        let mut input = String::new();
+        if self.cwd != cwd {
+            input.push_str(&format!("cd -- {}\n", quote(cwd)));
+        }
+        input.push_str(&format!(
+            "source {} < /dev/null\n\
+             builtin printf '\\n{s} %d %s\\n' \"$?\" \"$PWD\"\n\
+             builtin printf '\\n{s}\\n' >&2\n",
+            quote(&script),
+            s = self.sentinel,
+        ));
+
+        let result = self.exchange(&input, limit).await;
+        let _ = tokio::fs::remove_file(&script).await;
+        result
+    }
+
+    async fn exchange(&mut self, input: &str, limit: Duration) -> io::Result<ShellOutput> {
+        self.stdin.write_all(input.as_bytes()).await?;
+        self.stdin.flush().await?;
+
+        let mut out = Capture::default();
+        let mut err = Capture::default();
+        let mut timed_out = false;
+
+        let finished = timeout(
+            limit,
+            collect(
+                &mut self.stdout,
+                &mut self.stderr,
+                &self.sentinel,
+                &mut out,
+                &mut err,
+            ),
+        )
+        .await;
+        match finished {
+            Ok(result) => result?,
+            Err(_) => {
+                timed_out = true;
+                self.signal("INT").await;
+                let interrupted = timeout(
+                    INTERRUPT_GRACE,
+                    collect(
+                        &mut self.stdout,
+                        &mut self.stderr,
+                        &self.sentinel,
+                        &mut out,
+                        &mut err,
+                    ),
+                )
+                .await;
+                match interrupted {
+                    Ok(result) => result?,
+                    Err(_) => {
+                        // Ignored the interrupt; the shell goes with it
+                        tracing::warn!("Command ignored SIGINT, killing persistent shell");
+                        self.signal("KILL").await;
+                        return Ok(ShellOutput {
+                            stdout: out.text(),
+                            stderr: err.text(),
+                            exit_code: -1,
+                            cwd: self.cwd.clone(),
+                            timed_out,
+                            alive: false,
+                        });
+                    }
+                }
+            }
+        }
+
+        let (exit_code, alive) = match out.end.flatten() {
+            Some(status) => {
+                let (code, pwd) = status.split_once(' ').unwrap_or((status.as_str(), ""));
+                if !pwd.is_empty() {
+                    self.cwd = PathBuf::from(pwd);
+                }
+                (code.parse().unwrap_or(-1), true)
+            }
+            // The command ran `exit` (or killed the shell)
+            None => {
+                let status = self.child.wait().await?;
+                (status.code().unwrap_or(-1), false)
+            }
+        };
+
+        Ok(ShellOutput {
+            stdout: out.text(),
+            stderr: err.text(),
+            exit_code,
+            cwd: self.cwd.clone(),
+            timed_out,
+            alive,
+        })
+    }
+
+    /// Send a signal to the shell's process group (the shell and its command)
+    async fn signal(&self, signal: &str) {
+        if let Some(pid) = self.child.id() {
+            let _ = Command::new("kill")
+                .args(["-s", signal, "--", &format!("-{}", pid)])
+                .stderr(Stdio::null())
+                .status()
+                .await;
+        }
+    }
+}
+
+impl Drop for PersistentShell {
+    fn drop(&mut self) {
+        // kill_on_drop only reaches the shell; take its children down too
+        if let Some(pid) = self.child.id() {
+            let _ = std::process::Command::new("kill")
+                .args(["-s", "KILL", "--", &format!("-{}", pid)])
+                .stderr(Stdio::null())
+                .status();
+        }
+    }
+}
+
+/// Output read from one stream so far
+#[derive(Default)]
+struct Capture {
+    buf: Vec<u8>,
+    /// `Some` once the stream is finished: the text after the sentinel,
+    /// or `None` if the stream closed first
+    end: Option<Option<String>>,
+}
+
+impl Capture {
+    fn text(&self) -> String {
+        String::from_utf8_lossy(&self.buf).into_owned()
+    }
+}
+
+/// Read both streams up to their sentinels. Safe to call again after a
+/// timeout: read bytes stay in the captures.
+async fn collect(
+    stdout: &mut BufReader<ChildStdout>,
+    stderr: &mut BufReader<ChildStderr>,
+    sentinel: &str,
+    out: &mut Capture,
+    err: &mut Capture,
+) -> io::Result<()> {
+    tokio::try_join!(
+        read_to_sentinel(stdout, sentinel, out),
+        read_to_sentinel(stderr, sentinel, err)
+    )?;
+    Ok(())
+}
+
+async fn read_to_sentinel<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+    sentinel: &str,
+    capture: &mut Capture,
+) -> io::Result<()> {
+    while capture.end.is_none() {
+        // read_until appends what it has read even when cancelled
+        if reader.read_until(b'\n', &mut capture.buf).await? == 0 {
+            capture.end = Some(None);
+            break;
+        }
+        if capture.buf.last() != Some(&b'\n') {
+            continue;
+        }
+
+        let body = &capture.buf[..capture.buf.len() - 1];
+        let line_start = body.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+        if let Some(rest) = body[line_start..].strip_prefix(sentinel.as_bytes()) {
+            let rest = String::from_utf8_lossy(rest).trim().to_string();
+            capture.buf.truncate(line_start);
+            // Drop the newline printed ahead of the sentinel
+            if capture.buf.last() == Some(&b'\n') {
+                capture.buf.pop();
+            }
+            capture.end = Some(Some(rest));
+        }
+    }
+    Ok(())
+}
+
+/// Single-quote a path for the shell
+fn quote(path: &Path) -> String {
+    format!("'{}'", path.to_string_lossy().replace('\'', r"'\''"))
+}
@@ -0,0 +1,124 @@
+//! Signal Send Tool
+//!
+//! Agent-callable tool for proactively sending Signal messages.
+//! Uses the shared `SignalState` to reach the signal-cli daemon.
+
+use super::error::Result;
+use super::r#trait::{Tool, ToolCapability, ToolExecutionContext, ToolResult};
+use crate::channels::signal::{Recipient, SignalState};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Tool that sends a Signal message to the owner, a phone number or a group.
+pub struct SignalSendTool {
+    signal_state: Arc<SignalState>,
+}
+
+impl SignalSendTool {
+    pub fn new(signal_state: Arc<SignalState>) -> Self {
+        Self { signal_state }
+    }
+}
+
+#[async_trait]
+impl Tool for SignalSendTool {
+    fn name(&self) -> &str {
+        "signal_send"
+    }
+
+    fn description(&self) -> &str {
+        "Send a Signal message to the user. Use this to proactively reach out, share updates, \
+         or notify the user about completed tasks. If neither recipient nor group_id is \
+         specified, the message is sent to the owner (first allowed phone). Requires the \
+         Signal channel to be enabled."
+    }
+
+    fn input_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "message": {
+                    "type": "string",
+                    "description": "The message text to send"
+                },
+                "recipient": {
+                    "type": "string",
+                    "description": "Phone number to send to in E.164 format (e.g. +15551234567). Omit to message the owner."
+                },
+                "group_id": {
+                    "type": "string",
+                    "description": "Signal group ID to send to instead of a phone number"
+                }
+            },
+            "required": ["message"]
+        })
+    }
+
+    fn capabilities(&self) -> Vec<ToolCapability> {
+        vec![ToolCapability::Network]
+    }
+
+    async fn execute(&self, input: Value, _context: &ToolExecutionContext) -> Result<ToolResult> {
+        let message = match input.get("message").and_then(|v| v.as_str()) {
+            Some(m) if !m.is_empty() => m.to_string(),
+            _ => {
+                return Ok(ToolResult::error(
+                    "Missing or empty 'message' parameter.".to_string(),
+                ));
+            }
+        };
+
+        let client = match self.signal_state.client().await {
+            Some(c) => c,
+            None => {
+                return Ok(ToolResult::error(
+                    "Signal is not connected. Enable [channels.signal] in config.toml with the \
+                     account registered in signal-cli, and run the signal-cli daemon."
+                        .to_string(),
+                ));
+            }
+        };
+
+        // Resolve target: explicit group or number, otherwise the owner
+        let text_field = |name: &str| {
+            input
+                .get(name)
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.trim().is_empty())
+                .map(str::to_string)
+        };
+        let recipient = if let Some(group_id) = text_field("group_id") {
+            Recipient::Group(group_id)
+        } else if let Some(number) = text_field("recipient") {
+            Recipient::Number(number)
+        } else {
+            match self.signal_state.owner_number().await {
+                Some(number) => Recipient::Number(number),
+                None => {
+                    return Ok(ToolResult::error(
+                        "No owner number configured (allowed_phones is empty) and no \
+                         'recipient' or 'group_id' parameter provided."
+                            .to_string(),
+                    ));
+                }
+            }
+        };
+
+        if let Err(e) = client.send(&recipient, &message).await {
+            return Ok(ToolResult::error(format!(
+                "Failed to send Signal message: {}",
+                e
+            )));
+        }
+
+        let target = match recipient {
+            Recipient::Number(number) => number,
+            Recipient::Group(group_id) => format!("group {}", group_id),
+        };
+        Ok(ToolResult::success(format!(
+            "Message sent to {} via Signal.",
+            target
+        )))
+    }
+}
@@ -138,6 +138,7 @@ impl Tool for SlackConnectTool {
 
         let sl_agent = crate::channels::slack::SlackAgent::new(
             agent,
+            factory.approvals(),
             service_context,
             allowed_ids,
             shared_session,
@@ -85,6 +85,11 @@ impl Tool for SlashCommandTool {
                  in the input box to launch the floating voice-to-text tool."
                     .into(),
             )),
+            "/schedule" => Ok(ToolResult::success(
+                "/schedule is a shortcut for the schedule tool. Call the schedule tool \
+                 directly to create, list, pause, resume or delete scheduled prompts."
+                    .into(),
+            )),
             _ => self.handle_user_command(command, args),
         }
     }
@@ -215,7 +220,7 @@ impl SlashCommandTool {
             let available: Vec<String> = commands.iter().map(|c| c.name.clone()).collect();
             let builtin = [
                 "/cd", "/compact", "/rebuild", "/approve", "/models", "/sessions", "/help",
-                "/onboard", "/usage", "/whisper", "/settings",
+                "/onboard", "/usage", "/whisper", "/settings", "/schedule",
             ];
             Ok(ToolResult::error(format!(
                 "Unknown command: '{}'. Built-in: {}. User-defined: {}",
@@ -108,6 +108,7 @@ impl Tool for TelegramConnectTool {
 
         let tg_agent = crate::channels::telegram::TelegramAgent::new(
             agent,
+            factory.approvals(),
             service_context,
             allowed_users,
             voice_config,
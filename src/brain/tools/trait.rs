@@ -34,6 +34,14 @@ pub struct ToolExecutionContext {
     /// Shared working directory handle — tools can mutate this to change the
     /// working directory at runtime (e.g. config_manager set_working_directory).
     pub shared_working_directory: Option<Arc<std::sync::RwLock<std::path::PathBuf>>>,
+
+    /// Checkpoint for the current agent turn — file-changing tools snapshot
+    /// a file here before writing it (set by AgentService, used by /undo)
+    pub checkpoint: Option<Arc<crate::services::TurnCheckpoint>>,
+
+    /// Sandbox to run commands in — set by AgentService when a
+    /// `[permissions] sandbox` rule matches the call
+    pub sandbox: Option<Arc<crate::brain::tools::sandbox::Sandbox>>,
 }
 
 impl std::fmt::Debug for ToolExecutionContext {
@@ -45,6 +53,8 @@ impl std::fmt::Debug for ToolExecutionContext {
             .field("timeout_secs", &self.timeout_secs)
             .field("read_only_mode", &self.read_only_mode)
             .field("sudo_callback", &self.sudo_callback.is_some())
+            .field("checkpoint", &self.checkpoint.is_some())
+            .field("sandbox", &self.sandbox.is_some())
             .finish()
     }
 }
@@ -61,6 +71,8 @@ impl ToolExecutionContext {
             read_only_mode: false,
             sudo_callback: None,
             shared_working_directory: None,
+            checkpoint: None,
+            sandbox: None,
         }
     }
 
@@ -87,6 +99,16 @@ impl ToolExecutionContext {
         self.read_only_mode = read_only;
         self
     }
+
+    /// Save a file's current content to the turn's checkpoint before a tool
+    /// changes it. A failed snapshot is logged and does not block the edit.
+    pub async fn snapshot_file(&self, path: &std::path::Path) {
+        if let Some(ref checkpoint) = self.checkpoint
+            && let Err(e) = checkpoint.snapshot(path).await
+        {
+            tracing::warn!("Failed to checkpoint {}: {:#}", path.display(), e);
+        }
+    }
 }
 
 /// Tool result
@@ -179,6 +201,21 @@ pub trait Tool: Send + Sync {
             .any(|cap| dangerous_capabilities.contains(cap))
     }
 
+    /// Check if this particular call requires approval. Tools with read-only
+    /// operations override this; by default it's `requires_approval`.
+    fn requires_approval_for(&self, _input: &Value) -> bool {
+        self.requires_approval()
+    }
+
+    /// Check if the tool only reads state, so several calls can run concurrently
+    fn is_read_only(&self) -> bool {
+        let capabilities = self.capabilities();
+        !capabilities.is_empty()
+            && capabilities
+                .iter()
+                .all(|cap| *cap == ToolCapability::ReadFiles)
+    }
+
     /// Execute the tool with given input
     async fn execute(&self, input: Value, context: &ToolExecutionContext) -> Result<ToolResult>;
 
@@ -105,6 +105,10 @@ impl Tool for WebSearchTool {
         vec![ToolCapability::Network]
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     fn requires_approval(&self) -> bool {
         false // Web search is generally safe (read-only)
     }
@@ -6,7 +6,7 @@
 
 use super::error::Result;
 use super::r#trait::{Tool, ToolCapability, ToolExecutionContext, ToolResult};
-use crate::channels::ChannelFactory;
+use crate::channels::{ChannelFactory, SessionTurns};
 use crate::config::opencrabs_home;
 use crate::brain::agent::{ProgressCallback, ProgressEvent};
 use crate::channels::whatsapp::handler;
@@ -243,6 +243,7 @@ impl Tool for WhatsAppConnectTool {
         //    messages immediately after pairing — no abort/respawn needed.
         let factory = self.channel_factory.clone();
         let agent = factory.create_agent_service();
+        let approvals = factory.approvals();
         let session_svc =
             crate::services::SessionService::new(factory.service_context());
         let allowed: Arc<HashSet<String>> =
@@ -251,6 +252,7 @@ impl Tool for WhatsAppConnectTool {
         let shared_session = factory.shared_session_id();
         let extra_sessions: Arc<Mutex<HashMap<String, uuid::Uuid>>> =
             Arc::new(Mutex::new(HashMap::new()));
+        let turns = Arc::new(SessionTurns::new());
 
         // 4. Build bot with combined event handler (QR + Connected + Messages)
         let qr_tx_clone = qr_tx.clone();
@@ -268,9 +270,11 @@ impl Tool for WhatsAppConnectTool {
                 let qr_tx = qr_tx_clone.clone();
                 let connected_tx = connected_tx_clone.clone();
                 let agent = agent.clone();
+                let approvals = approvals.clone();
                 let session_svc = session_svc.clone();
                 let allowed = allowed.clone();
                 let extra_sessions = extra_sessions.clone();
+                let turns = turns.clone();
                 let voice_config = voice_config.clone();
                 let shared_session = shared_session.clone();
                 let wa_state = wa_state.clone();
@@ -291,18 +295,22 @@ impl Tool for WhatsAppConnectTool {
                             tracing::info!("WhatsApp: connected and ready for messages");
                         }
                         Event::Message(msg, info) => {
-                            handler::handle_message(
+                            // Handled in the background so an approval reply can
+                            // reach the turn that is waiting for it; turns of one
+                            // session still run in order (see `SessionTurns`)
+                            tokio::spawn(handler::handle_message(
                                 *msg,
                                 info,
                                 client,
                                 agent,
+                                approvals,
                                 session_svc,
                                 allowed,
                                 extra_sessions,
+                                turns,
                                 voice_config,
                                 shared_session,
-                            )
-                            .await;
+                            ));
                         }
                         Event::LoggedOut(_) => {
                             tracing::warn!("WhatsApp: logged out");
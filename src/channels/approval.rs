@@ -0,0 +1,366 @@
+//! Remote Tool Approval
+//!
+//! Channel agents don't run tools unattended. When a tool call needs approval,
+//! the request is sent back to the chat whose message started the turn —
+//! buttons on Telegram, Discord and Slack, reply keywords on WhatsApp — and the
+//! agent waits for the answer. No answer within the timeout means deny.
+//!
+//! Each handler registers a route (how to reach its chat) for the session
+//! before calling the agent, and resolves pending requests when a button is
+//! pressed or a keyword arrives.
+
+use crate::brain::agent::{AgentError, AgentService, ApprovalCallback, Result, ToolApprovalInfo};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use uuid::Uuid;
+
+/// How long a remote approval request waits before it is denied (same as the TUI)
+pub const APPROVAL_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Prefix for button payloads, so approval clicks can't be confused with other callbacks
+const CALLBACK_PREFIX: &str = "approval";
+
+/// Longest tool input shown in a prompt
+const MAX_INPUT_CHARS: usize = 1000;
+
+/// The user's answer to an approval prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApprovalDecision {
+    AllowOnce,
+    AllowAlways,
+    Deny,
+}
+
+impl ApprovalDecision {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::AllowOnce => "once",
+            Self::AllowAlways => "always",
+            Self::Deny => "deny",
+        }
+    }
+
+    /// Button payload for this decision on request `id`
+    pub fn callback_data(self, id: &str) -> String {
+        format!("{}:{}:{}", CALLBACK_PREFIX, self.as_str(), id)
+    }
+
+    /// Parse a button payload back into `(decision, request id)`
+    pub fn parse_callback_data(data: &str) -> Option<(Self, &str)> {
+        let rest = data.strip_prefix(CALLBACK_PREFIX)?.strip_prefix(':')?;
+        let (decision, id) = rest.split_once(':')?;
+        let decision = match decision {
+            "once" => Self::AllowOnce,
+            "always" => Self::AllowAlways,
+            "deny" => Self::Deny,
+            _ => return None,
+        };
+        Some((decision, id))
+    }
+
+    /// Parse a text reply (WhatsApp keywords)
+    pub fn from_reply(text: &str) -> Option<Self> {
+        let word = text.trim().trim_end_matches(['.', '!']).to_lowercase();
+        match word.as_str() {
+            "yes" | "y" | "approve" | "ok" => Some(Self::AllowOnce),
+            "always" => Some(Self::AllowAlways),
+            "no" | "n" | "deny" => Some(Self::Deny),
+            _ => None,
+        }
+    }
+}
+
+/// A prompt for a channel to deliver, with its buttons or keywords
+#[derive(Debug, Clone)]
+pub struct ApprovalPrompt {
+    /// Request ID — embed it in button payloads via [`ApprovalDecision::callback_data`]
+    pub id: String,
+    /// Human-readable description of the tool call
+    pub text: String,
+}
+
+/// Sends an approval prompt to one chat. Returns false if delivery failed.
+pub type ApprovalPrompter =
+    Arc<dyn Fn(ApprovalPrompt) -> Pin<Box<dyn Future<Output = bool> + Send>> + Send + Sync>;
+
+/// An approval request waiting for an answer
+struct PendingApproval {
+    session_id: Uuid,
+    tool_name: String,
+    tool_input: Value,
+    response_tx: oneshot::Sender<bool>,
+}
+
+/// Routes approval requests from channel agents to chats and collects the answers
+pub struct ChannelApprovals {
+    routes: Mutex<HashMap<Uuid, ApprovalPrompter>>,
+    pending: Mutex<HashMap<String, PendingApproval>>,
+    timeout: Duration,
+}
+
+impl Default for ChannelApprovals {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChannelApprovals {
+    pub fn new() -> Self {
+        Self {
+            routes: Mutex::new(HashMap::new()),
+            pending: Mutex::new(HashMap::new()),
+            timeout: APPROVAL_TIMEOUT,
+        }
+    }
+
+    /// Override the approval timeout
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Send approval requests for `session_id` to this chat (call before each agent turn)
+    pub fn set_route(&self, session_id: Uuid, prompter: ApprovalPrompter) {
+        self.routes
+            .lock()
+            .expect("approval routes lock poisoned")
+            .insert(session_id, prompter);
+    }
+
+    /// Whether a request for `session_id` is waiting for an answer
+    pub fn has_pending(&self, session_id: Uuid) -> bool {
+        self.pending
+            .lock()
+            .expect("pending approvals lock poisoned")
+            .values()
+            .any(|p| p.session_id == session_id)
+    }
+
+    /// Approval callback for a channel agent's `AgentService`
+    pub fn callback(self: &Arc<Self>) -> ApprovalCallback {
+        let approvals = Arc::clone(self);
+        Arc::new(move |info| {
+            let approvals = approvals.clone();
+            Box::pin(async move { approvals.request(info).await })
+        })
+    }
+
+    /// Prompt the session's chat and wait for the answer; anything but an explicit approval denies
+    async fn request(&self, info: ToolApprovalInfo) -> Result<bool> {
+        let prompter = self
+            .routes
+            .lock()
+            .expect("approval routes lock poisoned")
+            .get(&info.session_id)
+            .cloned()
+            .ok_or_else(|| {
+                AgentError::Internal("No chat to ask for approval — denied".to_string())
+            })?;
+
+        let id = Uuid::new_v4().simple().to_string();
+        let (response_tx, response_rx) = oneshot::channel();
+        let prompt = ApprovalPrompt {
+            id: id.clone(),
+            text: format_prompt(&info),
+        };
+        self.pending
+            .lock()
+            .expect("pending approvals lock poisoned")
+            .insert(
+                id.clone(),
+                PendingApproval {
+                    session_id: info.session_id,
+                    tool_name: info.tool_name,
+                    tool_input: info.tool_input,
+                    response_tx,
+                },
+            );
+
+        if !prompter(prompt).await {
+            self.remove(&id);
+            return Err(AgentError::Internal(
+                "Failed to deliver approval request — denied".to_string(),
+            ));
+        }
+
+        match tokio::time::timeout(self.timeout, response_rx).await {
+            Ok(Ok(approved)) => Ok(approved),
+            Ok(Err(_)) => Err(AgentError::Internal(
+                "Approval request dropped — denied".to_string(),
+            )),
+            Err(_) => {
+                self.remove(&id);
+                tracing::warn!(
+                    "Remote approval timed out after {}s, auto-denying",
+                    self.timeout.as_secs()
+                );
+                Err(AgentError::Internal(format!(
+                    "Approval request timed out ({}s) — auto-denied",
+                    self.timeout.as_secs()
+                )))
+            }
+        }
+    }
+
+    /// Answer request `id`. "Always" also saves a scoped allow rule on `agent`.
+    /// Returns a status line to show in the chat.
+    pub fn resolve(&self, id: &str, decision: ApprovalDecision, agent: &AgentService) -> String {
+        match self.remove(id) {
+            Some(pending) => Self::answer(pending, decision, agent),
+            None => "⌛ This approval request has expired.".to_string(),
+        }
+    }
+
+    /// Answer the oldest waiting request for `session_id` (for keyword replies)
+    pub fn resolve_for_session(
+        &self,
+        session_id: Uuid,
+        decision: ApprovalDecision,
+        agent: &AgentService,
+    ) -> String {
+        let pending = {
+            let mut pending = self.pending.lock().expect("pending approvals lock poisoned");
+            let id = pending
+                .iter()
+                .find(|(_, p)| p.session_id == session_id)
+                .map(|(id, _)| id.clone());
+            id.and_then(|id| pending.remove(&id))
+        };
+        match pending {
+            Some(pending) => Self::answer(pending, decision, agent),
+            None => "⌛ No approval request is waiting.".to_string(),
+        }
+    }
+
+    fn answer(pending: PendingApproval, decision: ApprovalDecision, agent: &AgentService) -> String {
+        let status = match decision {
+            ApprovalDecision::AllowOnce => format!("✅ Approved {}", pending.tool_name),
+            ApprovalDecision::AllowAlways => {
+                match agent.allow_always(&pending.tool_name, &pending.tool_input) {
+                    Some(rule) => format!("✅ Approved — always allowing {}", rule),
+                    None => format!(
+                        "✅ Approved {} once — no rule can allow only calls like this",
+                        pending.tool_name
+                    ),
+                }
+            }
+            ApprovalDecision::Deny => format!("❌ Denied {}", pending.tool_name),
+        };
+        if pending
+            .response_tx
+            .send(decision != ApprovalDecision::Deny)
+            .is_err()
+        {
+            return "⌛ This approval request has expired.".to_string();
+        }
+        status
+    }
+
+    fn remove(&self, id: &str) -> Option<PendingApproval> {
+        self.pending
+            .lock()
+            .expect("pending approvals lock poisoned")
+            .remove(id)
+    }
+}
+
+/// Describe a tool call for a chat prompt — the TUI summary line plus the input
+fn format_prompt(info: &ToolApprovalInfo) -> String {
+    let summary = crate::tui::App::format_tool_description(&info.tool_name, &info.tool_input);
+    let input = match info.tool_input.get("command").and_then(|v| v.as_str()) {
+        Some(command) if info.tool_name == "bash" => command.to_string(),
+        _ => serde_json::to_string_pretty(&info.tool_input).unwrap_or_default(),
+    };
+    let mut shown: String = input.chars().take(MAX_INPUT_CHARS).collect();
+    if shown.len() < input.len() {
+        shown.push('…');
+    }
+    format!("🔐 Approval needed: {}\n\n{}", summary, shown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_callback_data_round_trip() {
+        for decision in [
+            ApprovalDecision::AllowOnce,
+            ApprovalDecision::AllowAlways,
+            ApprovalDecision::Deny,
+        ] {
+            let data = decision.callback_data("abc123");
+            assert_eq!(
+                ApprovalDecision::parse_callback_data(&data),
+                Some((decision, "abc123"))
+            );
+        }
+        assert_eq!(ApprovalDecision::parse_callback_data("other:once:abc"), None);
+        assert_eq!(ApprovalDecision::parse_callback_data("approval:maybe:abc"), None);
+    }
+
+    #[test]
+    fn test_reply_keywords() {
+        assert_eq!(ApprovalDecision::from_reply(" Yes "), Some(ApprovalDecision::AllowOnce));
+        assert_eq!(ApprovalDecision::from_reply("always!"), Some(ApprovalDecision::AllowAlways));
+        assert_eq!(ApprovalDecision::from_reply("NO"), Some(ApprovalDecision::Deny));
+        assert_eq!(ApprovalDecision::from_reply("yes please run it"), None);
+    }
+
+    fn approval_info(session_id: Uuid) -> ToolApprovalInfo {
+        ToolApprovalInfo {
+            session_id,
+            tool_name: "bash".to_string(),
+            tool_description: "Run a shell command".to_string(),
+            tool_input: serde_json::json!({"command": "cargo test"}),
+            capabilities: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unrouted_and_timed_out_requests_are_denied() {
+        let approvals = Arc::new(ChannelApprovals::new().with_timeout(Duration::from_millis(20)));
+        let session_id = Uuid::new_v4();
+
+        // No chat registered for the session
+        assert!(approvals.request(approval_info(session_id)).await.is_err());
+
+        // Delivered, but nobody answers
+        let prompter: ApprovalPrompter = Arc::new(|_| Box::pin(async { true }));
+        approvals.set_route(session_id, prompter);
+        let err = approvals.request(approval_info(session_id)).await.unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+        assert!(!approvals.has_pending(session_id));
+    }
+
+    #[tokio::test]
+    async fn test_prompt_is_answered() {
+        let approvals = Arc::new(ChannelApprovals::new());
+        let session_id = Uuid::new_v4();
+        let (prompt_tx, mut prompt_rx) = tokio::sync::mpsc::unbounded_channel();
+        let prompter: ApprovalPrompter = Arc::new(move |prompt| {
+            let prompt_tx = prompt_tx.clone();
+            Box::pin(async move { prompt_tx.send(prompt).is_ok() })
+        });
+        approvals.set_route(session_id, prompter);
+
+        let request = tokio::spawn({
+            let approvals = approvals.clone();
+            async move { approvals.request(approval_info(session_id)).await }
+        });
+        let prompt = prompt_rx.recv().await.unwrap();
+        assert!(prompt.text.contains("cargo test"));
+        assert!(approvals.has_pending(session_id));
+
+        // Answer directly through the pending entry (resolve() needs an AgentService)
+        let pending = approvals.remove(&prompt.id).unwrap();
+        pending.response_tx.send(true).unwrap();
+        assert!(request.await.unwrap().unwrap());
+        assert!(!approvals.has_pending(session_id));
+    }
+}
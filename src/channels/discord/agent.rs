@@ -5,6 +5,7 @@
 use super::handler;
 use super::DiscordState;
 use crate::brain::agent::AgentService;
+use crate::channels::ChannelApprovals;
 use crate::config::{RespondTo, VoiceConfig};
 use crate::services::{ServiceContext, SessionService};
 use std::collections::{HashMap, HashSet};
@@ -13,6 +14,7 @@ use tokio::sync::Mutex;
 use uuid::Uuid;
 
 use serenity::async_trait;
+use serenity::model::application::Interaction;
 use serenity::model::channel::Message;
 use serenity::model::gateway::Ready;
 use serenity::prelude::*;
@@ -20,6 +22,7 @@ use serenity::prelude::*;
 /// Discord bot that forwards messages to the AgentService
 pub struct DiscordAgent {
     agent_service: Arc<AgentService>,
+    approvals: Arc<ChannelApprovals>,
     session_service: SessionService,
     allowed_users: Vec<i64>,
     voice_config: VoiceConfig,
@@ -34,6 +37,7 @@ impl DiscordAgent {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         agent_service: Arc<AgentService>,
+        approvals: Arc<ChannelApprovals>,
         service_context: ServiceContext,
         allowed_users: Vec<i64>,
         voice_config: VoiceConfig,
@@ -45,6 +49,7 @@ impl DiscordAgent {
     ) -> Self {
         Self {
             agent_service,
+            approvals,
             session_service: SessionService::new(service_context),
             allowed_users,
             voice_config,
@@ -83,6 +88,7 @@ impl DiscordAgent {
 
             let event_handler = Handler {
                 agent: self.agent_service,
+                approvals: self.approvals,
                 session_svc: self.session_service,
                 allowed,
                 extra_sessions,
@@ -119,6 +125,7 @@ impl DiscordAgent {
 /// Serenity event handler — routes messages to the agent
 struct Handler {
     agent: Arc<AgentService>,
+    approvals: Arc<ChannelApprovals>,
     session_svc: SessionService,
     allowed: Arc<HashSet<i64>>,
     extra_sessions: Arc<Mutex<HashMap<u64, Uuid>>>,
@@ -156,6 +163,7 @@ impl EventHandler for Handler {
             &ctx,
             &msg,
             self.agent.clone(),
+            self.approvals.clone(),
             self.session_svc.clone(),
             self.allowed.clone(),
             self.extra_sessions.clone(),
@@ -168,4 +176,12 @@ impl EventHandler for Handler {
         )
         .await;
     }
+
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        // Approval button presses
+        if let Interaction::Component(component) = interaction {
+            handler::handle_component(&ctx, &component, &self.agent, &self.approvals, &self.allowed)
+                .await;
+        }
+    }
 }
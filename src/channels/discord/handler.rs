@@ -1,19 +1,26 @@
 //! Discord Message Handler
 //!
 //! Processes incoming Discord messages: text + image attachments, allowlist enforcement,
-//! session routing (owner shares TUI session, others get per-user sessions).
+//! session routing (owner shares TUI session, others get per-user sessions), and
+//! tool approval button presses.
 
 use super::DiscordState;
 use crate::config::{RespondTo, VoiceConfig};
 use crate::brain::agent::AgentService;
+use crate::channels::{ApprovalDecision, ApprovalPrompter, ChannelApprovals};
 use crate::services::SessionService;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use uuid::Uuid;
 
-use serenity::builder::{CreateAttachment, CreateMessage};
+use serenity::builder::{
+    CreateActionRow, CreateAttachment, CreateButton, CreateInteractionResponse,
+    CreateInteractionResponseMessage, CreateMessage,
+};
+use serenity::model::application::{ButtonStyle, ComponentInteraction};
 use serenity::model::channel::Message;
+use serenity::model::id::ChannelId;
 use serenity::prelude::*;
 
 /// Split a message into chunks that fit Discord's 2000 char limit.
@@ -45,6 +52,7 @@ pub(crate) async fn handle_message(
     ctx: &Context,
     msg: &Message,
     agent: Arc<AgentService>,
+    approvals: Arc<ChannelApprovals>,
     session_svc: SessionService,
     allowed: Arc<HashSet<i64>>,
     extra_sessions: Arc<Mutex<HashMap<u64, Uuid>>>,
@@ -208,6 +216,9 @@ pub(crate) async fn handle_message(
         }
     };
 
+    // Tool approvals for this turn are asked in this channel
+    approvals.set_route(session_id, approval_prompter(ctx.http.clone(), msg.channel_id));
+
     // Send to agent
     match agent.send_message_with_tools(session_id, content, None).await {
         Ok(response) => {
@@ -254,6 +265,73 @@ pub(crate) async fn handle_message(
     }
 }
 
+/// Sends approval prompts to `channel_id` with Yes / Always / No buttons
+fn approval_prompter(http: Arc<serenity::http::Http>, channel_id: ChannelId) -> ApprovalPrompter {
+    Arc::new(move |prompt| {
+        let http = http.clone();
+        Box::pin(async move {
+            let buttons = CreateActionRow::Buttons(vec![
+                CreateButton::new(ApprovalDecision::AllowOnce.callback_data(&prompt.id))
+                    .label("Yes")
+                    .style(ButtonStyle::Success),
+                CreateButton::new(ApprovalDecision::AllowAlways.callback_data(&prompt.id))
+                    .label("Always")
+                    .style(ButtonStyle::Primary),
+                CreateButton::new(ApprovalDecision::Deny.callback_data(&prompt.id))
+                    .label("No")
+                    .style(ButtonStyle::Danger),
+            ]);
+            let message = CreateMessage::new()
+                .content(prompt.text)
+                .components(vec![buttons]);
+            match channel_id.send_message(&http, message).await {
+                Ok(_) => true,
+                Err(e) => {
+                    tracing::error!("Discord: failed to send approval prompt: {}", e);
+                    false
+                }
+            }
+        })
+    })
+}
+
+/// Handle a press on an approval button
+pub(crate) async fn handle_component(
+    ctx: &Context,
+    component: &ComponentInteraction,
+    agent: &AgentService,
+    approvals: &ChannelApprovals,
+    allowed: &HashSet<i64>,
+) {
+    let Some((decision, id)) = ApprovalDecision::parse_callback_data(&component.data.custom_id)
+    else {
+        return;
+    };
+
+    let user_id = component.user.id.get() as i64;
+    let response = if !allowed.is_empty() && !allowed.contains(&user_id) {
+        tracing::warn!("Discord: approval button pressed by non-allowed user {}", user_id);
+        CreateInteractionResponse::Message(
+            CreateInteractionResponseMessage::new()
+                .content("You are not authorized.")
+                .ephemeral(true),
+        )
+    } else {
+        let status = approvals.resolve(id, decision, agent);
+        tracing::info!("Discord: approval {} by user {}: {}", id, user_id, status);
+        // Replace the buttons with the outcome
+        CreateInteractionResponse::UpdateMessage(
+            CreateInteractionResponseMessage::new()
+                .content(format!("{}\n\n{}", component.message.content, status))
+                .components(vec![]),
+        )
+    };
+
+    if let Err(e) = component.create_response(&ctx.http, response).await {
+        tracing::error!("Discord: failed to answer approval interaction: {}", e);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
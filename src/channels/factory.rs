@@ -3,7 +3,8 @@
 //! Shared factory for creating channel agent services at runtime.
 //! Used by both static startup (ui.rs) and dynamic connection (whatsapp_connect tool).
 
-use crate::brain::agent::AgentService;
+use super::ChannelApprovals;
+use crate::brain::agent::{AgentService, ProgressCallback};
 use crate::brain::provider::Provider;
 use crate::brain::tools::ToolRegistry;
 use crate::config::VoiceConfig;
@@ -30,6 +31,8 @@ pub struct ChannelFactory {
     shared_session_id: Arc<Mutex<Option<Uuid>>>,
     voice_config: VoiceConfig,
     openai_tts_key: Option<String>,
+    /// Remote approval requests from every channel agent, routed per session
+    approvals: Arc<ChannelApprovals>,
 }
 
 impl ChannelFactory {
@@ -54,6 +57,7 @@ impl ChannelFactory {
             shared_session_id,
             voice_config,
             openai_tts_key,
+            approvals: Arc::new(ChannelApprovals::new()),
         }
     }
 
@@ -62,11 +66,35 @@ impl ChannelFactory {
         let _ = self.tool_registry.set(registry);
     }
 
-    /// Create a new AgentService configured for channel use (auto-approve, no TUI callbacks).
+    /// Create a new AgentService configured for channel use. Tool calls that need
+    /// approval are sent back to the originating chat via [`ChannelApprovals`].
     pub fn create_agent_service(&self) -> Arc<AgentService> {
+        Arc::new(
+            self.agent_service_builder()
+                .with_approval_callback(Some(self.approvals.callback())),
+        )
+    }
+
+    /// Create a new AgentService that auto-approves tool calls, for the A2A
+    /// gateway where there is no chat to ask.
+    pub fn create_unattended_agent_service(&self) -> Arc<AgentService> {
+        Arc::new(self.agent_service_builder().with_auto_approve_tools(true))
+    }
+
+    /// Like [`create_unattended_agent_service`](Self::create_unattended_agent_service),
+    /// reporting streamed text and tool calls to `progress`. The HTTP gateway
+    /// creates one per streaming request.
+    pub fn create_streaming_agent_service(&self, progress: ProgressCallback) -> Arc<AgentService> {
+        Arc::new(
+            self.agent_service_builder()
+                .with_auto_approve_tools(true)
+                .with_progress_callback(Some(progress)),
+        )
+    }
+
+    fn agent_service_builder(&self) -> AgentService {
         let mut builder = AgentService::new(self.provider.clone(), self.service_context.clone())
             .with_system_brain(self.shared_brain.clone())
-            .with_auto_approve_tools(true)
             .with_working_directory(self.working_directory.clone())
             .with_brain_path(self.brain_path.clone());
 
@@ -74,7 +102,12 @@ impl ChannelFactory {
             builder = builder.with_tool_registry(registry.clone());
         }
 
-        Arc::new(builder)
+        builder
+    }
+
+    /// Shared remote approval state — channel handlers route prompts and resolve answers here
+    pub fn approvals(&self) -> Arc<ChannelApprovals> {
+        self.approvals.clone()
     }
 
     pub fn shared_session_id(&self) -> Arc<Mutex<Option<Uuid>>> {
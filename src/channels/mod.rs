@@ -1,13 +1,18 @@
 //! Channel Integrations
 //!
-//! Messaging channel integrations (Telegram, WhatsApp, Discord, Slack) and the
-//! shared factory for creating channel-specific agent services.
+//! Messaging channel integrations (Telegram, WhatsApp, Discord, Slack, Signal), the
+//! shared factory for creating channel-specific agent services, remote
+//! tool approval for those agents, and the per-session turn queue.
 
+pub mod approval;
 mod factory;
+pub mod turns;
 pub mod voice;
 
 #[cfg(feature = "discord")]
 pub mod discord;
+#[cfg(feature = "signal")]
+pub mod signal;
 #[cfg(feature = "slack")]
 pub mod slack;
 #[cfg(feature = "telegram")]
@@ -15,4 +20,6 @@ pub mod telegram;
 #[cfg(feature = "whatsapp")]
 pub mod whatsapp;
 
+pub use approval::{ApprovalDecision, ApprovalPrompt, ApprovalPrompter, ChannelApprovals};
 pub use factory::ChannelFactory;
+pub use turns::SessionTurns;
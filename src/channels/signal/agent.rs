@@ -0,0 +1,130 @@
+//! Signal Agent
+//!
+//! Agent struct and startup logic. Follows the signal-cli event stream and
+//! reconnects with backoff when the daemon goes away.
+
+use super::SignalState;
+use super::client::{DEFAULT_URL, SignalClient};
+use super::handler::{self, HandlerState};
+use crate::brain::agent::AgentService;
+use crate::channels::{ChannelApprovals, SessionTurns};
+use crate::config::{RespondTo, VoiceConfig};
+use crate::services::{ServiceContext, SessionService};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+/// Longest wait between reconnection attempts
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Signal agent that forwards messages to the AgentService
+pub struct SignalAgent {
+    agent_service: Arc<AgentService>,
+    approvals: Arc<ChannelApprovals>,
+    session_service: SessionService,
+    allowed_phones: Vec<String>,
+    voice_config: VoiceConfig,
+    openai_api_key: Option<String>,
+    shared_session_id: Arc<Mutex<Option<Uuid>>>,
+    signal_state: Arc<SignalState>,
+    respond_to: RespondTo,
+    allowed_channels: Vec<String>,
+}
+
+impl SignalAgent {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        agent_service: Arc<AgentService>,
+        approvals: Arc<ChannelApprovals>,
+        service_context: ServiceContext,
+        allowed_phones: Vec<String>,
+        voice_config: VoiceConfig,
+        openai_api_key: Option<String>,
+        shared_session_id: Arc<Mutex<Option<Uuid>>>,
+        signal_state: Arc<SignalState>,
+        respond_to: RespondTo,
+        allowed_channels: Vec<String>,
+    ) -> Self {
+        Self {
+            agent_service,
+            approvals,
+            session_service: SessionService::new(service_context),
+            allowed_phones,
+            voice_config,
+            openai_api_key,
+            shared_session_id,
+            signal_state,
+            respond_to,
+            allowed_channels,
+        }
+    }
+
+    /// Start as a background task. `url` is the signal-cli daemon's HTTP
+    /// address (default `http://127.0.0.1:8080`) and `account` the bot's number.
+    pub fn start(self, url: Option<String>, account: String) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            if account.trim().is_empty() {
+                tracing::debug!("Signal account not configured, skipping agent start");
+                return;
+            }
+            if self.allowed_phones.is_empty() {
+                tracing::warn!("Signal: allowed_phones is empty — all messages will be ignored");
+            }
+
+            let url = url.unwrap_or_else(|| DEFAULT_URL.to_string());
+            tracing::info!(
+                "Starting Signal agent for {} via {} with {} allowed phone(s), STT={}, TTS={}",
+                account,
+                url,
+                self.allowed_phones.len(),
+                self.voice_config.stt_enabled,
+                self.voice_config.tts_enabled,
+            );
+
+            let client = SignalClient::new(url, Some(account.clone()));
+            self.signal_state
+                .set_connected(client.clone(), self.allowed_phones.first().cloned())
+                .await;
+
+            let state = Arc::new(HandlerState {
+                agent: self.agent_service,
+                approvals: self.approvals,
+                session_svc: self.session_service,
+                client: client.clone(),
+                account,
+                allowed: self.allowed_phones,
+                extra_sessions: Mutex::new(HashMap::new()),
+                turns: SessionTurns::new(),
+                voice_config: self.voice_config,
+                openai_key: self.openai_api_key,
+                shared_session: self.shared_session_id,
+                respond_to: self.respond_to,
+                allowed_channels: self.allowed_channels.into_iter().collect(),
+            });
+
+            let mut backoff = Duration::from_secs(1);
+            loop {
+                let result = client
+                    .listen(|envelope| {
+                        // Handled concurrently so approval replies reach a waiting turn;
+                        // turns of one session still run in order
+                        tokio::spawn(handler::handle_envelope(envelope, state.clone()));
+                    })
+                    .await;
+                match result {
+                    Ok(()) => {
+                        tracing::warn!("Signal: event stream closed, reconnecting");
+                        backoff = Duration::from_secs(1);
+                    }
+                    Err(e) => {
+                        tracing::warn!("Signal: {:#}. Retrying in {}s", e, backoff.as_secs());
+                    }
+                }
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+        })
+    }
+}
@@ -0,0 +1,367 @@
+//! signal-cli JSON-RPC Client
+//!
+//! Requests go to `POST {url}/api/v1/rpc`; incoming messages arrive as
+//! `receive` notifications on the `GET {url}/api/v1/events` SSE stream.
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+/// Default address of `signal-cli daemon --http`
+pub const DEFAULT_URL: &str = "http://127.0.0.1:8080";
+
+/// Where a message goes: a phone number or a group
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Recipient {
+    Number(String),
+    Group(String),
+}
+
+/// Client for one account of a signal-cli daemon
+#[derive(Clone)]
+pub struct SignalClient {
+    http: reqwest::Client,
+    url: String,
+    account: Option<String>,
+}
+
+impl SignalClient {
+    /// `account` selects the account on a daemon serving several; `None`
+    /// uses the daemon's only account.
+    pub fn new(url: impl Into<String>, account: Option<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            url: url.into().trim_end_matches('/').to_string(),
+            account,
+        }
+    }
+
+    /// Phone number of the account, if configured
+    pub fn account(&self) -> Option<&str> {
+        self.account.as_deref()
+    }
+
+    /// Call a JSON-RPC method and return its result
+    pub async fn rpc(&self, method: &str, mut params: Value) -> Result<Value> {
+        if let Some(ref account) = self.account {
+            params["account"] = json!(account);
+        }
+        let request = json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params,
+            "id": Uuid::new_v4().to_string(),
+        });
+
+        let response = self
+            .http
+            .post(format!("{}/api/v1/rpc", self.url))
+            .json(&request)
+            .send()
+            .await
+            .with_context(|| format!("signal-cli daemon unreachable at {}", self.url))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("signal-cli {} failed ({}): {}", method, status, body);
+        }
+
+        let rpc: Value = response
+            .json()
+            .await
+            .context("Failed to parse signal-cli response")?;
+        if let Some(error) = rpc.get("error") {
+            let message = error
+                .get("message")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .unwrap_or_else(|| error.to_string());
+            anyhow::bail!("signal-cli {} failed: {}", method, message);
+        }
+        Ok(rpc.get("result").cloned().unwrap_or(Value::Null))
+    }
+
+    /// Send a text message
+    pub async fn send(&self, to: &Recipient, text: &str) -> Result<()> {
+        self.send_with_attachments(to, text, &[]).await
+    }
+
+    /// Send a message with attachments, given as file paths or
+    /// `data:<mime>;filename=<name>;base64,<data>` URIs
+    pub async fn send_with_attachments(
+        &self,
+        to: &Recipient,
+        text: &str,
+        attachments: &[String],
+    ) -> Result<()> {
+        let mut params = match to {
+            Recipient::Number(number) => json!({ "recipient": [number] }),
+            Recipient::Group(id) => json!({ "groupId": id }),
+        };
+        params["message"] = json!(text);
+        if !attachments.is_empty() {
+            params["attachments"] = json!(attachments);
+        }
+        self.rpc("send", params).await.map(|_| ())
+    }
+
+    /// Download an attachment of a message received from `from`
+    pub async fn attachment(&self, id: &str, from: &Recipient) -> Result<Vec<u8>> {
+        let mut params = match from {
+            Recipient::Number(number) => json!({ "recipient": number }),
+            Recipient::Group(group_id) => json!({ "groupId": group_id }),
+        };
+        params["id"] = json!(id);
+        let result = self.rpc("getAttachment", params).await?;
+        let data = result
+            .get("data")
+            .and_then(Value::as_str)
+            .context("signal-cli returned no attachment data")?;
+        base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .context("Attachment data is not valid base64")
+    }
+
+    /// Pass each received envelope to `on_envelope` until the event stream
+    /// ends. Returns `Ok` when a connected stream closes.
+    pub async fn listen(&self, on_envelope: impl Fn(Envelope)) -> Result<()> {
+        let mut request = self.http.get(format!("{}/api/v1/events", self.url));
+        if let Some(ref account) = self.account {
+            request = request.query(&[("account", account)]);
+        }
+        let mut response = request
+            .header(reqwest::header::ACCEPT, "text/event-stream")
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .with_context(|| format!("signal-cli daemon unreachable at {}", self.url))?;
+
+        let mut buffer: Vec<u8> = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .context("signal-cli event stream broke")?
+        {
+            buffer.extend_from_slice(&chunk);
+            while let Some(end) = buffer.windows(2).position(|w| w == b"\n\n") {
+                let event: Vec<u8> = buffer.drain(..end + 2).collect();
+                let event = String::from_utf8_lossy(&event).replace('\r', "");
+                if let Some(envelope) = parse_event(&event) {
+                    on_envelope(envelope);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The envelope of a `receive` notification in one SSE event, if it has one
+fn parse_event(event: &str) -> Option<Envelope> {
+    let data = event
+        .lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(str::trim_start)
+        .collect::<Vec<_>>()
+        .join("\n");
+    if data.is_empty() {
+        return None;
+    }
+
+    let notification: Value = match serde_json::from_str(&data) {
+        Ok(value) => value,
+        Err(e) => {
+            tracing::warn!("Signal: unreadable event from signal-cli: {}", e);
+            return None;
+        }
+    };
+    // The events endpoint sends bare notification params; the TCP/stdio
+    // transports wrap them in a JSON-RPC "receive" call
+    let params = notification.get("params").unwrap_or(&notification);
+    let envelope = params.get("envelope")?;
+    match serde_json::from_value(envelope.clone()) {
+        Ok(envelope) => Some(envelope),
+        Err(e) => {
+            tracing::warn!("Signal: unreadable envelope: {}", e);
+            None
+        }
+    }
+}
+
+/// A received message or receipt, as signal-cli reports it
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Envelope {
+    pub source_number: Option<String>,
+    pub source_uuid: Option<String>,
+    pub source_name: Option<String>,
+    /// Absent for receipts, typing indicators and sync messages
+    pub data_message: Option<DataMessage>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataMessage {
+    pub message: Option<String>,
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+    #[serde(default)]
+    pub mentions: Vec<Mention>,
+    pub group_info: Option<GroupInfo>,
+    pub quote: Option<Quote>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Attachment {
+    pub id: Option<String>,
+    pub content_type: Option<String>,
+    pub filename: Option<String>,
+    pub size: Option<u64>,
+}
+
+/// An @mention; the text holds U+FFFC at `start` for each one
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Mention {
+    pub name: Option<String>,
+    pub number: Option<String>,
+    pub uuid: Option<String>,
+    #[serde(default)]
+    pub start: usize,
+    #[serde(default)]
+    pub length: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupInfo {
+    pub group_id: String,
+}
+
+/// The message a reply quotes
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Quote {
+    pub author_number: Option<String>,
+    pub author_uuid: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_receive_event() {
+        let event = concat!(
+            "event:receive\n",
+            r#"data:{"account":"+15550000000","envelope":{"sourceNumber":"+15551234567","sourceName":"Ana","#,
+            r#""dataMessage":{"message":"hi","groupInfo":{"groupId":"Zm9v"},"#,
+            r#""attachments":[{"id":"a1.jpg","contentType":"image/jpeg"}]}}}"#,
+            "\n\n"
+        );
+        let envelope = parse_event(event).expect("envelope");
+        assert_eq!(envelope.source_number.as_deref(), Some("+15551234567"));
+        let data = envelope.data_message.expect("data message");
+        assert_eq!(data.message.as_deref(), Some("hi"));
+        assert_eq!(data.group_info.expect("group").group_id, "Zm9v");
+        assert_eq!(
+            data.attachments[0].content_type.as_deref(),
+            Some("image/jpeg")
+        );
+    }
+
+    #[test]
+    fn test_parse_wrapped_and_empty_events() {
+        let wrapped = r#"data:{"jsonrpc":"2.0","method":"receive","params":{"envelope":{"sourceNumber":"+1555"}}}"#;
+        let envelope = parse_event(wrapped).expect("envelope");
+        assert!(envelope.data_message.is_none());
+
+        assert!(parse_event(":keepalive\n\n").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_send_and_attachment() {
+        let mut server = mockito::Server::new_async().await;
+        let send = server
+            .mock("POST", "/api/v1/rpc")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "method": "send",
+                "params": {"groupId": "Zm9v", "message": "done", "account": "+15550000000"}
+            })))
+            .with_body(r#"{"jsonrpc":"2.0","result":{"timestamp":1},"id":"1"}"#)
+            .create_async()
+            .await;
+        let attachment = server
+            .mock("POST", "/api/v1/rpc")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "method": "getAttachment",
+                "params": {"id": "a1.jpg", "recipient": "+15551234567"}
+            })))
+            .with_body(r#"{"jsonrpc":"2.0","result":{"data":"AQID"},"id":"2"}"#)
+            .create_async()
+            .await;
+
+        let client = SignalClient::new(server.url(), Some("+15550000000".to_string()));
+        client
+            .send(&Recipient::Group("Zm9v".to_string()), "done")
+            .await
+            .expect("sent");
+        let bytes = client
+            .attachment("a1.jpg", &Recipient::Number("+15551234567".to_string()))
+            .await
+            .expect("attachment");
+
+        send.assert_async().await;
+        attachment.assert_async().await;
+        assert_eq!(bytes, vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_rpc_error() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/api/v1/rpc")
+            .with_body(
+                r#"{"jsonrpc":"2.0","error":{"code":-1,"message":"Unregistered user"},"id":"1"}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = SignalClient::new(server.url(), None);
+        let err = client
+            .send(&Recipient::Number("+1555".to_string()), "hi")
+            .await
+            .expect_err("rpc error");
+        assert!(err.to_string().contains("Unregistered user"));
+    }
+
+    #[tokio::test]
+    async fn test_listen_delivers_envelopes() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/api/v1/events")
+            .with_header("content-type", "text/event-stream")
+            .with_body(concat!(
+                "data:{\"envelope\":{\"sourceNumber\":\"+1\",\"dataMessage\":{\"message\":\"a\"}}}\n\n",
+                ":keepalive\n\n",
+                "data:{\"envelope\":{\"sourceNumber\":\"+2\",\"dataMessage\":{\"message\":\"b\"}}}\n\n",
+            ))
+            .create_async()
+            .await;
+
+        let client = SignalClient::new(server.url(), None);
+        let received = std::sync::Mutex::new(Vec::new());
+        client
+            .listen(|envelope| {
+                received
+                    .lock()
+                    .unwrap()
+                    .push(envelope.source_number.unwrap_or_default());
+            })
+            .await
+            .expect("stream");
+        assert_eq!(*received.lock().unwrap(), vec!["+1", "+2"]);
+    }
+}
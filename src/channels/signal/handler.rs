@@ -0,0 +1,541 @@
+//! Signal Message Handler
+//!
+//! Processes incoming envelopes: text, image attachments, voice notes (STT/TTS),
+//! allowlist enforcement, group filtering, session routing (owner shares TUI
+//! session, others get per-number sessions) and tool approval keyword replies
+//! (Signal has no buttons).
+
+use super::client::{DataMessage, Envelope, Mention, Recipient, SignalClient};
+use crate::brain::agent::AgentService;
+use crate::channels::{ApprovalDecision, ApprovalPrompter, ChannelApprovals, SessionTurns};
+use crate::config::{RespondTo, VoiceConfig};
+use crate::services::SessionService;
+use base64::Engine;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+/// Placeholder signal-cli puts in the text where each @mention goes
+const MENTION_PLACEHOLDER: char = '\u{FFFC}';
+
+/// Everything the handler needs, shared across envelopes
+pub(crate) struct HandlerState {
+    pub agent: Arc<AgentService>,
+    pub approvals: Arc<ChannelApprovals>,
+    pub session_svc: SessionService,
+    pub client: SignalClient,
+    /// The bot's own number
+    pub account: String,
+    /// Allowlisted numbers; the first one is the owner
+    pub allowed: Vec<String>,
+    pub extra_sessions: Mutex<HashMap<String, Uuid>>,
+    /// Runs turns of one session in order
+    pub turns: SessionTurns,
+    pub voice_config: VoiceConfig,
+    pub openai_key: Option<String>,
+    pub shared_session: Arc<Mutex<Option<Uuid>>>,
+    pub respond_to: RespondTo,
+    pub allowed_channels: HashSet<String>,
+}
+
+pub(crate) async fn handle_envelope(envelope: Envelope, state: Arc<HandlerState>) {
+    // Receipts, typing indicators and sync messages carry no data message
+    let Some(data) = envelope.data_message else {
+        return;
+    };
+    let Some(number) = envelope.source_number else {
+        return;
+    };
+    if same_number(&number, &state.account) {
+        return;
+    }
+
+    // Allowlist check — an empty list lets nobody in
+    if !state.allowed.iter().any(|a| same_number(a, &number)) {
+        tracing::debug!(
+            "Signal: ignoring message from non-allowed number {}",
+            number
+        );
+        return;
+    }
+
+    let group_id = data.group_info.as_ref().map(|g| g.group_id.clone());
+    if let Some(ref group_id) = group_id
+        && !should_respond_in_group(
+            &state.respond_to,
+            &state.allowed_channels,
+            group_id,
+            &data,
+            &state.account,
+        )
+    {
+        return;
+    }
+    let chat = match group_id {
+        Some(id) => Recipient::Group(id),
+        None => Recipient::Number(number.clone()),
+    };
+    let sender = envelope.source_name.unwrap_or_else(|| number.clone());
+
+    let mut text = render_mentions(
+        data.message.as_deref().unwrap_or_default(),
+        &data.mentions,
+        &state.account,
+    );
+
+    // Attachments: images go to the agent as <<IMG:path>>, voice notes as text
+    let mut is_voice = false;
+    let mut images = Vec::new();
+    for attachment in &data.attachments {
+        let Some(ref id) = attachment.id else {
+            continue;
+        };
+        let mime = attachment.content_type.as_deref().unwrap_or_default();
+        if mime.starts_with("image/") {
+            match save_image(&state.client, id, mime, &chat).await {
+                Some(path) => images.push(path),
+                None => send_text(&state.client, &chat, "Failed to download image.").await,
+            }
+        } else if mime.starts_with("audio/") {
+            match transcribe(&state, id, mime, &chat).await {
+                Ok(transcript) => {
+                    if !text.is_empty() {
+                        text.push('\n');
+                    }
+                    text.push_str(&transcript);
+                    is_voice = true;
+                }
+                Err(reply) => {
+                    send_text(&state.client, &chat, &reply).await;
+                    return;
+                }
+            }
+        } else {
+            tracing::debug!("Signal: skipping unsupported attachment ({})", mime);
+        }
+    }
+
+    if text.trim().is_empty() {
+        if images.is_empty() {
+            return;
+        }
+        text = "Analyze this image".to_string();
+    }
+    for path in &images {
+        text.push_str(&format!(" <<IMG:{}>>", path));
+    }
+
+    tracing::info!(
+        "Signal: {} from {} ({}): {}",
+        if is_voice { "voice" } else { "text" },
+        number,
+        sender,
+        &text[..text.floor_char_boundary(50)]
+    );
+
+    // Resolve session: owner shares the TUI session, other numbers get their own
+    let is_owner = state
+        .allowed
+        .first()
+        .is_some_and(|owner| same_number(owner, &number));
+    let session_id = if is_owner {
+        let shared = state.shared_session.lock().await;
+        match *shared {
+            Some(id) => id,
+            None => {
+                tracing::warn!("Signal: no active TUI session, creating one for owner");
+                drop(shared);
+                match state
+                    .session_svc
+                    .create_session(Some("Chat".to_string()))
+                    .await
+                {
+                    Ok(session) => {
+                        *state.shared_session.lock().await = Some(session.id);
+                        session.id
+                    }
+                    Err(e) => {
+                        tracing::error!("Signal: failed to create session: {}", e);
+                        send_text(&state.client, &chat, "Internal error creating session.").await;
+                        return;
+                    }
+                }
+            }
+        }
+    } else {
+        let mut map = state.extra_sessions.lock().await;
+        match map.get(&number) {
+            Some(id) => *id,
+            None => {
+                let title = format!("Signal: {}", sender);
+                match state.session_svc.create_session(Some(title)).await {
+                    Ok(session) => {
+                        map.insert(number.clone(), session.id);
+                        session.id
+                    }
+                    Err(e) => {
+                        tracing::error!("Signal: failed to create session: {}", e);
+                        send_text(&state.client, &chat, "Internal error creating session.").await;
+                        return;
+                    }
+                }
+            }
+        }
+    };
+
+    // "yes" / "always" / "no" answers a waiting approval instead of starting a turn
+    if let Some(decision) = ApprovalDecision::from_reply(&text)
+        && state.approvals.has_pending(session_id)
+    {
+        let status = state
+            .approvals
+            .resolve_for_session(session_id, decision, &state.agent);
+        tracing::info!("Signal: approval reply from {}: {}", number, status);
+        send_text(&state.client, &chat, &status).await;
+        return;
+    }
+
+    // Wait for this session's earlier turns to finish
+    let _turn = state.turns.lock(session_id).await;
+
+    // Tool approvals for this turn are asked in this chat
+    state.approvals.set_route(
+        session_id,
+        approval_prompter(state.client.clone(), chat.clone()),
+    );
+
+    match state
+        .agent
+        .send_message_with_tools(session_id, text, None)
+        .await
+    {
+        Ok(response) => {
+            // Always send text reply first (keeps chat searchable)
+            send_text(&state.client, &chat, &response.content).await;
+
+            // If input was voice AND TTS is enabled, also send voice note after text
+            if is_voice
+                && state.voice_config.tts_enabled
+                && let Some(ref oai_key) = state.openai_key
+            {
+                match crate::channels::voice::synthesize_speech(
+                    &response.content,
+                    oai_key,
+                    &state.voice_config.tts_voice,
+                    &state.voice_config.tts_model,
+                )
+                .await
+                {
+                    Ok(audio_bytes) => {
+                        let attachment = format!(
+                            "data:audio/ogg;filename=reply.ogg;base64,{}",
+                            base64::engine::general_purpose::STANDARD.encode(audio_bytes)
+                        );
+                        if let Err(e) = state
+                            .client
+                            .send_with_attachments(&chat, "", &[attachment])
+                            .await
+                        {
+                            tracing::error!("Signal: failed to send voice reply: {}", e);
+                        }
+                    }
+                    Err(e) => {
+                        tracing::error!("Signal: TTS error: {}", e);
+                    }
+                }
+            }
+        }
+        Err(e) => {
+            tracing::error!("Signal: agent error: {}", e);
+            send_text(&state.client, &chat, &format!("Error: {}", e)).await;
+        }
+    }
+}
+
+/// Send a message, logging failures
+async fn send_text(client: &SignalClient, chat: &Recipient, text: &str) {
+    if let Err(e) = client.send(chat, text).await {
+        tracing::error!("Signal: failed to send message: {}", e);
+    }
+}
+
+/// Download an image attachment to a temp file for the <<IMG:path>> pipeline.
+/// The file is removed after a delay.
+async fn save_image(
+    client: &SignalClient,
+    id: &str,
+    mime: &str,
+    chat: &Recipient,
+) -> Option<String> {
+    let bytes = match client.attachment(id, chat).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!("Signal: failed to download image: {}", e);
+            return None;
+        }
+    };
+    let ext = match mime {
+        "image/png" => "png",
+        "image/webp" => "webp",
+        "image/gif" => "gif",
+        _ => "jpg",
+    };
+    let path = std::env::temp_dir().join(format!("signal_img_{}.{}", Uuid::new_v4(), ext));
+    if let Err(e) = tokio::fs::write(&path, &bytes).await {
+        tracing::error!("Signal: failed to write temp image: {}", e);
+        return None;
+    }
+
+    let cleanup_path = path.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+        let _ = tokio::fs::remove_file(cleanup_path).await;
+    });
+    Some(path.to_string_lossy().to_string())
+}
+
+/// Transcribe a voice note. The error is the reply to send instead.
+async fn transcribe(
+    state: &HandlerState,
+    id: &str,
+    mime: &str,
+    chat: &Recipient,
+) -> Result<String, String> {
+    if !state.voice_config.stt_enabled {
+        return Err("Voice notes are not enabled.".to_string());
+    }
+    let Some(stt_key) = state
+        .voice_config
+        .stt_provider
+        .as_ref()
+        .and_then(|p| p.api_key.clone())
+    else {
+        tracing::warn!("Signal: voice note received but no STT API key configured");
+        return Err("Voice transcription not configured (missing API key).".to_string());
+    };
+
+    let audio = state.client.attachment(id, chat).await.map_err(|e| {
+        tracing::error!("Signal: failed to download voice note: {}", e);
+        "Failed to download voice note.".to_string()
+    })?;
+    let ext = match mime {
+        "audio/ogg" => "ogg",
+        "audio/mpeg" => "mp3",
+        "audio/mp4" | "audio/x-m4a" => "m4a",
+        "audio/wav" | "audio/x-wav" => "wav",
+        _ => "aac",
+    };
+    let file_name = format!("voice.{}", ext);
+
+    match crate::channels::voice::transcribe_audio_file(audio, &stt_key, &file_name, mime).await {
+        Ok(transcript) => {
+            tracing::info!(
+                "Signal: transcribed voice: {}",
+                &transcript[..transcript.floor_char_boundary(80)]
+            );
+            Ok(transcript)
+        }
+        Err(e) => {
+            tracing::error!("Signal: STT error: {}", e);
+            Err(format!("Transcription error: {}", e))
+        }
+    }
+}
+
+/// Sends approval prompts to `chat`, answered by replying with a keyword
+fn approval_prompter(client: SignalClient, chat: Recipient) -> ApprovalPrompter {
+    Arc::new(move |prompt| {
+        let client = client.clone();
+        let chat = chat.clone();
+        Box::pin(async move {
+            let text = format!(
+                "{}\n\nReply yes to run it once, always to allow calls like this from now on, or no to deny.",
+                prompt.text
+            );
+            match client.send(&chat, &text).await {
+                Ok(()) => true,
+                Err(e) => {
+                    tracing::error!("Signal: failed to send approval prompt: {}", e);
+                    false
+                }
+            }
+        })
+    })
+}
+
+/// Whether two phone numbers match, ignoring formatting ("+1 555-0100" = "15550100")
+fn same_number(a: &str, b: &str) -> bool {
+    let digits = |s: &str| s.chars().filter(char::is_ascii_digit).collect::<String>();
+    let a = digits(a);
+    !a.is_empty() && a == digits(b)
+}
+
+/// respond_to / allowed_channels filtering for group messages
+fn should_respond_in_group(
+    respond_to: &RespondTo,
+    allowed_channels: &HashSet<String>,
+    group_id: &str,
+    data: &DataMessage,
+    account: &str,
+) -> bool {
+    // Check allowed_channels (empty = all groups allowed)
+    if !allowed_channels.is_empty() && !allowed_channels.contains(group_id) {
+        tracing::debug!("Signal: ignoring message in non-allowed group {}", group_id);
+        return false;
+    }
+
+    match respond_to {
+        RespondTo::DmOnly => {
+            tracing::debug!("Signal: respond_to=dm_only, ignoring group message");
+            false
+        }
+        RespondTo::Mention => {
+            // @mentioned, or a reply quoting one of the bot's messages
+            let mentioned = data
+                .mentions
+                .iter()
+                .any(|m| m.number.as_deref().is_some_and(|n| same_number(n, account)));
+            let replied_to_bot = data
+                .quote
+                .as_ref()
+                .and_then(|q| q.author_number.as_deref())
+                .is_some_and(|n| same_number(n, account));
+            if !mentioned && !replied_to_bot {
+                tracing::debug!("Signal: respond_to=mention, bot not mentioned — ignoring");
+            }
+            mentioned || replied_to_bot
+        }
+        RespondTo::All => true,
+    }
+}
+
+/// Replace mention placeholders with `@name`, dropping mentions of the bot.
+/// Mention offsets count UTF-16 code units.
+fn render_mentions(text: &str, mentions: &[Mention], account: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut offset = 0;
+    for c in text.chars() {
+        let mention = if c == MENTION_PLACEHOLDER {
+            mentions.iter().find(|m| m.start == offset)
+        } else {
+            None
+        };
+        match mention {
+            Some(m) if m.number.as_deref().is_some_and(|n| same_number(n, account)) => {}
+            Some(m) => {
+                let name = m
+                    .name
+                    .as_deref()
+                    .or(m.number.as_deref())
+                    .unwrap_or("someone");
+                result.push('@');
+                result.push_str(name);
+            }
+            None => result.push(c),
+        }
+        offset += c.len_utf16();
+    }
+    result.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::signal::client::Quote;
+
+    const BOT: &str = "+15550000000";
+
+    fn mention(number: &str, start: usize) -> Mention {
+        Mention {
+            name: Some(number.to_string()),
+            number: Some(number.to_string()),
+            uuid: None,
+            start,
+            length: 1,
+        }
+    }
+
+    #[test]
+    fn test_same_number() {
+        assert!(same_number("+1 555-000-0000", "15550000000"));
+        assert!(!same_number("+15550000000", "+15550000001"));
+        assert!(!same_number("", ""));
+    }
+
+    #[test]
+    fn test_render_mentions() {
+        let text = "\u{FFFC} ask \u{FFFC} about 🦀";
+        let mentions = vec![mention(BOT, 0), mention("+15551112222", 6)];
+        assert_eq!(
+            render_mentions(text, &mentions, BOT),
+            "ask @+15551112222 about 🦀"
+        );
+    }
+
+    #[test]
+    fn test_group_mention_gate() {
+        let no_channels = HashSet::new();
+        let plain = DataMessage {
+            message: Some("hello".to_string()),
+            ..Default::default()
+        };
+        let mentioned = DataMessage {
+            mentions: vec![mention(BOT, 0)],
+            ..Default::default()
+        };
+        let reply = DataMessage {
+            quote: Some(Quote {
+                author_number: Some(BOT.to_string()),
+                author_uuid: None,
+            }),
+            ..Default::default()
+        };
+
+        let mention_only = RespondTo::Mention;
+        assert!(!should_respond_in_group(
+            &mention_only,
+            &no_channels,
+            "g1",
+            &plain,
+            BOT
+        ));
+        assert!(should_respond_in_group(
+            &mention_only,
+            &no_channels,
+            "g1",
+            &mentioned,
+            BOT
+        ));
+        assert!(should_respond_in_group(
+            &mention_only,
+            &no_channels,
+            "g1",
+            &reply,
+            BOT
+        ));
+        assert!(!should_respond_in_group(
+            &RespondTo::DmOnly,
+            &no_channels,
+            "g1",
+            &mentioned,
+            BOT
+        ));
+        assert!(should_respond_in_group(
+            &RespondTo::All,
+            &no_channels,
+            "g1",
+            &plain,
+            BOT
+        ));
+
+        let only_g2: HashSet<String> = ["g2".to_string()].into();
+        assert!(!should_respond_in_group(
+            &RespondTo::All,
+            &only_g2,
+            "g1",
+            &plain,
+            BOT
+        ));
+    }
+}
@@ -0,0 +1,63 @@
+//! Signal Integration
+//!
+//! Talks to a local `signal-cli` daemon over its JSON-RPC HTTP interface
+//! (`signal-cli -a +15551234567 daemon --http 127.0.0.1:8080`), forwarding
+//! messages from allowlisted phone numbers to the AgentService and replying
+//! with responses.
+
+mod agent;
+pub(crate) mod client;
+pub(crate) mod handler;
+
+pub use agent::SignalAgent;
+pub use client::{Recipient, SignalClient};
+
+use tokio::sync::Mutex;
+
+/// Shared Signal state for proactive messaging.
+///
+/// Set when the agent starts (client and owner number from config).
+/// Read by the `signal_send` tool to send messages on demand.
+pub struct SignalState {
+    client: Mutex<Option<SignalClient>>,
+    /// Owner's phone number — first in allowed_phones
+    owner_number: Mutex<Option<String>>,
+}
+
+impl Default for SignalState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SignalState {
+    pub fn new() -> Self {
+        Self {
+            client: Mutex::new(None),
+            owner_number: Mutex::new(None),
+        }
+    }
+
+    /// Store the daemon client and the owner's number.
+    pub async fn set_connected(&self, client: SignalClient, owner_number: Option<String>) {
+        *self.client.lock().await = Some(client);
+        if let Some(number) = owner_number {
+            *self.owner_number.lock().await = Some(number);
+        }
+    }
+
+    /// Get a clone of the daemon client, if the agent is running.
+    pub async fn client(&self) -> Option<SignalClient> {
+        self.client.lock().await.clone()
+    }
+
+    /// Get the owner's phone number for proactive messaging.
+    pub async fn owner_number(&self) -> Option<String> {
+        self.owner_number.lock().await.clone()
+    }
+
+    /// Check if Signal is currently connected.
+    pub async fn is_connected(&self) -> bool {
+        self.client.lock().await.is_some()
+    }
+}
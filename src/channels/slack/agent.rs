@@ -6,6 +6,7 @@
 use super::handler;
 use super::SlackState;
 use crate::brain::agent::AgentService;
+use crate::channels::ChannelApprovals;
 use crate::config::RespondTo;
 use crate::services::{ServiceContext, SessionService};
 use slack_morphism::prelude::*;
@@ -17,6 +18,7 @@ use uuid::Uuid;
 /// Slack bot that forwards messages to the AgentService via Socket Mode
 pub struct SlackAgent {
     agent_service: Arc<AgentService>,
+    approvals: Arc<ChannelApprovals>,
     session_service: SessionService,
     allowed_ids: Vec<String>,
     shared_session_id: Arc<Mutex<Option<Uuid>>>,
@@ -26,8 +28,10 @@ pub struct SlackAgent {
 }
 
 impl SlackAgent {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         agent_service: Arc<AgentService>,
+        approvals: Arc<ChannelApprovals>,
         service_context: ServiceContext,
         allowed_ids: Vec<String>,
         shared_session_id: Arc<Mutex<Option<Uuid>>>,
@@ -37,6 +41,7 @@ impl SlackAgent {
     ) -> Self {
         Self {
             agent_service,
+            approvals,
             session_service: SessionService::new(service_context),
             allowed_ids,
             shared_session_id,
@@ -100,6 +105,7 @@ impl SlackAgent {
             // Set up handler state (global static — one Slack instance per process)
             let handler_state = handler::HandlerState {
                 agent: self.agent_service,
+                approvals: self.approvals,
                 session_svc: self.session_service,
                 allowed: Arc::new(self.allowed_ids.into_iter().collect()),
                 extra_sessions: Arc::new(Mutex::new(HashMap::new())),
@@ -116,8 +122,9 @@ impl SlackAgent {
                     tracing::warn!("Slack: handler state already initialized");
                 });
 
-            let socket_mode_callbacks =
-                SlackSocketModeListenerCallbacks::new().with_push_events(handler::on_push_event);
+            let socket_mode_callbacks = SlackSocketModeListenerCallbacks::new()
+                .with_push_events(handler::on_push_event)
+                .with_interaction_events(handler::on_interaction_event);
 
             let listener_environment = Arc::new(
                 SlackClientEventsListenerEnvironment::new(client)
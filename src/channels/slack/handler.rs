@@ -1,7 +1,8 @@
 //! Slack Message Handler
 //!
 //! Processes incoming Slack messages: text, allowlist enforcement,
-//! session routing (owner shares TUI session, others get per-user sessions).
+//! session routing (owner shares TUI session, others get per-user sessions),
+//! and tool approval button presses (Block Kit interactions).
 //!
 //! Uses a module-level static for handler state because slack-morphism's
 //! Socket Mode callbacks require plain function pointers (not closures).
@@ -9,6 +10,7 @@
 use super::SlackState;
 use crate::config::RespondTo;
 use crate::brain::agent::AgentService;
+use crate::channels::{ApprovalDecision, ApprovalPrompter, ChannelApprovals};
 use crate::services::SessionService;
 use slack_morphism::prelude::*;
 use std::collections::{HashMap, HashSet};
@@ -23,6 +25,7 @@ pub static HANDLER_STATE: OnceLock<Arc<HandlerState>> = OnceLock::new();
 /// Shared state for the Slack message handler callbacks.
 pub struct HandlerState {
     pub agent: Arc<AgentService>,
+    pub approvals: Arc<ChannelApprovals>,
     pub session_svc: SessionService,
     pub allowed: Arc<HashSet<String>>,
     pub extra_sessions: Arc<Mutex<HashMap<String, Uuid>>>,
@@ -68,7 +71,9 @@ pub async fn on_push_event(
     match event.event {
         SlackEventCallbackBody::Message(msg) => {
             tracing::debug!("Slack: message event from user={:?}, channel={:?}, bot_id={:?}", msg.sender.user, msg.origin.channel, msg.sender.bot_id);
-            handle_message(&msg, client).await;
+            // Run the turn in the background so the listener can deliver the
+            // approval button presses it may wait on
+            tokio::spawn(async move { handle_message(&msg, client).await });
         }
         _ => {
             tracing::debug!("Slack: unhandled event type");
@@ -77,6 +82,18 @@ pub async fn on_push_event(
     Ok(())
 }
 
+/// Socket Mode interaction callback — approval button presses.
+pub async fn on_interaction_event(
+    event: SlackInteractionEvent,
+    client: Arc<SlackHyperClient>,
+    _states: SlackClientEventsUserState,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if let SlackInteractionEvent::BlockActions(block_actions) = event {
+        tokio::spawn(async move { handle_block_actions(&block_actions, client).await });
+    }
+    Ok(())
+}
+
 /// Socket Mode error handler.
 pub fn on_error(
     err: Box<dyn std::error::Error + Send + Sync>,
@@ -240,6 +257,12 @@ async fn handle_message(msg: &SlackMessageEvent, client: Arc<SlackHyperClient>)
         }
     };
 
+    // Tool approvals for this turn are asked in this channel
+    state.approvals.set_route(
+        session_id,
+        approval_prompter(client.clone(), state.bot_token.clone(), channel_id.clone()),
+    );
+
     // Send to agent
     match state
         .agent
@@ -275,6 +298,104 @@ async fn handle_message(msg: &SlackMessageEvent, client: Arc<SlackHyperClient>)
     }
 }
 
+/// Sends approval prompts to `channel_id` as a Block Kit message with Yes / Always / No buttons
+fn approval_prompter(
+    client: Arc<SlackHyperClient>,
+    bot_token: String,
+    channel_id: String,
+) -> ApprovalPrompter {
+    Arc::new(move |prompt| {
+        let client = client.clone();
+        let bot_token = bot_token.clone();
+        let channel_id = channel_id.clone();
+        Box::pin(async move {
+            let button = |decision: ApprovalDecision, label: &str, style: Option<&str>| {
+                let data = decision.callback_data(&prompt.id);
+                let button = SlackBlockButtonElement::new(
+                    SlackActionId::new(data.clone()),
+                    SlackBlockPlainTextOnly::from(label),
+                )
+                .with_value(data);
+                SlackActionBlockElement::Button(match style {
+                    Some(style) => button.with_style(style.to_string()),
+                    None => button,
+                })
+            };
+            let text: String = prompt.text.chars().take(2900).collect();
+            let blocks = vec![
+                SlackBlock::Section(
+                    SlackSectionBlock::new()
+                        .with_text(SlackBlockText::Plain(SlackBlockPlainText::new(text.clone()))),
+                ),
+                SlackBlock::Actions(SlackActionsBlock::new(vec![
+                    button(ApprovalDecision::AllowOnce, "Yes", Some("primary")),
+                    button(ApprovalDecision::AllowAlways, "Always", None),
+                    button(ApprovalDecision::Deny, "No", Some("danger")),
+                ])),
+            ];
+
+            let token = SlackApiToken::new(SlackApiTokenValue::from(bot_token));
+            let session = client.open_session(&token);
+            let request = SlackApiChatPostMessageRequest::new(
+                SlackChannelId::new(channel_id),
+                SlackMessageContent::new().with_text(text).with_blocks(blocks),
+            );
+            match session.chat_post_message(&request).await {
+                Ok(_) => true,
+                Err(e) => {
+                    tracing::error!("Slack: failed to send approval prompt: {}", e);
+                    false
+                }
+            }
+        })
+    })
+}
+
+/// Handle a press on an approval button.
+async fn handle_block_actions(
+    event: &SlackInteractionBlockActionsEvent,
+    client: Arc<SlackHyperClient>,
+) {
+    let Some(state) = HANDLER_STATE.get() else {
+        tracing::error!("Slack: handler state not initialized");
+        return;
+    };
+
+    let Some((decision, id)) = event
+        .actions
+        .iter()
+        .flatten()
+        .filter_map(|action| action.value.as_deref())
+        .find_map(ApprovalDecision::parse_callback_data)
+    else {
+        return;
+    };
+
+    let user_id = event.user.as_ref().map(|u| u.id.to_string()).unwrap_or_default();
+    if !state.allowed.is_empty() && !state.allowed.contains(&user_id) {
+        tracing::warn!("Slack: approval button pressed by non-allowed user {}", user_id);
+        return;
+    }
+
+    let status = state.approvals.resolve(id, decision, &state.agent);
+    tracing::info!("Slack: approval {} by user {}: {}", id, user_id, status);
+
+    // Replace the buttons with the outcome
+    if let (Some(channel), Some(message)) = (&event.channel, &event.message) {
+        let original = message.content.text.clone().unwrap_or_default();
+        let token = SlackApiToken::new(SlackApiTokenValue::from(state.bot_token.clone()));
+        let session = client.open_session(&token);
+        let request = SlackApiChatUpdateRequest::new(
+            channel.id.clone(),
+            SlackMessageContent::new().with_text(format!("{}\n\n{}", original, status)),
+            message.origin.ts.clone(),
+        );
+        if let Err(e) = session.chat_update(&request).await {
+            tracing::error!("Slack: failed to update approval prompt: {}", e);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2,20 +2,23 @@
 //!
 //! Agent struct and startup logic.
 
-use super::handler::handle_message;
+use super::handler::{handle_callback_query, handle_message};
 use super::TelegramState;
 use crate::brain::agent::AgentService;
+use crate::channels::ChannelApprovals;
 use crate::config::{RespondTo, VoiceConfig};
 use crate::services::{ServiceContext, SessionService};
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use teloxide::prelude::*;
+use teloxide::types::{CallbackQuery, UpdateKind};
 use tokio::sync::Mutex;
 use uuid::Uuid;
 
 /// Telegram bot that forwards messages to the agent
 pub struct TelegramAgent {
     agent_service: Arc<AgentService>,
+    approvals: Arc<ChannelApprovals>,
     session_service: SessionService,
     allowed_users: HashSet<i64>,
     voice_config: VoiceConfig,
@@ -31,6 +34,7 @@ impl TelegramAgent {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         agent_service: Arc<AgentService>,
+        approvals: Arc<ChannelApprovals>,
         service_context: ServiceContext,
         allowed_users: Vec<i64>,
         voice_config: VoiceConfig,
@@ -42,6 +46,7 @@ impl TelegramAgent {
     ) -> Self {
         Self {
             agent_service,
+            approvals,
             session_service: SessionService::new(service_context),
             allowed_users: allowed_users.into_iter().collect(),
             voice_config,
@@ -115,6 +120,7 @@ impl TelegramAgent {
             let extra_sessions: Arc<Mutex<HashMap<i64, Uuid>>> =
                 Arc::new(Mutex::new(HashMap::new()));
             let agent = self.agent_service.clone();
+            let approvals = self.approvals.clone();
             let session_svc = self.session_service.clone();
             let allowed = Arc::new(self.allowed_users);
             let voice_config = Arc::new(self.voice_config);
@@ -125,8 +131,13 @@ impl TelegramAgent {
             let respond_to = Arc::new(self.respond_to);
             let allowed_channels: Arc<HashSet<String>> = Arc::new(self.allowed_channels);
 
-            let handler = Update::filter_message().endpoint(move |bot: Bot, msg: Message| {
+            let callback_agent = agent.clone();
+            let callback_approvals = approvals.clone();
+            let callback_allowed = allowed.clone();
+
+            let message_handler = Update::filter_message().endpoint(move |bot: Bot, msg: Message| {
                 let agent = agent.clone();
+                let approvals = approvals.clone();
                 let session_svc = session_svc.clone();
                 let allowed = allowed.clone();
                 let extra_sessions = extra_sessions.clone();
@@ -142,6 +153,7 @@ impl TelegramAgent {
                         bot,
                         msg,
                         agent,
+                        approvals,
                         session_svc,
                         allowed,
                         extra_sessions,
@@ -157,7 +169,29 @@ impl TelegramAgent {
                 }
             });
 
-            Dispatcher::builder(bot, handler).build().dispatch().await;
+            // Approval button presses
+            let callback_handler =
+                Update::filter_callback_query().endpoint(move |bot: Bot, query: CallbackQuery| {
+                    let agent = callback_agent.clone();
+                    let approvals = callback_approvals.clone();
+                    let allowed = callback_allowed.clone();
+                    async move { handle_callback_query(bot, query, agent, approvals, allowed).await }
+                });
+
+            let handler = dptree::entry()
+                .branch(message_handler)
+                .branch(callback_handler);
+
+            // Updates from one chat are handled in order, except button presses: the
+            // message handler for that chat is still waiting on the approval.
+            Dispatcher::builder(bot, handler)
+                .distribution_function(|update: &Update| match update.kind {
+                    UpdateKind::CallbackQuery(_) => None,
+                    _ => update.chat().map(|chat| chat.id),
+                })
+                .build()
+                .dispatch()
+                .await;
         })
     }
 }
@@ -1,15 +1,19 @@
 //! Telegram Message Handler
 //!
-//! Processes incoming messages: text, voice (STT/TTS), photos, image documents, allowlist enforcement.
+//! Processes incoming messages: text, voice (STT/TTS), photos, image documents, allowlist enforcement,
+//! and tool approval button presses.
 
 use super::TelegramState;
 use crate::config::{RespondTo, VoiceConfig};
 use crate::brain::agent::AgentService;
+use crate::channels::{ApprovalDecision, ApprovalPrompter, ChannelApprovals};
 use crate::services::SessionService;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use teloxide::prelude::*;
-use teloxide::types::{ChatKind, InputFile};
+use teloxide::types::{
+    CallbackQuery, ChatId, ChatKind, InlineKeyboardButton, InlineKeyboardMarkup, InputFile,
+};
 use tokio::sync::Mutex;
 use uuid::Uuid;
 
@@ -19,6 +23,7 @@ pub(crate) async fn handle_message(
     bot: Bot,
     msg: Message,
     agent: Arc<AgentService>,
+    approvals: Arc<ChannelApprovals>,
     session_svc: SessionService,
     allowed: Arc<HashSet<i64>>,
     extra_sessions: Arc<Mutex<HashMap<i64, Uuid>>>,
@@ -382,6 +387,9 @@ pub(crate) async fn handle_message(
         }
     };
 
+    // Tool approvals for this turn are asked in this chat
+    approvals.set_route(session_id, approval_prompter(bot.clone(), msg.chat.id));
+
     // Send to agent (with tools so the agent can use file ops, search, etc.)
     match agent.send_message_with_tools(session_id, text, None).await {
         Ok(response) => {
@@ -425,6 +433,73 @@ pub(crate) async fn handle_message(
     Ok(())
 }
 
+/// Sends approval prompts to `chat_id` with Yes / Always / No buttons
+fn approval_prompter(bot: Bot, chat_id: ChatId) -> ApprovalPrompter {
+    Arc::new(move |prompt| {
+        let bot = bot.clone();
+        Box::pin(async move {
+            let keyboard = InlineKeyboardMarkup::new(vec![vec![
+                InlineKeyboardButton::callback(
+                    "✅ Yes",
+                    ApprovalDecision::AllowOnce.callback_data(&prompt.id),
+                ),
+                InlineKeyboardButton::callback(
+                    "🔓 Always",
+                    ApprovalDecision::AllowAlways.callback_data(&prompt.id),
+                ),
+                InlineKeyboardButton::callback(
+                    "❌ No",
+                    ApprovalDecision::Deny.callback_data(&prompt.id),
+                ),
+            ]]);
+            match bot.send_message(chat_id, prompt.text).reply_markup(keyboard).await {
+                Ok(_) => true,
+                Err(e) => {
+                    tracing::error!("Telegram: failed to send approval prompt: {}", e);
+                    false
+                }
+            }
+        })
+    })
+}
+
+/// Handle a press on an approval button
+pub(crate) async fn handle_callback_query(
+    bot: Bot,
+    query: CallbackQuery,
+    agent: Arc<AgentService>,
+    approvals: Arc<ChannelApprovals>,
+    allowed: Arc<HashSet<i64>>,
+) -> ResponseResult<()> {
+    let Some((decision, id)) = query
+        .data
+        .as_deref()
+        .and_then(ApprovalDecision::parse_callback_data)
+    else {
+        return Ok(());
+    };
+
+    let user_id = query.from.id.0 as i64;
+    if !allowed.contains(&user_id) {
+        tracing::warn!("Telegram: approval button pressed by non-allowed user {}", user_id);
+        bot.answer_callback_query(query.id.clone())
+            .text("You are not authorized.")
+            .await?;
+        return Ok(());
+    }
+
+    let status = approvals.resolve(id, decision, &agent);
+    tracing::info!("Telegram: approval {} by user {}: {}", id, user_id, status);
+    bot.answer_callback_query(query.id.clone()).await?;
+
+    // Replace the buttons with the outcome
+    if let Some(message) = query.regular_message() {
+        let text = format!("{}\n\n{}", message.text().unwrap_or_default(), status);
+        bot.edit_message_text(message.chat.id, message.id, text).await?;
+    }
+    Ok(())
+}
+
 /// Convert markdown to Telegram-safe HTML
 /// Handles: code blocks, inline code, bold, italic. Escapes HTML entities.
 fn markdown_to_telegram_html(text: &str) -> String {
@@ -0,0 +1,65 @@
+//! Per-Session Turn Queue
+//!
+//! Channels whose handlers run each incoming message as its own task
+//! (WhatsApp, Signal) take the session's turn lock before calling the agent,
+//! so two messages in one chat run one after the other instead of
+//! interleaving history and tool calls. Approval replies don't take it: they
+//! answer the turn that is already running.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::OwnedMutexGuard;
+use uuid::Uuid;
+
+/// One agent turn at a time per session, in arrival order
+#[derive(Default)]
+pub struct SessionTurns {
+    locks: Mutex<HashMap<Uuid, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl SessionTurns {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wait until earlier turns of `session_id` have finished. The turn lasts
+    /// until the guard is dropped.
+    pub async fn lock(&self, session_id: Uuid) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.locks.lock().expect("session turns lock poisoned");
+            // Forget sessions nobody is running or waiting on
+            locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+            Arc::clone(locks.entry(session_id).or_default())
+        };
+        lock.lock_owned().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_turns_run_one_at_a_time_per_session() {
+        let turns = Arc::new(SessionTurns::new());
+        let session = Uuid::new_v4();
+
+        let first = turns.lock(session).await;
+        let waiting = tokio::spawn({
+            let turns = turns.clone();
+            async move { turns.lock(session).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiting.is_finished());
+
+        // Other sessions aren't held up
+        let _other = turns.lock(Uuid::new_v4()).await;
+
+        drop(first);
+        tokio::time::timeout(Duration::from_secs(1), waiting)
+            .await
+            .expect("second turn should start once the first ends")
+            .unwrap();
+    }
+}
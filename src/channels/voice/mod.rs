@@ -4,4 +4,4 @@
 
 mod service;
 
-pub use service::{synthesize_speech, transcribe_audio};
+pub use service::{synthesize_speech, transcribe_audio, transcribe_audio_file};
@@ -18,17 +18,46 @@ pub async fn transcribe_audio(audio_bytes: Vec<u8>, groq_api_key: &str) -> Resul
     transcribe_audio_with_url(audio_bytes, groq_api_key, GROQ_TRANSCRIPTION_URL).await
 }
 
+/// Transcribe audio in another format (e.g. AAC voice notes from Signal).
+///
+/// `file_name` carries the extension Whisper uses to detect the format.
+pub async fn transcribe_audio_file(
+    audio_bytes: Vec<u8>,
+    groq_api_key: &str,
+    file_name: &str,
+    mime_type: &str,
+) -> Result<String> {
+    transcribe_with_url(
+        audio_bytes,
+        groq_api_key,
+        GROQ_TRANSCRIPTION_URL,
+        file_name,
+        mime_type,
+    )
+    .await
+}
+
 /// Internal: transcribe with configurable URL (for testing).
 async fn transcribe_audio_with_url(
     audio_bytes: Vec<u8>,
     api_key: &str,
     url: &str,
+) -> Result<String> {
+    transcribe_with_url(audio_bytes, api_key, url, "voice.ogg", "audio/ogg").await
+}
+
+async fn transcribe_with_url(
+    audio_bytes: Vec<u8>,
+    api_key: &str,
+    url: &str,
+    file_name: &str,
+    mime_type: &str,
 ) -> Result<String> {
     let client = Client::new();
 
     let file_part = reqwest::multipart::Part::bytes(audio_bytes)
-        .file_name("voice.ogg")
-        .mime_str("audio/ogg")?;
+        .file_name(file_name.to_string())
+        .mime_str(mime_type)?;
 
     let form = reqwest::multipart::Form::new()
         .part("file", file_part)
@@ -6,6 +6,7 @@ use super::handler;
 use super::WhatsAppState;
 use crate::config::VoiceConfig;
 use crate::brain::agent::AgentService;
+use crate::channels::{ChannelApprovals, SessionTurns};
 use crate::services::{ServiceContext, SessionService};
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
@@ -21,6 +22,7 @@ use whatsapp_rust_ureq_http_client::UreqHttpClient;
 /// WhatsApp agent that forwards messages to the AgentService
 pub struct WhatsAppAgent {
     agent_service: Arc<AgentService>,
+    approvals: Arc<ChannelApprovals>,
     session_service: SessionService,
     allowed_phones: Vec<String>,
     voice_config: VoiceConfig,
@@ -31,6 +33,7 @@ pub struct WhatsAppAgent {
 impl WhatsAppAgent {
     pub fn new(
         agent_service: Arc<AgentService>,
+        approvals: Arc<ChannelApprovals>,
         service_context: ServiceContext,
         allowed_phones: Vec<String>,
         voice_config: VoiceConfig,
@@ -39,6 +42,7 @@ impl WhatsAppAgent {
     ) -> Self {
         Self {
             agent_service,
+            approvals,
             session_service: SessionService::new(service_context),
             allowed_phones,
             voice_config,
@@ -98,6 +102,7 @@ impl WhatsAppAgent {
                 .map(|p| format!("{}@s.whatsapp.net", p.trim_start_matches('+')));
 
             let agent = self.agent_service.clone();
+            let approvals = self.approvals.clone();
             let session_svc = self.session_service.clone();
             let allowed: Arc<HashSet<String>> =
                 Arc::new(self.allowed_phones.into_iter().collect());
@@ -107,6 +112,7 @@ impl WhatsAppAgent {
             let owner_jid_clone = owner_jid.clone();
             let extra_sessions: Arc<Mutex<HashMap<String, Uuid>>> =
                 Arc::new(Mutex::new(HashMap::new()));
+            let turns = Arc::new(SessionTurns::new());
 
             let bot_result = Bot::builder()
                 .with_backend(backend)
@@ -114,9 +120,11 @@ impl WhatsAppAgent {
                 .with_http_client(UreqHttpClient::new())
                 .on_event(move |event, client| {
                     let agent = agent.clone();
+                    let approvals = approvals.clone();
                     let session_svc = session_svc.clone();
                     let allowed = allowed.clone();
                     let extra_sessions = extra_sessions.clone();
+                    let turns = turns.clone();
                     let voice_config = voice_config.clone();
                     let shared_session = shared_session.clone();
                     let wa_state = wa_state.clone();
@@ -139,18 +147,22 @@ impl WhatsAppAgent {
                             }
                             Event::Message(msg, info) => {
                                 tracing::debug!("WhatsApp: Event::Message received");
-                                handler::handle_message(
+                                // Handled in the background so an approval reply can
+                                // reach the turn that is waiting for it; turns of one
+                                // session still run in order (see `SessionTurns`)
+                                tokio::spawn(handler::handle_message(
                                     *msg,
                                     info,
                                     client,
                                     agent,
+                                    approvals,
                                     session_svc,
                                     allowed,
                                     extra_sessions,
+                                    turns,
                                     voice_config,
                                     shared_session,
-                                )
-                                .await;
+                                ));
                             }
                             Event::LoggedOut(_) => {
                                 tracing::warn!("WhatsApp: logged out");
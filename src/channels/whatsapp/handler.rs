@@ -1,10 +1,12 @@
 //! WhatsApp Message Handler
 //!
 //! Processes incoming WhatsApp messages: text + images, allowlist enforcement,
-//! session routing (owner shares TUI session, others get per-phone sessions).
+//! session routing (owner shares TUI session, others get per-phone sessions),
+//! and tool approval keyword replies (WhatsApp has no buttons for linked devices).
 
 use crate::config::VoiceConfig;
 use crate::brain::agent::AgentService;
+use crate::channels::{ApprovalDecision, ApprovalPrompter, ChannelApprovals, SessionTurns};
 use crate::services::SessionService;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
@@ -12,6 +14,7 @@ use tokio::sync::Mutex;
 use uuid::Uuid;
 
 use wacore::types::message::MessageInfo;
+use wacore_binary::jid::Jid;
 use waproto::whatsapp::Message;
 use whatsapp_rust::client::Client;
 
@@ -184,9 +187,11 @@ pub(crate) async fn handle_message(
     info: MessageInfo,
     client: Arc<Client>,
     agent: Arc<AgentService>,
+    approvals: Arc<ChannelApprovals>,
     session_svc: SessionService,
     allowed: Arc<HashSet<String>>,
     extra_sessions: Arc<Mutex<HashMap<String, Uuid>>>,
+    turns: Arc<SessionTurns>,
     voice_config: Arc<VoiceConfig>,
     shared_session: Arc<Mutex<Option<Uuid>>>,
 ) {
@@ -324,6 +329,31 @@ pub(crate) async fn handle_message(
         }
     };
 
+    // "yes" / "always" / "no" answers a waiting approval instead of starting a turn
+    if let Some(decision) = ApprovalDecision::from_reply(&content)
+        && approvals.has_pending(session_id)
+    {
+        let status = approvals.resolve_for_session(session_id, decision, &agent);
+        tracing::info!("WhatsApp: approval reply from {}: {}", phone, status);
+        let reply = waproto::whatsapp::Message {
+            conversation: Some(format!("{}\n\n{}", MSG_HEADER, status)),
+            ..Default::default()
+        };
+        if let Err(e) = client.send_message(info.source.sender.clone(), reply).await {
+            tracing::error!("WhatsApp: failed to send approval status: {}", e);
+        }
+        return;
+    }
+
+    // Wait for this session's earlier turns to finish
+    let _turn = turns.lock(session_id).await;
+
+    // Tool approvals for this turn are asked in this chat
+    approvals.set_route(
+        session_id,
+        approval_prompter(client.clone(), info.source.sender.clone()),
+    );
+
     // Send to agent
     match agent.send_message_with_tools(session_id, content, None).await {
         Ok(response) => {
@@ -352,6 +382,31 @@ pub(crate) async fn handle_message(
     }
 }
 
+/// Sends approval prompts to `jid`, answered by replying with a keyword
+fn approval_prompter(client: Arc<Client>, jid: Jid) -> ApprovalPrompter {
+    Arc::new(move |prompt| {
+        let client = client.clone();
+        let jid = jid.clone();
+        Box::pin(async move {
+            let text = format!(
+                "{}\n\n{}\n\nReply *yes* to run it once, *always* to allow calls like this from now on, or *no* to deny.",
+                MSG_HEADER, prompt.text
+            );
+            let message = waproto::whatsapp::Message {
+                conversation: Some(text),
+                ..Default::default()
+            };
+            match client.send_message(jid, message).await {
+                Ok(_) => true,
+                Err(e) => {
+                    tracing::error!("WhatsApp: failed to send approval prompt: {}", e);
+                    false
+                }
+            }
+        })
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,4 +1,4 @@
-//! CLI subcommands — run, init, config, db, keyring, logs, and config loading.
+//! CLI subcommands — run, init, config, db, keyring, logs, mcp, and config loading.
 
 use anyhow::{Context, Result};
 use std::sync::Arc;
@@ -6,7 +6,7 @@ use std::sync::Arc;
 use crate::brain::prompt_builder::RuntimeInfo;
 use crate::brain::BrainLoader;
 
-use super::{DbCommands, LogCommands, OutputFormat};
+use super::{DbCommands, LogCommands, McpCommands, OutputFormat};
 
 /// Load configuration from file or defaults
 pub(crate) async fn load_config(config_path: Option<&str>) -> Result<crate::config::Config> {
@@ -215,47 +215,32 @@ pub(crate) async fn cmd_db(config: &crate::config::Config, operation: DbCommands
     }
 }
 
-/// Run a single command non-interactively
-pub(crate) async fn cmd_run(
+/// Build the standard tool registry used outside the TUI (`run`, `mcp serve`).
+pub(crate) fn build_tool_registry(
     config: &crate::config::Config,
-    prompt: String,
-    auto_approve: bool,
-    format: OutputFormat,
-) -> Result<()> {
-    use crate::{
-        db::Database,
-        brain::{
-            agent::AgentService,
-            tools::{
-                bash::BashTool, brave_search::BraveSearchTool, code_exec::CodeExecTool,
-                config_tool::ConfigTool, context::ContextTool, doc_parser::DocParserTool,
-                edit::EditTool, exa_search::ExaSearchTool, glob::GlobTool, grep::GrepTool,
-                http::HttpClientTool, ls::LsTool, memory_search::MemorySearchTool,
-                notebook::NotebookEditTool, plan_tool::PlanTool,
-                read::ReadTool, registry::ToolRegistry, session_search::SessionSearchTool,
-                slash_command::SlashCommandTool,
-                task::TaskTool, web_search::WebSearchTool, write::WriteTool,
-            },
-        },
-        services::{ServiceContext, SessionService},
+    db: &crate::db::Database,
+) -> crate::brain::tools::ToolRegistry {
+    use crate::brain::tools::{
+        a2a_delegate::A2aDelegateTool, bash::BashTool, brave_search::BraveSearchTool, code_exec::CodeExecTool,
+        config_tool::ConfigTool, context::ContextTool, doc_parser::DocParserTool,
+        edit::EditTool, exa_search::ExaSearchTool, glob::GlobTool, grep::GrepTool,
+        http::HttpClientTool, ls::LsTool, memory_search::MemorySearchTool,
+        notebook::NotebookEditTool, plan_tool::PlanTool,
+        process::{ProcessManager, ProcessTool},
+        read::ReadTool, registry::ToolRegistry, schedule::ScheduleTool,
+        session_search::SessionSearchTool,
+        slash_command::SlashCommandTool,
+        task::TaskTool, web_search::WebSearchTool, write::WriteTool,
     };
 
-    tracing::info!("Running non-interactive command: {}", prompt);
-
-    // Initialize database
-    let db = Database::connect(&config.database.path).await?;
-    db.run_migrations().await?;
-
-    // Select provider based on configuration using factory
-    let provider = crate::brain::provider::create_provider(config)?;
-
-    // Create tool registry
     let mut tool_registry = ToolRegistry::new();
     // Phase 1: Essential file operations
     tool_registry.register(Arc::new(ReadTool));
     tool_registry.register(Arc::new(WriteTool));
     tool_registry.register(Arc::new(EditTool));
-    tool_registry.register(Arc::new(BashTool));
+    tool_registry.register(Arc::new(
+        BashTool::new().with_persistent_shells(config.agent.persistent_shell),
+    ));
     tool_registry.register(Arc::new(LsTool));
     tool_registry.register(Arc::new(GlobTool));
     tool_registry.register(Arc::new(GrepTool));
@@ -269,10 +254,16 @@ pub(crate) async fn cmd_run(
     tool_registry.register(Arc::new(ContextTool));
     tool_registry.register(Arc::new(HttpClientTool));
     tool_registry.register(Arc::new(PlanTool));
+    // Background processes — stopped when the registry is dropped
+    tool_registry.register(Arc::new(ProcessTool::new(Arc::new(ProcessManager::new()))));
     // Memory search (built-in FTS5, always available)
     tool_registry.register(Arc::new(MemorySearchTool));
     // Session search — hybrid QMD search across all session message history
     tool_registry.register(Arc::new(SessionSearchTool::new(db.pool().clone())));
+    // Scheduled prompts (run by the TUI/daemon schedule runner)
+    tool_registry.register(Arc::new(ScheduleTool::new(db.pool().clone())));
+    // Delegation to remote A2A agents ([[a2a.peers]])
+    tool_registry.register(Arc::new(A2aDelegateTool::new(config.a2a.peers.clone())));
     // Config management (read/write config.toml, commands.toml)
     tool_registry.register(Arc::new(ConfigTool));
     // Slash command invocation (agent can call any slash command)
@@ -291,6 +282,42 @@ pub(crate) async fn cmd_run(
         tool_registry.register(Arc::new(BraveSearchTool::new(brave_key)));
     }
 
+    tool_registry
+}
+
+/// Run a single command non-interactively
+pub(crate) async fn cmd_run(
+    config: &crate::config::Config,
+    prompt: String,
+    auto_approve: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    use crate::{
+        db::Database,
+        brain::agent::AgentService,
+        services::{ServiceContext, SessionService},
+    };
+
+    tracing::info!("Running non-interactive command: {}", prompt);
+
+    // Initialize database
+    let db = Database::connect(&config.database.path).await?;
+    db.run_migrations().await?;
+
+    // Select provider based on configuration using factory
+    let provider = crate::brain::provider::create_provider(config)?;
+
+    // Create tool registry
+    let mut tool_registry = build_tool_registry(config, &db);
+    // Bee Colony debates ([[a2a.bees]]) — the active provider is the queen
+    tool_registry.register(Arc::new(crate::brain::tools::debate::DebateTool::new(
+        config,
+        provider.clone(),
+        db.pool().clone(),
+    )));
+    // MCP servers from [[mcp.servers]] — tools registered as mcp__<server>__<tool>
+    crate::mcp::register_servers(&config.mcp, &mut tool_registry).await;
+
     // Build dynamic system brain from workspace files
     let brain_path = BrainLoader::resolve_path();
     let brain_loader = BrainLoader::new(brain_path.clone());
@@ -367,6 +394,46 @@ pub(crate) async fn cmd_run(
     Ok(())
 }
 
+/// MCP commands
+pub(crate) async fn cmd_mcp(config: &crate::config::Config, operation: McpCommands) -> Result<()> {
+    use crate::brain::tools::PermissionRules;
+    use crate::brain::tools::sandbox::Sandbox;
+    use crate::db::Database;
+    use crate::mcp::{McpServer, server};
+
+    match operation {
+        McpCommands::Serve {
+            http,
+            bind,
+            port,
+            token,
+        } => {
+            let db = Database::connect(&config.database.path).await?;
+            db.run_migrations().await?;
+
+            // No [[mcp.servers]] here — re-exporting remote tools could loop back to us
+            let registry = Arc::new(build_tool_registry(config, &db));
+            let mcp_server = Arc::new(
+                McpServer::new(
+                    registry,
+                    PermissionRules::from_config(&config.permissions),
+                    BrainLoader::resolve_path(),
+                    std::env::current_dir().context("Failed to read working directory")?,
+                )
+                .with_sandbox(Sandbox::new(config.sandbox.clone())),
+            );
+
+            if let (true, Some(token)) = (http, token) {
+                server::serve_http(mcp_server, &bind, port, token).await
+            } else {
+                // stdout carries the protocol; logs go to stderr / log files
+                tracing::info!("MCP server running on stdio");
+                server::serve_stdio(mcp_server).await
+            }
+        }
+    }
+}
+
 /// Log management commands
 pub(crate) async fn cmd_logs(operation: LogCommands) -> Result<()> {
     use crate::logging;
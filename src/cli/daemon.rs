@@ -0,0 +1,307 @@
+//! Headless daemon — runs the messaging channels, the A2A and HTTP API gateways
+//! and scheduled prompts without a TUI.
+//!
+//! Writes `~/.opencrabs/daemon.pid` and a `daemon.health.json` heartbeat while
+//! running, and shuts down cleanly on SIGTERM or Ctrl+C.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::brain::prompt_builder::RuntimeInfo;
+use crate::brain::BrainLoader;
+
+/// How often the health file is refreshed
+const HEALTH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// PID and health files for a running daemon.
+pub(crate) struct DaemonFiles {
+    pid_path: PathBuf,
+    health_path: PathBuf,
+}
+
+impl DaemonFiles {
+    pub(crate) fn new(dir: &Path) -> Self {
+        Self {
+            pid_path: dir.join("daemon.pid"),
+            health_path: dir.join("daemon.health.json"),
+        }
+    }
+
+    /// Write our PID, refusing to start if another live daemon owns the file.
+    pub(crate) fn acquire(&self) -> Result<()> {
+        if let Ok(existing) = std::fs::read_to_string(&self.pid_path)
+            && let Ok(pid) = existing.trim().parse::<u32>()
+            && pid != std::process::id()
+            && process_alive(pid)
+        {
+            anyhow::bail!(
+                "OpenCrabs daemon already running (pid {}, {})",
+                pid,
+                self.pid_path.display()
+            );
+        }
+        std::fs::write(&self.pid_path, format!("{}\n", std::process::id()))
+            .with_context(|| format!("Failed to write {}", self.pid_path.display()))
+    }
+
+    /// Record that the daemon is alive and which services it runs.
+    pub(crate) fn write_health(
+        &self,
+        status: &str,
+        started_at: chrono::DateTime<chrono::Utc>,
+        services: &[&str],
+    ) -> Result<()> {
+        let health = serde_json::json!({
+            "status": status,
+            "pid": std::process::id(),
+            "version": crate::VERSION,
+            "started_at": started_at.to_rfc3339(),
+            "updated_at": chrono::Utc::now().to_rfc3339(),
+            "services": services,
+        });
+        // Write then rename so readers never see a half-written file
+        let tmp = self.health_path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(&health)?)?;
+        std::fs::rename(&tmp, &self.health_path)?;
+        Ok(())
+    }
+
+    /// Remove both files on shutdown.
+    pub(crate) fn release(&self) {
+        let _ = std::fs::remove_file(&self.pid_path);
+        let _ = std::fs::remove_file(&self.health_path);
+    }
+}
+
+/// Whether a process with this PID exists. Only checked on Linux; elsewhere a
+/// leftover PID file is treated as stale.
+fn process_alive(pid: u32) -> bool {
+    if cfg!(target_os = "linux") {
+        Path::new("/proc").join(pid.to_string()).exists()
+    } else {
+        false
+    }
+}
+
+/// Wait for SIGTERM (systemd/launchd stop) or Ctrl+C.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                tokio::select! {
+                    _ = sigterm.recv() => tracing::info!("Received SIGTERM"),
+                    _ = tokio::signal::ctrl_c() => tracing::info!("Received Ctrl+C"),
+                }
+            }
+            Err(e) => {
+                tracing::warn!("Failed to install SIGTERM handler: {}", e);
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+        tracing::info!("Received Ctrl+C");
+    }
+}
+
+/// Run channels and the A2A gateway headless until stopped
+pub(crate) async fn cmd_daemon(config: &crate::config::Config) -> Result<()> {
+    let files = DaemonFiles::new(&crate::config::opencrabs_home());
+    files.acquire()?;
+    let started_at = chrono::Utc::now();
+    tracing::info!("OpenCrabs daemon starting (pid {})", std::process::id());
+
+    let result = run_services(config, &files, started_at).await;
+    if let Err(ref e) = result {
+        tracing::error!("OpenCrabs daemon failed: {:#}", e);
+    }
+    files.release();
+    tracing::info!("OpenCrabs daemon stopped");
+    result
+}
+
+/// Boot provider, tools, channels and A2A, then block until a shutdown signal
+async fn run_services(
+    config: &crate::config::Config,
+    files: &DaemonFiles,
+    started_at: chrono::DateTime<chrono::Utc>,
+) -> Result<()> {
+    use crate::{db::Database, services::ServiceContext};
+
+    // Initialize database
+    let db = Database::connect(&config.database.path)
+        .await
+        .context("Failed to connect to database")?;
+    db.run_migrations()
+        .await
+        .context("Failed to run database migrations")?;
+
+    let provider = crate::brain::provider::create_provider(config)?;
+    tracing::info!("Using provider: {}", provider.name());
+
+    super::runtime::spawn_memory_warmup();
+
+    let service_context = ServiceContext::new(db.pool().clone());
+    let working_directory = std::env::current_dir().unwrap_or_default();
+
+    // Build dynamic system brain from workspace files
+    let brain_path = BrainLoader::resolve_path();
+    let brain_loader = BrainLoader::new(brain_path.clone());
+    let runtime_info = RuntimeInfo {
+        model: Some(provider.default_model().to_string()),
+        provider: Some(provider.name().to_string()),
+        working_directory: Some(working_directory.to_string_lossy().to_string()),
+    };
+    let system_brain = brain_loader.build_system_brain(Some(&runtime_info), None);
+
+    // Channels share one "current session" the way they do under the TUI
+    let shared_session_id = Arc::new(tokio::sync::Mutex::new(None));
+    let openai_tts_key = config.providers.tts.as_ref()
+        .and_then(|t| t.openai.as_ref())
+        .and_then(|p| p.api_key.clone());
+    let channel_factory = Arc::new(crate::channels::ChannelFactory::new(
+        provider.clone(),
+        service_context.clone(),
+        system_brain,
+        working_directory,
+        brain_path.clone(),
+        shared_session_id.clone(),
+        config.voice.clone(),
+        openai_tts_key,
+    ));
+
+    let mut tool_registry = super::commands::build_tool_registry(config, &db);
+    tool_registry.register(Arc::new(crate::brain::tools::debate::DebateTool::new(
+        config,
+        provider,
+        db.pool().clone(),
+    )));
+    crate::mcp::register_servers(&config.mcp, &mut tool_registry).await;
+    let channel_states = super::runtime::ChannelStates::new();
+    channel_states.register_tools(&mut tool_registry, &channel_factory, None);
+    channel_factory.set_tool_registry(Arc::new(tool_registry));
+
+    let a2a_handle = super::runtime::spawn_a2a(config, &channel_factory, &service_context);
+    let mut handles = channel_states.spawn_agents(
+        config,
+        &channel_factory,
+        &service_context,
+        shared_session_id,
+    );
+    if let Some(handle) = a2a_handle {
+        handles.push(("a2a", handle));
+    }
+    if let Some(handle) =
+        super::runtime::spawn_gateway(config, &channel_factory, &service_context)
+    {
+        handles.push(("gateway", handle));
+    }
+    if let Some(handle) =
+        super::runtime::spawn_heartbeat(config, &channel_factory, &brain_path, None)
+    {
+        handles.push(("heartbeat", handle));
+    }
+    handles.push((
+        "schedules",
+        super::runtime::spawn_schedules(&channel_factory, None),
+    ));
+
+    let services: Vec<&str> = handles.iter().map(|(name, _)| *name).collect();
+    if services == ["schedules"] {
+        tracing::warn!(
+            "No channels, gateways or heartbeat enabled in config.toml — the daemon only runs scheduled prompts"
+        );
+    } else {
+        tracing::info!("Daemon running: {}", services.join(", "));
+    }
+
+    files.write_health("running", started_at, &services)?;
+    let heartbeat = async {
+        let mut interval = tokio::time::interval(HEALTH_INTERVAL);
+        loop {
+            interval.tick().await;
+            let running: Vec<&str> = handles
+                .iter()
+                .filter(|(_, handle)| !handle.is_finished())
+                .map(|(name, _)| *name)
+                .collect();
+            if let Err(e) = files.write_health("running", started_at, &running) {
+                tracing::warn!("Failed to update daemon health file: {}", e);
+            }
+        }
+    };
+
+    tokio::select! {
+        _ = shutdown_signal() => {}
+        _ = heartbeat => {}
+    }
+
+    tracing::info!("Shutting down {} service(s)", handles.len());
+    for (name, handle) in handles {
+        handle.abort();
+        tracing::debug!("Stopped {}", name);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_pid_file_lifecycle() {
+        let dir = TempDir::new().unwrap();
+        let files = DaemonFiles::new(dir.path());
+
+        files.acquire().unwrap();
+        let pid = std::fs::read_to_string(dir.path().join("daemon.pid")).unwrap();
+        assert_eq!(pid.trim(), std::process::id().to_string());
+
+        // Our own PID in the file is not "another daemon"
+        files.acquire().unwrap();
+
+        files.write_health("running", chrono::Utc::now(), &["telegram", "a2a"]).unwrap();
+        let health: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(dir.path().join("daemon.health.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(health["status"], "running");
+        assert_eq!(health["services"], serde_json::json!(["telegram", "a2a"]));
+
+        files.release();
+        assert!(!dir.path().join("daemon.pid").exists());
+        assert!(!dir.path().join("daemon.health.json").exists());
+    }
+
+    #[test]
+    fn test_stale_pid_file_is_replaced() {
+        let dir = TempDir::new().unwrap();
+        // PIDs are capped well below u32::MAX, so this process cannot exist
+        std::fs::write(dir.path().join("daemon.pid"), format!("{}\n", u32::MAX)).unwrap();
+
+        let files = DaemonFiles::new(dir.path());
+        files.acquire().unwrap();
+        let pid = std::fs::read_to_string(dir.path().join("daemon.pid")).unwrap();
+        assert_eq!(pid.trim(), std::process::id().to_string());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_live_pid_blocks_second_daemon() {
+        let dir = TempDir::new().unwrap();
+        // PID 1 always exists on Linux
+        std::fs::write(dir.path().join("daemon.pid"), "1\n").unwrap();
+
+        let files = DaemonFiles::new(dir.path());
+        let err = files.acquire().unwrap_err();
+        assert!(err.to_string().contains("already running"));
+    }
+}
@@ -3,6 +3,8 @@
 //! Command-line interface for OpenCrabs using Clap v4.
 
 mod commands;
+mod daemon;
+mod runtime;
 mod ui;
 
 use anyhow::Result;
@@ -42,6 +44,9 @@ pub enum Commands {
     /// Run the onboarding setup wizard
     Onboard,
 
+    /// Run channels, gateways and schedules headless, without the TUI (for systemd/launchd)
+    Daemon,
+
     /// Run a single command non-interactively
     Run {
         /// The prompt to execute
@@ -82,6 +87,12 @@ pub enum Commands {
         operation: LogCommands,
     },
 
+    /// Model Context Protocol operations
+    Mcp {
+        #[command(subcommand)]
+        operation: McpCommands,
+    },
+
 }
 
 #[derive(Subcommand, Debug)]
@@ -104,6 +115,28 @@ pub enum LogCommands {
     Open,
 }
 
+#[derive(Subcommand, Debug)]
+pub enum McpCommands {
+    /// Expose OpenCrabs tools and brain files as an MCP server (stdio by default)
+    Serve {
+        /// Serve streamable HTTP instead of stdio (requires --token)
+        #[arg(long, requires = "token")]
+        http: bool,
+
+        /// HTTP bind address
+        #[arg(long, default_value = "127.0.0.1")]
+        bind: String,
+
+        /// HTTP port
+        #[arg(short, long, default_value = "18791")]
+        port: u16,
+
+        /// Bearer token HTTP clients must send as `Authorization: Bearer <token>`
+        #[arg(long, env = "OPENCRABS_MCP_TOKEN")]
+        token: Option<String>,
+    },
+}
+
 #[derive(Subcommand, Debug)]
 pub enum DbCommands {
     /// Initialize database
@@ -167,10 +200,12 @@ pub async fn run() -> Result<()> {
             // Launch TUI with onboarding wizard (skip splash)
             ui::cmd_chat(&config, None, true).await
         }
+        Some(Commands::Daemon) => daemon::cmd_daemon(&config).await,
         Some(Commands::Init { force }) => commands::cmd_init(&config, force).await,
         Some(Commands::Config { show_secrets }) => commands::cmd_config(&config, show_secrets).await,
         Some(Commands::Db { operation }) => commands::cmd_db(&config, operation).await,
         Some(Commands::Logs { operation }) => commands::cmd_logs(operation).await,
+        Some(Commands::Mcp { operation }) => commands::cmd_mcp(&config, operation).await,
         Some(Commands::Run {
             prompt,
             auto_approve,
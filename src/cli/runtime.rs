@@ -0,0 +1,433 @@
+//! Background services shared by the TUI and the headless daemon — memory
+//! warmup, channel connect/send tools, channel agents, the A2A and HTTP API
+//! gateways, the HEARTBEAT.md scheduler and scheduled prompts.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::brain::agent::ProgressCallback;
+use crate::brain::tools::ToolRegistry;
+use crate::channels::ChannelFactory;
+use crate::config::Config;
+use crate::services::ServiceContext;
+use crate::tui::events::TuiEvent;
+use tokio::sync::Mutex;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+/// Index existing memory files and warm up the embedding engine in the background
+pub(crate) fn spawn_memory_warmup() {
+    tokio::spawn(async {
+        match crate::memory::get_store() {
+            Ok(store) => {
+                match crate::memory::reindex(store).await {
+                    Ok(n) => tracing::info!("Startup memory reindex: {n} files"),
+                    Err(e) => tracing::warn!("Startup memory reindex failed: {e}"),
+                }
+            }
+            Err(e) => tracing::warn!("Memory store init failed at startup: {e}"),
+        }
+        // Warm up embedding engine so first search doesn't pay model download cost.
+        // reindex() already calls get_engine() during backfill, but if all docs were
+        // already embedded, this ensures the engine is ready for search.
+        match tokio::task::spawn_blocking(crate::memory::get_engine).await {
+            Ok(Ok(_)) => tracing::info!("Embedding engine warmed up"),
+            Ok(Err(e)) => tracing::warn!("Embedding engine init skipped: {e}"),
+            Err(e) => tracing::warn!("Embedding engine warmup failed: {e}"),
+        }
+    });
+}
+
+/// Spawn the A2A gateway if configured
+pub(crate) fn spawn_a2a(
+    config: &Config,
+    channel_factory: &ChannelFactory,
+    service_context: &ServiceContext,
+) -> Option<JoinHandle<()>> {
+    if !config.a2a.enabled {
+        return None;
+    }
+    let a2a_agent = channel_factory.create_unattended_agent_service();
+    let a2a_ctx = service_context.clone();
+    let a2a_config = config.a2a.clone();
+    Some(tokio::spawn(async move {
+        if let Err(e) = crate::a2a::server::start_server(&a2a_config, a2a_agent, a2a_ctx).await {
+            tracing::error!("A2A gateway error: {}", e);
+        }
+    }))
+}
+
+/// Spawn the HTTP API gateway (OpenAI-compatible chat + REST) if enabled.
+pub(crate) fn spawn_gateway(
+    config: &Config,
+    channel_factory: &Arc<ChannelFactory>,
+    service_context: &ServiceContext,
+) -> Option<JoinHandle<()>> {
+    if !config.gateway.enabled {
+        return None;
+    }
+    let factory = channel_factory.clone();
+    let ctx = service_context.clone();
+    let gateway_config = config.gateway.clone();
+    Some(tokio::spawn(async move {
+        if let Err(e) = crate::gateway::start_server(&gateway_config, factory, ctx).await {
+            tracing::error!("HTTP API gateway error: {}", e);
+        }
+    }))
+}
+
+/// Spawn the HEARTBEAT.md scheduler if enabled. Reports go to the configured
+/// channel, or to `tui` when it is given and no channel is configured.
+pub(crate) fn spawn_heartbeat(
+    config: &Config,
+    channel_factory: &ChannelFactory,
+    brain_path: &Path,
+    tui: Option<UnboundedSender<TuiEvent>>,
+) -> Option<JoinHandle<()>> {
+    use crate::scheduler::{Delivery, DeliveryChannel, DeliveryTarget, HeartbeatScheduler};
+
+    let heartbeat = &config.heartbeat;
+    if !heartbeat.enabled {
+        return None;
+    }
+    let target = match heartbeat.deliver_to.as_deref().map(str::parse::<DeliveryChannel>) {
+        Some(Ok(channel)) => Some(DeliveryTarget::new(channel, heartbeat.target.clone())),
+        Some(Err(e)) => {
+            tracing::warn!("[heartbeat] deliver_to ignored: {}", e);
+            None
+        }
+        None => None,
+    };
+
+    // Nobody is there to approve tool calls for a scheduled turn
+    let agent = channel_factory.create_unattended_agent_service();
+    let mut delivery = Delivery::new(agent.tool_registry().clone(), agent.working_directory());
+    if let Some(sender) = tui {
+        delivery = delivery.with_tui(sender);
+    }
+    let pool = channel_factory.service_context().pool();
+    let scheduler = HeartbeatScheduler::new(agent, pool, brain_path, delivery)
+        .with_target(target)
+        .with_check_interval(std::time::Duration::from_secs(
+            heartbeat.check_interval_secs.max(10),
+        ));
+    Some(scheduler.spawn())
+}
+
+/// Spawn the runner for scheduled prompts (the `schedules` table). Runs
+/// without a channel go to `tui`, when it is given.
+pub(crate) fn spawn_schedules(
+    channel_factory: &ChannelFactory,
+    tui: Option<UnboundedSender<TuiEvent>>,
+) -> JoinHandle<()> {
+    use crate::scheduler::{Delivery, ScheduleRunner, ScheduleStore};
+
+    let agent = channel_factory.create_unattended_agent_service();
+    let mut delivery = Delivery::new(agent.tool_registry().clone(), agent.working_directory());
+    if let Some(sender) = tui {
+        delivery = delivery.with_tui(sender);
+    }
+    let store = ScheduleStore::new(channel_factory.service_context().pool());
+    ScheduleRunner::new(agent, store, delivery).spawn()
+}
+
+/// Shared per-channel state for proactive messaging, used by the connect/send
+/// tools and by the statically configured channel agents.
+pub(crate) struct ChannelStates {
+    #[cfg(feature = "telegram")]
+    telegram: Arc<crate::channels::telegram::TelegramState>,
+    #[cfg(feature = "whatsapp")]
+    whatsapp: Arc<crate::channels::whatsapp::WhatsAppState>,
+    #[cfg(feature = "discord")]
+    discord: Arc<crate::channels::discord::DiscordState>,
+    #[cfg(feature = "slack")]
+    slack: Arc<crate::channels::slack::SlackState>,
+    #[cfg(feature = "signal")]
+    signal: Arc<crate::channels::signal::SignalState>,
+}
+
+impl ChannelStates {
+    pub(crate) fn new() -> Self {
+        Self {
+            #[cfg(feature = "telegram")]
+            telegram: Arc::new(crate::channels::telegram::TelegramState::new()),
+            #[cfg(feature = "whatsapp")]
+            whatsapp: Arc::new(crate::channels::whatsapp::WhatsAppState::new()),
+            #[cfg(feature = "discord")]
+            discord: Arc::new(crate::channels::discord::DiscordState::new()),
+            #[cfg(feature = "slack")]
+            slack: Arc::new(crate::channels::slack::SlackState::new()),
+            #[cfg(feature = "signal")]
+            signal: Arc::new(crate::channels::signal::SignalState::new()),
+        }
+    }
+
+    /// Register the agent-callable connect and send tools for every compiled-in channel.
+    #[allow(unused_variables)]
+    pub(crate) fn register_tools(
+        &self,
+        tool_registry: &mut ToolRegistry,
+        channel_factory: &Arc<ChannelFactory>,
+        progress_callback: Option<ProgressCallback>,
+    ) {
+        // Register Telegram connect tool (agent-callable bot setup)
+        #[cfg(feature = "telegram")]
+        tool_registry.register(Arc::new(
+            crate::brain::tools::telegram_connect::TelegramConnectTool::new(
+                channel_factory.clone(),
+                self.telegram.clone(),
+            ),
+        ));
+
+        // Register Telegram send tool (proactive messaging)
+        #[cfg(feature = "telegram")]
+        tool_registry.register(Arc::new(
+            crate::brain::tools::telegram_send::TelegramSendTool::new(self.telegram.clone()),
+        ));
+
+        // Register WhatsApp connect tool (agent-callable QR pairing)
+        #[cfg(feature = "whatsapp")]
+        tool_registry.register(Arc::new(
+            crate::brain::tools::whatsapp_connect::WhatsAppConnectTool::new(
+                progress_callback,
+                channel_factory.clone(),
+                self.whatsapp.clone(),
+            ),
+        ));
+
+        // Register WhatsApp send tool (proactive messaging)
+        #[cfg(feature = "whatsapp")]
+        tool_registry.register(Arc::new(
+            crate::brain::tools::whatsapp_send::WhatsAppSendTool::new(self.whatsapp.clone()),
+        ));
+
+        // Register Discord connect tool (agent-callable bot setup)
+        #[cfg(feature = "discord")]
+        tool_registry.register(Arc::new(
+            crate::brain::tools::discord_connect::DiscordConnectTool::new(
+                channel_factory.clone(),
+                self.discord.clone(),
+            ),
+        ));
+
+        // Register Discord send tool (proactive messaging)
+        #[cfg(feature = "discord")]
+        tool_registry.register(Arc::new(
+            crate::brain::tools::discord_send::DiscordSendTool::new(self.discord.clone()),
+        ));
+
+        // Register Slack connect tool (agent-callable bot setup)
+        #[cfg(feature = "slack")]
+        tool_registry.register(Arc::new(
+            crate::brain::tools::slack_connect::SlackConnectTool::new(
+                channel_factory.clone(),
+                self.slack.clone(),
+            ),
+        ));
+
+        // Register Slack send tool (proactive messaging)
+        #[cfg(feature = "slack")]
+        tool_registry.register(Arc::new(
+            crate::brain::tools::slack_send::SlackSendTool::new(self.slack.clone()),
+        ));
+
+        // Register Signal send tool (proactive messaging)
+        #[cfg(feature = "signal")]
+        tool_registry.register(Arc::new(
+            crate::brain::tools::signal_send::SignalSendTool::new(self.signal.clone()),
+        ));
+    }
+
+    /// Spawn every channel agent enabled in config. Returns the running agents by name.
+    #[allow(unused_variables, unused_mut)]
+    pub(crate) fn spawn_agents(
+        &self,
+        config: &Config,
+        channel_factory: &ChannelFactory,
+        service_context: &ServiceContext,
+        shared_session_id: Arc<Mutex<Option<Uuid>>>,
+    ) -> Vec<(&'static str, JoinHandle<()>)> {
+        let mut handles = Vec::new();
+
+        // Spawn Telegram bot if configured
+        #[cfg(feature = "telegram")]
+        {
+            let tg = &config.channels.telegram;
+            let tg_token = tg.token.clone();
+            let has_valid_token = tg_token.as_ref().map(|t| {
+                if t.is_empty() || !t.contains(':') {
+                    return false;
+                }
+                let parts: Vec<&str> = t.splitn(2, ':').collect();
+                parts.len() == 2 && parts[0].parse::<u64>().is_ok() && parts[1].len() >= 30
+            }).unwrap_or(false);
+
+            tracing::debug!("[Telegram] enabled={}, has_token={}, has_valid_token={}",
+                tg.enabled, tg_token.is_some(), has_valid_token);
+
+            if tg.enabled && has_valid_token {
+                if let Some(ref token) = tg_token {
+                    let tg_agent = channel_factory.create_agent_service();
+                    // Extract OpenAI API key for TTS (from providers.tts.openai)
+                    let openai_key = config.providers.tts.as_ref()
+                        .and_then(|t| t.openai.as_ref())
+                        .and_then(|p| p.api_key.clone());
+                    // Extract STT provider config from providers.stt.*
+                    let mut voice_cfg = config.voice.clone();
+                    voice_cfg.stt_provider = config.providers.stt.as_ref()
+                        .and_then(|s| s.groq.clone());
+                    voice_cfg.tts_provider = config.providers.tts.as_ref()
+                        .and_then(|t| t.openai.clone());
+                    let bot = crate::channels::telegram::TelegramAgent::new(
+                        tg_agent,
+                        channel_factory.approvals(),
+                        service_context.clone(),
+                        tg.allowed_users.clone(),
+                        voice_cfg,
+                        openai_key,
+                        shared_session_id.clone(),
+                        self.telegram.clone(),
+                        tg.respond_to.clone(),
+                        tg.allowed_channels.clone(),
+                    );
+                    tracing::info!("Spawning Telegram bot ({} allowed users)", tg.allowed_users.len());
+                    handles.push(("telegram", bot.start(token.clone())));
+                } else {
+                    tracing::debug!("Telegram enabled but no valid token configured");
+                }
+            }
+        }
+
+        // Spawn WhatsApp agent if configured (already paired via session.db)
+        #[cfg(feature = "whatsapp")]
+        {
+            let wa = &config.channels.whatsapp;
+            if wa.enabled {
+                let wa_agent = crate::channels::whatsapp::WhatsAppAgent::new(
+                    channel_factory.create_agent_service(),
+                    channel_factory.approvals(),
+                    service_context.clone(),
+                    wa.allowed_phones.clone(),
+                    config.voice.clone(),
+                    shared_session_id.clone(),
+                    self.whatsapp.clone(),
+                );
+                tracing::info!(
+                    "Spawning WhatsApp agent ({} allowed phones)",
+                    wa.allowed_phones.len()
+                );
+                handles.push(("whatsapp", wa_agent.start()));
+            }
+        }
+
+        // Spawn Discord bot if configured (token-based, like Telegram)
+        #[cfg(feature = "discord")]
+        {
+            let dc = &config.channels.discord;
+            let dc_token = dc.token.clone();
+            // Discord tokens are typically ~70 chars, base64-like
+            let has_valid_token = dc_token.as_ref().map(|t| !t.is_empty() && t.len() > 50).unwrap_or(false);
+            if dc.enabled && has_valid_token {
+                if let Some(ref token) = dc_token {
+                    // Extract OpenAI API key for TTS (from providers.tts.openai in keys.toml)
+                    let openai_key = config.providers.tts.as_ref()
+                        .and_then(|t| t.openai.as_ref())
+                        .and_then(|p| p.api_key.clone());
+                    let dc_agent = crate::channels::discord::DiscordAgent::new(
+                        channel_factory.create_agent_service(),
+                        channel_factory.approvals(),
+                        service_context.clone(),
+                        dc.allowed_users.clone(),
+                        config.voice.clone(),
+                        openai_key,
+                        shared_session_id.clone(),
+                        self.discord.clone(),
+                        dc.respond_to.clone(),
+                        dc.allowed_channels.clone(),
+                    );
+                    tracing::info!(
+                        "Spawning Discord bot ({} allowed users)",
+                        dc.allowed_users.len()
+                    );
+                    handles.push(("discord", dc_agent.start(token.clone())));
+                } else {
+                    tracing::debug!("Discord enabled but no valid token configured");
+                }
+            }
+        }
+
+        // Spawn Slack bot if configured (needs both bot token + app token for Socket Mode)
+        #[cfg(feature = "slack")]
+        {
+            let sl = &config.channels.slack;
+            let sl_token = sl.token.clone();
+            let sl_app_token = sl.app_token.clone();
+            let has_valid_tokens = sl_token.as_ref().map(|t| !t.is_empty() && t.starts_with("xoxb-")).unwrap_or(false)
+                && sl_app_token.as_ref().map(|t| !t.is_empty() && t.starts_with("xapp-")).unwrap_or(false);
+            if sl.enabled && has_valid_tokens {
+                if let (Some(bot_tok), Some(app_tok)) = (sl_token, sl_app_token) {
+                    let sl_agent = crate::channels::slack::SlackAgent::new(
+                        channel_factory.create_agent_service(),
+                        channel_factory.approvals(),
+                        service_context.clone(),
+                        sl.allowed_ids.clone(),
+                        shared_session_id.clone(),
+                        self.slack.clone(),
+                        sl.respond_to.clone(),
+                        sl.allowed_channels.clone(),
+                    );
+                    tracing::info!(
+                        "Spawning Slack bot ({} allowed IDs)",
+                        sl.allowed_ids.len()
+                    );
+                    handles.push(("slack", sl_agent.start(bot_tok, app_tok)));
+                } else {
+                    tracing::debug!("Slack enabled but missing valid tokens");
+                }
+            }
+        }
+
+        // Spawn Signal agent if configured (talks to a local signal-cli daemon)
+        #[cfg(feature = "signal")]
+        {
+            let sg = &config.channels.signal;
+            let account = sg.account.clone().filter(|a| !a.trim().is_empty());
+            if sg.enabled {
+                if let Some(account) = account {
+                    // Extract OpenAI API key for TTS (from providers.tts.openai)
+                    let openai_key = config.providers.tts.as_ref()
+                        .and_then(|t| t.openai.as_ref())
+                        .and_then(|p| p.api_key.clone());
+                    // Extract STT provider config from providers.stt.*
+                    let mut voice_cfg = config.voice.clone();
+                    voice_cfg.stt_provider = config.providers.stt.as_ref()
+                        .and_then(|s| s.groq.clone());
+                    voice_cfg.tts_provider = config.providers.tts.as_ref()
+                        .and_then(|t| t.openai.clone());
+                    let sg_agent = crate::channels::signal::SignalAgent::new(
+                        channel_factory.create_agent_service(),
+                        channel_factory.approvals(),
+                        service_context.clone(),
+                        sg.allowed_phones.clone(),
+                        voice_cfg,
+                        openai_key,
+                        shared_session_id.clone(),
+                        self.signal.clone(),
+                        sg.respond_to.clone(),
+                        sg.allowed_channels.clone(),
+                    );
+                    tracing::info!(
+                        "Spawning Signal agent ({} allowed phones)",
+                        sg.allowed_phones.len()
+                    );
+                    handles.push(("signal", sg_agent.start(sg.url.clone(), account)));
+                } else {
+                    tracing::debug!("Signal enabled but no account configured");
+                }
+            }
+        }
+
+        handles
+    }
+}
@@ -1,4 +1,4 @@
-//! TUI chat startup — provider init, tool registry, approval callbacks, Telegram spawn.
+//! TUI chat startup — provider init, tool registry, approval callbacks, channel spawn.
 
 use anyhow::{Context, Result};
 use std::sync::Arc;
@@ -17,12 +17,14 @@ pub(crate) async fn cmd_chat(
         brain::{
             agent::AgentService,
             tools::{
-                bash::BashTool, brave_search::BraveSearchTool, code_exec::CodeExecTool,
+                a2a_delegate::A2aDelegateTool, bash::BashTool, brave_search::BraveSearchTool, code_exec::CodeExecTool,
                 config_tool::ConfigTool, context::ContextTool, doc_parser::DocParserTool,
                 edit::EditTool, exa_search::ExaSearchTool, glob::GlobTool, grep::GrepTool,
                 http::HttpClientTool, ls::LsTool, memory_search::MemorySearchTool,
                 notebook::NotebookEditTool, plan_tool::PlanTool,
-                read::ReadTool, registry::ToolRegistry, session_search::SessionSearchTool,
+                process::{ProcessManager, ProcessTool},
+                read::ReadTool, registry::ToolRegistry, schedule::ScheduleTool,
+                session_search::SessionSearchTool,
                 slash_command::SlashCommandTool,
                 task::TaskTool, web_search::WebSearchTool, write::WriteTool,
             },
@@ -75,7 +77,9 @@ pub(crate) async fn cmd_chat(
     tool_registry.register(Arc::new(ReadTool));
     tool_registry.register(Arc::new(WriteTool));
     tool_registry.register(Arc::new(EditTool));
-    tool_registry.register(Arc::new(BashTool));
+    tool_registry.register(Arc::new(
+        BashTool::new().with_persistent_shells(config.agent.persistent_shell),
+    ));
     tool_registry.register(Arc::new(LsTool));
     tool_registry.register(Arc::new(GlobTool));
     tool_registry.register(Arc::new(GrepTool));
@@ -89,10 +93,17 @@ pub(crate) async fn cmd_chat(
     tool_registry.register(Arc::new(ContextTool));
     tool_registry.register(Arc::new(HttpClientTool));
     tool_registry.register(Arc::new(PlanTool));
+    // Background processes (dev servers, watchers) — listed in the /processes panel
+    let process_manager = Arc::new(ProcessManager::new());
+    tool_registry.register(Arc::new(ProcessTool::new(process_manager.clone())));
     // Memory search (built-in FTS5, always available)
     tool_registry.register(Arc::new(MemorySearchTool));
     // Session search — hybrid QMD search across all session message history
     tool_registry.register(Arc::new(SessionSearchTool::new(db.pool().clone())));
+    // Scheduled prompts (run by the TUI/daemon schedule runner)
+    tool_registry.register(Arc::new(ScheduleTool::new(db.pool().clone())));
+    // Delegation to remote A2A agents ([[a2a.peers]])
+    tool_registry.register(Arc::new(A2aDelegateTool::new(config.a2a.peers.clone())));
     // Config management (read/write config.toml, commands.toml)
     tool_registry.register(Arc::new(ConfigTool));
     // Slash command invocation (agent can call any slash command)
@@ -113,27 +124,14 @@ pub(crate) async fn cmd_chat(
         tool_registry.register(Arc::new(BraveSearchTool::new(brave_key)));
         tracing::info!("Registered Brave search tool");
     }
+    // MCP servers from [[mcp.servers]] — tools registered as mcp__<server>__<tool>
+    let mcp_clients = crate::mcp::register_servers(&config.mcp, &mut tool_registry).await;
+    if !mcp_clients.is_empty() {
+        tracing::info!("Connected to {} MCP server(s)", mcp_clients.len());
+    }
 
     // Index existing memory files and warm up embedding engine in the background
-    tokio::spawn(async {
-        match crate::memory::get_store() {
-            Ok(store) => {
-                match crate::memory::reindex(store).await {
-                    Ok(n) => tracing::info!("Startup memory reindex: {n} files"),
-                    Err(e) => tracing::warn!("Startup memory reindex failed: {e}"),
-                }
-            }
-            Err(e) => tracing::warn!("Memory store init failed at startup: {e}"),
-        }
-        // Warm up embedding engine so first search doesn't pay model download cost.
-        // reindex() already calls get_engine() during backfill, but if all docs were
-        // already embedded, this ensures the engine is ready for search.
-        match tokio::task::spawn_blocking(crate::memory::get_engine).await {
-            Ok(Ok(_)) => tracing::info!("Embedding engine warmed up"),
-            Ok(Err(e)) => tracing::warn!("Embedding engine init skipped: {e}"),
-            Err(e) => tracing::warn!("Embedding engine warmup failed: {e}"),
-        }
-    });
+    super::runtime::spawn_memory_warmup();
 
     // Create service context
     let service_context = ServiceContext::new(db.pool().clone());
@@ -253,6 +251,9 @@ pub(crate) async fn cmd_chat(
             ProgressEvent::StreamingChunk { text } => {
                 progress_sender.send(TuiEvent::ResponseChunk(text))
             }
+            ProgressEvent::ThinkingChunk { text } => {
+                progress_sender.send(TuiEvent::ThinkingChunk(text))
+            }
             ProgressEvent::Thinking => return, // spinner handles this already
             ProgressEvent::Compacting => {
                 progress_sender.send(TuiEvent::AgentProcessing)
@@ -266,6 +267,15 @@ pub(crate) async fn cmd_chat(
             ProgressEvent::TokenCount(count) => {
                 progress_sender.send(TuiEvent::TokenCountUpdated(count))
             }
+            ProgressEvent::ProviderSwitched { from, to, reason } => {
+                progress_sender.send(TuiEvent::SystemMessage(format!(
+                    "Provider {} failed ({}), switched to {}",
+                    from, reason, to
+                )))
+            }
+            ProgressEvent::DebateUpdated(session) => {
+                progress_sender.send(TuiEvent::DebateUpdated(session))
+            }
         };
         if let Err(e) = result {
             tracing::error!("Progress event channel closed: {}", e);
@@ -284,6 +294,12 @@ pub(crate) async fn cmd_chat(
         crate::brain::tools::rebuild::RebuildTool::new(Some(progress_callback.clone())),
     ));
 
+    // Bee Colony debates ([[a2a.bees]]) — rounds show live in the /debate panel
+    tool_registry.register(Arc::new(
+        crate::brain::tools::debate::DebateTool::new(config, provider.clone(), db.pool().clone())
+            .with_progress(progress_callback.clone()),
+    ));
+
     // Create ChannelFactory (shared by static channel spawn + WhatsApp connect tool).
     // Tool registry is set lazily after Arc wrapping to break circular dependency.
     let openai_tts_key = config.providers.tts.as_ref()
@@ -300,82 +316,13 @@ pub(crate) async fn cmd_chat(
         openai_tts_key,
     ));
 
-    // Shared Telegram state for proactive messaging
-    #[cfg(feature = "telegram")]
-    let telegram_state = Arc::new(crate::channels::telegram::TelegramState::new());
-
-    // Register Telegram connect tool (agent-callable bot setup)
-    #[cfg(feature = "telegram")]
-    tool_registry.register(Arc::new(
-        crate::brain::tools::telegram_connect::TelegramConnectTool::new(
-            channel_factory.clone(),
-            telegram_state.clone(),
-        ),
-    ));
-
-    // Register Telegram send tool (proactive messaging)
-    #[cfg(feature = "telegram")]
-    tool_registry.register(Arc::new(
-        crate::brain::tools::telegram_send::TelegramSendTool::new(telegram_state.clone()),
-    ));
-
-    // Shared WhatsApp state for proactive messaging (connect + send tools + static agent)
-    #[cfg(feature = "whatsapp")]
-    let whatsapp_state = Arc::new(crate::channels::whatsapp::WhatsAppState::new());
-
-    // Register WhatsApp connect tool (agent-callable QR pairing)
-    #[cfg(feature = "whatsapp")]
-    tool_registry.register(Arc::new(
-        crate::brain::tools::whatsapp_connect::WhatsAppConnectTool::new(
-            Some(progress_callback.clone()),
-            channel_factory.clone(),
-            whatsapp_state.clone(),
-        ),
-    ));
-
-    // Register WhatsApp send tool (proactive messaging)
-    #[cfg(feature = "whatsapp")]
-    tool_registry.register(Arc::new(
-        crate::brain::tools::whatsapp_send::WhatsAppSendTool::new(whatsapp_state.clone()),
-    ));
-
-    // Shared Discord state for proactive messaging
-    #[cfg(feature = "discord")]
-    let discord_state = Arc::new(crate::channels::discord::DiscordState::new());
-
-    // Register Discord connect tool (agent-callable bot setup)
-    #[cfg(feature = "discord")]
-    tool_registry.register(Arc::new(
-        crate::brain::tools::discord_connect::DiscordConnectTool::new(
-            channel_factory.clone(),
-            discord_state.clone(),
-        ),
-    ));
-
-    // Register Discord send tool (proactive messaging)
-    #[cfg(feature = "discord")]
-    tool_registry.register(Arc::new(
-        crate::brain::tools::discord_send::DiscordSendTool::new(discord_state.clone()),
-    ));
-
-    // Shared Slack state for proactive messaging
-    #[cfg(feature = "slack")]
-    let slack_state = Arc::new(crate::channels::slack::SlackState::new());
-
-    // Register Slack connect tool (agent-callable bot setup)
-    #[cfg(feature = "slack")]
-    tool_registry.register(Arc::new(
-        crate::brain::tools::slack_connect::SlackConnectTool::new(
-            channel_factory.clone(),
-            slack_state.clone(),
-        ),
-    ));
-
-    // Register Slack send tool (proactive messaging)
-    #[cfg(feature = "slack")]
-    tool_registry.register(Arc::new(
-        crate::brain::tools::slack_send::SlackSendTool::new(slack_state.clone()),
-    ));
+    // Channel connect/send tools (agent-callable bot setup + proactive messaging)
+    let channel_states = super::runtime::ChannelStates::new();
+    channel_states.register_tools(
+        &mut tool_registry,
+        &channel_factory,
+        Some(progress_callback.clone()),
+    );
 
     // Create sudo password callback that sends requests to TUI
     let sudo_sender = app.event_sender();
@@ -439,12 +386,15 @@ pub(crate) async fn cmd_chat(
             .with_message_queue_callback(Some(message_queue_callback))
             .with_sudo_callback(Some(sudo_callback))
             .with_working_directory(working_directory.clone())
-            .with_brain_path(brain_path),
+            .with_brain_path(brain_path.clone()),
     );
 
     // Update app with the configured agent service (preserve event channels!)
     app.set_agent_service(agent_service);
 
+    // The /processes panel lists what the process tool started
+    app.process_manager = Some(process_manager.clone());
+
     // Set force onboard flag if requested
     if force_onboard {
         app.force_onboard = true;
@@ -457,166 +407,31 @@ pub(crate) async fn cmd_chat(
         app.resume_session_id = Some(uuid);
     }
 
-    // Spawn A2A gateway if configured
-    if config.a2a.enabled {
-        let a2a_agent = channel_factory.create_agent_service();
-        let a2a_ctx = service_context.clone();
-        let a2a_config = config.a2a.clone();
-        tokio::spawn(async move {
-            if let Err(e) = crate::a2a::server::start_server(&a2a_config, a2a_agent, a2a_ctx).await {
-                tracing::error!("A2A gateway error: {}", e);
-            }
-        });
-    }
-
-    // Spawn Telegram bot if configured
-    #[cfg(feature = "telegram")]
-    let _telegram_handle = {
-        let tg = &config.channels.telegram;
-        let tg_token = tg.token.clone();
-        let has_valid_token = tg_token.as_ref().map(|t| {
-            if t.is_empty() || !t.contains(':') {
-                return false;
-            }
-            let parts: Vec<&str> = t.splitn(2, ':').collect();
-            parts.len() == 2 && parts[0].parse::<u64>().is_ok() && parts[1].len() >= 30
-        }).unwrap_or(false);
-        
-        tracing::debug!("[Telegram] enabled={}, has_token={}, has_valid_token={}", 
-            tg.enabled, tg_token.is_some(), has_valid_token);
-        
-        if tg.enabled && has_valid_token {
-            if let Some(ref token) = tg_token {
-                let tg_agent = channel_factory.create_agent_service();
-                // Extract OpenAI API key for TTS (from providers.tts.openai)
-                let openai_key = config.providers.tts.as_ref()
-                    .and_then(|t| t.openai.as_ref())
-                    .and_then(|p| p.api_key.clone());
-                // Extract STT provider config from providers.stt.*
-                let mut voice_cfg = config.voice.clone();
-                voice_cfg.stt_provider = config.providers.stt.as_ref()
-                    .and_then(|s| s.groq.clone());
-                voice_cfg.tts_provider = config.providers.tts.as_ref()
-                    .and_then(|t| t.openai.clone());
-                let bot = crate::channels::telegram::TelegramAgent::new(
-                    tg_agent,
-                    service_context.clone(),
-                    tg.allowed_users.clone(),
-                    voice_cfg,
-                    openai_key,
-                    app.shared_session_id(),
-                    telegram_state.clone(),
-                    tg.respond_to.clone(),
-                    tg.allowed_channels.clone(),
-                );
-                tracing::info!("Spawning Telegram bot ({} allowed users)", tg.allowed_users.len());
-                Some(bot.start(token.clone()))
-            } else {
-                tracing::debug!("Telegram enabled but no valid token configured");
-                None
-            }
-        } else {
-            None
-        }
-    };
-
-    // Spawn WhatsApp agent if configured (already paired via session.db)
-    #[cfg(feature = "whatsapp")]
-    let _whatsapp_handle = {
-        let wa = &config.channels.whatsapp;
-        if wa.enabled {
-            let wa_agent = crate::channels::whatsapp::WhatsAppAgent::new(
-                channel_factory.create_agent_service(),
-                service_context.clone(),
-                wa.allowed_phones.clone(),
-                config.voice.clone(),
-                app.shared_session_id(),
-                whatsapp_state.clone(),
-            );
-            tracing::info!(
-                "Spawning WhatsApp agent ({} allowed phones)",
-                wa.allowed_phones.len()
-            );
-            Some(wa_agent.start())
-        } else {
-            None
-        }
-    };
-
-    // Spawn Discord bot if configured (token-based, like Telegram)
-    #[cfg(feature = "discord")]
-    let _discord_handle = {
-        let dc = &config.channels.discord;
-        let dc_token = dc.token.clone();
-        // Discord tokens are typically ~70 chars, base64-like
-        let has_valid_token = dc_token.as_ref().map(|t| !t.is_empty() && t.len() > 50).unwrap_or(false);
-        if dc.enabled && has_valid_token {
-            if let Some(ref token) = dc_token {
-                // Extract OpenAI API key for TTS (from providers.tts.openai in keys.toml)
-                let openai_key = config.providers.tts.as_ref()
-                    .and_then(|t| t.openai.as_ref())
-                    .and_then(|p| p.api_key.clone());
-                let dc_agent = crate::channels::discord::DiscordAgent::new(
-                    channel_factory.create_agent_service(),
-                    service_context.clone(),
-                    dc.allowed_users.clone(),
-                    config.voice.clone(),
-                    openai_key,
-                    app.shared_session_id(),
-                    discord_state.clone(),
-                    dc.respond_to.clone(),
-                    dc.allowed_channels.clone(),
-                );
-                tracing::info!(
-                    "Spawning Discord bot ({} allowed users)",
-                    dc.allowed_users.len()
-                );
-                Some(dc_agent.start(token.clone()))
-            } else {
-                tracing::debug!("Discord enabled but no valid token configured");
-                None
-            }
-        } else {
-            None
-        }
-    };
-
-    // Spawn Slack bot if configured (needs both bot token + app token for Socket Mode)
-    #[cfg(feature = "slack")]
-    let _slack_handle = {
-        let sl = &config.channels.slack;
-        let sl_token = sl.token.clone();
-        let sl_app_token = sl.app_token.clone();
-        let has_valid_tokens = sl_token.as_ref().map(|t| !t.is_empty() && t.starts_with("xoxb-")).unwrap_or(false)
-            && sl_app_token.as_ref().map(|t| !t.is_empty() && t.starts_with("xapp-")).unwrap_or(false);
-        if sl.enabled && has_valid_tokens {
-            if let (Some(bot_tok), Some(app_tok)) = (sl_token, sl_app_token) {
-                let sl_agent = crate::channels::slack::SlackAgent::new(
-                    channel_factory.create_agent_service(),
-                    service_context.clone(),
-                    sl.allowed_ids.clone(),
-                    app.shared_session_id(),
-                    slack_state.clone(),
-                    sl.respond_to.clone(),
-                    sl.allowed_channels.clone(),
-                );
-                tracing::info!(
-                    "Spawning Slack bot ({} allowed IDs)",
-                    sl.allowed_ids.len()
-                );
-                Some(sl_agent.start(bot_tok, app_tok))
-            } else {
-                tracing::debug!("Slack enabled but missing valid tokens");
-                None
-            }
-        } else {
-            None
-        }
-    };
+    // Spawn the A2A and HTTP API gateways, messaging channel agents, the
+    // heartbeat scheduler and the scheduled prompt runner
+    let _a2a_handle = super::runtime::spawn_a2a(config, &channel_factory, &service_context);
+    let _gateway_handle =
+        super::runtime::spawn_gateway(config, &channel_factory, &service_context);
+    let _channel_handles = channel_states.spawn_agents(
+        config,
+        &channel_factory,
+        &service_context,
+        app.shared_session_id(),
+    );
+    let _heartbeat_handle = super::runtime::spawn_heartbeat(
+        config,
+        &channel_factory,
+        &brain_path,
+        Some(app.event_sender()),
+    );
+    let _schedule_handle =
+        super::runtime::spawn_schedules(&channel_factory, Some(app.event_sender()));
 
     // Run TUI
     tracing::debug!("Launching TUI");
-    tui::run(app).await.context("TUI error")?;
+    let tui_result = tui::run(app).await.context("TUI error");
+    process_manager.kill_all().await;
+    tui_result?;
 
     // Print shutdown logo and rolling message
     {
@@ -49,6 +49,22 @@ pub struct Config {
     /// A2A (Agent-to-Agent) protocol gateway configuration
     #[serde(default)]
     pub a2a: A2aConfig,
+
+    /// Tool permission rules
+    #[serde(default)]
+    pub permissions: PermissionsConfig,
+
+    /// Sandbox for calls matching `[permissions] sandbox` rules
+    #[serde(default)]
+    pub sandbox: SandboxConfig,
+
+    /// MCP (Model Context Protocol) server connections
+    #[serde(default)]
+    pub mcp: McpConfig,
+
+    /// HEARTBEAT.md scheduler
+    #[serde(default)]
+    pub heartbeat: HeartbeatConfig,
 }
 
 /// HTTP API gateway configuration
@@ -69,6 +85,15 @@ pub struct GatewayConfig {
     /// Whether the gateway is enabled
     #[serde(default)]
     pub enabled: bool,
+
+    /// Bearer token required when `auth_mode = "token"`. Best kept in keys.toml
+    /// under `[gateway] token = "..."`.
+    #[serde(default)]
+    pub token: Option<String>,
+
+    /// Allowed CORS origins — empty means no cross-origin requests
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
 }
 
 fn default_gateway_port() -> u16 {
@@ -107,6 +132,62 @@ pub struct A2aConfig {
     /// If unset, no authentication is required (suitable for loopback-only use).
     #[serde(default)]
     pub api_key: Option<String>,
+
+    /// Secret for signing push notification webhooks (HMAC-SHA256 of the body,
+    /// sent as `X-A2A-Signature: sha256=<hex>`). Unsigned when unset.
+    #[serde(default)]
+    pub push_secret: Option<String>,
+
+    /// Remote agents the `a2a_delegate` tool can delegate to (`[[a2a.peers]]`)
+    #[serde(default)]
+    pub peers: Vec<A2aPeerConfig>,
+
+    /// Bee Colony debate participants used by the `debate` tool (`[[a2a.bees]]`)
+    #[serde(default)]
+    pub bees: Vec<A2aBeeConfig>,
+}
+
+/// A remote A2A agent known by name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct A2aPeerConfig {
+    /// Name the agent refers to the peer by
+    pub name: String,
+
+    /// Base URL of the peer (its Agent Card is at `/.well-known/agent.json`)
+    pub url: String,
+
+    /// Optional Bearer token sent with every request to the peer
+    #[serde(default)]
+    pub token: Option<String>,
+
+    /// What the peer is good at, shown in the tool description
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// A Bee Colony debate participant: a remote A2A agent when `url` is set,
+/// otherwise a local bee running in-process on one of our providers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct A2aBeeConfig {
+    /// Name the bee is selected by
+    pub name: String,
+
+    /// JSON-RPC endpoint of a remote bee (e.g. `http://host:18790/a2a/v1`)
+    #[serde(default)]
+    pub url: Option<String>,
+
+    /// Provider of a local bee, as in `[providers.fallback] chain`
+    /// (e.g. "anthropic", "openrouter", "custom.ollama"). Defaults to the active provider.
+    #[serde(default)]
+    pub provider: Option<String>,
+
+    /// Model of a local bee. Defaults to the provider's default model.
+    #[serde(default)]
+    pub model: Option<String>,
+
+    /// Angle the bee argues from (e.g. "a security auditor")
+    #[serde(default)]
+    pub perspective: Option<String>,
 }
 
 fn default_a2a_bind() -> String {
@@ -124,6 +205,8 @@ impl Default for GatewayConfig {
             bind: default_gateway_bind(),
             auth_mode: default_gateway_auth(),
             enabled: false,
+            token: None,
+            allowed_origins: vec![],
         }
     }
 }
@@ -136,6 +219,9 @@ impl Default for A2aConfig {
             port: default_a2a_port(),
             allowed_origins: vec![],
             api_key: None,
+            push_secret: None,
+            peers: vec![],
+            bees: vec![],
         }
     }
 }
@@ -198,6 +284,12 @@ pub struct ChannelConfig {
     /// Restrict bot to specific channel IDs. Empty = all channels. DMs always pass.
     #[serde(default)]
     pub allowed_channels: Vec<String>,
+    /// Local daemon address (Signal: `signal-cli daemon --http`, default `http://127.0.0.1:8080`)
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Account the channel runs as (Signal: phone number registered with signal-cli)
+    #[serde(default)]
+    pub account: Option<String>,
 }
 
 /// Voice processing configuration (STT + TTS)
@@ -247,6 +339,184 @@ impl Default for VoiceConfig {
     }
 }
 
+/// Tool permission rules, checked before a tool's own approval requirement.
+///
+/// Rules look like `tool` or `tool(pattern)`, e.g. `bash(cargo test:*)` or
+/// `write_file(/etc/**)`. Deny wins over ask, and ask wins over allow.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PermissionsConfig {
+    /// Run without asking
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+
+    /// Never run; the model is told which rule blocked the call
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<String>,
+
+    /// Always ask, even in auto-approve mode
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ask: Vec<String>,
+
+    /// Run inside the `[sandbox]` (`bash` and `execute_code` only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sandbox: Vec<String>,
+}
+
+/// Linux sandbox for `bash` and `execute_code` calls that match a
+/// `[permissions] sandbox` rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SandboxConfig {
+    /// "auto" or "bubblewrap", or "unshare" to accept weaker filesystem isolation
+    #[serde(default = "default_sandbox_backend")]
+    pub backend: String,
+
+    /// Project directory mounted into the sandbox (default: the directory
+    /// OpenCrabs was started in). Sandboxed commands must run inside it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_dir: Option<String>,
+
+    /// Let sandboxed commands write to the project directory (default: read-only)
+    #[serde(default)]
+    pub project_writable: bool,
+
+    /// Extra paths mounted read-only, e.g. toolchains in `~/.cargo` (bubblewrap)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub read_only_paths: Vec<String>,
+
+    /// Extra paths mounted writable (bubblewrap)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub writable_paths: Vec<String>,
+
+    /// Keep network access for tools that declare it (`bash`); off by default
+    #[serde(default)]
+    pub network: bool,
+
+    /// Data segment limit per process in MB, 0 for none (default: 4096)
+    #[serde(default = "default_sandbox_memory_mb")]
+    pub memory_mb: u64,
+
+    /// CPU time limit per process in seconds, 0 for none (default: 600)
+    #[serde(default = "default_sandbox_cpu_secs")]
+    pub cpu_secs: u64,
+}
+
+fn default_sandbox_backend() -> String {
+    "auto".to_string()
+}
+
+fn default_sandbox_memory_mb() -> u64 {
+    4096
+}
+
+fn default_sandbox_cpu_secs() -> u64 {
+    600
+}
+
+impl Default for SandboxConfig {
+    fn default() -> Self {
+        Self {
+            backend: default_sandbox_backend(),
+            project_dir: None,
+            project_writable: false,
+            read_only_paths: Vec::new(),
+            writable_paths: Vec::new(),
+            network: false,
+            memory_mb: default_sandbox_memory_mb(),
+            cpu_secs: default_sandbox_cpu_secs(),
+        }
+    }
+}
+
+/// MCP (Model Context Protocol) client configuration.
+///
+/// Each `[[mcp.servers]]` entry either launches a stdio server (`command`)
+/// or connects to a streamable-HTTP server (`url`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct McpConfig {
+    /// Servers whose tools are registered as `mcp__<server>__<tool>`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub servers: Vec<McpServerConfig>,
+}
+
+/// A single MCP server connection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpServerConfig {
+    /// Server name, used as the tool namespace
+    pub name: String,
+
+    /// Whether to connect to this server (default: true)
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Executable for a stdio server (e.g. "npx")
+    #[serde(default)]
+    pub command: Option<String>,
+
+    /// Arguments passed to `command`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+
+    /// Extra environment variables for the stdio server process
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+
+    /// Endpoint of a streamable-HTTP server (e.g. "https://example.com/mcp")
+    #[serde(default)]
+    pub url: Option<String>,
+
+    /// Extra HTTP headers sent with every request (e.g. Authorization)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+
+    /// Per-request timeout in seconds (default: 60)
+    #[serde(default = "default_mcp_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_mcp_timeout_secs() -> u64 {
+    60
+}
+
+/// HEARTBEAT.md scheduler configuration.
+///
+/// Entries like `every 30m: check CI status of main` in the brain's
+/// HEARTBEAT.md run as agent turns while the TUI or daemon is up.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeartbeatConfig {
+    /// Whether HEARTBEAT.md entries run (default: true). A file with only
+    /// comments never calls the provider either way.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Where reports go: "tui", "telegram", "discord", "slack" or "whatsapp".
+    /// Unset posts to the TUI when it is running.
+    #[serde(default)]
+    pub deliver_to: Option<String>,
+
+    /// Chat ID, channel ID or phone number for `deliver_to` (default: the channel owner)
+    #[serde(default)]
+    pub target: Option<String>,
+
+    /// How often HEARTBEAT.md is checked for due entries, in seconds (default: 60)
+    #[serde(default = "default_heartbeat_check_secs")]
+    pub check_interval_secs: u64,
+}
+
+fn default_heartbeat_check_secs() -> u64 {
+    60
+}
+
+impl Default for HeartbeatConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            deliver_to: None,
+            target: None,
+            check_interval_secs: default_heartbeat_check_secs(),
+        }
+    }
+}
+
 /// Agent behaviour configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentConfig {
@@ -254,7 +524,7 @@ pub struct AgentConfig {
     #[serde(default = "default_approval_policy")]
     pub approval_policy: String,
 
-    /// Maximum concurrent tool calls
+    /// Maximum concurrent tool calls (read-only tools requested in the same turn)
     #[serde(default = "default_max_concurrent")]
     pub max_concurrent: u32,
 
@@ -265,6 +535,30 @@ pub struct AgentConfig {
     /// Max output tokens for API calls (default: 65536)
     #[serde(default = "default_max_tokens")]
     pub max_tokens: u32,
+
+    /// Extended thinking budget in tokens, per model.
+    /// Keys match as case-insensitive substrings of the model name (longest key wins);
+    /// models without a match, or with a budget of 0, don't think.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub thinking_budget: BTreeMap<String, u32>,
+
+    /// Keep one bash process per session so `cd`, `export` and virtualenvs
+    /// persist between `bash` tool calls (default: false)
+    #[serde(default)]
+    pub persistent_shell: bool,
+}
+
+impl AgentConfig {
+    /// Thinking budget configured for a model, if any
+    pub fn thinking_budget_for(&self, model: &str) -> Option<u32> {
+        let model = model.to_lowercase();
+        self.thinking_budget
+            .iter()
+            .filter(|(key, _)| model.contains(&key.to_lowercase()))
+            .max_by_key(|(key, _)| key.len())
+            .map(|(_, budget)| *budget)
+            .filter(|budget| *budget > 0)
+    }
 }
 
 fn default_approval_policy() -> String {
@@ -290,6 +584,8 @@ impl Default for AgentConfig {
             max_concurrent: default_max_concurrent(),
             context_limit: default_context_limit(),
             max_tokens: default_max_tokens(),
+            thinking_budget: BTreeMap::new(),
+            persistent_shell: false,
         }
     }
 }
@@ -353,8 +649,9 @@ pub struct ProviderConfigs {
     #[serde(default)]
     pub web_search: Option<WebSearchProviders>,
 
-    /// Fallback provider configuration (under [providers.fallback] in config)
-    #[serde(default)]
+    /// Fallback provider configuration: either a runtime failover chain
+    /// (`fallback = ["anthropic", "custom.ollama"]`) or the [providers.fallback] table
+    #[serde(default, deserialize_with = "deserialize_fallback")]
     pub fallback: Option<FallbackProviderConfig>,
 }
 
@@ -413,9 +710,46 @@ pub struct FallbackProviderConfig {
     #[serde(default)]
     pub enabled: bool,
 
-    /// Fallback provider type
+    /// Fallback provider type (used at startup when no primary provider is enabled)
     #[serde(default)]
     pub provider: Option<String>,
+
+    /// Ordered runtime failover chain, e.g. ["anthropic", "openrouter", "custom.ollama"].
+    /// When the active provider returns 5xx/429/overloaded, the next entry takes over.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chain: Vec<String>,
+}
+
+/// Accept both `fallback = ["a", "b"]` (failover chain shorthand) and the
+/// `[providers.fallback]` table.
+fn deserialize_fallback<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Option<FallbackProviderConfig>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de;
+
+    let value: Option<toml::Value> = Option::deserialize(deserializer)?;
+    match value {
+        None => Ok(None),
+        Some(toml::Value::Array(items)) => {
+            let chain = items
+                .into_iter()
+                .map(|v| {
+                    v.as_str()
+                        .map(str::to_string)
+                        .ok_or_else(|| de::Error::custom("fallback entries must be provider names"))
+                })
+                .collect::<std::result::Result<Vec<_>, D::Error>>()?;
+            Ok(Some(FallbackProviderConfig {
+                enabled: !chain.is_empty(),
+                provider: None,
+                chain,
+            }))
+        }
+        Some(value) => value.try_into().map(Some).map_err(de::Error::custom),
+    }
 }
 
 /// STT (Speech-to-Text) provider configurations
@@ -496,7 +830,7 @@ fn default_db_path() -> PathBuf {
 }
 
 /// Expand leading `~` or `~/` in a path to the actual home directory.
-fn expand_tilde(p: &Path) -> PathBuf {
+pub fn expand_tilde(p: &Path) -> PathBuf {
     if let Ok(rest) = p.strip_prefix("~") {
         dirs::home_dir()
             .unwrap_or_else(|| PathBuf::from("."))
@@ -535,6 +869,8 @@ pub fn save_keys(keys: &ProviderConfigs) -> Result<()> {
         ("providers.openrouter", keys.openrouter.as_ref()),
         ("providers.minimax", keys.minimax.as_ref()),
         ("providers.gemini", keys.gemini.as_ref()),
+        ("providers.bedrock", keys.bedrock.as_ref()),
+        ("providers.vertex", keys.vertex.as_ref()),
     ];
 
     for (section, provider) in providers {
@@ -628,12 +964,22 @@ pub struct KeysFile {
     pub channels: ChannelsConfig,
     #[serde(default)]
     pub a2a: Option<KeysA2a>,
+    #[serde(default)]
+    pub gateway: Option<KeysGateway>,
 }
 
 /// A2A keys section in keys.toml
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct KeysA2a {
     pub api_key: Option<String>,
+    #[serde(default)]
+    pub push_secret: Option<String>,
+}
+
+/// HTTP API gateway keys section in keys.toml
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct KeysGateway {
+    pub token: Option<String>,
 }
 
 /// Load API keys from keys.toml
@@ -679,6 +1025,16 @@ fn merge_provider_keys(mut base: ProviderConfigs, keys: ProviderConfigs) -> Prov
             let entry = base.gemini.get_or_insert_with(ProviderConfig::default);
             entry.api_key = Some(key);
         }
+    if let Some(k) = keys.bedrock
+        && let Some(key) = k.api_key {
+            let entry = base.bedrock.get_or_insert_with(ProviderConfig::default);
+            entry.api_key = Some(key);
+        }
+    if let Some(k) = keys.vertex
+        && let Some(key) = k.api_key {
+            let entry = base.vertex.get_or_insert_with(ProviderConfig::default);
+            entry.api_key = Some(key);
+        }
     if let Some(custom_keys) = keys.custom {
         let base_customs = base.custom.get_or_insert_with(BTreeMap::new);
         for (name, key_cfg) in custom_keys {
@@ -797,6 +1153,10 @@ impl Default for Config {
             voice: VoiceConfig::default(),
             agent: AgentConfig::default(),
             a2a: A2aConfig::default(),
+            permissions: PermissionsConfig::default(),
+            sandbox: SandboxConfig::default(),
+            mcp: McpConfig::default(),
+            heartbeat: HeartbeatConfig::default(),
         }
     }
 }
@@ -833,11 +1193,22 @@ impl Config {
         if let Ok(keys) = load_keys_from_file() {
             config.providers = merge_provider_keys(config.providers, keys.providers);
             config.channels = merge_channel_keys(config.channels, keys.channels);
-            // Merge A2A API key from keys.toml
-            if let Some(a2a_keys) = keys.a2a
-                && let Some(key) = a2a_keys.api_key
-                && !key.is_empty() {
-                    config.a2a.api_key = Some(key);
+            // Merge A2A API key and push secret from keys.toml
+            if let Some(a2a_keys) = keys.a2a {
+                if let Some(key) = a2a_keys.api_key
+                    && !key.is_empty() {
+                        config.a2a.api_key = Some(key);
+                }
+                if let Some(secret) = a2a_keys.push_secret
+                    && !secret.is_empty() {
+                        config.a2a.push_secret = Some(secret);
+                }
+            }
+            // Merge HTTP API gateway token from keys.toml
+            if let Some(gateway_keys) = keys.gateway
+                && let Some(token) = gateway_keys.token
+                && !token.is_empty() {
+                    config.gateway.token = Some(token);
             }
         }
 
@@ -917,6 +1288,9 @@ impl Config {
             voice: overlay.voice,
             agent: overlay.agent,
             a2a: overlay.a2a,
+            permissions: overlay.permissions,
+            mcp: overlay.mcp,
+            heartbeat: overlay.heartbeat,
         }
     }
 
@@ -1374,6 +1748,50 @@ api_key = "test-openai-key"
         );
     }
 
+    #[test]
+    fn test_fallback_chain_shorthand() {
+        let config: Config = toml::from_str(
+            r#"
+[providers]
+fallback = ["anthropic", "openrouter", "custom.ollama"]
+            "#,
+        )
+        .unwrap();
+        let fallback = config.providers.fallback.unwrap();
+        assert!(fallback.enabled);
+        assert_eq!(fallback.chain, vec!["anthropic", "openrouter", "custom.ollama"]);
+
+        let config: Config = toml::from_str(
+            r#"
+[providers.fallback]
+enabled = true
+provider = "openrouter"
+            "#,
+        )
+        .unwrap();
+        let fallback = config.providers.fallback.unwrap();
+        assert_eq!(fallback.provider.as_deref(), Some("openrouter"));
+        assert!(fallback.chain.is_empty());
+    }
+
+    #[test]
+    fn test_thinking_budget_per_model() {
+        let config: Config = toml::from_str(
+            r#"
+[agent.thinking_budget]
+"claude" = 4000
+"claude-opus-4" = 16000
+"claude-3-5-haiku" = 0
+            "#,
+        )
+        .unwrap();
+        let agent = &config.agent;
+        assert_eq!(agent.thinking_budget_for("claude-opus-4-6"), Some(16000));
+        assert_eq!(agent.thinking_budget_for("claude-sonnet-4-5"), Some(4000));
+        assert_eq!(agent.thinking_budget_for("claude-3-5-haiku-20241022"), None);
+        assert_eq!(agent.thinking_budget_for("gpt-4o"), None);
+    }
+
     #[test]
     fn test_system_config_path() {
         let path = Config::system_config_path();
@@ -1535,6 +1953,18 @@ pub fn resolve_provider_from_config(config: &Config) -> (&str, &str) {
             .unwrap_or("default");
         return ("Google Gemini", model);
     }
+    if config.providers.bedrock.as_ref().is_some_and(|p| p.enabled) {
+        let model = config.providers.bedrock.as_ref()
+            .and_then(|p| p.default_model.as_deref())
+            .unwrap_or("default");
+        return ("AWS Bedrock", model);
+    }
+    if config.providers.vertex.as_ref().is_some_and(|p| p.enabled) {
+        let model = config.providers.vertex.as_ref()
+            .and_then(|p| p.default_model.as_deref())
+            .unwrap_or("default");
+        return ("Vertex AI", model);
+    }
     if let Some((name, cfg)) = config.providers.active_custom() {
         let model = cfg.default_model.as_deref().unwrap_or("default");
         // Return a static string for the provider name; we can't return the dynamic name
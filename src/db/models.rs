@@ -31,6 +31,10 @@ pub struct Message {
     pub created_at: DateTime<Utc>,
     pub token_count: Option<i32>,
     pub cost: Option<f64>,
+    /// Prompt tokens written to the provider's cache for this message
+    pub cache_creation_tokens: Option<i32>,
+    /// Prompt tokens served from the provider's cache for this message
+    pub cache_read_tokens: Option<i32>,
 }
 
 /// File model
@@ -116,6 +120,29 @@ pub struct PlanTask {
     pub completed_at: Option<DateTime<Utc>>,
 }
 
+/// Checkpoint model — file contents from before one agent turn changed them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    /// User message that started the turn
+    pub message_id: Option<Uuid>,
+    /// First line of the user message
+    pub prompt: String,
+    pub created_at: DateTime<Utc>,
+    /// Set once the turn's changes have been reverted
+    pub restored_at: Option<DateTime<Utc>>,
+}
+
+/// Checkpoint file model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointFile {
+    pub checkpoint_id: Uuid,
+    pub path: std::path::PathBuf,
+    /// Content before the turn; `None` if the turn created the file
+    pub content: Option<Vec<u8>>,
+}
+
 impl Session {
     /// Create a new session
     pub fn new(title: Option<String>, model: Option<String>) -> Self {
@@ -150,6 +177,8 @@ impl Message {
             created_at: Utc::now(),
             token_count: None,
             cost: None,
+            cache_creation_tokens: None,
+            cache_read_tokens: None,
         }
     }
 }
@@ -208,6 +237,8 @@ impl<'r> sqlx::FromRow<'r, sqlx::sqlite::SqliteRow> for Message {
                 .ok_or_else(|| sqlx::Error::Decode("Invalid timestamp for created_at".into()))?,
             token_count: row.try_get("token_count")?,
             cost: row.try_get("cost")?,
+            cache_creation_tokens: row.try_get("cache_creation_tokens")?,
+            cache_read_tokens: row.try_get("cache_read_tokens")?,
         })
     }
 }
@@ -283,6 +314,43 @@ impl<'r> sqlx::FromRow<'r, sqlx::sqlite::SqliteRow> for PlanTask {
     }
 }
 
+impl<'r> sqlx::FromRow<'r, sqlx::sqlite::SqliteRow> for Checkpoint {
+    fn from_row(row: &'r sqlx::sqlite::SqliteRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+
+        Ok(Checkpoint {
+            id: Uuid::parse_str(row.try_get("id")?)
+                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+            session_id: Uuid::parse_str(row.try_get("session_id")?)
+                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+            message_id: row
+                .try_get::<Option<String>, _>("message_id")?
+                .map(|id| Uuid::parse_str(&id))
+                .transpose()
+                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+            prompt: row.try_get("prompt")?,
+            created_at: DateTime::from_timestamp(row.try_get("created_at")?, 0)
+                .ok_or_else(|| sqlx::Error::Decode("Invalid timestamp for created_at".into()))?,
+            restored_at: row
+                .try_get::<Option<i64>, _>("restored_at")?
+                .and_then(|ts| DateTime::from_timestamp(ts, 0)),
+        })
+    }
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::sqlite::SqliteRow> for CheckpointFile {
+    fn from_row(row: &'r sqlx::sqlite::SqliteRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+
+        Ok(CheckpointFile {
+            checkpoint_id: Uuid::parse_str(row.try_get("checkpoint_id")?)
+                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+            path: std::path::PathBuf::from(row.try_get::<String, _>("path")?),
+            content: row.try_get("content")?,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
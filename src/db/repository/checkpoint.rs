@@ -0,0 +1,160 @@
+//! Checkpoint Repository
+//!
+//! Database operations for per-turn file checkpoints.
+
+use crate::db::models::{Checkpoint, CheckpointFile};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use std::path::Path;
+use uuid::Uuid;
+
+/// Repository for checkpoint operations
+#[derive(Clone)]
+pub struct CheckpointRepository {
+    pool: SqlitePool,
+}
+
+impl CheckpointRepository {
+    /// Create a new checkpoint repository
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Find checkpoint by ID
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<Checkpoint>> {
+        let checkpoint = sqlx::query_as::<_, Checkpoint>("SELECT * FROM checkpoints WHERE id = ?")
+            .bind(id.to_string())
+            .fetch_optional(&self.pool)
+            .await
+            .context("Failed to find checkpoint")?;
+
+        Ok(checkpoint)
+    }
+
+    /// Find all checkpoints for a session, newest first
+    pub async fn find_by_session(&self, session_id: Uuid) -> Result<Vec<Checkpoint>> {
+        let checkpoints = sqlx::query_as::<_, Checkpoint>(
+            "SELECT * FROM checkpoints WHERE session_id = ? ORDER BY created_at DESC, rowid DESC",
+        )
+        .bind(session_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to find checkpoints by session")?;
+
+        Ok(checkpoints)
+    }
+
+    /// Create a new checkpoint record
+    pub async fn create(&self, checkpoint: &Checkpoint) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO checkpoints (id, session_id, message_id, prompt, created_at, restored_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(checkpoint.id.to_string())
+        .bind(checkpoint.session_id.to_string())
+        .bind(checkpoint.message_id.map(|id| id.to_string()))
+        .bind(&checkpoint.prompt)
+        .bind(checkpoint.created_at.timestamp())
+        .bind(checkpoint.restored_at.map(|t| t.timestamp()))
+        .execute(&self.pool)
+        .await
+        .context("Failed to create checkpoint")?;
+
+        tracing::debug!("Created checkpoint: {}", checkpoint.id);
+        Ok(())
+    }
+
+    /// Save a file's prior content; the first snapshot of a path wins
+    pub async fn add_file(
+        &self,
+        checkpoint_id: Uuid,
+        path: &Path,
+        content: Option<&[u8]>,
+    ) -> Result<()> {
+        let path_str = path.to_string_lossy();
+
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO checkpoint_files (checkpoint_id, path, content)
+            VALUES (?, ?, ?)
+            "#,
+        )
+        .bind(checkpoint_id.to_string())
+        .bind(path_str.as_ref())
+        .bind(content)
+        .execute(&self.pool)
+        .await
+        .context("Failed to save checkpoint file")?;
+
+        Ok(())
+    }
+
+    /// Files saved in a checkpoint
+    pub async fn files(&self, checkpoint_id: Uuid) -> Result<Vec<CheckpointFile>> {
+        let files = sqlx::query_as::<_, CheckpointFile>(
+            "SELECT * FROM checkpoint_files WHERE checkpoint_id = ? ORDER BY path",
+        )
+        .bind(checkpoint_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list checkpoint files")?;
+
+        Ok(files)
+    }
+
+    /// Paths saved in a checkpoint, without their content
+    pub async fn paths(&self, checkpoint_id: Uuid) -> Result<Vec<String>> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            "SELECT path FROM checkpoint_files WHERE checkpoint_id = ? ORDER BY path",
+        )
+        .bind(checkpoint_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list checkpoint paths")?;
+
+        Ok(rows.into_iter().map(|(path,)| path).collect())
+    }
+
+    /// Mark a checkpoint's changes as reverted
+    pub async fn mark_restored(&self, id: Uuid, restored_at: DateTime<Utc>) -> Result<()> {
+        sqlx::query("UPDATE checkpoints SET restored_at = ? WHERE id = ?")
+            .bind(restored_at.timestamp())
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .context("Failed to mark checkpoint restored")?;
+
+        Ok(())
+    }
+
+    /// Delete all but the `keep` newest checkpoints of a session
+    pub async fn prune(&self, session_id: Uuid, keep: i64) -> Result<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM checkpoints
+            WHERE session_id = ?1 AND id NOT IN (
+                SELECT id FROM checkpoints WHERE session_id = ?1
+                ORDER BY created_at DESC, rowid DESC LIMIT ?2
+            )
+            "#,
+        )
+        .bind(session_id.to_string())
+        .bind(keep)
+        .execute(&self.pool)
+        .await
+        .context("Failed to prune checkpoints")?;
+
+        // In case the connection runs without foreign key enforcement
+        sqlx::query(
+            "DELETE FROM checkpoint_files WHERE checkpoint_id NOT IN (SELECT id FROM checkpoints)",
+        )
+        .execute(&self.pool)
+        .await
+        .context("Failed to prune checkpoint files")?;
+
+        Ok(())
+    }
+}
@@ -48,8 +48,9 @@ impl MessageRepository {
         sqlx::query(
             r#"
             INSERT INTO messages (id, session_id, role, content, sequence,
-                                 created_at, token_count, cost)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                                 created_at, token_count, cost,
+                                 cache_creation_tokens, cache_read_tokens)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(message.id.to_string())
@@ -60,6 +61,8 @@ impl MessageRepository {
         .bind(message.created_at.timestamp())
         .bind(message.token_count)
         .bind(message.cost)
+        .bind(message.cache_creation_tokens)
+        .bind(message.cache_read_tokens)
         .execute(&self.pool)
         .await
         .context("Failed to create message")?;
@@ -77,13 +80,16 @@ impl MessageRepository {
         sqlx::query(
             r#"
             UPDATE messages
-            SET content = ?, token_count = ?, cost = ?
+            SET content = ?, token_count = ?, cost = ?,
+                cache_creation_tokens = ?, cache_read_tokens = ?
             WHERE id = ?
             "#,
         )
         .bind(&message.content)
         .bind(message.token_count)
         .bind(message.cost)
+        .bind(message.cache_creation_tokens)
+        .bind(message.cache_read_tokens)
         .bind(message.id.to_string())
         .execute(&self.pool)
         .await
@@ -2,11 +2,13 @@
 //!
 //! Repository pattern implementations for database access.
 
+pub mod checkpoint;
 pub mod file;
 pub mod message;
 pub mod plan;
 pub mod session;
 
+pub use checkpoint::CheckpointRepository;
 pub use file::FileRepository;
 pub use message::MessageRepository;
 pub use plan::PlanRepository;
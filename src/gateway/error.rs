@@ -0,0 +1,75 @@
+//! Gateway error types
+
+use crate::brain::agent::AgentError;
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use thiserror::Error;
+
+/// Gateway error types, returned to clients as OpenAI-style error bodies
+#[derive(Debug, Error)]
+pub enum GatewayError {
+    /// Missing or wrong Bearer token
+    #[error("Unauthorized: invalid or missing Bearer token")]
+    Unauthorized,
+
+    /// Request body or parameters are invalid
+    #[error("Invalid request: {0}")]
+    BadRequest(String),
+
+    /// Session, plan or other resource does not exist
+    #[error("{0} not found")]
+    NotFound(String),
+
+    /// Agent turn failed
+    #[error("Agent error: {0}")]
+    Agent(#[from] AgentError),
+
+    /// Database or other internal failure
+    #[error("{0:#}")]
+    Internal(#[from] anyhow::Error),
+}
+
+impl GatewayError {
+    fn status(&self) -> StatusCode {
+        match self {
+            Self::Unauthorized => StatusCode::UNAUTHORIZED,
+            Self::BadRequest(_) => StatusCode::BAD_REQUEST,
+            Self::NotFound(_) | Self::Agent(AgentError::SessionNotFound(_)) => {
+                StatusCode::NOT_FOUND
+            }
+            Self::Agent(_) => StatusCode::BAD_GATEWAY,
+            Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// OpenAI error `type` for this error
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Unauthorized => "authentication_error",
+            Self::BadRequest(_) => "invalid_request_error",
+            Self::NotFound(_) | Self::Agent(AgentError::SessionNotFound(_)) => "not_found_error",
+            Self::Agent(_) | Self::Internal(_) => "server_error",
+        }
+    }
+
+    /// `{"error": {"message": ..., "type": ...}}`
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "error": { "message": self.to_string(), "type": self.kind() }
+        })
+    }
+}
+
+impl IntoResponse for GatewayError {
+    fn into_response(self) -> Response {
+        if matches!(self, Self::Internal(_)) {
+            tracing::error!("Gateway: {}", self);
+        }
+        (self.status(), Json(self.to_json())).into_response()
+    }
+}
+
+/// Result type for gateway handlers
+pub type Result<T> = std::result::Result<T, GatewayError>;
@@ -0,0 +1,19 @@
+//! HTTP API gateway for OpenCrabs.
+//!
+//! Configured by `[gateway]` in config.toml. Serves:
+//! - an OpenAI-compatible `POST /v1/chat/completions` (JSON or SSE streaming)
+//!   backed by [`AgentService`](crate::brain::agent::AgentService), so IDE
+//!   plugins and other OpenAI clients can use OpenCrabs — tools, memory and
+//!   all — as a "model"
+//! - REST endpoints for sessions, their messages and plans
+//!
+//! With `auth_mode = "token"` every request except `/v1/health` needs
+//! `Authorization: Bearer <token>`.
+
+pub mod error;
+pub mod openai;
+pub mod rest;
+pub mod server;
+
+pub use error::{GatewayError, Result};
+pub use server::start_server;
@@ -0,0 +1,334 @@
+//! OpenAI-compatible endpoints: `POST /v1/chat/completions` and `GET /v1/models`.
+//!
+//! Each completion is one agent turn, tools included. OpenAI clients resend the
+//! whole conversation every time, so without a session a new one is created and
+//! the earlier turns are passed to the agent as context. To continue a session
+//! instead, send back the `x-session-id` response header (or `session_id` in the
+//! body); only the last user message is used then.
+
+use super::error::{GatewayError, Result};
+use super::server::GatewayState;
+use crate::brain::agent::{AgentError, AgentResponse, ProgressCallback, ProgressEvent};
+use crate::services::SessionService;
+use axum::{
+    extract::State,
+    http::HeaderMap,
+    response::{sse, IntoResponse, Json, Response, Sse},
+};
+use futures::{stream, StreamExt};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::convert::Infallible;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+/// Model name reported to clients when the request names none
+pub const MODEL_NAME: &str = "opencrabs";
+
+/// Header carrying the session a completion ran in
+pub const SESSION_HEADER: &str = "x-session-id";
+
+/// `POST /v1/chat/completions` request body. Sampling parameters are accepted
+/// and ignored — the configured provider and model are always used.
+#[derive(Debug, Deserialize)]
+pub struct ChatCompletionRequest {
+    #[serde(default)]
+    pub model: Option<String>,
+    pub messages: Vec<ChatMessage>,
+    #[serde(default)]
+    pub stream: bool,
+    /// OpenCrabs extension: continue this session
+    #[serde(default)]
+    pub session_id: Option<Uuid>,
+}
+
+/// One message of a chat completion request
+#[derive(Debug, Deserialize)]
+pub struct ChatMessage {
+    pub role: String,
+    /// A string, or an array of `{"type": "text", "text": ...}` parts
+    #[serde(default)]
+    pub content: Value,
+}
+
+impl ChatMessage {
+    /// Text content. Non-text parts (images, audio) are dropped.
+    pub fn text(&self) -> String {
+        match &self.content {
+            Value::String(text) => text.clone(),
+            Value::Array(parts) => parts
+                .iter()
+                .filter_map(|p| p.get("text").and_then(Value::as_str))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => String::new(),
+        }
+    }
+}
+
+/// The prompt for a new session: the client's system messages, the earlier
+/// turns, then the last user message.
+pub(crate) fn build_prompt(messages: &[ChatMessage]) -> String {
+    let Some((last, earlier)) = messages.split_last() else {
+        return String::new();
+    };
+
+    let instructions: Vec<String> = earlier
+        .iter()
+        .filter(|m| m.role == "system" || m.role == "developer")
+        .map(ChatMessage::text)
+        .filter(|text| !text.trim().is_empty())
+        .collect();
+    let history: Vec<String> = earlier
+        .iter()
+        .filter(|m| m.role == "user" || m.role == "assistant")
+        .map(|m| format!("{}: {}", m.role, m.text()))
+        .collect();
+
+    let mut prompt = String::new();
+    if !instructions.is_empty() {
+        prompt.push_str("Instructions from the client:\n");
+        prompt.push_str(&instructions.join("\n\n"));
+        prompt.push_str("\n\n");
+    }
+    if !history.is_empty() {
+        prompt.push_str("Earlier conversation:\n");
+        prompt.push_str(&history.join("\n"));
+        prompt.push_str("\n\n");
+    }
+    prompt.push_str(&last.text());
+    prompt
+}
+
+/// `POST /v1/chat/completions`
+pub async fn chat_completions(
+    State(state): State<GatewayState>,
+    headers: HeaderMap,
+    Json(req): Json<ChatCompletionRequest>,
+) -> Result<Response> {
+    let last = req
+        .messages
+        .last()
+        .filter(|m| m.role == "user")
+        .ok_or_else(|| GatewayError::BadRequest("the last message must be from the user".into()))?;
+    let last_text = last.text();
+    if last_text.trim().is_empty() {
+        return Err(GatewayError::BadRequest("the last user message is empty".into()));
+    }
+
+    let header_session = headers
+        .get(SESSION_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(Uuid::parse_str)
+        .transpose()
+        .map_err(|_| GatewayError::BadRequest(format!("{} must be a UUID", SESSION_HEADER)))?;
+
+    let sessions = SessionService::new(state.service_context.clone());
+    let (session_id, prompt) = match req.session_id.or(header_session) {
+        Some(id) => {
+            if sessions.get_session(id).await?.is_none() {
+                return Err(GatewayError::NotFound(format!("Session {}", id)));
+            }
+            (id, last_text)
+        }
+        None => {
+            let title = format!("API: {}", last_text.chars().take(60).collect::<String>());
+            let session = sessions.create_session(Some(title)).await?;
+            (session.id, build_prompt(&req.messages))
+        }
+    };
+
+    let completion = Completion::new(req.model);
+    if req.stream {
+        return Ok(stream_completion(&state, session_id, prompt, completion));
+    }
+
+    let response = state
+        .agent_service
+        .send_message_with_tools(session_id, prompt, None)
+        .await?;
+    Ok((
+        [(SESSION_HEADER, session_id.to_string())],
+        Json(completion.response(&response, session_id)),
+    )
+        .into_response())
+}
+
+/// `GET /v1/models`
+pub async fn list_models() -> Json<Value> {
+    Json(json!({
+        "object": "list",
+        "data": [{
+            "id": MODEL_NAME,
+            "object": "model",
+            "created": 0,
+            "owned_by": "opencrabs"
+        }]
+    }))
+}
+
+/// Ids shared by every chunk of one completion
+struct Completion {
+    id: String,
+    created: i64,
+    model: String,
+}
+
+impl Completion {
+    fn new(model: Option<String>) -> Self {
+        Self {
+            id: format!("chatcmpl-{}", Uuid::new_v4().simple()),
+            created: chrono::Utc::now().timestamp(),
+            model: model.unwrap_or_else(|| MODEL_NAME.to_string()),
+        }
+    }
+
+    fn response(&self, response: &AgentResponse, session_id: Uuid) -> Value {
+        let usage = &response.usage;
+        json!({
+            "id": self.id,
+            "object": "chat.completion",
+            "created": self.created,
+            "model": self.model,
+            "choices": [{
+                "index": 0,
+                "message": { "role": "assistant", "content": response.content },
+                "finish_reason": "stop"
+            }],
+            "usage": {
+                "prompt_tokens": usage.input_tokens,
+                "completion_tokens": usage.output_tokens,
+                "total_tokens": u64::from(usage.input_tokens) + u64::from(usage.output_tokens)
+            },
+            "session_id": session_id
+        })
+    }
+
+    fn chunk(&self, delta: Value, finish_reason: Option<&str>) -> sse::Event {
+        let chunk = json!({
+            "id": self.id,
+            "object": "chat.completion.chunk",
+            "created": self.created,
+            "model": self.model,
+            "choices": [{ "index": 0, "delta": delta, "finish_reason": finish_reason }]
+        });
+        sse::Event::default().data(chunk.to_string())
+    }
+}
+
+/// Events from a running streaming turn
+enum StreamItem {
+    Chunk(String),
+    Done(std::result::Result<AgentResponse, AgentError>),
+}
+
+/// Run the turn in the background and stream its text as
+/// `chat.completion.chunk` events, ending with `[DONE]`.
+fn stream_completion(
+    state: &GatewayState,
+    session_id: Uuid,
+    prompt: String,
+    completion: Completion,
+) -> Response {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let chunks = tx.clone();
+    let progress: ProgressCallback = Arc::new(move |event| {
+        if let ProgressEvent::StreamingChunk { text } = event {
+            let _ = chunks.send(StreamItem::Chunk(text));
+        }
+    });
+    let agent = state.channel_factory.create_streaming_agent_service(progress);
+    tokio::spawn(async move {
+        let result = agent.send_message_with_tools(session_id, prompt, None).await;
+        let _ = tx.send(StreamItem::Done(result));
+    });
+
+    let completion = Arc::new(completion);
+    let first = completion.chunk(json!({ "role": "assistant", "content": "" }), None);
+    let rest = stream::unfold(Some((rx, false)), move |next| {
+        let completion = completion.clone();
+        async move {
+            let (mut rx, streamed) = next?;
+            match rx.recv().await? {
+                StreamItem::Chunk(text) => Some((
+                    vec![completion.chunk(json!({ "content": text }), None)],
+                    Some((rx, true)),
+                )),
+                StreamItem::Done(result) => {
+                    let mut events = Vec::new();
+                    match result {
+                        Ok(response) => {
+                            // Providers that don't stream only deliver the final text
+                            if !streamed && !response.content.is_empty() {
+                                events.push(
+                                    completion.chunk(json!({ "content": response.content }), None),
+                                );
+                            }
+                            events.push(completion.chunk(json!({}), Some("stop")));
+                        }
+                        Err(e) => {
+                            let error = GatewayError::from(e).to_json();
+                            events.push(sse::Event::default().data(error.to_string()));
+                        }
+                    }
+                    events.push(sse::Event::default().data("[DONE]"));
+                    Some((events, None))
+                }
+            }
+        }
+    });
+
+    let events = stream::once(async move { vec![first] })
+        .chain(rest)
+        .flat_map(|events| stream::iter(events.into_iter().map(Ok::<_, Infallible>)));
+    (
+        [(SESSION_HEADER, session_id.to_string())],
+        Sse::new(events).keep_alive(sse::KeepAlive::default()),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: &str, content: Value) -> ChatMessage {
+        ChatMessage {
+            role: role.to_string(),
+            content,
+        }
+    }
+
+    #[test]
+    fn test_message_text_parts() {
+        let msg = message(
+            "user",
+            json!([
+                { "type": "text", "text": "first" },
+                { "type": "image_url", "image_url": { "url": "data:..." } },
+                { "type": "text", "text": "second" }
+            ]),
+        );
+        assert_eq!(msg.text(), "first\nsecond");
+        assert_eq!(message("user", Value::Null).text(), "");
+    }
+
+    #[test]
+    fn test_build_prompt() {
+        assert_eq!(build_prompt(&[message("user", json!("hi"))]), "hi");
+
+        let prompt = build_prompt(&[
+            message("system", json!("Answer in French.")),
+            message("user", json!("What is 2+2?")),
+            message("assistant", json!("Quatre.")),
+            message("user", json!("And 3+3?")),
+        ]);
+        assert_eq!(
+            prompt,
+            "Instructions from the client:\nAnswer in French.\n\n\
+             Earlier conversation:\nuser: What is 2+2?\nassistant: Quatre.\n\n\
+             And 3+3?"
+        );
+    }
+}
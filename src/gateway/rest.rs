@@ -0,0 +1,165 @@
+//! REST endpoints for sessions, their messages and plans.
+
+use super::error::{GatewayError, Result};
+use super::server::GatewayState;
+use crate::db::models::{Message, Session};
+use crate::db::repository::SessionListOptions;
+use crate::services::{MessageService, PlanService, SessionService};
+use crate::tui::plan::PlanDocument;
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+/// Sessions returned by `GET /v1/sessions` unless `limit` is given
+const DEFAULT_SESSION_LIMIT: usize = 50;
+
+#[derive(Debug, Deserialize)]
+pub struct ListSessionsQuery {
+    #[serde(default)]
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub offset: Option<usize>,
+    #[serde(default)]
+    pub archived: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct CreateSessionRequest {
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SendMessageRequest {
+    pub content: String,
+}
+
+/// 404 unless the session exists
+async fn require_session(state: &GatewayState, id: Uuid) -> Result<Session> {
+    SessionService::new(state.service_context.clone())
+        .get_session(id)
+        .await?
+        .ok_or_else(|| GatewayError::NotFound(format!("Session {}", id)))
+}
+
+/// `GET /v1/sessions?limit=&offset=&archived=`
+pub async fn list_sessions(
+    State(state): State<GatewayState>,
+    Query(query): Query<ListSessionsQuery>,
+) -> Result<Json<Vec<Session>>> {
+    let sessions = SessionService::new(state.service_context.clone())
+        .list_sessions(SessionListOptions {
+            include_archived: query.archived,
+            limit: Some(query.limit.unwrap_or(DEFAULT_SESSION_LIMIT)),
+            offset: query.offset.unwrap_or(0),
+        })
+        .await?;
+    Ok(Json(sessions))
+}
+
+/// `POST /v1/sessions` with an optional `{"title": ...}`
+pub async fn create_session(
+    State(state): State<GatewayState>,
+    body: Bytes,
+) -> Result<(StatusCode, Json<Session>)> {
+    let req: CreateSessionRequest = if body.is_empty() {
+        CreateSessionRequest::default()
+    } else {
+        serde_json::from_slice(&body).map_err(|e| GatewayError::BadRequest(e.to_string()))?
+    };
+    let session = SessionService::new(state.service_context.clone())
+        .create_session(req.title)
+        .await?;
+    Ok((StatusCode::CREATED, Json(session)))
+}
+
+/// `GET /v1/sessions/{id}`
+pub async fn get_session(
+    State(state): State<GatewayState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Session>> {
+    Ok(Json(require_session(&state, id).await?))
+}
+
+/// `DELETE /v1/sessions/{id}`
+pub async fn delete_session(
+    State(state): State<GatewayState>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode> {
+    require_session(&state, id).await?;
+    SessionService::new(state.service_context.clone())
+        .delete_session(id)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `GET /v1/sessions/{id}/messages`
+pub async fn list_messages(
+    State(state): State<GatewayState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<Message>>> {
+    require_session(&state, id).await?;
+    let messages = MessageService::new(state.service_context.clone())
+        .list_messages_for_session(id)
+        .await?;
+    Ok(Json(messages))
+}
+
+/// `POST /v1/sessions/{id}/messages` with `{"content": ...}` — runs one agent
+/// turn in the session and returns the reply.
+pub async fn send_message(
+    State(state): State<GatewayState>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<SendMessageRequest>,
+) -> Result<Json<Value>> {
+    if req.content.trim().is_empty() {
+        return Err(GatewayError::BadRequest("content is empty".into()));
+    }
+    require_session(&state, id).await?;
+
+    let response = state
+        .agent_service
+        .send_message_with_tools(id, req.content, None)
+        .await?;
+    Ok(Json(json!({
+        "session_id": id,
+        "message_id": response.message_id,
+        "content": response.content,
+        "model": response.model,
+        "usage": {
+            "input_tokens": response.usage.input_tokens,
+            "output_tokens": response.usage.output_tokens
+        },
+        "cost": response.cost
+    })))
+}
+
+/// `GET /v1/sessions/{id}/plans`
+pub async fn list_plans(
+    State(state): State<GatewayState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<PlanDocument>>> {
+    require_session(&state, id).await?;
+    let plans = PlanService::new(state.service_context.clone())
+        .find_by_session_id(id)
+        .await?;
+    Ok(Json(plans))
+}
+
+/// `GET /v1/plans/{id}`
+pub async fn get_plan(
+    State(state): State<GatewayState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<PlanDocument>> {
+    PlanService::new(state.service_context.clone())
+        .find_by_id(id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| GatewayError::NotFound(format!("Plan {}", id)))
+}
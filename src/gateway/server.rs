@@ -0,0 +1,346 @@
+//! HTTP API gateway server powered by axum.
+//!
+//! Serves:
+//! - `GET    /v1/health`                — Health check (no auth)
+//! - `GET    /v1/models`                — Model list for OpenAI clients
+//! - `POST   /v1/chat/completions`      — OpenAI-compatible chat (JSON or SSE)
+//! - `GET    /v1/sessions`              — List sessions
+//! - `POST   /v1/sessions`              — Create a session
+//! - `GET    /v1/sessions/{id}`         — Get a session
+//! - `DELETE /v1/sessions/{id}`         — Delete a session
+//! - `GET    /v1/sessions/{id}/messages` — Message history
+//! - `POST   /v1/sessions/{id}/messages` — Run an agent turn in a session
+//! - `GET    /v1/sessions/{id}/plans`   — Plans of a session
+//! - `GET    /v1/plans/{id}`            — Get a plan
+
+use super::error::GatewayError;
+use super::openai::{self, SESSION_HEADER};
+use super::rest;
+use crate::a2a::server::cors_layer;
+use crate::brain::agent::AgentService;
+use crate::channels::ChannelFactory;
+use crate::config::GatewayConfig;
+use crate::services::ServiceContext;
+use axum::{
+    extract::State,
+    http::{header, HeaderName, Method},
+    middleware,
+    response::{IntoResponse, Json},
+    routing::{get, post},
+    Router,
+};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+/// Shared state for the HTTP API gateway.
+#[derive(Clone)]
+pub struct GatewayState {
+    /// Answers non-streaming requests
+    pub agent_service: Arc<AgentService>,
+    /// Creates an agent per streaming request, to collect its output
+    pub channel_factory: Arc<ChannelFactory>,
+    pub service_context: ServiceContext,
+    /// Bearer token required on every endpoint but `/v1/health`
+    pub token: Option<String>,
+}
+
+/// Bearer token auth middleware. Skipped when no token is required.
+async fn require_token(
+    State(state): State<GatewayState>,
+    req: axum::http::Request<axum::body::Body>,
+    next: middleware::Next,
+) -> axum::response::Response {
+    let Some(ref expected) = state.token else {
+        return next.run(req).await;
+    };
+
+    let authorized = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .is_some_and(|token| token == expected);
+
+    if authorized {
+        next.run(req).await
+    } else {
+        GatewayError::Unauthorized.into_response()
+    }
+}
+
+/// Build the axum router for the gateway.
+pub fn build_router(state: GatewayState, allowed_origins: &[String]) -> Router {
+    let cors = cors_layer(allowed_origins)
+        .allow_methods([Method::GET, Method::POST, Method::DELETE])
+        .allow_headers([
+            header::AUTHORIZATION,
+            header::CONTENT_TYPE,
+            HeaderName::from_static(SESSION_HEADER),
+        ])
+        .expose_headers([HeaderName::from_static(SESSION_HEADER)]);
+
+    let protected = Router::new()
+        .route("/v1/models", get(openai::list_models))
+        .route("/v1/chat/completions", post(openai::chat_completions))
+        .route(
+            "/v1/sessions",
+            get(rest::list_sessions).post(rest::create_session),
+        )
+        .route(
+            "/v1/sessions/{id}",
+            get(rest::get_session).delete(rest::delete_session),
+        )
+        .route(
+            "/v1/sessions/{id}/messages",
+            get(rest::list_messages).post(rest::send_message),
+        )
+        .route("/v1/sessions/{id}/plans", get(rest::list_plans))
+        .route("/v1/plans/{id}", get(rest::get_plan))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_token));
+
+    Router::new()
+        .route("/v1/health", get(health_check))
+        .merge(protected)
+        .layer(cors)
+        .with_state(state)
+}
+
+/// The token `auth_mode` calls for: `Some` for "token", `None` for "none".
+fn resolve_token(config: &GatewayConfig) -> anyhow::Result<Option<String>> {
+    match config.auth_mode.as_str() {
+        "token" => config
+            .token
+            .clone()
+            .filter(|t| !t.trim().is_empty())
+            .map(Some)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "gateway auth_mode is \"token\" but no token is set — add \
+                     `[gateway] token = \"...\"` to keys.toml, or set auth_mode = \"none\""
+                )
+            }),
+        "none" => {
+            let loopback = config.bind == "localhost"
+                || config.bind.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback());
+            if !loopback {
+                tracing::warn!(
+                    "Gateway bound to {} with auth_mode = \"none\" — anyone who can reach it can run the agent",
+                    config.bind
+                );
+            }
+            Ok(None)
+        }
+        other => anyhow::bail!(
+            "Unknown gateway auth_mode '{}' (expected \"token\" or \"none\")",
+            other
+        ),
+    }
+}
+
+/// Start the HTTP API gateway server.
+///
+/// Runs as a background task — call from `tokio::spawn`.
+pub async fn start_server(
+    config: &GatewayConfig,
+    channel_factory: Arc<ChannelFactory>,
+    service_context: ServiceContext,
+) -> anyhow::Result<()> {
+    if !config.enabled {
+        tracing::info!("HTTP API gateway disabled in config");
+        return Ok(());
+    }
+
+    let state = GatewayState {
+        token: resolve_token(config)?,
+        // Nobody is there to approve tool calls for an API request
+        agent_service: channel_factory.create_unattended_agent_service(),
+        channel_factory,
+        service_context,
+    };
+
+    let app = build_router(state, &config.allowed_origins);
+    let addr: SocketAddr = format!("{}:{}", config.bind, config.port)
+        .parse()
+        .map_err(|e| anyhow::anyhow!("Invalid gateway address: {}", e))?;
+
+    tracing::info!("HTTP API gateway starting on http://{}", addr);
+    tracing::info!("   Chat completions: http://{}/v1/chat/completions", addr);
+    tracing::info!("   Sessions:         http://{}/v1/sessions", addr);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
+
+/// GET /v1/health — Health check.
+async fn health_check() -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "status": "ok",
+        "version": crate::VERSION
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::test_helpers::helpers;
+    use crate::brain::provider::PlaceholderProvider;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    async fn test_state(token: Option<&str>) -> GatewayState {
+        let service_context = helpers::placeholder_service_context().await;
+        let channel_factory = Arc::new(ChannelFactory::new(
+            Arc::new(PlaceholderProvider),
+            service_context.clone(),
+            String::new(),
+            std::env::temp_dir(),
+            std::env::temp_dir(),
+            Arc::new(tokio::sync::Mutex::new(None)),
+            crate::config::VoiceConfig::default(),
+            None,
+        ));
+        GatewayState {
+            agent_service: channel_factory.create_unattended_agent_service(),
+            channel_factory,
+            service_context,
+            token: token.map(str::to_string),
+        }
+    }
+
+    fn request(method: &str, uri: &str, token: Option<&str>, body: &str) -> Request<Body> {
+        let mut builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::CONTENT_TYPE, "application/json");
+        if let Some(token) = token {
+            builder = builder.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        builder.body(Body::from(body.to_string())).expect("request")
+    }
+
+    async fn json_body(resp: axum::response::Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        serde_json::from_slice(&bytes).expect("json")
+    }
+
+    #[tokio::test]
+    async fn test_token_auth() {
+        let app = build_router(test_state(Some("s3cret")).await, &[]);
+
+        let resp = app
+            .clone()
+            .oneshot(request("GET", "/v1/health", None, ""))
+            .await
+            .expect("response");
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp = app
+            .clone()
+            .oneshot(request("GET", "/v1/models", Some("wrong"), ""))
+            .await
+            .expect("response");
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(json_body(resp).await["error"]["type"], "authentication_error");
+
+        let resp = app
+            .oneshot(request("GET", "/v1/models", Some("s3cret"), ""))
+            .await
+            .expect("response");
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_session_endpoints() {
+        let app = build_router(test_state(None).await, &[]);
+
+        let resp = app
+            .clone()
+            .oneshot(request("POST", "/v1/sessions", None, r#"{"title":"From the API"}"#))
+            .await
+            .expect("response");
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let id = json_body(resp).await["id"]
+            .as_str()
+            .expect("id")
+            .to_string();
+
+        let resp = app
+            .clone()
+            .oneshot(request("GET", "/v1/sessions", None, ""))
+            .await
+            .expect("response");
+        let sessions = json_body(resp).await;
+        assert_eq!(sessions[0]["title"], "From the API");
+
+        let resp = app
+            .clone()
+            .oneshot(request("GET", &format!("/v1/sessions/{}/messages", id), None, ""))
+            .await
+            .expect("response");
+        assert_eq!(json_body(resp).await, serde_json::json!([]));
+
+        let resp = app
+            .clone()
+            .oneshot(request("DELETE", &format!("/v1/sessions/{}", id), None, ""))
+            .await
+            .expect("response");
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+        let resp = app
+            .oneshot(request("GET", &format!("/v1/sessions/{}", id), None, ""))
+            .await
+            .expect("response");
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_chat_completion_validation() {
+        let app = build_router(test_state(None).await, &[]);
+
+        let resp = app
+            .clone()
+            .oneshot(request(
+                "POST",
+                "/v1/chat/completions",
+                None,
+                r#"{"model":"opencrabs","messages":[{"role":"assistant","content":"hi"}]}"#,
+            ))
+            .await
+            .expect("response");
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let resp = app
+            .oneshot(request(
+                "POST",
+                "/v1/chat/completions",
+                None,
+                &format!(
+                    r#"{{"messages":[{{"role":"user","content":"hi"}}],"session_id":"{}"}}"#,
+                    uuid::Uuid::new_v4()
+                ),
+            ))
+            .await
+            .expect("response");
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn test_resolve_token() {
+        let mut config = GatewayConfig::default();
+        assert!(resolve_token(&config).is_err());
+
+        config.token = Some("s3cret".to_string());
+        assert_eq!(resolve_token(&config).unwrap().as_deref(), Some("s3cret"));
+
+        config.auth_mode = "none".to_string();
+        assert_eq!(resolve_token(&config).unwrap(), None);
+
+        config.auth_mode = "basic".to_string();
+        assert!(resolve_token(&config).is_err());
+    }
+}
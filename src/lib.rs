@@ -33,12 +33,15 @@ pub mod db;
 pub mod error;
 pub mod logging;
 pub mod memory;
+pub mod scheduler;
 pub mod services;
 pub mod tui;
 pub mod utils;
 
 pub mod channels;
 pub mod a2a;
+pub mod gateway;
+pub mod mcp;
 
 // Re-export commonly used types
 pub use error::{OpenCrabsError, ErrorCode};
@@ -22,6 +22,9 @@ pub struct LogConfig {
     /// Enable console output (for non-TUI modes)
     pub console_output: bool,
 
+    /// Write log files even outside debug mode (headless daemon)
+    pub file_output: bool,
+
     /// Log file name prefix
     pub log_prefix: String,
 
@@ -37,6 +40,7 @@ impl Default for LogConfig {
             log_dir: home.join(".opencrabs").join("logs"),
             log_level: Level::INFO,
             console_output: false,
+            file_output: false,
             log_prefix: "opencrabs".to_string(),
             max_age_days: 7,
         }
@@ -76,6 +80,12 @@ impl LogConfig {
         self
     }
 
+    /// Write log files at the configured level without enabling debug mode
+    pub fn with_file_output(mut self, enabled: bool) -> Self {
+        self.file_output = enabled;
+        self
+    }
+
     /// Set log file prefix
     pub fn with_log_prefix(mut self, prefix: String) -> Self {
         self.log_prefix = prefix;
@@ -114,8 +124,9 @@ impl LoggerGuard {
 /// # Behavior
 /// - **Debug mode OFF**: No log files created, minimal console output
 /// - **Debug mode ON**: Creates log files in `.opencrabs/logs/`, detailed logging
+/// - **File output ON**: Creates log files at the configured level (daemon mode)
 pub fn init_logging(config: LogConfig) -> Result<LoggerGuard, Box<dyn std::error::Error>> {
-    if config.debug_mode {
+    if config.debug_mode || config.file_output {
         // Debug mode: Create log files in .opencrabs/logs/
         init_debug_logging(config)
     } else {
@@ -167,7 +178,11 @@ fn init_debug_logging(config: LogConfig) -> Result<LoggerGuard, Box<dyn std::err
         .init();
 
     // Log startup information
-    tracing::info!("🚀 OpenCrabs debug mode enabled");
+    if config.debug_mode {
+        tracing::info!("🚀 OpenCrabs debug mode enabled");
+    } else {
+        tracing::info!("🚀 OpenCrabs file logging enabled");
+    }
     tracing::info!("📁 Log directory: {}", config.log_dir.display());
     tracing::info!("📊 Log level: {:?}", config.log_level);
     tracing::debug!("Debug logging initialized successfully");
@@ -12,7 +12,11 @@ async fn main() -> Result<()> {
     let cli_args = cli::Cli::parse();
 
     // Initialize logging based on --debug flag
-    let mut log_config = logging::LogConfig::new().with_debug_mode(cli_args.debug);
+    // The headless daemon has no terminal, so it always logs to file
+    let daemon_mode = matches!(cli_args.command, Some(cli::Commands::Daemon));
+    let mut log_config = logging::LogConfig::new()
+        .with_debug_mode(cli_args.debug)
+        .with_file_output(daemon_mode);
 
     // Custom log directory from env
     if let Ok(log_dir) = std::env::var("DEBUG_LOGS_LOCATION") {
@@ -23,7 +27,7 @@ async fn main() -> Result<()> {
         .map_err(|e| anyhow::anyhow!("Failed to initialize logging: {}", e))?;
 
     // Clean up old log files (keep last 7 days)
-    if cli_args.debug
+    if (cli_args.debug || daemon_mode)
         && let Ok(removed) = logging::cleanup_old_logs(7)
             && removed > 0 {
                 tracing::info!("🧹 Cleaned up {} old log file(s)", removed);
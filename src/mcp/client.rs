@@ -0,0 +1,391 @@
+//! MCP client for one configured server.
+//!
+//! The client owns at most one live [`Transport`]. When a request fails
+//! because the connection is gone (stdio process crashed, HTTP session
+//! expired), the client reconnects, repeats the `initialize` handshake and
+//! retries the request once — unless it is a `tools/call`, which the server
+//! may already have run. That error goes back to the caller and the next
+//! request reconnects.
+
+use super::error::{McpError, Result};
+use super::protocol::{self, InitializeResult, McpPrompt, McpResource, McpToolInfo};
+use super::transport::Transport;
+use crate::config::McpServerConfig;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// Pause before reconnecting, so a server that dies on startup doesn't spin.
+const RECONNECT_DELAY: Duration = Duration::from_millis(500);
+
+/// Methods that are safe to send twice, so they are retried after a lost
+/// connection.
+const IDEMPOTENT_METHODS: &[&str] = &[
+    "initialize",
+    "tools/list",
+    "resources/list",
+    "resources/read",
+    "prompts/list",
+];
+
+/// Everything a server offered when we connected.
+#[derive(Debug, Clone, Default)]
+pub struct ServerInventory {
+    pub tools: Vec<McpToolInfo>,
+    pub resources: Vec<McpResource>,
+    pub prompts: Vec<McpPrompt>,
+}
+
+struct Connection {
+    transport: Transport,
+    info: InitializeResult,
+}
+
+/// Client for a single `[[mcp.servers]]` entry.
+pub struct McpClient {
+    config: McpServerConfig,
+    connection: Mutex<Option<Arc<Connection>>>,
+    next_id: AtomicU64,
+}
+
+impl McpClient {
+    /// Create a client; nothing is started until the first request.
+    pub fn new(config: McpServerConfig) -> Self {
+        Self {
+            config,
+            connection: Mutex::new(None),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Server name from config (the tool namespace)
+    pub fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(self.config.timeout_secs.max(1))
+    }
+
+    /// Connect and list the server's tools, resources and prompts.
+    pub async fn discover(&self) -> Result<ServerInventory> {
+        let connection = self.connection().await?;
+        let tools = if connection.info.supports("tools") {
+            self.list_all("tools/list", "tools").await?
+        } else {
+            Vec::new()
+        };
+        // Resources and prompts are optional extras; a server that advertises
+        // them but fails to list them still gets its tools registered.
+        let resources = if connection.info.supports("resources") {
+            self.list_all("resources/list", "resources")
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!("MCP server '{}': resources/list failed: {}", self.name(), e);
+                    Vec::new()
+                })
+        } else {
+            Vec::new()
+        };
+        let prompts = if connection.info.supports("prompts") {
+            self.list_all("prompts/list", "prompts")
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!("MCP server '{}': prompts/list failed: {}", self.name(), e);
+                    Vec::new()
+                })
+        } else {
+            Vec::new()
+        };
+        Ok(ServerInventory {
+            tools,
+            resources,
+            prompts,
+        })
+    }
+
+    /// Invoke a tool and return the raw `tools/call` result.
+    pub async fn call_tool(&self, name: &str, arguments: Value) -> Result<Value> {
+        self.request(
+            "tools/call",
+            json!({ "name": name, "arguments": arguments }),
+        )
+        .await
+    }
+
+    /// Read a resource and return the raw `resources/read` result.
+    pub async fn read_resource(&self, uri: &str) -> Result<Value> {
+        self.request("resources/read", json!({ "uri": uri })).await
+    }
+
+    /// Send a request. If the connection was lost, the next request
+    /// reconnects; idempotent methods are retried once right away.
+    pub async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        let connection = self.connection().await?;
+        match self.send(&connection, method, params.clone()).await {
+            Err(e) if e.is_connection_lost() => {
+                self.drop_connection(&connection).await;
+                if !IDEMPOTENT_METHODS.contains(&method) {
+                    tracing::warn!(
+                        "MCP server '{}' connection lost during {} ({}), not retrying",
+                        self.name(),
+                        method,
+                        e
+                    );
+                    return Err(e);
+                }
+                tracing::warn!(
+                    "MCP server '{}' connection lost ({}), reconnecting",
+                    self.name(),
+                    e
+                );
+                tokio::time::sleep(RECONNECT_DELAY).await;
+                let connection = self.connection().await?;
+                self.send(&connection, method, params).await
+            }
+            result => result,
+        }
+    }
+
+    async fn send(&self, connection: &Connection, method: &str, params: Value) -> Result<Value> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let response = connection
+            .transport
+            .request(id, protocol::request(id, method, params), self.timeout())
+            .await?;
+        protocol::into_result(response)
+    }
+
+    /// Page through a `*/list` method, following `nextCursor`.
+    async fn list_all<T: DeserializeOwned>(&self, method: &str, key: &str) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let params = match cursor {
+                Some(ref c) => json!({ "cursor": c }),
+                None => json!({}),
+            };
+            let mut result = self.request(method, params).await?;
+            if let Some(page) = result.get_mut(key).map(Value::take) {
+                items.extend(serde_json::from_value::<Vec<T>>(page)?);
+            }
+            cursor = result
+                .get("nextCursor")
+                .and_then(|c| c.as_str())
+                .map(String::from);
+            if cursor.is_none() {
+                return Ok(items);
+            }
+        }
+    }
+
+    /// Current connection, opening a new one (with handshake) if there is none
+    /// or the old one died.
+    async fn connection(&self) -> Result<Arc<Connection>> {
+        let mut slot = self.connection.lock().await;
+        if let Some(ref connection) = *slot
+            && connection.transport.is_alive()
+        {
+            return Ok(connection.clone());
+        }
+
+        let transport = Transport::open(&self.config)?;
+        let info = self.initialize(&transport).await?;
+        tracing::info!(
+            "Connected to MCP server '{}' ({} {}, protocol {})",
+            self.name(),
+            info.server_info.as_ref().map(|s| s.name.as_str()).unwrap_or("unknown"),
+            info.server_info.as_ref().map(|s| s.version.as_str()).unwrap_or(""),
+            info.protocol_version
+        );
+        let connection = Arc::new(Connection { transport, info });
+        *slot = Some(connection.clone());
+        Ok(connection)
+    }
+
+    /// Forget a dead connection, unless another caller already replaced it.
+    async fn drop_connection(&self, dead: &Arc<Connection>) {
+        let mut slot = self.connection.lock().await;
+        if slot.as_ref().is_some_and(|c| Arc::ptr_eq(c, dead)) {
+            *slot = None;
+        }
+    }
+
+    async fn initialize(&self, transport: &Transport) -> Result<InitializeResult> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let params = json!({
+            "protocolVersion": protocol::PROTOCOL_VERSION,
+            "capabilities": {},
+            "clientInfo": {
+                "name": "opencrabs",
+                "version": env!("CARGO_PKG_VERSION")
+            }
+        });
+        let response = transport
+            .request(id, protocol::request(id, "initialize", params), self.timeout())
+            .await?;
+        let info: InitializeResult = serde_json::from_value(protocol::into_result(response)?)?;
+        transport
+            .notify(protocol::notification("notifications/initialized", json!({})))
+            .await?;
+        Ok(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+
+    fn http_config(url: String) -> McpServerConfig {
+        McpServerConfig {
+            name: "test".to_string(),
+            enabled: true,
+            command: None,
+            args: Vec::new(),
+            env: Default::default(),
+            url: Some(url),
+            headers: [("Authorization".to_string(), "Bearer t0k".to_string())].into(),
+            timeout_secs: 5,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_http_handshake_and_tool_call() {
+        let mut server = mockito::Server::new_async().await;
+        let init = server
+            .mock("POST", "/mcp")
+            .match_header("Authorization", "Bearer t0k")
+            .match_body(Matcher::PartialJson(json!({"method": "initialize"})))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_header("mcp-session-id", "sess-1")
+            .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2025-03-26","capabilities":{"tools":{}},"serverInfo":{"name":"mock","version":"1.0"}}}"#)
+            .create_async()
+            .await;
+        let initialized = server
+            .mock("POST", "/mcp")
+            .match_header("Mcp-Session-Id", "sess-1")
+            .match_body(Matcher::PartialJson(json!({"method": "notifications/initialized"})))
+            .with_status(202)
+            .create_async()
+            .await;
+        let list = server
+            .mock("POST", "/mcp")
+            .match_header("Mcp-Session-Id", "sess-1")
+            .match_body(Matcher::PartialJson(json!({"method": "tools/list"})))
+            .with_status(200)
+            .with_header("content-type", "text/event-stream")
+            .with_body("data: {\"jsonrpc\":\"2.0\",\"id\":3,\"result\":{\"tools\":[{\"name\":\"echo\",\"inputSchema\":{\"type\":\"object\"},\"annotations\":{\"readOnlyHint\":true}}]}}\n\n")
+            .create_async()
+            .await;
+        let call = server
+            .mock("POST", "/mcp")
+            .match_body(Matcher::PartialJson(json!({"method": "tools/call", "params": {"name": "echo"}})))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"jsonrpc":"2.0","id":4,"result":{"content":[{"type":"text","text":"hi"}]}}"#)
+            .create_async()
+            .await;
+
+        let client = McpClient::new(http_config(format!("{}/mcp", server.url())));
+        let inventory = client.discover().await.unwrap();
+        assert_eq!(inventory.tools.len(), 1);
+        assert_eq!(inventory.tools[0].name, "echo");
+        assert_eq!(inventory.tools[0].annotations.read_only_hint, Some(true));
+        assert!(inventory.resources.is_empty());
+
+        let result = client.call_tool("echo", json!({"text": "hi"})).await.unwrap();
+        assert_eq!(result["content"][0]["text"], "hi");
+
+        init.assert_async().await;
+        initialized.assert_async().await;
+        list.assert_async().await;
+        call.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_rpc_error_is_not_retried() {
+        let mut server = mockito::Server::new_async().await;
+        let init = server
+            .mock("POST", "/")
+            .match_body(Matcher::PartialJson(json!({"method": "initialize"})))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{"capabilities":{"tools":{}}}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/")
+            .match_body(Matcher::PartialJson(json!({"method": "notifications/initialized"})))
+            .with_status(202)
+            .create_async()
+            .await;
+        let call = server
+            .mock("POST", "/")
+            .match_body(Matcher::PartialJson(json!({"method": "tools/call"})))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"jsonrpc":"2.0","id":3,"error":{"code":-32602,"message":"Unknown tool"}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = McpClient::new(http_config(server.url()));
+        let err = client.call_tool("missing", json!({})).await.unwrap_err();
+        assert!(matches!(err, McpError::Rpc { code: -32602, .. }));
+        init.assert_async().await;
+        call.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_lost_tool_call_is_not_retried() {
+        let mut server = mockito::Server::new_async().await;
+        let init = server
+            .mock("POST", "/")
+            .match_body(Matcher::PartialJson(json!({"method": "initialize"})))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{"capabilities":{"tools":{}}}}"#)
+            .expect(2)
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/")
+            .match_body(Matcher::PartialJson(json!({"method": "notifications/initialized"})))
+            .with_status(202)
+            .create_async()
+            .await;
+        let call = server
+            .mock("POST", "/")
+            .match_body(Matcher::PartialJson(json!({"method": "tools/call"})))
+            .with_status(502)
+            .expect(2)
+            .create_async()
+            .await;
+
+        // The server may have run the tool before the connection broke
+        let client = McpClient::new(http_config(server.url()));
+        let err = client.call_tool("deploy", json!({})).await.unwrap_err();
+        assert!(matches!(err, McpError::Transport(_)));
+        // The next call starts a new connection
+        client.call_tool("deploy", json!({})).await.unwrap_err();
+        init.assert_async().await;
+        call.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_config_requires_one_transport() {
+        let mut config = http_config("http://localhost".to_string());
+        config.command = Some("server".to_string());
+        let client = McpClient::new(config);
+        assert!(matches!(
+            client.discover().await,
+            Err(McpError::Config(_))
+        ));
+    }
+}
@@ -0,0 +1,52 @@
+//! MCP client error types
+
+use thiserror::Error;
+
+/// MCP client error types
+#[derive(Debug, Error)]
+pub enum McpError {
+    /// Server entry in config.toml is unusable
+    #[error("Invalid MCP server config: {0}")]
+    Config(String),
+
+    /// Could not reach the server or read its reply
+    #[error("MCP transport error: {0}")]
+    Transport(String),
+
+    /// Server process exited or closed its stdout
+    #[error("MCP server closed the connection")]
+    Closed,
+
+    /// Streamable-HTTP server no longer knows our session
+    #[error("MCP session expired")]
+    SessionExpired,
+
+    /// JSON-RPC error returned by the server
+    #[error("MCP error {code}: {message}")]
+    Rpc { code: i64, message: String },
+
+    /// No reply within the configured timeout
+    #[error("MCP request timed out after {0}s")]
+    Timeout(u64),
+
+    /// I/O error
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// JSON error
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+impl McpError {
+    /// Whether the connection is gone and a fresh one should be tried
+    pub fn is_connection_lost(&self) -> bool {
+        matches!(
+            self,
+            Self::Closed | Self::SessionExpired | Self::Transport(_) | Self::Io(_)
+        )
+    }
+}
+
+/// Result type for MCP operations
+pub type Result<T> = std::result::Result<T, McpError>;
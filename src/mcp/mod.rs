@@ -0,0 +1,77 @@
+//! MCP (Model Context Protocol) support for OpenCrabs.
+//!
+//! As a client, connects to the servers listed under `[[mcp.servers]]` in config.toml:
+//! - stdio servers launched as child processes (`command` + `args`)
+//! - streamable-HTTP servers (`url` + optional `headers`)
+//!
+//! Each remote tool is registered in the [`ToolRegistry`] as
+//! `mcp__<server>__<tool>`; servers with resources also get a
+//! `mcp__<server>__read_resource` tool.
+//!
+//! As a server (`opencrabs mcp serve`), exposes OpenCrabs' own tools and brain
+//! files over stdio or streamable HTTP.
+
+pub mod client;
+pub mod error;
+pub mod protocol;
+pub mod server;
+pub mod tool;
+pub mod transport;
+
+pub use client::{McpClient, ServerInventory};
+pub use error::{McpError, Result};
+pub use server::McpServer;
+pub use tool::{McpResourceTool, McpTool};
+
+use crate::brain::tools::ToolRegistry;
+use crate::config::McpConfig;
+use std::sync::Arc;
+
+/// Connect to every enabled server and register its tools.
+///
+/// Servers are contacted concurrently. One that fails to start is logged and
+/// skipped so it can't block the rest. Returns the connected clients.
+pub async fn register_servers(
+    config: &McpConfig,
+    registry: &mut ToolRegistry,
+) -> Vec<Arc<McpClient>> {
+    let clients: Vec<Arc<McpClient>> = config
+        .servers
+        .iter()
+        .filter(|server| server.enabled)
+        .map(|server| Arc::new(McpClient::new(server.clone())))
+        .collect();
+
+    let inventories =
+        futures::future::join_all(clients.iter().map(|client| client.discover())).await;
+
+    let mut connected = Vec::new();
+    for (client, inventory) in clients.into_iter().zip(inventories) {
+        let inventory = match inventory {
+            Ok(inventory) => inventory,
+            Err(e) => {
+                tracing::warn!("MCP server '{}' unavailable: {}", client.name(), e);
+                continue;
+            }
+        };
+
+        if !inventory.resources.is_empty() {
+            registry.register(Arc::new(McpResourceTool::new(
+                client.clone(),
+                &inventory.resources,
+            )));
+        }
+        for info in inventory.tools.iter().cloned() {
+            registry.register(Arc::new(McpTool::new(client.clone(), info)));
+        }
+        tracing::info!(
+            "Registered MCP server '{}': {} tools, {} resources, {} prompts",
+            client.name(),
+            inventory.tools.len(),
+            inventory.resources.len(),
+            inventory.prompts.len()
+        );
+        connected.push(client);
+    }
+    connected
+}
@@ -0,0 +1,267 @@
+//! MCP wire format: JSON-RPC framing, SSE parsing, and the shapes returned by
+//! `initialize`, `tools/list`, `resources/list` and `prompts/list`.
+
+use super::error::{McpError, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+/// Protocol revision we speak (streamable-HTTP transport).
+pub const PROTOCOL_VERSION: &str = "2025-03-26";
+
+/// Build a JSON-RPC request.
+pub fn request(id: u64, method: &str, params: Value) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": method,
+        "params": params,
+    })
+}
+
+/// Build a JSON-RPC notification (no id, no reply).
+pub fn notification(method: &str, params: Value) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": method,
+        "params": params,
+    })
+}
+
+/// Build a JSON-RPC response to a server-initiated request.
+pub fn response(id: Value, result: std::result::Result<Value, (i64, &str)>) -> Value {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": code, "message": message },
+        }),
+    }
+}
+
+/// Whether a message is a reply to one of our requests.
+pub fn is_response(message: &Value) -> bool {
+    message.get("id").is_some()
+        && (message.get("result").is_some() || message.get("error").is_some())
+}
+
+/// Unwrap a JSON-RPC response into its `result`, turning `error` into [`McpError::Rpc`].
+pub fn into_result(mut message: Value) -> Result<Value> {
+    if let Some(error) = message.get("error") {
+        return Err(McpError::Rpc {
+            code: error.get("code").and_then(|c| c.as_i64()).unwrap_or(0),
+            message: error
+                .get("message")
+                .and_then(|m| m.as_str())
+                .unwrap_or("Unknown MCP error")
+                .to_string(),
+        });
+    }
+    message
+        .get_mut("result")
+        .map(Value::take)
+        .ok_or_else(|| McpError::Transport("response missing 'result' field".to_string()))
+}
+
+/// Parse an SSE body into the JSON messages carried by its `data:` fields.
+///
+/// Multi-line `data:` fields within one event are joined with newlines, as
+/// the SSE spec requires. Events whose data is not JSON are skipped.
+pub fn parse_sse_messages(body: &str) -> Vec<Value> {
+    let mut messages = Vec::new();
+    let mut data: Vec<&str> = Vec::new();
+
+    let mut flush = |data: &mut Vec<&str>| {
+        if !data.is_empty() {
+            if let Ok(parsed) = serde_json::from_str::<Value>(&data.join("\n")) {
+                messages.push(parsed);
+            }
+            data.clear();
+        }
+    };
+
+    for line in body.lines() {
+        if line.trim().is_empty() {
+            flush(&mut data);
+        } else if let Some(value) = line.strip_prefix("data:") {
+            data.push(value.strip_prefix(' ').unwrap_or(value));
+        }
+    }
+    flush(&mut data);
+
+    messages
+}
+
+/// Render MCP content blocks (`tools/call` content, `resources/read` contents) as text.
+pub fn render_content(items: &[Value]) -> String {
+    let parts: Vec<String> = items
+        .iter()
+        .filter_map(|item| {
+            if let Some(text) = item.get("text").and_then(|t| t.as_str()) {
+                return Some(text.to_string());
+            }
+            match item.get("type").and_then(|t| t.as_str()) {
+                Some(kind @ ("image" | "audio")) => Some(format!(
+                    "[{} content: {}]",
+                    kind,
+                    item.get("mimeType").and_then(|m| m.as_str()).unwrap_or("unknown type")
+                )),
+                Some("resource") => item
+                    .get("resource")
+                    .map(|r| render_content(std::slice::from_ref(r))),
+                Some("resource_link") => item
+                    .get("uri")
+                    .and_then(|u| u.as_str())
+                    .map(|uri| format!("[resource: {}]", uri)),
+                _ => item
+                    .get("uri")
+                    .and_then(|u| u.as_str())
+                    .map(|uri| format!("[binary resource: {}]", uri)),
+            }
+        })
+        .collect();
+    parts.join("\n")
+}
+
+/// What the server told us during `initialize`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitializeResult {
+    #[serde(default)]
+    pub protocol_version: String,
+    #[serde(default)]
+    pub capabilities: Value,
+    #[serde(default)]
+    pub server_info: Option<Implementation>,
+    #[serde(default)]
+    pub instructions: Option<String>,
+}
+
+impl InitializeResult {
+    /// Whether the server advertised a capability (`tools`, `resources`, `prompts`).
+    pub fn supports(&self, capability: &str) -> bool {
+        self.capabilities.get(capability).is_some_and(|c| !c.is_null())
+    }
+}
+
+/// Name and version of an MCP client or server.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Implementation {
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+}
+
+/// A tool exposed by a server (`tools/list`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpToolInfo {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub input_schema: Value,
+    #[serde(default)]
+    pub annotations: ToolAnnotations,
+}
+
+/// Behaviour hints attached to a tool. Absent hints take the spec defaults:
+/// not read-only, destructive, and open-world.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolAnnotations {
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub read_only_hint: Option<bool>,
+    #[serde(default)]
+    pub destructive_hint: Option<bool>,
+    #[serde(default)]
+    pub idempotent_hint: Option<bool>,
+    #[serde(default)]
+    pub open_world_hint: Option<bool>,
+}
+
+/// A resource exposed by a server (`resources/list`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpResource {
+    pub uri: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub mime_type: Option<String>,
+}
+
+/// A prompt template exposed by a server (`prompts/list`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct McpPrompt {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub arguments: Vec<Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sse_messages() {
+        let body = "event: message\ndata: {\"jsonrpc\":\"2.0\",\"method\":\"notifications/progress\"}\n\n\
+                    data: {\"jsonrpc\":\"2.0\",\n\
+                    data: \"id\":7,\"result\":{}}\n\n\
+                    : keep-alive\n\
+                    data: not json\n\n";
+        let messages = parse_sse_messages(body);
+        assert_eq!(messages.len(), 2);
+        assert!(!is_response(&messages[0]));
+        assert!(is_response(&messages[1]));
+        assert_eq!(messages[1]["id"], 7);
+    }
+
+    #[test]
+    fn test_into_result() {
+        let ok = json!({"jsonrpc": "2.0", "id": 1, "result": {"tools": []}});
+        assert_eq!(into_result(ok).unwrap(), json!({"tools": []}));
+
+        let err = json!({"jsonrpc": "2.0", "id": 1, "error": {"code": -32601, "message": "nope"}});
+        match into_result(err) {
+            Err(McpError::Rpc { code, message }) => {
+                assert_eq!(code, -32601);
+                assert_eq!(message, "nope");
+            }
+            other => panic!("expected Rpc error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_render_content() {
+        let items = vec![
+            json!({"type": "text", "text": "hello"}),
+            json!({"type": "image", "data": "...", "mimeType": "image/png"}),
+            json!({"type": "resource", "resource": {"uri": "file:///a", "text": "inner"}}),
+            json!({"uri": "file:///b.bin", "blob": "AAAA"}),
+        ];
+        assert_eq!(
+            render_content(&items),
+            "hello\n[image content: image/png]\ninner\n[binary resource: file:///b.bin]"
+        );
+    }
+
+    #[test]
+    fn test_tool_info_deserializes_annotations() {
+        let info: McpToolInfo = serde_json::from_value(json!({
+            "name": "list_issues",
+            "inputSchema": {"type": "object"},
+            "annotations": {"readOnlyHint": true, "openWorldHint": false}
+        }))
+        .unwrap();
+        assert_eq!(info.annotations.read_only_hint, Some(true));
+        assert_eq!(info.annotations.open_world_hint, Some(false));
+        assert_eq!(info.annotations.destructive_hint, None);
+    }
+}
@@ -0,0 +1,609 @@
+//! MCP server — exposes OpenCrabs to other MCP clients (`opencrabs mcp serve`).
+//!
+//! Serves:
+//! - every registered tool (file editing, bash, plan, `memory_search`,
+//!   `session_search`, ...) as an MCP tool, annotated from its capabilities
+//! - the brain files (SOUL.md, MEMORY.md, ...) as `opencrabs://brain/<file>` resources
+//!
+//! `[permissions]` rules gate every call: deny rules block it, and `ask` rules
+//! block it too since there is nobody on this side to ask. Tools that would
+//! need approval in the TUI (bash, file writes, ...) only run when an allow
+//! rule matches; read-only tools run freely. Calls matching a `sandbox` rule
+//! run inside the `[sandbox]`.
+
+use super::protocol::{self, Implementation};
+use crate::brain::BrainLoader;
+use crate::brain::prompt_builder::BRAIN_FILES;
+use crate::brain::tools::sandbox::Sandbox;
+use crate::brain::tools::{
+    Permission, PermissionRules, Tool, ToolExecutionContext, ToolRegistry,
+};
+use axum::{
+    Json, Router,
+    extract::State,
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::post,
+};
+use serde_json::{Value, json};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+/// URI prefix for brain file resources.
+pub const BRAIN_URI_PREFIX: &str = "opencrabs://brain/";
+
+/// Protocol revisions we can answer `initialize` with, newest first.
+const SUPPORTED_VERSIONS: &[&str] = &["2025-06-18", protocol::PROTOCOL_VERSION, "2024-11-05"];
+
+/// Tools that only make sense inside an OpenCrabs conversation.
+const HIDDEN_TOOLS: &[&str] = &["slash_command"];
+
+/// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const RESOURCE_NOT_FOUND: i64 = -32002;
+
+/// Handles MCP requests against a tool registry and the brain workspace.
+pub struct McpServer {
+    registry: Arc<ToolRegistry>,
+    rules: PermissionRules,
+    brain: BrainLoader,
+    context: ToolExecutionContext,
+    sandbox: Option<Arc<Sandbox>>,
+}
+
+impl McpServer {
+    pub fn new(
+        registry: Arc<ToolRegistry>,
+        rules: PermissionRules,
+        brain_path: PathBuf,
+        working_directory: PathBuf,
+    ) -> Self {
+        // Calls are gated by `rules` before they reach the registry (approval
+        // tools need an allow rule), so the registry's own check is satisfied here.
+        let context = ToolExecutionContext::new(Uuid::new_v4())
+            .with_working_directory(working_directory)
+            .with_auto_approve(true);
+        Self {
+            registry,
+            rules,
+            brain: BrainLoader::new(brain_path),
+            context,
+            sandbox: None,
+        }
+    }
+
+    /// Run calls that match a `[permissions] sandbox` rule in this sandbox
+    pub fn with_sandbox(mut self, sandbox: Sandbox) -> Self {
+        self.sandbox = Some(Arc::new(sandbox));
+        self
+    }
+
+    /// Handle one JSON-RPC message. Returns the reply, or `None` for notifications.
+    pub async fn handle(&self, message: Value) -> Option<Value> {
+        let Some(method) = message.get("method").and_then(|m| m.as_str()) else {
+            // Replies to requests we never send, or garbage
+            return message
+                .get("id")
+                .filter(|_| !protocol::is_response(&message))
+                .map(|id| {
+                    protocol::response(id.clone(), Err((INVALID_REQUEST, "Invalid Request")))
+                });
+        };
+        let id = message.get("id").cloned()?;
+        let params = message.get("params").cloned().unwrap_or_else(|| json!({}));
+
+        let result = match method {
+            "initialize" => Ok(self.initialize(&params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(self.list_tools()),
+            "tools/call" => self.call_tool(&params).await,
+            "resources/list" => Ok(self.list_resources()),
+            "resources/read" => self.read_resource(&params),
+            _ => Err((METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+        };
+        Some(match result {
+            Ok(result) => protocol::response(id, Ok(result)),
+            Err((code, message)) => protocol::response(id, Err((code, message.as_str()))),
+        })
+    }
+
+    fn initialize(&self, params: &Value) -> Value {
+        let requested = params
+            .get("protocolVersion")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+        let version = SUPPORTED_VERSIONS
+            .iter()
+            .find(|v| **v == requested)
+            .copied()
+            .unwrap_or(SUPPORTED_VERSIONS[0]);
+        json!({
+            "protocolVersion": version,
+            "capabilities": {
+                "tools": { "listChanged": false },
+                "resources": { "listChanged": false },
+            },
+            "serverInfo": Implementation {
+                name: "opencrabs".to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+            "instructions": "OpenCrabs tools for file editing, shell, plans, memory and \
+                             session search. Brain files are available as resources.",
+        })
+    }
+
+    fn visible_tools(&self) -> Vec<Arc<dyn Tool>> {
+        let mut names: Vec<String> = self
+            .registry
+            .list_tools()
+            .into_iter()
+            .filter(|name| !HIDDEN_TOOLS.contains(&name.as_str()))
+            .collect();
+        names.sort();
+        names
+            .iter()
+            .filter_map(|name| self.registry.get(name))
+            .collect()
+    }
+
+    fn list_tools(&self) -> Value {
+        let tools: Vec<Value> = self
+            .visible_tools()
+            .iter()
+            .map(|tool| {
+                json!({
+                    "name": tool.name(),
+                    "description": tool.description(),
+                    "inputSchema": tool.input_schema(),
+                    "annotations": annotations(tool.as_ref()),
+                })
+            })
+            .collect();
+        json!({ "tools": tools })
+    }
+
+    async fn call_tool(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let name = params
+            .get("name")
+            .and_then(|n| n.as_str())
+            .ok_or_else(|| (INVALID_PARAMS, "Missing tool name".to_string()))?;
+        let tool = self
+            .registry
+            .get(name)
+            .filter(|_| !HIDDEN_TOOLS.contains(&name))
+            .ok_or_else(|| (INVALID_PARAMS, format!("Unknown tool: {}", name)))?;
+        let arguments = params
+            .get("arguments")
+            .cloned()
+            .unwrap_or_else(|| json!({}));
+
+        match self
+            .rules
+            .evaluate(name, &arguments, &self.context.working_directory)
+        {
+            Permission::Deny(rule) => {
+                return Ok(tool_result(
+                    format!(
+                        "Blocked by the permission rule `{}` ([permissions] deny in config.toml).",
+                        rule
+                    ),
+                    true,
+                ));
+            }
+            Permission::Ask => {
+                return Ok(tool_result(
+                    format!(
+                        "`{}` matches a [permissions] ask rule and needs interactive approval, \
+                         which is not available over MCP. Add an allow rule to run it here.",
+                        name
+                    ),
+                    true,
+                ));
+            }
+            Permission::Default if tool.requires_approval_for(&arguments) => {
+                return Ok(tool_result(
+                    format!(
+                        "`{}` needs approval, which is not available over MCP. \
+                         Add a [permissions] allow rule to run it here.",
+                        name
+                    ),
+                    true,
+                ));
+            }
+            Permission::Allow | Permission::Default => {}
+        }
+
+        let sandboxed;
+        let context = match self.sandbox {
+            Some(ref sandbox)
+                if self
+                    .rules
+                    .sandboxes(name, &arguments, &self.context.working_directory) =>
+            {
+                sandboxed = ToolExecutionContext {
+                    sandbox: Some(Arc::clone(sandbox)),
+                    ..self.context.clone()
+                };
+                &sandboxed
+            }
+            _ => &self.context,
+        };
+
+        tracing::info!("MCP serve: executing tool '{}'", name);
+        Ok(
+            match self.registry.execute(name, arguments, context).await {
+                Ok(result) if result.success => tool_result(result.output, false),
+                Ok(result) => tool_result(
+                    result.error.unwrap_or_else(|| "Tool failed".to_string()),
+                    true,
+                ),
+                Err(e) => tool_result(e.to_string(), true),
+            },
+        )
+    }
+
+    fn list_resources(&self) -> Value {
+        let resources: Vec<Value> = BRAIN_FILES
+            .iter()
+            .filter(|(file, _)| {
+                self.brain
+                    .load_file(file)
+                    .is_some_and(|content| !content.trim().is_empty())
+            })
+            .map(|(file, label)| {
+                json!({
+                    "uri": format!("{}{}", BRAIN_URI_PREFIX, file),
+                    "name": file,
+                    "description": format!("OpenCrabs brain file ({})", label),
+                    "mimeType": "text/markdown",
+                })
+            })
+            .collect();
+        json!({ "resources": resources })
+    }
+
+    fn read_resource(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let uri = params
+            .get("uri")
+            .and_then(|u| u.as_str())
+            .ok_or_else(|| (INVALID_PARAMS, "Missing resource uri".to_string()))?;
+        // Only the known brain files — never an arbitrary path under the brain dir
+        let content = uri
+            .strip_prefix(BRAIN_URI_PREFIX)
+            .filter(|file| BRAIN_FILES.iter().any(|(name, _)| name == file))
+            .and_then(|file| self.brain.load_file(file))
+            .ok_or_else(|| (RESOURCE_NOT_FOUND, format!("Resource not found: {}", uri)))?;
+        Ok(json!({
+            "contents": [{
+                "uri": uri,
+                "mimeType": "text/markdown",
+                "text": content,
+            }]
+        }))
+    }
+}
+
+/// MCP annotations derived from a tool's capabilities.
+fn annotations(tool: &dyn Tool) -> Value {
+    use crate::brain::tools::ToolCapability;
+
+    let capabilities = tool.capabilities();
+    json!({
+        "readOnlyHint": tool.is_read_only(),
+        "destructiveHint": tool.requires_approval(),
+        "openWorldHint": capabilities.contains(&ToolCapability::Network),
+    })
+}
+
+fn tool_result(text: String, is_error: bool) -> Value {
+    json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": is_error,
+    })
+}
+
+fn parse_error(error: &serde_json::Error) -> Value {
+    let message = format!("Parse error: {}", error);
+    protocol::response(Value::Null, Err((PARSE_ERROR, message.as_str())))
+}
+
+/// Serve newline-delimited JSON-RPC on stdin/stdout until stdin closes.
+///
+/// Requests are handled concurrently; replies are written as they finish.
+pub async fn serve_stdio(server: Arc<McpServer>) -> anyhow::Result<()> {
+    let (tx, mut rx) = mpsc::unbounded_channel::<Value>();
+
+    let writer = tokio::spawn(async move {
+        let mut stdout = tokio::io::stdout();
+        while let Some(message) = rx.recv().await {
+            let mut line = message.to_string();
+            line.push('\n');
+            if stdout.write_all(line.as_bytes()).await.is_err() || stdout.flush().await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let message = match serde_json::from_str::<Value>(&line) {
+            Ok(message) => message,
+            Err(e) => {
+                let _ = tx.send(parse_error(&e));
+                continue;
+            }
+        };
+        let server = server.clone();
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            if let Some(reply) = server.handle(message).await {
+                let _ = tx.send(reply);
+            }
+        });
+    }
+
+    // Let in-flight calls finish writing their replies
+    drop(tx);
+    let _ = writer.await;
+    Ok(())
+}
+
+/// Shared state for the streamable-HTTP endpoint.
+#[derive(Clone)]
+struct HttpState {
+    server: Arc<McpServer>,
+    token: String,
+}
+
+/// Build the axum router for the streamable-HTTP transport (`POST /mcp`).
+pub fn build_router(server: Arc<McpServer>, token: String) -> Router {
+    Router::new()
+        .route("/mcp", post(handle_post))
+        .with_state(HttpState { server, token })
+}
+
+/// Serve the streamable-HTTP transport. Every request must carry `token`.
+pub async fn serve_http(
+    server: Arc<McpServer>,
+    bind: &str,
+    port: u16,
+    token: String,
+) -> anyhow::Result<()> {
+    let addr: SocketAddr = format!("{}:{}", bind, port)
+        .parse()
+        .map_err(|e| anyhow::anyhow!("Invalid MCP server address: {}", e))?;
+    if token.is_empty() {
+        anyhow::bail!("MCP server over HTTP needs a non-empty --token");
+    }
+
+    tracing::info!("MCP server listening on http://{}/mcp", addr);
+    eprintln!("🦀 OpenCrabs MCP server listening on http://{}/mcp", addr);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, build_router(server, token)).await?;
+    Ok(())
+}
+
+/// POST /mcp — one JSON-RPC message in, one JSON reply (or 202 for notifications) out.
+async fn handle_post(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+) -> Response {
+    if !origin_allowed(&headers) {
+        return (StatusCode::FORBIDDEN, "Origin not allowed").into_response();
+    }
+    let authorized = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .is_some_and(|token| token == state.token);
+    if !authorized {
+        return (StatusCode::UNAUTHORIZED, "Invalid or missing Bearer token").into_response();
+    }
+
+    let message = match serde_json::from_slice::<Value>(&body) {
+        Ok(message) => message,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(parse_error(&e))).into_response(),
+    };
+    match state.server.handle(message).await {
+        Some(reply) => Json(reply).into_response(),
+        None => StatusCode::ACCEPTED.into_response(),
+    }
+}
+
+/// Browsers send `Origin`; only accept local pages so a website can't drive
+/// the server through DNS rebinding. Non-browser clients send no `Origin`.
+fn origin_allowed(headers: &HeaderMap) -> bool {
+    let Some(origin) = headers.get(header::ORIGIN) else {
+        return true;
+    };
+    let Ok(origin) = origin.to_str() else {
+        return false;
+    };
+    let host = origin
+        .split_once("://")
+        .map(|(_, rest)| rest)
+        .unwrap_or(origin);
+    let host = if host.starts_with('[') {
+        host.split_once(']').map(|(h, _)| &h[1..]).unwrap_or(host)
+    } else {
+        host.split([':', '/']).next().unwrap_or(host)
+    };
+    matches!(host, "localhost" | "127.0.0.1" | "::1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brain::tools::read::ReadTool;
+    use crate::brain::tools::slash_command::SlashCommandTool;
+    use crate::brain::tools::write::WriteTool;
+    use crate::config::PermissionsConfig;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tempfile::TempDir;
+    use tower::ServiceExt;
+
+    fn test_server(dir: &TempDir, allow: &[&str], deny: &[&str]) -> Arc<McpServer> {
+        let mut registry = ToolRegistry::new();
+        registry.register(Arc::new(ReadTool));
+        registry.register(Arc::new(WriteTool));
+        registry.register(Arc::new(SlashCommandTool));
+        let rules = PermissionRules::from_config(&PermissionsConfig {
+            allow: allow.iter().map(|r| r.to_string()).collect(),
+            deny: deny.iter().map(|r| r.to_string()).collect(),
+            ..Default::default()
+        });
+        Arc::new(McpServer::new(
+            Arc::new(registry),
+            rules,
+            dir.path().to_path_buf(),
+            dir.path().to_path_buf(),
+        ))
+    }
+
+    async fn call(server: &McpServer, method: &str, params: Value) -> Value {
+        server
+            .handle(protocol::request(1, method, params))
+            .await
+            .expect("reply")
+    }
+
+    #[tokio::test]
+    async fn test_initialize_and_list_tools() {
+        let dir = TempDir::new().unwrap();
+        let server = test_server(&dir, &[], &[]);
+
+        let init = call(&server, "initialize", json!({"protocolVersion": "2024-11-05"})).await;
+        assert_eq!(init["result"]["protocolVersion"], "2024-11-05");
+        assert_eq!(init["result"]["serverInfo"]["name"], "opencrabs");
+
+        let notification = protocol::notification("notifications/initialized", json!({}));
+        assert!(server.handle(notification).await.is_none());
+
+        let list = call(&server, "tools/list", json!({})).await;
+        let tools = list["result"]["tools"].as_array().unwrap();
+        let names: Vec<&str> = tools.iter().filter_map(|t| t["name"].as_str()).collect();
+        assert_eq!(names, vec!["read_file", "write_file"]);
+        assert_eq!(tools[0]["annotations"]["readOnlyHint"], true);
+        assert_eq!(tools[1]["annotations"]["destructiveHint"], true);
+    }
+
+    #[tokio::test]
+    async fn test_call_tool_respects_deny_rules() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "hello crab").unwrap();
+        std::fs::create_dir(dir.path().join("secret")).unwrap();
+        std::fs::write(dir.path().join("secret/key.txt"), "hunter2").unwrap();
+        let server = test_server(&dir, &[], &["read_file(secret/**)"]);
+
+        let ok = call(
+            &server,
+            "tools/call",
+            json!({"name": "read_file", "arguments": {"path": "notes.txt"}}),
+        )
+        .await;
+        assert_eq!(ok["result"]["isError"], false);
+        assert!(ok["result"]["content"][0]["text"].as_str().unwrap().contains("hello crab"));
+
+        let denied = call(
+            &server,
+            "tools/call",
+            json!({"name": "read_file", "arguments": {"path": "secret/key.txt"}}),
+        )
+        .await;
+        assert_eq!(denied["result"]["isError"], true);
+        let text = denied["result"]["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("read_file(secret/**)"));
+        assert!(!text.contains("hunter2"));
+
+        let hidden = call(&server, "tools/call", json!({"name": "slash_command"})).await;
+        assert_eq!(hidden["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_approval_tools_need_allow_rule() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("notes")).unwrap();
+        let write = |path: &str| {
+            json!({"name": "write_file", "arguments": {"path": path, "content": "crab"}})
+        };
+
+        let server = test_server(&dir, &[], &[]);
+        let refused = call(&server, "tools/call", write("out.txt")).await;
+        assert_eq!(refused["result"]["isError"], true);
+        assert!(!dir.path().join("out.txt").exists());
+
+        let server = test_server(&dir, &["write_file(notes/**)"], &[]);
+        let allowed = call(&server, "tools/call", write("notes/out.txt")).await;
+        assert_eq!(allowed["result"]["isError"], false);
+        assert!(dir.path().join("notes/out.txt").exists());
+
+        let outside = call(&server, "tools/call", write("out.txt")).await;
+        assert_eq!(outside["result"]["isError"], true);
+        assert!(!dir.path().join("out.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_brain_resources() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("SOUL.md"), "I am a helpful crab.").unwrap();
+        std::fs::write(dir.path().join("notes.md"), "not a brain file").unwrap();
+        let server = test_server(&dir, &[], &[]);
+
+        let list = call(&server, "resources/list", json!({})).await;
+        let resources = list["result"]["resources"].as_array().unwrap();
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0]["uri"], "opencrabs://brain/SOUL.md");
+
+        let read = call(&server, "resources/read", json!({"uri": "opencrabs://brain/SOUL.md"})).await;
+        assert_eq!(read["result"]["contents"][0]["text"], "I am a helpful crab.");
+
+        let other = call(&server, "resources/read", json!({"uri": "opencrabs://brain/notes.md"})).await;
+        assert_eq!(other["error"]["code"], RESOURCE_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_http_auth_and_origin() {
+        let dir = TempDir::new().unwrap();
+        let app = build_router(test_server(&dir, &[], &[]), "s3cret".to_string());
+        let ping = protocol::request(1, "ping", json!({})).to_string();
+        let request = |auth: Option<&str>, origin: Option<&str>| {
+            let mut builder = Request::post("/mcp").header("content-type", "application/json");
+            if let Some(auth) = auth {
+                builder = builder.header("authorization", auth);
+            }
+            if let Some(origin) = origin {
+                builder = builder.header("origin", origin);
+            }
+            builder.body(Body::from(ping.clone())).unwrap()
+        };
+
+        let resp = app.clone().oneshot(request(None, None)).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let resp = app
+            .clone()
+            .oneshot(request(Some("Bearer s3cret"), Some("https://evil.example")))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        let resp = app
+            .oneshot(request(Some("Bearer s3cret"), Some("http://localhost:5173")))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,347 @@
+//! MCP tools exposed through the [`Tool`] trait.
+//!
+//! Remote tools are registered as `mcp__<server>__<tool>`. Their capabilities
+//! come from the MCP tool annotations so the normal approval flow and
+//! permission rules apply to them like to any built-in tool.
+
+use super::client::McpClient;
+use super::error::McpError;
+use super::protocol::{self, McpResource, McpToolInfo, ToolAnnotations};
+use crate::brain::tools::error::{Result, ToolError};
+use crate::brain::tools::{Tool, ToolCapability, ToolExecutionContext, ToolResult};
+use async_trait::async_trait;
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+/// Longest tool name every provider accepts.
+const MAX_TOOL_NAME_LEN: usize = 64;
+
+/// Resources listed in the `read_resource` tool description before truncating.
+const MAX_LISTED_RESOURCES: usize = 25;
+
+/// Hex digits of the hash that keeps truncated tool names apart.
+const NAME_HASH_LEN: usize = 8;
+
+/// Namespaced registry name for a remote tool: `mcp__<server>__<tool>`.
+///
+/// Characters providers reject in tool names are replaced with `_`. Names
+/// longer than `MAX_TOOL_NAME_LEN` are cut short and end in `_<hash>` of the
+/// full name, so two long names sharing a prefix don't collide.
+pub fn tool_name(server: &str, tool: &str) -> String {
+    let name = format!("mcp__{}__{}", sanitize(server), sanitize(tool));
+    if name.len() <= MAX_TOOL_NAME_LEN {
+        return name;
+    }
+    let hash: String = openssl::sha::sha256(name.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    format!(
+        "{}_{}",
+        &name[..MAX_TOOL_NAME_LEN - NAME_HASH_LEN - 1],
+        &hash[..NAME_HASH_LEN]
+    )
+}
+
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Map MCP annotations onto tool capabilities.
+///
+/// Unannotated tools take the spec defaults (may write, destructive,
+/// open-world), so they require approval.
+pub fn capabilities_for(annotations: &ToolAnnotations) -> Vec<ToolCapability> {
+    let mut capabilities = if annotations.read_only_hint == Some(true) {
+        vec![ToolCapability::ReadFiles]
+    } else if annotations.destructive_hint == Some(false) {
+        vec![ToolCapability::WriteFiles]
+    } else {
+        vec![ToolCapability::WriteFiles, ToolCapability::SystemModification]
+    };
+    if annotations.open_world_hint != Some(false) {
+        capabilities.push(ToolCapability::Network);
+    }
+    capabilities
+}
+
+fn map_error(error: McpError) -> ToolError {
+    match error {
+        McpError::Timeout(secs) => ToolError::Timeout(secs),
+        other => ToolError::Execution(other.to_string()),
+    }
+}
+
+/// A tool provided by an MCP server.
+pub struct McpTool {
+    client: Arc<McpClient>,
+    name: String,
+    remote_name: String,
+    description: String,
+    input_schema: Value,
+    annotations: ToolAnnotations,
+}
+
+impl McpTool {
+    pub fn new(client: Arc<McpClient>, info: McpToolInfo) -> Self {
+        let summary = info
+            .description
+            .clone()
+            .or_else(|| info.annotations.title.clone())
+            .unwrap_or_else(|| info.name.clone());
+        let input_schema = if info.input_schema.is_object() {
+            info.input_schema
+        } else {
+            json!({ "type": "object", "properties": {} })
+        };
+        Self {
+            name: tool_name(client.name(), &info.name),
+            description: format!("[MCP server '{}'] {}", client.name(), summary),
+            client,
+            remote_name: info.name,
+            input_schema,
+            annotations: info.annotations,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for McpTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn input_schema(&self) -> Value {
+        self.input_schema.clone()
+    }
+
+    fn capabilities(&self) -> Vec<ToolCapability> {
+        capabilities_for(&self.annotations)
+    }
+
+    fn is_read_only(&self) -> bool {
+        self.annotations.read_only_hint == Some(true)
+    }
+
+    async fn execute(&self, input: Value, _context: &ToolExecutionContext) -> Result<ToolResult> {
+        let arguments = if input.is_null() { json!({}) } else { input };
+        let result = match self.client.call_tool(&self.remote_name, arguments).await {
+            Ok(result) => result,
+            Err(McpError::Rpc { message, .. }) => {
+                return Ok(ToolResult::error(format!("MCP error: {}", message)));
+            }
+            Err(e) => return Err(map_error(e)),
+        };
+
+        let content = result
+            .get("content")
+            .and_then(|c| c.as_array())
+            .map(|items| protocol::render_content(items.as_slice()))
+            .unwrap_or_default();
+        let text = match result.get("structuredContent") {
+            Some(structured) if content.is_empty() => {
+                serde_json::to_string_pretty(structured).unwrap_or_default()
+            }
+            _ => content,
+        };
+
+        if result.get("isError") == Some(&Value::Bool(true)) {
+            Ok(ToolResult::error(text))
+        } else {
+            Ok(ToolResult::success(text))
+        }
+    }
+}
+
+/// Reads resources from an MCP server that advertised some.
+pub struct McpResourceTool {
+    client: Arc<McpClient>,
+    name: String,
+    description: String,
+}
+
+impl McpResourceTool {
+    pub fn new(client: Arc<McpClient>, resources: &[McpResource]) -> Self {
+        let mut description = format!(
+            "Read a resource from MCP server '{}' by URI. Available resources:",
+            client.name()
+        );
+        for resource in resources.iter().take(MAX_LISTED_RESOURCES) {
+            description.push_str(&format!("\n- {}", resource.uri));
+            if !resource.name.is_empty() {
+                description.push_str(&format!(" ({})", resource.name));
+            }
+            if let Some(ref desc) = resource.description {
+                description.push_str(&format!(": {}", desc));
+            }
+        }
+        if resources.len() > MAX_LISTED_RESOURCES {
+            description.push_str(&format!(
+                "\n- ... and {} more",
+                resources.len() - MAX_LISTED_RESOURCES
+            ));
+        }
+        Self {
+            name: tool_name(client.name(), "read_resource"),
+            client,
+            description,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for McpResourceTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "uri": {
+                    "type": "string",
+                    "description": "Resource URI"
+                }
+            },
+            "required": ["uri"]
+        })
+    }
+
+    fn capabilities(&self) -> Vec<ToolCapability> {
+        vec![ToolCapability::ReadFiles]
+    }
+
+    fn validate_input(&self, input: &Value) -> Result<()> {
+        match input.get("uri").and_then(|u| u.as_str()) {
+            Some(uri) if !uri.is_empty() => Ok(()),
+            _ => Err(ToolError::InvalidInput("'uri' is required".to_string())),
+        }
+    }
+
+    async fn execute(&self, input: Value, _context: &ToolExecutionContext) -> Result<ToolResult> {
+        let uri = input.get("uri").and_then(|u| u.as_str()).unwrap_or_default();
+        match self.client.read_resource(uri).await {
+            Ok(result) => {
+                let contents = result
+                    .get("contents")
+                    .and_then(|c| c.as_array())
+                    .map(|items| protocol::render_content(items.as_slice()))
+                    .unwrap_or_default();
+                Ok(ToolResult::success(contents))
+            }
+            Err(McpError::Rpc { message, .. }) => {
+                Ok(ToolResult::error(format!("MCP error: {}", message)))
+            }
+            Err(e) => Err(map_error(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn annotations(
+        read_only: Option<bool>,
+        destructive: Option<bool>,
+        open_world: Option<bool>,
+    ) -> ToolAnnotations {
+        ToolAnnotations {
+            read_only_hint: read_only,
+            destructive_hint: destructive,
+            open_world_hint: open_world,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_tool_name_namespacing() {
+        assert_eq!(tool_name("github", "list_issues"), "mcp__github__list_issues");
+        assert_eq!(tool_name("my server", "fs.read"), "mcp__my_server__fs_read");
+        assert_eq!(tool_name("s", &"x".repeat(100)).len(), MAX_TOOL_NAME_LEN);
+
+        // Long names that only differ past the cut stay distinct and stable
+        let long = "list_repository_pull_request_review_comments";
+        let (user, team) = (format!("{}_for_user", long), format!("{}_for_team", long));
+        let a = tool_name("github_enterprise", &user);
+        assert_eq!(a.len(), MAX_TOOL_NAME_LEN);
+        assert_ne!(a, tool_name("github_enterprise", &team));
+        assert_eq!(a, tool_name("github_enterprise", &user));
+    }
+
+    #[test]
+    fn test_capabilities_from_annotations() {
+        // Unannotated: spec defaults — destructive and open-world
+        assert_eq!(
+            capabilities_for(&ToolAnnotations::default()),
+            vec![
+                ToolCapability::WriteFiles,
+                ToolCapability::SystemModification,
+                ToolCapability::Network
+            ]
+        );
+        assert_eq!(
+            capabilities_for(&annotations(Some(true), None, Some(false))),
+            vec![ToolCapability::ReadFiles]
+        );
+        assert_eq!(
+            capabilities_for(&annotations(Some(true), None, None)),
+            vec![ToolCapability::ReadFiles, ToolCapability::Network]
+        );
+        assert_eq!(
+            capabilities_for(&annotations(None, Some(false), Some(false))),
+            vec![ToolCapability::WriteFiles]
+        );
+    }
+
+    #[test]
+    fn test_approval_follows_annotations() {
+        let client = Arc::new(McpClient::new(crate::config::McpServerConfig {
+            name: "srv".to_string(),
+            enabled: true,
+            command: Some("true".to_string()),
+            args: Vec::new(),
+            env: Default::default(),
+            url: None,
+            headers: Default::default(),
+            timeout_secs: 5,
+        }));
+        let tool = |annotations: Value| {
+            McpTool::new(
+                client.clone(),
+                serde_json::from_value(json!({
+                    "name": "t",
+                    "annotations": annotations
+                }))
+                .unwrap(),
+            )
+        };
+
+        let read_only = tool(json!({"readOnlyHint": true}));
+        assert_eq!(read_only.name(), "mcp__srv__t");
+        assert!(!read_only.requires_approval());
+        assert!(read_only.is_read_only());
+        assert_eq!(read_only.input_schema()["type"], "object");
+
+        let unannotated = tool(json!({}));
+        assert!(unannotated.requires_approval());
+        assert!(!unannotated.is_read_only());
+    }
+}
@@ -0,0 +1,323 @@
+//! MCP transports: newline-delimited JSON over a child process's stdio, and
+//! streamable HTTP (JSON or SSE replies, `Mcp-Session-Id` header).
+
+use super::error::{McpError, Result};
+use super::protocol;
+use crate::config::McpServerConfig;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, Command};
+use tokio::sync::oneshot;
+
+/// A live connection to one MCP server.
+pub enum Transport {
+    Stdio(StdioTransport),
+    Http(HttpTransport),
+}
+
+impl Transport {
+    /// Open the transport described by a `[[mcp.servers]]` entry.
+    pub fn open(config: &McpServerConfig) -> Result<Self> {
+        match (&config.command, &config.url) {
+            (Some(command), None) => Ok(Self::Stdio(StdioTransport::spawn(config, command)?)),
+            (None, Some(url)) => Ok(Self::Http(HttpTransport::new(config, url)?)),
+            (Some(_), Some(_)) => Err(McpError::Config(format!(
+                "server '{}' sets both `command` and `url`",
+                config.name
+            ))),
+            (None, None) => Err(McpError::Config(format!(
+                "server '{}' needs either `command` (stdio) or `url` (HTTP)",
+                config.name
+            ))),
+        }
+    }
+
+    /// Send a request and wait for the matching response message.
+    pub async fn request(&self, id: u64, message: Value, timeout: Duration) -> Result<Value> {
+        match self {
+            Self::Stdio(t) => t.request(id, message, timeout).await,
+            Self::Http(t) => t.request(id, message, timeout).await,
+        }
+    }
+
+    /// Send a notification.
+    pub async fn notify(&self, message: Value) -> Result<()> {
+        match self {
+            Self::Stdio(t) => t.send(&message).await,
+            Self::Http(t) => t.notify(message).await,
+        }
+    }
+
+    /// Whether the connection can still carry requests.
+    pub fn is_alive(&self) -> bool {
+        match self {
+            Self::Stdio(t) => t.alive.load(Ordering::SeqCst),
+            Self::Http(_) => true,
+        }
+    }
+}
+
+type PendingMap = Arc<Mutex<HashMap<u64, oneshot::Sender<Value>>>>;
+
+/// Stdio transport — the server is a child process killed when this is dropped.
+pub struct StdioTransport {
+    _child: Child,
+    stdin: Arc<tokio::sync::Mutex<ChildStdin>>,
+    pending: PendingMap,
+    alive: Arc<AtomicBool>,
+}
+
+impl StdioTransport {
+    fn spawn(config: &McpServerConfig, command: &str) -> Result<Self> {
+        let mut child = Command::new(command)
+            .args(&config.args)
+            .envs(&config.env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| {
+                McpError::Transport(format!("failed to start '{}': {}", command, e))
+            })?;
+
+        let (Some(stdin), Some(stdout), Some(stderr)) =
+            (child.stdin.take(), child.stdout.take(), child.stderr.take())
+        else {
+            return Err(McpError::Transport("child process has no stdio pipes".to_string()));
+        };
+
+        let stdin = Arc::new(tokio::sync::Mutex::new(stdin));
+        let pending: PendingMap = Arc::new(Mutex::new(HashMap::new()));
+        let alive = Arc::new(AtomicBool::new(true));
+
+        // Server logs go to stderr; keep them in our log rather than the TUI
+        let server = config.name.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                tracing::debug!("MCP server '{}' stderr: {}", server, line);
+            }
+        });
+
+        let reader_stdin = stdin.clone();
+        let reader_pending = pending.clone();
+        let reader_alive = alive.clone();
+        let server = config.name.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                let Ok(message) = serde_json::from_str::<Value>(&line) else {
+                    tracing::debug!("MCP server '{}' wrote non-JSON to stdout: {}", server, line);
+                    continue;
+                };
+                if protocol::is_response(&message) {
+                    let sender = message.get("id").and_then(|id| id.as_u64()).and_then(|id| {
+                        reader_pending
+                            .lock()
+                            .expect("MCP pending lock poisoned")
+                            .remove(&id)
+                    });
+                    if let Some(sender) = sender {
+                        let _ = sender.send(message);
+                    }
+                } else if let Some(reply) = answer_server_request(&message) {
+                    let mut line = reply.to_string();
+                    line.push('\n');
+                    let mut stdin = reader_stdin.lock().await;
+                    let _ = stdin.write_all(line.as_bytes()).await;
+                    let _ = stdin.flush().await;
+                } else if let Some(method) = message.get("method").and_then(|m| m.as_str()) {
+                    tracing::debug!("MCP server '{}' notification: {}", server, method);
+                }
+            }
+
+            // EOF: the process exited. Dropping the senders fails every waiting request.
+            tracing::warn!("MCP server '{}' closed its stdout", server);
+            reader_alive.store(false, Ordering::SeqCst);
+            reader_pending
+                .lock()
+                .expect("MCP pending lock poisoned")
+                .clear();
+        });
+
+        Ok(Self {
+            _child: child,
+            stdin,
+            pending,
+            alive,
+        })
+    }
+
+    async fn send(&self, message: &Value) -> Result<()> {
+        if !self.alive.load(Ordering::SeqCst) {
+            return Err(McpError::Closed);
+        }
+        let mut line = message.to_string();
+        line.push('\n');
+        let mut stdin = self.stdin.lock().await;
+        let written = match stdin.write_all(line.as_bytes()).await {
+            Ok(()) => stdin.flush().await,
+            Err(e) => Err(e),
+        };
+        written.map_err(|_| {
+            self.alive.store(false, Ordering::SeqCst);
+            McpError::Closed
+        })
+    }
+
+    async fn request(&self, id: u64, message: Value, timeout: Duration) -> Result<Value> {
+        let (tx, rx) = oneshot::channel();
+        self.pending
+            .lock()
+            .expect("MCP pending lock poisoned")
+            .insert(id, tx);
+
+        if let Err(e) = self.send(&message).await {
+            self.pending
+                .lock()
+                .expect("MCP pending lock poisoned")
+                .remove(&id);
+            return Err(e);
+        }
+
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(response)) => Ok(response),
+            Ok(Err(_)) => Err(McpError::Closed),
+            Err(_) => {
+                self.pending
+                    .lock()
+                    .expect("MCP pending lock poisoned")
+                    .remove(&id);
+                Err(McpError::Timeout(timeout.as_secs()))
+            }
+        }
+    }
+}
+
+/// Reply to requests the server sends us. We advertise no client capabilities,
+/// so only `ping` is supported; anything else gets "method not found".
+fn answer_server_request(message: &Value) -> Option<Value> {
+    let id = message.get("id")?.clone();
+    let method = message.get("method")?.as_str()?;
+    Some(match method {
+        "ping" => protocol::response(id, Ok(json!({}))),
+        _ => protocol::response(id, Err((-32601, "Method not found"))),
+    })
+}
+
+/// Streamable-HTTP transport.
+pub struct HttpTransport {
+    client: reqwest::Client,
+    url: String,
+    headers: Vec<(String, String)>,
+    session_id: RwLock<Option<String>>,
+}
+
+impl HttpTransport {
+    fn new(config: &McpServerConfig, url: &str) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .build()
+            .map_err(|e| McpError::Transport(format!("failed to build HTTP client: {}", e)))?;
+        Ok(Self {
+            client,
+            url: url.to_string(),
+            headers: config
+                .headers
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+            session_id: RwLock::new(None),
+        })
+    }
+
+    async fn post(&self, message: &Value, timeout: Duration) -> Result<reqwest::Response> {
+        let mut request = self
+            .client
+            .post(&self.url)
+            .timeout(timeout)
+            .header("Content-Type", "application/json")
+            .header("Accept", "application/json, text/event-stream")
+            .header("MCP-Protocol-Version", protocol::PROTOCOL_VERSION);
+        for (name, value) in &self.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        let session_id = self
+            .session_id
+            .read()
+            .expect("MCP session lock poisoned")
+            .clone();
+        if let Some(ref session_id) = session_id {
+            request = request.header("Mcp-Session-Id", session_id);
+        }
+
+        let response = request.json(message).send().await.map_err(|e| {
+            if e.is_timeout() {
+                McpError::Timeout(timeout.as_secs())
+            } else {
+                McpError::Transport(e.to_string())
+            }
+        })?;
+
+        let status = response.status();
+        if status.as_u16() == 404 && session_id.is_some() {
+            *self.session_id.write().expect("MCP session lock poisoned") = None;
+            return Err(McpError::SessionExpired);
+        }
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(McpError::Transport(format!("HTTP {}: {}", status, body)));
+        }
+
+        if let Some(id) = response
+            .headers()
+            .get("mcp-session-id")
+            .and_then(|v| v.to_str().ok())
+        {
+            *self.session_id.write().expect("MCP session lock poisoned") = Some(id.to_string());
+        }
+        Ok(response)
+    }
+
+    async fn request(&self, id: u64, message: Value, timeout: Duration) -> Result<Value> {
+        let response = self.post(&message, timeout).await?;
+        let is_sse = response
+            .headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|ct| ct.contains("text/event-stream"));
+        let body = response
+            .text()
+            .await
+            .map_err(|e| McpError::Transport(format!("failed to read response body: {}", e)))?;
+
+        if !is_sse {
+            return Ok(serde_json::from_str(&body)?);
+        }
+
+        // The stream may carry progress notifications and server requests
+        // before our reply; pick the response with our id.
+        let mut responses: Vec<Value> = protocol::parse_sse_messages(&body)
+            .into_iter()
+            .filter(protocol::is_response)
+            .collect();
+        let position = responses
+            .iter()
+            .position(|m| m.get("id").and_then(|v| v.as_u64()) == Some(id));
+        match position {
+            Some(i) => Ok(responses.swap_remove(i)),
+            None => responses.pop().ok_or_else(|| {
+                McpError::Transport("no JSON-RPC response found in SSE stream".to_string())
+            }),
+        }
+    }
+
+    async fn notify(&self, message: Value) -> Result<()> {
+        self.post(&message, Duration::from_secs(30)).await.map(|_| ())
+    }
+}
@@ -0,0 +1,63 @@
+//! Daily logs — `~/.opencrabs/memory/YYYY-MM-DD.md`, where compaction
+//! summaries and debate results are appended as sections.
+
+use std::path::PathBuf;
+
+use super::store::memory_dir;
+
+/// Append a `## <heading> (<time>)` section to today's log, then index the log
+/// in the background so `memory_search` finds it. Returns the log's path.
+pub async fn append_daily_log(heading: &str, body: &str) -> Result<PathBuf, String> {
+    let dir = memory_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create memory directory: {e}"))?;
+
+    let now = chrono::Local::now();
+    let path = dir.join(format!("{}.md", now.format("%Y-%m-%d")));
+    let existing = std::fs::read_to_string(&path).unwrap_or_default();
+    let timestamp = now.format("%Y-%m-%d %H:%M:%S").to_string();
+    std::fs::write(&path, with_section(&existing, heading, &timestamp, body))
+        .map_err(|e| format!("Failed to write daily memory log: {e}"))?;
+
+    let indexed = path.clone();
+    tokio::spawn(async move {
+        if let Ok(store) = super::get_store() {
+            let _ = super::index_file(store, &indexed).await;
+        }
+    });
+    Ok(path)
+}
+
+/// `existing` log content with a new section after a `---` separator
+fn with_section(existing: &str, heading: &str, timestamp: &str, body: &str) -> String {
+    let content = format!(
+        "{}\n\n---\n\n## {} ({})\n\n{}\n",
+        existing.trim(),
+        heading,
+        timestamp,
+        body.trim_end()
+    );
+    content.trim_start().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_section() {
+        let first = with_section("", "Auto-Compaction Summary", "2026-01-02 10:00:00", "one");
+        assert_eq!(
+            first,
+            "---\n\n## Auto-Compaction Summary (2026-01-02 10:00:00)\n\none\n"
+        );
+
+        let second = with_section(
+            &first,
+            "Bee Colony Debate",
+            "2026-01-02 11:00:00",
+            "two\n\n",
+        );
+        assert!(second.starts_with(&first));
+        assert!(second.ends_with("---\n\n## Bee Colony Debate (2026-01-02 11:00:00)\n\ntwo\n"));
+    }
+}
@@ -4,11 +4,13 @@
 //! vector semantic search (embeddinggemma-300M). Hybrid RRF when the model
 //! is available, FTS-only fallback otherwise.
 
+mod daily_log;
 mod embedding;
 mod index;
 mod search;
 mod store;
 
+pub use daily_log::append_daily_log;
 pub use embedding::{embed_content, engine_if_ready, get_engine};
 pub use index::{index_file, reindex};
 pub use search::search;
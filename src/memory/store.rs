@@ -33,7 +33,7 @@ pub fn get_store() -> Result<&'static Mutex<Store>, String> {
 }
 
 /// Path to the memory directory: `~/.opencrabs/memory/`
-fn memory_dir() -> PathBuf {
+pub(super) fn memory_dir() -> PathBuf {
     crate::config::opencrabs_home().join("memory")
 }
 
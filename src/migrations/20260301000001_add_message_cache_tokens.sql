@@ -0,0 +1,6 @@
+-- Re-add prompt caching columns to messages.
+-- The initial schema had them, but the modernize migration dropped them when it
+-- collapsed input/output tokens into token_count.
+
+ALTER TABLE messages ADD COLUMN cache_creation_tokens INTEGER;
+ALTER TABLE messages ADD COLUMN cache_read_tokens INTEGER;
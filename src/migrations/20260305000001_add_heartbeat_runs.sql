@@ -0,0 +1,10 @@
+-- Heartbeat scheduler state: last run of each HEARTBEAT.md entry, so
+-- intervals survive restarts instead of firing on every launch.
+
+CREATE TABLE IF NOT EXISTS heartbeat_runs (
+    task_key TEXT PRIMARY KEY NOT NULL,   -- Entry as written, e.g. "every 30m: check CI"
+    last_run_at INTEGER NOT NULL,         -- Unix timestamp
+    last_status TEXT NOT NULL,            -- reported, quiet, failed
+    last_output TEXT,                     -- Agent reply or error message
+    run_count INTEGER NOT NULL DEFAULT 0
+);
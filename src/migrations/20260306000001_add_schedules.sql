@@ -0,0 +1,31 @@
+-- Scheduled prompts created with the `schedule` tool or /schedule, and their run history.
+
+CREATE TABLE IF NOT EXISTS schedules (
+    id TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    prompt TEXT NOT NULL,
+    schedule TEXT NOT NULL,          -- "every 30m" or "cron 0 9 * * mon-fri"
+    timezone TEXT,                   -- IANA name; NULL = local time
+    session_id TEXT,                 -- Session runs go to; NULL until the first run creates one
+    deliver_to TEXT,                 -- tui, telegram, discord, slack, whatsapp; NULL = TUI if running
+    target TEXT,                     -- Chat/channel ID or phone; NULL = channel owner
+    enabled INTEGER NOT NULL DEFAULT 1,
+    created_at INTEGER NOT NULL,     -- Unix timestamp
+    updated_at INTEGER NOT NULL,     -- Unix timestamp
+    last_run_at INTEGER,             -- Unix timestamp
+    next_run_at INTEGER              -- Unix timestamp; NULL = never fires again
+);
+
+CREATE INDEX IF NOT EXISTS idx_schedules_due ON schedules(enabled, next_run_at);
+
+CREATE TABLE IF NOT EXISTS schedule_runs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    schedule_id TEXT NOT NULL REFERENCES schedules(id) ON DELETE CASCADE,
+    started_at INTEGER NOT NULL,     -- Unix timestamp
+    finished_at INTEGER,             -- Unix timestamp
+    status TEXT NOT NULL,            -- running, completed, failed
+    output TEXT,                     -- Agent reply or error message
+    delivered INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_schedule_runs_schedule ON schedule_runs(schedule_id, started_at DESC);
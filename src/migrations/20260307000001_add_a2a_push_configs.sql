@@ -0,0 +1,8 @@
+-- A2A push notification configs: one webhook per task, kept across restarts
+-- so notifications for restored tasks still get delivered.
+
+CREATE TABLE IF NOT EXISTS a2a_push_configs (
+    task_id TEXT PRIMARY KEY NOT NULL,
+    data TEXT NOT NULL,                        -- PushNotificationConfig JSON blob
+    created_at INTEGER NOT NULL                -- Unix timestamp
+);
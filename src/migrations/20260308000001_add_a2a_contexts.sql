@@ -0,0 +1,9 @@
+-- A2A contexts: each contextId is one OpenCrabs session, so follow-up
+-- messages in the same context see the whole conversation.
+
+CREATE TABLE IF NOT EXISTS a2a_contexts (
+    context_id TEXT PRIMARY KEY NOT NULL,
+    session_id TEXT NOT NULL,
+    created_at INTEGER NOT NULL,               -- Unix timestamp
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
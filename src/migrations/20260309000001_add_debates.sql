@@ -0,0 +1,11 @@
+-- Finished Bee Colony debates, kept for the /debate panel and later recall.
+
+CREATE TABLE IF NOT EXISTS debates (
+    id TEXT PRIMARY KEY NOT NULL,
+    topic TEXT NOT NULL,
+    state TEXT NOT NULL,                       -- DebateState (concluded, exhausted, ...)
+    data TEXT NOT NULL,                        -- DebateSession JSON blob
+    created_at INTEGER NOT NULL                -- Unix timestamp
+);
+
+CREATE INDEX IF NOT EXISTS idx_debates_created_at ON debates(created_at DESC);
@@ -0,0 +1,20 @@
+-- Per-turn file checkpoints: what files looked like before an agent turn changed them.
+-- Used by /undo and /checkpoints.
+
+CREATE TABLE IF NOT EXISTS checkpoints (
+    id TEXT PRIMARY KEY NOT NULL,
+    session_id TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+    message_id TEXT,                 -- User message that started the turn
+    prompt TEXT NOT NULL,            -- First line of that message, for listings
+    created_at INTEGER NOT NULL,     -- Unix timestamp
+    restored_at INTEGER              -- Unix timestamp; NULL = changes still applied
+);
+
+CREATE INDEX IF NOT EXISTS idx_checkpoints_session ON checkpoints(session_id, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS checkpoint_files (
+    checkpoint_id TEXT NOT NULL REFERENCES checkpoints(id) ON DELETE CASCADE,
+    path TEXT NOT NULL,              -- Absolute path
+    content BLOB,                    -- Content before the turn; NULL = file did not exist
+    PRIMARY KEY (checkpoint_id, path)
+);
@@ -11,11 +11,29 @@ use std::sync::OnceLock;
 /// A single model pricing entry.
 /// `prefix` is matched as a substring of the model name (case-insensitive).
 /// First match wins, so put more specific prefixes before general ones.
+/// Prompt-cache prices are optional and default to Anthropic's multipliers
+/// (writes 1.25× input, reads 0.1× input).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PricingEntry {
     pub prefix: String,
     pub input_per_m: f64,
     pub output_per_m: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_write_per_m: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read_per_m: Option<f64>,
+}
+
+impl PricingEntry {
+    /// Price per million tokens written to the prompt cache
+    pub fn cache_write_rate(&self) -> f64 {
+        self.cache_write_per_m.unwrap_or(self.input_per_m * 1.25)
+    }
+
+    /// Price per million tokens read from the prompt cache
+    pub fn cache_read_rate(&self) -> f64 {
+        self.cache_read_per_m.unwrap_or(self.input_per_m * 0.1)
+    }
 }
 
 /// Per-provider block in the TOML file.
@@ -38,33 +56,45 @@ impl PricingConfig {
     /// Searches all providers, matches by prefix (case-insensitive, first match wins).
     /// Returns 0.0 if no match found.
     pub fn calculate_cost(&self, model: &str, input_tokens: u32, output_tokens: u32) -> f64 {
+        self.calculate_cost_with_cache(model, input_tokens, output_tokens, 0, 0)
+    }
+
+    /// Calculate cost including prompt-cache writes and reads.
+    /// `input_tokens` is the uncached part of the prompt, as Anthropic reports it.
+    pub fn calculate_cost_with_cache(
+        &self,
+        model: &str,
+        input_tokens: u32,
+        output_tokens: u32,
+        cache_write_tokens: u32,
+        cache_read_tokens: u32,
+    ) -> f64 {
+        let Some(entry) = self.find(model) else {
+            return 0.0;
+        };
+        let per_m = |tokens: u32, rate: f64| (tokens as f64 / 1_000_000.0) * rate;
+        per_m(input_tokens, entry.input_per_m)
+            + per_m(output_tokens, entry.output_per_m)
+            + per_m(cache_write_tokens, entry.cache_write_rate())
+            + per_m(cache_read_tokens, entry.cache_read_rate())
+    }
+
+    /// First pricing entry whose prefix matches the model (case-insensitive)
+    fn find(&self, model: &str) -> Option<&PricingEntry> {
         let m = model.to_lowercase();
-        for block in self.providers.values() {
-            for entry in &block.entries {
-                if m.contains(&entry.prefix.to_lowercase()) {
-                    let input = (input_tokens as f64 / 1_000_000.0) * entry.input_per_m;
-                    let output = (output_tokens as f64 / 1_000_000.0) * entry.output_per_m;
-                    return input + output;
-                }
-            }
-        }
-        0.0
+        self.providers
+            .values()
+            .flat_map(|block| block.entries.iter())
+            .find(|entry| m.contains(&entry.prefix.to_lowercase()))
     }
 
     /// Estimate cost from a combined token count using an 80/20 input/output split.
     /// Returns None if model is unknown.
     pub fn estimate_cost(&self, model: &str, token_count: i64) -> Option<f64> {
-        let m = model.to_lowercase();
-        for block in self.providers.values() {
-            for entry in &block.entries {
-                if m.contains(&entry.prefix.to_lowercase()) {
-                    let input = (token_count as f64 * 0.80 / 1_000_000.0) * entry.input_per_m;
-                    let output = (token_count as f64 * 0.20 / 1_000_000.0) * entry.output_per_m;
-                    return Some(input + output);
-                }
-            }
-        }
-        None
+        let entry = self.find(model)?;
+        let input = (token_count as f64 * 0.80 / 1_000_000.0) * entry.input_per_m;
+        let output = (token_count as f64 * 0.20 / 1_000_000.0) * entry.output_per_m;
+        Some(input + output)
     }
 
     /// Load from ~/.opencrabs/usage_pricing.toml.
@@ -150,9 +180,16 @@ impl PricingConfig {
         for (name, block) in providers {
             out.push_str(&format!("[providers.{}]\nentries = [\n", name));
             for e in &block.entries {
+                let mut cache = String::new();
+                if let Some(w) = e.cache_write_per_m {
+                    cache.push_str(&format!(", cache_write_per_m = {}", w));
+                }
+                if let Some(r) = e.cache_read_per_m {
+                    cache.push_str(&format!(", cache_read_per_m = {}", r));
+                }
                 out.push_str(&format!(
-                    "  {{ prefix = {:?}, input_per_m = {}, output_per_m = {} }},\n",
-                    e.prefix, e.input_per_m, e.output_per_m
+                    "  {{ prefix = {:?}, input_per_m = {}, output_per_m = {}{} }},\n",
+                    e.prefix, e.input_per_m, e.output_per_m, cache
                 ));
             }
             out.push_str("]\n\n");
@@ -197,27 +234,29 @@ pub const DEFAULT_PRICING_TOML: &str = r#"
 #   - `prefix` is matched as a case-insensitive substring of the model name
 #   - First match within each provider wins — put specific prefixes before general ones
 #   - Costs are per 1 million tokens (USD)
+#   - Optional `cache_write_per_m` / `cache_read_per_m` price prompt caching;
+#     when omitted they default to 1.25× / 0.1× the input price
 
 [providers.anthropic]
 entries = [
   # Claude Opus 4.x — $5/$25 per M tokens
-  { prefix = "claude-opus-4",      input_per_m = 5.0,  output_per_m = 25.0 },
+  { prefix = "claude-opus-4",      input_per_m = 5.0,  output_per_m = 25.0, cache_write_per_m = 6.25, cache_read_per_m = 0.5 },
   # Claude Opus 3 (legacy) — $15/$75
-  { prefix = "claude-3-opus",      input_per_m = 15.0, output_per_m = 75.0 },
+  { prefix = "claude-3-opus",      input_per_m = 15.0, output_per_m = 75.0, cache_write_per_m = 18.75, cache_read_per_m = 1.5 },
   # Claude Sonnet 4.x — $3/$15
-  { prefix = "claude-sonnet-4",    input_per_m = 3.0,  output_per_m = 15.0 },
+  { prefix = "claude-sonnet-4",    input_per_m = 3.0,  output_per_m = 15.0, cache_write_per_m = 3.75, cache_read_per_m = 0.3 },
   # Claude 3.7 Sonnet — $3/$15
-  { prefix = "claude-3-7-sonnet",  input_per_m = 3.0,  output_per_m = 15.0 },
+  { prefix = "claude-3-7-sonnet",  input_per_m = 3.0,  output_per_m = 15.0, cache_write_per_m = 3.75, cache_read_per_m = 0.3 },
   # Claude 3.5 Sonnet — $3/$15
-  { prefix = "claude-3-5-sonnet",  input_per_m = 3.0,  output_per_m = 15.0 },
+  { prefix = "claude-3-5-sonnet",  input_per_m = 3.0,  output_per_m = 15.0, cache_write_per_m = 3.75, cache_read_per_m = 0.3 },
   # Claude 3 Sonnet (legacy) — $3/$15
-  { prefix = "claude-3-sonnet",    input_per_m = 3.0,  output_per_m = 15.0 },
+  { prefix = "claude-3-sonnet",    input_per_m = 3.0,  output_per_m = 15.0, cache_write_per_m = 3.75, cache_read_per_m = 0.3 },
   # Claude Haiku 4.x — $1/$5
-  { prefix = "claude-haiku-4",     input_per_m = 1.0,  output_per_m = 5.0  },
+  { prefix = "claude-haiku-4",     input_per_m = 1.0,  output_per_m = 5.0,  cache_write_per_m = 1.25, cache_read_per_m = 0.1 },
   # Claude 3.5 Haiku — $0.80/$4
-  { prefix = "claude-3-5-haiku",   input_per_m = 0.80, output_per_m = 4.0  },
+  { prefix = "claude-3-5-haiku",   input_per_m = 0.80, output_per_m = 4.0,  cache_write_per_m = 1.0, cache_read_per_m = 0.08 },
   # Claude 3 Haiku (legacy) — $0.25/$1.25
-  { prefix = "claude-3-haiku",     input_per_m = 0.25, output_per_m = 1.25 },
+  { prefix = "claude-3-haiku",     input_per_m = 0.25, output_per_m = 1.25, cache_write_per_m = 0.3125, cache_read_per_m = 0.025 },
 ]
 
 [providers.openai]
@@ -310,6 +349,30 @@ mod tests {
         assert_eq!(cost, 0.0);
     }
 
+    #[test]
+    fn test_calculate_cost_with_cache() {
+        let cfg = PricingConfig::defaults();
+        // Sonnet 4: $3 input, $15 output, $3.75 cache write, $0.30 cache read
+        let cost = cfg.calculate_cost_with_cache(
+            "claude-sonnet-4-6",
+            1_000_000,
+            1_000_000,
+            1_000_000,
+            10_000_000,
+        );
+        assert!((cost - (3.0 + 15.0 + 3.75 + 3.0)).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_cache_rates_default_from_input() {
+        let entry: PricingEntry =
+            toml::from_str(r#"prefix = "x"
+input_per_m = 10.0
+output_per_m = 20.0"#).unwrap();
+        assert_eq!(entry.cache_write_rate(), 12.5);
+        assert_eq!(entry.cache_read_rate(), 1.0);
+    }
+
     #[test]
     fn test_estimate_cost() {
         let cfg = PricingConfig::defaults();
@@ -0,0 +1,233 @@
+//! Five-field cron expressions: `minute hour day-of-month month day-of-week`.
+//!
+//! Supports `*`, lists (`1,15`), ranges (`1-5`), steps (`*/15`, `9-17/2`),
+//! month and weekday names (`jan`, `mon-fri`), `0` or `7` for Sunday, and the
+//! `@hourly`/`@daily`/`@weekly`/`@monthly`/`@yearly` shorthands.
+
+use super::error::{Result, SchedulerError};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+
+/// How far ahead to look for the next match (covers Feb 29 schedules).
+const SEARCH_DAYS: i64 = 366 * 8;
+
+const MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const DAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// A parsed cron expression. Each field is a bitmask of allowed values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronExpr {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    /// Classic cron: when both day fields are restricted, either one matching is enough
+    dom_restricted: bool,
+    dow_restricted: bool,
+}
+
+impl CronExpr {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let lower = spec.trim().to_ascii_lowercase();
+        let expanded = match lower.as_str() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(SchedulerError::invalid(
+                spec,
+                "expected 5 fields: minute hour day-of-month month day-of-week",
+            ));
+        }
+
+        let field = |index: usize, min: u32, max: u32, names: &[&str], name_base: u32| {
+            parse_field(fields[index], min, max, names, name_base)
+                .map_err(|reason| SchedulerError::invalid(spec, reason))
+        };
+        let days_of_week = field(4, 0, 7, &DAY_NAMES, 0)?;
+
+        Ok(Self {
+            minutes: field(0, 0, 59, &[], 0)?,
+            hours: field(1, 0, 23, &[], 0)?,
+            days_of_month: field(2, 1, 31, &[], 0)?,
+            months: field(3, 1, 12, &MONTH_NAMES, 1)?,
+            // 7 is an alias for Sunday
+            days_of_week: (days_of_week | (days_of_week >> 7)) & 0x7f,
+            dom_restricted: !fields[2].starts_with('*'),
+            dow_restricted: !fields[4].starts_with('*'),
+        })
+    }
+
+    /// First matching minute strictly after `after`, in `after`'s timezone.
+    ///
+    /// Local times skipped by a DST change never match; repeated ones fire once.
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let tz = after.timezone();
+        let start = after.naive_local();
+        let mut t = start.date().and_hms_opt(start.hour(), start.minute(), 0)? + Duration::minutes(1);
+        let limit = t + Duration::days(SEARCH_DAYS);
+
+        while t <= limit {
+            if self.months & (1 << t.month()) == 0 {
+                t = first_of_next_month(t.date())?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !self.matches_day(t.date()) {
+                t = t.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if self.hours & (1 << t.hour()) == 0 {
+                t = start_of_hour(t)? + Duration::hours(1);
+                continue;
+            }
+            if self.minutes & (1 << t.minute()) == 0 {
+                t += Duration::minutes(1);
+                continue;
+            }
+            if let Some(candidate) = tz.from_local_datetime(&t).earliest()
+                && candidate > *after
+            {
+                return Some(candidate);
+            }
+            t += Duration::minutes(1);
+        }
+        None
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let dom = self.days_of_month & (1 << date.day()) != 0;
+        let dow = self.days_of_week & (1 << date.weekday().num_days_from_sunday()) != 0;
+        if self.dom_restricted && self.dow_restricted {
+            dom || dow
+        } else {
+            dom && dow
+        }
+    }
+}
+
+fn first_of_next_month(date: NaiveDate) -> Option<NaiveDate> {
+    if date.month() == 12 {
+        NaiveDate::from_ymd_opt(date.year() + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(date.year(), date.month() + 1, 1)
+    }
+}
+
+fn start_of_hour(t: NaiveDateTime) -> Option<NaiveDateTime> {
+    t.date().and_hms_opt(t.hour(), 0, 0)
+}
+
+/// Parse one field into a bitmask of allowed values.
+fn parse_field(
+    field: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+    name_base: u32,
+) -> std::result::Result<u64, String> {
+    let value = |s: &str| -> std::result::Result<u32, String> {
+        let v = match names.iter().position(|name| *name == s) {
+            Some(index) => index as u32 + name_base,
+            None => s.parse::<u32>().map_err(|_| format!("invalid value '{}'", s))?,
+        };
+        if v < min || v > max {
+            return Err(format!("'{}' is outside {}-{}", s, min, max));
+        }
+        Ok(v)
+    };
+
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => match step.parse::<u32>() {
+                Ok(step) if step > 0 => (range, Some(step)),
+                _ => return Err(format!("invalid step '{}'", step)),
+            },
+            None => (part, None),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (value(a)?, value(b)?)
+        } else {
+            let v = value(range)?;
+            // `5/15` means "from 5, every 15"
+            if step.is_some() { (v, max) } else { (v, v) }
+        };
+        if start > end {
+            return Err(format!("range '{}' runs backwards", range));
+        }
+
+        let step = step.unwrap_or(1);
+        let mut v = start;
+        while v <= end {
+            mask |= 1 << v;
+            v += step;
+        }
+    }
+    Ok(mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M")
+            .unwrap()
+            .and_utc()
+    }
+
+    fn next(expr: &str, after: &str) -> String {
+        CronExpr::parse(expr)
+            .unwrap()
+            .next_after(&at(after))
+            .unwrap()
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
+    }
+
+    #[test]
+    fn test_next_after_basic_fields() {
+        assert_eq!(next("*/15 * * * *", "2026-03-02 10:07"), "2026-03-02 10:15");
+        assert_eq!(next("*/15 * * * *", "2026-03-02 10:15"), "2026-03-02 10:30");
+        assert_eq!(next("0 9 * * *", "2026-03-02 09:00"), "2026-03-03 09:00");
+        assert_eq!(next("30 23 31 12 *", "2026-03-02 00:00"), "2026-12-31 23:30");
+        assert_eq!(next("@monthly", "2026-12-15 08:00"), "2027-01-01 00:00");
+    }
+
+    #[test]
+    fn test_weekday_names_and_sunday_alias() {
+        // 2026-03-06 is a Friday
+        assert_eq!(next("0 9 * * mon-fri", "2026-03-06 10:00"), "2026-03-09 09:00");
+        assert_eq!(next("0 9 * * 7", "2026-03-06 10:00"), "2026-03-08 09:00");
+        assert_eq!(next("0 9 * * SUN", "2026-03-06 10:00"), "2026-03-08 09:00");
+    }
+
+    #[test]
+    fn test_restricted_day_fields_match_either() {
+        // The 15th (a Sunday) or any Monday
+        assert_eq!(next("0 0 15 * mon", "2026-03-10 00:00"), "2026-03-15 00:00");
+        assert_eq!(next("0 0 15 * mon", "2026-03-15 00:00"), "2026-03-16 00:00");
+        // Leap day is found years ahead
+        assert_eq!(next("0 0 29 feb *", "2026-03-01 00:00"), "2028-02-29 00:00");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for bad in ["* * * *", "60 * * * *", "* * 0 * *", "*/0 * * * *", "5-1 * * * *", "x * * * *"] {
+            assert!(CronExpr::parse(bad).is_err(), "{bad} should not parse");
+        }
+        assert!(CronExpr::parse("0 0 30 2 *").unwrap().next_after(&at("2026-01-01 00:00")).is_none());
+    }
+}
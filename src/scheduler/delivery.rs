@@ -0,0 +1,186 @@
+//! Delivering scheduled results to the TUI or a messaging channel.
+//!
+//! Channels are reached through their `*_send` tools, so delivery works for
+//! whichever bots are connected and reuses their owner/chat resolution.
+
+use super::error::{Result, SchedulerError};
+use crate::brain::tools::{ToolExecutionContext, ToolRegistry};
+use crate::tui::events::TuiEvent;
+use serde_json::json;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::mpsc::UnboundedSender;
+use uuid::Uuid;
+
+/// Where results are posted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryChannel {
+    Tui,
+    Telegram,
+    Discord,
+    Slack,
+    WhatsApp,
+    Signal,
+}
+
+impl DeliveryChannel {
+    /// Tool that sends to this channel
+    fn send_tool(self) -> Option<&'static str> {
+        match self {
+            Self::Tui => None,
+            Self::Telegram => Some("telegram_send"),
+            Self::Discord => Some("discord_send"),
+            Self::Slack => Some("slack_send"),
+            Self::WhatsApp => Some("whatsapp_send"),
+            Self::Signal => Some("signal_send"),
+        }
+    }
+
+    /// Input field of the send tool that picks a recipient other than the owner
+    fn target_field(self) -> &'static str {
+        match self {
+            Self::Tui => "",
+            Self::Telegram => "chat_id",
+            Self::Discord => "channel_id",
+            Self::Slack => "channel",
+            Self::WhatsApp => "phone",
+            Self::Signal => "recipient",
+        }
+    }
+}
+
+impl FromStr for DeliveryChannel {
+    type Err = SchedulerError;
+
+    /// Accepts the channel name or its send tool, e.g. `slack` or `slack_send`.
+    fn from_str(s: &str) -> Result<Self> {
+        let lower = s.trim().to_ascii_lowercase();
+        match lower.strip_suffix("_send").unwrap_or(&lower) {
+            "tui" => Ok(Self::Tui),
+            "telegram" => Ok(Self::Telegram),
+            "discord" => Ok(Self::Discord),
+            "slack" => Ok(Self::Slack),
+            "whatsapp" => Ok(Self::WhatsApp),
+            "signal" => Ok(Self::Signal),
+            _ => Err(SchedulerError::UnknownChannel(s.to_string())),
+        }
+    }
+}
+
+/// A channel plus an optional recipient (chat ID, channel ID or phone number).
+/// Without a recipient, channels message their owner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeliveryTarget {
+    pub channel: DeliveryChannel,
+    pub recipient: Option<String>,
+}
+
+impl DeliveryTarget {
+    pub fn new(channel: DeliveryChannel, recipient: Option<String>) -> Self {
+        Self {
+            channel,
+            recipient: recipient.filter(|r| !r.trim().is_empty()),
+        }
+    }
+}
+
+/// Sends scheduler output to its destination.
+#[derive(Clone)]
+pub struct Delivery {
+    registry: Arc<ToolRegistry>,
+    working_directory: PathBuf,
+    tui: Option<UnboundedSender<TuiEvent>>,
+}
+
+impl Delivery {
+    pub fn new(registry: Arc<ToolRegistry>, working_directory: PathBuf) -> Self {
+        Self {
+            registry,
+            working_directory,
+            tui: None,
+        }
+    }
+
+    /// Post to the chat view when the TUI is running
+    pub fn with_tui(mut self, sender: UnboundedSender<TuiEvent>) -> Self {
+        self.tui = Some(sender);
+        self
+    }
+
+    /// Send `text` to `target`. With no target the TUI is used if present;
+    /// otherwise the result stays in the scheduler's session only.
+    pub async fn deliver(&self, target: Option<&DeliveryTarget>, text: &str) -> Result<()> {
+        let Some(target) = target else {
+            if let Some(ref tui) = self.tui {
+                let _ = tui.send(TuiEvent::SystemMessage(text.to_string()));
+            }
+            return Ok(());
+        };
+
+        let Some(tool) = target.channel.send_tool() else {
+            let tui = self
+                .tui
+                .as_ref()
+                .ok_or_else(|| SchedulerError::Delivery("the TUI is not running".to_string()))?;
+            return tui
+                .send(TuiEvent::SystemMessage(text.to_string()))
+                .map_err(|_| SchedulerError::Delivery("the TUI has closed".to_string()));
+        };
+
+        let mut input = json!({ "message": text });
+        if let Some(ref recipient) = target.recipient {
+            let field = target.channel.target_field();
+            input[field] = match (target.channel, recipient.parse::<i64>()) {
+                (DeliveryChannel::Telegram, Ok(chat_id)) => json!(chat_id),
+                _ => json!(recipient),
+            };
+        }
+
+        let context = ToolExecutionContext::new(Uuid::new_v4())
+            .with_working_directory(self.working_directory.clone())
+            .with_auto_approve(true);
+        let result = self
+            .registry
+            .execute(tool, input, &context)
+            .await
+            .map_err(|e| SchedulerError::Delivery(e.to_string()))?;
+        if result.success {
+            Ok(())
+        } else {
+            Err(SchedulerError::Delivery(
+                result.error.unwrap_or(result.output),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_names() {
+        assert_eq!("slack".parse::<DeliveryChannel>().unwrap(), DeliveryChannel::Slack);
+        assert_eq!(
+            "telegram_send".parse::<DeliveryChannel>().unwrap(),
+            DeliveryChannel::Telegram
+        );
+        assert_eq!("TUI".parse::<DeliveryChannel>().unwrap(), DeliveryChannel::Tui);
+        assert!("email".parse::<DeliveryChannel>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_deliver_to_tui_and_missing_channel() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let delivery = Delivery::new(Arc::new(ToolRegistry::new()), PathBuf::from("."))
+            .with_tui(tx);
+
+        delivery.deliver(None, "hello").await.unwrap();
+        assert!(matches!(rx.recv().await, Some(TuiEvent::SystemMessage(text)) if text == "hello"));
+
+        // Channel not compiled in or not registered
+        let target = DeliveryTarget::new(DeliveryChannel::Slack, None);
+        assert!(delivery.deliver(Some(&target), "hello").await.is_err());
+    }
+}
@@ -0,0 +1,35 @@
+//! Scheduler error types
+
+use thiserror::Error;
+
+/// Scheduler error types
+#[derive(Debug, Error)]
+pub enum SchedulerError {
+    /// Schedule spec could not be parsed
+    #[error("Invalid schedule '{spec}': {reason}")]
+    InvalidSchedule { spec: String, reason: String },
+
+    /// Timezone is not an IANA name like "Europe/Berlin"
+    #[error("Unknown timezone '{0}' (use an IANA name like \"America/New_York\")")]
+    UnknownTimezone(String),
+
+    /// Delivery channel name is not one we can send to
+    #[error("Unknown delivery channel '{0}' (expected tui, telegram, discord, slack, whatsapp or signal)")]
+    UnknownChannel(String),
+
+    /// Result could not be delivered
+    #[error("Delivery failed: {0}")]
+    Delivery(String),
+}
+
+impl SchedulerError {
+    pub(crate) fn invalid(spec: &str, reason: impl Into<String>) -> Self {
+        Self::InvalidSchedule {
+            spec: spec.to_string(),
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Result type for scheduler operations
+pub type Result<T> = std::result::Result<T, SchedulerError>;
@@ -0,0 +1,346 @@
+//! HEARTBEAT.md scheduler.
+//!
+//! Each entry in the brain's `HEARTBEAT.md` is a schedule and a prompt:
+//!
+//! ```text
+//! - every 30m: check CI status of main
+//! - cron 0 9 * * mon-fri: summarize open PRs waiting on me
+//! ```
+//!
+//! Due entries run as agent turns in a dedicated "Heartbeat" session. The file
+//! is re-read on every check, so edits apply without a restart, and a file with
+//! no entries (only comments) never calls the provider.
+
+use super::delivery::{Delivery, DeliveryTarget};
+use super::persistence;
+use super::schedule::{Schedule, Zone};
+use crate::brain::agent::{AgentError, AgentService};
+use crate::services::SessionService;
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+/// Title of the session heartbeat turns run in
+pub const HEARTBEAT_SESSION_TITLE: &str = "Heartbeat";
+
+/// Reply meaning "nothing to report" — the run is recorded but not delivered
+pub const HEARTBEAT_OK: &str = "HEARTBEAT_OK";
+
+/// How often HEARTBEAT.md is checked for due entries by default
+pub const DEFAULT_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// One scheduled entry from HEARTBEAT.md.
+#[derive(Debug, Clone)]
+pub struct HeartbeatTask {
+    /// The entry as written; identifies it across restarts
+    pub key: String,
+    pub schedule: Schedule,
+    pub prompt: String,
+}
+
+impl HeartbeatTask {
+    /// Whether the task should run at `now`.
+    ///
+    /// Interval entries that never ran are due immediately. Cron entries that
+    /// never ran wait for their first fire time after `started_at`. Cron
+    /// fields are read in the local timezone.
+    pub fn is_due(
+        &self,
+        last_run: Option<DateTime<Utc>>,
+        started_at: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> bool {
+        let from = match (last_run, &self.schedule) {
+            (Some(last_run), _) => last_run,
+            (None, Schedule::Every(_)) => return true,
+            (None, Schedule::Cron(_)) => started_at,
+        };
+        Zone::Local
+            .next_run(&self.schedule, from)
+            .is_some_and(|next| next <= now)
+    }
+}
+
+/// Parse the entries out of HEARTBEAT.md.
+///
+/// `#` lines, HTML comments and code blocks are ignored; list markers are
+/// optional. Lines that don't start with a schedule are skipped, and invalid
+/// schedules are logged and skipped.
+pub fn parse_heartbeat(content: &str) -> Vec<HeartbeatTask> {
+    let mut tasks = Vec::new();
+    let mut in_code_block = false;
+
+    for line in strip_html_comments(content).lines() {
+        let line = line.trim();
+        if line.starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block || line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let entry = strip_list_marker(line);
+        let Some((spec, prompt)) = entry.split_once(':') else {
+            continue;
+        };
+        let spec = spec.trim();
+        let prompt = prompt.trim();
+        let lower = spec.to_ascii_lowercase();
+        if prompt.is_empty()
+            || !(lower.starts_with("every ") || lower.starts_with("cron ") || lower.starts_with('@'))
+        {
+            continue;
+        }
+
+        match Schedule::parse(spec) {
+            Ok(schedule) => tasks.push(HeartbeatTask {
+                key: format!("{}: {}", spec, prompt),
+                schedule,
+                prompt: prompt.to_string(),
+            }),
+            Err(e) => tracing::warn!("HEARTBEAT.md: skipping entry: {}", e),
+        }
+    }
+    tasks
+}
+
+fn strip_html_comments(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("<!--") {
+        out.push_str(&rest[..start]);
+        match rest[start..].find("-->") {
+            Some(end) => rest = &rest[start + end + 3..],
+            None => return out,
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn strip_list_marker(line: &str) -> &str {
+    let line = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))
+        .unwrap_or_else(|| {
+            // Numbered lists: "1. every 30m: ..."
+            match line.split_once(". ") {
+                Some((n, rest)) if !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()) => rest,
+                _ => line,
+            }
+        });
+    line.strip_prefix("[ ] ")
+        .or_else(|| line.strip_prefix("[x] "))
+        .unwrap_or(line)
+        .trim()
+}
+
+/// True when the agent replied that there is nothing to report.
+fn is_quiet(reply: &str) -> bool {
+    let reply = reply.trim_matches(|c: char| c.is_whitespace() || matches!(c, '`' | '*' | '.'));
+    reply.is_empty() || reply == HEARTBEAT_OK
+}
+
+/// Runs due HEARTBEAT.md entries in the background.
+pub struct HeartbeatScheduler {
+    agent: Arc<AgentService>,
+    pool: SqlitePool,
+    heartbeat_path: PathBuf,
+    delivery: Delivery,
+    target: Option<DeliveryTarget>,
+    check_interval: Duration,
+}
+
+impl HeartbeatScheduler {
+    /// `agent` should be unattended (auto-approving) — nobody is around to
+    /// answer approval prompts for a heartbeat.
+    pub fn new(
+        agent: Arc<AgentService>,
+        pool: SqlitePool,
+        brain_path: &Path,
+        delivery: Delivery,
+    ) -> Self {
+        Self {
+            agent,
+            pool,
+            heartbeat_path: brain_path.join("HEARTBEAT.md"),
+            delivery,
+            target: None,
+            check_interval: DEFAULT_CHECK_INTERVAL,
+        }
+    }
+
+    /// Where reports go (default: the TUI if running)
+    pub fn with_target(mut self, target: Option<DeliveryTarget>) -> Self {
+        self.target = target;
+        self
+    }
+
+    pub fn with_check_interval(mut self, interval: Duration) -> Self {
+        self.check_interval = interval;
+        self
+    }
+
+    /// Start checking HEARTBEAT.md on the configured interval.
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let started_at = Utc::now();
+            let mut session_id = None;
+            let mut interval = tokio::time::interval(self.check_interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            tracing::info!(
+                "Heartbeat scheduler watching {} every {}s",
+                self.heartbeat_path.display(),
+                self.check_interval.as_secs()
+            );
+            loop {
+                interval.tick().await;
+                self.check(started_at, &mut session_id).await;
+            }
+        })
+    }
+
+    /// Run every due entry, one after another.
+    async fn check(&self, started_at: DateTime<Utc>, session_id: &mut Option<Uuid>) {
+        let Ok(content) = tokio::fs::read_to_string(&self.heartbeat_path).await else {
+            return;
+        };
+        let tasks = parse_heartbeat(&content);
+        if tasks.is_empty() {
+            return;
+        }
+
+        let last_runs = persistence::load_heartbeat_runs(&self.pool).await;
+        for task in tasks {
+            if !task.is_due(last_runs.get(&task.key).copied(), started_at, Utc::now()) {
+                continue;
+            }
+            let Some(session) = self.session(session_id).await else {
+                return;
+            };
+            if let Err(AgentError::SessionNotFound(_)) = self.run(&task, session).await {
+                // Session was deleted from the TUI — make a new one next time
+                *session_id = None;
+            }
+        }
+    }
+
+    async fn session(&self, cached: &mut Option<Uuid>) -> Option<Uuid> {
+        if cached.is_none() {
+            *cached = match persistence::find_session_by_title(&self.pool, HEARTBEAT_SESSION_TITLE)
+                .await
+            {
+                Some(id) => Some(id),
+                None => SessionService::new(self.agent.context().clone())
+                    .create_session(Some(HEARTBEAT_SESSION_TITLE.to_string()))
+                    .await
+                    .inspect_err(|e| tracing::error!("Heartbeat: failed to create session: {}", e))
+                    .ok()
+                    .map(|session| session.id),
+            };
+        }
+        *cached
+    }
+
+    async fn run(&self, task: &HeartbeatTask, session_id: Uuid) -> Result<(), AgentError> {
+        tracing::info!("Heartbeat: running '{}'", task.key);
+        let prompt = format!(
+            "[Heartbeat] Scheduled check from HEARTBEAT.md: {}\n\n\
+             This is an automatic run; nobody is waiting on a reply. Do the check and \
+             answer with a short report. If there is nothing worth reporting, reply \
+             with exactly {}.",
+            task.prompt, HEARTBEAT_OK
+        );
+        let ran_at = Utc::now();
+
+        let response = match self
+            .agent
+            .send_message_with_tools(session_id, prompt, None)
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => {
+                tracing::warn!("Heartbeat '{}' failed: {}", task.key, e);
+                persistence::record_heartbeat_run(&self.pool, &task.key, ran_at, "failed", &e.to_string())
+                    .await;
+                return Err(e);
+            }
+        };
+
+        let reply = response.content.trim();
+        if is_quiet(reply) {
+            tracing::debug!("Heartbeat '{}': nothing to report", task.key);
+            persistence::record_heartbeat_run(&self.pool, &task.key, ran_at, "quiet", reply).await;
+            return Ok(());
+        }
+
+        persistence::record_heartbeat_run(&self.pool, &task.key, ran_at, "reported", reply).await;
+        let report = format!("💓 Heartbeat — {}\n\n{}", task.prompt, reply);
+        if let Err(e) = self.delivery.deliver(self.target.as_ref(), &report).await {
+            tracing::warn!("Heartbeat '{}': {}", task.key, e);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEMPLATE: &str = "# HEARTBEAT.md\n\n\
+        # Keep this file empty (or with only comments) to skip heartbeat API calls.\n\
+        # Add tasks below when you want the agent to check something periodically.\n";
+
+    #[test]
+    fn test_template_has_no_entries() {
+        assert!(parse_heartbeat(TEMPLATE).is_empty());
+        assert!(parse_heartbeat("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_entries() {
+        let content = format!(
+            "{TEMPLATE}\n\
+             - every 30m: check CI status of main\n\
+             2. cron 0 9 * * mon-fri: summarize open PRs\n\
+             <!-- every 5m: commented out -->\n\
+             ```\nevery 1h: inside a code block\n```\n\
+             Note: plain prose is ignored\n\
+             - every 10s: too frequent\n"
+        );
+        let tasks = parse_heartbeat(&content);
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].key, "every 30m: check CI status of main");
+        assert_eq!(tasks[0].schedule, Schedule::Every(Duration::from_secs(1800)));
+        assert_eq!(tasks[1].prompt, "summarize open PRs");
+        assert!(matches!(tasks[1].schedule, Schedule::Cron(_)));
+    }
+
+    #[test]
+    fn test_is_due() {
+        let task = parse_heartbeat("every 30m: check CI").remove(0);
+        let now = Utc::now();
+        let started = now - chrono::Duration::minutes(5);
+        assert!(task.is_due(None, started, now));
+        assert!(!task.is_due(Some(now - chrono::Duration::minutes(10)), started, now));
+        assert!(task.is_due(Some(now - chrono::Duration::minutes(31)), started, now));
+
+        // Cron entries never fire just because the app started
+        let cron = parse_heartbeat("cron 0 0 1 1 *: new year").remove(0);
+        assert!(!cron.is_due(None, now, now));
+    }
+
+    #[test]
+    fn test_quiet_replies() {
+        assert!(is_quiet("HEARTBEAT_OK"));
+        assert!(is_quiet("`HEARTBEAT_OK`."));
+        assert!(is_quiet(""));
+        assert!(!is_quiet("CI is failing on main"));
+    }
+}
@@ -0,0 +1,26 @@
+//! Scheduled autonomous agent turns.
+//!
+//! - [`heartbeat`]: entries in the brain's `HEARTBEAT.md` (`every 30m: ...`,
+//!   `cron 0 9 * * mon-fri: ...`) run in a dedicated "Heartbeat" session
+//! - [`runner`]: prompts in the `schedules` table, managed with the `schedule`
+//!   tool or `/schedule`, each with a timezone, target session and run history
+//!
+//! Schedules only fire while the TUI or `opencrabs daemon` is running. Results
+//! are posted to the TUI or a messaging channel via [`Delivery`].
+
+pub mod cron;
+pub mod delivery;
+pub mod error;
+pub mod heartbeat;
+pub mod persistence;
+pub mod runner;
+pub mod schedule;
+pub mod store;
+
+pub use cron::CronExpr;
+pub use delivery::{Delivery, DeliveryChannel, DeliveryTarget};
+pub use error::{Result, SchedulerError};
+pub use heartbeat::{HeartbeatScheduler, HeartbeatTask};
+pub use runner::ScheduleRunner;
+pub use schedule::{Schedule, Zone};
+pub use store::{ScheduleRun, ScheduleStore, ScheduledPrompt};
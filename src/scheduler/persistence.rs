@@ -0,0 +1,101 @@
+//! SQLite persistence for scheduler run state.
+
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Last run time of every heartbeat entry that has run at least once.
+pub async fn load_heartbeat_runs(pool: &SqlitePool) -> HashMap<String, DateTime<Utc>> {
+    let rows: Vec<(String, i64)> =
+        match sqlx::query_as("SELECT task_key, last_run_at FROM heartbeat_runs")
+            .fetch_all(pool)
+            .await
+        {
+            Ok(rows) => rows,
+            Err(e) => {
+                tracing::error!("Heartbeat persistence: failed to load runs: {}", e);
+                return HashMap::new();
+            }
+        };
+
+    rows.into_iter()
+        .filter_map(|(key, at)| DateTime::from_timestamp(at, 0).map(|at| (key, at)))
+        .collect()
+}
+
+/// Record the outcome of a heartbeat run.
+pub async fn record_heartbeat_run(
+    pool: &SqlitePool,
+    task_key: &str,
+    ran_at: DateTime<Utc>,
+    status: &str,
+    output: &str,
+) {
+    let result = sqlx::query(
+        "INSERT INTO heartbeat_runs (task_key, last_run_at, last_status, last_output, run_count)
+         VALUES (?1, ?2, ?3, ?4, 1)
+         ON CONFLICT(task_key) DO UPDATE SET
+             last_run_at = ?2, last_status = ?3, last_output = ?4, run_count = run_count + 1",
+    )
+    .bind(task_key)
+    .bind(ran_at.timestamp())
+    .bind(status)
+    .bind(output)
+    .execute(pool)
+    .await;
+
+    if let Err(e) = result {
+        tracing::error!("Heartbeat persistence: failed to record run of '{}': {}", task_key, e);
+    }
+}
+
+/// Most recently used, non-archived session with this title.
+pub async fn find_session_by_title(pool: &SqlitePool, title: &str) -> Option<Uuid> {
+    let row: Option<(String,)> = sqlx::query_as(
+        "SELECT id FROM sessions WHERE title = ? AND archived_at IS NULL
+         ORDER BY updated_at DESC LIMIT 1",
+    )
+    .bind(title)
+    .fetch_optional(pool)
+    .await
+    .inspect_err(|e| tracing::error!("Heartbeat persistence: session lookup failed: {}", e))
+    .ok()
+    .flatten();
+
+    row.and_then(|(id,)| Uuid::parse_str(&id).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+
+    #[tokio::test]
+    async fn test_heartbeat_runs_roundtrip() {
+        let db = Database::connect_in_memory().await.unwrap();
+        db.run_migrations().await.unwrap();
+        let pool = db.pool();
+
+        assert!(load_heartbeat_runs(pool).await.is_empty());
+
+        let first = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let second = DateTime::from_timestamp(1_700_001_800, 0).unwrap();
+        record_heartbeat_run(pool, "every 30m: check CI", first, "quiet", "").await;
+        record_heartbeat_run(pool, "every 30m: check CI", second, "reported", "CI is red").await;
+
+        let runs = load_heartbeat_runs(pool).await;
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs["every 30m: check CI"], second);
+
+        let (count, status): (i64, String) = sqlx::query_as(
+            "SELECT run_count, last_status FROM heartbeat_runs WHERE task_key = ?",
+        )
+        .bind("every 30m: check CI")
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(status, "reported");
+    }
+}
@@ -0,0 +1,144 @@
+//! Runs scheduled prompts when they come due.
+
+use super::delivery::Delivery;
+use super::store::{ScheduleStore, ScheduledPrompt};
+use crate::brain::agent::AgentService;
+use crate::services::SessionService;
+use chrono::Utc;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+/// How often the `schedules` table is checked for due prompts by default
+pub const DEFAULT_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Background runner for the `schedules` table.
+pub struct ScheduleRunner {
+    agent: Arc<AgentService>,
+    store: ScheduleStore,
+    delivery: Delivery,
+    check_interval: Duration,
+}
+
+impl ScheduleRunner {
+    /// `agent` should be unattended (auto-approving) — scheduled runs have
+    /// nobody to answer approval prompts.
+    pub fn new(agent: Arc<AgentService>, store: ScheduleStore, delivery: Delivery) -> Self {
+        Self {
+            agent,
+            store,
+            delivery,
+            check_interval: DEFAULT_CHECK_INTERVAL,
+        }
+    }
+
+    pub fn with_check_interval(mut self, interval: Duration) -> Self {
+        self.check_interval = interval;
+        self
+    }
+
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.check_interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                self.check().await;
+            }
+        })
+    }
+
+    /// Run every due schedule, one after another. A schedule that was due
+    /// several times while nothing was running fires once.
+    async fn check(&self) {
+        let now = Utc::now();
+        let due = match self.store.due(now).await {
+            Ok(due) => due,
+            Err(e) => {
+                tracing::warn!("Schedules: {:#}", e);
+                return;
+            }
+        };
+
+        for schedule in due {
+            match self
+                .store
+                .claim(&schedule, now, schedule.next_run_after(now))
+                .await
+            {
+                Ok(true) => self.run(&schedule).await,
+                Ok(false) => {}
+                Err(e) => tracing::warn!("Schedule '{}': {:#}", schedule.name, e),
+            }
+        }
+    }
+
+    async fn run(&self, schedule: &ScheduledPrompt) {
+        tracing::info!("Schedule '{}': running", schedule.name);
+        let run_id = match self.store.start_run(schedule.id, Utc::now()).await {
+            Ok(id) => id,
+            Err(e) => {
+                tracing::warn!("Schedule '{}': {:#}", schedule.name, e);
+                return;
+            }
+        };
+
+        let (status, output, delivered) = match self.session(schedule).await {
+            None => ("failed", "Could not create a session".to_string(), false),
+            Some(session_id) => {
+                let prompt = format!(
+                    "[Scheduled: {}] {}\n\nThis is an automatic scheduled run; nobody is \
+                     waiting on a reply. Do the task and answer with the result.",
+                    schedule.name, schedule.prompt
+                );
+                match self
+                    .agent
+                    .send_message_with_tools(session_id, prompt, None)
+                    .await
+                {
+                    Ok(response) => {
+                        let report = format!("⏰ {}\n\n{}", schedule.name, response.content.trim());
+                        let target = schedule.delivery_target();
+                        let delivered = match self.delivery.deliver(target.as_ref(), &report).await {
+                            Ok(()) => true,
+                            Err(e) => {
+                                tracing::warn!("Schedule '{}': {}", schedule.name, e);
+                                false
+                            }
+                        };
+                        ("completed", response.content, delivered)
+                    }
+                    Err(e) => {
+                        tracing::warn!("Schedule '{}' failed: {}", schedule.name, e);
+                        ("failed", e.to_string(), false)
+                    }
+                }
+            }
+        };
+
+        if let Err(e) = self.store.finish_run(run_id, status, &output, delivered).await {
+            tracing::warn!("Schedule '{}': {:#}", schedule.name, e);
+        }
+    }
+
+    /// The schedule's target session, or a new one if it has none or it was deleted.
+    async fn session(&self, schedule: &ScheduledPrompt) -> Option<Uuid> {
+        let sessions = SessionService::new(self.agent.context().clone());
+        if let Some(id) = schedule.session_id
+            && let Ok(Some(_)) = sessions.get_session(id).await
+        {
+            return Some(id);
+        }
+
+        let session = sessions
+            .create_session(Some(format!("⏰ {}", schedule.name)))
+            .await
+            .inspect_err(|e| tracing::error!("Schedule '{}': {}", schedule.name, e))
+            .ok()?;
+        if let Err(e) = self.store.set_session(schedule.id, session.id).await {
+            tracing::warn!("Schedule '{}': {:#}", schedule.name, e);
+        }
+        Some(session.id)
+    }
+}
@@ -0,0 +1,173 @@
+//! When a task runs: a fixed interval or a cron expression, read in a timezone.
+
+use super::cron::CronExpr;
+use super::error::{Result, SchedulerError};
+use chrono::{DateTime, Local, TimeZone, Utc};
+use std::time::Duration;
+
+/// Shortest interval accepted — the scheduler only checks once a minute.
+const MIN_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A parsed schedule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Schedule {
+    /// `every 30m`, `every 1h30m`, `every 2d`
+    Every(Duration),
+    /// `cron 0 9 * * mon-fri`, a bare `0 9 * * mon-fri`, or `@daily`
+    Cron(CronExpr),
+}
+
+impl Schedule {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let trimmed = spec.trim();
+        let lower = trimmed.to_ascii_lowercase();
+        if let Some(interval) = lower.strip_prefix("every ") {
+            Ok(Self::Every(parse_interval(spec, interval)?))
+        } else if let Some(expr) = lower.strip_prefix("cron ") {
+            Ok(Self::Cron(CronExpr::parse(expr)?))
+        } else if lower.starts_with('@') || lower.split_whitespace().count() == 5 {
+            Ok(Self::Cron(CronExpr::parse(&lower)?))
+        } else {
+            Err(SchedulerError::invalid(
+                spec,
+                "expected 'every <interval>' or 'cron <expression>'",
+            ))
+        }
+    }
+
+    /// Next run after `after`. Cron fields are read in `after`'s timezone.
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        match self {
+            Self::Every(interval) => {
+                Some(after.clone() + chrono::Duration::from_std(*interval).ok()?)
+            }
+            Self::Cron(expr) => expr.next_after(after),
+        }
+    }
+}
+
+/// Timezone a schedule's cron fields are read in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Zone {
+    /// The machine's local time
+    Local,
+    /// An IANA zone such as `Europe/Berlin`
+    Named(chrono_tz::Tz),
+}
+
+impl Zone {
+    /// `None`, empty or `local` means local time; anything else must be an IANA name.
+    pub fn parse(name: Option<&str>) -> Result<Self> {
+        match name.map(str::trim) {
+            None | Some("") => Ok(Self::Local),
+            Some(name) if name.eq_ignore_ascii_case("local") => Ok(Self::Local),
+            Some(name) => name
+                .parse::<chrono_tz::Tz>()
+                .map(Self::Named)
+                .map_err(|_| SchedulerError::UnknownTimezone(name.to_string())),
+        }
+    }
+
+    /// Next run of `schedule` after `after`, evaluated in this zone.
+    pub fn next_run(&self, schedule: &Schedule, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Local => schedule
+                .next_after(&after.with_timezone(&Local))
+                .map(|t| t.with_timezone(&Utc)),
+            Self::Named(tz) => schedule
+                .next_after(&after.with_timezone(tz))
+                .map(|t| t.with_timezone(&Utc)),
+        }
+    }
+
+    /// `2026-03-02 09:00 CET`, in this zone
+    pub fn format(&self, t: DateTime<Utc>) -> String {
+        match self {
+            Self::Local => t.with_timezone(&Local).format("%Y-%m-%d %H:%M %Z").to_string(),
+            Self::Named(tz) => t.with_timezone(tz).format("%Y-%m-%d %H:%M %Z").to_string(),
+        }
+    }
+}
+
+/// Parse `30m`, `1h30m`, `2 hours`, `1d` into a duration.
+fn parse_interval(spec: &str, text: &str) -> Result<Duration> {
+    let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let mut total = 0u64;
+    let mut rest = compact.as_str();
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        let amount: u64 = rest[..digits]
+            .parse()
+            .map_err(|_| SchedulerError::invalid(spec, "interval must start with a number"))?;
+        rest = &rest[digits..];
+        let unit_len = rest.find(|c: char| c.is_ascii_digit()).unwrap_or(rest.len());
+        let seconds = match &rest[..unit_len] {
+            "s" | "sec" | "secs" | "second" | "seconds" => 1,
+            "m" | "min" | "mins" | "minute" | "minutes" => 60,
+            "h" | "hr" | "hrs" | "hour" | "hours" => 3600,
+            "d" | "day" | "days" => 86_400,
+            other => {
+                return Err(SchedulerError::invalid(
+                    spec,
+                    format!("unknown interval unit '{}' (use s, m, h or d)", other),
+                ));
+            }
+        };
+        rest = &rest[unit_len..];
+        total = total.saturating_add(amount.saturating_mul(seconds));
+    }
+
+    let interval = Duration::from_secs(total);
+    if interval < MIN_INTERVAL {
+        return Err(SchedulerError::invalid(spec, "interval must be at least 1 minute"));
+    }
+    Ok(interval)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_intervals() {
+        assert_eq!(
+            Schedule::parse("every 30m").unwrap(),
+            Schedule::Every(Duration::from_secs(1800))
+        );
+        assert_eq!(
+            Schedule::parse("Every 1h30m").unwrap(),
+            Schedule::Every(Duration::from_secs(5400))
+        );
+        assert_eq!(
+            Schedule::parse("every 2 hours").unwrap(),
+            Schedule::Every(Duration::from_secs(7200))
+        );
+        assert!(Schedule::parse("every 30s").is_err());
+        assert!(Schedule::parse("every 5 fortnights").is_err());
+        assert!(Schedule::parse("every m").is_err());
+        assert!(Schedule::parse("hourly").is_err());
+    }
+
+    #[test]
+    fn test_parse_cron() {
+        assert!(matches!(Schedule::parse("cron */15 * * * *"), Ok(Schedule::Cron(_))));
+        assert!(matches!(Schedule::parse("@daily"), Ok(Schedule::Cron(_))));
+        assert!(matches!(Schedule::parse("0 9 * * mon-fri"), Ok(Schedule::Cron(_))));
+        assert!(Schedule::parse("cron * * *").is_err());
+    }
+
+    #[test]
+    fn test_zone_next_run() {
+        let schedule = Schedule::parse("cron 0 9 * * *").unwrap();
+        let zone = Zone::parse(Some("America/New_York")).unwrap();
+        // 2026-07-01 12:00 UTC is 08:00 EDT, so the next 09:00 is 13:00 UTC
+        let after = DateTime::from_timestamp(1_782_907_200, 0).unwrap();
+        assert_eq!(
+            zone.next_run(&schedule, after).unwrap(),
+            DateTime::from_timestamp(1_782_910_800, 0).unwrap()
+        );
+        assert_eq!(Zone::parse(Some("local")).unwrap(), Zone::Local);
+        assert_eq!(Zone::parse(None).unwrap(), Zone::Local);
+        assert!(Zone::parse(Some("Mars/Olympus")).is_err());
+    }
+}
@@ -0,0 +1,430 @@
+//! Scheduled prompts and their run history, stored in SQLite.
+
+use super::delivery::{DeliveryChannel, DeliveryTarget};
+use super::error::Result as ScheduleResult;
+use super::schedule::{Schedule, Zone};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// A prompt that runs on a schedule.
+#[derive(Debug, Clone)]
+pub struct ScheduledPrompt {
+    pub id: Uuid,
+    pub name: String,
+    pub prompt: String,
+    /// Spec as entered, e.g. `cron 0 9 * * mon-fri`
+    pub schedule: String,
+    /// IANA timezone; `None` means local time
+    pub timezone: Option<String>,
+    /// Session runs are added to; created on the first run when `None`
+    pub session_id: Option<Uuid>,
+    pub deliver_to: Option<String>,
+    pub target: Option<String>,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub next_run_at: Option<DateTime<Utc>>,
+}
+
+impl ScheduledPrompt {
+    /// Validate the schedule, timezone and channel and compute the first run.
+    pub fn new(
+        name: String,
+        prompt: String,
+        schedule: String,
+        timezone: Option<String>,
+    ) -> ScheduleResult<Self> {
+        let parsed = Schedule::parse(&schedule)?;
+        let zone = Zone::parse(timezone.as_deref())?;
+        let now = Utc::now();
+        Ok(Self {
+            id: Uuid::new_v4(),
+            name,
+            prompt,
+            schedule,
+            timezone: timezone.filter(|tz| !tz.trim().is_empty()),
+            session_id: None,
+            deliver_to: None,
+            target: None,
+            enabled: true,
+            created_at: now,
+            last_run_at: None,
+            next_run_at: zone.next_run(&parsed, now),
+        })
+    }
+
+    /// Post results to `channel` (and `target`, if given) instead of the TUI.
+    pub fn with_delivery(
+        mut self,
+        channel: Option<String>,
+        target: Option<String>,
+    ) -> ScheduleResult<Self> {
+        if let Some(ref channel) = channel {
+            channel.parse::<DeliveryChannel>()?;
+        }
+        self.deliver_to = channel;
+        self.target = target;
+        Ok(self)
+    }
+
+    pub fn with_session(mut self, session_id: Option<Uuid>) -> Self {
+        self.session_id = session_id;
+        self
+    }
+
+    pub fn zone(&self) -> Zone {
+        Zone::parse(self.timezone.as_deref()).unwrap_or(Zone::Local)
+    }
+
+    /// Next run strictly after `after`, or `None` if the schedule never fires again.
+    pub fn next_run_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let schedule = Schedule::parse(&self.schedule).ok()?;
+        self.zone().next_run(&schedule, after)
+    }
+
+    pub fn delivery_target(&self) -> Option<DeliveryTarget> {
+        let channel = self.deliver_to.as_deref()?.parse().ok()?;
+        Some(DeliveryTarget::new(channel, self.target.clone()))
+    }
+
+    /// First 8 characters of the ID, enough to refer to it
+    pub fn short_id(&self) -> String {
+        self.id.to_string()[..8].to_string()
+    }
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::sqlite::SqliteRow> for ScheduledPrompt {
+    fn from_row(row: &'r sqlx::sqlite::SqliteRow) -> std::result::Result<Self, sqlx::Error> {
+        use sqlx::Row;
+
+        let timestamp = |column: &str| -> std::result::Result<Option<DateTime<Utc>>, sqlx::Error> {
+            Ok(row
+                .try_get::<Option<i64>, _>(column)?
+                .and_then(|ts| DateTime::from_timestamp(ts, 0)))
+        };
+
+        Ok(ScheduledPrompt {
+            id: Uuid::parse_str(row.try_get("id")?)
+                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+            name: row.try_get("name")?,
+            prompt: row.try_get("prompt")?,
+            schedule: row.try_get("schedule")?,
+            timezone: row.try_get("timezone")?,
+            session_id: row
+                .try_get::<Option<String>, _>("session_id")?
+                .and_then(|id| Uuid::parse_str(&id).ok()),
+            deliver_to: row.try_get("deliver_to")?,
+            target: row.try_get("target")?,
+            enabled: row.try_get("enabled")?,
+            created_at: timestamp("created_at")?
+                .ok_or_else(|| sqlx::Error::Decode("Invalid timestamp for created_at".into()))?,
+            last_run_at: timestamp("last_run_at")?,
+            next_run_at: timestamp("next_run_at")?,
+        })
+    }
+}
+
+/// One execution of a scheduled prompt.
+#[derive(Debug, Clone)]
+pub struct ScheduleRun {
+    pub id: i64,
+    pub schedule_id: Uuid,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// running, completed or failed
+    pub status: String,
+    pub output: Option<String>,
+    pub delivered: bool,
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::sqlite::SqliteRow> for ScheduleRun {
+    fn from_row(row: &'r sqlx::sqlite::SqliteRow) -> std::result::Result<Self, sqlx::Error> {
+        use sqlx::Row;
+
+        Ok(ScheduleRun {
+            id: row.try_get("id")?,
+            schedule_id: Uuid::parse_str(row.try_get("schedule_id")?)
+                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+            started_at: DateTime::from_timestamp(row.try_get("started_at")?, 0)
+                .ok_or_else(|| sqlx::Error::Decode("Invalid timestamp for started_at".into()))?,
+            finished_at: row
+                .try_get::<Option<i64>, _>("finished_at")?
+                .and_then(|ts| DateTime::from_timestamp(ts, 0)),
+            status: row.try_get("status")?,
+            output: row.try_get("output")?,
+            delivered: row.try_get("delivered")?,
+        })
+    }
+}
+
+/// Database access for scheduled prompts
+#[derive(Clone)]
+pub struct ScheduleStore {
+    pool: SqlitePool,
+}
+
+impl ScheduleStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, schedule: &ScheduledPrompt) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO schedules (id, name, prompt, schedule, timezone, session_id, deliver_to,
+                                   target, enabled, created_at, updated_at, last_run_at, next_run_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(schedule.id.to_string())
+        .bind(&schedule.name)
+        .bind(&schedule.prompt)
+        .bind(&schedule.schedule)
+        .bind(&schedule.timezone)
+        .bind(schedule.session_id.map(|id| id.to_string()))
+        .bind(&schedule.deliver_to)
+        .bind(&schedule.target)
+        .bind(schedule.enabled)
+        .bind(schedule.created_at.timestamp())
+        .bind(Utc::now().timestamp())
+        .bind(schedule.last_run_at.map(|t| t.timestamp()))
+        .bind(schedule.next_run_at.map(|t| t.timestamp()))
+        .execute(&self.pool)
+        .await
+        .context("Failed to create schedule")?;
+        Ok(())
+    }
+
+    pub async fn list(&self) -> Result<Vec<ScheduledPrompt>> {
+        sqlx::query_as::<_, ScheduledPrompt>("SELECT * FROM schedules ORDER BY created_at ASC")
+            .fetch_all(&self.pool)
+            .await
+            .context("Failed to list schedules")
+    }
+
+    /// Look up by full ID, ID prefix (4+ characters) or name.
+    pub async fn find(&self, key: &str) -> Result<Option<ScheduledPrompt>> {
+        let key = key.trim();
+        let all = self.list().await?;
+        if let Some(found) = all.iter().find(|s| s.id.to_string() == key || s.name == key) {
+            return Ok(Some(found.clone()));
+        }
+        let matches: Vec<&ScheduledPrompt> = all
+            .iter()
+            .filter(|s| {
+                (key.len() >= 4 && s.id.to_string().starts_with(key))
+                    || s.name.eq_ignore_ascii_case(key)
+            })
+            .collect();
+        match matches.as_slice() {
+            [only] => Ok(Some((*only).clone())),
+            [] => Ok(None),
+            _ => anyhow::bail!("'{}' matches more than one schedule — use the ID", key),
+        }
+    }
+
+    /// Enabled schedules whose next run is at or before `now`
+    pub async fn due(&self, now: DateTime<Utc>) -> Result<Vec<ScheduledPrompt>> {
+        sqlx::query_as::<_, ScheduledPrompt>(
+            "SELECT * FROM schedules WHERE enabled = 1 AND next_run_at IS NOT NULL
+             AND next_run_at <= ? ORDER BY next_run_at ASC",
+        )
+        .bind(now.timestamp())
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to load due schedules")
+    }
+
+    /// Move a due schedule to its next run. Returns false if another process
+    /// (TUI and daemon both running) already claimed this run.
+    pub async fn claim(
+        &self,
+        schedule: &ScheduledPrompt,
+        ran_at: DateTime<Utc>,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE schedules SET last_run_at = ?, next_run_at = ?, updated_at = ?
+             WHERE id = ? AND next_run_at IS ?",
+        )
+        .bind(ran_at.timestamp())
+        .bind(next_run_at.map(|t| t.timestamp()))
+        .bind(Utc::now().timestamp())
+        .bind(schedule.id.to_string())
+        .bind(schedule.next_run_at.map(|t| t.timestamp()))
+        .execute(&self.pool)
+        .await
+        .context("Failed to claim schedule run")?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Pause or resume. Resuming sets the next run from now.
+    pub async fn set_enabled(&self, schedule: &ScheduledPrompt, enabled: bool) -> Result<()> {
+        let next_run_at = if enabled {
+            schedule.next_run_after(Utc::now())
+        } else {
+            schedule.next_run_at
+        };
+        sqlx::query("UPDATE schedules SET enabled = ?, next_run_at = ?, updated_at = ? WHERE id = ?")
+            .bind(enabled)
+            .bind(next_run_at.map(|t| t.timestamp()))
+            .bind(Utc::now().timestamp())
+            .bind(schedule.id.to_string())
+            .execute(&self.pool)
+            .await
+            .context("Failed to update schedule")?;
+        Ok(())
+    }
+
+    pub async fn set_session(&self, id: Uuid, session_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE schedules SET session_id = ?, updated_at = ? WHERE id = ?")
+            .bind(session_id.to_string())
+            .bind(Utc::now().timestamp())
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .context("Failed to set schedule session")?;
+        Ok(())
+    }
+
+    /// Delete a schedule and its run history. Returns false if it didn't exist.
+    pub async fn delete(&self, id: Uuid) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM schedule_runs WHERE schedule_id = ?")
+            .bind(id.to_string())
+            .execute(&mut *tx)
+            .await
+            .context("Failed to delete schedule runs")?;
+        let result = sqlx::query("DELETE FROM schedules WHERE id = ?")
+            .bind(id.to_string())
+            .execute(&mut *tx)
+            .await
+            .context("Failed to delete schedule")?;
+        tx.commit().await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record the start of a run; returns the run ID.
+    pub async fn start_run(&self, schedule_id: Uuid, started_at: DateTime<Utc>) -> Result<i64> {
+        let result = sqlx::query(
+            "INSERT INTO schedule_runs (schedule_id, started_at, status) VALUES (?, ?, 'running')",
+        )
+        .bind(schedule_id.to_string())
+        .bind(started_at.timestamp())
+        .execute(&self.pool)
+        .await
+        .context("Failed to record schedule run")?;
+        Ok(result.last_insert_rowid())
+    }
+
+    pub async fn finish_run(
+        &self,
+        run_id: i64,
+        status: &str,
+        output: &str,
+        delivered: bool,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE schedule_runs SET finished_at = ?, status = ?, output = ?, delivered = ?
+             WHERE id = ?",
+        )
+        .bind(Utc::now().timestamp())
+        .bind(status)
+        .bind(output)
+        .bind(delivered)
+        .bind(run_id)
+        .execute(&self.pool)
+        .await
+        .context("Failed to finish schedule run")?;
+        Ok(())
+    }
+
+    /// Most recent runs first
+    pub async fn runs(&self, schedule_id: Uuid, limit: i64) -> Result<Vec<ScheduleRun>> {
+        sqlx::query_as::<_, ScheduleRun>(
+            "SELECT * FROM schedule_runs WHERE schedule_id = ?
+             ORDER BY started_at DESC, id DESC LIMIT ?",
+        )
+        .bind(schedule_id.to_string())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to load schedule runs")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+
+    async fn store() -> ScheduleStore {
+        let db = Database::connect_in_memory().await.unwrap();
+        db.run_migrations().await.unwrap();
+        ScheduleStore::new(db.pool().clone())
+    }
+
+    fn daily(name: &str) -> ScheduledPrompt {
+        ScheduledPrompt::new(
+            name.to_string(),
+            "summarize yesterday's merged PRs".to_string(),
+            "cron 0 9 * * mon-fri".to_string(),
+            Some("Europe/Berlin".to_string()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_new_validates() {
+        assert!(daily("prs").next_run_at.is_some());
+        assert!(ScheduledPrompt::new("x".into(), "p".into(), "sometimes".into(), None).is_err());
+        assert!(ScheduledPrompt::new("x".into(), "p".into(), "every 1h".into(), Some("Nowhere".into())).is_err());
+        assert!(daily("prs").with_delivery(Some("slack_send".into()), None).is_ok());
+        assert!(daily("prs").with_delivery(Some("fax".into()), None).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_find_pause_delete() {
+        let store = store().await;
+        let schedule = daily("PR digest")
+            .with_delivery(Some("slack".into()), Some("C0123".into()))
+            .unwrap();
+        store.create(&schedule).await.unwrap();
+
+        let found = store.find("pr digest").await.unwrap().unwrap();
+        assert_eq!(found.id, schedule.id);
+        assert_eq!(found.timezone.as_deref(), Some("Europe/Berlin"));
+        assert_eq!(found.delivery_target().unwrap().recipient.as_deref(), Some("C0123"));
+        assert_eq!(store.find(&schedule.short_id()).await.unwrap().unwrap().id, schedule.id);
+
+        store.set_enabled(&found, false).await.unwrap();
+        assert!(store.due(Utc::now() + chrono::Duration::days(30)).await.unwrap().is_empty());
+        store.set_enabled(&found, true).await.unwrap();
+        assert_eq!(store.due(Utc::now() + chrono::Duration::days(30)).await.unwrap().len(), 1);
+
+        assert!(store.delete(schedule.id).await.unwrap());
+        assert!(store.find("PR digest").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_claim_and_history() {
+        let store = store().await;
+        let schedule = daily("digest");
+        store.create(&schedule).await.unwrap();
+
+        let now = Utc::now();
+        let next = schedule.next_run_after(now);
+        assert!(store.claim(&schedule, now, next).await.unwrap());
+        // A second process holding the stale row loses the race
+        assert!(!store.claim(&schedule, now, next).await.unwrap());
+
+        let run = store.start_run(schedule.id, now).await.unwrap();
+        store.finish_run(run, "completed", "3 PRs merged", true).await.unwrap();
+        let runs = store.runs(schedule.id, 10).await.unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].status, "completed");
+        assert!(runs[0].delivered);
+    }
+}
@@ -0,0 +1,339 @@
+//! Checkpoint Service
+//!
+//! Snapshots files before the agent's tools change them, one checkpoint per
+//! turn, and puts them back for `/undo` and `/checkpoints restore`.
+
+use crate::db::{models::Checkpoint, repository::CheckpointRepository};
+use crate::services::ServiceContext;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+/// Checkpoints kept per session; older ones are dropped
+const MAX_CHECKPOINTS_PER_SESSION: i64 = 50;
+
+/// Largest file saved to a checkpoint; bigger ones are not snapshotted
+const MAX_SNAPSHOT_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Service for managing file checkpoints
+#[derive(Clone)]
+pub struct CheckpointService {
+    context: ServiceContext,
+}
+
+/// A checkpoint with the paths it saved
+#[derive(Debug, Clone)]
+pub struct CheckpointSummary {
+    pub checkpoint: Checkpoint,
+    pub paths: Vec<String>,
+}
+
+/// What a restore did to one file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RestoredFile {
+    /// Prior content written back
+    Restored(PathBuf),
+    /// Created during the turn, so removed
+    Removed(PathBuf),
+}
+
+impl CheckpointService {
+    /// Create a new checkpoint service
+    pub fn new(context: ServiceContext) -> Self {
+        Self { context }
+    }
+
+    /// Start recording a turn. Nothing is stored until a tool snapshots a file.
+    pub fn begin_turn(
+        &self,
+        session_id: Uuid,
+        message_id: Option<Uuid>,
+        prompt: &str,
+    ) -> TurnCheckpoint {
+        let prompt = prompt
+            .lines()
+            .find(|l| !l.trim().is_empty())
+            .unwrap_or("")
+            .trim();
+        let prompt = if prompt.len() > 120 {
+            format!("{}…", &prompt[..prompt.floor_char_boundary(120)])
+        } else {
+            prompt.to_string()
+        };
+
+        TurnCheckpoint {
+            service: self.clone(),
+            checkpoint: Checkpoint {
+                id: Uuid::new_v4(),
+                session_id,
+                message_id,
+                prompt,
+                created_at: Utc::now(),
+                restored_at: None,
+            },
+            saved: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// List a session's checkpoints, newest first
+    pub async fn list_for_session(&self, session_id: Uuid) -> Result<Vec<CheckpointSummary>> {
+        let repo = CheckpointRepository::new(self.context.pool());
+        let checkpoints = repo
+            .find_by_session(session_id)
+            .await
+            .context("Failed to list checkpoints")?;
+
+        let mut summaries = Vec::with_capacity(checkpoints.len());
+        for checkpoint in checkpoints {
+            let paths = repo.paths(checkpoint.id).await?;
+            summaries.push(CheckpointSummary { checkpoint, paths });
+        }
+        Ok(summaries)
+    }
+
+    /// Revert the latest turn whose changes are still applied.
+    /// Returns `None` when there is nothing left to undo.
+    pub async fn undo_last(
+        &self,
+        session_id: Uuid,
+    ) -> Result<Option<(Checkpoint, Vec<RestoredFile>)>> {
+        let repo = CheckpointRepository::new(self.context.pool());
+        let latest = repo
+            .find_by_session(session_id)
+            .await?
+            .into_iter()
+            .find(|c| c.restored_at.is_none());
+
+        match latest {
+            Some(checkpoint) => {
+                let restored = self.restore(&checkpoint).await?;
+                Ok(Some((checkpoint, restored)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Put files back the way they were before `checkpoint_id`'s turn,
+    /// reverting every later turn first.
+    pub async fn restore_to(
+        &self,
+        session_id: Uuid,
+        checkpoint_id: Uuid,
+    ) -> Result<Vec<RestoredFile>> {
+        let repo = CheckpointRepository::new(self.context.pool());
+        let checkpoints = repo.find_by_session(session_id).await?;
+        let Some(index) = checkpoints.iter().position(|c| c.id == checkpoint_id) else {
+            anyhow::bail!("Checkpoint not found: {}", checkpoint_id);
+        };
+
+        // Newest first, so each file ends at its oldest saved content
+        let mut restored = Vec::new();
+        for checkpoint in checkpoints[..=index]
+            .iter()
+            .filter(|c| c.restored_at.is_none())
+        {
+            restored.extend(self.restore(checkpoint).await?);
+        }
+        Ok(restored)
+    }
+
+    /// Write back one checkpoint's files and mark it restored
+    async fn restore(&self, checkpoint: &Checkpoint) -> Result<Vec<RestoredFile>> {
+        let repo = CheckpointRepository::new(self.context.pool());
+        let files = repo.files(checkpoint.id).await?;
+
+        let mut restored = Vec::with_capacity(files.len());
+        for file in files {
+            match file.content {
+                Some(content) => {
+                    if let Some(parent) = file.path.parent() {
+                        tokio::fs::create_dir_all(parent)
+                            .await
+                            .with_context(|| format!("Failed to recreate {}", parent.display()))?;
+                    }
+                    tokio::fs::write(&file.path, content)
+                        .await
+                        .with_context(|| format!("Failed to restore {}", file.path.display()))?;
+                    restored.push(RestoredFile::Restored(file.path));
+                }
+                None => {
+                    match tokio::fs::remove_file(&file.path).await {
+                        Ok(()) => {}
+                        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                        Err(e) => {
+                            return Err(e).with_context(|| {
+                                format!("Failed to remove {}", file.path.display())
+                            });
+                        }
+                    }
+                    restored.push(RestoredFile::Removed(file.path));
+                }
+            }
+        }
+
+        repo.mark_restored(checkpoint.id, Utc::now()).await?;
+        tracing::info!(
+            "Restored checkpoint {} ({} files) in session {}",
+            checkpoint.id,
+            restored.len(),
+            checkpoint.session_id
+        );
+        Ok(restored)
+    }
+}
+
+/// Checkpoint for one agent turn, handed to tools through the
+/// `ToolExecutionContext`
+pub struct TurnCheckpoint {
+    service: CheckpointService,
+    checkpoint: Checkpoint,
+    /// Paths saved so far; the row is created with the first one
+    saved: Mutex<Vec<PathBuf>>,
+}
+
+impl TurnCheckpoint {
+    /// Save `path`'s current content (or its absence) before a tool changes it.
+    /// Later snapshots of the same path in this turn are ignored. Files over
+    /// `MAX_SNAPSHOT_BYTES` are refused rather than copied into the database.
+    pub async fn snapshot(&self, path: &Path) -> Result<()> {
+        let mut saved = self.saved.lock().await;
+        if saved.iter().any(|p| p == path) {
+            return Ok(());
+        }
+
+        if let Ok(metadata) = tokio::fs::metadata(path).await
+            && metadata.len() > MAX_SNAPSHOT_BYTES
+        {
+            anyhow::bail!(
+                "{} is over {} MB, too large to checkpoint",
+                path.display(),
+                MAX_SNAPSHOT_BYTES / (1024 * 1024)
+            );
+        }
+
+        let content = match tokio::fs::read(path).await {
+            Ok(bytes) => Some(bytes),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read {}", path.display()));
+            }
+        };
+
+        let repo = CheckpointRepository::new(self.service.context.pool());
+        if saved.is_empty() {
+            repo.create(&self.checkpoint).await?;
+            repo.prune(self.checkpoint.session_id, MAX_CHECKPOINTS_PER_SESSION)
+                .await?;
+        }
+        repo.add_file(self.checkpoint.id, path, content.as_deref())
+            .await?;
+
+        saved.push(path.to_path_buf());
+        Ok(())
+    }
+
+    /// Checkpoint ID
+    pub fn id(&self) -> Uuid {
+        self.checkpoint.id
+    }
+
+    /// Files changed so far this turn
+    pub async fn files(&self) -> Vec<PathBuf> {
+        self.saved.lock().await.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::SessionService;
+    use tempfile::TempDir;
+
+    async fn create_test_service() -> (CheckpointService, Uuid) {
+        use crate::db::Database;
+
+        let db = Database::connect_in_memory().await.unwrap();
+        db.run_migrations().await.unwrap();
+        let context = ServiceContext::new(db.pool().clone());
+
+        let session = SessionService::new(context.clone())
+            .create_session(Some("Test Session".to_string()))
+            .await
+            .unwrap();
+        (CheckpointService::new(context), session.id)
+    }
+
+    #[tokio::test]
+    async fn test_undo_restores_edits_and_removes_new_files() {
+        let (service, session_id) = create_test_service().await;
+        let dir = TempDir::new().unwrap();
+        let edited = dir.path().join("edited.txt");
+        let created = dir.path().join("created.txt");
+        std::fs::write(&edited, "before").unwrap();
+
+        let turn = service.begin_turn(session_id, None, "change things\nmore detail");
+        turn.snapshot(&edited).await.unwrap();
+        std::fs::write(&edited, "after").unwrap();
+        turn.snapshot(&edited).await.unwrap();
+        std::fs::write(&edited, "after again").unwrap();
+        turn.snapshot(&created).await.unwrap();
+        std::fs::write(&created, "new").unwrap();
+        assert_eq!(turn.files().await.len(), 2);
+
+        let (checkpoint, restored) = service.undo_last(session_id).await.unwrap().unwrap();
+        assert_eq!(checkpoint.prompt, "change things");
+        assert_eq!(restored.len(), 2);
+        assert_eq!(std::fs::read_to_string(&edited).unwrap(), "before");
+        assert!(!created.exists());
+
+        // Already undone
+        assert!(service.undo_last(session_id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_restore_to_reverts_later_turns() {
+        let (service, session_id) = create_test_service().await;
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("file.txt");
+        std::fs::write(&file, "v1").unwrap();
+
+        let first = service.begin_turn(session_id, None, "first");
+        first.snapshot(&file).await.unwrap();
+        std::fs::write(&file, "v2").unwrap();
+
+        let second = service.begin_turn(session_id, None, "second");
+        second.snapshot(&file).await.unwrap();
+        std::fs::write(&file, "v3").unwrap();
+
+        // A turn that touched nothing leaves no checkpoint
+        let _ = service.begin_turn(session_id, None, "chat only");
+
+        let listed = service.list_for_session(session_id).await.unwrap();
+        assert_eq!(listed.len(), 2);
+
+        service.restore_to(session_id, first.id()).await.unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "v1");
+
+        let listed = service.list_for_session(session_id).await.unwrap();
+        assert!(listed.iter().all(|s| s.checkpoint.restored_at.is_some()));
+    }
+
+    #[tokio::test]
+    async fn test_large_files_are_not_snapshotted() {
+        let (service, session_id) = create_test_service().await;
+        let dir = TempDir::new().unwrap();
+        let large = dir.path().join("large.bin");
+        std::fs::File::create(&large)
+            .unwrap()
+            .set_len(MAX_SNAPSHOT_BYTES + 1)
+            .unwrap();
+
+        let turn = service.begin_turn(session_id, None, "rewrite the dump");
+        assert!(turn.snapshot(&large).await.is_err());
+        assert!(turn.files().await.is_empty());
+        let listed = service.list_for_session(session_id).await.unwrap();
+        assert!(listed.is_empty());
+    }
+}
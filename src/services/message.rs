@@ -41,6 +41,8 @@ impl MessageService {
             created_at: Utc::now(),
             token_count: None,
             cost: None,
+            cache_creation_tokens: None,
+            cache_read_tokens: None,
         };
 
         repo.create(&message)
@@ -88,6 +90,37 @@ impl MessageService {
         Ok(())
     }
 
+    /// Update message usage statistics, including prompt-cache token counts
+    pub async fn update_message_usage_with_cache(
+        &self,
+        id: Uuid,
+        token_count: i32,
+        cost: f64,
+        cache_creation_tokens: i32,
+        cache_read_tokens: i32,
+    ) -> Result<()> {
+        let mut message = self.get_message_required(id).await?;
+        message.token_count = Some(token_count);
+        message.cost = Some(cost);
+        message.cache_creation_tokens = Some(cache_creation_tokens);
+        message.cache_read_tokens = Some(cache_read_tokens);
+
+        let repo = MessageRepository::new(self.context.pool());
+        repo.update(&message)
+            .await
+            .context("Failed to update message usage")?;
+
+        tracing::debug!(
+            "Updated message usage: {} ({} tokens, {} cache write, {} cache read, ${:.4})",
+            id,
+            token_count,
+            cache_creation_tokens,
+            cache_read_tokens,
+            cost
+        );
+        Ok(())
+    }
+
     /// Update message usage statistics
     pub async fn update_message_usage(&self, id: Uuid, token_count: i32, cost: f64) -> Result<()> {
         let mut message = self.get_message_required(id).await?;
@@ -277,6 +310,32 @@ mod tests {
         assert_eq!(updated.cost, Some(0.05));
     }
 
+    #[tokio::test]
+    async fn test_update_message_usage_with_cache() {
+        let (message_service, session_service) = create_test_service().await;
+        let session = session_service
+            .create_session(Some("Test".to_string()))
+            .await
+            .unwrap();
+
+        let message = message_service
+            .create_message(session.id, "assistant".to_string(), "Test".to_string())
+            .await
+            .unwrap();
+
+        message_service
+            .update_message_usage_with_cache(message.id, 5000, 0.02, 1200, 3500)
+            .await
+            .unwrap();
+
+        let updated = message_service
+            .get_message_required(message.id)
+            .await
+            .unwrap();
+        assert_eq!(updated.cache_creation_tokens, Some(1200));
+        assert_eq!(updated.cache_read_tokens, Some(3500));
+    }
+
     #[tokio::test]
     async fn test_delete_message() {
         let (message_service, session_service) = create_test_service().await;
@@ -3,12 +3,14 @@
 //! This module contains the business logic services that orchestrate
 //! operations between the database layer and the application layer.
 
+pub mod checkpoint;
 mod context;
 pub mod file;
 pub mod message;
 pub mod plan;
 pub mod session;
 
+pub use checkpoint::{CheckpointService, RestoredFile, TurnCheckpoint};
 pub use context::{ServiceContext, ServiceManager};
 pub use file::FileService;
 pub use message::MessageService;
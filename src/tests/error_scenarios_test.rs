@@ -101,7 +101,7 @@ async fn create_error_agent(
     let mut tool_registry = ToolRegistry::new();
     tool_registry.register(Arc::new(ReadTool));
     tool_registry.register(Arc::new(WriteTool));
-    tool_registry.register(Arc::new(BashTool));
+    tool_registry.register(Arc::new(BashTool::new()));
 
     let agent_service = AgentService::new(provider, service_context.clone())
         .with_tool_registry(Arc::new(tool_registry));
@@ -330,6 +330,7 @@ impl Provider for WorkingMockProvider {
             usage: TokenUsage {
                 input_tokens: 10,
                 output_tokens: 20,
+                ..Default::default()
             },
         })
     }
@@ -29,6 +29,7 @@ impl StreamingMockProvider {
                 usage: TokenUsage {
                     input_tokens: 10,
                     output_tokens: 0,
+                    ..Default::default()
                 },
             },
         }];
@@ -63,6 +64,7 @@ impl StreamingMockProvider {
             usage: TokenUsage {
                 input_tokens: 10,
                 output_tokens: 20,
+                ..Default::default()
             },
         });
 
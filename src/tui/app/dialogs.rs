@@ -698,8 +698,10 @@ impl App {
         )
         .with_max_tokens(65536);
 
-        // Call the provider
-        match provider.complete(request).await {
+        // Call the provider; failover switches were logged as they happened
+        let result = provider.complete(request).await;
+        provider.take_switches();
+        match result {
             Ok(response) => {
                 // Extract text from response
                 let text: String = response
@@ -213,15 +213,23 @@ impl App {
                 return Ok(());
             } else if keys::is_enter(&event) || keys::is_submit(&event) {
                 // Confirm: Yes(0)=approve once, Always(1)=approve always, No(2)=deny
-                let approval_data: Option<(Uuid, usize, mpsc::UnboundedSender<ToolApprovalResponse>)> = self
+                let approval_data = self
                     .messages
                     .iter()
                     .rev()
                     .find_map(|m| m.approval.as_ref())
                     .filter(|a| a.state == ApprovalState::Pending)
-                    .map(|a| (a.request_id, a.selected_option, a.response_tx.clone()));
+                    .map(|a| {
+                        (
+                            a.request_id,
+                            a.selected_option,
+                            a.response_tx.clone(),
+                            a.tool_name.clone(),
+                            a.tool_input.clone(),
+                        )
+                    });
 
-                if let Some((request_id, selected, response_tx)) = approval_data {
+                if let Some((request_id, selected, response_tx, tool_name, tool_input)) = approval_data {
                     if selected == 2 {
                         // "No" — deny
                         let response = ToolApprovalResponse {
@@ -241,8 +249,18 @@ impl App {
                             ApprovalOption::AllowOnce
                         };
                         if matches!(option, ApprovalOption::AllowAlways) {
-                            self.approval_auto_session = true;
-                            self.push_system_message("Auto-approve enabled for this session. Use /approve to reset.".to_string());
+                            let rule = self.agent_service.allow_always(&tool_name, &tool_input);
+                            let message = match rule {
+                                Some(rule) => format!(
+                                    "Always allowing `{}` — saved to [permissions] in config.toml",
+                                    rule
+                                ),
+                                None => format!(
+                                    "Allowed `{}` once — no rule can allow only calls like this",
+                                    tool_name
+                                ),
+                            };
+                            self.push_system_message(message);
                         }
                         let response = ToolApprovalResponse {
                             request_id,
@@ -304,6 +322,8 @@ impl App {
                     !group.expanded
                 } else if let Some(msg) = self.messages.iter().rev().find(|m| m.tool_group.is_some()) {
                     !msg.tool_group.as_ref().expect("checked").expanded
+                } else if let Some(msg) = self.messages.iter().rev().find(|m| m.role == "thinking") {
+                    !msg.expanded
                 } else {
                     true
                 };
@@ -313,6 +333,8 @@ impl App {
                 for msg in self.messages.iter_mut() {
                     if let Some(ref mut group) = msg.tool_group {
                         group.expanded = target;
+                    } else if msg.role == "thinking" {
+                        msg.expanded = target;
                     }
                 }
                 return Ok(());
@@ -601,13 +623,16 @@ impl App {
                 // Load more history from DB
                 self.load_more_history().await?;
             } else {
-                // Ctrl+O — toggle expand/collapse on ALL tool groups in the session
+                // Ctrl+O — toggle expand/collapse on ALL tool groups and thinking blocks in the session
                 // Determine target state from the active group or most recent group
                 let target = if let Some(ref group) = self.active_tool_group {
                     !group.expanded
                 } else if let Some(msg) = self.messages.iter().rev()
                     .find(|m| m.tool_group.is_some()) {
                     !msg.tool_group.as_ref().expect("tool_group checked is_some above").expanded
+                } else if let Some(msg) = self.messages.iter().rev()
+                    .find(|m| m.role == "thinking") {
+                    !msg.expanded
                 } else {
                     true
                 };
@@ -617,6 +642,8 @@ impl App {
                 for msg in self.messages.iter_mut() {
                     if let Some(ref mut group) = msg.tool_group {
                         group.expanded = target;
+                    } else if msg.role == "thinking" {
+                        msg.expanded = target;
                     }
                 }
             }
@@ -815,6 +842,10 @@ impl App {
                     .map(|s| s.id == session_id)
                     .unwrap_or(false);
                 self.session_service.delete_session(session_id).await?;
+                // The session has ended — stop its background processes
+                if let Some(ref processes) = self.process_manager {
+                    processes.kill_session(session_id).await;
+                }
                 if is_current {
                     self.current_session = None;
                     self.messages.clear();
@@ -831,6 +862,46 @@ impl App {
         Ok(())
     }
 
+    /// Handle keys in the background processes panel
+    pub(crate) async fn handle_processes_key(&mut self, event: crossterm::event::KeyEvent) -> Result<()> {
+        use super::events::keys;
+        use crossterm::event::KeyCode;
+
+        let Some(manager) = self.process_manager.clone() else {
+            return self.switch_mode(AppMode::Chat).await;
+        };
+        let processes = manager.list(None);
+
+        if keys::is_cancel(&event) {
+            self.switch_mode(AppMode::Chat).await?;
+        } else if keys::is_up(&event) {
+            self.selected_process_index = self.selected_process_index.saturating_sub(1);
+        } else if keys::is_down(&event) {
+            if self.selected_process_index + 1 < processes.len() {
+                self.selected_process_index += 1;
+            }
+        } else if (event.code == KeyCode::Char('k') || event.code == KeyCode::Char('K'))
+            && let Some(process) = processes.get(self.selected_process_index)
+            && process.status == crate::brain::tools::process::ProcessStatus::Running
+        {
+            // Stopping can take a few seconds, so don't hold up the UI
+            let handle = process.handle.clone();
+            let sender = self.event_sender();
+            tokio::spawn(async move {
+                if let Some(info) = manager.kill(&handle).await {
+                    let _ = sender.send(TuiEvent::SystemMessage(format!(
+                        "Background process {} {}: {}",
+                        info.handle,
+                        info.status_label(),
+                        info.command
+                    )));
+                }
+            });
+        }
+
+        Ok(())
+    }
+
     /// Handle keys in plan mode
     pub(crate) async fn handle_plan_key(&mut self, event: crossterm::event::KeyEvent) -> Result<()> {
         use super::events::keys;
@@ -5,11 +5,17 @@ use super::dialogs::ensure_whispercrabs;
 use super::events::{AppMode, ToolApprovalResponse, TuiEvent};
 use super::onboarding::OnboardingWizard;
 use crate::brain::SelfUpdater;
+use crate::brain::tools::ToolExecutionContext;
+use crate::services::{CheckpointService, RestoredFile};
 use anyhow::Result;
 use serde_json::Value;
 use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
+/// Checkpoints only hold what file tools saved; shown with /undo and /checkpoints
+const SHELL_CHANGES_NOTE: &str =
+    "Changes made through bash, execute_code or process are not checkpointed and stay as they are.";
+
 impl App {
     /// Create a new session
     pub(crate) async fn create_new_session(&mut self) -> Result<()> {
@@ -355,6 +361,39 @@ impl App {
                 let _ = self.open_directory_picker().await;
                 true
             }
+            "/schedule" => {
+                self.handle_schedule_command(input.trim_start_matches("/schedule").trim())
+                    .await;
+                true
+            }
+            "/debate" => {
+                self.handle_debate_command(input.trim_start_matches("/debate").trim())
+                    .await;
+                true
+            }
+            "/undo" => {
+                self.handle_undo_command().await;
+                true
+            }
+            "/checkpoints" => {
+                self.handle_checkpoints_command(input.trim_start_matches("/checkpoints").trim())
+                    .await;
+                true
+            }
+            "/processes" => {
+                match self.process_manager {
+                    Some(ref manager) => {
+                        let count = manager.list(None).len();
+                        self.selected_process_index =
+                            self.selected_process_index.min(count.saturating_sub(1));
+                        self.mode = AppMode::Processes;
+                    }
+                    None => self.push_system_message(
+                        "Background processes are not available here.".to_string(),
+                    ),
+                }
+                true
+            }
             _ if input.starts_with('/') => {
                 // Check user-defined commands
                 if let Some(user_cmd) = self.user_commands.iter().find(|c| c.name == cmd) {
@@ -382,6 +421,243 @@ impl App {
         }
     }
 
+    /// `/schedule [list|pause|resume|delete|history <id>]` runs the schedule tool
+    /// directly; anything else is handed to the agent to create a schedule.
+    async fn handle_schedule_command(&mut self, args: &str) {
+        let (operation, id) = args.split_once(' ').unwrap_or((args, ""));
+        let input = match operation {
+            "" | "list" => serde_json::json!({ "operation": "list" }),
+            "pause" | "resume" | "delete" | "history" if !id.trim().is_empty() => {
+                serde_json::json!({ "operation": operation, "id": id.trim() })
+            }
+            "pause" | "resume" | "delete" | "history" => {
+                self.push_system_message(format!("Usage: /schedule {} <id or name>", operation));
+                return;
+            }
+            _ => {
+                let _ = self.event_sender().send(TuiEvent::MessageSubmitted(format!(
+                    "Use the schedule tool to create this scheduled prompt: {}",
+                    args
+                )));
+                return;
+            }
+        };
+
+        let session_id = self
+            .current_session
+            .as_ref()
+            .map(|s| s.id)
+            .unwrap_or_else(Uuid::nil);
+        // Typed by the user, so no approval prompt
+        let context = ToolExecutionContext::new(session_id)
+            .with_working_directory(self.agent_service.working_directory())
+            .with_auto_approve(true);
+        match self
+            .agent_service
+            .tool_registry()
+            .execute("schedule", input, &context)
+            .await
+        {
+            Ok(result) if result.success => self.push_system_message(result.output),
+            Ok(result) => self.push_system_message(result.error.unwrap_or(result.output)),
+            Err(e) => self.push_system_message(format!("Schedule: {}", e)),
+        }
+    }
+
+    /// `/debate <topic>` starts a Bee Colony debate and opens its panel;
+    /// `/debate` alone shows the running or last saved debate.
+    async fn handle_debate_command(&mut self, topic: &str) {
+        if topic.is_empty() {
+            if self.debate.is_none() {
+                let pool = self.agent_service.context().pool();
+                self.debate = crate::a2a::persistence::load_debate(&pool, None).await;
+                self.debate_scroll_offset = 0;
+            }
+            if self.debate.is_some() {
+                self.mode = AppMode::Debate;
+            } else {
+                self.push_system_message(
+                    "No debates yet. Start one with /debate <topic>.".to_string(),
+                );
+            }
+            return;
+        }
+
+        let session_id = self
+            .current_session
+            .as_ref()
+            .map(|s| s.id)
+            .unwrap_or_else(Uuid::nil);
+        // Typed by the user, so no approval prompt
+        let context = ToolExecutionContext::new(session_id)
+            .with_working_directory(self.agent_service.working_directory())
+            .with_auto_approve(true);
+        let registry = self.agent_service.tool_registry().clone();
+        let input = serde_json::json!({ "topic": topic });
+        let sender = self.event_sender();
+        tokio::spawn(async move {
+            let message = match registry.execute("debate", input, &context).await {
+                Ok(result) if result.success => result.output,
+                Ok(result) => result.error.unwrap_or(result.output),
+                Err(e) => format!("Debate: {}", e),
+            };
+            let _ = sender.send(TuiEvent::SystemMessage(message));
+        });
+
+        self.debate = None;
+        self.debate_scroll_offset = 0;
+        self.mode = AppMode::Debate;
+    }
+
+    /// `/undo` reverts the file changes of the latest agent turn that still has them.
+    async fn handle_undo_command(&mut self) {
+        if self.is_processing {
+            self.push_system_message("Wait for the agent to finish before /undo.".to_string());
+            return;
+        }
+        let Some(session_id) = self.current_session.as_ref().map(|s| s.id) else {
+            return;
+        };
+
+        let checkpoints = CheckpointService::new(self.agent_service.context().clone());
+        match checkpoints.undo_last(session_id).await {
+            Ok(Some((checkpoint, restored))) => {
+                let message = format!(
+                    "Undid \"{}\":\n{}\n\n{}",
+                    checkpoint.prompt,
+                    self.format_restored_files(&restored),
+                    SHELL_CHANGES_NOTE
+                );
+                self.push_system_message(message);
+            }
+            Ok(None) => self.push_system_message(format!(
+                "Nothing to undo — no file tool has changed files in this session. {}",
+                SHELL_CHANGES_NOTE
+            )),
+            Err(e) => self.push_system_message(format!("Undo failed: {:#}", e)),
+        }
+    }
+
+    /// `/checkpoints` lists the session's checkpoints, newest first;
+    /// `/checkpoints restore <n>` rewinds files to before the n-th one.
+    async fn handle_checkpoints_command(&mut self, args: &str) {
+        let Some(session_id) = self.current_session.as_ref().map(|s| s.id) else {
+            return;
+        };
+        let checkpoints = CheckpointService::new(self.agent_service.context().clone());
+        let listed = match checkpoints.list_for_session(session_id).await {
+            Ok(listed) => listed,
+            Err(e) => {
+                self.push_system_message(format!("Checkpoints: {:#}", e));
+                return;
+            }
+        };
+
+        let (operation, number) = args.split_once(' ').unwrap_or((args, ""));
+        match operation {
+            "" | "list" => {
+                if listed.is_empty() {
+                    self.push_system_message(
+                        "No checkpoints yet. One is saved each turn the agent changes files."
+                            .to_string(),
+                    );
+                    return;
+                }
+                let mut message = "Checkpoints (newest first):\n".to_string();
+                for (i, summary) in listed.iter().enumerate() {
+                    let checkpoint = &summary.checkpoint;
+                    message.push_str(&format!(
+                        "  {}. {}{} \"{}\" — {}\n",
+                        i + 1,
+                        checkpoint
+                            .created_at
+                            .with_timezone(&chrono::Local)
+                            .format("%Y-%m-%d %H:%M"),
+                        if checkpoint.restored_at.is_some() {
+                            " [undone]"
+                        } else {
+                            ""
+                        },
+                        checkpoint.prompt,
+                        summary
+                            .paths
+                            .iter()
+                            .map(|p| self.display_path(std::path::Path::new(p)))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ));
+                }
+                message.push_str(
+                    "\nRewind files to before a turn with /checkpoints restore <number>.\n",
+                );
+                message.push_str(SHELL_CHANGES_NOTE);
+                self.push_system_message(message);
+            }
+            "restore" => {
+                if self.is_processing {
+                    self.push_system_message(
+                        "Wait for the agent to finish before restoring a checkpoint.".to_string(),
+                    );
+                    return;
+                }
+                let Some(summary) = number
+                    .trim()
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|n| n.checked_sub(1))
+                    .and_then(|i| listed.get(i))
+                else {
+                    self.push_system_message(format!(
+                        "Usage: /checkpoints restore <number> (1-{})",
+                        listed.len()
+                    ));
+                    return;
+                };
+
+                let checkpoint = &summary.checkpoint;
+                match checkpoints.restore_to(session_id, checkpoint.id).await {
+                    Ok(restored) if restored.is_empty() => self.push_system_message(format!(
+                        "\"{}\" and every later turn are already undone.",
+                        checkpoint.prompt
+                    )),
+                    Ok(restored) => {
+                        let message = format!(
+                            "Files rewound to before \"{}\":\n{}\n\n{}",
+                            checkpoint.prompt,
+                            self.format_restored_files(&restored),
+                            SHELL_CHANGES_NOTE
+                        );
+                        self.push_system_message(message);
+                    }
+                    Err(e) => self.push_system_message(format!("Restore failed: {:#}", e)),
+                }
+            }
+            _ => self
+                .push_system_message("Usage: /checkpoints [list | restore <number>]".to_string()),
+        }
+    }
+
+    /// One line per file a restore touched
+    fn format_restored_files(&self, restored: &[RestoredFile]) -> String {
+        restored
+            .iter()
+            .map(|file| match file {
+                RestoredFile::Restored(path) => format!("  restored {}", self.display_path(path)),
+                RestoredFile::Removed(path) => format!("  removed {}", self.display_path(path)),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Path relative to the working directory when it is inside it
+    fn display_path(&self, path: &std::path::Path) -> String {
+        let working_directory = self.agent_service.working_directory();
+        path.strip_prefix(&working_directory)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+
     /// Format a human-readable description of a tool call from its name and input
     pub fn format_tool_description(tool_name: &str, tool_input: &Value) -> String {
         match tool_name {
@@ -855,6 +1131,61 @@ impl App {
         }
     }
 
+    /// Append a reasoning chunk to the live thinking block, starting one if needed
+    pub(crate) fn append_thinking_chunk(&mut self, chunk: String) {
+        let live = self.active_tool_group.is_none()
+            && self.streaming_response.is_none()
+            && self.messages.last().is_some_and(|m| m.role == "thinking");
+
+        if live {
+            if let Some(msg) = self.messages.last_mut() {
+                msg.details.get_or_insert_with(String::new).push_str(&chunk);
+            }
+        } else {
+            // Tools from the previous iteration belong above this iteration's reasoning
+            self.flush_tool_group();
+            self.messages.push(DisplayMessage {
+                id: Uuid::new_v4(),
+                role: "thinking".to_string(),
+                content: "Thinking".to_string(),
+                timestamp: chrono::Utc::now(),
+                token_count: None,
+                cost: None,
+                approval: None,
+                approve_menu: None,
+                details: Some(chunk),
+                expanded: false,
+                tool_group: None,
+                plan_approval: None,
+            });
+        }
+
+        if self.auto_scroll {
+            self.scroll_offset = 0;
+        }
+    }
+
+    /// Move the in-progress tool group into the message list
+    pub(crate) fn flush_tool_group(&mut self) {
+        if let Some(group) = self.active_tool_group.take() {
+            let count = group.calls.len();
+            self.messages.push(DisplayMessage {
+                id: Uuid::new_v4(),
+                role: "tool_group".to_string(),
+                content: format!("{} tool call{}", count, if count == 1 { "" } else { "s" }),
+                timestamp: chrono::Utc::now(),
+                token_count: None,
+                cost: None,
+                approval: None,
+                approve_menu: None,
+                details: None,
+                expanded: false,
+                tool_group: Some(group),
+                plan_approval: None,
+            });
+        }
+    }
+
     /// Complete the streaming response
     pub(crate) async fn complete_response(
         &mut self,
@@ -957,6 +1288,21 @@ impl App {
             self.messages.push(assistant_msg);
         }
 
+        // Summarize the files this turn changed (saved in its checkpoint)
+        if !response.changed_files.is_empty() {
+            let files: Vec<String> = response
+                .changed_files
+                .iter()
+                .map(|path| self.display_path(path))
+                .collect();
+            self.push_system_message(format!(
+                "Changed {} file{}: {} — /undo to revert",
+                files.len(),
+                if files.len() == 1 { "" } else { "s" },
+                files.join(", ")
+            ));
+        }
+
         // Update session model if not already set
         if let Some(session) = &mut self.current_session
             && session.model.is_none() {
@@ -69,6 +69,26 @@ pub const SLASH_COMMANDS: &[SlashCommand] = &[
         name: "/cd",
         description: "Change working directory",
     },
+    SlashCommand {
+        name: "/schedule",
+        description: "Scheduled prompts",
+    },
+    SlashCommand {
+        name: "/debate",
+        description: "Bee Colony debate",
+    },
+    SlashCommand {
+        name: "/undo",
+        description: "Revert the last turn's file changes",
+    },
+    SlashCommand {
+        name: "/checkpoints",
+        description: "List & restore file checkpoints",
+    },
+    SlashCommand {
+        name: "/processes",
+        description: "Background processes",
+    },
 ];
 
 /// Approval option selected by the user
@@ -267,6 +287,14 @@ pub struct App {
     pub selected_task_index: Option<usize>,
     pub executing_plan: bool,
 
+    /// Debate panel state (/debate): the running or last finished debate
+    pub debate: Option<crate::a2a::debate::DebateSession>,
+    pub debate_scroll_offset: usize,
+
+    /// Background processes started by the process tool (/processes panel)
+    pub process_manager: Option<Arc<crate::brain::tools::process::ProcessManager>>,
+    pub selected_process_index: usize,
+
     /// File picker state
     pub file_picker_files: Vec<std::path::PathBuf>,
     pub file_picker_selected: usize,
@@ -412,6 +440,10 @@ impl App {
             plan_scroll_offset: 0,
             selected_task_index: None,
             executing_plan: false,
+            debate: None,
+            debate_scroll_offset: 0,
+            process_manager: None,
+            selected_process_index: 0,
             file_picker_files: Vec::new(),
             file_picker_selected: 0,
             file_picker_scroll_offset: 0,
@@ -556,6 +588,8 @@ impl App {
             config.providers.anthropic.as_ref().filter(|p| p.enabled).map(|_| "anthropic"),
             config.providers.openai.as_ref().filter(|p| p.enabled).map(|_| "openai"),
             config.providers.gemini.as_ref().filter(|p| p.enabled).map(|_| "gemini"),
+            config.providers.bedrock.as_ref().filter(|p| p.enabled).map(|_| "bedrock"),
+            config.providers.vertex.as_ref().filter(|p| p.enabled).map(|_| "vertex"),
             config.providers.openrouter.as_ref().filter(|p| p.enabled).map(|_| "openrouter"),
             config.providers.minimax.as_ref().filter(|p| p.enabled).map(|_| "minimax"),
             config.providers.active_custom().map(|_| "custom"),
@@ -746,6 +780,9 @@ impl App {
             TuiEvent::ResponseChunk(chunk) => {
                 self.append_streaming_chunk(chunk);
             }
+            TuiEvent::ThinkingChunk(chunk) => {
+                self.append_thinking_chunk(chunk);
+            }
             TuiEvent::ResponseComplete(response) => {
                 self.complete_response(response).await?;
             }
@@ -844,23 +881,7 @@ impl App {
 
                 // Flush previous iteration's tool group FIRST, so tools appear
                 // before the next iteration's text (matches DB order).
-                if let Some(group) = self.active_tool_group.take() {
-                    let count = group.calls.len();
-                    self.messages.push(DisplayMessage {
-                        id: Uuid::new_v4(),
-                        role: "tool_group".to_string(),
-                        content: format!("{} tool call{}", count, if count == 1 { "" } else { "s" }),
-                        timestamp: chrono::Utc::now(),
-                        token_count: None,
-                        cost: None,
-                        approval: None,
-                        approve_menu: None,
-                        details: None,
-                        expanded: false,
-                        tool_group: Some(group),
-                        plan_approval: None,
-                    });
-                }
+                self.flush_tool_group();
 
                 // Then add the new intermediate text as a separate assistant message
                 self.messages.push(DisplayMessage {
@@ -1045,6 +1066,12 @@ impl App {
             TuiEvent::SystemMessage(msg) => {
                 self.push_system_message(msg);
             }
+            TuiEvent::DebateUpdated(session) => {
+                if self.debate.as_ref().is_none_or(|d| d.id != session.id) {
+                    self.debate_scroll_offset = 0;
+                }
+                self.debate = Some(*session);
+            }
             TuiEvent::FocusGained | TuiEvent::FocusLost => {
                 // Handled by the event loop for tick coalescing
             }
@@ -1262,6 +1289,22 @@ impl App {
             AppMode::Onboarding => {
                 self.handle_onboarding_key(event).await?;
             }
+            AppMode::Debate => {
+                if keys::is_cancel(&event) {
+                    self.switch_mode(AppMode::Chat).await?;
+                } else if keys::is_up(&event) {
+                    self.debate_scroll_offset = self.debate_scroll_offset.saturating_sub(1);
+                } else if keys::is_down(&event) {
+                    self.debate_scroll_offset = self.debate_scroll_offset.saturating_add(1);
+                } else if keys::is_page_up(&event) {
+                    self.debate_scroll_offset = self.debate_scroll_offset.saturating_sub(10);
+                } else if keys::is_page_down(&event) {
+                    self.debate_scroll_offset = self.debate_scroll_offset.saturating_add(10);
+                }
+            }
+            AppMode::Processes => {
+                self.handle_processes_key(event).await?;
+            }
             AppMode::Help | AppMode::Settings => {
                 if keys::is_cancel(&event) {
                     self.help_scroll_offset = 0;
@@ -1550,6 +1593,8 @@ mod tests {
             created_at: chrono::Utc::now(),
             token_count: Some(10),
             cost: Some(0.001),
+            cache_creation_tokens: None,
+            cache_read_tokens: None,
         };
 
         let display_msg: DisplayMessage = msg.into();
@@ -38,6 +38,9 @@ pub enum TuiEvent {
     /// Agent sent a response chunk (streaming)
     ResponseChunk(String),
 
+    /// Agent sent a reasoning chunk (streaming, rendered collapsed)
+    ThinkingChunk(String),
+
     /// Agent completed response
     ResponseComplete(AgentResponse),
 
@@ -107,6 +110,9 @@ pub enum TuiEvent {
 
     /// Sudo password requested by bash tool
     SudoPasswordRequested(SudoPasswordRequest),
+
+    /// A Bee Colony debate started or finished a round
+    DebateUpdated(Box<crate::a2a::debate::DebateSession>),
 }
 
 /// Sudo password request from the bash tool
@@ -210,6 +216,10 @@ pub enum AppMode {
     DirectoryPicker,
     /// Onboarding wizard
     Onboarding,
+    /// Bee Colony debate panel (triggered by /debate)
+    Debate,
+    /// Background processes panel (triggered by /processes)
+    Processes,
 }
 
 /// Event handler for the TUI
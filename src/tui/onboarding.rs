@@ -72,7 +72,7 @@ pub const CHANNEL_NAMES: &[(&str, &str)] = &[
     ("Discord",     "Bot token (via Developer Portal)"),
     ("WhatsApp",    "QR code pairing"),
     ("Slack",       "Socket Mode (bot + app tokens)"),
-    ("Signal",      "Coming soon"),
+    ("Signal",      "signal-cli daemon (JSON-RPC)"),
     ("Google Chat", "Coming soon"),
     ("iMessage",    "Coming soon"),
 ];
@@ -2581,13 +2581,24 @@ Respond with EXACTLY six sections using these delimiters. No extra text before t
         let _ = Config::write_key("gateway", "port", &self.gateway_port);
         let _ = Config::write_key("gateway", "bind", &self.gateway_bind);
         let _ = Config::write_key("gateway", "auth_mode", if self.gateway_auth == 0 { "token" } else { "none" });
+        // Token auth needs a token — generate one into keys.toml the first time
+        if self.gateway_auth == 0
+            && Config::load().map(|c| c.gateway.token.is_none()).unwrap_or(true)
+        {
+            let token = uuid::Uuid::new_v4().simple().to_string();
+            if let Err(e) = crate::config::write_secret_key("gateway", "token", &token) {
+                tracing::warn!("Failed to write gateway token to keys.toml: {}", e);
+            }
+        }
 
-        // Channel enabled flags (from channel_toggles: 0=Telegram, 1=Discord, 2=WhatsApp, 3=Slack)
+        // Channel enabled flags (from channel_toggles: 0=Telegram, 1=Discord, 2=WhatsApp, 3=Slack, 4=Signal)
         let _ = Config::write_key("channels.telegram", "enabled", &self.is_telegram_enabled().to_string());
         let _ = Config::write_key("channels.discord", "enabled", &self.is_discord_enabled().to_string());
         let _ = Config::write_key("channels.whatsapp", "enabled",
             &self.channel_toggles.get(2).is_some_and(|t| t.1).to_string());
         let _ = Config::write_key("channels.slack", "enabled", &self.is_slack_enabled().to_string());
+        let _ = Config::write_key("channels.signal", "enabled",
+            &self.channel_toggles.get(4).is_some_and(|t| t.1).to_string());
 
         // Voice config
         let groq_key_exists = !self.groq_api_key_input.is_empty() || self.has_existing_groq_key();
@@ -2815,7 +2826,8 @@ After=network.target
 
 [Service]
 Type=simple
-ExecStart={}
+ExecStart={} daemon
+KillSignal=SIGTERM
 Restart=on-failure
 RestartSec=5
 
@@ -2861,6 +2873,7 @@ fn install_launchagent() -> Result<(), String> {
     <key>ProgramArguments</key>
     <array>
         <string>{}</string>
+        <string>daemon</string>
     </array>
     <key>RunAtLoad</key>
     <true/>
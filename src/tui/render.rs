@@ -129,6 +129,12 @@ pub fn render(f: &mut Frame, app: &mut App) {
         AppMode::Onboarding => {
             // Handled by early return above
         }
+        AppMode::Debate => {
+            render_debate(f, app, full_content_area);
+        }
+        AppMode::Processes => {
+            render_processes(f, app, full_content_area);
+        }
     }
 }
 
@@ -313,6 +319,45 @@ fn render_chat(f: &mut Frame, app: &mut App, area: Rect) {
             continue;
         }
 
+        if app.messages[msg_idx].role == "thinking" {
+            // Model reasoning: one dim header line, full text only when expanded
+            let msg = &app.messages[msg_idx];
+            let thinking_style = Style::default()
+                .fg(Color::Rgb(140, 140, 160))
+                .add_modifier(Modifier::ITALIC);
+            let details = msg.details.as_deref().unwrap_or_default();
+            let line_count = details.trim().lines().count();
+            let hint = if msg.expanded {
+                " (ctrl+o to collapse)"
+            } else {
+                " (ctrl+o to expand)"
+            };
+            lines.push(Line::from(vec![
+                Span::styled("  💭 ", thinking_style),
+                Span::styled(
+                    format!(
+                        "{} ({} line{})",
+                        msg.content,
+                        line_count,
+                        if line_count == 1 { "" } else { "s" }
+                    ),
+                    thinking_style,
+                ),
+                Span::styled(hint, Style::default().fg(Color::Rgb(120, 120, 120))),
+            ]));
+
+            if msg.expanded {
+                for detail_line in details.trim().lines() {
+                    lines.push(Line::from(vec![
+                        Span::styled("    │ ", Style::default().fg(Color::Rgb(80, 80, 100))),
+                        Span::styled(detail_line.to_string(), thinking_style),
+                    ]));
+                }
+            }
+            lines.push(Line::from(""));
+            continue;
+        }
+
         if app.messages[msg_idx].role == "system" {
             // System messages: visible yellow label, split on newlines so
             // multi-line content actually renders (not clipped to one line).
@@ -1493,6 +1538,11 @@ fn render_help(f: &mut Frame, app: &App, area: Rect) {
         kv("/compact", "Compact context now", blue),
         kv("/rebuild", "Build & restart from source", blue),
         kv("/cd", "Change working directory", blue),
+        kv("/schedule", "Scheduled prompts", blue),
+        kv("/debate", "Bee Colony debate", blue),
+        kv("/undo", "Revert last turn's file changes", blue),
+        kv("/checkpoints", "List & restore file checkpoints", blue),
+        kv("/processes", "Background processes", blue),
         kv("/whisper", "Speak anywhere, paste to clipboard", blue),
         Line::from(""),
         Line::from(""),
@@ -1597,6 +1647,281 @@ fn render_help(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(right_para, columns[1]);
 }
 
+/// Render the Bee Colony debate panel: rounds, each bee's position and
+/// confidence, the consensus analysis and the queen's synthesis.
+fn render_debate(f: &mut Frame, app: &App, area: Rect) {
+    use crate::a2a::debate::DebateState;
+
+    let accent = Color::Rgb(184, 134, 11);
+    let label_style = Style::default().fg(Color::DarkGray);
+    let value_style = Style::default().fg(Color::White).add_modifier(Modifier::BOLD);
+    let header_style = Style::default().fg(accent).add_modifier(Modifier::BOLD);
+    let spinner = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+    let mut lines: Vec<Line> = vec![Line::from("")];
+    match app.debate {
+        None => {
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "  {} Gathering the bees… rounds appear here as they answer.",
+                    spinner[app.animation_frame % spinner.len()]
+                ),
+                label_style,
+            )));
+        }
+        Some(ref debate) => {
+            let state = match debate.state {
+                DebateState::Pending => "pending",
+                DebateState::InRound => "debating",
+                DebateState::Analyzing => "analyzing",
+                DebateState::Concluded => "consensus reached",
+                DebateState::Exhausted => "no consensus (rounds exhausted)",
+            };
+            lines.push(Line::from(vec![
+                Span::styled("  Topic:  ", label_style),
+                Span::styled(debate.config.topic.clone(), value_style),
+            ]));
+            lines.push(Line::from(vec![
+                Span::styled("  Bees:   ", label_style),
+                Span::styled(debate.config.num_bees.to_string(), value_style),
+                Span::styled("   Round: ", label_style),
+                Span::styled(
+                    format!("{}/{}", debate.current_round, debate.config.max_rounds),
+                    value_style,
+                ),
+                Span::styled("   State: ", label_style),
+                Span::styled(state, value_style),
+            ]));
+
+            for round in &debate.rounds {
+                lines.push(Line::from(""));
+                lines.push(Line::from(Span::styled(
+                    format!("  ── Round {} ──", round.round_number),
+                    header_style,
+                )));
+                for resp in &round.responses {
+                    let filled = (resp.confidence * 10.0).round().clamp(0.0, 10.0) as usize;
+                    lines.push(Line::from(vec![
+                        Span::styled(format!("  🐝 {:<7}", resp.bee_id), value_style),
+                        Span::styled(
+                            format!("{}{}", "█".repeat(filled), "░".repeat(10 - filled)),
+                            Style::default().fg(accent),
+                        ),
+                        Span::styled(format!(" {:.2}  ", resp.confidence), label_style),
+                        Span::styled(
+                            resp.position
+                                .clone()
+                                .unwrap_or_else(|| "(no stated position)".to_string()),
+                            Style::default().fg(Color::White),
+                        ),
+                    ]));
+                    lines.push(Line::from(Span::styled(
+                        format!("            {}", resp.endpoint),
+                        label_style,
+                    )));
+                }
+
+                if let Some(ref consensus) = round.consensus {
+                    lines.push(Line::from(vec![
+                        Span::styled("  Avg confidence: ", label_style),
+                        Span::styled(format!("{:.2}", consensus.avg_confidence), value_style),
+                        Span::styled("   Consensus: ", label_style),
+                        if consensus.consensus_reached {
+                            Span::styled("yes", Style::default().fg(Color::Green))
+                        } else {
+                            Span::styled("no", Style::default().fg(Color::Yellow))
+                        },
+                    ]));
+                    let groups = [
+                        ("Agreement", &consensus.agreement_points, Color::Green, "✓"),
+                        ("Contention", &consensus.contention_points, Color::Yellow, "⚡"),
+                        ("Blind spots", &consensus.blind_spots, Color::Magenta, "?"),
+                    ];
+                    for (title, points, color, marker) in groups {
+                        if points.is_empty() {
+                            continue;
+                        }
+                        lines.push(Line::from(Span::styled(format!("  {}:", title), label_style)));
+                        for point in points {
+                            lines.push(Line::from(vec![
+                                Span::styled(format!("    {} ", marker), Style::default().fg(color)),
+                                Span::styled(point.clone(), Style::default().fg(Color::White)),
+                            ]));
+                        }
+                    }
+                }
+            }
+
+            if debate.state == DebateState::InRound {
+                lines.push(Line::from(""));
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "  {} Round {} in progress…",
+                        spinner[app.animation_frame % spinner.len()],
+                        debate.current_round
+                    ),
+                    label_style,
+                )));
+            }
+
+            if let Some(ref synthesis) = debate.final_synthesis {
+                lines.push(Line::from(""));
+                lines.push(Line::from(Span::styled("  ── Queen's Synthesis ──", header_style)));
+                for line in synthesis.lines() {
+                    lines.push(Line::from(Span::styled(
+                        format!("  {}", line),
+                        Style::default().fg(Color::White),
+                    )));
+                }
+            }
+        }
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled(
+            " [↑↓ PgUp/Dn]",
+            Style::default()
+                .fg(Color::Rgb(70, 130, 180))
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(" Scroll  ", Style::default().fg(Color::DarkGray)),
+        Span::styled("[Esc]", Style::default().fg(accent).add_modifier(Modifier::BOLD)),
+        Span::styled(" Back", Style::default().fg(Color::DarkGray)),
+    ]));
+
+    let panel = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(Span::styled(
+                    " 🐝 Bee Colony Debate ",
+                    Style::default().fg(accent).add_modifier(Modifier::BOLD),
+                ))
+                .border_style(Style::default().fg(accent)),
+        )
+        .wrap(Wrap { trim: false })
+        .scroll((app.debate_scroll_offset as u16, 0));
+
+    f.render_widget(panel, area);
+}
+
+/// Render the background processes panel: every process the process tool
+/// started, its status and its last lines of output.
+fn render_processes(f: &mut Frame, app: &App, area: Rect) {
+    use crate::brain::tools::process::ProcessStatus;
+
+    let accent = Color::Rgb(70, 130, 180);
+    let label_style = Style::default().fg(Color::DarkGray);
+    let current_session = app.current_session.as_ref().map(|s| s.id);
+    let processes = app
+        .process_manager
+        .as_ref()
+        .map(|m| m.list(None))
+        .unwrap_or_default();
+
+    let mut lines: Vec<Line> = vec![Line::from("")];
+    if processes.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  No background processes. The agent starts them with the process tool \
+             (dev servers, watchers, long test runs).",
+            label_style,
+        )));
+    }
+
+    for (idx, process) in processes.iter().enumerate() {
+        let is_selected = idx == app.selected_process_index;
+        let (marker, status_color) = match process.status {
+            ProcessStatus::Running => ("●", Color::Green),
+            ProcessStatus::Exited(0) => ("✓", Color::DarkGray),
+            ProcessStatus::Exited(_) => ("✗", Color::Red),
+            ProcessStatus::Killed => ("■", Color::Yellow),
+        };
+        let name_style = if is_selected {
+            Style::default()
+                .fg(Color::Rgb(184, 134, 11))
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD)
+        };
+
+        let mut spans = vec![
+            Span::styled(
+                format!(
+                    "{}{:<4}",
+                    if is_selected { "  > " } else { "    " },
+                    process.handle
+                ),
+                name_style,
+            ),
+            Span::styled(format!("{} ", marker), Style::default().fg(status_color)),
+            Span::styled(process.command.clone(), name_style),
+            Span::styled(format!("  {}", process.status_label()), label_style),
+        ];
+        if current_session != Some(process.session_id) {
+            spans.push(Span::styled(
+                format!("  [session {}]", &process.session_id.to_string()[..8]),
+                label_style,
+            ));
+        }
+        lines.push(Line::from(spans));
+        lines.push(Line::from(Span::styled(
+            format!("          {}", process.cwd.display()),
+            label_style,
+        )));
+        for line in &process.recent {
+            lines.push(Line::from(vec![
+                Span::styled("          │ ", label_style),
+                Span::styled(line.clone(), Style::default().fg(Color::Gray)),
+            ]));
+        }
+        lines.push(Line::from(""));
+    }
+
+    lines.push(Line::from(vec![
+        Span::styled(
+            " [↑↓]",
+            Style::default().fg(accent).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(" Select  ", Style::default().fg(Color::DarkGray)),
+        Span::styled(
+            "[K]",
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(" Kill  ", Style::default().fg(Color::DarkGray)),
+        Span::styled(
+            "[Esc]",
+            Style::default().fg(accent).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(" Back", Style::default().fg(Color::DarkGray)),
+    ]));
+
+    // Keep the selected process in view
+    let visible = area.height.saturating_sub(2) as usize;
+    let selected_line = 1 + processes
+        .iter()
+        .take(app.selected_process_index + 1)
+        .map(|p| p.recent.len() + 3)
+        .sum::<usize>();
+    let scroll = selected_line.saturating_sub(visible);
+
+    let panel = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(Span::styled(
+                    " ⚙ Background Processes ",
+                    Style::default().fg(accent).add_modifier(Modifier::BOLD),
+                ))
+                .border_style(Style::default().fg(accent)),
+        )
+        .scroll((scroll as u16, 0));
+
+    f.render_widget(panel, area);
+}
+
 /// Render help text in the input area during Plan Mode
 fn render_plan_help(f: &mut Frame, area: Rect) {
     let help_text = vec![Line::from(vec![